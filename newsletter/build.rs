@@ -1,21 +1,76 @@
 use std::{env, error::Error, path::PathBuf};
 
+use vergen_gitcl::{BuildBuilder, Emitter, GitclBuilder};
+
 fn main() -> Result<(), Box<dyn Error>> {
-    let protos = &[
-        "src/infrastructure/rpc/newsletter/v1/newsletter.proto",
-        "src/infrastructure/rpc/newsletter/v1/api.proto",
+    // Each package gets its own descriptor set so it can be registered for reflection.
+    let packages: &[(&str, &[&str])] = &[
+        (
+            "infrastructure.rpc.newsletter.v1",
+            &[
+                "src/infrastructure/rpc/newsletter/v1/newsletter.proto",
+                "src/infrastructure/rpc/newsletter/v1/api.proto",
+            ],
+        ),
+        (
+            "infrastructure.rpc.newsletter.v2",
+            &[
+                "src/infrastructure/rpc/newsletter/v2/newsletter.proto",
+                "src/infrastructure/rpc/newsletter/v2/api.proto",
+            ],
+        ),
+        (
+            "infrastructure.rpc.campaign.v1",
+            &[
+                "src/infrastructure/rpc/campaign/v1/campaign.proto",
+                "src/infrastructure/rpc/campaign/v1/api.proto",
+            ],
+        ),
+        (
+            "infrastructure.rpc.template.v1",
+            &[
+                "src/infrastructure/rpc/template/v1/template.proto",
+                "src/infrastructure/rpc/template/v1/api.proto",
+            ],
+        ),
+        (
+            "infrastructure.rpc.admin.v1",
+            &["src/infrastructure/rpc/admin/v1/api.proto"],
+        ),
     ];
     let out_dir = PathBuf::from(env::var("OUT_DIR")?);
-    let fds = out_dir.join("infrastructure.rpc.newsletter.v1_descriptor.bin");
 
-    tonic_prost_build::configure()
-        .file_descriptor_set_path(&fds) // <- generate descriptor set
-        .build_client(true)
-        .build_server(true)
-        .compile_protos(protos, &["src"])?;
+    for (package, protos) in packages {
+        let fds = out_dir.join(format!("{package}_descriptor.bin"));
+
+        tonic_prost_build::configure()
+            .file_descriptor_set_path(&fds) // <- generate descriptor set
+            .build_client(true)
+            .build_server(true)
+            .compile_protos(protos, &["src"])?;
 
-    for p in protos {
-        println!("cargo:rerun-if-changed={}", p);
+        for p in protos.iter() {
+            println!("cargo:rerun-if-changed={}", p);
+        }
+    }
+    // VERGEN_BUILD_TIMESTAMP and VERGEN_GIT_SHA, read by infrastructure::build_info
+    Emitter::default()
+        .add_instructions(&BuildBuilder::default().build_timestamp(true).build()?)?
+        .emit()?;
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    match env::var("GIT_COMMIT") {
+        // Images are built without the checkout, so the commit is passed in
+        Ok(commit) if !commit.is_empty() => println!("cargo:rustc-env=VERGEN_GIT_SHA={commit}"),
+        _ => {
+            let git = GitclBuilder::default().sha(false).build()?;
+            let mut emitter = Emitter::default();
+            // vergen would otherwise put a placeholder in place of an unknown commit
+            if emitter.fail_on_error().add_instructions(&git).is_ok() {
+                emitter.emit()?;
+            } else {
+                println!("cargo:rustc-env=VERGEN_GIT_SHA=");
+            }
+        }
     }
     Ok(())
 }
@@ -33,5 +33,14 @@ test-cucumber: ## Run cucumber tests
 test-integration: ## Run integration tests
 	@cargo test --test cucumber_simple --test cucumber_expressions
 
+test-postgres: ## Run cucumber tests on Postgres (needs Docker)
+	@cargo test --features postgres-tests --test cucumber_simple --test cucumber_expressions
+
+test-contract: ## Run gRPC contract tests (needs Docker)
+	@cargo test --test contract -- --ignored
+
+load-test: ## Drive a local server with concurrent gRPC calls and report latencies
+	@cargo run --release --bin newsletter-loadtest -- $(ARGS)
+
 test-coverage: ## Generate test coverage report
 	@cargo tarpaulin --out html --output-dir ./coverage
@@ -0,0 +1,32 @@
+use newsletter::domain::tenant::TenantScope;
+use newsletter::infrastructure::config::Settings;
+use newsletter::infrastructure::db::build_pool;
+use newsletter::infrastructure::{logging, tenant};
+use newsletter::repository::newsletter::postgres::PostgresNewsletterRepository;
+use newsletter::repository::newsletter::NewsletterRepository;
+use tracing::info;
+
+/// Fold subscriptions stored under mixed-case addresses into their lowercase
+/// form. Run it before upgrading past the `index_newsletters_lower_email`
+/// migration, which cannot apply while such duplicates exist; it deliberately
+/// runs no migrations itself.
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv::dotenv().ok();
+    logging::init_tracing()?;
+
+    let settings = Settings::load()?;
+    logging::set_log_pii(settings.logging.pii);
+    if let Some(pseudonyms) = settings.privacy.pseudonymizer() {
+        logging::set_pseudonyms(pseudonyms);
+    }
+    let pool = build_pool(&settings.database.without_statement_timeout()).await?;
+    let repository = PostgresNewsletterRepository::new(pool);
+
+    // Merged rows are rewritten in their own tenant, so each is folded separately
+    for id in tenant::scope(TenantScope::All, repository.list_tenants()).await? {
+        let folded = tenant::scope(TenantScope::One(id.clone()), repository.merge_case_duplicates()).await?;
+        info!(tenant = %id, folded = folded, "Merged mixed-case addresses into their lowercase form");
+    }
+    Ok(())
+}
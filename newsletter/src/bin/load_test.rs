@@ -0,0 +1,314 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use clap::Parser;
+use tokio::sync::Mutex;
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Code, Request};
+
+use newsletter::infrastructure::rpc::newsletter::v1::proto::newsletter_service_client::NewsletterServiceClient;
+use newsletter::infrastructure::rpc::newsletter::v1::proto::{
+    GetRequest, ImportFormat, ImportSubscribersRequest, ListRequest, SubscribeRequest,
+};
+use newsletter::infrastructure::rpc::tenant::TENANT_HEADER;
+
+/// Drives the newsletter gRPC API with a mix of calls from concurrent
+/// workers and reports latency percentiles per call, to check bulk inserts
+/// and pagination under load. Point it at a server that is not in production:
+/// every subscribe and import writes new addresses.
+#[derive(Parser)]
+#[command(name = "newsletter-loadtest", version)]
+struct Cli {
+    /// Address of the server
+    #[arg(long, default_value = "http://127.0.0.1:50051")]
+    endpoint: String,
+    /// API key sent as a bearer token; needs the admin scope for imports
+    #[arg(long)]
+    api_key: Option<String>,
+    /// Tenant the calls are made for
+    #[arg(long, default_value = "default")]
+    tenant: String,
+    /// Calls in flight at once
+    #[arg(long, default_value_t = 16)]
+    concurrency: usize,
+    /// Calls to make in total
+    #[arg(long, default_value_t = 10_000)]
+    requests: u64,
+    /// Stop after this many seconds even if calls are left
+    #[arg(long)]
+    duration: Option<u64>,
+    /// Relative weights of the calls, such as `subscribe=1,get=3,list=1,import=0`
+    #[arg(long, default_value = "subscribe=1,get=3,list=1", value_parser = Mix::parse)]
+    mix: Mix,
+    /// Page size of list calls; each worker follows its own page tokens
+    #[arg(long, default_value_t = 100)]
+    page_size: i32,
+    /// Addresses per import call
+    #[arg(long, default_value_t = 1000)]
+    import_batch: usize,
+    /// Consent version sent with subscribe calls
+    #[arg(long, default_value = "load-test")]
+    consent_version: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Call {
+    Subscribe,
+    Get,
+    List,
+    Import,
+}
+
+impl Call {
+    const ALL: [Call; 4] = [Call::Subscribe, Call::Get, Call::List, Call::Import];
+
+    fn name(self) -> &'static str {
+        match self {
+            Call::Subscribe => "subscribe",
+            Call::Get => "get",
+            Call::List => "list",
+            Call::Import => "import",
+        }
+    }
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
+/// Weighted calls, picked in a fixed rotation so runs are repeatable
+#[derive(Debug, Clone)]
+struct Mix {
+    rotation: Vec<Call>,
+}
+
+impl Mix {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut rotation = Vec::new();
+        for part in spec.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let (name, weight) = part
+                .split_once('=')
+                .ok_or_else(|| format!("{part:?} is not `call=weight`"))?;
+            let call = Call::ALL
+                .into_iter()
+                .find(|call| call.name() == name.trim())
+                .ok_or_else(|| format!("unknown call {name:?}; expected subscribe, get, list or import"))?;
+            let weight: usize = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("weight of {name} is not a whole number"))?;
+            rotation.extend(std::iter::repeat_n(call, weight));
+        }
+        if rotation.is_empty() {
+            return Err("at least one call needs a weight above zero".to_string());
+        }
+        Ok(Self { rotation })
+    }
+
+    fn call(&self, n: u64) -> Call {
+        self.rotation[(n % self.rotation.len() as u64) as usize]
+    }
+}
+
+/// Latencies of the calls that succeeded and codes of the ones that failed
+#[derive(Default)]
+struct Samples {
+    latencies: BTreeMap<Call, Vec<Duration>>,
+    /// Keyed by the numeric status code, as `Code` is not ordered
+    errors: BTreeMap<(Call, i32), u64>,
+}
+
+struct Worker {
+    client: NewsletterServiceClient<Channel>,
+    cli: Arc<Cli>,
+    /// Distinguishes the addresses of this run from earlier ones
+    run: String,
+    id: usize,
+    written: u64,
+    page_token: String,
+}
+
+impl Worker {
+    fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        if let Some(key) = &self.cli.api_key {
+            if let Ok(value) = MetadataValue::try_from(format!("Bearer {key}")) {
+                request.metadata_mut().insert("authorization", value);
+            }
+        }
+        if let Ok(value) = MetadataValue::try_from(self.cli.tenant.as_str()) {
+            request.metadata_mut().insert(TENANT_HEADER, value);
+        }
+        request
+    }
+
+    fn next_email(&mut self) -> String {
+        self.written += 1;
+        format!("load-{}-{}-{}@example.com", self.run, self.id, self.written)
+    }
+
+    /// An address this worker wrote before, or one that does not exist yet
+    fn known_email(&self, n: u64) -> String {
+        let written = self.written.max(1);
+        format!("load-{}-{}-{}@example.com", self.run, self.id, n % written + 1)
+    }
+
+    async fn call(&mut self, call: Call, n: u64) -> Result<(), tonic::Status> {
+        match call {
+            Call::Subscribe => {
+                let email = self.next_email();
+                let request = self.request(SubscribeRequest {
+                    email,
+                    consent_version: self.cli.consent_version.clone(),
+                    source: "load-test".to_string(),
+                    ..Default::default()
+                });
+                self.client.subscribe(request).await?;
+            }
+            Call::Get => {
+                let request = self.request(GetRequest {
+                    email: self.known_email(n),
+                    read_mask: None,
+                });
+                self.client.get(request).await?;
+            }
+            Call::List => {
+                let page_token = std::mem::take(&mut self.page_token);
+                let request = self.request(ListRequest {
+                    page_size: self.cli.page_size,
+                    page_token,
+                    ..Default::default()
+                });
+                self.page_token = self.client.list(request).await?.into_inner().next_page_token;
+            }
+            Call::Import => {
+                let mut csv = String::from("email\n");
+                for _ in 0..self.cli.import_batch {
+                    csv.push_str(&self.next_email());
+                    csv.push('\n');
+                }
+                let request = self.request(futures::stream::iter([ImportSubscribersRequest {
+                    format: ImportFormat::Csv as i32,
+                    chunk: csv.into_bytes(),
+                    dry_run: false,
+                }]));
+                self.client.import_subscribers(request).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Arc::new(Cli::parse());
+    anyhow::ensure!(cli.concurrency > 0, "--concurrency must be at least 1");
+
+    let channel = Endpoint::from_shared(cli.endpoint.clone())?
+        .connect()
+        .await
+        .with_context(|| format!("failed to connect to {}", cli.endpoint))?;
+    let run = format!("{:x}", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs());
+    let deadline = cli.duration.map(|seconds| Instant::now() + Duration::from_secs(seconds));
+
+    let next = Arc::new(AtomicU64::new(0));
+    let samples = Arc::new(Mutex::new(Samples::default()));
+    let started = Instant::now();
+
+    let workers: Vec<_> = (0..cli.concurrency)
+        .map(|id| {
+            let mut worker = Worker {
+                client: NewsletterServiceClient::new(channel.clone()),
+                cli: cli.clone(),
+                run: run.clone(),
+                id,
+                written: 0,
+                page_token: String::new(),
+            };
+            let next = next.clone();
+            let samples = samples.clone();
+            tokio::spawn(async move {
+                let mut local = Samples::default();
+                loop {
+                    let n = next.fetch_add(1, Ordering::Relaxed);
+                    if n >= worker.cli.requests || deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                        break;
+                    }
+                    let call = worker.cli.mix.call(n);
+                    let start = Instant::now();
+                    match worker.call(call, n).await {
+                        Ok(()) => local.latencies.entry(call).or_default().push(start.elapsed()),
+                        Err(status) => *local.errors.entry((call, status.code() as i32)).or_default() += 1,
+                    }
+                }
+                let mut samples = samples.lock().await;
+                for (call, latencies) in local.latencies {
+                    samples.latencies.entry(call).or_default().extend(latencies);
+                }
+                for (key, count) in local.errors {
+                    *samples.errors.entry(key).or_default() += count;
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.await?;
+    }
+
+    report(&mut *samples.lock().await, started.elapsed());
+    Ok(())
+}
+
+fn report(samples: &mut Samples, elapsed: Duration) {
+    let total: usize = samples.latencies.values().map(Vec::len).sum::<usize>()
+        + samples.errors.values().sum::<u64>() as usize;
+    println!(
+        "{total} calls in {:.1}s, {:.0} calls/s",
+        elapsed.as_secs_f64(),
+        total as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+    );
+    println!("{:<10} {:>8} {:>8} {:>10} {:>10} {:>10} {:>10}", "call", "ok", "errors", "p50", "p95", "p99", "max");
+    for call in Call::ALL {
+        let latencies = samples.latencies.entry(call).or_default();
+        let errors: u64 = samples
+            .errors
+            .iter()
+            .filter(|((failed, _), _)| *failed == call)
+            .map(|(_, count)| count)
+            .sum();
+        if latencies.is_empty() && errors == 0 {
+            continue;
+        }
+        latencies.sort_unstable();
+        println!(
+            "{:<10} {:>8} {:>8} {:>10} {:>10} {:>10} {:>10}",
+            call,
+            latencies.len(),
+            errors,
+            millis(percentile(latencies, 50.0)),
+            millis(percentile(latencies, 95.0)),
+            millis(percentile(latencies, 99.0)),
+            millis(latencies.last().copied()),
+        );
+    }
+    for ((call, code), count) in &samples.errors {
+        eprintln!("{call}: {count} failed with {:?}", Code::from(*code));
+    }
+}
+
+/// Nearest-rank percentile of sorted latencies
+fn percentile(sorted: &[Duration], p: f64) -> Option<Duration> {
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.saturating_sub(1)).copied()
+}
+
+fn millis(latency: Option<Duration>) -> String {
+    latency.map_or_else(|| "-".to_string(), |latency| format!("{:.2}ms", latency.as_secs_f64() * 1000.0))
+}
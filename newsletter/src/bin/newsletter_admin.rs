@@ -0,0 +1,383 @@
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+
+use newsletter::domain::newsletter::EmailAddress;
+use newsletter::domain::pagination::{PageRequest, MAX_PAGE_SIZE};
+use newsletter::domain::tenant::{TenantId, TenantScope};
+use newsletter::infrastructure::config::Settings;
+use newsletter::infrastructure::rpc::service_config;
+use newsletter::infrastructure::db::{build_pool, run_migrations, PgPool};
+use newsletter::infrastructure::token::TokenSigner;
+use newsletter::infrastructure::{logging, tenant};
+use newsletter::repository::jobs::postgres::PostgresJobRepository;
+use newsletter::repository::newsletter::postgres::PostgresNewsletterRepository;
+use newsletter::repository::newsletter::NewsletterRepository;
+use newsletter::repository::outbox::postgres::PostgresOutboxRepository;
+use newsletter::repository::outbox::OutboxRepository;
+use newsletter::repository::subscriber_view::postgres::PostgresSubscriberViewRepository;
+use newsletter::service::newsletter::import::{ImportFormat, SubscriberImport};
+use newsletter::service::newsletter::seed::{self, ActivityMix, SeedPlan};
+use newsletter::service::newsletter::{ConfirmationConfig, DefaultNewsletterService, NewsletterService};
+
+/// Bytes read from an import file per chunk
+const IMPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Operational tasks against the newsletter database, for use in the
+/// cluster without hand-crafted gRPC calls. Reads the same settings as the
+/// server.
+#[derive(Parser)]
+#[command(name = "newsletter-admin", version)]
+struct Cli {
+    /// Tenant the command works on
+    #[arg(long, global = true, default_value = "default")]
+    tenant: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Apply pending database migrations
+    Migrate,
+    /// Print subscription counts
+    Stats {
+        /// One line per tenant instead of only `--tenant`
+        #[arg(long)]
+        all_tenants: bool,
+    },
+    /// Import confirmed subscribers from a CSV or NDJSON file (`-` for stdin)
+    Import {
+        path: PathBuf,
+        #[arg(long, value_enum, default_value = "csv")]
+        format: Format,
+        /// Validate the file and print the summary without storing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write every subscription as `email,active` CSV (`-` for stdout)
+    Export { path: PathBuf },
+    /// Delete a subscriber with its tags, topic choices and tokens
+    Purge { email: String },
+    /// Print the address behind a pseudonym from logs, events or consent
+    /// records, by hashing every subscription of the tenant
+    Lookup { pseudonym: String },
+    /// Publish outbox events sent since a time again, through the running relay
+    ReplayOutbox {
+        /// RFC 3339 time, such as 2026-10-17T00:00:00Z
+        #[arg(long)]
+        since: DateTime<Utc>,
+    },
+    /// Refill the read model from the newsletters table, for changes that
+    /// record no event, such as retention and the expiry of pending subscriptions
+    RebuildReadModel {
+        /// Every tenant instead of only `--tenant`
+        #[arg(long)]
+        all_tenants: bool,
+    },
+    /// Print the subscription of an address as it stood at a time, replayed
+    /// from the subscriber history, with the events it was replayed from
+    History {
+        email: String,
+        /// RFC 3339 time, such as 2026-05-03T00:00:00Z; now by default
+        #[arg(long)]
+        at: Option<DateTime<Utc>>,
+    },
+    /// Correct the status of stored subscriptions that disagrees with the
+    /// subscriber history, such as after restoring an older backup of the
+    /// newsletters table
+    RebuildSnapshots {
+        /// Every tenant instead of only `--tenant`
+        #[arg(long)]
+        all_tenants: bool,
+    },
+    /// Generate subscribers for a demo or load test; the same options
+    /// generate the same addresses again
+    Seed {
+        #[arg(long, default_value_t = 1000)]
+        count: usize,
+        /// Domain of the addresses, repeatable; example.com, .org and .net by default
+        #[arg(long = "domain")]
+        domains: Vec<String>,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Relative weight of active subscribers
+        #[arg(long, default_value_t = 80)]
+        active: u32,
+        /// Relative weight of unsubscribed subscribers
+        #[arg(long, default_value_t = 15)]
+        unsubscribed: u32,
+        /// Relative weight of suppressed subscribers
+        #[arg(long, default_value_t = 5)]
+        suppressed: u32,
+    },
+    /// Anonymize or delete the unsubscribed subscriptions past their
+    /// retention now, instead of waiting for the server's hourly run
+    Retention {
+        /// Every tenant instead of only `--tenant`
+        #[arg(long)]
+        all_tenants: bool,
+    },
+    /// Print the gRPC service config for clients, as published in
+    /// `src/infrastructure/rpc/service_config.json`
+    ServiceConfig,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Csv,
+    Ndjson,
+}
+
+impl From<Format> for ImportFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Csv => ImportFormat::Csv,
+            Format::Ndjson => ImportFormat::Ndjson,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv::dotenv().ok();
+    logging::init_tracing()?;
+
+    let cli = Cli::parse();
+    if let Command::ServiceConfig = cli.command {
+        // Needs neither the settings nor the database
+        println!("{}", serde_json::to_string_pretty(&service_config::service_config())?);
+        return Ok(());
+    }
+    let settings = Settings::load()?;
+    logging::set_log_pii(settings.logging.pii);
+    if let Some(pseudonyms) = settings.privacy.pseudonymizer() {
+        logging::set_pseudonyms(pseudonyms);
+    }
+    let tenant = TenantId::parse(&cli.tenant).map_err(|e| anyhow::anyhow!("--tenant: {e}"))?;
+    let pool = build_pool(&settings.database.without_statement_timeout()).await?;
+    tenant::scope(TenantScope::One(tenant), run(cli.command, &settings, pool)).await
+}
+
+async fn run(command: Command, settings: &Settings, pool: PgPool) -> anyhow::Result<()> {
+    let pseudonyms = settings.privacy.pseudonymizer();
+    let mut repository = PostgresNewsletterRepository::new(pool.clone());
+    if let Some(pseudonyms) = &pseudonyms {
+        repository = repository.with_pseudonyms(pseudonyms.clone());
+    }
+    let repository = Arc::new(repository);
+
+    match command {
+        Command::Migrate => {
+            run_migrations(&settings.database).await?;
+            println!("migrations applied");
+        }
+        Command::Stats { all_tenants } => {
+            let tenants = if all_tenants {
+                tenant::scope(TenantScope::All, repository.list_tenants()).await?
+            } else {
+                tenant::current().tenant().cloned().into_iter().collect()
+            };
+            println!("tenant\tactive\tinactive\tunsubscribed\tsuppressed");
+            for id in tenants {
+                let stats = tenant::scope(TenantScope::One(id.clone()), repository.stats()).await?;
+                println!(
+                    "{id}\t{}\t{}\t{}\t{}",
+                    stats.active, stats.inactive, stats.unsubscribed, stats.suppressed
+                );
+            }
+        }
+        Command::Import { path, format, dry_run } => {
+            let service = service(settings, repository, pool);
+
+            let mut input = open(&path)?;
+            let mut import = SubscriberImport::new(format.into()).with_dry_run(dry_run);
+            let mut chunk = vec![0; IMPORT_CHUNK_SIZE];
+            loop {
+                let read = input.read(&mut chunk)?;
+                if read == 0 {
+                    break;
+                }
+                import.push(&service, &chunk[..read]).await?;
+            }
+            let summary = import.finish(&service).await?;
+
+            println!(
+                "{} {}, skipped {}, invalid {}",
+                if dry_run { "would import" } else { "imported" },
+                summary.imported,
+                summary.skipped,
+                summary.invalid
+            );
+            for row in &summary.errors {
+                eprintln!("record {}: {}", row.record, row.reason);
+            }
+        }
+        Command::Export { path } => {
+            let mut writer = csv::Writer::from_writer(create(&path)?);
+            writer.write_record(["email", "active", "status"])?;
+
+            let mut exported = 0;
+            let mut cursor = None;
+            loop {
+                let page = repository.list(PageRequest::new(MAX_PAGE_SIZE, cursor)).await?;
+                for newsletter in &page.items {
+                    writer.write_record([
+                        newsletter.email.as_str(),
+                        if newsletter.is_active() { "true" } else { "false" },
+                        newsletter.status.as_str(),
+                    ])?;
+                }
+                exported += page.items.len();
+                match page.next_cursor {
+                    Some(next) => cursor = Some(next),
+                    None => break,
+                }
+            }
+            writer.flush()?;
+            eprintln!("exported {exported} subscriptions");
+        }
+        Command::Purge { email } => {
+            let email = settings.normalization.rules().apply(&EmailAddress::parse(&email)?);
+            if repository.delete(email.as_str()).await? {
+                println!("purged {email}");
+            } else {
+                println!("{email} has no subscription");
+            }
+        }
+        Command::Lookup { pseudonym } => {
+            let Some(pseudonyms) = &pseudonyms else {
+                anyhow::bail!("privacy.pseudonym_key (PII_PSEUDONYM_KEY) is not set");
+            };
+
+            let mut cursor = None;
+            loop {
+                let page = repository.list(PageRequest::new(MAX_PAGE_SIZE, cursor)).await?;
+                if let Some(newsletter) = page.items.iter().find(|n| pseudonyms.pseudonym(&n.email) == pseudonym) {
+                    println!("{}", newsletter.email);
+                    return Ok(());
+                }
+                match page.next_cursor {
+                    Some(next) => cursor = Some(next),
+                    None => break,
+                }
+            }
+            anyhow::bail!("no subscription of this tenant has pseudonym {pseudonym}");
+        }
+        Command::ReplayOutbox { since } => {
+            let replayed = PostgresOutboxRepository::new(pool).replay(since).await?;
+            println!("requeued {replayed} outbox events sent since {since}");
+        }
+        Command::RebuildReadModel { all_tenants } => {
+            let views = PostgresSubscriberViewRepository::new(pool);
+            let rebuilt = if all_tenants {
+                tenant::scope(TenantScope::All, views.rebuild()).await?
+            } else {
+                views.rebuild().await?
+            };
+            println!("rebuilt the read model with {rebuilt} subscriptions");
+        }
+        Command::History { email, at } => {
+            let at = at.unwrap_or_else(Utc::now);
+            let history = service(settings, repository, pool)
+                .subscriber_history(&EmailAddress::parse(&email)?, at)
+                .await?;
+            match &history.state {
+                Some(state) => println!(
+                    "{} at {at}: {}, created {}, changed {}",
+                    state.email, state.status, state.created_at, state.updated_at
+                ),
+                None => println!("{email} had no subscription at {at}"),
+            }
+            for event in &history.events {
+                let status = event.status.map(|status| status.as_str()).unwrap_or("-");
+                let from = event.previous_email.as_deref().unwrap_or("-");
+                println!("{}\t{}\t{}\t{status}\t{from}", event.occurred_at, event.kind, event.email);
+            }
+        }
+        Command::RebuildSnapshots { all_tenants } => {
+            let corrected = if all_tenants {
+                tenant::scope(TenantScope::All, repository.rebuild_snapshots()).await?
+            } else {
+                repository.rebuild_snapshots().await?
+            };
+            println!("corrected {corrected} subscriptions from the subscriber history");
+        }
+        Command::Seed {
+            count,
+            domains,
+            seed,
+            active,
+            unsubscribed,
+            suppressed,
+        } => {
+            let plan = SeedPlan::new(count)
+                .with_domains(domains)
+                .with_mix(ActivityMix {
+                    active,
+                    unsubscribed,
+                    suppressed,
+                })
+                .with_seed(seed);
+            let summary = seed::seed(&service(settings, repository, pool), &plan).await?;
+            println!(
+                "created {}; active {}, unsubscribed {}, suppressed {}",
+                summary.created, summary.active, summary.unsubscribed, summary.suppressed
+            );
+        }
+        Command::Retention { all_tenants } => {
+            let service = service(settings, repository, pool).with_retention(settings.retention.rules()?);
+            let runs = if all_tenants {
+                tenant::scope(TenantScope::All, service.apply_retention()).await?
+            } else {
+                service.apply_retention().await?
+            };
+            println!("tenant\taction\tanonymized\tdeleted");
+            for run in runs {
+                println!("{}\t{}\t{}\t{}", run.tenant, run.action, run.purge.anonymized, run.purge.deleted);
+            }
+        }
+        Command::ServiceConfig => unreachable!("printed before connecting"),
+    }
+    Ok(())
+}
+
+/// The newsletter service as the server sets it up, for commands that write
+/// subscriptions
+fn service(
+    settings: &Settings,
+    repository: Arc<PostgresNewsletterRepository>,
+    pool: PgPool,
+) -> DefaultNewsletterService<PostgresNewsletterRepository> {
+    let confirmation = ConfirmationConfig {
+        signer: TokenSigner::new(settings.confirmation.secret.clone()),
+        ttl: settings.confirmation.ttl(),
+        confirm_url: settings.confirmation.url.clone(),
+        email_change_url: settings.confirmation.email_change_url.clone(),
+        topic_url: settings.confirmation.topic_url.clone(),
+        required_on_resubscribe: settings.confirmation.required_on_resubscribe,
+        pending_retention: settings.confirmation.pending_retention(),
+    };
+    DefaultNewsletterService::new(repository, confirmation, Arc::new(PostgresJobRepository::new(pool)))
+        .with_normalization(settings.normalization.rules())
+}
+
+fn open(path: &Path) -> io::Result<Box<dyn Read>> {
+    if path.as_os_str() == "-" {
+        Ok(Box::new(io::stdin()))
+    } else {
+        Ok(Box::new(std::fs::File::open(path)?))
+    }
+}
+
+fn create(path: &Path) -> io::Result<Box<dyn Write>> {
+    if path.as_os_str() == "-" {
+        Ok(Box::new(io::stdout()))
+    } else {
+        Ok(Box::new(std::fs::File::create(path)?))
+    }
+}
@@ -0,0 +1,145 @@
+use std::fmt;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+/// How long after midnight UTC a day is left open for changes that were
+/// still committing when it ended
+pub const EXPORT_GRACE: Duration = Duration::minutes(15);
+
+/// Most days of one dataset exported in a single run, so catching up on a
+/// long backlog is spread over several runs
+pub const MAX_DAYS_PER_RUN: usize = 7;
+
+/// Tables copied to the warehouse, one Parquet file per UTC day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dataset {
+    /// Opens and clicks recorded for campaigns
+    Engagement,
+    /// The subscriber history: every subscription event written
+    SubscriptionEvents,
+}
+
+impl Dataset {
+    pub const ALL: [Dataset; 2] = [Dataset::Engagement, Dataset::SubscriptionEvents];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Dataset::Engagement => "engagement_events",
+            Dataset::SubscriptionEvents => "subscription_events",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "engagement_events" => Some(Dataset::Engagement),
+            "subscription_events" => Some(Dataset::SubscriptionEvents),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Dataset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An open or click, as exported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngagementRecord {
+    pub tenant: String,
+    pub campaign_id: i64,
+    pub email: String,
+    pub kind: String,
+    pub url: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// A subscription event from the subscriber history, as exported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionEventRecord {
+    pub tenant: String,
+    pub event_type: String,
+    pub email: String,
+    pub previous_email: Option<String>,
+    pub status: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Every row of one dataset from one day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DailyRows {
+    Engagement(Vec<EngagementRecord>),
+    SubscriptionEvents(Vec<SubscriptionEventRecord>),
+}
+
+impl DailyRows {
+    pub fn dataset(&self) -> Dataset {
+        match self {
+            DailyRows::Engagement(_) => Dataset::Engagement,
+            DailyRows::SubscriptionEvents(_) => Dataset::SubscriptionEvents,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            DailyRows::Engagement(rows) => rows.len(),
+            DailyRows::SubscriptionEvents(rows) => rows.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Replace every address with `pseudonym(address)`
+    pub fn map_emails(&mut self, pseudonym: impl Fn(&str) -> String) {
+        match self {
+            DailyRows::Engagement(rows) => {
+                for row in rows {
+                    row.email = pseudonym(&row.email);
+                }
+            }
+            DailyRows::SubscriptionEvents(rows) => {
+                for row in rows {
+                    row.email = pseudonym(&row.email);
+                    row.previous_email = row.previous_email.as_deref().map(&pseudonym);
+                }
+            }
+        }
+    }
+}
+
+/// Hive-style partition of a dataset's day, `engagement_events/date=2026-10-16`
+pub fn partition(dataset: Dataset, day: NaiveDate) -> String {
+    format!("{dataset}/date={}", day.format("%Y-%m-%d"))
+}
+
+/// Last day that has ended, grace included, at `now`
+pub fn last_finished_day(now: DateTime<Utc>) -> Option<NaiveDate> {
+    (now - EXPORT_GRACE).date_naive().pred_opt()
+}
+
+/// Days still to export, oldest first and at most `MAX_DAYS_PER_RUN`: those
+/// after the last one exported, or from the day of the oldest row when
+/// nothing was exported yet, up to `last_finished`. Days without rows in
+/// between are included, so every partition up to the last one exists.
+pub fn days_to_export(
+    last_exported: Option<NaiveDate>,
+    first_row: Option<NaiveDate>,
+    last_finished: NaiveDate,
+) -> Vec<NaiveDate> {
+    let start = match (last_exported, first_row) {
+        (Some(day), _) => day.succ_opt(),
+        (None, first) => first,
+    };
+    let Some(start) = start else {
+        return Vec::new();
+    };
+
+    start
+        .iter_days()
+        .take_while(|day| *day <= last_finished)
+        .take(MAX_DAYS_PER_RUN)
+        .collect()
+}
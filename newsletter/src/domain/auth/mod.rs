@@ -0,0 +1,65 @@
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+
+use crate::domain::tenant::TenantId;
+
+/// What an API key is allowed to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Scope {
+    /// Read-only methods
+    Read,
+    /// Every method, including writes and admin operations
+    Admin,
+    /// Every method plus the runtime operations of the admin service
+    Operator,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Admin => "admin",
+            Scope::Operator => "operator",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(Scope::Read),
+            "admin" => Some(Scope::Admin),
+            "operator" => Some(Scope::Operator),
+            _ => None,
+        }
+    }
+
+    /// Whether a key with this scope may call a method requiring `required`
+    pub fn allows(&self, required: Scope) -> bool {
+        *self >= required
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An active API key, as seen by the service after authentication
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKey {
+    pub id: i64,
+    pub name: String,
+    pub scope: Scope,
+    /// Tenant the key is confined to; unbound keys pick one per call
+    pub tenant: Option<TenantId>,
+}
+
+/// Hex SHA-256 under which a key is stored. Keys are long random strings, so
+/// a fast unsalted hash is enough to keep them out of the database.
+pub fn hash_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::domain::newsletter::Tag;
+
+use super::CampaignError;
+
+/// Matching addresses an audience estimate shows
+pub const AUDIENCE_SAMPLE_SIZE: i64 = 10;
+
+/// Longest activity window an estimate may look back over
+pub const MAX_ACTIVITY_DAYS: i64 = 365;
+
+/// Active subscribers a campaign could be sent to; each set condition
+/// narrows the audience further
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudienceFilter {
+    /// Subscribers carrying every one of these tags
+    pub tags: Vec<Tag>,
+    /// Subscribers receiving every one of these topics, chosen or by default
+    pub topics: Vec<String>,
+    /// Subscribers who opened or clicked a campaign since then
+    pub engaged_since: Option<DateTime<Utc>>,
+    /// Subscribers at this mail domain, in lowercase
+    pub domain: Option<String>,
+}
+
+impl AudienceFilter {
+    /// Only keep subscribers who engaged within the last `days`
+    pub fn engaged_within(mut self, days: i64, now: DateTime<Utc>) -> Result<Self, CampaignError> {
+        if !(1..=MAX_ACTIVITY_DAYS).contains(&days) {
+            return Err(CampaignError::Validation(format!(
+                "the activity window must be 1 to {MAX_ACTIVITY_DAYS} days"
+            )));
+        }
+        self.engaged_since = Some(now - Duration::days(days));
+        Ok(self)
+    }
+
+    /// Only keep subscribers at `domain`, given with or without its `@`
+    pub fn at_domain(mut self, domain: &str) -> Result<Self, CampaignError> {
+        let domain = domain.trim().trim_start_matches('@').to_ascii_lowercase();
+        if domain.is_empty() || !domain.contains('.') || domain.contains('@') {
+            return Err(CampaignError::Validation(format!("{domain:?} is not a mail domain")));
+        }
+        self.domain = Some(domain);
+        Ok(self)
+    }
+}
+
+/// How many subscribers an audience holds, with the first few of them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudienceEstimate {
+    pub recipients: i64,
+    /// Up to [`AUDIENCE_SAMPLE_SIZE`] matching addresses, in order
+    pub sample: Vec<String>,
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+
+/// Largest complaint report accepted, headers and returned message included
+pub const MAX_REPORT_BYTES: usize = 256 * 1024;
+
+/// Feedback types registered for ARF; anything else is kept as `other`
+const FEEDBACK_TYPES: &[&str] = &["abuse", "auth-failure", "fraud", "not-spam", "other", "virus"];
+
+/// Why a complaint report could not be used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComplaintError {
+    /// Not an RFC 5965 feedback report
+    Malformed(&'static str),
+    /// A valid report about an email this service did not send, or one
+    /// whose unsubscribe link was stripped
+    UnknownRecipient,
+}
+
+impl fmt::Display for ComplaintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComplaintError::Malformed(reason) => write!(f, "malformed feedback report: {reason}"),
+            ComplaintError::UnknownRecipient => write!(f, "the reported email carries no unsubscribe link of ours"),
+        }
+    }
+}
+
+impl std::error::Error for ComplaintError {}
+
+/// The machine-readable part of an ARF (RFC 5965) feedback report, with the
+/// reported message as returned by the mailbox provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedbackReport {
+    /// `abuse` for "this is spam", or another registered type such as
+    /// `fraud` or `virus`; unregistered ones become `other`
+    pub feedback_type: String,
+    /// The provider's software that generated the report
+    pub user_agent: Option<String>,
+    /// Recipient of the reported email; providers often redact it
+    pub original_rcpt_to: Option<String>,
+    /// Headers, and possibly the body, of the reported email
+    pub original_message: String,
+}
+
+impl FeedbackReport {
+    /// Parse a `multipart/report; report-type=feedback-report` message
+    pub fn parse(raw: &str) -> Result<Self, ComplaintError> {
+        if raw.len() > MAX_REPORT_BYTES {
+            return Err(ComplaintError::Malformed("the report is too large"));
+        }
+
+        let (headers, body) = split_message(raw);
+        let content_type = header(headers, "content-type")
+            .ok_or(ComplaintError::Malformed("the report has no content type"))?;
+        let lowered = content_type.to_ascii_lowercase();
+        if !lowered.starts_with("multipart/report") || !lowered.contains("feedback-report") {
+            return Err(ComplaintError::Malformed("the report is not a multipart/report feedback report"));
+        }
+        let boundary = parameter(&content_type, "boundary")
+            .ok_or(ComplaintError::Malformed("the report has no MIME boundary"))?;
+
+        let mut feedback = None;
+        let mut original_message = String::new();
+        for part in parts(body, &boundary) {
+            let (part_headers, part_body) = split_message(part);
+            let part_type = header(part_headers, "content-type").unwrap_or_default().to_ascii_lowercase();
+            if part_type.starts_with("message/feedback-report") {
+                feedback = Some(part_body);
+            } else if part_type.starts_with("message/rfc822") || part_type.starts_with("text/rfc822-headers") {
+                original_message = part_body.to_string();
+            }
+        }
+        let feedback = feedback.ok_or(ComplaintError::Malformed("the report has no message/feedback-report part"))?;
+
+        let feedback_type = header(feedback, "feedback-type")
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| !value.is_empty())
+            .ok_or(ComplaintError::Malformed("the feedback report has no Feedback-Type"))?;
+        let feedback_type = if FEEDBACK_TYPES.contains(&feedback_type.as_str()) {
+            feedback_type
+        } else {
+            "other".to_string()
+        };
+
+        Ok(Self {
+            feedback_type,
+            user_agent: header(feedback, "user-agent"),
+            original_rcpt_to: header(feedback, "original-rcpt-to")
+                .map(|value| value.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+                .filter(|value| !value.is_empty()),
+            original_message,
+        })
+    }
+}
+
+/// A subscriber's spam complaint about one campaign
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Complaint {
+    pub campaign_id: i64,
+    pub email: String,
+    pub feedback_type: String,
+    pub user_agent: Option<String>,
+}
+
+/// Headers and body of a message or MIME part
+fn split_message(raw: &str) -> (&str, &str) {
+    let raw = raw.trim_start_matches(['\r', '\n']);
+    match (raw.find("\r\n\r\n"), raw.find("\n\n")) {
+        (Some(crlf), Some(lf)) if lf < crlf => (&raw[..lf], &raw[lf + 2..]),
+        (Some(crlf), _) => (&raw[..crlf], &raw[crlf + 4..]),
+        (None, Some(lf)) => (&raw[..lf], &raw[lf + 2..]),
+        (None, None) => (raw, ""),
+    }
+}
+
+/// Unfolded value of the first header called `name`, ignoring case
+fn header(headers: &str, name: &str) -> Option<String> {
+    let mut value: Option<String> = None;
+    for line in headers.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with([' ', '\t']) {
+            if let Some(value) = value.as_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        if value.is_some() {
+            break;
+        }
+        if let Some((field, rest)) = line.split_once(':') {
+            if field.trim().eq_ignore_ascii_case(name) {
+                value = Some(rest.trim().to_string());
+            }
+        }
+    }
+    value
+}
+
+/// A `name=value` parameter of a header value, unquoted
+fn parameter(value: &str, name: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// The parts of a multipart body, without their delimiters
+fn parts<'a>(body: &'a str, boundary: &str) -> Vec<&'a str> {
+    let delimiter = format!("--{boundary}");
+    let mut parts: Vec<&str> = body.split(delimiter.as_str()).skip(1).collect();
+    if let Some(last) = parts.last() {
+        if last.starts_with("--") {
+            parts.pop();
+        }
+    }
+    parts
+}
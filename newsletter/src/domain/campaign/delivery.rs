@@ -0,0 +1,96 @@
+use std::fmt;
+
+use crate::domain::newsletter::attributes::Attributes;
+
+/// Sends of one campaign to one address before it is given up on
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 3;
+
+/// Progress of a campaign to one recipient
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// Not sent yet, or waiting to be retried
+    Pending,
+    /// Claimed by a sender
+    Sending,
+    Sent,
+    Failed,
+    /// The address unsubscribed or was deactivated before its turn
+    Skipped,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Sending => "sending",
+            DeliveryStatus::Sent => "sent",
+            DeliveryStatus::Failed => "failed",
+            DeliveryStatus::Skipped => "skipped",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(DeliveryStatus::Pending),
+            "sending" => Some(DeliveryStatus::Sending),
+            "sent" => Some(DeliveryStatus::Sent),
+            "failed" => Some(DeliveryStatus::Failed),
+            "skipped" => Some(DeliveryStatus::Skipped),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A claimed delivery with what is needed to personalize it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipient {
+    pub email: String,
+    pub attributes: Attributes,
+    /// Picks the template translation sent
+    pub locale: Option<String>,
+    /// Earlier failed sends
+    pub attempts: i32,
+}
+
+/// What happened when sending to one recipient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeliveryResult {
+    Sent,
+    /// Failed in a way worth trying again later
+    Retry(String),
+    Failed(String),
+}
+
+impl DeliveryResult {
+    /// A retryable failure becomes final once the recipient is out of attempts
+    pub fn transient(recipient: &Recipient, error: String) -> Self {
+        if recipient.attempts + 1 >= MAX_DELIVERY_ATTEMPTS {
+            DeliveryResult::Failed(error)
+        } else {
+            DeliveryResult::Retry(error)
+        }
+    }
+}
+
+/// Deliveries of a campaign per status
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeliveryCounts {
+    pub pending: i64,
+    pub sending: i64,
+    pub sent: i64,
+    pub failed: i64,
+    pub skipped: i64,
+}
+
+impl DeliveryCounts {
+    /// Whether every delivery reached a final status
+    pub fn is_finished(&self) -> bool {
+        self.pending == 0 && self.sending == 0
+    }
+}
@@ -0,0 +1,99 @@
+use std::cmp::Reverse;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::campaign::{CampaignError, NewCampaign};
+use crate::domain::tenant::TenantId;
+
+/// Encoding of a digest's content source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceFormat {
+    /// RSS or Atom feed
+    Feed,
+    /// JSON API answering `{"items": [{"title", "url", "summary", "published_at"}]}`
+    Json,
+}
+
+impl fmt::Display for SourceFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceFormat::Feed => f.write_str("feed"),
+            SourceFormat::Json => f.write_str("json"),
+        }
+    }
+}
+
+/// One entry of a digest, as handed to its template under `items`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestItem {
+    pub title: String,
+    pub url: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Periodic campaign to the subscribers of one topic, assembled from the
+/// newest entries of a content source
+#[derive(Debug, Clone)]
+pub struct Digest {
+    pub topic: String,
+    pub tenant: TenantId,
+    /// When digests are assembled, in UTC
+    pub schedule: Schedule,
+    pub source_url: String,
+    pub source_format: SourceFormat,
+    pub template_id: i64,
+    pub subject: String,
+    /// Most entries in one digest, newest first
+    pub max_items: usize,
+    /// How long after assembly the campaign may still start sending
+    pub send_window: Duration,
+}
+
+impl Digest {
+    /// Parse a cron expression with seconds: `sec min hour day month weekday [year]`
+    pub fn parse_schedule(expression: &str) -> Result<Schedule, CampaignError> {
+        Schedule::from_str(expression)
+            .map_err(|e| CampaignError::Validation(format!("invalid digest schedule {expression:?}: {e}")))
+    }
+
+    /// First time the digest is due strictly after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.schedule.after(&after).next()
+    }
+
+    /// Entries for a digest due now: those published since the previous one
+    /// (all of them on the first run, or when the source gives no dates),
+    /// newest first, at most `max_items`
+    pub fn select(&self, mut items: Vec<DigestItem>, since: Option<DateTime<Utc>>) -> Vec<DigestItem> {
+        if let Some(since) = since {
+            items.retain(|item| item.published_at.is_none_or(|published| published > since));
+        }
+        items.sort_by_key(|item| Reverse(item.published_at));
+        items.truncate(self.max_items);
+        items
+    }
+
+    /// Campaign sending `items` to the topic's subscribers
+    pub fn campaign(&self, items: &[DigestItem], due_at: DateTime<Utc>) -> NewCampaign {
+        NewCampaign {
+            name: format!("{} digest {}", self.topic, due_at.format("%Y-%m-%d %H:%M")),
+            subject: self.subject.clone(),
+            template_id: self.template_id,
+            topic: Some(self.topic.clone()),
+            tag: None,
+            variables: serde_json::json!({
+                "topic": self.topic,
+                "items": items,
+            }),
+            local_send_hours: None,
+        }
+    }
+}
@@ -0,0 +1,111 @@
+/// What a recipient did with a campaign email
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngagementKind {
+    /// The tracking pixel was loaded
+    Open,
+    /// A tracked link was followed
+    Click,
+}
+
+impl EngagementKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EngagementKind::Open => "open",
+            EngagementKind::Click => "click",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "open" => Some(EngagementKind::Open),
+            "click" => Some(EngagementKind::Click),
+            _ => None,
+        }
+    }
+}
+
+/// One open or click by a campaign recipient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngagementEvent {
+    pub campaign_id: i64,
+    pub email: String,
+    pub kind: EngagementKind,
+    /// Link target of a click
+    pub url: Option<String>,
+}
+
+/// Opens and clicks of a campaign against the number of emails sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngagementStats {
+    /// Recipients the campaign was sent to
+    pub sent: i64,
+    pub opens: i64,
+    /// Recipients who opened at least once
+    pub unique_opens: i64,
+    pub clicks: i64,
+    /// Recipients who clicked at least once
+    pub unique_clicks: i64,
+    /// Recipients who reported the email as spam
+    pub complaints: i64,
+}
+
+impl EngagementStats {
+    /// Share of recipients who opened the email
+    pub fn open_rate(&self) -> f64 {
+        Self::rate(self.unique_opens, self.sent)
+    }
+
+    /// Share of recipients who followed a link
+    pub fn click_rate(&self) -> f64 {
+        Self::rate(self.unique_clicks, self.sent)
+    }
+
+    /// Share of recipients who reported the email as spam; mailbox
+    /// providers start filtering senders above about 0.3%
+    pub fn complaint_rate(&self) -> f64 {
+        Self::rate(self.complaints, self.sent)
+    }
+
+    fn rate(count: i64, sent: i64) -> f64 {
+        if sent > 0 {
+            count as f64 / sent as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Clicks on one link of a campaign
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkEngagement {
+    pub url: String,
+    pub clicks: i64,
+    pub unique_clicks: i64,
+}
+
+/// Subscribers and campaign outcomes of one email domain, across all campaigns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainStats {
+    /// The part of the addresses after the `@`
+    pub domain: String,
+    /// Active subscriptions
+    pub subscribers: i64,
+    /// Deliveries that went out
+    pub sent: i64,
+    /// Deliveries the provider refused for good
+    pub bounced: i64,
+    /// Sent deliveries opened at least once
+    pub unique_opens: i64,
+}
+
+impl DomainStats {
+    /// Share of send attempts that bounced
+    pub fn bounce_rate(&self) -> f64 {
+        EngagementStats::rate(self.bounced, self.sent + self.bounced)
+    }
+
+    /// Share of sent emails that were opened
+    pub fn open_rate(&self) -> f64 {
+        EngagementStats::rate(self.unique_opens, self.sent)
+    }
+}
@@ -0,0 +1,352 @@
+use std::fmt;
+
+use chrono::{DateTime, Days, Duration, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::newsletter::attributes::{merge_context, Attributes};
+
+pub mod audience;
+pub mod complaint;
+pub mod delivery;
+pub mod digest;
+pub mod engagement;
+pub mod reengagement;
+pub mod sending_domain;
+pub mod test_send;
+pub mod warmup;
+
+/// Lifecycle of a campaign
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CampaignStatus {
+    Draft,
+    Scheduled,
+    Sending,
+    Sent,
+    Cancelled,
+}
+
+impl CampaignStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CampaignStatus::Draft => "draft",
+            CampaignStatus::Scheduled => "scheduled",
+            CampaignStatus::Sending => "sending",
+            CampaignStatus::Sent => "sent",
+            CampaignStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "draft" => Some(CampaignStatus::Draft),
+            "scheduled" => Some(CampaignStatus::Scheduled),
+            "sending" => Some(CampaignStatus::Sending),
+            "sent" => Some(CampaignStatus::Sent),
+            "cancelled" => Some(CampaignStatus::Cancelled),
+            _ => None,
+        }
+    }
+
+    /// Draft and scheduled campaigns can still be edited, rescheduled or cancelled
+    pub fn is_editable(&self) -> bool {
+        matches!(self, CampaignStatus::Draft | CampaignStatus::Scheduled)
+    }
+}
+
+impl fmt::Display for CampaignStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Errors raised by campaign invariants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CampaignError {
+    /// A field failed validation
+    Validation(String),
+    /// The requested change is not allowed in the current status
+    InvalidTransition {
+        from: CampaignStatus,
+        action: &'static str,
+    },
+    /// The campaign was saved by someone else since it was read
+    VersionMismatch { expected: i64, actual: i64 },
+    /// A test send went to an address outside the allow-list
+    RecipientNotAllowed(String),
+    /// The campaign's template was deleted
+    TemplateNotFound(i64),
+}
+
+impl fmt::Display for CampaignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CampaignError::Validation(message) => write!(f, "{message}"),
+            CampaignError::InvalidTransition { from, action } => {
+                write!(f, "cannot {action} a campaign in status {from}")
+            }
+            CampaignError::VersionMismatch { expected, actual } => {
+                write!(f, "campaign is at version {actual}, not {expected}")
+            }
+            CampaignError::RecipientNotAllowed(email) => {
+                write!(f, "{email} is not an allowed test recipient")
+            }
+            CampaignError::TemplateNotFound(id) => write!(f, "template {id} not found"),
+        }
+    }
+}
+
+impl std::error::Error for CampaignError {}
+
+/// Time range during which a campaign may be sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SendWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl SendWindow {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Self, CampaignError> {
+        if end <= start {
+            return Err(CampaignError::Validation(
+                "send window end must be after its start".to_string(),
+            ));
+        }
+
+        Ok(Self { start, end })
+    }
+}
+
+/// Hours of each recipient's own day a campaign may arrive in, such as 8 to
+/// 11 for their morning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalSendHours {
+    /// Hour the window opens, from 0 to 23
+    pub start: u32,
+    /// Hour the window closes, after `start` and at most 24
+    pub end: u32,
+}
+
+impl LocalSendHours {
+    pub fn new(start: u32, end: u32) -> Result<Self, CampaignError> {
+        if start >= end || end > 24 {
+            return Err(CampaignError::Validation(
+                "local send hours must open before they close, within 0 to 24".to_string(),
+            ));
+        }
+
+        Ok(Self { start, end })
+    }
+
+    /// When a recipient living in `tz` may be sent to; `None` while `now` is
+    /// inside their window. Outside it, the next opening: later today before
+    /// the window, tomorrow after it.
+    pub fn delay_until(&self, now: DateTime<Utc>, tz: Tz) -> Option<DateTime<Utc>> {
+        let local = now.with_timezone(&tz);
+        if (self.start..self.end).contains(&local.hour()) {
+            return None;
+        }
+
+        let day = if local.hour() < self.start {
+            local.date_naive()
+        } else {
+            local.date_naive().checked_add_days(Days::new(1))?
+        };
+        let opening = day.and_hms_opt(self.start, 0, 0)?;
+        // An opening skipped by a daylight saving change moves an hour on
+        let opening = tz
+            .from_local_datetime(&opening)
+            .earliest()
+            .or_else(|| tz.from_local_datetime(&(opening + Duration::hours(1))).earliest())?;
+        Some(opening.with_timezone(&Utc))
+    }
+}
+
+/// Campaign aggregate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Campaign {
+    pub id: i64,
+    pub name: String,
+    pub subject: String,
+    pub template_id: i64,
+    pub status: CampaignStatus,
+    pub send_window: Option<SendWindow>,
+    /// Only subscribers receiving this topic get the campaign; everyone
+    /// active when `None`
+    pub topic: Option<String>,
+    /// Only subscribers carrying this tag get the campaign, on top of the
+    /// topic
+    pub tag: Option<String>,
+    /// Template variables shared by all recipients, next to `email` and
+    /// `attributes`
+    pub variables: serde_json::Value,
+    /// Holds each delivery until these hours of the recipient's own day;
+    /// recipients without a timezone are sent at once
+    pub local_send_hours: Option<LocalSendHours>,
+    /// Bumped by every save, which only applies on top of the version read
+    pub version: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to create a campaign
+#[derive(Debug, Clone)]
+pub struct NewCampaign {
+    pub name: String,
+    pub subject: String,
+    pub template_id: i64,
+    pub topic: Option<String>,
+    pub tag: Option<String>,
+    pub variables: serde_json::Value,
+    pub local_send_hours: Option<LocalSendHours>,
+}
+
+impl NewCampaign {
+    /// A campaign for every active subscriber, without shared variables
+    pub fn new(name: String, subject: String, template_id: i64) -> Self {
+        Self {
+            name,
+            subject,
+            template_id,
+            topic: None,
+            tag: None,
+            variables: serde_json::json!({}),
+            local_send_hours: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), CampaignError> {
+        validate_text("name", &self.name)?;
+        validate_text("subject", &self.subject)?;
+        if let Some(topic) = &self.topic {
+            validate_text("topic", topic)?;
+        }
+        if let Some(tag) = &self.tag {
+            validate_text("tag", tag)?;
+        }
+        if !self.variables.is_object() {
+            return Err(CampaignError::Validation("variables must be an object".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Partial update of a campaign; `None` leaves the field untouched
+#[derive(Debug, Clone, Default)]
+pub struct CampaignUpdate {
+    pub name: Option<String>,
+    pub subject: Option<String>,
+    pub template_id: Option<i64>,
+    /// `Some(None)` sends at any hour again
+    pub local_send_hours: Option<Option<LocalSendHours>>,
+    /// Refuse the update unless the campaign is still at this version
+    pub expected_version: Option<i64>,
+}
+
+impl Campaign {
+    /// Render context of one recipient: the campaign's variables, with the
+    /// recipient's `email` and `attributes` winning over variables of the
+    /// same name
+    pub fn render_context(&self, email: &str, attributes: &Attributes) -> serde_json::Value {
+        let mut context = merge_context(email, attributes);
+        if let (Some(context), Some(variables)) = (context.as_object_mut(), self.variables.as_object()) {
+            for (key, value) in variables {
+                context.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+        context
+    }
+
+    pub fn apply_update(&mut self, update: CampaignUpdate) -> Result<(), CampaignError> {
+        if let Some(expected) = update.expected_version {
+            if expected != self.version {
+                return Err(CampaignError::VersionMismatch {
+                    expected,
+                    actual: self.version,
+                });
+            }
+        }
+        self.ensure_editable("update")?;
+
+        if let Some(name) = update.name {
+            validate_text("name", &name)?;
+            self.name = name;
+        }
+        if let Some(subject) = update.subject {
+            validate_text("subject", &subject)?;
+            self.subject = subject;
+        }
+        if let Some(template_id) = update.template_id {
+            self.template_id = template_id;
+        }
+        if let Some(hours) = update.local_send_hours {
+            self.local_send_hours = hours;
+        }
+
+        Ok(())
+    }
+
+    pub fn schedule(&mut self, window: SendWindow) -> Result<(), CampaignError> {
+        self.ensure_editable("schedule")?;
+
+        self.send_window = Some(window);
+        self.status = CampaignStatus::Scheduled;
+        Ok(())
+    }
+
+    /// Move a scheduled campaign to sending once its window has opened
+    pub fn start_sending(&mut self, now: DateTime<Utc>) -> Result<(), CampaignError> {
+        let opened = self.status == CampaignStatus::Scheduled
+            && self
+                .send_window
+                .is_some_and(|window| window.start <= now && now < window.end);
+        if !opened {
+            return Err(CampaignError::InvalidTransition {
+                from: self.status,
+                action: "start sending",
+            });
+        }
+
+        self.status = CampaignStatus::Sending;
+        Ok(())
+    }
+
+    /// Mark a sending campaign as sent once every delivery is final
+    pub fn finish_sending(&mut self) -> Result<(), CampaignError> {
+        if self.status != CampaignStatus::Sending {
+            return Err(CampaignError::InvalidTransition {
+                from: self.status,
+                action: "finish sending",
+            });
+        }
+
+        self.status = CampaignStatus::Sent;
+        Ok(())
+    }
+
+    pub fn cancel(&mut self) -> Result<(), CampaignError> {
+        self.ensure_editable("cancel")?;
+
+        self.status = CampaignStatus::Cancelled;
+        Ok(())
+    }
+
+    fn ensure_editable(&self, action: &'static str) -> Result<(), CampaignError> {
+        if self.status.is_editable() {
+            Ok(())
+        } else {
+            Err(CampaignError::InvalidTransition {
+                from: self.status,
+                action,
+            })
+        }
+    }
+}
+
+fn validate_text(field: &str, value: &str) -> Result<(), CampaignError> {
+    if value.trim().is_empty() {
+        return Err(CampaignError::Validation(format!("{field} cannot be empty")));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use cron::Schedule;
+
+use crate::domain::campaign::{CampaignError, NewCampaign};
+use crate::domain::newsletter::{InvalidTag, Tag};
+use crate::domain::tenant::TenantId;
+
+/// Win-back flow of one tenant.
+///
+/// Active subscribers who were mailed but neither opened nor clicked
+/// anything for `inactive_after` are tagged into the `segment` and sent a
+/// win-back campaign. Members who engage again leave the segment; those
+/// still silent once `grace` has passed are unsubscribed.
+#[derive(Debug, Clone)]
+pub struct Reengagement {
+    pub tenant: TenantId,
+    /// When the flow runs, in UTC
+    pub schedule: Schedule,
+    pub inactive_after: Duration,
+    pub grace: Duration,
+    /// Tag marking the members of the segment
+    pub segment: Tag,
+    pub template_id: i64,
+    pub subject: String,
+    /// How long after a run the win-back campaign may still start sending
+    pub send_window: Duration,
+}
+
+/// A subscriber in a re-engagement segment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentMember {
+    pub email: String,
+    /// When the subscriber was tagged into the segment
+    pub joined_at: DateTime<Utc>,
+    /// Whether the subscription is still active
+    pub active: bool,
+    /// Whether the subscriber opened or clicked anything since joining
+    pub engaged: bool,
+}
+
+/// What a run does with the current members of a segment
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SegmentReview {
+    /// Members who engaged again or are no longer active; they only leave
+    pub released: Vec<String>,
+    /// Members whose grace period ran out without engagement
+    pub lapsed: Vec<String>,
+}
+
+impl Reengagement {
+    /// Parse a cron expression with seconds: `sec min hour day month weekday [year]`
+    pub fn parse_schedule(expression: &str) -> Result<Schedule, CampaignError> {
+        Schedule::from_str(expression)
+            .map_err(|e| CampaignError::Validation(format!("invalid re-engagement schedule {expression:?}: {e}")))
+    }
+
+    /// First time the flow is due strictly after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.schedule.after(&after).next()
+    }
+
+    /// Subscribers without an open or click since this point are inactive
+    pub fn inactive_since(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - self.inactive_after
+    }
+
+    /// Tag of the subscribers who joined the segment in the run due at
+    /// `due_at`; the win-back campaign of that run goes to them alone
+    pub fn cohort(&self, due_at: DateTime<Utc>) -> Result<Tag, InvalidTag> {
+        Tag::parse(&format!("{}-{}", self.segment, due_at.format("%Y%m%d%H%M")))
+    }
+
+    /// Sort the segment's members into those who leave it and those who
+    /// are unsubscribed; everyone else is still within their grace period
+    pub fn review(&self, members: Vec<SegmentMember>, now: DateTime<Utc>) -> SegmentReview {
+        let mut review = SegmentReview::default();
+        for member in members {
+            if member.engaged || !member.active {
+                review.released.push(member.email);
+            } else if member.joined_at + self.grace <= now {
+                review.lapsed.push(member.email);
+            }
+        }
+        review
+    }
+
+    /// Win-back campaign to the cohort of the run due at `due_at`
+    pub fn campaign(&self, cohort: &Tag, due_at: DateTime<Utc>) -> NewCampaign {
+        NewCampaign {
+            name: format!("Re-engagement {}", due_at.format("%Y-%m-%d %H:%M")),
+            subject: self.subject.clone(),
+            template_id: self.template_id,
+            topic: None,
+            tag: Some(cohort.to_string()),
+            variables: serde_json::json!({
+                "unsubscribe_on": (due_at + self.grace).format("%Y-%m-%d").to_string(),
+            }),
+            local_send_hours: None,
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::newsletter::{validate_domain, EmailAddress};
+
+/// Errors raised by sending domain rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendingDomainError {
+    /// A field failed validation
+    Validation(String),
+    /// The tenant already sends from a domain
+    AlreadyExists(String),
+    /// The tenant has no sending domain
+    NotFound,
+}
+
+impl fmt::Display for SendingDomainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendingDomainError::Validation(message) => write!(f, "{message}"),
+            SendingDomainError::AlreadyExists(domain) => write!(f, "campaigns are already sent from {domain}"),
+            SendingDomainError::NotFound => write!(f, "no sending domain is configured"),
+        }
+    }
+}
+
+impl std::error::Error for SendingDomainError {}
+
+/// A domain to send a tenant's campaigns from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewSendingDomain {
+    pub domain: String,
+    /// The `From` address of campaigns, at the domain or one of its subdomains
+    /// so DKIM signatures align with it
+    pub from_address: String,
+}
+
+impl NewSendingDomain {
+    /// Lowercase both fields and check the address belongs to the domain
+    pub fn parse(domain: &str, from_address: &str) -> Result<Self, SendingDomainError> {
+        let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+        validate_domain(&domain).map_err(|e| SendingDomainError::Validation(format!("domain: {e}")))?;
+
+        let from = EmailAddress::parse(from_address)
+            .map_err(|e| SendingDomainError::Validation(format!("from_address: {e}")))?;
+        let aligned = from.domain() == domain || from.domain().ends_with(&format!(".{domain}"));
+        if !aligned {
+            return Err(SendingDomainError::Validation(format!(
+                "from_address must be at {domain} or one of its subdomains"
+            )));
+        }
+
+        Ok(Self {
+            domain,
+            from_address: from.into_inner(),
+        })
+    }
+}
+
+/// Where a DKIM key is in its rotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DkimKeyStatus {
+    /// Generated, waiting for its DNS record to be published
+    Pending,
+    /// Messages are signed with it
+    Active,
+}
+
+impl DkimKeyStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DkimKeyStatus::Pending => "pending",
+            DkimKeyStatus::Active => "active",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(DkimKeyStatus::Pending),
+            "active" => Some(DkimKeyStatus::Active),
+            _ => None,
+        }
+    }
+}
+
+/// A freshly generated RSA key pair
+#[derive(Clone, PartialEq, Eq)]
+pub struct GeneratedKey {
+    pub selector: String,
+    /// PKCS#1 PEM
+    pub private_key: String,
+    /// Base64 of the DER `SubjectPublicKeyInfo`, as published in `p=`
+    pub public_key: String,
+}
+
+impl fmt::Debug for GeneratedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GeneratedKey")
+            .field("selector", &self.selector)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A DKIM key of a sending domain
+#[derive(Clone, PartialEq, Eq)]
+pub struct DkimKey {
+    pub id: i64,
+    pub selector: String,
+    /// PKCS#1 PEM; never leaves the service
+    pub private_key: String,
+    pub public_key: String,
+    pub status: DkimKeyStatus,
+    pub created_at: DateTime<Utc>,
+    pub activated_at: Option<DateTime<Utc>>,
+}
+
+impl fmt::Debug for DkimKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DkimKey")
+            .field("id", &self.id)
+            .field("selector", &self.selector)
+            .field("status", &self.status)
+            .field("created_at", &self.created_at)
+            .field("activated_at", &self.activated_at)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DkimKey {
+    /// The TXT record publishing the key under `domain`
+    pub fn dns_record(&self, domain: &str) -> DnsRecord {
+        DnsRecord {
+            name: format!("{}._domainkey.{domain}", self.selector),
+            kind: "TXT",
+            value: format!("v=DKIM1; k=rsa; p={}", self.public_key),
+            status: self.status,
+        }
+    }
+
+    /// Whether one of the TXT strings found at the key's name publishes it
+    pub fn published_in(&self, records: &[String]) -> bool {
+        records.iter().any(|record| {
+            record
+                .split(';')
+                .filter_map(|tag| tag.split_once('='))
+                .any(|(name, value)| {
+                    name.trim() == "p"
+                        && value.chars().filter(|c| !c.is_whitespace()).eq(self.public_key.chars())
+                })
+        })
+    }
+}
+
+/// A DNS record the tenant has to publish
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsRecord {
+    pub name: String,
+    pub kind: &'static str,
+    pub value: String,
+    /// The key the record publishes
+    pub status: DkimKeyStatus,
+}
+
+/// The domain a tenant's campaigns are sent from, with its DKIM keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendingDomain {
+    pub id: i64,
+    pub domain: String,
+    pub from_address: String,
+    pub created_at: DateTime<Utc>,
+    /// When a key of the domain was first found published
+    pub verified_at: Option<DateTime<Utc>>,
+    /// The key messages are signed with, once one was verified
+    pub active_key: Option<DkimKey>,
+    /// The key waiting for its DNS record, right after creation or a rotation
+    pub pending_key: Option<DkimKey>,
+}
+
+impl SendingDomain {
+    /// Records for the active key, and for the pending one while a rotation
+    /// is under way; both stay published until the rotation completes
+    pub fn dns_records(&self) -> Vec<DnsRecord> {
+        self.active_key
+            .iter()
+            .chain(&self.pending_key)
+            .map(|key| key.dns_record(&self.domain))
+            .collect()
+    }
+
+    /// What campaigns are sent as: the domain's address, signed with its
+    /// active key; `None` until the domain is verified
+    pub fn identity(&self) -> Option<SenderIdentity> {
+        let key = self.active_key.as_ref()?;
+        Some(SenderIdentity {
+            from: self.from_address.clone(),
+            domain: self.domain.clone(),
+            selector: key.selector.clone(),
+            private_key: key.private_key.clone(),
+        })
+    }
+}
+
+/// Outcome of looking up a domain's pending key in DNS
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainVerification {
+    pub domain: SendingDomain,
+    /// Whether campaigns are signed with the domain's newest key: its pending
+    /// key was found published and activated, or none was pending
+    pub verified: bool,
+}
+
+/// The address and DKIM key a verified domain signs campaigns with
+#[derive(Clone, PartialEq, Eq)]
+pub struct SenderIdentity {
+    pub from: String,
+    pub domain: String,
+    pub selector: String,
+    pub private_key: String,
+}
+
+impl fmt::Debug for SenderIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SenderIdentity")
+            .field("from", &self.from)
+            .field("domain", &self.domain)
+            .field("selector", &self.selector)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Selector of a key generated at `now`; new keys sort after older ones and
+/// never reuse a published name
+pub fn selector_at(now: DateTime<Utc>) -> String {
+    format!("nl{}", now.format("%Y%m%d%H%M%S"))
+}
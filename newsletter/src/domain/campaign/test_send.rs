@@ -0,0 +1,71 @@
+use crate::domain::newsletter::attributes::Attributes;
+
+use super::CampaignError;
+
+/// Most addresses a single test send goes to
+pub const MAX_TEST_RECIPIENTS: usize = 10;
+
+/// Internal addresses test sends may go to: each entry is a full address,
+/// or `@example.com` for everyone at a domain
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestRecipients {
+    entries: Vec<String>,
+}
+
+impl TestRecipients {
+    pub fn new(entries: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            entries: entries
+                .into_iter()
+                .map(|entry| entry.trim().to_ascii_lowercase())
+                .filter(|entry| !entry.is_empty())
+                .collect(),
+        }
+    }
+
+    pub fn allows(&self, email: &str) -> bool {
+        let email = email.trim().to_ascii_lowercase();
+        let domain = email.rfind('@').map(|at| &email[at..]);
+        self.entries
+            .iter()
+            .any(|entry| *entry == email || Some(entry.as_str()) == domain)
+    }
+
+    /// Reject an empty, oversized or not allow-listed set of recipients
+    pub fn check(&self, recipients: &[String]) -> Result<(), CampaignError> {
+        if recipients.is_empty() {
+            return Err(CampaignError::Validation("at least one test recipient is required".to_string()));
+        }
+        if recipients.len() > MAX_TEST_RECIPIENTS {
+            return Err(CampaignError::Validation(format!(
+                "at most {MAX_TEST_RECIPIENTS} test recipients are allowed"
+            )));
+        }
+        match recipients.iter().find(|email| !self.allows(email)) {
+            Some(email) => Err(CampaignError::RecipientNotAllowed(email.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A proof of a campaign sent to internal addresses
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestSend {
+    /// Where the proof goes; every address must be allow-listed
+    pub recipients: Vec<String>,
+    /// The subscriber the email is rendered for; the first recipient when unset
+    pub email: Option<String>,
+    pub attributes: Attributes,
+    /// The subscriber's locale, picking the translation a real send would
+    pub locale: Option<String>,
+}
+
+/// The email a test send rendered, and who it went to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestSendOutcome {
+    pub subject: String,
+    pub html: String,
+    /// The locale of the translation rendered; `None` for the template's own body
+    pub locale: Option<String>,
+    pub sent_to: Vec<String>,
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+
+/// Daily send caps of a domain warming up, one per day from the first day
+/// it sends; past the last one it sends without a cap
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WarmupSchedule {
+    daily_caps: Vec<i64>,
+}
+
+impl WarmupSchedule {
+    pub fn new(daily_caps: Vec<i64>) -> Self {
+        Self { daily_caps }
+    }
+
+    /// Whether any domain is capped at all
+    pub fn is_empty(&self) -> bool {
+        self.daily_caps.is_empty()
+    }
+
+    /// Positive and never falling, as a ramp should
+    pub fn is_valid(&self) -> bool {
+        self.daily_caps.iter().all(|cap| *cap > 0) && self.daily_caps.windows(2).all(|pair| pair[0] <= pair[1])
+    }
+
+    /// Sends allowed on `day` to a domain that started on `started_on`;
+    /// `None` once the ramp is over
+    pub fn cap(&self, started_on: NaiveDate, day: NaiveDate) -> Option<i64> {
+        let elapsed = (day - started_on).num_days().max(0);
+        usize::try_from(elapsed).ok().and_then(|i| self.daily_caps.get(i)).copied()
+    }
+}
+
+/// Which domains are new to sending and so ramp up.
+///
+/// A tenant's verified sending domain always is; the `EMAIL_FROM` domain has
+/// a reputation of its own and only ramps when listed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WarmupDomains {
+    /// Ramped although no tenant verified them, such as a fresh `EMAIL_FROM` domain
+    pub listed: Vec<String>,
+    /// Already sending in bulk elsewhere; never capped
+    pub skipped: Vec<String>,
+    /// Day a domain's ramp counts from, in place of the first day it sends
+    pub started_on: HashMap<String, NaiveDate>,
+}
+
+impl WarmupDomains {
+    /// The domain a campaign's sends count against, if it ramps: the tenant's
+    /// verified domain, or else `default` when listed
+    pub fn ramped<'a>(&self, verified: Option<&'a str>, default: Option<&'a str>) -> Option<&'a str> {
+        let domain = match verified {
+            Some(domain) => domain,
+            None => default.filter(|domain| self.listed.iter().any(|listed| listed == domain))?,
+        };
+        (!self.skipped.iter().any(|skipped| skipped == domain)).then_some(domain)
+    }
+}
+
+/// Sends set aside for one batch of a warming domain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarmupReservation {
+    pub domain: String,
+    pub day: NaiveDate,
+    /// At most the batch size; zero once the day's cap is used up
+    pub granted: i64,
+}
+
+/// When a domain capped today may send again
+pub fn next_day(now: DateTime<Utc>) -> DateTime<Utc> {
+    let tomorrow = now.date_naive().checked_add_days(Days::new(1)).unwrap_or(NaiveDate::MAX);
+    tomorrow.and_hms_opt(0, 0, 0).map_or(now, |midnight| midnight.and_utc())
+}
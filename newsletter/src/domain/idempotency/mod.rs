@@ -0,0 +1,53 @@
+use std::fmt;
+
+use serde_json::Value;
+
+/// Longest idempotency key accepted from clients
+pub const MAX_KEY_LEN: usize = 255;
+
+/// What is stored for an idempotency key
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdempotencyRecord {
+    /// Hash of the request that first used the key
+    pub request_hash: String,
+    /// Result of the first request; `None` while it is still being processed
+    pub response: Option<Value>,
+}
+
+/// Reasons an idempotent request cannot be served
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyError {
+    /// The key is empty, too long or not printable ASCII
+    InvalidKey,
+    /// The key was already used for a different request
+    Mismatch,
+    /// The first request with this key has not finished yet
+    InProgress,
+}
+
+impl fmt::Display for IdempotencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdempotencyError::InvalidKey => write!(
+                f,
+                "idempotency key must be 1 to {MAX_KEY_LEN} printable ASCII characters"
+            ),
+            IdempotencyError::Mismatch => {
+                write!(f, "idempotency key was already used for a different request")
+            }
+            IdempotencyError::InProgress => {
+                write!(f, "a request with this idempotency key is still in progress")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IdempotencyError {}
+
+/// Check a client-supplied key before it is stored
+pub fn validate_key(key: &str) -> Result<(), IdempotencyError> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN || !key.bytes().all(|b| b.is_ascii_graphic()) {
+        return Err(IdempotencyError::InvalidKey);
+    }
+    Ok(())
+}
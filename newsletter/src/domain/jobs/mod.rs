@@ -0,0 +1,242 @@
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::domain::tenant::{TenantId, TenantScope};
+
+/// Attempts a job gets unless it asks for a different number
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+
+/// Longest wait between attempts of a failing job
+pub const MAX_RETRY_DELAY: Duration = Duration::hours(1);
+
+/// Kinds of deferred work, each run by its own handler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobKind {
+    /// Send the double opt-in email for a pending subscription
+    SendConfirmation,
+    /// Send the link confirming a new address to that address
+    SendEmailChange,
+    /// Send the link confirming an opt-in to a topic that requires it
+    SendTopicConfirmation,
+    /// POST one subscription event to one webhook endpoint
+    DeliverWebhook,
+    /// Start sending a scheduled campaign once its window opens
+    DispatchCampaign,
+    /// Send the next throttled batch of a campaign
+    SendCampaignBatch,
+    /// Remove pending subscriptions whose confirmation expired
+    ExpirePending,
+    /// Build and schedule one topic's digest campaign when it comes due
+    AssembleDigest,
+    /// Move unengaged subscribers through a tenant's re-engagement flow
+    RunReengagement,
+    /// Snapshot each tenant's list growth for the day that just ended
+    RollupGrowth,
+    /// Anonymize or delete unsubscribed subscriptions past their retention
+    ApplyRetention,
+    /// Write the days that ended to the analytics bucket as Parquet
+    ExportAnalytics,
+    /// Delete template assets no template has referenced for a while
+    CollectAssets,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::SendConfirmation => "send_confirmation",
+            JobKind::SendEmailChange => "send_email_change",
+            JobKind::SendTopicConfirmation => "send_topic_confirmation",
+            JobKind::DeliverWebhook => "deliver_webhook",
+            JobKind::DispatchCampaign => "dispatch_campaign",
+            JobKind::SendCampaignBatch => "send_campaign_batch",
+            JobKind::ExpirePending => "expire_pending",
+            JobKind::AssembleDigest => "assemble_digest",
+            JobKind::RunReengagement => "run_reengagement",
+            JobKind::RollupGrowth => "rollup_growth",
+            JobKind::ApplyRetention => "apply_retention",
+            JobKind::ExportAnalytics => "export_analytics",
+            JobKind::CollectAssets => "collect_assets",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "send_confirmation" => Some(JobKind::SendConfirmation),
+            "send_email_change" => Some(JobKind::SendEmailChange),
+            "send_topic_confirmation" => Some(JobKind::SendTopicConfirmation),
+            "deliver_webhook" => Some(JobKind::DeliverWebhook),
+            "dispatch_campaign" => Some(JobKind::DispatchCampaign),
+            "send_campaign_batch" => Some(JobKind::SendCampaignBatch),
+            "expire_pending" => Some(JobKind::ExpirePending),
+            "assemble_digest" => Some(JobKind::AssembleDigest),
+            "run_reengagement" => Some(JobKind::RunReengagement),
+            "rollup_growth" => Some(JobKind::RollupGrowth),
+            "apply_retention" => Some(JobKind::ApplyRetention),
+            "export_analytics" => Some(JobKind::ExportAnalytics),
+            "collect_assets" => Some(JobKind::CollectAssets),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for JobKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A job to be queued
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewJob {
+    pub kind: JobKind,
+    pub payload: Value,
+    pub run_at: DateTime<Utc>,
+    pub max_attempts: i32,
+    /// While a pending job holds this key, enqueueing another with it is a no-op
+    pub unique_key: Option<String>,
+}
+
+impl NewJob {
+    /// A job due now with the default number of attempts
+    pub fn new(kind: JobKind, payload: &impl Serialize) -> serde_json::Result<Self> {
+        Ok(Self {
+            kind,
+            payload: serde_json::to_value(payload)?,
+            run_at: Utc::now(),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            unique_key: None,
+        })
+    }
+
+    pub fn run_at(mut self, run_at: DateTime<Utc>) -> Self {
+        self.run_at = run_at;
+        self
+    }
+
+    pub fn max_attempts(mut self, max_attempts: i32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn unique_key(mut self, key: impl Into<String>) -> Self {
+        self.unique_key = Some(key.into());
+        self
+    }
+}
+
+/// A job claimed by a worker
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    pub id: i64,
+    pub kind: JobKind,
+    pub payload: Value,
+    /// Failed attempts so far
+    pub attempts: i32,
+    pub max_attempts: i32,
+    /// Tenant the job was queued in, and runs in
+    pub tenant: TenantScope,
+}
+
+impl Job {
+    pub fn payload<T: for<'de> Deserialize<'de>>(&self) -> serde_json::Result<T> {
+        serde_json::from_value(self.payload.clone())
+    }
+
+    /// Whether this run is the job's final attempt
+    pub fn is_last_attempt(&self) -> bool {
+        self.attempts + 1 >= self.max_attempts
+    }
+
+    /// Wait before the next attempt after this one failed: 10 seconds doubled
+    /// per earlier failure, capped at `MAX_RETRY_DELAY`
+    pub fn retry_delay(&self) -> Duration {
+        let exponent = self.attempts.clamp(0, 12) as u32;
+        Duration::seconds(10 << exponent).min(MAX_RETRY_DELAY)
+    }
+}
+
+/// Payload of [`JobKind::SendConfirmation`] and [`JobKind::SendEmailChange`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SendConfirmation {
+    pub email: String,
+    /// Stored confirmation token; the signed link is rebuilt when the mail is sent
+    pub token_id: Uuid,
+}
+
+/// Payload of [`JobKind::SendTopicConfirmation`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SendTopicConfirmation {
+    pub email: String,
+    pub token_id: Uuid,
+    /// Name of the topic as shown to the reader
+    pub topic_name: String,
+}
+
+/// Payload of [`JobKind::DeliverWebhook`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeliverWebhook {
+    pub url: String,
+    pub event_type: String,
+    pub event: Value,
+}
+
+/// Payload of [`JobKind::DispatchCampaign`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DispatchCampaign {
+    pub campaign_id: i64,
+}
+
+/// Payload of [`JobKind::SendCampaignBatch`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SendCampaignBatch {
+    pub campaign_id: i64,
+    /// Position in the campaign's chain of batches, starting at 0
+    pub batch: u32,
+}
+
+impl SendCampaignBatch {
+    /// Queue this batch, unless a job for it is already pending
+    pub fn job(&self) -> serde_json::Result<NewJob> {
+        Ok(NewJob::new(JobKind::SendCampaignBatch, self)?
+            .unique_key(format!("campaign:{}:batch:{}", self.campaign_id, self.batch)))
+    }
+}
+
+/// Payload of [`JobKind::AssembleDigest`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssembleDigest {
+    pub topic: String,
+    pub due_at: DateTime<Utc>,
+    /// When the previous digest of the topic was due; its entries are skipped
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl AssembleDigest {
+    /// Queue this digest for when it is due, unless a job for that time is
+    /// already pending in the tenant
+    pub fn job(&self, tenant: &TenantId) -> serde_json::Result<NewJob> {
+        Ok(NewJob::new(JobKind::AssembleDigest, self)?
+            .run_at(self.due_at)
+            .unique_key(format!("digest:{tenant}:{}:{}", self.topic, self.due_at.timestamp())))
+    }
+}
+
+/// Payload of [`JobKind::RunReengagement`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunReengagement {
+    pub due_at: DateTime<Utc>,
+}
+
+impl RunReengagement {
+    /// Queue this run for when it is due, unless a job for that time is
+    /// already pending in the tenant
+    pub fn job(&self, tenant: &TenantId) -> serde_json::Result<NewJob> {
+        Ok(NewJob::new(JobKind::RunReengagement, self)?
+            .run_at(self.due_at)
+            .unique_key(format!("reengagement:{tenant}:{}", self.due_at.timestamp())))
+    }
+}
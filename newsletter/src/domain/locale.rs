@@ -0,0 +1,60 @@
+/// Longest locale tag accepted
+pub const MAX_LEN: usize = 35;
+
+/// Whether `value` has the shape of a BCP 47 language tag: a 2-3 or 5-8
+/// letter language followed by subtags of 1-8 letters or digits
+pub fn is_valid(value: &str) -> bool {
+    if value.len() > MAX_LEN {
+        return false;
+    }
+
+    let mut subtags = value.split('-');
+    let language = subtags.next().unwrap_or_default();
+    let language_ok = matches!(language.len(), 2 | 3 | 5..=8)
+        && language.chars().all(|c| c.is_ascii_alphabetic());
+
+    language_ok && subtags.all(|s| (1..=8).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// The conventional casing of a tag: lowercase language, titlecase script and
+/// uppercase region, so `EN-latn-us` becomes `en-Latn-US`. Tags compare
+/// without regard to case, but are stored in this form.
+pub fn canonical(tag: &str) -> String {
+    tag.split('-')
+        .enumerate()
+        .map(|(i, subtag)| match subtag.len() {
+            2 if i > 0 => subtag.to_ascii_uppercase(),
+            4 if i > 0 && subtag.chars().all(|c| c.is_ascii_alphabetic()) => {
+                let (first, rest) = subtag.split_at(1);
+                first.to_ascii_uppercase() + &rest.to_ascii_lowercase()
+            }
+            _ => subtag.to_ascii_lowercase(),
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Tags to look for, best first: `preferred` and each shorter prefix of it
+/// (`de-AT`, then `de`), then `fallbacks` in order. Repeats are dropped.
+pub fn lookup_chain(preferred: Option<&str>, fallbacks: &[String]) -> Vec<String> {
+    let mut chain: Vec<String> = Vec::new();
+    let mut push = |tag: &str| {
+        if !tag.is_empty() && !chain.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            chain.push(tag.to_string());
+        }
+    };
+
+    if let Some(mut tag) = preferred {
+        loop {
+            push(tag);
+            match tag.rfind('-') {
+                Some(end) => tag = &tag[..end],
+                None => break,
+            }
+        }
+    }
+    for tag in fallbacks {
+        push(tag);
+    }
+    chain
+}
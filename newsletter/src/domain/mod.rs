@@ -1 +1,13 @@
+pub mod analytics;
+pub mod auth;
+pub mod campaign;
+pub mod idempotency;
+pub mod jobs;
+pub mod locale;
 pub mod newsletter;
+pub mod outbox;
+pub mod pagination;
+pub mod template;
+pub mod tenant;
+pub mod timezone;
+pub mod webhook;
@@ -0,0 +1,156 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+/// Longest attribute key
+pub const MAX_ATTRIBUTE_KEY_LEN: usize = 64;
+
+/// Longest string attribute value, in characters
+pub const MAX_ATTRIBUTE_STRING_LEN: usize = 1024;
+
+/// Custom fields stored on a subscription, keyed by attribute key
+pub type Attributes = Map<String, Value>;
+
+/// JSON type an attribute's values must have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeType {
+    String,
+    Number,
+    Boolean,
+}
+
+impl AttributeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttributeType::String => "string",
+            AttributeType::Number => "number",
+            AttributeType::Boolean => "boolean",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "string" => Some(AttributeType::String),
+            "number" => Some(AttributeType::Number),
+            "boolean" => Some(AttributeType::Boolean),
+            _ => None,
+        }
+    }
+
+    fn accepts(&self, value: &Value) -> bool {
+        matches!(
+            (self, value),
+            (AttributeType::String, Value::String(_))
+                | (AttributeType::Number, Value::Number(_))
+                | (AttributeType::Boolean, Value::Bool(_))
+        )
+    }
+}
+
+impl fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An entry of the attribute schema registry; only registered keys can be stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeDefinition {
+    pub key: String,
+    pub kind: AttributeType,
+    pub description: String,
+}
+
+impl AttributeDefinition {
+    /// Build a definition, checking the key is a lowercase identifier such as `first_name`
+    pub fn new(key: &str, kind: AttributeType, description: &str) -> Result<Self, AttributeError> {
+        let key = key.trim();
+        let valid = !key.is_empty()
+            && key.len() <= MAX_ATTRIBUTE_KEY_LEN
+            && key.starts_with(|c: char| c.is_ascii_lowercase())
+            && key
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid {
+            return Err(AttributeError::InvalidKey(key.to_string()));
+        }
+
+        Ok(Self {
+            key: key.to_string(),
+            kind,
+            description: description.trim().to_string(),
+        })
+    }
+}
+
+/// Check attribute changes against the registry. A `null` value removes the
+/// attribute and is accepted for any registered key.
+pub fn validate(definitions: &[AttributeDefinition], changes: &Attributes) -> Result<(), AttributeError> {
+    for (key, value) in changes {
+        let definition = definitions
+            .iter()
+            .find(|d| &d.key == key)
+            .ok_or_else(|| AttributeError::Unknown(key.clone()))?;
+
+        if value.is_null() {
+            continue;
+        }
+        if !definition.kind.accepts(value) {
+            return Err(AttributeError::TypeMismatch {
+                key: key.clone(),
+                expected: definition.kind,
+            });
+        }
+        if let Value::String(s) = value {
+            if s.chars().count() > MAX_ATTRIBUTE_STRING_LEN {
+                return Err(AttributeError::TooLong(key.clone()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Template context for mail to one subscriber: `{"email": ..., "attributes": {...}}`
+pub fn merge_context(email: &str, attributes: &Attributes) -> Value {
+    serde_json::json!({
+        "email": email,
+        "attributes": attributes,
+    })
+}
+
+/// Why attributes or their definitions were rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeError {
+    /// The address has no subscription
+    NotSubscribed,
+    /// No definition is registered for this key
+    Unknown(String),
+    /// A definition with this key is already registered
+    AlreadyDefined(String),
+    /// The key is not a lowercase identifier
+    InvalidKey(String),
+    TypeMismatch { key: String, expected: AttributeType },
+    TooLong(String),
+}
+
+impl fmt::Display for AttributeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttributeError::NotSubscribed => write!(f, "email is not subscribed"),
+            AttributeError::Unknown(key) => write!(f, "unknown attribute: {key}"),
+            AttributeError::AlreadyDefined(key) => write!(f, "attribute {key} is already defined"),
+            AttributeError::InvalidKey(key) => write!(
+                f,
+                "invalid attribute key {key:?}: use up to {MAX_ATTRIBUTE_KEY_LEN} lowercase letters, digits and underscores, starting with a letter"
+            ),
+            AttributeError::TypeMismatch { key, expected } => {
+                write!(f, "attribute {key} must be a {expected}")
+            }
+            AttributeError::TooLong(key) => {
+                write!(f, "attribute {key} is longer than {MAX_ATTRIBUTE_STRING_LEN} characters")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AttributeError {}
@@ -0,0 +1,81 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+/// Longest policy version or source label accepted with a consent
+pub const MAX_CONSENT_LABEL_LEN: usize = 64;
+
+/// Step of the opt-in a consent record proves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConsentAction {
+    /// The reader asked to subscribe
+    Given,
+    /// The reader followed the double opt-in link
+    Confirmed,
+    /// The reader unsubscribed
+    Withdrawn,
+}
+
+impl ConsentAction {
+    pub const ALL: [ConsentAction; 3] = [
+        ConsentAction::Given,
+        ConsentAction::Confirmed,
+        ConsentAction::Withdrawn,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsentAction::Given => "given",
+            ConsentAction::Confirmed => "confirmed",
+            ConsentAction::Withdrawn => "withdrawn",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.as_str() == value)
+    }
+}
+
+impl fmt::Display for ConsentAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Circumstances of a consent step as reported by the caller
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsentContext {
+    /// Version of the consent text the reader agreed to
+    pub policy_version: Option<String>,
+    /// Where the request came from, such as `footer-form` or `checkout`
+    pub source: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl ConsentContext {
+    /// Values are trimmed and blank ones dropped; lengths are checked by the
+    /// caller, since a cut policy version would no longer prove anything
+    pub fn new(policy_version: Option<String>, source: Option<String>, ip_address: Option<String>) -> Self {
+        Self {
+            policy_version: present(policy_version),
+            source: present(source),
+            ip_address: present(ip_address),
+        }
+    }
+}
+
+fn present(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// A recorded consent step; kept after the subscription itself is gone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsentRecord {
+    pub id: i64,
+    pub email: String,
+    pub action: ConsentAction,
+    pub policy_version: Option<String>,
+    pub source: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
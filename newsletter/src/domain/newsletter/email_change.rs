@@ -0,0 +1,19 @@
+use super::error::NewsletterError;
+use super::lifecycle::SubscriptionStatus;
+
+/// A subscription moved to another address once the new address confirmed it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailChange {
+    /// The address the subscription was stored under
+    pub from: String,
+    pub to: String,
+}
+
+/// Why `email` cannot take over another subscription while it has one of
+/// its own in `status`
+pub fn taken(email: &str, status: SubscriptionStatus) -> NewsletterError {
+    match status {
+        SubscriptionStatus::Suppressed => NewsletterError::Suppressed(email.to_string()),
+        _ => NewsletterError::AlreadySubscribed(email.to_string()),
+    }
+}
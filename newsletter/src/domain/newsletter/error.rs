@@ -0,0 +1,122 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::domain::newsletter::attributes::AttributeError;
+use crate::domain::newsletter::lifecycle::InvalidTransition;
+use crate::domain::newsletter::preferences::PreferencesError;
+use crate::domain::newsletter::{InvalidEmail, InvalidTag};
+use crate::domain::pagination::StaleCursor;
+
+/// Result of the newsletter repository and service
+pub type Result<T, E = NewsletterError> = std::result::Result<T, E>;
+
+/// Everything the newsletter repository and service can fail with; each
+/// variant maps to one gRPC status code
+#[derive(Debug)]
+pub enum NewsletterError {
+    /// The subscription, or another record the call names, does not exist
+    NotFound(String),
+    /// The address already has a subscription
+    AlreadySubscribed(String),
+    /// The address may not be subscribed, for example after a hard bounce
+    Suppressed(String),
+    /// The request breaks a domain rule
+    Validation(String),
+    /// The change clashes with what is stored, such as a key that is taken
+    Conflict(String),
+    /// The subscription's status does not allow the change
+    InvalidTransition(String),
+    /// The store failed, or returned data it should never hold
+    Database(Box<dyn Error + Send + Sync>),
+    /// The store kept failing, so calls are refused for the given time
+    Unavailable(Duration),
+}
+
+impl NewsletterError {
+    pub fn database(error: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        NewsletterError::Database(error.into())
+    }
+}
+
+impl fmt::Display for NewsletterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NewsletterError::NotFound(message)
+            | NewsletterError::Validation(message)
+            | NewsletterError::Conflict(message)
+            | NewsletterError::InvalidTransition(message) => f.write_str(message),
+            NewsletterError::AlreadySubscribed(email) => write!(f, "{email} is already subscribed"),
+            NewsletterError::Suppressed(email) => write!(f, "{email} is suppressed and cannot be subscribed"),
+            NewsletterError::Database(e) => write!(f, "database error: {e}"),
+            NewsletterError::Unavailable(retry_after) => write!(
+                f,
+                "the database is unavailable, retry in {}s",
+                retry_after.as_secs_f64().ceil()
+            ),
+        }
+    }
+}
+
+impl Error for NewsletterError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NewsletterError::Database(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<PreferencesError> for NewsletterError {
+    fn from(e: PreferencesError) -> Self {
+        match e {
+            PreferencesError::NotSubscribed => NewsletterError::NotFound(e.to_string()),
+            PreferencesError::UnknownTopic(_) => NewsletterError::Validation(e.to_string()),
+        }
+    }
+}
+
+impl From<AttributeError> for NewsletterError {
+    fn from(e: AttributeError) -> Self {
+        match e {
+            AttributeError::NotSubscribed => NewsletterError::NotFound(e.to_string()),
+            AttributeError::AlreadyDefined(_) => NewsletterError::Conflict(e.to_string()),
+            _ => NewsletterError::Validation(e.to_string()),
+        }
+    }
+}
+
+impl From<InvalidTransition> for NewsletterError {
+    fn from(e: InvalidTransition) -> Self {
+        NewsletterError::InvalidTransition(e.to_string())
+    }
+}
+
+impl From<InvalidEmail> for NewsletterError {
+    fn from(e: InvalidEmail) -> Self {
+        NewsletterError::Validation(e.to_string())
+    }
+}
+
+impl From<InvalidTag> for NewsletterError {
+    fn from(e: InvalidTag) -> Self {
+        NewsletterError::Validation(e.to_string())
+    }
+}
+
+impl From<StaleCursor> for NewsletterError {
+    fn from(e: StaleCursor) -> Self {
+        NewsletterError::Validation(format!("invalid page token: {e}"))
+    }
+}
+
+/// Failures of collaborators that report through `anyhow`, such as the job
+/// queue, keep their type if they already are a `NewsletterError`
+impl From<anyhow::Error> for NewsletterError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<NewsletterError>() {
+            Ok(e) => e,
+            Err(e) => NewsletterError::Database(e.into()),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+
+use super::attributes::Attributes;
+use super::lifecycle::SubscriptionStatus;
+use super::merge::SubscriberMerge;
+use super::unsubscribe::UnsubscribeEvent;
+
+/// Everything stored about one email address, for subject-access requests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriberExport {
+    pub email: String,
+    /// `None` once the subscription has been deleted
+    pub subscription: Option<SubscriptionRecord>,
+    pub tags: Vec<TagRecord>,
+    pub pending_confirmations: Vec<PendingConfirmation>,
+    /// Unsubscribes with their feedback, oldest first
+    pub unsubscribes: Vec<UnsubscribeEvent>,
+    /// Merges the address took part in, kept or merged, oldest first
+    pub merges: Vec<SubscriberMerge>,
+}
+
+impl SubscriberExport {
+    /// Whether nothing at all is stored for the address
+    pub fn is_empty(&self) -> bool {
+        self.subscription.is_none()
+            && self.tags.is_empty()
+            && self.pending_confirmations.is_empty()
+            && self.unsubscribes.is_empty()
+            && self.merges.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionRecord {
+    pub status: SubscriptionStatus,
+    pub created_at: DateTime<Utc>,
+    pub attributes: Attributes,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub resubscribed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagRecord {
+    pub tag: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An outstanding double opt-in confirmation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingConfirmation {
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
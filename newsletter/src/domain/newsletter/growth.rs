@@ -0,0 +1,115 @@
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::domain::newsletter::error::{NewsletterError, Result};
+
+/// Most points a single growth time series returns
+pub const MAX_GROWTH_POINTS: i64 = 366;
+
+/// A tenant's list at the end of one UTC day, as recorded by the nightly rollup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyMetrics {
+    pub day: NaiveDate,
+    /// Active subscriptions when the day was rolled up
+    pub subscribers: i64,
+    /// Double opt-in links followed during the day
+    pub confirms: i64,
+    pub unsubscribes: i64,
+    /// `confirms - unsubscribes`
+    pub net_growth: i64,
+}
+
+/// Width of the buckets a growth time series is summed into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Granularity {
+    #[default]
+    Day,
+    /// ISO weeks, starting on Monday
+    Week,
+    Month,
+}
+
+impl Granularity {
+    /// First day of the bucket `day` falls in
+    pub fn bucket(&self, day: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => day,
+            Granularity::Week => day - Duration::days(i64::from(day.weekday().num_days_from_monday())),
+            Granularity::Month => day.with_day(1).unwrap_or(day),
+        }
+    }
+
+    /// Buckets touched by the days from `from` to `to`, both included
+    fn buckets_between(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        match self {
+            Granularity::Day => (to - from).num_days() + 1,
+            Granularity::Week => (self.bucket(to) - self.bucket(from)).num_days() / 7 + 1,
+            Granularity::Month => {
+                let months = |day: NaiveDate| i64::from(day.year()) * 12 + i64::from(day.month0());
+                months(to) - months(from) + 1
+            }
+        }
+    }
+}
+
+/// Days a growth time series covers, both ends included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrowthRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub granularity: Granularity,
+}
+
+impl GrowthRange {
+    /// Reject a range that ends before it starts or spans more than
+    /// [`MAX_GROWTH_POINTS`] buckets
+    pub fn new(from: NaiveDate, to: NaiveDate, granularity: Granularity) -> Result<Self> {
+        if to < from {
+            return Err(NewsletterError::Validation(format!(
+                "the range ends on {to}, before it starts on {from}"
+            )));
+        }
+        if granularity.buckets_between(from, to) > MAX_GROWTH_POINTS {
+            return Err(NewsletterError::Validation(format!(
+                "a range spans at most {MAX_GROWTH_POINTS} points; use a coarser granularity"
+            )));
+        }
+        Ok(Self { from, to, granularity })
+    }
+}
+
+/// One bucket of a growth time series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrowthPoint {
+    /// First day of the bucket
+    pub start: NaiveDate,
+    /// Active subscriptions at the end of the bucket's last recorded day
+    pub subscribers: i64,
+    pub confirms: i64,
+    pub unsubscribes: i64,
+    pub net_growth: i64,
+}
+
+/// Sum daily snapshots, ordered by day, into buckets of `granularity`.
+/// Buckets without a recorded day are left out rather than reported as zero.
+pub fn time_series(days: &[DailyMetrics], granularity: Granularity) -> Vec<GrowthPoint> {
+    let mut points: Vec<GrowthPoint> = Vec::new();
+    for day in days {
+        let start = granularity.bucket(day.day);
+        match points.last_mut() {
+            Some(point) if point.start == start => {
+                point.subscribers = day.subscribers;
+                point.confirms += day.confirms;
+                point.unsubscribes += day.unsubscribes;
+                point.net_growth += day.net_growth;
+            }
+            _ => points.push(GrowthPoint {
+                start,
+                subscribers: day.subscribers,
+                confirms: day.confirms,
+                unsubscribes: day.unsubscribes,
+                net_growth: day.net_growth,
+            }),
+        }
+    }
+    points
+}
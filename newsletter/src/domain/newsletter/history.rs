@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use super::view::{SubscriberView, ViewChange};
+use super::{SubscriptionEvent, SubscriptionEventKind};
+
+/// What the history tells about a subscription at a point in time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriberHistory {
+    /// The subscription as it stood then; `None` if the address had none
+    pub state: Option<SubscriberView>,
+    /// The events it was folded from, oldest first, including those of the
+    /// addresses it moved from
+    pub events: Vec<SubscriptionEvent>,
+}
+
+/// Subscriptions as `events` leave them, keyed by address. Events are folded
+/// oldest first, in the order given for the same instant.
+pub fn replay<'a>(events: impl IntoIterator<Item = &'a SubscriptionEvent>) -> HashMap<String, SubscriberView> {
+    let mut events: Vec<&SubscriptionEvent> = events.into_iter().collect();
+    events.sort_by_key(|event| event.occurred_at);
+
+    let mut subscriptions: HashMap<String, SubscriberView> = HashMap::new();
+    for event in events {
+        let current = subscriptions.get(&event.email);
+        let previous = event.previous_email.as_deref().and_then(|email| subscriptions.get(email));
+        for change in SubscriberView::changes(event, current, previous) {
+            match change {
+                ViewChange::Upsert(view) => {
+                    subscriptions.insert(view.email.clone(), view);
+                }
+                ViewChange::Remove(email) => {
+                    subscriptions.remove(&email);
+                }
+            }
+        }
+    }
+    subscriptions
+}
+
+/// The subscription of `email` as it stood at `at`, from the events of the
+/// address and of the addresses it moved from; `None` if it had none then
+pub fn state_at(events: &[SubscriptionEvent], email: &str, at: DateTime<Utc>) -> Option<SubscriberView> {
+    replay(events.iter().filter(|event| event.occurred_at <= at)).remove(email)
+}
+
+/// Addresses `email` took over by a change of address, with when it did;
+/// their earlier events belong to the same subscription
+pub fn predecessors<'a>(events: &'a [SubscriptionEvent], email: &str) -> Vec<(&'a str, DateTime<Utc>)> {
+    events
+        .iter()
+        .filter(|event| event.kind == SubscriptionEventKind::EmailChanged && event.email == email)
+        .filter_map(|event| Some((event.previous_email.as_deref()?, event.occurred_at)))
+        .collect()
+}
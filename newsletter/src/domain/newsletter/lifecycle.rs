@@ -0,0 +1,120 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Where a subscription stands.
+///
+/// New signups start out pending until the reader confirms, unsubscribing
+/// keeps the row so the history and consent trail survive, and suppressed
+/// addresses are never mailed nor let back in by a signup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubscriptionStatus {
+    Pending,
+    Active,
+    Unsubscribed,
+    Suppressed,
+}
+
+impl SubscriptionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubscriptionStatus::Pending => "pending",
+            SubscriptionStatus::Active => "active",
+            SubscriptionStatus::Unsubscribed => "unsubscribed",
+            SubscriptionStatus::Suppressed => "suppressed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(SubscriptionStatus::Pending),
+            "active" => Some(SubscriptionStatus::Active),
+            "unsubscribed" => Some(SubscriptionStatus::Unsubscribed),
+            "suppressed" => Some(SubscriptionStatus::Suppressed),
+            _ => None,
+        }
+    }
+
+    /// Only active subscribers receive mail
+    pub fn is_active(&self) -> bool {
+        *self == SubscriptionStatus::Active
+    }
+
+    /// Whether a subscription may move to `next`; staying put always is
+    pub fn can_become(&self, next: SubscriptionStatus) -> bool {
+        use SubscriptionStatus::*;
+
+        *self == next
+            || matches!(
+                (self, next),
+                (Pending, Active)
+                    | (Pending | Active, Unsubscribed)
+                    | (Unsubscribed, Pending | Active)
+                    | (Pending | Active | Unsubscribed, Suppressed)
+                    // Lifting a suppression does not resubscribe anyone
+                    | (Suppressed, Unsubscribed)
+            )
+    }
+
+    /// The status after moving to `next`, if the lifecycle allows it
+    pub fn transition(self, next: SubscriptionStatus) -> Result<Self, InvalidTransition> {
+        if self.can_become(next) {
+            Ok(next)
+        } else {
+            Err(InvalidTransition { from: self, to: next })
+        }
+    }
+
+    /// Status of two case variants of one address folded together: a
+    /// suppression always survives, otherwise the most engaged one wins
+    pub fn merge(self, other: SubscriptionStatus) -> SubscriptionStatus {
+        let rank = |s: SubscriptionStatus| match s {
+            SubscriptionStatus::Unsubscribed => 0,
+            SubscriptionStatus::Pending => 1,
+            SubscriptionStatus::Active => 2,
+            SubscriptionStatus::Suppressed => 3,
+        };
+        if rank(other) > rank(self) {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl fmt::Display for SubscriptionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A status change the subscription lifecycle does not allow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidTransition {
+    pub from: SubscriptionStatus,
+    pub to: SubscriptionStatus,
+}
+
+impl fmt::Display for InvalidTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot move a {} subscription to {}", self.from, self.to)
+    }
+}
+
+impl std::error::Error for InvalidTransition {}
+
+/// Unconfirmed subscriptions cleared by a purge of lapsed confirmations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingPurge {
+    /// New subscriptions removed
+    pub deleted: usize,
+    /// Resubscriptions put back to unsubscribed, keeping their history
+    pub reverted: usize,
+}
+
+impl PendingPurge {
+    pub fn total(&self) -> usize {
+        self.deleted + self.reverted
+    }
+}
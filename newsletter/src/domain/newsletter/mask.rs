@@ -0,0 +1,106 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use super::lifecycle::SubscriptionStatus;
+
+/// Newsletter fields a caller asked for; the rest are neither loaded nor returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewsletterMask {
+    pub email: bool,
+    /// Derived from the status, for callers that predate it
+    pub active: bool,
+    pub status: bool,
+    pub created_at: bool,
+    pub locale: bool,
+    pub timezone: bool,
+    pub resubscribed_at: bool,
+}
+
+impl NewsletterMask {
+    pub const ALL: NewsletterMask = NewsletterMask {
+        email: true,
+        active: true,
+        status: true,
+        created_at: true,
+        locale: true,
+        timezone: true,
+        resubscribed_at: true,
+    };
+
+    /// Build a mask from field mask paths; no paths, or `*`, select every field
+    pub fn from_paths<S: AsRef<str>>(paths: &[S]) -> Result<Self, InvalidFieldMask> {
+        if paths.is_empty() {
+            return Ok(Self::ALL);
+        }
+
+        let mut mask = NewsletterMask {
+            email: false,
+            active: false,
+            status: false,
+            created_at: false,
+            locale: false,
+            timezone: false,
+            resubscribed_at: false,
+        };
+        for path in paths {
+            match path.as_ref() {
+                "*" => return Ok(Self::ALL),
+                "email" => mask.email = true,
+                "active" => mask.active = true,
+                "status" => mask.status = true,
+                "created_at" => mask.created_at = true,
+                "locale" => mask.locale = true,
+                "timezone" => mask.timezone = true,
+                "resubscribed_at" => mask.resubscribed_at = true,
+                other => return Err(InvalidFieldMask(other.to_string())),
+            }
+        }
+        Ok(mask)
+    }
+
+    /// The selected fields as field mask paths
+    pub fn paths(&self) -> Vec<String> {
+        [
+            (self.email, "email"),
+            (self.active, "active"),
+            (self.status, "status"),
+            (self.created_at, "created_at"),
+            (self.locale, "locale"),
+            (self.timezone, "timezone"),
+            (self.resubscribed_at, "resubscribed_at"),
+        ]
+        .into_iter()
+        .filter(|(selected, _)| *selected)
+        .map(|(_, path)| path.to_string())
+        .collect()
+    }
+}
+
+/// A field mask path that does not name a newsletter field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidFieldMask(String);
+
+impl fmt::Display for InvalidFieldMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown field mask path: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidFieldMask {}
+
+/// A newsletter loaded through a `NewsletterMask`; unselected fields are `None`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartialNewsletter {
+    pub email: Option<String>,
+    pub active: Option<bool>,
+    pub status: Option<SubscriptionStatus>,
+    pub created_at: Option<DateTime<Utc>>,
+    /// Also `None` when selected but never given
+    pub locale: Option<String>,
+    /// IANA zone name; also `None` when selected but never given
+    pub timezone: Option<String>,
+    /// Last time the address subscribed again after unsubscribing; also
+    /// `None` when selected but it never did
+    pub resubscribed_at: Option<DateTime<Utc>>,
+}
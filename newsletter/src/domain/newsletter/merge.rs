@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+
+use super::attributes::Attributes;
+use super::lifecycle::SubscriptionStatus;
+
+/// One subscription folded into another of the same person, such as
+/// duplicates created before address normalization was switched on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriberMerge {
+    /// The address kept
+    pub email: String,
+    /// The address merged into it, which no longer has a subscription
+    pub merged_email: String,
+    /// Status the merged subscription had
+    pub merged_status: SubscriptionStatus,
+    /// Status of the kept subscription after the merge
+    pub status: SubscriptionStatus,
+    pub merged_at: DateTime<Utc>,
+}
+
+/// The fields of a subscription a merge reconciles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedFields {
+    pub status: SubscriptionStatus,
+    pub created_at: DateTime<Utc>,
+    pub attributes: Attributes,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub resubscribed_at: Option<DateTime<Utc>>,
+}
+
+impl MergedFields {
+    /// Fold `other` into `self`: statuses by `SubscriptionStatus::merge`,
+    /// created with the oldest, resubscribed with the latest, and the
+    /// attributes, locale and timezone of `self` winning where both are set
+    pub fn merge(mut self, other: MergedFields) -> Self {
+        self.status = self.status.merge(other.status);
+        self.created_at = self.created_at.min(other.created_at);
+        self.resubscribed_at = self.resubscribed_at.max(other.resubscribed_at);
+        self.locale = self.locale.or(other.locale);
+        self.timezone = self.timezone.or(other.timezone);
+        for (key, value) in other.attributes {
+            self.attributes.entry(key).or_insert(value);
+        }
+        self
+    }
+}
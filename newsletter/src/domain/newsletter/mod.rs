@@ -1,7 +1,362 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::domain::tenant::TenantId;
+use email_change::EmailChange;
+use lifecycle::SubscriptionStatus;
+use merge::SubscriberMerge;
+
+pub mod attributes;
+pub mod consent;
+pub mod email_change;
+pub mod error;
+pub mod export;
+pub mod growth;
+pub mod history;
+pub mod lifecycle;
+pub mod mask;
+pub mod merge;
+pub mod normalize;
+pub mod preferences;
+pub mod preview;
+pub mod query;
+pub mod retention;
+pub mod search;
+pub mod signup;
+pub mod stats;
+pub mod unsubscribe;
+pub mod verification;
+pub mod view;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Newsletter {
     pub email: String,
-    pub active: bool,
+    pub status: SubscriptionStatus,
+}
+
+impl Newsletter {
+    pub fn is_active(&self) -> bool {
+        self.status.is_active()
+    }
+}
+
+/// Subscription lifecycle changes announced to external listeners
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SubscriptionEventKind {
+    /// A pending subscription was created and awaits confirmation
+    #[serde(rename = "newsletter.subscribed")]
+    Subscribed,
+    /// An unsubscribed address subscribed again; `status` says whether it
+    /// awaits confirmation or is active at once
+    #[serde(rename = "newsletter.resubscribed")]
+    Resubscribed,
+    /// A pending subscription was confirmed and is now active
+    #[serde(rename = "newsletter.confirmed")]
+    Confirmed,
+    /// The reader unsubscribed, or the subscription was deleted
+    #[serde(rename = "newsletter.unsubscribed")]
+    Unsubscribed,
+    /// An administrator created the subscription, active, or moved it to
+    /// another status
+    #[serde(rename = "newsletter.status_changed")]
+    StatusChanged,
+    /// The subscription moved to another address; `email` is the new one
+    #[serde(rename = "newsletter.email_changed")]
+    EmailChanged,
+    /// An administrator folded the subscription of `previous_email` into
+    /// this one
+    #[serde(rename = "newsletter.merged")]
+    Merged,
+}
+
+impl SubscriptionEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubscriptionEventKind::Subscribed => "newsletter.subscribed",
+            SubscriptionEventKind::Resubscribed => "newsletter.resubscribed",
+            SubscriptionEventKind::Confirmed => "newsletter.confirmed",
+            SubscriptionEventKind::Unsubscribed => "newsletter.unsubscribed",
+            SubscriptionEventKind::StatusChanged => "newsletter.status_changed",
+            SubscriptionEventKind::EmailChanged => "newsletter.email_changed",
+            SubscriptionEventKind::Merged => "newsletter.merged",
+        }
+    }
+}
+
+impl fmt::Display for SubscriptionEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionEvent {
+    #[serde(rename = "type")]
+    pub kind: SubscriptionEventKind,
+    pub email: String,
+    /// Whether the new status is active, set for `newsletter.status_changed`
+    /// only; kept for listeners that predate `status`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    /// New status, set for `newsletter.status_changed` and
+    /// `newsletter.resubscribed` only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<SubscriptionStatus>,
+    /// Address the subscription moved from, set for
+    /// `newsletter.email_changed` and `newsletter.merged` only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_email: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    /// Tenant the subscriber belongs to; stamped when the event is written
+    /// to the outbox
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<TenantId>,
+}
+
+impl SubscriptionEvent {
+    pub fn now(kind: SubscriptionEventKind, email: impl Into<String>) -> Self {
+        Self {
+            kind,
+            email: email.into(),
+            active: None,
+            status: None,
+            previous_email: None,
+            occurred_at: Utc::now(),
+            tenant: None,
+        }
+    }
+
+    pub fn status_changed(email: impl Into<String>, status: SubscriptionStatus) -> Self {
+        Self {
+            active: Some(status.is_active()),
+            status: Some(status),
+            ..Self::now(SubscriptionEventKind::StatusChanged, email)
+        }
+    }
+
+    pub fn resubscribed(email: impl Into<String>, status: SubscriptionStatus) -> Self {
+        Self {
+            status: Some(status),
+            ..Self::now(SubscriptionEventKind::Resubscribed, email)
+        }
+    }
+
+    pub fn email_changed(change: &EmailChange) -> Self {
+        Self {
+            previous_email: Some(change.from.clone()),
+            ..Self::now(SubscriptionEventKind::EmailChanged, change.to.as_str())
+        }
+    }
+
+    pub fn merged(merge: &SubscriberMerge) -> Self {
+        Self {
+            status: Some(merge.status),
+            previous_email: Some(merge.merged_email.clone()),
+            ..Self::now(SubscriptionEventKind::Merged, merge.email.as_str())
+        }
+    }
+}
+
+/// Longest forward-path is 256 octets including the angle brackets (RFC 5321 §4.5.3.1.3)
+pub const MAX_ADDRESS_LEN: usize = 254;
+const MAX_LOCAL_PART_LEN: usize = 64;
+const MAX_LABEL_LEN: usize = 63;
+
+/// Reason an address was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidEmail(&'static str);
+
+impl fmt::Display for InvalidEmail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid email address: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidEmail {}
+
+/// A syntactically valid, normalized mailbox address.
+///
+/// Accepts the dot-atom form of RFC 5321 with a hostname domain; quoted local
+/// parts and address literals are rejected since no provider we send through
+/// handles them reliably. The address is trimmed and lowercased so that the
+/// same mailbox always maps to the same subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct EmailAddress(String);
+
+impl EmailAddress {
+    pub fn parse(value: &str) -> Result<Self, InvalidEmail> {
+        let address = value.trim().to_lowercase();
+
+        if address.is_empty() {
+            return Err(InvalidEmail("email cannot be empty"));
+        }
+        if address.len() > MAX_ADDRESS_LEN {
+            return Err(InvalidEmail("address is too long"));
+        }
+
+        let (local, domain) = address
+            .rsplit_once('@')
+            .ok_or(InvalidEmail("missing '@'"))?;
+
+        validate_local_part(local)?;
+        validate_domain(domain)?;
+
+        Ok(Self(address))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The part after the `@`
+    pub fn domain(&self) -> &str {
+        self.0.rsplit_once('@').map_or("", |(_, domain)| domain)
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for EmailAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for EmailAddress {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for EmailAddress {
+    type Error = InvalidEmail;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<EmailAddress> for String {
+    fn from(value: EmailAddress) -> Self {
+        value.0
+    }
+}
+
+const MAX_TAG_LEN: usize = 64;
+
+/// Reason a tag was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTag(&'static str);
+
+impl fmt::Display for InvalidTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid tag: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidTag {}
+
+/// A segment label attached to subscribers, e.g. `beta-users` or `churned`.
+///
+/// Tags are lowercased and limited to ASCII letters, digits, `-` and `_` so they
+/// can be used verbatim in URLs and campaign targeting.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Tag(String);
+
+impl Tag {
+    pub fn parse(value: &str) -> Result<Self, InvalidTag> {
+        let tag = value.trim().to_lowercase();
+
+        if tag.is_empty() {
+            return Err(InvalidTag("tag cannot be empty"));
+        }
+        if tag.len() > MAX_TAG_LEN {
+            return Err(InvalidTag("tag is too long"));
+        }
+        if !tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(InvalidTag("only letters, digits, '-' and '_' are allowed"));
+        }
+
+        Ok(Self(tag))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for Tag {
+    type Error = InvalidTag;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Tag> for String {
+    fn from(value: Tag) -> Self {
+        value.0
+    }
+}
+
+/// `atext` from RFC 5322 §3.2.3
+fn is_atext(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c)
+}
+
+fn validate_local_part(local: &str) -> Result<(), InvalidEmail> {
+    if local.is_empty() {
+        return Err(InvalidEmail("local part cannot be empty"));
+    }
+    if local.len() > MAX_LOCAL_PART_LEN {
+        return Err(InvalidEmail("local part is too long"));
+    }
+    if local.split('.').any(str::is_empty) {
+        return Err(InvalidEmail("local part has a misplaced '.'"));
+    }
+    if !local.chars().all(|c| c == '.' || is_atext(c)) {
+        return Err(InvalidEmail("local part contains an invalid character"));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn validate_domain(domain: &str) -> Result<(), InvalidEmail> {
+    if domain.is_empty() {
+        return Err(InvalidEmail("domain cannot be empty"));
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 {
+        return Err(InvalidEmail("domain must be fully qualified"));
+    }
+
+    for label in labels {
+        if label.is_empty() || label.len() > MAX_LABEL_LEN {
+            return Err(InvalidEmail("domain has an invalid label"));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(InvalidEmail("domain label cannot start or end with '-'"));
+        }
+        if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(InvalidEmail("domain contains an invalid character"));
+        }
+    }
+
+    Ok(())
 }
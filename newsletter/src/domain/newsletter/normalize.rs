@@ -0,0 +1,38 @@
+use crate::domain::newsletter::EmailAddress;
+
+/// Domains whose mailboxes ignore dots and `+tag` suffixes in the local part
+const GMAIL_DOMAINS: &[&str] = &["gmail.com", "googlemail.com"];
+
+/// Folding applied to addresses on top of the trimming and lowercasing every
+/// [`EmailAddress`] gets, so that aliases of one mailbox share a subscription
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Normalization {
+    /// Drop dots and `+tag` suffixes from Gmail addresses and map
+    /// googlemail.com to gmail.com
+    pub fold_gmail_aliases: bool,
+}
+
+impl Normalization {
+    pub fn apply(&self, email: &EmailAddress) -> EmailAddress {
+        if !self.fold_gmail_aliases {
+            return email.clone();
+        }
+
+        let Some((local, domain)) = email.as_str().rsplit_once('@') else {
+            return email.clone();
+        };
+        if !GMAIL_DOMAINS.contains(&domain) {
+            return email.clone();
+        }
+
+        let mailbox: String = local
+            .split('+')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| *c != '.')
+            .collect();
+        // An address that is nothing but a tag has no mailbox to fold into
+        EmailAddress::parse(&format!("{mailbox}@gmail.com")).unwrap_or_else(|_| email.clone())
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+
+/// A kind of mail readers can opt in to or out of, such as the weekly digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Topic {
+    pub key: String,
+    pub name: String,
+    pub description: String,
+    /// Whether subscribers receive it until they choose otherwise
+    pub default_subscribed: bool,
+    /// Whether opting in takes a link confirmed by the subscriber, even one
+    /// whose subscription is already active; nobody receives such a topic
+    /// by default
+    pub requires_confirmation: bool,
+}
+
+/// A subscriber's choice for one topic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicPreference {
+    pub topic: String,
+    pub subscribed: bool,
+}
+
+/// A topic together with whether a subscriber receives it, by choice or by default
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicSubscription {
+    pub topic: Topic,
+    pub subscribed: bool,
+}
+
+impl TopicSubscription {
+    /// Resolve a topic against an explicit choice, if there is one
+    pub fn resolve(topic: Topic, choice: Option<bool>) -> Self {
+        let subscribed = choice.unwrap_or(topic.default_subscribed && !topic.requires_confirmation);
+        Self { topic, subscribed }
+    }
+}
+
+/// Topic choices after a change; opt-ins to topics that require
+/// confirmation are left out of `preferences` until their link is followed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreferencesUpdate {
+    pub preferences: Vec<TopicSubscription>,
+    /// Keys of the topics whose confirmation link was just sent
+    pub awaiting_confirmation: Vec<String>,
+}
+
+/// An opt-in to a topic that requires confirmation, completed by its link
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicConfirmation {
+    pub email: String,
+    pub topic: String,
+}
+
+/// Why preferences could not be read or changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreferencesError {
+    /// The address has no subscription
+    NotSubscribed,
+    /// No topic has this key
+    UnknownTopic(String),
+}
+
+impl fmt::Display for PreferencesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreferencesError::NotSubscribed => write!(f, "email is not subscribed"),
+            PreferencesError::UnknownTopic(topic) => write!(f, "unknown topic: {topic}"),
+        }
+    }
+}
+
+impl std::error::Error for PreferencesError {}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use super::lifecycle::SubscriptionStatus;
+
+/// What a bulk status update or delete would do, reported by a dry run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BulkPreview {
+    /// Subscriptions that would move to the new status, or be deleted
+    pub changed: u64,
+    /// Subscriptions already in the requested status
+    pub unchanged: u64,
+    /// Unknown addresses that would be added as active subscriptions
+    pub created: u64,
+    /// Unknown addresses that would be left alone
+    pub not_found: u64,
+    /// Entries a real run would refuse; any one of them fails it as a whole
+    pub rejected: Vec<RejectedEntry>,
+}
+
+/// An entry of a bulk request that cannot be applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedEntry {
+    pub email: String,
+    pub reason: String,
+}
+
+impl BulkPreview {
+    /// Preview moving `emails` to `status`, given the current status of the
+    /// known ones. Activating adds the unknown addresses, as the update does.
+    pub fn status_update(
+        emails: &[String],
+        current: &HashMap<String, SubscriptionStatus>,
+        status: SubscriptionStatus,
+    ) -> Self {
+        let mut preview = BulkPreview::default();
+        for email in emails {
+            match current.get(email) {
+                None if status.is_active() => preview.created += 1,
+                None => preview.not_found += 1,
+                Some(&from) if from == status => preview.unchanged += 1,
+                Some(&from) => match from.transition(status) {
+                    Ok(_) => preview.changed += 1,
+                    Err(e) => preview.reject(email, e.to_string()),
+                },
+            }
+        }
+        preview
+    }
+
+    /// Preview deleting `emails`, given the current status of the known ones
+    pub fn delete(emails: &[String], current: &HashMap<String, SubscriptionStatus>) -> Self {
+        let mut preview = BulkPreview::default();
+        for email in emails {
+            if current.contains_key(email) {
+                preview.changed += 1;
+            } else {
+                preview.not_found += 1;
+            }
+        }
+        preview
+    }
+
+    pub fn reject(&mut self, email: impl Into<String>, reason: impl Into<String>) {
+        self.rejected.push(RejectedEntry {
+            email: email.into(),
+            reason: reason.into(),
+        });
+    }
+}
@@ -0,0 +1,162 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::newsletter::attributes::Attributes;
+use crate::domain::newsletter::lifecycle::SubscriptionStatus;
+use crate::domain::newsletter::MAX_ADDRESS_LEN;
+
+/// Narrows the newsletters returned by a list
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewsletterFilter {
+    pub active: Option<bool>,
+    pub status: Option<SubscriptionStatus>,
+    /// Lowercased start of the address
+    pub email_prefix: Option<String>,
+    /// Lowercased domain, matched against everything after the `@`
+    pub email_domain: Option<String>,
+    pub created_since: Option<DateTime<Utc>>,
+    pub created_until: Option<DateTime<Utc>>,
+    /// Attributes that must all be present with these exact values
+    pub attributes: Attributes,
+}
+
+impl NewsletterFilter {
+    /// Normalize an email prefix; blank values filter nothing
+    pub fn email_prefix(value: &str) -> Result<Option<String>, InvalidQuery> {
+        Self::fragment("email prefix", value)
+    }
+
+    /// Normalize an email domain, tolerating a leading `@`; blank values filter nothing
+    pub fn email_domain(value: &str) -> Result<Option<String>, InvalidQuery> {
+        Self::fragment("email domain", value.trim().trim_start_matches('@'))
+    }
+
+    fn fragment(field: &'static str, value: &str) -> Result<Option<String>, InvalidQuery> {
+        let value = value.trim().to_lowercase();
+        if value.len() > MAX_ADDRESS_LEN {
+            return Err(InvalidQuery::TooLong(field));
+        }
+        Ok((!value.is_empty()).then_some(value))
+    }
+
+    /// Whether a newsletter passes the filter; mirrors the SQL the repository builds
+    pub fn matches(
+        &self,
+        email: &str,
+        status: SubscriptionStatus,
+        created_at: DateTime<Utc>,
+        attributes: &Attributes,
+    ) -> bool {
+        self.active.is_none_or(|a| a == status.is_active())
+            && self.status.is_none_or(|s| s == status)
+            && self.email_prefix.as_deref().is_none_or(|p| email.starts_with(p))
+            && self.email_domain.as_deref().is_none_or(|d| {
+                email.rsplit_once('@').is_some_and(|(_, domain)| domain == d)
+            })
+            && self.created_since.is_none_or(|since| created_at >= since)
+            && self.created_until.is_none_or(|until| created_at < until)
+            && self
+                .attributes
+                .iter()
+                .all(|(key, value)| attributes.get(key) == Some(value))
+    }
+}
+
+/// Column a list is sorted by; ties are broken by id in the same direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    CreatedAt,
+    Email,
+}
+
+/// Sort order of a list, parsed from an `order_by` such as `"email"` or `"created_at desc"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewsletterOrder {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl Default for NewsletterOrder {
+    /// Newest first
+    fn default() -> Self {
+        Self {
+            field: SortField::CreatedAt,
+            descending: true,
+        }
+    }
+}
+
+impl NewsletterOrder {
+    /// Parse `<field> [asc|desc]`; a blank value gives the default order
+    pub fn parse(value: &str) -> Result<Self, InvalidQuery> {
+        let mut parts = value.split_whitespace();
+        let Some(field) = parts.next() else {
+            return Ok(Self::default());
+        };
+
+        let field = match field.to_ascii_lowercase().as_str() {
+            "created_at" => SortField::CreatedAt,
+            "email" => SortField::Email,
+            _ => return Err(InvalidQuery::OrderBy(value.to_string())),
+        };
+        let descending = match parts.next().map(str::to_ascii_lowercase).as_deref() {
+            None | Some("asc") => false,
+            Some("desc") => true,
+            Some(_) => return Err(InvalidQuery::OrderBy(value.to_string())),
+        };
+        if parts.next().is_some() {
+            return Err(InvalidQuery::OrderBy(value.to_string()));
+        }
+
+        Ok(Self { field, descending })
+    }
+
+    /// Compare two newsletters' sort keys `(field, id)` in this order
+    pub fn compare(
+        &self,
+        a: (&str, DateTime<Utc>, i64),
+        b: (&str, DateTime<Utc>, i64),
+    ) -> Ordering {
+        let ordering = match self.field {
+            SortField::CreatedAt => a.1.cmp(&b.1),
+            SortField::Email => a.0.cmp(b.0),
+        }
+        .then(a.2.cmp(&b.2));
+
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// Filter and order of a newsletter list
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NewsletterQuery {
+    pub filter: NewsletterFilter,
+    pub order: NewsletterOrder,
+}
+
+/// A list filter or order the caller got wrong
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidQuery {
+    TooLong(&'static str),
+    OrderBy(String),
+}
+
+impl fmt::Display for InvalidQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidQuery::TooLong(field) => write!(f, "{field} is too long"),
+            InvalidQuery::OrderBy(value) => write!(
+                f,
+                "unsupported order_by {value:?}; expected \"created_at\" or \"email\", optionally followed by \"asc\" or \"desc\""
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InvalidQuery {}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::tenant::TenantId;
+
+/// Domain anonymized subscriptions are renamed into; `.invalid` never
+/// resolves, so nothing is ever sent there
+pub const ANONYMIZED_DOMAIN: &str = "anonymized.invalid";
+
+/// Placeholder address of the anonymized subscription with row id `id`,
+/// unique like the addresses it replaces
+pub fn anonymized_email(id: i64) -> String {
+    format!("anonymized-{id}@{ANONYMIZED_DOMAIN}")
+}
+
+/// What becomes of an unsubscribed subscription once its retention runs out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionAction {
+    /// Replace the address with a placeholder and drop its attributes, tags
+    /// and topic choices; the row and its history stay for the statistics
+    #[default]
+    Anonymize,
+    /// Remove the subscription and everything recorded under its address
+    Delete,
+}
+
+impl RetentionAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetentionAction::Anonymize => "anonymize",
+            RetentionAction::Delete => "delete",
+        }
+    }
+}
+
+impl fmt::Display for RetentionAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How long unsubscribed subscriptions are kept, and what happens after
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Time since unsubscribing after which the action applies
+    pub unsubscribed_for: Duration,
+    pub action: RetentionAction,
+}
+
+/// Retention of every tenant: a default policy with per-tenant overrides.
+/// `None` keeps unsubscribed subscriptions forever.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionRules {
+    pub default: Option<RetentionPolicy>,
+    pub overrides: HashMap<TenantId, Option<RetentionPolicy>>,
+}
+
+impl RetentionRules {
+    /// The policy `tenant` runs under
+    pub fn policy(&self, tenant: &TenantId) -> Option<RetentionPolicy> {
+        self.overrides.get(tenant).copied().unwrap_or(self.default)
+    }
+
+    /// Whether any tenant has a policy at all
+    pub fn is_enabled(&self) -> bool {
+        self.default.is_some() || self.overrides.values().any(Option::is_some)
+    }
+}
+
+/// Unsubscribed subscriptions cleared by one retention run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPurge {
+    pub anonymized: usize,
+    pub deleted: usize,
+}
+
+impl RetentionPurge {
+    pub fn total(&self) -> usize {
+        self.anonymized + self.deleted
+    }
+}
+
+impl std::ops::AddAssign for RetentionPurge {
+    fn add_assign(&mut self, other: Self) {
+        self.anonymized += other.anonymized;
+        self.deleted += other.deleted;
+    }
+}
+
+/// What a retention run did in one tenant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantRetention {
+    pub tenant: TenantId,
+    pub action: RetentionAction,
+    pub purge: RetentionPurge,
+}
@@ -0,0 +1,125 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use crate::domain::newsletter::error::{NewsletterError, Result};
+use crate::domain::newsletter::Newsletter;
+
+/// Shortest search text; shorter text has no trigrams to match on
+pub const MIN_SEARCH_LEN: usize = 3;
+
+/// Longest search text, the longest address there can be
+pub const MAX_SEARCH_LEN: usize = 254;
+
+/// Least trigram similarity of an address that does not contain the text,
+/// as `pg_trgm.similarity_threshold` defaults to
+pub const SIMILARITY_THRESHOLD: f64 = 0.3;
+
+/// Text a support agent searches subscriptions for, trimmed and in lowercase
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery(String);
+
+impl SearchQuery {
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim().to_lowercase();
+        let len = value.chars().count();
+        if len < MIN_SEARCH_LEN {
+            return Err(NewsletterError::Validation(format!(
+                "search text must be at least {MIN_SEARCH_LEN} characters"
+            )));
+        }
+        if len > MAX_SEARCH_LEN {
+            return Err(NewsletterError::Validation(format!(
+                "search text must be at most {MAX_SEARCH_LEN} characters"
+            )));
+        }
+        Ok(Self(value))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A subscription matching a search, with how close its address came
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub newsletter: Newsletter,
+    /// Trigram similarity of the address to the text, from 0 to 1
+    pub score: f64,
+}
+
+/// How an address ranks for a search: exact matches first, then addresses
+/// containing the text, then by similarity and edit distance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rank {
+    pub exact: bool,
+    pub contains: bool,
+    pub score: f64,
+    pub distance: usize,
+}
+
+impl Rank {
+    /// Rank `email` against `query`; `None` when it does not match at all
+    pub fn of(email: &str, query: &SearchQuery) -> Option<Self> {
+        let email = email.to_lowercase();
+        let query = query.as_str();
+        let rank = Rank {
+            exact: email == query,
+            contains: email.contains(query),
+            score: similarity(&email, query),
+            distance: levenshtein(&email, query),
+        };
+        (rank.contains || rank.score >= SIMILARITY_THRESHOLD).then_some(rank)
+    }
+
+    /// Best match first
+    pub fn cmp_best_first(&self, other: &Self) -> Ordering {
+        other
+            .exact
+            .cmp(&self.exact)
+            .then(other.contains.cmp(&self.contains))
+            .then(other.score.total_cmp(&self.score))
+            .then(self.distance.cmp(&other.distance))
+    }
+}
+
+/// Trigram similarity as `pg_trgm` computes it: words of letters and digits
+/// are padded with two spaces in front and one behind, and the score is the
+/// share of distinct trigrams the two texts have in common
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (trigrams(a), trigrams(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+fn trigrams(value: &str) -> HashSet<[char; 3]> {
+    let mut trigrams = HashSet::new();
+    for word in value.to_lowercase().split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+        let padded: Vec<char> = "  ".chars().chain(word.chars()).chain(" ".chars()).collect();
+        trigrams.extend(padded.windows(3).map(|w| [w[0], w[1], w[2]]));
+    }
+    trigrams
+}
+
+/// Edits turning one text into the other, as `fuzzystrmatch` counts them
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
@@ -0,0 +1,54 @@
+use serde_json::Value;
+
+use super::attributes::Attributes;
+use super::preferences::{PreferencesError, Topic, TopicPreference};
+
+/// Attribute holding where the reader signed up
+pub const SOURCE_ATTRIBUTE: &str = "signup_source";
+
+/// What a reader tells us when signing up, besides the consent itself
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignupDetails {
+    /// Preferred language as a BCP 47 tag, such as `en-US`
+    pub locale: Option<String>,
+    /// Where the reader signed up, such as `footer-form`
+    pub source: Option<String>,
+    /// Keys of the topics to receive; the others are opted out of. Empty
+    /// leaves every topic at its default.
+    pub topics: Vec<String>,
+}
+
+impl SignupDetails {
+    pub fn is_empty(&self) -> bool {
+        self.locale.is_none() && self.source.is_none() && self.topics.is_empty()
+    }
+
+    /// The custom attributes recording the source; the locale has a column
+    /// of its own
+    pub fn attributes(&self) -> Attributes {
+        let mut attributes = Attributes::new();
+        if let Some(source) = &self.source {
+            attributes.insert(SOURCE_ATTRIBUTE.to_string(), Value::String(source.clone()));
+        }
+        attributes
+    }
+
+    /// A choice for every topic in `catalogue`: in for the requested ones,
+    /// out for the rest. Nothing when no topics were requested.
+    pub fn preferences(&self, catalogue: &[Topic]) -> Result<Vec<TopicPreference>, PreferencesError> {
+        if self.topics.is_empty() {
+            return Ok(Vec::new());
+        }
+        if let Some(unknown) = self.topics.iter().find(|key| !catalogue.iter().any(|t| &t.key == *key)) {
+            return Err(PreferencesError::UnknownTopic(unknown.clone()));
+        }
+
+        Ok(catalogue
+            .iter()
+            .map(|topic| TopicPreference {
+                topic: topic.key.clone(),
+                subscribed: self.topics.contains(&topic.key),
+            })
+            .collect())
+    }
+}
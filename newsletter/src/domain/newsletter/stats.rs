@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Subscription counts of one tenant
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriberStats {
+    pub active: i64,
+    /// Subscriptions awaiting confirmation
+    pub inactive: i64,
+    /// Recorded unsubscribes, including those of readers who signed up again
+    pub unsubscribed: i64,
+    /// Addresses that may no longer be mailed or subscribed
+    pub suppressed: i64,
+}
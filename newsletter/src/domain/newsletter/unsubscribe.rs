@@ -0,0 +1,87 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Longest free-text comment kept with an unsubscribe
+pub const MAX_COMMENT_LEN: usize = 1000;
+
+/// Why a reader left, as picked from the unsubscribe form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UnsubscribeReason {
+    TooFrequent,
+    NotRelevant,
+    NeverSubscribed,
+    Other,
+}
+
+impl UnsubscribeReason {
+    pub const ALL: [UnsubscribeReason; 4] = [
+        UnsubscribeReason::TooFrequent,
+        UnsubscribeReason::NotRelevant,
+        UnsubscribeReason::NeverSubscribed,
+        UnsubscribeReason::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnsubscribeReason::TooFrequent => "too_frequent",
+            UnsubscribeReason::NotRelevant => "not_relevant",
+            UnsubscribeReason::NeverSubscribed => "never_subscribed",
+            UnsubscribeReason::Other => "other",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|reason| reason.as_str() == value)
+    }
+}
+
+impl fmt::Display for UnsubscribeReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Optional feedback given when unsubscribing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnsubscribeFeedback {
+    pub reason: Option<UnsubscribeReason>,
+    pub comment: Option<String>,
+}
+
+impl UnsubscribeFeedback {
+    /// Blank comments are dropped and long ones are cut at `MAX_COMMENT_LEN` characters
+    pub fn new(reason: Option<UnsubscribeReason>, comment: Option<String>) -> Self {
+        let comment = comment
+            .map(|c| c.trim().chars().take(MAX_COMMENT_LEN).collect::<String>())
+            .filter(|c| !c.is_empty());
+
+        Self { reason, comment }
+    }
+}
+
+/// A recorded unsubscribe with its feedback
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsubscribeEvent {
+    pub id: i64,
+    pub email: String,
+    pub reason: Option<UnsubscribeReason>,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Narrows the unsubscribe events returned for churn analysis
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UnsubscribeEventFilter {
+    pub reason: Option<UnsubscribeReason>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Number of unsubscribes per reason; `reason` is `None` for those given without one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReasonCount {
+    pub reason: Option<UnsubscribeReason>,
+    pub count: i64,
+}
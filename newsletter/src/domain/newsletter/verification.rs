@@ -0,0 +1,44 @@
+use std::collections::HashSet;
+
+/// Domains handing out throwaway mailboxes, which are refused at subscribe
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisposableDomains {
+    domains: HashSet<String>,
+}
+
+impl DisposableDomains {
+    /// Read a list with one domain per line; blank lines and `#` comments
+    /// are skipped
+    pub fn parse(list: &str) -> Self {
+        let domains = list
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default().trim().trim_end_matches('.'))
+            .filter(|domain| !domain.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        Self { domains }
+    }
+
+    pub fn len(&self) -> usize {
+        self.domains.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.domains.is_empty()
+    }
+
+    /// Whether `domain` or one of its parents is listed, so that
+    /// `mx.mailinator.com` counts as `mailinator.com`
+    pub fn contains(&self, domain: &str) -> bool {
+        let mut rest = domain;
+        loop {
+            if self.domains.contains(rest) {
+                return true;
+            }
+            match rest.split_once('.') {
+                Some((_, parent)) => rest = parent,
+                None => return false,
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+
+use super::lifecycle::SubscriptionStatus;
+use super::mask::{NewsletterMask, PartialNewsletter};
+use super::query::{NewsletterFilter, NewsletterOrder, NewsletterQuery};
+use super::{SubscriptionEvent, SubscriptionEventKind};
+
+/// A subscription as the read model lists it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriberView {
+    pub email: String,
+    pub status: SubscriptionStatus,
+    pub created_at: DateTime<Utc>,
+    /// When the last event applied to the row occurred
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A write to the read model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViewChange {
+    /// Insert the row of the address, or replace it
+    Upsert(SubscriberView),
+    /// Drop the row of the address
+    Remove(String),
+}
+
+impl SubscriberView {
+    /// Writes that fold `event` into the read model, given the rows held for
+    /// its address and for its previous address. Events that occurred no
+    /// later than the row they touch change nothing, so a redelivered or
+    /// replayed event is harmless.
+    pub fn changes(
+        event: &SubscriptionEvent,
+        current: Option<&SubscriberView>,
+        previous: Option<&SubscriberView>,
+    ) -> Vec<ViewChange> {
+        let at = event.occurred_at;
+        if current.is_some_and(|row| row.updated_at >= at) {
+            return Vec::new();
+        }
+        // Gone already when the event changed or merged the address again
+        let previous = previous.filter(|row| row.updated_at < at);
+
+        let status = match event.kind {
+            SubscriptionEventKind::Subscribed => SubscriptionStatus::Pending,
+            SubscriptionEventKind::Resubscribed => event.status.unwrap_or(SubscriptionStatus::Pending),
+            SubscriptionEventKind::Confirmed => SubscriptionStatus::Active,
+            SubscriptionEventKind::Unsubscribed => SubscriptionStatus::Unsubscribed,
+            SubscriptionEventKind::StatusChanged => event.status.unwrap_or(match event.active {
+                Some(true) => SubscriptionStatus::Active,
+                _ => SubscriptionStatus::Unsubscribed,
+            }),
+            // Only an active subscription changes its address
+            SubscriptionEventKind::EmailChanged => previous.map_or(SubscriptionStatus::Active, |row| row.status),
+            SubscriptionEventKind::Merged => event
+                .status
+                .or(current.map(|row| row.status))
+                .unwrap_or(SubscriptionStatus::Active),
+        };
+        let created_at = [current, previous]
+            .into_iter()
+            .flatten()
+            .map(|row| row.created_at)
+            .min()
+            .unwrap_or(at);
+
+        let mut changes = vec![ViewChange::Upsert(SubscriberView {
+            email: event.email.clone(),
+            status,
+            created_at,
+            updated_at: at,
+        })];
+        if matches!(event.kind, SubscriptionEventKind::EmailChanged | SubscriptionEventKind::Merged) {
+            if let Some(row) = previous {
+                changes.push(ViewChange::Remove(row.email.clone()));
+            }
+        }
+        changes
+    }
+
+    /// Whether a list of `query` through `mask` can be read from the read
+    /// model: newest first, narrowed by status at most, and asking for no
+    /// field the view does not keep
+    pub fn serves(query: &NewsletterQuery, mask: NewsletterMask) -> bool {
+        let by_status_only = NewsletterFilter {
+            status: None,
+            ..query.filter.clone()
+        } == NewsletterFilter::default();
+        query.order == NewsletterOrder::default()
+            && by_status_only
+            && !(mask.locale || mask.timezone || mask.resubscribed_at)
+    }
+
+    /// The fields of the row selected by `mask`
+    pub fn masked(self, mask: NewsletterMask) -> PartialNewsletter {
+        PartialNewsletter {
+            active: mask.active.then(|| self.status.is_active()),
+            status: mask.status.then_some(self.status),
+            created_at: mask.created_at.then_some(self.created_at),
+            email: mask.email.then_some(self.email),
+            ..PartialNewsletter::default()
+        }
+    }
+}
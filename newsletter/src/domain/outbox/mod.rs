@@ -0,0 +1,35 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::domain::newsletter::SubscriptionEvent;
+
+/// Longest wait between attempts to publish a failing message
+pub const MAX_RETRY_DELAY: Duration = Duration::hours(1);
+
+/// An event written in the same transaction as the change it announces,
+/// waiting to be published
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxMessage {
+    pub id: i64,
+    pub event: SubscriptionEvent,
+    /// Failed publish attempts so far
+    pub attempts: i32,
+}
+
+impl OutboxMessage {
+    /// Wait before the next attempt after this one failed: 2^attempts seconds,
+    /// capped at `MAX_RETRY_DELAY`
+    pub fn retry_delay(&self) -> Duration {
+        let exponent = self.attempts.clamp(0, 12) as u32;
+        Duration::seconds(1 << exponent).min(MAX_RETRY_DELAY)
+    }
+}
+
+/// Messages not yet published, across every tenant
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutboxBacklog {
+    pub pending: i64,
+    /// Pending messages that failed to publish at least once
+    pub failing: i64,
+    /// When the oldest pending message was written
+    pub oldest_pending_at: Option<DateTime<Utc>>,
+}
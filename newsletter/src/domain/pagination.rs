@@ -0,0 +1,52 @@
+/// Default number of items returned when the caller does not specify a page size.
+pub const DEFAULT_PAGE_SIZE: i64 = 100;
+
+/// Upper bound for a single page, regardless of what the caller asks for.
+pub const MAX_PAGE_SIZE: i64 = 1000;
+
+/// Keyset pagination request: fetch up to `limit` items strictly after `after`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    pub limit: i64,
+    pub after: Option<i64>,
+}
+
+impl PageRequest {
+    /// Build a page request, clamping the size into `1..=MAX_PAGE_SIZE`
+    /// (non-positive sizes fall back to `DEFAULT_PAGE_SIZE`).
+    pub fn new(limit: i64, after: Option<i64>) -> Self {
+        let limit = if limit <= 0 {
+            DEFAULT_PAGE_SIZE
+        } else {
+            limit.min(MAX_PAGE_SIZE)
+        };
+
+        Self { limit, after }
+    }
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self::new(DEFAULT_PAGE_SIZE, None)
+    }
+}
+
+/// A single page of results with the cursor of the next page, if any.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<i64>,
+}
+
+/// A cursor whose item is gone, so a list sorted on another column cannot
+/// tell where the next page starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaleCursor(pub i64);
+
+impl std::fmt::Display for StaleCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "page cursor {} no longer refers to an item", self.0)
+    }
+}
+
+impl std::error::Error for StaleCursor {}
@@ -0,0 +1,144 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Duration, Utc};
+
+use super::translation::TemplateTranslation;
+use super::{Template, TemplateError};
+
+/// Largest asset accepted
+pub const MAX_ASSET_BYTES: usize = 5 * 1024 * 1024;
+
+/// How long an asset no template references is kept after it was uploaded
+/// or last referenced, so it can be uploaded before the template that uses
+/// it is saved and an edit that drops it by mistake can be undone
+pub const UNREFERENCED_GRACE: Duration = Duration::days(1);
+
+/// How long the signed URLs handed out for an asset stay valid
+pub const SIGNED_URL_TTL: Duration = Duration::minutes(15);
+
+/// Longest asset name
+const MAX_NAME_LEN: usize = 200;
+
+/// Content types accepted. SVG is left out since it can carry scripts.
+pub const CONTENT_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp", "application/pdf"];
+
+/// Image or attachment uploaded for templates, referenced from their bodies
+/// as `{{asset "logo.png"}}`. Assets are never replaced, nor collected once
+/// an email showing them was sent, so a sent email keeps showing what it was
+/// sent with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateAsset {
+    pub id: i64,
+    /// Unique per tenant
+    pub name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Hex SHA-256 of the content
+    pub sha256: String,
+    /// Key of the blob in the asset store
+    pub storage_key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An asset with a URL to download it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedAsset {
+    pub asset: TemplateAsset,
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// What an upload declares before its content arrives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewAsset {
+    pub name: String,
+    pub content_type: String,
+}
+
+impl NewAsset {
+    /// Names are 1 to 200 letters, digits, `-`, `_` and `.`, not starting
+    /// with a dot; the content type must be one of [`CONTENT_TYPES`]
+    pub fn validate(&self) -> Result<(), TemplateError> {
+        let valid_name = !self.name.is_empty()
+            && self.name.len() <= MAX_NAME_LEN
+            && !self.name.starts_with('.')
+            && self.name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid_name {
+            return Err(TemplateError::Validation(format!("invalid asset name: {:?}", self.name)));
+        }
+
+        if !CONTENT_TYPES.contains(&self.content_type.as_str()) {
+            return Err(TemplateError::Validation(format!(
+                "content type must be one of {}",
+                CONTENT_TYPES.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Check the size of the content and that it starts like a file of the
+    /// declared type
+    pub fn check_content(&self, content: &[u8]) -> Result<(), TemplateError> {
+        if content.is_empty() {
+            return Err(TemplateError::Validation("asset is empty".to_string()));
+        }
+        if content.len() > MAX_ASSET_BYTES {
+            return Err(TemplateError::Validation(format!("asset is larger than {MAX_ASSET_BYTES} bytes")));
+        }
+
+        let matches = match self.content_type.as_str() {
+            "image/png" => content.starts_with(b"\x89PNG\r\n\x1a\n"),
+            "image/jpeg" => content.starts_with(b"\xff\xd8\xff"),
+            "image/gif" => content.starts_with(b"GIF87a") || content.starts_with(b"GIF89a"),
+            "image/webp" => content.starts_with(b"RIFF") && content.get(8..12) == Some(b"WEBP".as_slice()),
+            "application/pdf" => content.starts_with(b"%PDF-"),
+            _ => false,
+        };
+        if !matches {
+            return Err(TemplateError::Validation(format!("content is not {}", self.content_type)));
+        }
+
+        Ok(())
+    }
+}
+
+/// An upload whose content is in the asset store, to be recorded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredAsset {
+    pub name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub storage_key: String,
+}
+
+/// Names of the assets a template body references with `{{asset "name"}}`
+pub fn references(body: &str) -> BTreeSet<String> {
+    body.match_indices("{{")
+        .filter_map(|(at, open)| {
+            let expression = body[at + open.len()..].trim_start_matches(['{', '~']);
+            let argument = expression.strip_prefix("asset")?;
+            if !argument.starts_with(char::is_whitespace) {
+                return None;
+            }
+
+            let argument = argument.trim_start();
+            let quote = argument.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+            let name = &argument[1..];
+            name.find(quote).map(|end| name[..end].to_string())
+        })
+        .collect()
+}
+
+/// Names of the assets a template and its translations reference
+pub fn template_references(template: &Template, translations: &[TemplateTranslation]) -> BTreeSet<String> {
+    let mut names = references(&template.body);
+    if let Some(text_body) = &template.text_body {
+        names.extend(references(text_body));
+    }
+    for translation in translations {
+        names.extend(references(&translation.body));
+    }
+    names
+}
@@ -0,0 +1,143 @@
+use serde_json::{json, Map, Value};
+
+use super::{TemplateError, TemplateFormat};
+use crate::domain::newsletter::attributes::{AttributeDefinition, AttributeType};
+
+/// Size of HTML past which Gmail clips a message behind "View entire message",
+/// hiding everything after the cut, the unsubscribe link and open pixel included
+pub const CLIP_BYTES: usize = 102 * 1024;
+
+/// Address the preview is rendered for
+const SAMPLE_EMAIL: &str = "subscriber@example.com";
+
+/// Unsubscribe link the preview is rendered with
+const SAMPLE_UNSUBSCRIBE_URL: &str = "https://example.com/unsubscribe";
+
+/// A template body checked before it is saved
+#[derive(Debug, Clone)]
+pub struct TemplateDraft {
+    pub format: TemplateFormat,
+    pub body: String,
+    pub text_body: Option<String>,
+    pub required_variables: Vec<String>,
+}
+
+/// What is wrong with a draft
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    /// The body does not compile as Handlebars or parse as MJML
+    Syntax,
+    /// The body reads a variable no send provides: neither `email`,
+    /// `unsubscribe_url`, a required variable nor a defined attribute
+    UnknownVariable,
+    /// The body compiles but rendering the sample failed
+    Render,
+    /// The rendered HTML is larger than [`CLIP_BYTES`]; sends still work
+    Clipped,
+}
+
+impl IssueKind {
+    /// Whether sending the template would fail; clipping only degrades it
+    pub fn is_error(&self) -> bool {
+        !matches!(self, IssueKind::Clipped)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateIssue {
+    pub kind: IssueKind,
+    pub message: String,
+    /// Path of the variable, for [`IssueKind::UnknownVariable`]
+    pub variable: Option<String>,
+}
+
+impl TemplateIssue {
+    pub fn syntax(error: TemplateError) -> Self {
+        Self {
+            kind: IssueKind::Syntax,
+            message: error.to_string(),
+            variable: None,
+        }
+    }
+
+    pub fn render(error: TemplateError) -> Self {
+        Self {
+            kind: IssueKind::Render,
+            message: error.to_string(),
+            variable: None,
+        }
+    }
+
+    pub fn unknown_variable(path: String) -> Self {
+        Self {
+            kind: IssueKind::UnknownVariable,
+            message: format!("{path} is not a defined attribute or required variable"),
+            variable: Some(path),
+        }
+    }
+
+    /// An issue if `html` would be clipped
+    pub fn clipping(html: &str) -> Option<Self> {
+        (html.len() > CLIP_BYTES).then(|| Self {
+            kind: IssueKind::Clipped,
+            message: format!("rendered HTML is {} bytes; Gmail clips messages over {CLIP_BYTES}", html.len()),
+            variable: None,
+        })
+    }
+}
+
+/// Outcome of checking a draft, with previews when it rendered
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateReport {
+    pub issues: Vec<TemplateIssue>,
+    pub html: Option<String>,
+    pub text: Option<String>,
+}
+
+impl TemplateReport {
+    /// Whether campaigns could send the draft
+    pub fn is_valid(&self) -> bool {
+        !self.issues.iter().any(|issue| issue.kind.is_error())
+    }
+}
+
+/// Render context of a made-up subscriber holding every defined attribute
+/// and a placeholder for each required variable, shaped like the one
+/// campaigns send with
+pub fn sample_context(definitions: &[AttributeDefinition], required_variables: &[String]) -> Value {
+    let attributes: Map<String, Value> = definitions
+        .iter()
+        .map(|definition| {
+            let value = match definition.kind {
+                AttributeType::String => json!(format!("[{}]", definition.key)),
+                AttributeType::Number => json!(42),
+                AttributeType::Boolean => json!(true),
+            };
+            (definition.key.clone(), value)
+        })
+        .collect();
+
+    let mut context = json!({
+        "email": SAMPLE_EMAIL,
+        "unsubscribe_url": SAMPLE_UNSUBSCRIBE_URL,
+        "attributes": attributes,
+    });
+    if let Some(context) = context.as_object_mut() {
+        for name in required_variables {
+            context.entry(name.clone()).or_insert_with(|| json!(format!("[{name}]")));
+        }
+    }
+    context
+}
+
+/// Lay the caller's `sample` over `context`, merging objects key by key
+pub fn merge_sample(context: &mut Value, sample: Value) {
+    match (context, sample) {
+        (Value::Object(context), Value::Object(sample)) => {
+            for (key, value) in sample {
+                merge_sample(context.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (context, sample) => *context = sample,
+    }
+}
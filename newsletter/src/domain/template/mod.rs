@@ -0,0 +1,199 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub mod asset;
+pub mod lint;
+pub mod translation;
+
+/// Markup language of a template body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemplateFormat {
+    /// Handlebars producing HTML directly
+    Handlebars,
+    /// Handlebars producing MJML, compiled to HTML after substitution
+    Mjml,
+}
+
+impl TemplateFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TemplateFormat::Handlebars => "handlebars",
+            TemplateFormat::Mjml => "mjml",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "handlebars" => Some(TemplateFormat::Handlebars),
+            "mjml" => Some(TemplateFormat::Mjml),
+            _ => None,
+        }
+    }
+}
+
+/// Errors raised by template invariants and rendering
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A field failed validation
+    Validation(String),
+    /// The render context lacks variables the template declares as required
+    MissingVariables(Vec<String>),
+    /// The template could not be compiled or rendered
+    Render(String),
+    /// The template was saved by someone else since it was read
+    VersionMismatch { expected: i64, actual: i64 },
+    /// The tenant already has an asset of this name
+    AssetExists(String),
+    /// No asset store is configured
+    AssetStorageDisabled,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Validation(message) => write!(f, "{message}"),
+            TemplateError::MissingVariables(names) => {
+                write!(f, "missing required variables: {}", names.join(", "))
+            }
+            TemplateError::Render(message) => write!(f, "template render failed: {message}"),
+            TemplateError::VersionMismatch { expected, actual } => {
+                write!(f, "template is at version {actual}, not {expected}")
+            }
+            TemplateError::AssetExists(name) => write!(f, "asset {name:?} already exists"),
+            TemplateError::AssetStorageDisabled => write!(f, "asset storage is not configured"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Reusable email template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    pub id: i64,
+    pub name: String,
+    pub format: TemplateFormat,
+    pub body: String,
+    /// Handlebars source of the text/plain part; `None` derives it from the
+    /// rendered HTML
+    pub text_body: Option<String>,
+    pub required_variables: Vec<String>,
+    /// Bumped by every save, which only applies on top of the version read
+    pub version: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Template {
+    /// Names of required variables absent (or null) in the render context
+    pub fn missing_variables(&self, context: &serde_json::Value) -> Vec<String> {
+        self.required_variables
+            .iter()
+            .filter(|name| context.get(name.as_str()).is_none_or(|v| v.is_null()))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Data required to create a template
+#[derive(Debug, Clone)]
+pub struct NewTemplate {
+    pub name: String,
+    pub format: TemplateFormat,
+    pub body: String,
+    pub text_body: Option<String>,
+    pub required_variables: Vec<String>,
+}
+
+impl NewTemplate {
+    pub fn validate(&self) -> Result<(), TemplateError> {
+        validate_text("name", &self.name)?;
+        validate_text("body", &self.body)?;
+        if let Some(text_body) = &self.text_body {
+            validate_text("text_body", text_body)?;
+        }
+        validate_variables(&self.required_variables)
+    }
+}
+
+/// Partial update of a template; `None` leaves the field untouched
+#[derive(Debug, Clone, Default)]
+pub struct TemplateUpdate {
+    pub name: Option<String>,
+    pub format: Option<TemplateFormat>,
+    pub body: Option<String>,
+    /// `Some(None)` goes back to deriving the plaintext from the HTML
+    pub text_body: Option<Option<String>>,
+    pub required_variables: Option<Vec<String>>,
+    /// Refuse the update unless the template is still at this version
+    pub expected_version: Option<i64>,
+}
+
+impl Template {
+    pub fn apply_update(&mut self, update: TemplateUpdate) -> Result<(), TemplateError> {
+        if let Some(expected) = update.expected_version {
+            if expected != self.version {
+                return Err(TemplateError::VersionMismatch {
+                    expected,
+                    actual: self.version,
+                });
+            }
+        }
+        if let Some(name) = update.name {
+            validate_text("name", &name)?;
+            self.name = name;
+        }
+        if let Some(format) = update.format {
+            self.format = format;
+        }
+        if let Some(body) = update.body {
+            validate_text("body", &body)?;
+            self.body = body;
+        }
+        if let Some(text_body) = update.text_body {
+            if let Some(text_body) = &text_body {
+                validate_text("text_body", text_body)?;
+            }
+            self.text_body = text_body;
+        }
+        if let Some(required_variables) = update.required_variables {
+            validate_variables(&required_variables)?;
+            self.required_variables = required_variables;
+        }
+
+        Ok(())
+    }
+}
+
+/// Output of rendering a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedTemplate {
+    pub html: String,
+    /// The text/plain alternative to the HTML
+    pub text: String,
+    /// Locale of the translation rendered; `None` for the template's own body
+    pub locale: Option<String>,
+}
+
+fn validate_text(field: &str, value: &str) -> Result<(), TemplateError> {
+    if value.trim().is_empty() {
+        return Err(TemplateError::Validation(format!("{field} cannot be empty")));
+    }
+
+    Ok(())
+}
+
+fn validate_variables(names: &[String]) -> Result<(), TemplateError> {
+    let is_identifier = |name: &String| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+
+    match names.iter().find(|name| !is_identifier(name)) {
+        Some(name) => Err(TemplateError::Validation(format!(
+            "invalid variable name: {name:?}"
+        ))),
+        None => Ok(()),
+    }
+}
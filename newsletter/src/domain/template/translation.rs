@@ -0,0 +1,90 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{validate_text, Template, TemplateError};
+use crate::domain::locale;
+
+/// A template body in another language. It is rendered with the format and
+/// required variables of its template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateTranslation {
+    pub template_id: i64,
+    /// BCP 47 tag in canonical casing
+    pub locale: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Most translations one bundle may carry
+pub const MAX_BUNDLE_LEN: usize = 200;
+
+impl TemplateTranslation {
+    /// Check a translation before it is stored; returns the locale in
+    /// canonical casing
+    pub fn validate(locale: &str, body: &str) -> Result<String, TemplateError> {
+        if !locale::is_valid(locale) {
+            return Err(TemplateError::Validation(format!(
+                "locale must be a BCP 47 tag of at most {} characters, such as en-US",
+                locale::MAX_LEN
+            )));
+        }
+        validate_text("body", body)?;
+
+        Ok(locale::canonical(locale))
+    }
+}
+
+/// Check a bundle of bodies keyed by locale; returns them keyed by canonical
+/// locale. Two keys differing only in case are one locale given twice.
+pub fn validate_bundle(bundle: HashMap<String, String>) -> Result<BTreeMap<String, String>, TemplateError> {
+    if bundle.len() > MAX_BUNDLE_LEN {
+        return Err(TemplateError::Validation(format!(
+            "a bundle holds at most {MAX_BUNDLE_LEN} translations"
+        )));
+    }
+
+    let mut validated = BTreeMap::new();
+    for (locale, body) in bundle {
+        let locale = TemplateTranslation::validate(&locale, &body)?;
+        if validated.contains_key(&locale) {
+            return Err(TemplateError::Validation(format!("locale {locale} is given twice")));
+        }
+        validated.insert(locale, body);
+    }
+    Ok(validated)
+}
+
+/// The first translation along `chain`, as built by `locale::lookup_chain`
+pub fn pick<'a>(translations: &'a [TemplateTranslation], chain: &[String]) -> Option<&'a TemplateTranslation> {
+    chain.iter().find_map(|tag| translations.iter().find(|t| t.locale.eq_ignore_ascii_case(tag)))
+}
+
+/// The template to render for a reader whose locales are `chain`, with the
+/// locale of the translation it took; the template itself when none matches
+pub fn localize<'a>(
+    template: &'a Template,
+    translations: &'a [TemplateTranslation],
+    chain: &[String],
+) -> (Cow<'a, Template>, Option<&'a str>) {
+    match pick(translations, chain) {
+        Some(translation) => (Cow::Owned(template.translated(translation)), Some(&translation.locale)),
+        None => (Cow::Borrowed(template), None),
+    }
+}
+
+impl Template {
+    /// This template with the body of `translation`. Its plaintext is
+    /// derived from the translated HTML, since the template's own
+    /// `text_body` is in the wrong language.
+    pub fn translated(&self, translation: &TemplateTranslation) -> Template {
+        Template {
+            body: translation.body.clone(),
+            text_body: None,
+            ..self.clone()
+        }
+    }
+}
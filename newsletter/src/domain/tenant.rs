@@ -0,0 +1,121 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Tenant of data written without naming one, and of every row that existed
+/// before tenants were introduced
+pub const DEFAULT_TENANT: &str = "default";
+
+const MAX_TENANT_LEN: usize = 63;
+
+/// Reason a tenant id was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTenant(&'static str);
+
+impl fmt::Display for InvalidTenant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid tenant id: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidTenant {}
+
+/// A shortlink workspace whose subscribers, campaigns and templates are kept
+/// apart from every other one's.
+///
+/// Lowercase ASCII letters, digits and `-`, starting with a letter or digit;
+/// the same rule is enforced by a check constraint on every tenant column.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TenantId(String);
+
+impl TenantId {
+    pub fn parse(value: &str) -> Result<Self, InvalidTenant> {
+        let tenant = value.trim().to_lowercase();
+
+        if tenant.is_empty() {
+            return Err(InvalidTenant("tenant cannot be empty"));
+        }
+        if tenant.len() > MAX_TENANT_LEN {
+            return Err(InvalidTenant("tenant is too long"));
+        }
+        if tenant.starts_with('-') {
+            return Err(InvalidTenant("tenant must start with a letter or digit"));
+        }
+        if !tenant.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(InvalidTenant("only letters, digits and '-' are allowed"));
+        }
+
+        Ok(Self(tenant))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for TenantId {
+    fn default() -> Self {
+        Self(DEFAULT_TENANT.to_string())
+    }
+}
+
+impl fmt::Display for TenantId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for TenantId {
+    type Error = InvalidTenant;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<TenantId> for String {
+    fn from(value: TenantId) -> Self {
+        value.0
+    }
+}
+
+/// Which tenants' rows a unit of work may see
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TenantScope {
+    /// A single tenant; everything written belongs to it
+    One(TenantId),
+    /// Every tenant, for maintenance that sweeps the whole database. Reads,
+    /// updates and deletes span all tenants; inserts are rejected since the
+    /// new rows would belong to none.
+    All,
+}
+
+impl TenantScope {
+    /// Value of the `app.tenant_id` setting the row-level security policies compare against
+    pub fn setting(&self) -> &str {
+        match self {
+            TenantScope::One(tenant) => tenant.as_str(),
+            TenantScope::All => "*",
+        }
+    }
+
+    pub fn tenant(&self) -> Option<&TenantId> {
+        match self {
+            TenantScope::One(tenant) => Some(tenant),
+            TenantScope::All => None,
+        }
+    }
+}
+
+impl Default for TenantScope {
+    fn default() -> Self {
+        TenantScope::One(TenantId::default())
+    }
+}
+
+impl fmt::Display for TenantScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.setting())
+    }
+}
@@ -0,0 +1,17 @@
+use chrono_tz::Tz;
+
+/// Longest zone name accepted
+pub const MAX_LEN: usize = 64;
+
+/// The IANA zone named `name`, such as `Europe/Berlin`
+pub fn parse(name: &str) -> Option<Tz> {
+    if name.len() > MAX_LEN {
+        return None;
+    }
+    name.parse::<Tz>().ok()
+}
+
+/// Whether `name` is a known IANA zone
+pub fn is_valid(name: &str) -> bool {
+    parse(name).is_some()
+}
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// A webhook delivery that exhausted its retries and was parked for inspection
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeadLetter {
+    pub url: String,
+    pub event_type: String,
+    pub payload: Value,
+    pub attempts: u32,
+    pub last_error: String,
+}
+
+/// Deliveries to one endpoint that have not gone through
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookBacklog {
+    pub url: String,
+    /// Deliveries queued or waiting for a retry
+    pub pending: i64,
+    /// When the oldest pending delivery was queued
+    pub oldest_pending_at: Option<DateTime<Utc>>,
+    /// Deliveries given up on and parked
+    pub dead_letters: i64,
+}
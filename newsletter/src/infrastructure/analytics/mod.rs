@@ -0,0 +1,41 @@
+use std::{env, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::domain::analytics::DailyRows;
+
+#[cfg(feature = "analytics-export")]
+pub mod parquet;
+
+/// Stores exported days where the data team's warehouse reads them
+#[async_trait]
+pub trait AnalyticsSink: Send + Sync {
+    /// Short sink name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Write one day of a dataset, replacing whatever an earlier attempt
+    /// left in its partition
+    async fn write(&self, day: NaiveDate, rows: &DailyRows) -> anyhow::Result<()>;
+}
+
+/// Build the configured sink from the environment.
+///
+/// `ANALYTICS_EXPORT_URL` names the bucket and prefix the Parquet files go
+/// to: `s3://bucket/prefix`, `gs://bucket/prefix` or `file:///path`; unset
+/// or empty exports nothing.
+pub fn sink_from_env() -> anyhow::Result<Option<Arc<dyn AnalyticsSink>>> {
+    let url = env::var("ANALYTICS_EXPORT_URL").unwrap_or_default();
+    if url.is_empty() {
+        return Ok(None);
+    }
+
+    #[cfg(feature = "analytics-export")]
+    {
+        Ok(Some(Arc::new(parquet::ParquetSink::from_url(&url)?)))
+    }
+    #[cfg(not(feature = "analytics-export"))]
+    {
+        anyhow::bail!("ANALYTICS_EXPORT_URL requires the `analytics-export` feature")
+    }
+}
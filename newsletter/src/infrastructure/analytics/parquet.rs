@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use object_store::aws::AmazonS3Builder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::{ObjectStore, PutPayload};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+use tracing::info;
+
+use super::AnalyticsSink;
+use crate::domain::analytics::{partition, DailyRows, EngagementRecord, SubscriptionEventRecord};
+
+/// Name of the single file written to each partition
+const PART_FILE: &str = "part-00000.parquet";
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()))
+}
+
+fn engagement_batch(rows: &[EngagementRecord]) -> anyhow::Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("tenant_id", DataType::Utf8, false),
+        Field::new("campaign_id", DataType::Int64, false),
+        Field::new("email", DataType::Utf8, false),
+        Field::new("kind", DataType::Utf8, false),
+        Field::new("url", DataType::Utf8, true),
+        Field::new("occurred_at", timestamp_type(), false),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.tenant.as_str()))),
+        Arc::new(Int64Array::from_iter_values(rows.iter().map(|r| r.campaign_id))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.email.as_str()))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.kind.as_str()))),
+        Arc::new(StringArray::from_iter(rows.iter().map(|r| r.url.as_deref()))),
+        Arc::new(
+            TimestampMicrosecondArray::from_iter_values(rows.iter().map(|r| r.occurred_at.timestamp_micros()))
+                .with_timezone("UTC"),
+        ),
+    ];
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+fn subscription_events_batch(rows: &[SubscriptionEventRecord]) -> anyhow::Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        Field::new("tenant_id", DataType::Utf8, false),
+        Field::new("event_type", DataType::Utf8, false),
+        Field::new("email", DataType::Utf8, false),
+        Field::new("previous_email", DataType::Utf8, true),
+        Field::new("status", DataType::Utf8, true),
+        Field::new("occurred_at", timestamp_type(), false),
+    ]);
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.tenant.as_str()))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.event_type.as_str()))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.email.as_str()))),
+        Arc::new(StringArray::from_iter(rows.iter().map(|r| r.previous_email.as_deref()))),
+        Arc::new(StringArray::from_iter(rows.iter().map(|r| r.status.as_deref()))),
+        Arc::new(
+            TimestampMicrosecondArray::from_iter_values(rows.iter().map(|r| r.occurred_at.timestamp_micros()))
+                .with_timezone("UTC"),
+        ),
+    ];
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+/// Encode a day as one zstd-compressed Parquet file; a day without rows
+/// still gets a file, with the schema only
+fn encode(rows: &DailyRows) -> anyhow::Result<Vec<u8>> {
+    let batch = match rows {
+        DailyRows::Engagement(rows) => engagement_batch(rows)?,
+        DailyRows::SubscriptionEvents(rows) => subscription_events_batch(rows)?,
+    };
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::ZSTD(ZstdLevel::default()))
+        .build();
+
+    let mut buffer = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(properties))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(buffer)
+}
+
+/// Writes each day as a Parquet file to S3, GCS or a local directory, under
+/// `{prefix}/{dataset}/date={day}/part-00000.parquet`
+pub struct ParquetSink {
+    store: Arc<dyn ObjectStore>,
+    prefix: Path,
+}
+
+impl ParquetSink {
+    pub fn new(store: Arc<dyn ObjectStore>, prefix: Path) -> Self {
+        Self { store, prefix }
+    }
+
+    /// Configure from a `s3://`, `gs://` or `file://` URL. Credentials and
+    /// regions come from the usual `AWS_*` and `GOOGLE_*` variables.
+    pub fn from_url(url: &str) -> anyhow::Result<Self> {
+        let (scheme, rest) = url
+            .split_once("://")
+            .with_context(|| format!("ANALYTICS_EXPORT_URL is not a URL: {url}"))?;
+
+        let (store, prefix): (Arc<dyn ObjectStore>, &str) = match scheme {
+            "s3" | "s3a" => {
+                let store = AmazonS3Builder::from_env()
+                    .with_url(url)
+                    .build()
+                    .context("failed to configure the S3 analytics bucket")?;
+                (Arc::new(store), rest.split_once('/').map_or("", |(_, prefix)| prefix))
+            }
+            "gs" => {
+                let store = GoogleCloudStorageBuilder::from_env()
+                    .with_url(url)
+                    .build()
+                    .context("failed to configure the GCS analytics bucket")?;
+                (Arc::new(store), rest.split_once('/').map_or("", |(_, prefix)| prefix))
+            }
+            "file" => {
+                std::fs::create_dir_all(rest)
+                    .with_context(|| format!("failed to create the analytics directory {rest}"))?;
+                let store = LocalFileSystem::new_with_prefix(rest)
+                    .context("failed to open the analytics directory")?;
+                (Arc::new(store), "")
+            }
+            other => anyhow::bail!("unsupported ANALYTICS_EXPORT_URL scheme: {other}"),
+        };
+
+        Ok(Self::new(store, Path::from(prefix)))
+    }
+
+    fn path(&self, rows: &DailyRows, day: NaiveDate) -> Path {
+        let mut path = self.prefix.clone();
+        for part in partition(rows.dataset(), day).split('/').chain([PART_FILE]) {
+            path = path.child(part);
+        }
+        path
+    }
+}
+
+#[async_trait]
+impl AnalyticsSink for ParquetSink {
+    fn name(&self) -> &'static str {
+        "parquet"
+    }
+
+    async fn write(&self, day: NaiveDate, rows: &DailyRows) -> anyhow::Result<()> {
+        let path = self.path(rows, day);
+        let file = encode(rows)?;
+        let bytes = file.len();
+
+        self.store
+            .put(&path, PutPayload::from(file))
+            .await
+            .with_context(|| format!("failed to write {path}"))?;
+
+        info!(path = %path, rows = rows.len(), bytes = bytes, "Wrote analytics partition");
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use std::{env, sync::Arc};
+
+use async_trait::async_trait;
+use chrono::Duration;
+
+#[cfg(feature = "template-assets")]
+pub mod s3;
+
+/// Blob storage holding the content of template assets
+#[async_trait]
+pub trait AssetStore: Send + Sync {
+    /// Short store name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Store `content` under `key`
+    async fn put(&self, key: &str, content_type: &str, content: Vec<u8>) -> anyhow::Result<()>;
+
+    /// Remove the blob under `key`; a missing blob is not an error
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+
+    /// URL that downloads the blob under `key` without credentials until
+    /// `expires_in` has passed
+    async fn signed_url(&self, key: &str, expires_in: Duration) -> anyhow::Result<String>;
+}
+
+/// Build the configured store from the environment.
+///
+/// `ASSET_STORE_URL` names the S3 bucket and prefix assets go to, as
+/// `s3://bucket/prefix`; unset or empty disables asset uploads.
+pub fn store_from_env() -> anyhow::Result<Option<Arc<dyn AssetStore>>> {
+    let url = env::var("ASSET_STORE_URL").unwrap_or_default();
+    if url.is_empty() {
+        return Ok(None);
+    }
+
+    #[cfg(feature = "template-assets")]
+    {
+        Ok(Some(Arc::new(s3::S3AssetStore::from_url(&url)?)))
+    }
+    #[cfg(not(feature = "template-assets"))]
+    {
+        anyhow::bail!("ASSET_STORE_URL requires the `template-assets` feature")
+    }
+}
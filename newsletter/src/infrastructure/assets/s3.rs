@@ -0,0 +1,82 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::Duration;
+use http::Method;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path;
+use object_store::signer::Signer;
+use object_store::{Attribute, Attributes, ObjectStore, PutOptions, PutPayload};
+
+use super::AssetStore;
+
+/// Keeps assets in an S3 bucket, or any S3-compatible store such as MinIO
+/// or R2 when `AWS_ENDPOINT` points at it
+pub struct S3AssetStore {
+    store: AmazonS3,
+    prefix: Path,
+}
+
+impl S3AssetStore {
+    /// Configure from a `s3://bucket/prefix` URL. Credentials, region and
+    /// endpoint come from the usual `AWS_*` variables.
+    pub fn from_url(url: &str) -> anyhow::Result<Self> {
+        let rest = url
+            .strip_prefix("s3://")
+            .with_context(|| format!("ASSET_STORE_URL must be an s3:// URL: {url}"))?;
+        let store = AmazonS3Builder::from_env()
+            .with_url(url)
+            .build()
+            .context("failed to configure the asset bucket")?;
+
+        Ok(Self {
+            store,
+            prefix: Path::from(rest.split_once('/').map_or("", |(_, prefix)| prefix)),
+        })
+    }
+
+    fn path(&self, key: &str) -> Path {
+        key.split('/').fold(self.prefix.clone(), |path, part| path.child(part))
+    }
+}
+
+#[async_trait]
+impl AssetStore for S3AssetStore {
+    fn name(&self) -> &'static str {
+        "s3"
+    }
+
+    async fn put(&self, key: &str, content_type: &str, content: Vec<u8>) -> anyhow::Result<()> {
+        let path = self.path(key);
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute::ContentType, content_type.to_string().into());
+        let options = PutOptions {
+            attributes,
+            ..PutOptions::default()
+        };
+
+        self.store
+            .put_opts(&path, PutPayload::from(content), options)
+            .await
+            .with_context(|| format!("failed to write {path}"))?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        let path = self.path(key);
+        match self.store.delete(&path).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(anyhow::Error::new(e).context(format!("failed to delete {path}"))),
+        }
+    }
+
+    async fn signed_url(&self, key: &str, expires_in: Duration) -> anyhow::Result<String> {
+        let path = self.path(key);
+        let expires_in = expires_in.to_std().context("signed URL lifetime must be positive")?;
+        let url = self
+            .store
+            .signed_url(Method::GET, &path, expires_in)
+            .await
+            .with_context(|| format!("failed to sign a URL for {path}"))?;
+        Ok(url.to_string())
+    }
+}
@@ -0,0 +1,36 @@
+//! What the running binary was built from, embedded by `build.rs`
+
+use chrono::{DateTime, Utc};
+
+/// The crate version
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Commit the binary was built from: `GIT_COMMIT` when set at build time,
+/// otherwise the checkout's HEAD; empty when neither was available
+pub const GIT_SHA: &str = env!("VERGEN_GIT_SHA");
+
+/// RFC 3339 time the build script last ran
+pub const BUILD_TIMESTAMP: &str = env!("VERGEN_BUILD_TIMESTAMP");
+
+/// Optional features compiled into this binary
+const FEATURES: &[(&str, bool)] = &[
+    ("analytics-export", cfg!(feature = "analytics-export")),
+    ("kafka", cfg!(feature = "kafka")),
+    ("nats", cfg!(feature = "nats")),
+    ("redis", cfg!(feature = "redis")),
+    ("ses", cfg!(feature = "ses")),
+    ("sqlite", cfg!(feature = "sqlite")),
+    ("template-assets", cfg!(feature = "template-assets")),
+    ("testing", cfg!(feature = "testing")),
+];
+
+/// Names of the optional features compiled in, such as `kafka`
+pub fn features() -> Vec<&'static str> {
+    FEATURES.iter().filter(|(_, enabled)| *enabled).map(|(name, _)| *name).collect()
+}
+
+pub fn built_at() -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(BUILD_TIMESTAMP)
+        .ok()
+        .map(|at| at.with_timezone(&Utc))
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use super::CacheProvider;
+
+/// CacheProvider kept in process memory, for tests that run without Redis
+#[derive(Debug, Default)]
+pub struct InMemoryCacheProvider {
+    entries: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl InMemoryCacheProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<String, (String, Instant)>> {
+        self.entries.lock().expect("in-memory cache lock poisoned")
+    }
+}
+
+#[async_trait]
+impl CacheProvider for InMemoryCacheProvider {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<String>> {
+        let now = Instant::now();
+        Ok(self
+            .entries()
+            .get(key)
+            .filter(|(_, expires_at)| *expires_at > now)
+            .map(|(value, _)| value.clone()))
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> anyhow::Result<()> {
+        self.entries()
+            .insert(key.to_string(), (value.to_string(), Instant::now() + ttl));
+        Ok(())
+    }
+
+    async fn delete(&self, keys: &[String]) -> anyhow::Result<()> {
+        let mut entries = self.entries();
+        for key in keys {
+            entries.remove(key);
+        }
+        Ok(())
+    }
+
+    async fn clear(&self) -> anyhow::Result<usize> {
+        let mut entries = self.entries();
+        let cleared = entries.len();
+        entries.clear();
+        Ok(cleared)
+    }
+}
@@ -0,0 +1,122 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::{info, warn};
+
+use crate::infrastructure::config::CacheSettings;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod memory;
+#[cfg(feature = "redis")]
+pub mod redis;
+
+/// How long a cached read is served when the settings do not say
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// Key-value store for cached reads, shared by every instance
+#[async_trait]
+pub trait CacheProvider: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<String>>;
+
+    /// Store `value` under `key` until `ttl` passes
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> anyhow::Result<()>;
+
+    /// Drop the given keys; missing ones are ignored
+    async fn delete(&self, keys: &[String]) -> anyhow::Result<()>;
+
+    /// Drop every entry; returns how many there were
+    async fn clear(&self) -> anyhow::Result<usize>;
+}
+
+/// Cache configured by `settings`; `None` when no Redis URL is set
+pub async fn from_settings(settings: &CacheSettings) -> anyhow::Result<Option<Cache>> {
+    let Some(url) = settings.enabled() else {
+        return Ok(None);
+    };
+
+    let provider = connect(url).await?;
+    info!(cache = provider.name(), ttl_secs = settings.ttl_secs, "Configured read cache");
+
+    Ok(Some(Cache::new(provider, settings.ttl())))
+}
+
+#[cfg(feature = "redis")]
+async fn connect(url: &str) -> anyhow::Result<Arc<dyn CacheProvider>> {
+    Ok(Arc::new(redis::RedisCacheProvider::connect(url).await?))
+}
+
+#[cfg(not(feature = "redis"))]
+async fn connect(_url: &str) -> anyhow::Result<Arc<dyn CacheProvider>> {
+    anyhow::bail!("cache.redis_url (CACHE_REDIS_URL) requires the `redis` feature")
+}
+
+/// Read-through cache of serializable values on top of a [`CacheProvider`].
+///
+/// The cache only ever saves work: a provider that fails is logged and
+/// treated as a miss, and a failed invalidation leaves the entry to expire
+/// with its TTL.
+#[derive(Clone)]
+pub struct Cache {
+    provider: Arc<dyn CacheProvider>,
+    ttl: Duration,
+}
+
+impl Cache {
+    pub fn new(provider: Arc<dyn CacheProvider>, ttl: Duration) -> Self {
+        Self { provider, ttl }
+    }
+
+    /// The value cached under `key`, or the result of `load`, which is cached
+    /// when it succeeds
+    pub async fn get_or_load<T, E, F, Fut>(&self, key: &str, load: F) -> Result<T, E>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Result<T, E>> + Send,
+    {
+        match self.provider.get(key).await {
+            Ok(Some(cached)) => match serde_json::from_str(&cached) {
+                Ok(value) => return Ok(value),
+                Err(e) => warn!(cache = self.provider.name(), key = key, error = %e, "Ignoring unreadable cache entry"),
+            },
+            Ok(None) => {}
+            Err(e) => warn!(cache = self.provider.name(), key = key, error = %e, "Failed to read cache"),
+        }
+
+        let value = load().await?;
+        match serde_json::to_string(&value) {
+            Ok(encoded) => {
+                if let Err(e) = self.provider.set(key, &encoded, self.ttl).await {
+                    warn!(cache = self.provider.name(), key = key, error = %e, "Failed to fill cache");
+                }
+            }
+            Err(e) => warn!(cache = self.provider.name(), key = key, error = %e, "Failed to encode cache entry"),
+        }
+        Ok(value)
+    }
+
+    /// Drop entries after the data behind them changed
+    pub async fn invalidate(&self, keys: &[String]) {
+        if keys.is_empty() {
+            return;
+        }
+        if let Err(e) = self.provider.delete(keys).await {
+            warn!(cache = self.provider.name(), count = keys.len(), error = %e, "Failed to invalidate cache, entries expire with their TTL");
+        }
+    }
+
+    /// Drop every entry, for every tenant; returns how many were dropped.
+    /// Unlike the other operations a failure is returned, as the caller asked
+    /// for nothing else.
+    pub async fn clear(&self) -> anyhow::Result<usize> {
+        let cleared = self.provider.clear().await?;
+        info!(cache = self.provider.name(), entries = cleared, "Cleared cache");
+        Ok(cleared)
+    }
+}
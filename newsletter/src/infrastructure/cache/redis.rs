@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+
+use super::CacheProvider;
+
+/// Keys are stored under this prefix, apart from the rate limiter's buckets
+const KEY_PREFIX: &str = "newsletter:cache:";
+
+/// Keys looked at by each `SCAN` of a clear
+const SCAN_COUNT: usize = 500;
+
+/// Cached reads shared by every instance through Redis
+pub struct RedisCacheProvider {
+    connection: ConnectionManager,
+}
+
+impl RedisCacheProvider {
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        let connection = redis::Client::open(url)?.get_connection_manager().await?;
+        Ok(Self { connection })
+    }
+
+    fn key(key: &str) -> String {
+        format!("{KEY_PREFIX}{key}")
+    }
+}
+
+#[async_trait]
+impl CacheProvider for RedisCacheProvider {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<String>> {
+        let mut connection = self.connection.clone();
+        Ok(connection.get(Self::key(key)).await?)
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> anyhow::Result<()> {
+        let mut connection = self.connection.clone();
+        let millis = u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX).max(1);
+        let _: () = redis::cmd("SET")
+            .arg(Self::key(key))
+            .arg(value)
+            .arg("PX")
+            .arg(millis)
+            .query_async(&mut connection)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, keys: &[String]) -> anyhow::Result<()> {
+        let mut connection = self.connection.clone();
+        let keys: Vec<String> = keys.iter().map(|key| Self::key(key)).collect();
+        let _: i64 = connection.del(keys).await?;
+        Ok(())
+    }
+
+    async fn clear(&self) -> anyhow::Result<usize> {
+        let mut connection = self.connection.clone();
+        let pattern = format!("{KEY_PREFIX}*");
+        let mut cursor: u64 = 0;
+        let mut cleared = 0;
+        // SCAN rather than KEYS, so a large cache does not block Redis
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(SCAN_COUNT)
+                .query_async(&mut connection)
+                .await?;
+            if !keys.is_empty() {
+                let deleted: usize = connection.del(keys).await?;
+                cleared += deleted;
+            }
+            if next == 0 {
+                return Ok(cleared);
+            }
+            cursor = next;
+        }
+    }
+}
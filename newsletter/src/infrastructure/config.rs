@@ -0,0 +1,1355 @@
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use figment::providers::{Env, Format, Yaml};
+use figment::Figment;
+use serde::Deserialize;
+
+use crate::domain::campaign::digest::{Digest, SourceFormat};
+use crate::domain::campaign::reengagement::Reengagement;
+use crate::domain::campaign::warmup::{WarmupDomains, WarmupSchedule};
+use crate::domain::locale;
+use crate::domain::newsletter::normalize::Normalization;
+use crate::domain::newsletter::retention::{RetentionAction, RetentionPolicy, RetentionRules};
+use crate::domain::newsletter::{validate_domain, Tag};
+use crate::domain::tenant::TenantId;
+use crate::infrastructure::db::{DatabaseBackend, MigrationsMode};
+use crate::infrastructure::email::failover::{FailoverPolicy, SelectionStrategy};
+use crate::infrastructure::email::smtp::SmtpConfig;
+use crate::infrastructure::email::ProviderKind;
+use crate::infrastructure::logging;
+use crate::infrastructure::metrics::{self, MetricsBackend};
+use crate::infrastructure::pseudonym::Pseudonymizer;
+use crate::infrastructure::rpc::compression::Codec;
+use crate::infrastructure::rpc::rate_limit::{Quota, RateLimitConfig};
+use crate::repository::breaker::BreakerPolicy;
+use crate::repository::retry::RetryPolicy;
+use crate::service::campaign::sender::SendThrottle;
+
+/// File read when `CONFIG_FILE` is not set; a missing file is ignored
+pub const DEFAULT_CONFIG_FILE: &str = "config.yaml";
+
+/// Environment variables that override a setting, with the setting they set
+const ENV_KEYS: &[(&str, &str)] = &[
+    ("HOST", "server.host"),
+    ("PORT", "server.port"),
+    ("STRICT_STATUS_CODES", "server.strict_status_codes"),
+    ("TRUST_FORWARDED_FOR", "server.trust_forwarded_for"),
+    ("SERVER_MAX_DEADLINE_MS", "server.max_deadline_ms"),
+    ("SERVER_COMPRESSION", "server.compression"),
+    ("SERVER_COMPRESSION_MIN_BYTES", "server.compression_min_bytes"),
+    ("NEWSLETTER_MAX_RECEIVE_BYTES", "messages.newsletter.max_receive_bytes"),
+    ("NEWSLETTER_MAX_SEND_BYTES", "messages.newsletter.max_send_bytes"),
+    ("CAMPAIGN_MAX_RECEIVE_BYTES", "messages.campaign.max_receive_bytes"),
+    ("CAMPAIGN_MAX_SEND_BYTES", "messages.campaign.max_send_bytes"),
+    ("TEMPLATE_MAX_RECEIVE_BYTES", "messages.template.max_receive_bytes"),
+    ("TEMPLATE_MAX_SEND_BYTES", "messages.template.max_send_bytes"),
+    ("ADMIN_MAX_RECEIVE_BYTES", "messages.admin.max_receive_bytes"),
+    ("ADMIN_MAX_SEND_BYTES", "messages.admin.max_send_bytes"),
+    ("TLS_CERT_PATH", "tls.cert_path"),
+    ("TLS_KEY_PATH", "tls.key_path"),
+    ("TLS_CLIENT_CA_PATH", "tls.client_ca_path"),
+    ("DATABASE_BACKEND", "database.backend"),
+    ("DATABASE_URL", "database.url"),
+    ("DATABASE_REPLICA_URL", "database.replica_url"),
+    ("DATABASE_MIGRATE_ON_STARTUP", "database.migrate_on_startup"),
+    ("MIGRATIONS_MODE", "database.migrations_mode"),
+    ("DATABASE_POOL_MAX_SIZE", "database.max_size"),
+    ("DATABASE_POOL_MIN_IDLE", "database.min_idle"),
+    ("DATABASE_ACQUIRE_TIMEOUT_MS", "database.acquire_timeout_ms"),
+    ("DATABASE_MAX_LIFETIME_SECS", "database.max_lifetime_secs"),
+    ("DATABASE_IDLE_TIMEOUT_SECS", "database.idle_timeout_secs"),
+    ("DATABASE_STATEMENT_TIMEOUT_MS", "database.statement_timeout_ms"),
+    ("DATABASE_RETRY_MAX_ATTEMPTS", "database.retry_max_attempts"),
+    ("DATABASE_RETRY_BASE_DELAY_MS", "database.retry_base_delay_ms"),
+    ("DATABASE_RETRY_BUDGET", "database.retry_budget"),
+    ("DATABASE_BREAKER_WINDOW", "database.breaker_window"),
+    ("DATABASE_BREAKER_MIN_CALLS", "database.breaker_min_calls"),
+    ("DATABASE_BREAKER_FAILURE_PERCENT", "database.breaker_failure_percent"),
+    ("DATABASE_BREAKER_OPEN_MS", "database.breaker_open_ms"),
+    ("CONFIRMATION_SECRET", "confirmation.secret"),
+    ("CONFIRMATION_TTL_SECS", "confirmation.ttl_secs"),
+    ("CONFIRMATION_URL", "confirmation.url"),
+    ("CONFIRMATION_EMAIL_CHANGE_URL", "confirmation.email_change_url"),
+    ("CONFIRMATION_TOPIC_URL", "confirmation.topic_url"),
+    ("CONFIRMATION_REQUIRED_ON_RESUBSCRIBE", "confirmation.required_on_resubscribe"),
+    ("CONFIRMATION_PENDING_RETENTION_SECS", "confirmation.pending_retention_secs"),
+    ("RETENTION_UNSUBSCRIBED_DAYS", "retention.unsubscribed_days"),
+    ("RETENTION_ACTION", "retention.action"),
+    ("NORMALIZATION_FOLD_GMAIL_ALIASES", "normalization.fold_gmail_aliases"),
+    ("VERIFICATION_MX_LOOKUP", "verification.mx_lookup"),
+    ("VERIFICATION_DISPOSABLE_DOMAINS", "verification.disposable_domains"),
+    ("AUTH_ENABLED", "auth.enabled"),
+    ("AUTH_BOOTSTRAP_ADMIN_KEY", "auth.bootstrap_admin_key"),
+    ("AUTH_BOOTSTRAP_OPERATOR_KEY", "auth.bootstrap_operator_key"),
+    ("JOBS_POLL_INTERVAL_MS", "jobs.poll_interval_ms"),
+    ("JOBS_BATCH_SIZE", "jobs.batch_size"),
+    ("OUTBOX_POLL_INTERVAL_MS", "outbox.poll_interval_ms"),
+    ("OUTBOX_BATCH_SIZE", "outbox.batch_size"),
+    ("OUTBOX_RETENTION_SECS", "outbox.retention_secs"),
+    ("OUTBOX_READ_MODEL", "outbox.read_model"),
+    ("IDEMPOTENCY_TTL_SECS", "idempotency.ttl_secs"),
+    ("IMPORT_MAX_BATCHES_IN_FLIGHT", "import.max_batches_in_flight"),
+    ("IMPORT_MAX_WAIT_MS", "import.max_wait_ms"),
+    ("CACHE_REDIS_URL", "cache.redis_url"),
+    ("CACHE_TTL_SECS", "cache.ttl_secs"),
+    ("CAMPAIGN_BATCH_SIZE", "campaign.batch_size"),
+    ("CAMPAIGN_BATCHES_PER_MINUTE", "campaign.batches_per_minute"),
+    ("CAMPAIGN_LOCALE_FALLBACKS", "campaign.locale_fallbacks"),
+    ("CAMPAIGN_TEST_RECIPIENTS", "campaign.test_recipients"),
+    ("CAMPAIGN_WARMUP", "campaign.warmup"),
+    ("CAMPAIGN_WARMUP_DOMAINS", "campaign.warmup_domains"),
+    ("CAMPAIGN_WARMUP_SKIP", "campaign.warmup_skip"),
+    ("TRACKING_URL", "tracking.url"),
+    ("TRACKING_SECRET", "tracking.secret"),
+    ("TRACKING_PORT", "tracking.port"),
+    ("SHUTDOWN_DRAIN_TIMEOUT_SECS", "shutdown.drain_timeout_secs"),
+    ("RATE_LIMIT_IP_RPS", "rate_limit.ip_rps"),
+    ("RATE_LIMIT_IP_BURST", "rate_limit.ip_burst"),
+    ("RATE_LIMIT_KEY_RPS", "rate_limit.key_rps"),
+    ("RATE_LIMIT_KEY_BURST", "rate_limit.key_burst"),
+    ("RATE_LIMIT_TRUST_FORWARDED", "rate_limit.trust_forwarded"),
+    ("LOG_PII", "logging.pii"),
+    ("LOG_FILTER", "logging.filter"),
+    ("METRICS_BACKEND", "metrics.backend"),
+    ("METRICS_PORT", "metrics.port"),
+    ("METRICS_STATSD_ADDR", "metrics.statsd_addr"),
+    ("METRICS_PREFIX", "metrics.prefix"),
+    ("PII_PSEUDONYM_KEY", "privacy.pseudonym_key"),
+];
+
+/// Typed settings of the service.
+///
+/// Read from a YAML file laid out like this struct, then overridden by the
+/// environment variables in `ENV_KEYS`. A single email provider, events,
+/// webhooks and the rate limit store are configured by their own modules. `Reloader` applies the log
+/// filter, rate limits and blocklist again on SIGHUP.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub server: ServerSettings,
+    pub messages: MessageSizeSettings,
+    pub tls: TlsSettings,
+    pub database: DatabaseSettings,
+    pub confirmation: ConfirmationSettings,
+    pub retention: RetentionSettings,
+    pub normalization: NormalizationSettings,
+    pub verification: VerificationSettings,
+    pub auth: AuthSettings,
+    pub jobs: JobsSettings,
+    pub outbox: OutboxSettings,
+    pub idempotency: IdempotencySettings,
+    pub import: ImportSettings,
+    pub cache: CacheSettings,
+    pub campaign: CampaignSettings,
+    /// Email providers to fail over between; only settable in the file
+    pub email: EmailSettings,
+    /// Periodic digest campaigns; only settable in the file
+    pub digests: Vec<DigestSettings>,
+    /// Win-back flows for inactive subscribers, at most one per tenant; only
+    /// settable in the file
+    pub reengagement: Vec<ReengagementSettings>,
+    pub tracking: TrackingSettings,
+    pub shutdown: ShutdownSettings,
+    pub rate_limit: RateLimitSettings,
+    pub logging: LoggingSettings,
+    pub metrics: MetricsSettings,
+    pub privacy: PrivacySettings,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSettings {
+    pub host: String,
+    /// gRPC port
+    pub port: u16,
+    /// Report subscribing an active address and unsubscribing an unknown one
+    /// as ALREADY_EXISTS and NOT_FOUND; off for clients that expect success
+    pub strict_status_codes: bool,
+    /// Record the first `x-forwarded-for` entry as the consenting client's IP
+    /// instead of the peer address; only safe behind a proxy that overwrites it
+    pub trust_forwarded_for: bool,
+    /// Longest a call may run, and the deadline of calls that set none;
+    /// shorter `grpc-timeout` deadlines from clients win, 0 leaves calls
+    /// without one unbounded
+    pub max_deadline_ms: u64,
+    /// Codecs the newsletter service decompresses requests with and
+    /// compresses replies with when the client accepts them; empty turns
+    /// compression off
+    pub compression: Vec<Codec>,
+    /// Replies of the newsletter service smaller than this go uncompressed
+    pub compression_min_bytes: usize,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            host: "0.0.0.0".to_string(),
+            port: 50051,
+            strict_status_codes: false,
+            trust_forwarded_for: false,
+            max_deadline_ms: 60_000,
+            compression: vec![Codec::Gzip, Codec::Zstd],
+            compression_min_bytes: 1024,
+        }
+    }
+}
+
+impl ServerSettings {
+    pub fn max_deadline(&self) -> Option<Duration> {
+        (self.max_deadline_ms > 0).then(|| Duration::from_millis(self.max_deadline_ms))
+    }
+}
+
+/// Largest gRPC message accepted by a limit, 256 MiB
+pub const MAX_MESSAGE_BYTES: usize = 256 * 1024 * 1024;
+
+const MIB: usize = 1024 * 1024;
+
+/// Message size limits of each gRPC service; v2 of the newsletter service
+/// shares v1's
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MessageSizeSettings {
+    pub newsletter: MessageLimits,
+    pub campaign: MessageLimits,
+    pub template: MessageLimits,
+    pub admin: MessageLimits,
+}
+
+impl Default for MessageSizeSettings {
+    fn default() -> Self {
+        Self {
+            // Each ImportSubscribers chunk is one message; uploaders that
+            // send whole files in one chunk need the headroom
+            newsletter: MessageLimits {
+                max_receive_bytes: 16 * MIB,
+                ..MessageLimits::default()
+            },
+            campaign: MessageLimits::default(),
+            template: MessageLimits::default(),
+            admin: MessageLimits::default(),
+        }
+    }
+}
+
+impl MessageSizeSettings {
+    fn is_valid(&self) -> bool {
+        [self.newsletter, self.campaign, self.template, self.admin]
+            .iter()
+            .all(|limits| limits.is_valid())
+    }
+}
+
+/// Largest messages a service reads and writes. Bigger messages fail with
+/// OUT_OF_RANGE. A message is buffered whole, so a limit bounds the
+/// memory of one call; the server may need a limit's worth per call in
+/// flight. Up to 64 MiB is comfortable on a default deployment, and
+/// [`MAX_MESSAGE_BYTES`] is the most accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MessageLimits {
+    /// Largest request; tonic's default is 4 MiB
+    pub max_receive_bytes: usize,
+    /// Largest reply, such as a `List` page of the longest page size
+    pub max_send_bytes: usize,
+}
+
+impl Default for MessageLimits {
+    fn default() -> Self {
+        Self {
+            max_receive_bytes: 4 * MIB,
+            max_send_bytes: 16 * MIB,
+        }
+    }
+}
+
+impl MessageLimits {
+    fn is_valid(&self) -> bool {
+        (1..=MAX_MESSAGE_BYTES).contains(&self.max_receive_bytes) && (1..=MAX_MESSAGE_BYTES).contains(&self.max_send_bytes)
+    }
+}
+
+/// TLS termination of the gRPC server, off unless a certificate is set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TlsSettings {
+    /// PEM certificate chain
+    pub cert_path: Option<String>,
+    /// PEM private key of the certificate
+    pub key_path: Option<String>,
+    /// PEM CA bundle; when set, clients must present a certificate it signed
+    pub client_ca_path: Option<String>,
+}
+
+impl TlsSettings {
+    /// Certificate and key paths, when TLS is on
+    pub fn identity(&self) -> Option<(&str, &str)> {
+        Some((Self::path(&self.cert_path)?, Self::path(&self.key_path)?))
+    }
+
+    pub fn client_ca(&self) -> Option<&str> {
+        Self::path(&self.client_ca_path)
+    }
+
+    fn path(path: &Option<String>) -> Option<&str> {
+        path.as_deref().filter(|path| !path.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSettings {
+    /// `postgres` or `sqlite`
+    pub backend: DatabaseBackend,
+    /// Required; the database file under `sqlite`
+    pub url: String,
+    /// Read replica for listings, lookups and stats; reads fall back to
+    /// `url` while it is down
+    pub replica_url: Option<String>,
+    /// `check`, `apply` or `skip`; see [`DatabaseSettings::migrations_mode`]
+    pub migrations_mode: Option<MigrationsMode>,
+    /// Older switch between `apply` (on) and `check` (off), used while
+    /// `migrations_mode` is unset
+    pub migrate_on_startup: bool,
+    /// Most connections the pool opens
+    pub max_size: u32,
+    /// Idle connections the pool keeps open; 0 opens them on demand
+    pub min_idle: u32,
+    /// How long a caller waits for a connection before giving up
+    pub acquire_timeout_ms: u64,
+    /// Age at which a connection is closed; 0 keeps it forever
+    pub max_lifetime_secs: u64,
+    /// Idle time after which a connection above `min_idle` is closed; 0 keeps it forever
+    pub idle_timeout_secs: u64,
+    /// How long Postgres runs one statement before cancelling it; 0 lets it run
+    pub statement_timeout_ms: u64,
+    /// Attempts of a read that hits a transient error; 1 disables retries
+    pub retry_max_attempts: u32,
+    /// First backoff, doubled on every retry
+    pub retry_base_delay_ms: u64,
+    /// Retries that can be spent back to back across calls
+    pub retry_budget: u32,
+    /// Recent calls the circuit breaker judges the database by; 0 disables it
+    pub breaker_window: u32,
+    /// Calls in the window before the breaker may open
+    pub breaker_min_calls: u32,
+    /// Share of the window, in percent, that must have failed to open the breaker
+    pub breaker_failure_percent: u32,
+    /// How long the open breaker refuses calls before trying one
+    pub breaker_open_ms: u64,
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        let retry = RetryPolicy::default();
+        let breaker = BreakerPolicy::default();
+        Self {
+            backend: DatabaseBackend::default(),
+            url: String::new(),
+            replica_url: None,
+            migrations_mode: None,
+            migrate_on_startup: true,
+            max_size: 16,
+            min_idle: 0,
+            acquire_timeout_ms: 5_000,
+            max_lifetime_secs: 30 * 60,
+            idle_timeout_secs: 10 * 60,
+            statement_timeout_ms: 30_000,
+            retry_max_attempts: retry.max_attempts,
+            retry_base_delay_ms: retry.base_delay.as_millis() as u64,
+            retry_budget: retry.budget,
+            breaker_window: breaker.window,
+            breaker_min_calls: breaker.min_calls,
+            breaker_failure_percent: breaker.failure_percent,
+            breaker_open_ms: breaker.open_for.as_millis() as u64,
+        }
+    }
+}
+
+impl DatabaseSettings {
+    /// What startup does about migrations: `migrations_mode` when set,
+    /// otherwise whatever `migrate_on_startup` asks for
+    pub fn migrations_mode(&self) -> MigrationsMode {
+        self.migrations_mode.unwrap_or(if self.migrate_on_startup {
+            MigrationsMode::Apply
+        } else {
+            MigrationsMode::Check
+        })
+    }
+
+    pub fn acquire_timeout(&self) -> Duration {
+        Duration::from_millis(self.acquire_timeout_ms)
+    }
+
+    pub fn max_lifetime(&self) -> Option<Duration> {
+        (self.max_lifetime_secs > 0).then(|| Duration::from_secs(self.max_lifetime_secs))
+    }
+
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        (self.idle_timeout_secs > 0).then(|| Duration::from_secs(self.idle_timeout_secs))
+    }
+
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        (self.statement_timeout_ms > 0).then(|| Duration::from_millis(self.statement_timeout_ms))
+    }
+
+    /// These settings with no statement timeout, for batch jobs whose
+    /// statements may take longer than any call should
+    pub fn without_statement_timeout(&self) -> Self {
+        Self {
+            statement_timeout_ms: 0,
+            ..self.clone()
+        }
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.retry_max_attempts,
+            base_delay: Duration::from_millis(self.retry_base_delay_ms),
+            budget: self.retry_budget,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// `None` when `breaker_window` is 0
+    pub fn breaker_policy(&self) -> Option<BreakerPolicy> {
+        (self.breaker_window > 0).then(|| BreakerPolicy {
+            window: self.breaker_window,
+            min_calls: self.breaker_min_calls,
+            failure_percent: self.breaker_failure_percent,
+            open_for: Duration::from_millis(self.breaker_open_ms),
+        })
+    }
+}
+
+/// Double opt-in
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfirmationSettings {
+    /// Key signing confirmation tokens; required
+    pub secret: String,
+    pub ttl_secs: i64,
+    /// Link target of the confirmation email
+    pub url: String,
+    /// Link target of the email confirming a new address
+    pub email_change_url: String,
+    /// Link target of the email confirming an opt-in to a topic that requires it
+    pub topic_url: String,
+    /// Make an address that unsubscribed confirm again when it subscribes
+    pub required_on_resubscribe: bool,
+    /// How long an unconfirmed subscription is kept once its link expired;
+    /// 0 purges it with the link
+    pub pending_retention_secs: i64,
+}
+
+impl Default for ConfirmationSettings {
+    fn default() -> Self {
+        Self {
+            secret: String::new(),
+            ttl_secs: 48 * 60 * 60,
+            url: "http://localhost:3000/newsletter/confirm".to_string(),
+            email_change_url: "http://localhost:3000/newsletter/confirm-email".to_string(),
+            topic_url: "http://localhost:3000/newsletter/confirm-topic".to_string(),
+            required_on_resubscribe: true,
+            pending_retention_secs: 0,
+        }
+    }
+}
+
+impl ConfirmationSettings {
+    pub fn ttl(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.ttl_secs)
+    }
+
+    pub fn pending_retention(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.pending_retention_secs)
+    }
+}
+
+/// What becomes of unsubscribed subscriptions after a while
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionSettings {
+    /// Days an unsubscribed subscription is kept before `action` applies;
+    /// 0 keeps them forever
+    pub unsubscribed_days: u32,
+    pub action: RetentionAction,
+    /// Tenants with a policy of their own; only settable in the file
+    pub tenants: Vec<TenantRetentionSettings>,
+}
+
+/// Retention of one tenant, replacing the default one
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TenantRetentionSettings {
+    pub tenant: String,
+    /// 0 keeps the tenant's unsubscribed subscriptions forever
+    pub unsubscribed_days: u32,
+    #[serde(default)]
+    pub action: RetentionAction,
+}
+
+impl RetentionSettings {
+    fn policy(unsubscribed_days: u32, action: RetentionAction) -> Option<RetentionPolicy> {
+        (unsubscribed_days > 0).then(|| RetentionPolicy {
+            unsubscribed_for: chrono::Duration::days(unsubscribed_days.into()),
+            action,
+        })
+    }
+
+    pub fn rules(&self) -> anyhow::Result<RetentionRules> {
+        let mut overrides = HashMap::new();
+        for tenant in &self.tenants {
+            let policy = Self::policy(tenant.unsubscribed_days, tenant.action);
+            if overrides.insert(TenantId::parse(&tenant.tenant)?, policy).is_some() {
+                anyhow::bail!("retention allows one policy per tenant, {} has more", tenant.tenant);
+            }
+        }
+        Ok(RetentionRules {
+            default: Self::policy(self.unsubscribed_days, self.action),
+            overrides,
+        })
+    }
+}
+
+/// Address folding on top of trimming and lowercasing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NormalizationSettings {
+    /// Treat Gmail dot and `+tag` variants as one mailbox
+    pub fold_gmail_aliases: bool,
+}
+
+impl NormalizationSettings {
+    pub fn rules(&self) -> Normalization {
+        Normalization {
+            fold_gmail_aliases: self.fold_gmail_aliases,
+        }
+    }
+}
+
+/// API key authentication
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthSettings {
+    /// Turning this off lets any caller use the API; for local development only
+    pub enabled: bool,
+    /// Stored as an admin key on startup
+    pub bootstrap_admin_key: Option<String>,
+    /// Stored as an operator key on startup, for the admin service
+    pub bootstrap_operator_key: Option<String>,
+}
+
+impl Default for AuthSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            bootstrap_admin_key: None,
+            bootstrap_operator_key: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobsSettings {
+    pub poll_interval_ms: u64,
+    /// Jobs claimed per poll
+    pub batch_size: i64,
+}
+
+impl Default for JobsSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 1000,
+            batch_size: crate::service::jobs::DEFAULT_BATCH_SIZE,
+        }
+    }
+}
+
+impl JobsSettings {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutboxSettings {
+    pub poll_interval_ms: u64,
+    /// Messages published per poll
+    pub batch_size: i64,
+    /// How long published messages are kept
+    pub retention_secs: i64,
+    /// Keep the `subscriber_views` read model up to date and serve stats
+    /// and listings from it; Postgres only
+    pub read_model: bool,
+}
+
+impl Default for OutboxSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 1000,
+            batch_size: crate::service::outbox::DEFAULT_BATCH_SIZE,
+            retention_secs: crate::service::outbox::DEFAULT_RETENTION.num_seconds(),
+            read_model: false,
+        }
+    }
+}
+
+impl OutboxSettings {
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+
+    pub fn retention(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.retention_secs)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IdempotencySettings {
+    /// How long a stored response is replayed for its key
+    pub ttl_secs: i64,
+}
+
+impl Default for IdempotencySettings {
+    fn default() -> Self {
+        Self {
+            ttl_secs: crate::service::idempotency::DEFAULT_TTL.num_seconds(),
+        }
+    }
+}
+
+impl IdempotencySettings {
+    pub fn ttl(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.ttl_secs)
+    }
+}
+
+/// Pacing of ImportSubscribers uploads
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImportSettings {
+    /// Import batches written at once, across every upload
+    pub max_batches_in_flight: usize,
+    /// Longest a batch waits for its turn and a free database connection
+    /// before the upload fails with RESOURCE_EXHAUSTED
+    pub max_wait_ms: u64,
+}
+
+impl Default for ImportSettings {
+    fn default() -> Self {
+        Self {
+            max_batches_in_flight: 4,
+            max_wait_ms: 5_000,
+        }
+    }
+}
+
+impl ImportSettings {
+    pub fn max_wait(&self) -> Duration {
+        Duration::from_millis(self.max_wait_ms)
+    }
+}
+
+/// Checks refusing fake addresses at subscribe
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VerificationSettings {
+    /// Refuse domains that do not exist or publish no mail servers
+    pub mx_lookup: bool,
+    /// File or `http(s)://` URL listing disposable domains, one per line
+    pub disposable_domains: Option<String>,
+}
+
+impl VerificationSettings {
+    pub fn disposable_domains(&self) -> Option<&str> {
+        self.disposable_domains.as_deref().filter(|location| !location.is_empty())
+    }
+}
+
+/// Cache of subscription status and stats reads
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheSettings {
+    /// Redis holding the entries; unset disables caching
+    pub redis_url: Option<String>,
+    /// How long an entry is served before it is read again
+    pub ttl_secs: u64,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            redis_url: None,
+            ttl_secs: crate::infrastructure::cache::DEFAULT_TTL.as_secs(),
+        }
+    }
+}
+
+impl CacheSettings {
+    /// Redis URL, when caching is on
+    pub fn enabled(&self) -> Option<&str> {
+        self.redis_url.as_deref().filter(|url| !url.is_empty())
+    }
+
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_secs)
+    }
+}
+
+/// Pace and language of campaign sends
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CampaignSettings {
+    /// Recipients per batch
+    pub batch_size: i64,
+    pub batches_per_minute: u32,
+    /// Template translations tried, in order, for recipients whose own
+    /// locale has none; `[de, en]` in the environment
+    pub locale_fallbacks: Vec<String>,
+    /// Internal addresses `SendTestEmail` may send proofs to, or `@domain`
+    /// for a whole domain; test sends are refused while empty
+    pub test_recipients: Vec<String>,
+    /// Campaign sends allowed per domain on each day of its warm-up, from
+    /// the first day it sends; `[50, 100, 500]` in the environment. Empty
+    /// turns warm-up off
+    pub warmup: Vec<i64>,
+    /// Domains ramped besides tenants' verified sending domains, such as a
+    /// fresh `EMAIL_FROM` domain; `[mail.example.com]` in the environment
+    pub warmup_domains: Vec<String>,
+    /// Domains that never ramp, as they already send in bulk
+    pub warmup_skip: Vec<String>,
+    /// Day a domain's ramp counts from, for one that started elsewhere; only
+    /// read from the config file
+    pub warmup_started_on: HashMap<String, NaiveDate>,
+}
+
+impl Default for CampaignSettings {
+    fn default() -> Self {
+        let throttle = SendThrottle::default();
+        Self {
+            batch_size: throttle.batch_size,
+            batches_per_minute: throttle.batches_per_minute,
+            locale_fallbacks: Vec::new(),
+            test_recipients: Vec::new(),
+            warmup: Vec::new(),
+            warmup_domains: Vec::new(),
+            warmup_skip: Vec::new(),
+            warmup_started_on: HashMap::new(),
+        }
+    }
+}
+
+impl CampaignSettings {
+    pub fn throttle(&self) -> SendThrottle {
+        SendThrottle {
+            batch_size: self.batch_size,
+            batches_per_minute: self.batches_per_minute,
+        }
+    }
+
+    /// The warm-up ramp, when one is configured
+    pub fn warmup(&self) -> Option<WarmupSchedule> {
+        let schedule = WarmupSchedule::new(self.warmup.clone());
+        (!schedule.is_empty()).then_some(schedule)
+    }
+
+    pub fn warmup_domains(&self) -> WarmupDomains {
+        WarmupDomains {
+            listed: self.warmup_domains.clone(),
+            skipped: self.warmup_skip.clone(),
+            started_on: self.warmup_started_on.clone(),
+        }
+    }
+}
+
+/// Several email providers, failed over between; with none listed the
+/// provider of `EMAIL_PROVIDER` sends everything
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmailSettings {
+    /// `priority`, `round_robin` or `weighted`
+    pub strategy: SelectionStrategy,
+    /// Failed sends in a row that take a provider out of rotation
+    pub failover_after: u32,
+    /// How long a provider stays out of rotation
+    pub cooldown_secs: u64,
+    /// In priority order
+    pub providers: Vec<ProviderSettings>,
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        let policy = FailoverPolicy::default();
+        Self {
+            strategy: policy.strategy,
+            failover_after: policy.failover_after,
+            cooldown_secs: policy.cooldown.as_secs(),
+            providers: Vec::new(),
+        }
+    }
+}
+
+impl EmailSettings {
+    pub fn failover_policy(&self) -> FailoverPolicy {
+        FailoverPolicy {
+            strategy: self.strategy,
+            failover_after: self.failover_after,
+            cooldown: Duration::from_secs(self.cooldown_secs),
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        let mut names: Vec<&str> = self.providers.iter().map(|p| p.name.as_str()).collect();
+        names.sort_unstable();
+        self.failover_after > 0
+            && self.cooldown_secs > 0
+            && self.providers.iter().all(ProviderSettings::is_valid)
+            && names.windows(2).all(|pair| pair[0] != pair[1])
+            && (self.strategy != SelectionStrategy::Weighted
+                || self.providers.is_empty()
+                || self.providers.iter().any(|p| p.weight > 0))
+    }
+}
+
+/// One email provider and the limits its account is held to
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderSettings {
+    /// Unique name used in logs
+    pub name: String,
+    pub kind: ProviderKind,
+    /// Share of messages under the `weighted` strategy; 0 only takes over
+    #[serde(default = "ProviderSettings::default_weight")]
+    pub weight: u32,
+    /// Messages per second the provider accepts
+    #[serde(default)]
+    pub max_per_second: Option<f64>,
+    /// Messages per UTC day the provider accepts, counted per instance
+    #[serde(default)]
+    pub daily_quota: Option<u64>,
+    /// SMTP relay of `smtp` providers
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// `starttls` (default), `tls` or `none`
+    #[serde(default = "ProviderSettings::default_tls")]
+    pub tls: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Configuration set of `ses` providers
+    #[serde(default)]
+    pub configuration_set: Option<String>,
+}
+
+impl ProviderSettings {
+    fn default_weight() -> u32 {
+        1
+    }
+
+    fn default_tls() -> String {
+        "starttls".to_string()
+    }
+
+    pub fn smtp(&self) -> anyhow::Result<SmtpConfig> {
+        let host = self
+            .host
+            .clone()
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("email provider {} needs a host", self.name))?;
+        Ok(SmtpConfig {
+            host,
+            port: self.port,
+            tls: self.tls.clone(),
+            username: self.username.clone(),
+            password: self.password.clone(),
+        })
+    }
+
+    /// Token bucket of `max_per_second`, a second's worth deep
+    pub fn rate(&self) -> Option<Quota> {
+        self.max_per_second.map(|per_second| Quota {
+            per_second,
+            burst: per_second.ceil() as u32,
+        })
+    }
+
+    fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && self.max_per_second.is_none_or(|rps| rps > 0.0 && rps.is_finite())
+            && self.daily_quota != Some(0)
+            && (self.kind != ProviderKind::Smtp
+                || (self.smtp().is_ok() && matches!(self.tls.as_str(), "starttls" | "tls" | "none")))
+    }
+}
+
+/// Digest of one topic, assembled from a feed or JSON API on a cron schedule
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DigestSettings {
+    /// Topic key; the digest goes to the subscribers receiving it
+    pub topic: String,
+    #[serde(default = "DigestSettings::default_tenant")]
+    pub tenant: String,
+    /// Cron expression in UTC with a leading seconds field, such as
+    /// `0 0 8 * * Mon` for Mondays at 08:00
+    pub schedule: String,
+    pub source_url: String,
+    #[serde(default = "DigestSettings::default_source_format")]
+    pub source_format: SourceFormat,
+    /// Template rendered with `topic` and `items` besides the usual variables
+    pub template_id: i64,
+    pub subject: String,
+    #[serde(default = "DigestSettings::default_max_items")]
+    pub max_items: usize,
+    /// How long the campaign may wait for its send window to be used
+    #[serde(default = "DigestSettings::default_send_window_secs")]
+    pub send_window_secs: u64,
+}
+
+impl DigestSettings {
+    fn default_tenant() -> String {
+        TenantId::default().to_string()
+    }
+
+    fn default_source_format() -> SourceFormat {
+        SourceFormat::Feed
+    }
+
+    fn default_max_items() -> usize {
+        10
+    }
+
+    fn default_send_window_secs() -> u64 {
+        6 * 3600
+    }
+
+    pub fn digest(&self) -> anyhow::Result<Digest> {
+        if self.topic.trim().is_empty() || self.source_url.trim().is_empty() || self.subject.trim().is_empty() {
+            anyhow::bail!("digest topic, source_url and subject cannot be empty");
+        }
+        if self.max_items == 0 || self.send_window_secs == 0 {
+            anyhow::bail!("digest max_items and send_window_secs must be positive");
+        }
+
+        Ok(Digest {
+            topic: self.topic.clone(),
+            tenant: TenantId::parse(&self.tenant)?,
+            schedule: Digest::parse_schedule(&self.schedule)?,
+            source_url: self.source_url.clone(),
+            source_format: self.source_format,
+            template_id: self.template_id,
+            subject: self.subject.clone(),
+            max_items: self.max_items,
+            send_window: chrono::Duration::seconds(self.send_window_secs.min(i64::MAX as u64) as i64),
+        })
+    }
+}
+
+/// Re-engagement flow of one tenant, run on a cron schedule
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReengagementSettings {
+    #[serde(default = "ReengagementSettings::default_tenant")]
+    pub tenant: String,
+    /// Cron expression in UTC with a leading seconds field; daily at 03:00
+    /// by default
+    #[serde(default = "ReengagementSettings::default_schedule")]
+    pub schedule: String,
+    /// Days without an open or click before a mailed subscriber joins the segment
+    #[serde(default = "ReengagementSettings::default_inactive_days")]
+    pub inactive_days: u32,
+    /// Days a segment member has to engage before being unsubscribed
+    #[serde(default = "ReengagementSettings::default_grace_days")]
+    pub grace_days: u32,
+    /// Tag of the segment; each run's cohort is tagged `<segment>-<yyyymmddhhmm>`
+    #[serde(default = "ReengagementSettings::default_segment")]
+    pub segment: String,
+    /// Win-back template, rendered with `unsubscribe_on` besides the usual variables
+    pub template_id: i64,
+    pub subject: String,
+    /// How long the win-back campaign may wait for its send window to be used
+    #[serde(default = "ReengagementSettings::default_send_window_secs")]
+    pub send_window_secs: u64,
+}
+
+impl ReengagementSettings {
+    fn default_tenant() -> String {
+        TenantId::default().to_string()
+    }
+
+    fn default_schedule() -> String {
+        "0 0 3 * * *".to_string()
+    }
+
+    fn default_inactive_days() -> u32 {
+        90
+    }
+
+    fn default_grace_days() -> u32 {
+        30
+    }
+
+    fn default_segment() -> String {
+        "re-engagement".to_string()
+    }
+
+    fn default_send_window_secs() -> u64 {
+        6 * 3600
+    }
+
+    pub fn reengagement(&self) -> anyhow::Result<Reengagement> {
+        if self.subject.trim().is_empty() {
+            anyhow::bail!("re-engagement subject cannot be empty");
+        }
+        if self.inactive_days == 0 || self.grace_days == 0 || self.send_window_secs == 0 {
+            anyhow::bail!("re-engagement inactive_days, grace_days and send_window_secs must be positive");
+        }
+
+        let flow = Reengagement {
+            tenant: TenantId::parse(&self.tenant)?,
+            schedule: Reengagement::parse_schedule(&self.schedule)?,
+            inactive_after: chrono::Duration::days(self.inactive_days.into()),
+            grace: chrono::Duration::days(self.grace_days.into()),
+            segment: Tag::parse(&self.segment)?,
+            template_id: self.template_id,
+            subject: self.subject.clone(),
+            send_window: chrono::Duration::seconds(self.send_window_secs.min(i64::MAX as u64) as i64),
+        };
+        // Cohort tags must fit the tag rules too
+        flow.cohort(chrono::Utc::now())?;
+        Ok(flow)
+    }
+}
+
+/// Open and click tracking
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrackingSettings {
+    /// Public base of the tracking routes; unset disables tracking
+    pub url: Option<String>,
+    /// Key signing tracking links; required with `url`
+    pub secret: Option<String>,
+    pub port: u16,
+}
+
+impl Default for TrackingSettings {
+    fn default() -> Self {
+        Self {
+            url: None,
+            secret: None,
+            port: 8080,
+        }
+    }
+}
+
+impl TrackingSettings {
+    /// Base URL and signing key, when tracking is on
+    pub fn enabled(&self) -> Option<(&str, &str)> {
+        let url = self.url.as_deref().filter(|url| !url.is_empty())?;
+        Some((url, self.secret.as_deref().unwrap_or_default()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShutdownSettings {
+    /// How long in-flight work gets to finish before it is aborted
+    pub drain_timeout_secs: u64,
+}
+
+impl Default for ShutdownSettings {
+    fn default() -> Self {
+        Self {
+            drain_timeout_secs: crate::infrastructure::shutdown::DEFAULT_DRAIN_TIMEOUT.as_secs(),
+        }
+    }
+}
+
+impl ShutdownSettings {
+    pub fn drain_timeout(&self) -> Duration {
+        Duration::from_secs(self.drain_timeout_secs)
+    }
+}
+
+/// Token buckets per client; a limit left unset does not apply
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitSettings {
+    /// Sustained requests per second of a client IP, with or without an
+    /// API key; counted before the key is checked
+    pub ip_rps: Option<f64>,
+    /// Requests an IP may make at once; one second's worth when unset
+    pub ip_burst: Option<u32>,
+    /// Sustained requests per second of an API key
+    pub key_rps: Option<f64>,
+    /// Requests a key may make at once; one second's worth when unset
+    pub key_burst: Option<u32>,
+    /// Take the client IP from the first `x-forwarded-for` entry; only safe
+    /// behind a proxy that overwrites the header
+    pub trust_forwarded: bool,
+}
+
+impl RateLimitSettings {
+    /// The limits to enforce; `None` when neither is set
+    pub fn config(&self) -> Option<RateLimitConfig> {
+        let quota = |rps: Option<f64>, burst: Option<u32>| {
+            rps.map(|per_second| Quota {
+                per_second,
+                burst: burst.unwrap_or(per_second.ceil() as u32),
+            })
+        };
+        let per_ip = quota(self.ip_rps, self.ip_burst);
+        let per_key = quota(self.key_rps, self.key_burst);
+        if per_ip.is_none() && per_key.is_none() {
+            return None;
+        }
+
+        Some(RateLimitConfig {
+            per_ip,
+            per_key,
+            trust_forwarded: self.trust_forwarded,
+        })
+    }
+
+    fn is_valid(&self) -> bool {
+        let rate_ok = |rps: Option<f64>| rps.is_none_or(|rps| rps > 0.0 && rps.is_finite());
+        rate_ok(self.ip_rps)
+            && rate_ok(self.key_rps)
+            && self.ip_burst != Some(0)
+            && self.key_burst != Some(0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingSettings {
+    /// Log subscriber addresses as they are; off, they appear as digests
+    pub pii: bool,
+    /// `tracing` directives such as `info,newsletter=debug`; `RUST_LOG`, or
+    /// `info`, when unset
+    pub filter: Option<String>,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self { pii: true, filter: None }
+    }
+}
+
+impl LoggingSettings {
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_deref().filter(|filter| !filter.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsSettings {
+    /// `log`, `prometheus`, `statsd` or `dogstatsd`
+    pub backend: MetricsBackend,
+    /// Port serving `/metrics` with the `prometheus` backend
+    pub port: u16,
+    /// Agent the `statsd` and `dogstatsd` backends send to, as `host:port`
+    pub statsd_addr: String,
+    /// Prepended to every metric name
+    pub prefix: String,
+}
+
+impl Default for MetricsSettings {
+    fn default() -> Self {
+        Self {
+            backend: MetricsBackend::Log,
+            port: 9090,
+            statsd_addr: "127.0.0.1:8125".to_string(),
+            prefix: "newsletter".to_string(),
+        }
+    }
+}
+
+/// Pseudonymous addresses outside the newsletters table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrivacySettings {
+    /// Key of the HMAC replacing addresses in logs, published events and
+    /// consent and unsubscribe records; unset keeps the addresses. Changing
+    /// it breaks the link to records written under the old key.
+    pub pseudonym_key: Option<String>,
+}
+
+impl PrivacySettings {
+    pub fn pseudonymizer(&self) -> Option<Pseudonymizer> {
+        self.pseudonym_key
+            .as_deref()
+            .filter(|key| !key.is_empty())
+            .map(Pseudonymizer::new)
+    }
+}
+
+impl Settings {
+    /// Load the file named by `CONFIG_FILE` (default `config.yaml`), apply
+    /// environment overrides and validate the result
+    pub fn load() -> anyhow::Result<Self> {
+        let file = env::var("CONFIG_FILE").unwrap_or_else(|_| DEFAULT_CONFIG_FILE.to_string());
+
+        let env = Env::raw()
+            .only(&ENV_KEYS.iter().map(|(var, _)| *var).collect::<Vec<_>>())
+            .map(|var| {
+                ENV_KEYS
+                    .iter()
+                    .find(|(name, _)| var == *name)
+                    .map_or(var.into(), |(_, key)| (*key).into())
+            });
+
+        let settings: Self = Figment::new()
+            .merge(Yaml::file(file))
+            .merge(env)
+            .extract()
+            .map_err(|e| anyhow::anyhow!("invalid settings: {e}"))?;
+
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Reject settings the service cannot start with
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut problems = Vec::new();
+
+        if !self.messages.is_valid() {
+            problems.push("messages limits (NEWSLETTER_MAX_RECEIVE_BYTES and the like) must be between 1 byte and 256 MiB");
+        }
+        let has_cert = TlsSettings::path(&self.tls.cert_path).is_some();
+        let has_key = TlsSettings::path(&self.tls.key_path).is_some();
+        if has_cert != has_key {
+            problems.push("tls.cert_path (TLS_CERT_PATH) and tls.key_path (TLS_KEY_PATH) must be set together");
+        }
+        if self.tls.client_ca().is_some() && !has_cert {
+            problems.push("tls.client_ca_path (TLS_CLIENT_CA_PATH) needs tls.cert_path and tls.key_path");
+        }
+        if self.database.url.is_empty() {
+            problems.push("database.url (DATABASE_URL) is required");
+        }
+        if self.database.backend == DatabaseBackend::Sqlite && self.auth.enabled {
+            // API keys live in Postgres only
+            problems.push("database.backend (DATABASE_BACKEND) sqlite needs auth.enabled (AUTH_ENABLED) off");
+        }
+        if self.database.max_size == 0 {
+            problems.push("database.max_size must be positive");
+        }
+        if self.database.min_idle > self.database.max_size {
+            problems.push("database.min_idle must not exceed database.max_size");
+        }
+        if self.database.acquire_timeout_ms == 0 {
+            problems.push("database.acquire_timeout_ms must be positive");
+        }
+        if self.database.retry_max_attempts == 0 {
+            problems.push("database.retry_max_attempts must be positive");
+        }
+        if self.database.breaker_window > 0 {
+            if !(1..=100).contains(&self.database.breaker_failure_percent) {
+                problems.push("database.breaker_failure_percent must be between 1 and 100");
+            }
+            if self.database.breaker_min_calls > self.database.breaker_window {
+                problems.push("database.breaker_min_calls must not exceed database.breaker_window");
+            }
+            if self.database.breaker_open_ms == 0 {
+                problems.push("database.breaker_open_ms must be positive");
+            }
+        }
+        if self.confirmation.secret.is_empty() {
+            problems.push("confirmation.secret (CONFIRMATION_SECRET) is required");
+        }
+        if self.confirmation.pending_retention_secs < 0 {
+            problems.push("confirmation.pending_retention_secs must not be negative");
+        }
+        if self.confirmation.ttl_secs <= 0 {
+            problems.push("confirmation.ttl_secs must be positive");
+        }
+        if self.retention.rules().is_err() {
+            problems.push("retention.tenants need valid tenant ids, each listed once");
+        }
+        if self.jobs.batch_size < 1 || self.outbox.batch_size < 1 {
+            problems.push("jobs.batch_size and outbox.batch_size must be positive");
+        }
+        if self.jobs.poll_interval_ms == 0 || self.outbox.poll_interval_ms == 0 {
+            problems.push("jobs.poll_interval_ms and outbox.poll_interval_ms must be positive");
+        }
+        if self.import.max_batches_in_flight == 0 {
+            problems.push("import.max_batches_in_flight must be positive");
+        }
+        if self.cache.ttl_secs == 0 {
+            problems.push("cache.ttl_secs must be positive");
+        }
+        if self.campaign.batch_size < 1 || self.campaign.batches_per_minute < 1 {
+            problems.push("campaign.batch_size and campaign.batches_per_minute must be positive");
+        }
+        if !self.campaign.locale_fallbacks.iter().all(|tag| locale::is_valid(tag)) {
+            problems.push("campaign.locale_fallbacks (CAMPAIGN_LOCALE_FALLBACKS) must be BCP 47 tags such as en-US");
+        }
+        if !self.campaign.test_recipients.iter().all(|entry| entry.contains('@')) {
+            problems.push("campaign.test_recipients (CAMPAIGN_TEST_RECIPIENTS) must be addresses or @domain entries");
+        }
+        if !WarmupSchedule::new(self.campaign.warmup.clone()).is_valid() {
+            problems.push("campaign.warmup (CAMPAIGN_WARMUP) must be positive daily caps that never decrease");
+        }
+        let mut warmup_domains = self
+            .campaign
+            .warmup_domains
+            .iter()
+            .chain(&self.campaign.warmup_skip)
+            .chain(self.campaign.warmup_started_on.keys());
+        if !warmup_domains.all(|domain| validate_domain(domain).is_ok() && *domain == domain.to_ascii_lowercase()) {
+            problems.push("campaign.warmup_domains (CAMPAIGN_WARMUP_DOMAINS), campaign.warmup_skip (CAMPAIGN_WARMUP_SKIP) and campaign.warmup_started_on must name lowercase domains");
+        }
+        if !self.email.is_valid() {
+            problems.push("email providers need a unique name, an smtp host and tls mode of starttls, tls or none, positive limits and, with the weighted strategy, a weight; failover_after and cooldown_secs must be positive");
+        }
+        if self.digests.iter().any(|digest| digest.digest().is_err()) {
+            problems.push("digests need a topic, tenant id, cron schedule with seconds, source_url, subject and positive limits");
+        }
+        if self.reengagement.iter().any(|flow| flow.reengagement().is_err()) {
+            problems.push("reengagement flows need a tenant id, cron schedule with seconds, valid segment tag, subject and positive limits");
+        }
+        let mut tenants: Vec<&str> = self.reengagement.iter().map(|flow| flow.tenant.as_str()).collect();
+        tenants.sort_unstable();
+        if tenants.windows(2).any(|pair| pair[0] == pair[1]) {
+            problems.push("reengagement allows one flow per tenant");
+        }
+        if !self.reengagement.is_empty() && self.tracking.enabled().is_none() {
+            // Without tracked opens and clicks everyone mailed looks inactive
+            problems.push("reengagement needs tracking.url (TRACKING_URL)");
+        }
+        if self.tracking.enabled().is_some_and(|(_, secret)| secret.is_empty()) {
+            problems.push("tracking.secret (TRACKING_SECRET) is required when tracking.url is set");
+        }
+        if !self.rate_limit.is_valid() {
+            problems.push("rate_limit rates (RATE_LIMIT_IP_RPS, RATE_LIMIT_KEY_RPS) and bursts must be positive");
+        }
+        if self.logging.filter().is_some_and(|filter| logging::parse_filter(filter).is_err()) {
+            problems.push("logging.filter (LOG_FILTER) must be tracing directives such as info,newsletter=debug");
+        }
+        if !metrics::is_valid_prefix(&self.metrics.prefix) {
+            problems.push("metrics.prefix (METRICS_PREFIX) must be dotted names of letters, digits and underscores");
+        }
+        if matches!(self.metrics.backend, MetricsBackend::Statsd | MetricsBackend::Dogstatsd)
+            && self.metrics.statsd_addr.rsplit_once(':').is_none_or(|(_, port)| port.parse::<u16>().is_err())
+        {
+            problems.push("metrics.statsd_addr (METRICS_STATSD_ADDR) must be host:port");
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("invalid settings: {}", problems.join("; "))
+        }
+    }
+}
@@ -1,8 +1,386 @@
+diesel::table! {
+    api_keys (id) {
+        id -> BigInt,
+        name -> Text,
+        key_hash -> Text,
+        scope -> Text,
+        created_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+        tenant_id -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     newsletters (id) {
         id -> BigInt,
         email -> Text,
         active -> Bool,
         created_at -> Timestamptz,
+        attributes -> Jsonb,
+        tenant_id -> Text,
+        status -> Text,
+        status_changed_at -> Timestamptz,
+        locale -> Nullable<Text>,
+        timezone -> Nullable<Text>,
+        resubscribed_at -> Nullable<Timestamptz>,
+        anonymized_at -> Nullable<Timestamptz>,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    attribute_definitions (key) {
+        key -> Text,
+        kind -> Text,
+        description -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    confirmation_tokens (token_id) {
+        token_id -> Uuid,
+        email -> Text,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+        tenant_id -> Text,
+    }
+}
+
+diesel::table! {
+    email_changes (token_id) {
+        token_id -> Uuid,
+        tenant_id -> Text,
+        email -> Text,
+        new_email -> Text,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    subscriber_tags (email, tag) {
+        email -> Text,
+        tag -> Text,
+        created_at -> Timestamptz,
+        tenant_id -> Text,
+    }
+}
+
+diesel::table! {
+    topics (key) {
+        key -> Text,
+        name -> Text,
+        description -> Text,
+        default_subscribed -> Bool,
+        created_at -> Timestamptz,
+        requires_confirmation -> Bool,
+    }
+}
+
+diesel::table! {
+    topic_confirmations (token_id) {
+        token_id -> Uuid,
+        tenant_id -> Text,
+        email -> Text,
+        topic -> Text,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    subscriber_topics (email, topic) {
+        email -> Text,
+        topic -> Text,
+        subscribed -> Bool,
+        updated_at -> Timestamptz,
+        tenant_id -> Text,
+    }
+}
+
+diesel::table! {
+    jobs (id) {
+        id -> BigInt,
+        kind -> Text,
+        payload -> Jsonb,
+        status -> Text,
+        unique_key -> Nullable<Text>,
+        attempts -> Integer,
+        max_attempts -> Integer,
+        last_error -> Nullable<Text>,
+        run_at -> Timestamptz,
+        created_at -> Timestamptz,
+        finished_at -> Nullable<Timestamptz>,
+        tenant_id -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    outbox (id) {
+        id -> BigInt,
+        event_type -> Text,
+        payload -> Jsonb,
+        attempts -> Integer,
+        last_error -> Nullable<Text>,
+        available_at -> Timestamptz,
+        created_at -> Timestamptz,
+        sent_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    webhook_dead_letters (id) {
+        id -> BigInt,
+        url -> Text,
+        event_type -> Text,
+        payload -> Jsonb,
+        attempts -> Integer,
+        last_error -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    idempotency_keys (key, operation) {
+        key -> Text,
+        operation -> Text,
+        request_hash -> Text,
+        response -> Nullable<Jsonb>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    unsubscribe_events (id) {
+        id -> BigInt,
+        email -> Text,
+        reason -> Nullable<Text>,
+        comment -> Nullable<Text>,
+        created_at -> Timestamptz,
+        tenant_id -> Text,
+    }
+}
+
+diesel::table! {
+    subscriber_merges (id) {
+        id -> BigInt,
+        tenant_id -> Text,
+        email -> Text,
+        merged_email -> Text,
+        merged_status -> Text,
+        status -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    subscriber_views (id) {
+        id -> BigInt,
+        tenant_id -> Text,
+        email -> Text,
+        status -> Text,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    subscriber_events (id) {
+        id -> BigInt,
+        tenant_id -> Text,
+        email -> Text,
+        previous_email -> Nullable<Text>,
+        event_type -> Text,
+        status -> Nullable<Text>,
+        payload -> Jsonb,
+        occurred_at -> Timestamptz,
+        recorded_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    analytics_exports (dataset, day) {
+        dataset -> Text,
+        day -> Date,
+        rows -> BigInt,
+        exported_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    list_metrics_daily (tenant_id, day) {
+        tenant_id -> Text,
+        day -> Date,
+        subscribers -> BigInt,
+        confirms -> BigInt,
+        unsubscribes -> BigInt,
+        net_growth -> BigInt,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    consents (id) {
+        id -> BigInt,
+        email -> Text,
+        action -> Text,
+        policy_version -> Nullable<Text>,
+        source -> Nullable<Text>,
+        ip_address -> Nullable<Text>,
+        created_at -> Timestamptz,
+        tenant_id -> Text,
+    }
+}
+
+diesel::table! {
+    campaign_deliveries (campaign_id, email) {
+        campaign_id -> BigInt,
+        email -> Text,
+        status -> Text,
+        attempts -> Integer,
+        last_error -> Nullable<Text>,
+        claimed_at -> Nullable<Timestamptz>,
+        sent_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        tenant_id -> Text,
+        not_before -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    engagement_events (id) {
+        id -> BigInt,
+        campaign_id -> BigInt,
+        email -> Text,
+        kind -> Text,
+        url -> Nullable<Text>,
+        created_at -> Timestamptz,
+        tenant_id -> Text,
+    }
+}
+
+diesel::table! {
+    campaign_complaints (id) {
+        id -> BigInt,
+        tenant_id -> Text,
+        campaign_id -> BigInt,
+        email -> Text,
+        feedback_type -> Text,
+        user_agent -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    campaigns (id) {
+        id -> BigInt,
+        name -> Text,
+        subject -> Text,
+        template_id -> BigInt,
+        status -> Text,
+        send_window_start -> Nullable<Timestamptz>,
+        send_window_end -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        tenant_id -> Text,
+        topic -> Nullable<Text>,
+        variables -> Jsonb,
+        tag -> Nullable<Text>,
+        version -> BigInt,
+        local_send_start_hour -> Nullable<Integer>,
+        local_send_end_hour -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    templates (id) {
+        id -> BigInt,
+        name -> Text,
+        format -> Text,
+        body -> Text,
+        required_variables -> Array<Text>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        tenant_id -> Text,
+        version -> BigInt,
+        text_body -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    template_assets (id) {
+        id -> BigInt,
+        tenant_id -> Text,
+        name -> Text,
+        content_type -> Text,
+        size_bytes -> BigInt,
+        sha256 -> Text,
+        storage_key -> Text,
+        created_at -> Timestamptz,
+        last_referenced_at -> Timestamptz,
+        sent_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    template_translations (template_id, locale) {
+        template_id -> BigInt,
+        locale -> Text,
+        body -> Text,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        tenant_id -> Text,
+    }
+}
+
+diesel::table! {
+    sending_domains (id) {
+        id -> BigInt,
+        tenant_id -> Text,
+        domain -> Text,
+        from_address -> Text,
+        created_at -> Timestamptz,
+        verified_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    dkim_keys (id) {
+        id -> BigInt,
+        tenant_id -> Text,
+        domain_id -> BigInt,
+        selector -> Text,
+        private_key -> Text,
+        public_key -> Text,
+        status -> Text,
+        created_at -> Timestamptz,
+        activated_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    warmup_domains (domain) {
+        domain -> Text,
+        started_on -> Date,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    warmup_volumes (domain, day) {
+        domain -> Text,
+        day -> Date,
+        sent -> BigInt,
     }
 }
+
+diesel::allow_tables_to_appear_in_same_query!(newsletters, confirmation_tokens, subscriber_tags);
+diesel::allow_tables_to_appear_in_same_query!(newsletters, email_changes);
+diesel::allow_tables_to_appear_in_same_query!(topics, subscriber_topics);
+diesel::allow_tables_to_appear_in_same_query!(topics, topic_confirmations);
+diesel::allow_tables_to_appear_in_same_query!(newsletters, topic_confirmations);
+diesel::allow_tables_to_appear_in_same_query!(campaign_deliveries, newsletters);
+diesel::allow_tables_to_appear_in_same_query!(newsletters, subscriber_topics);
+diesel::allow_tables_to_appear_in_same_query!(newsletters, topics);
+diesel::allow_tables_to_appear_in_same_query!(sending_domains, dkim_keys);
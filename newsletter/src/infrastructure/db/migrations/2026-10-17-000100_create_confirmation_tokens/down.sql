@@ -0,0 +1 @@
+DROP TABLE IF EXISTS confirmation_tokens;
\ No newline at end of file
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS confirmation_tokens (
+    token_id   UUID        PRIMARY KEY,
+    email      TEXT        NOT NULL REFERENCES newsletters (email) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS confirmation_tokens_expires_at_idx ON confirmation_tokens (expires_at);
+CREATE INDEX IF NOT EXISTS confirmation_tokens_email_idx ON confirmation_tokens (email);
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS campaigns (
+    id                BIGSERIAL   PRIMARY KEY,
+    name              TEXT        NOT NULL,
+    subject           TEXT        NOT NULL,
+    template_id       BIGINT      NOT NULL,
+    status            TEXT        NOT NULL DEFAULT 'draft'
+        CHECK (status IN ('draft', 'scheduled', 'sending', 'sent', 'cancelled')),
+    send_window_start TIMESTAMPTZ,
+    send_window_end   TIMESTAMPTZ,
+    created_at        TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at        TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK (send_window_start IS NULL OR send_window_end > send_window_start)
+);
+
+CREATE INDEX IF NOT EXISTS campaigns_status_idx ON campaigns (status);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS templates (
+    id                 BIGSERIAL   PRIMARY KEY,
+    name               TEXT        NOT NULL UNIQUE,
+    format             TEXT        NOT NULL CHECK (format IN ('handlebars', 'mjml')),
+    body               TEXT        NOT NULL,
+    required_variables TEXT[]      NOT NULL DEFAULT '{}',
+    created_at         TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at         TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS subscriber_tags (
+    email      TEXT        NOT NULL REFERENCES newsletters (email) ON DELETE CASCADE,
+    tag        TEXT        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (email, tag)
+);
+
+CREATE INDEX IF NOT EXISTS subscriber_tags_tag_idx ON subscriber_tags (tag);
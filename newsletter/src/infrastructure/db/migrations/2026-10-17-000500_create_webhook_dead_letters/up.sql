@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS webhook_dead_letters (
+    id         BIGSERIAL   PRIMARY KEY,
+    url        TEXT        NOT NULL,
+    event_type TEXT        NOT NULL,
+    payload    JSONB       NOT NULL,
+    attempts   INTEGER     NOT NULL,
+    last_error TEXT        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS webhook_dead_letters_created_at_idx ON webhook_dead_letters (created_at);
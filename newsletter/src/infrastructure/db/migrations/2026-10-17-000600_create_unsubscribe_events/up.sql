@@ -0,0 +1,11 @@
+-- No foreign key: the subscription row is gone by the time the event is read.
+CREATE TABLE IF NOT EXISTS unsubscribe_events (
+    id         BIGSERIAL   PRIMARY KEY,
+    email      TEXT        NOT NULL,
+    reason     TEXT        NULL CHECK (reason IN ('too_frequent', 'not_relevant', 'never_subscribed', 'other')),
+    comment    TEXT        NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS unsubscribe_events_created_at_idx ON unsubscribe_events (created_at);
+CREATE INDEX IF NOT EXISTS unsubscribe_events_reason_idx ON unsubscribe_events (reason);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    key          TEXT        NOT NULL,
+    operation    TEXT        NOT NULL,
+    request_hash TEXT        NOT NULL,
+    -- NULL while the first request is still being processed
+    response     JSONB       NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (key, operation)
+);
+
+CREATE INDEX IF NOT EXISTS idempotency_keys_created_at_idx ON idempotency_keys (created_at);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS api_keys (
+    id         BIGSERIAL   PRIMARY KEY,
+    name       TEXT        NOT NULL,
+    -- Hex SHA-256 of the key; the key itself is never stored
+    key_hash   TEXT        NOT NULL UNIQUE,
+    scope      TEXT        NOT NULL CHECK (scope IN ('read', 'admin')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    revoked_at TIMESTAMPTZ NULL
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS subscriber_topics;
+DROP TABLE IF EXISTS topics;
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS topics (
+    key                TEXT        PRIMARY KEY,
+    name               TEXT        NOT NULL,
+    description        TEXT        NOT NULL DEFAULT '',
+    default_subscribed BOOLEAN     NOT NULL DEFAULT TRUE,
+    created_at         TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+INSERT INTO topics (key, name, description) VALUES
+    ('product_updates', 'Product updates', 'New features and changes to the product'),
+    ('promotions', 'Promotions', 'Offers and discounts'),
+    ('weekly_digest', 'Weekly digest', 'A summary of the week, sent every Monday')
+ON CONFLICT (key) DO NOTHING;
+
+-- Explicit choices only; a topic without a row falls back to its default_subscribed
+CREATE TABLE IF NOT EXISTS subscriber_topics (
+    email      TEXT        NOT NULL REFERENCES newsletters (email) ON DELETE CASCADE,
+    topic      TEXT        NOT NULL REFERENCES topics (key) ON DELETE CASCADE,
+    subscribed BOOLEAN     NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (email, topic)
+);
+
+CREATE INDEX IF NOT EXISTS subscriber_topics_topic_idx ON subscriber_topics (topic);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS attribute_definitions;
+DROP INDEX IF EXISTS newsletters_attributes_idx;
+ALTER TABLE newsletters DROP COLUMN IF EXISTS attributes;
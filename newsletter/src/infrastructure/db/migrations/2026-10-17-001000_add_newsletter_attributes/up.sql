@@ -0,0 +1,19 @@
+ALTER TABLE newsletters ADD COLUMN IF NOT EXISTS attributes JSONB NOT NULL DEFAULT '{}';
+
+-- Serves containment (@>) filters on attributes
+CREATE INDEX IF NOT EXISTS newsletters_attributes_idx ON newsletters USING GIN (attributes jsonb_path_ops);
+
+-- Schema registry: only keys defined here can be stored in newsletters.attributes
+CREATE TABLE IF NOT EXISTS attribute_definitions (
+    key         TEXT        PRIMARY KEY,
+    kind        TEXT        NOT NULL CHECK (kind IN ('string', 'number', 'boolean')),
+    description TEXT        NOT NULL DEFAULT '',
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+INSERT INTO attribute_definitions (key, kind, description) VALUES
+    ('first_name', 'string', 'Given name used in greetings'),
+    ('last_name', 'string', 'Family name'),
+    ('locale', 'string', 'Preferred language, e.g. en-US'),
+    ('signup_source', 'string', 'Where the subscriber signed up')
+ON CONFLICT (key) DO NOTHING;
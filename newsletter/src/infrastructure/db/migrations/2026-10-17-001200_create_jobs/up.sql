@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS jobs (
+    id           BIGSERIAL   PRIMARY KEY,
+    kind         TEXT        NOT NULL,
+    payload      JSONB       NOT NULL DEFAULT '{}',
+    status       TEXT        NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'done', 'failed')),
+    -- At most one pending job per key; recurring jobs use their kind
+    unique_key   TEXT        NULL,
+    attempts     INTEGER     NOT NULL DEFAULT 0,
+    max_attempts INTEGER     NOT NULL DEFAULT 5,
+    last_error   TEXT        NULL,
+    -- Not before this time; pushed forward while a worker holds the job and after failures
+    run_at       TIMESTAMPTZ NOT NULL DEFAULT now(),
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    finished_at  TIMESTAMPTZ NULL
+);
+
+CREATE INDEX IF NOT EXISTS jobs_pending_idx ON jobs (run_at, id) WHERE status = 'pending';
+CREATE UNIQUE INDEX IF NOT EXISTS jobs_unique_key_idx ON jobs (unique_key) WHERE status = 'pending';
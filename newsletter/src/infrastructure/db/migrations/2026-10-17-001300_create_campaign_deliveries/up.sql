@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS campaign_deliveries (
+    campaign_id BIGINT      NOT NULL REFERENCES campaigns (id) ON DELETE CASCADE,
+    email       TEXT        NOT NULL,
+    status      TEXT        NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'sending', 'sent', 'failed', 'skipped')),
+    attempts    INTEGER     NOT NULL DEFAULT 0,
+    last_error  TEXT        NULL,
+    -- Set while a sender holds the delivery; an old value means the sender died
+    claimed_at  TIMESTAMPTZ NULL,
+    sent_at     TIMESTAMPTZ NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (campaign_id, email)
+);
+
+CREATE INDEX IF NOT EXISTS campaign_deliveries_status_idx ON campaign_deliveries (campaign_id, status);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS engagement_events (
+    id          BIGSERIAL   PRIMARY KEY,
+    campaign_id BIGINT      NOT NULL REFERENCES campaigns (id) ON DELETE CASCADE,
+    email       TEXT        NOT NULL,
+    kind        TEXT        NOT NULL CHECK (kind IN ('open', 'click')),
+    -- Link target of a click; NULL for opens
+    url         TEXT        NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS engagement_events_campaign_idx ON engagement_events (campaign_id, kind);
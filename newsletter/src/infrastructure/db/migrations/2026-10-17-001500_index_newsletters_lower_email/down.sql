@@ -0,0 +1 @@
+DROP INDEX IF EXISTS newsletters_email_lower_idx;
@@ -0,0 +1,4 @@
+-- Serves case-insensitive lookups by address and keeps addresses that differ
+-- only by case from being stored twice. Addresses are lowercased on the way in,
+-- so only rows written before that can clash and stop this from applying.
+CREATE UNIQUE INDEX IF NOT EXISTS newsletters_email_lower_idx ON newsletters (lower(email));
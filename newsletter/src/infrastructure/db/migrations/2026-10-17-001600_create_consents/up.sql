@@ -0,0 +1,12 @@
+-- No foreign key: the proof of opt-in has to outlive the subscription.
+CREATE TABLE IF NOT EXISTS consents (
+    id             BIGSERIAL   PRIMARY KEY,
+    email          TEXT        NOT NULL,
+    action         TEXT        NOT NULL CHECK (action IN ('given', 'confirmed', 'withdrawn')),
+    policy_version TEXT        NULL,
+    source         TEXT        NULL,
+    ip_address     TEXT        NULL,
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS consents_email_idx ON consents (lower(email), id);
@@ -0,0 +1,52 @@
+ALTER TABLE api_keys DROP COLUMN IF EXISTS tenant_id;
+ALTER TABLE jobs DROP COLUMN IF EXISTS tenant_id;
+
+DROP TRIGGER IF EXISTS engagement_events_tenant ON engagement_events;
+DROP FUNCTION IF EXISTS engagement_events_tenant();
+
+DROP INDEX IF EXISTS consents_email_idx;
+CREATE INDEX consents_email_idx ON consents (lower(email), id);
+
+ALTER TABLE templates DROP CONSTRAINT IF EXISTS templates_tenant_name_key;
+ALTER TABLE templates ADD CONSTRAINT templates_name_key UNIQUE (name);
+
+ALTER TABLE subscriber_topics DROP CONSTRAINT IF EXISTS subscriber_topics_email_fkey;
+ALTER TABLE subscriber_topics DROP CONSTRAINT subscriber_topics_pkey;
+ALTER TABLE subscriber_topics ADD PRIMARY KEY (email, topic);
+
+ALTER TABLE subscriber_tags DROP CONSTRAINT IF EXISTS subscriber_tags_email_fkey;
+ALTER TABLE subscriber_tags DROP CONSTRAINT subscriber_tags_pkey;
+ALTER TABLE subscriber_tags ADD PRIMARY KEY (email, tag);
+
+ALTER TABLE confirmation_tokens DROP CONSTRAINT IF EXISTS confirmation_tokens_email_fkey;
+DROP INDEX IF EXISTS confirmation_tokens_email_idx;
+CREATE INDEX confirmation_tokens_email_idx ON confirmation_tokens (email);
+
+DROP INDEX IF EXISTS newsletters_email_lower_idx;
+CREATE UNIQUE INDEX newsletters_email_lower_idx ON newsletters (lower(email));
+ALTER TABLE newsletters DROP CONSTRAINT IF EXISTS newsletters_tenant_email_key;
+ALTER TABLE newsletters ADD CONSTRAINT newsletters_email_key UNIQUE (email);
+
+ALTER TABLE confirmation_tokens ADD CONSTRAINT confirmation_tokens_email_fkey
+    FOREIGN KEY (email) REFERENCES newsletters (email) ON DELETE CASCADE;
+ALTER TABLE subscriber_tags ADD CONSTRAINT subscriber_tags_email_fkey
+    FOREIGN KEY (email) REFERENCES newsletters (email) ON DELETE CASCADE;
+ALTER TABLE subscriber_topics ADD CONSTRAINT subscriber_topics_email_fkey
+    FOREIGN KEY (email) REFERENCES newsletters (email) ON DELETE CASCADE;
+
+DO $$
+DECLARE
+    t TEXT;
+BEGIN
+    FOREACH t IN ARRAY ARRAY[
+        'newsletters', 'confirmation_tokens', 'subscriber_tags', 'subscriber_topics', 'unsubscribe_events',
+        'consents', 'campaigns', 'campaign_deliveries', 'engagement_events', 'templates'
+    ]
+    LOOP
+        EXECUTE format('DROP POLICY IF EXISTS tenant_isolation ON %I', t);
+        EXECUTE format('ALTER TABLE %I NO FORCE ROW LEVEL SECURITY', t);
+        EXECUTE format('ALTER TABLE %I DISABLE ROW LEVEL SECURITY', t);
+        EXECUTE format('ALTER TABLE %I DROP COLUMN IF EXISTS tenant_id', t);
+    END LOOP;
+END
+$$;
@@ -0,0 +1,80 @@
+-- Existing rows belong to the default tenant. New rows take their tenant from
+-- the app.tenant_id setting of the connection, so repositories never write it.
+-- Rows are only visible to connections whose setting names their tenant, or
+-- '*' for maintenance across all of them; FORCE applies the policies to the
+-- table owner the service connects as. Superusers and BYPASSRLS roles skip
+-- them entirely.
+DO $$
+DECLARE
+    t TEXT;
+BEGIN
+    FOREACH t IN ARRAY ARRAY[
+        'newsletters', 'confirmation_tokens', 'subscriber_tags', 'subscriber_topics', 'unsubscribe_events',
+        'consents', 'campaigns', 'campaign_deliveries', 'engagement_events', 'templates'
+    ]
+    LOOP
+        EXECUTE format('ALTER TABLE %I ADD COLUMN IF NOT EXISTS tenant_id TEXT NOT NULL DEFAULT ''default''', t);
+        EXECUTE format('ALTER TABLE %I ALTER COLUMN tenant_id SET DEFAULT current_setting(''app.tenant_id'', true)', t);
+        EXECUTE format('ALTER TABLE %I ADD CONSTRAINT %I CHECK (tenant_id ~ ''^[a-z0-9][a-z0-9-]{0,62}$'')', t, t || '_tenant_id_check');
+        EXECUTE format('ALTER TABLE %I ENABLE ROW LEVEL SECURITY', t);
+        EXECUTE format('ALTER TABLE %I FORCE ROW LEVEL SECURITY', t);
+        EXECUTE format(
+            'CREATE POLICY tenant_isolation ON %I USING (tenant_id = current_setting(''app.tenant_id'', true) OR current_setting(''app.tenant_id'', true) = ''*'')',
+            t
+        );
+    END LOOP;
+END
+$$;
+
+-- Addresses, tags, topic choices and template names are unique per tenant
+ALTER TABLE confirmation_tokens DROP CONSTRAINT IF EXISTS confirmation_tokens_email_fkey;
+ALTER TABLE subscriber_tags DROP CONSTRAINT IF EXISTS subscriber_tags_email_fkey;
+ALTER TABLE subscriber_topics DROP CONSTRAINT IF EXISTS subscriber_topics_email_fkey;
+
+ALTER TABLE newsletters DROP CONSTRAINT IF EXISTS newsletters_email_key;
+ALTER TABLE newsletters ADD CONSTRAINT newsletters_tenant_email_key UNIQUE (tenant_id, email);
+DROP INDEX IF EXISTS newsletters_email_lower_idx;
+CREATE UNIQUE INDEX newsletters_email_lower_idx ON newsletters (tenant_id, lower(email));
+
+ALTER TABLE confirmation_tokens ADD CONSTRAINT confirmation_tokens_email_fkey
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE;
+DROP INDEX IF EXISTS confirmation_tokens_email_idx;
+CREATE INDEX confirmation_tokens_email_idx ON confirmation_tokens (tenant_id, email);
+
+ALTER TABLE subscriber_tags DROP CONSTRAINT subscriber_tags_pkey;
+ALTER TABLE subscriber_tags ADD PRIMARY KEY (tenant_id, email, tag);
+ALTER TABLE subscriber_tags ADD CONSTRAINT subscriber_tags_email_fkey
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE;
+
+ALTER TABLE subscriber_topics DROP CONSTRAINT subscriber_topics_pkey;
+ALTER TABLE subscriber_topics ADD PRIMARY KEY (tenant_id, email, topic);
+ALTER TABLE subscriber_topics ADD CONSTRAINT subscriber_topics_email_fkey
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE;
+
+ALTER TABLE templates DROP CONSTRAINT IF EXISTS templates_name_key;
+ALTER TABLE templates ADD CONSTRAINT templates_tenant_name_key UNIQUE (tenant_id, name);
+
+DROP INDEX IF EXISTS consents_email_idx;
+CREATE INDEX consents_email_idx ON consents (tenant_id, lower(email), id);
+
+-- Opens and clicks arrive through tracking links that name only the campaign,
+-- so they take the campaign's tenant
+CREATE OR REPLACE FUNCTION engagement_events_tenant() RETURNS trigger AS $$
+BEGIN
+    SELECT tenant_id INTO NEW.tenant_id FROM campaigns WHERE id = NEW.campaign_id;
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER engagement_events_tenant
+    BEFORE INSERT ON engagement_events
+    FOR EACH ROW EXECUTE FUNCTION engagement_events_tenant();
+
+-- A job runs in the tenant that queued it; NULL runs it across all tenants,
+-- as recurring maintenance does
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS tenant_id TEXT NULL;
+UPDATE jobs SET tenant_id = 'default' WHERE unique_key IS DISTINCT FROM kind;
+
+-- A key bound to a tenant can only reach that tenant; NULL keys pick one per call
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS tenant_id TEXT NULL
+    CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$');
@@ -0,0 +1,4 @@
+-- A campaign with a topic only goes to subscribers receiving that topic, by
+-- choice or by default; variables are shared by every recipient's render
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS topic TEXT NULL;
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS variables JSONB NOT NULL DEFAULT '{}'::jsonb;
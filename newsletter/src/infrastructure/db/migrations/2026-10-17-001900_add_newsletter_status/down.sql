@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS newsletters_status_idx;
+
+-- Unsubscribed and suppressed rows did not exist before
+DELETE FROM newsletters WHERE status IN ('unsubscribed', 'suppressed');
+
+ALTER TABLE newsletters DROP COLUMN active;
+ALTER TABLE newsletters ADD COLUMN active BOOLEAN NOT NULL DEFAULT TRUE;
+UPDATE newsletters SET active = (status = 'active');
+
+ALTER TABLE newsletters DROP COLUMN IF EXISTS status_changed_at;
+ALTER TABLE newsletters DROP COLUMN IF EXISTS status;
@@ -0,0 +1,13 @@
+-- Subscriptions move through pending -> active -> unsubscribed, and may be
+-- suppressed from any of them; unsubscribing keeps the row from now on
+ALTER TABLE newsletters ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'pending'
+    CHECK (status IN ('pending', 'active', 'unsubscribed', 'suppressed'));
+ALTER TABLE newsletters ADD COLUMN IF NOT EXISTS status_changed_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+UPDATE newsletters SET status = CASE WHEN active THEN 'active' ELSE 'pending' END;
+
+-- The flag stays readable for queries that only care about who gets mail
+ALTER TABLE newsletters DROP COLUMN active;
+ALTER TABLE newsletters ADD COLUMN active BOOLEAN GENERATED ALWAYS AS (status = 'active') STORED;
+
+CREATE INDEX IF NOT EXISTS newsletters_status_idx ON newsletters (tenant_id, status);
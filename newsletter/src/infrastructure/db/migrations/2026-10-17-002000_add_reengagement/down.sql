@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS engagement_events_email_idx;
+ALTER TABLE campaigns DROP COLUMN IF EXISTS tag;
@@ -0,0 +1,6 @@
+-- A campaign with a tag only goes to subscribers carrying it, such as the
+-- members of a re-engagement segment
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS tag TEXT NULL;
+
+-- Finding subscribers without recent opens or clicks
+CREATE INDEX IF NOT EXISTS engagement_events_email_idx ON engagement_events (tenant_id, email, created_at);
@@ -0,0 +1,4 @@
+-- Every save bumps the version and only applies while it still matches the
+-- one that was read, so concurrent edits cannot overwrite each other unseen
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE templates ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 1;
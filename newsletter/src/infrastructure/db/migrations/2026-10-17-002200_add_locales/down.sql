@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS template_translations;
+ALTER TABLE newsletters DROP COLUMN IF EXISTS locale;
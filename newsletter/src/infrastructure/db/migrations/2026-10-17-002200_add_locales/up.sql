@@ -0,0 +1,26 @@
+-- Preferred language of a subscriber as a BCP 47 tag; NULL when unknown
+ALTER TABLE newsletters ADD COLUMN IF NOT EXISTS locale TEXT;
+
+-- Signups recorded the locale as a custom attribute until now
+UPDATE newsletters
+SET locale = attributes->>'locale'
+WHERE locale IS NULL
+  AND attributes->>'locale' ~ '^[A-Za-z]{2,3}(-[A-Za-z0-9]{1,8})*$';
+
+-- A template body in another language, picked by the campaign sender for
+-- subscribers of that locale. It shares the template's format and variables.
+CREATE TABLE IF NOT EXISTS template_translations (
+    template_id BIGINT      NOT NULL REFERENCES templates (id) ON DELETE CASCADE,
+    locale      TEXT        NOT NULL,
+    body        TEXT        NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+    tenant_id   TEXT        NOT NULL DEFAULT current_setting('app.tenant_id', true)
+        CONSTRAINT template_translations_tenant_id_check CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$'),
+    PRIMARY KEY (template_id, locale)
+);
+
+ALTER TABLE template_translations ENABLE ROW LEVEL SECURITY;
+ALTER TABLE template_translations FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON template_translations
+    USING (tenant_id = current_setting('app.tenant_id', true) OR current_setting('app.tenant_id', true) = '*');
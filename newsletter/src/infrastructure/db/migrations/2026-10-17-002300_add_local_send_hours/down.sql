@@ -0,0 +1,6 @@
+ALTER TABLE campaign_deliveries DROP COLUMN IF EXISTS not_before;
+ALTER TABLE campaigns
+    DROP CONSTRAINT IF EXISTS campaigns_local_send_hours_check,
+    DROP COLUMN IF EXISTS local_send_end_hour,
+    DROP COLUMN IF EXISTS local_send_start_hour;
+ALTER TABLE newsletters DROP COLUMN IF EXISTS timezone;
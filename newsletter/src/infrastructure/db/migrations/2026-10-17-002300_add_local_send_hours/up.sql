@@ -0,0 +1,16 @@
+-- IANA timezone of a subscriber, such as Europe/Berlin; NULL when unknown
+ALTER TABLE newsletters ADD COLUMN IF NOT EXISTS timezone TEXT;
+
+-- Hours of the recipient's own day a campaign may arrive in; NULL sends at
+-- any hour
+ALTER TABLE campaigns
+    ADD COLUMN IF NOT EXISTS local_send_start_hour INTEGER,
+    ADD COLUMN IF NOT EXISTS local_send_end_hour INTEGER,
+    ADD CONSTRAINT campaigns_local_send_hours_check CHECK (
+        (local_send_start_hour IS NULL AND local_send_end_hour IS NULL)
+        OR (0 <= local_send_start_hour AND local_send_start_hour < local_send_end_hour AND local_send_end_hour <= 24)
+    );
+
+-- A delivery held back until the recipient's local send hours open; it is
+-- not claimed before then
+ALTER TABLE campaign_deliveries ADD COLUMN IF NOT EXISTS not_before TIMESTAMPTZ;
@@ -0,0 +1,4 @@
+DELETE FROM api_keys WHERE scope = 'operator';
+ALTER TABLE api_keys
+    DROP CONSTRAINT IF EXISTS api_keys_scope_check,
+    ADD CONSTRAINT api_keys_scope_check CHECK (scope IN ('read', 'admin'));
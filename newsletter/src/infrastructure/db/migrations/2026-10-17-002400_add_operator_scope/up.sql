@@ -0,0 +1,4 @@
+-- Operator keys may also call the runtime operations of the admin service
+ALTER TABLE api_keys
+    DROP CONSTRAINT IF EXISTS api_keys_scope_check,
+    ADD CONSTRAINT api_keys_scope_check CHECK (scope IN ('read', 'admin', 'operator'));
@@ -0,0 +1,2 @@
+-- Last time an unsubscribed address subscribed again; NULL when it never did
+ALTER TABLE newsletters ADD COLUMN IF NOT EXISTS resubscribed_at TIMESTAMPTZ;
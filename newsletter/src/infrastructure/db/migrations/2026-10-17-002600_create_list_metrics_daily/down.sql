@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS consents_confirmed_at_idx;
+DROP TABLE IF EXISTS list_metrics_daily;
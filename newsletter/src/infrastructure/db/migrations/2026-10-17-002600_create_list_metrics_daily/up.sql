@@ -0,0 +1,22 @@
+-- One snapshot of each tenant's list per UTC day, written by the nightly
+-- rollup shortly after the day ends. Subscribers are counted when the rollup
+-- runs, so a missed day cannot be filled in later.
+CREATE TABLE IF NOT EXISTS list_metrics_daily (
+    tenant_id    TEXT        NOT NULL DEFAULT current_setting('app.tenant_id', true)
+        CONSTRAINT list_metrics_daily_tenant_id_check CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$'),
+    day          DATE        NOT NULL,
+    subscribers  BIGINT      NOT NULL,
+    confirms     BIGINT      NOT NULL,
+    unsubscribes BIGINT      NOT NULL,
+    net_growth   BIGINT      NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (tenant_id, day)
+);
+
+ALTER TABLE list_metrics_daily ENABLE ROW LEVEL SECURITY;
+ALTER TABLE list_metrics_daily FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON list_metrics_daily
+    USING (tenant_id = current_setting('app.tenant_id', true) OR current_setting('app.tenant_id', true) = '*');
+
+-- The rollup counts a day's confirmations
+CREATE INDEX IF NOT EXISTS consents_confirmed_at_idx ON consents (created_at) WHERE action = 'confirmed';
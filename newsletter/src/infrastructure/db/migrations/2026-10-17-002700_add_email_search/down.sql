@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS newsletters_email_trgm_idx;
+-- The extensions are left installed; other schemas may use them
@@ -0,0 +1,6 @@
+-- Fuzzy address search for support: trigram matching and edit distance
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+CREATE EXTENSION IF NOT EXISTS fuzzystrmatch;
+
+-- Serves both the similarity operator and LIKE '%text%'
+CREATE INDEX IF NOT EXISTS newsletters_email_trgm_idx ON newsletters USING gin (lower(email) gin_trgm_ops);
@@ -0,0 +1,18 @@
+-- Spam complaints received through mailbox providers' feedback loops, one
+-- per recipient and campaign however often the provider reports it
+CREATE TABLE IF NOT EXISTS campaign_complaints (
+    id            BIGSERIAL   PRIMARY KEY,
+    tenant_id     TEXT        NOT NULL DEFAULT current_setting('app.tenant_id', true)
+        CONSTRAINT campaign_complaints_tenant_id_check CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$'),
+    campaign_id   BIGINT      NOT NULL REFERENCES campaigns (id) ON DELETE CASCADE,
+    email         TEXT        NOT NULL,
+    feedback_type TEXT        NOT NULL,
+    user_agent    TEXT,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (campaign_id, email)
+);
+
+ALTER TABLE campaign_complaints ENABLE ROW LEVEL SECURITY;
+ALTER TABLE campaign_complaints FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON campaign_complaints
+    USING (tenant_id = current_setting('app.tenant_id', true) OR current_setting('app.tenant_id', true) = '*');
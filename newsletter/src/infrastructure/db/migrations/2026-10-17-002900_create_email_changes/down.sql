@@ -0,0 +1,13 @@
+ALTER TABLE subscriber_topics DROP CONSTRAINT subscriber_topics_email_fkey;
+ALTER TABLE subscriber_topics ADD CONSTRAINT subscriber_topics_email_fkey
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE;
+
+ALTER TABLE subscriber_tags DROP CONSTRAINT subscriber_tags_email_fkey;
+ALTER TABLE subscriber_tags ADD CONSTRAINT subscriber_tags_email_fkey
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE;
+
+ALTER TABLE confirmation_tokens DROP CONSTRAINT confirmation_tokens_email_fkey;
+ALTER TABLE confirmation_tokens ADD CONSTRAINT confirmation_tokens_email_fkey
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE;
+
+DROP TABLE IF EXISTS email_changes;
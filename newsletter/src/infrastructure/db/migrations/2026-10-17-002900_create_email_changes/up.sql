@@ -0,0 +1,33 @@
+-- Moves of a subscription to another address, waiting for the new address to
+-- confirm; dropped with the subscription they belong to
+CREATE TABLE IF NOT EXISTS email_changes (
+    token_id   UUID        PRIMARY KEY,
+    tenant_id  TEXT        NOT NULL DEFAULT current_setting('app.tenant_id', true)
+        CONSTRAINT email_changes_tenant_id_check CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$'),
+    email      TEXT        NOT NULL,
+    new_email  TEXT        NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS email_changes_email_idx ON email_changes (tenant_id, email);
+
+ALTER TABLE email_changes ENABLE ROW LEVEL SECURITY;
+ALTER TABLE email_changes FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON email_changes
+    USING (tenant_id = current_setting('app.tenant_id', true) OR current_setting('app.tenant_id', true) = '*');
+
+-- A confirmed change renames the subscription, and with it the rows keyed by
+-- its address
+ALTER TABLE confirmation_tokens DROP CONSTRAINT confirmation_tokens_email_fkey;
+ALTER TABLE confirmation_tokens ADD CONSTRAINT confirmation_tokens_email_fkey
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE ON UPDATE CASCADE;
+
+ALTER TABLE subscriber_tags DROP CONSTRAINT subscriber_tags_email_fkey;
+ALTER TABLE subscriber_tags ADD CONSTRAINT subscriber_tags_email_fkey
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE ON UPDATE CASCADE;
+
+ALTER TABLE subscriber_topics DROP CONSTRAINT subscriber_topics_email_fkey;
+ALTER TABLE subscriber_topics ADD CONSTRAINT subscriber_topics_email_fkey
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE ON UPDATE CASCADE;
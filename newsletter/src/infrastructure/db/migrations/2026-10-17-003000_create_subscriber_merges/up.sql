@@ -0,0 +1,19 @@
+-- Audit trail of subscriptions an operator folded into another; addresses
+-- are stored like consents, pseudonymized when that is configured
+CREATE TABLE IF NOT EXISTS subscriber_merges (
+    id            BIGSERIAL   PRIMARY KEY,
+    tenant_id     TEXT        NOT NULL DEFAULT current_setting('app.tenant_id', true)
+        CONSTRAINT subscriber_merges_tenant_id_check CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$'),
+    email         TEXT        NOT NULL,
+    merged_email  TEXT        NOT NULL,
+    merged_status TEXT        NOT NULL,
+    status        TEXT        NOT NULL,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS subscriber_merges_email_idx ON subscriber_merges (tenant_id, email);
+
+ALTER TABLE subscriber_merges ENABLE ROW LEVEL SECURITY;
+ALTER TABLE subscriber_merges FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON subscriber_merges
+    USING (tenant_id = current_setting('app.tenant_id', true) OR current_setting('app.tenant_id', true) = '*');
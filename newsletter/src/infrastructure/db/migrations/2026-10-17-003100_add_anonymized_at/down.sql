@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS newsletters_retention_idx;
+ALTER TABLE newsletters DROP COLUMN IF EXISTS anonymized_at;
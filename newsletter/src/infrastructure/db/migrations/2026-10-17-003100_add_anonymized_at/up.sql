@@ -0,0 +1,8 @@
+-- When retention replaced the address of an unsubscribed subscription with a
+-- placeholder; NULL while it still holds the real one
+ALTER TABLE newsletters ADD COLUMN IF NOT EXISTS anonymized_at TIMESTAMPTZ;
+
+-- Retention runs look for unsubscribes older than the tenant's limit
+CREATE INDEX IF NOT EXISTS newsletters_retention_idx
+    ON newsletters (tenant_id, status_changed_at)
+    WHERE status = 'unsubscribed';
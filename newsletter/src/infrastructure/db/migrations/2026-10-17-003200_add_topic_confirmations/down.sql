@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS topic_confirmations;
+ALTER TABLE topics DROP COLUMN IF EXISTS requires_confirmation;
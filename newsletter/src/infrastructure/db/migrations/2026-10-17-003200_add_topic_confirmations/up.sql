@@ -0,0 +1,23 @@
+-- Topics such as promotions in some markets need their own double opt-in:
+-- opting in takes a confirmed link even for an active subscriber
+ALTER TABLE topics ADD COLUMN IF NOT EXISTS requires_confirmation BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Opt-ins to such topics waiting for the subscriber to follow the link;
+-- dropped with the subscription and renamed with its address
+CREATE TABLE IF NOT EXISTS topic_confirmations (
+    token_id   UUID        PRIMARY KEY,
+    tenant_id  TEXT        NOT NULL DEFAULT current_setting('app.tenant_id', true)
+        CONSTRAINT topic_confirmations_tenant_id_check CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$'),
+    email      TEXT        NOT NULL,
+    topic      TEXT        NOT NULL REFERENCES topics (key) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS topic_confirmations_email_idx ON topic_confirmations (tenant_id, email);
+
+ALTER TABLE topic_confirmations ENABLE ROW LEVEL SECURITY;
+ALTER TABLE topic_confirmations FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON topic_confirmations
+    USING (tenant_id = current_setting('app.tenant_id', true) OR current_setting('app.tenant_id', true) = '*');
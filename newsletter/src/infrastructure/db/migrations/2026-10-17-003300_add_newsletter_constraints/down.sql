@@ -0,0 +1,8 @@
+DROP TRIGGER IF EXISTS newsletters_set_updated_at ON newsletters;
+DROP FUNCTION IF EXISTS set_updated_at();
+ALTER TABLE newsletters DROP COLUMN IF EXISTS updated_at;
+ALTER TABLE newsletters DROP CONSTRAINT IF EXISTS newsletters_email_lowercase_check;
+
+ALTER TABLE email_changes DROP CONSTRAINT IF EXISTS email_changes_tenant_id_email_fkey;
+ALTER TABLE email_changes ADD CONSTRAINT email_changes_tenant_id_email_fkey
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE;
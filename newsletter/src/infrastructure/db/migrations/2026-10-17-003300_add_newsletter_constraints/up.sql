@@ -0,0 +1,37 @@
+-- Integrity the repository used to keep on its own. Addresses are stored
+-- lowercased; rows written before that are folded here, and cannot collide
+-- since newsletters_email_lower_idx already keeps case variants apart.
+-- Row-level security would hide every row from the backfill otherwise.
+SELECT set_config('app.tenant_id', '*', true);
+
+-- Folding renames pending address changes along with their subscription
+ALTER TABLE email_changes DROP CONSTRAINT IF EXISTS email_changes_tenant_id_email_fkey;
+ALTER TABLE email_changes ADD CONSTRAINT email_changes_tenant_id_email_fkey
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email) ON DELETE CASCADE ON UPDATE CASCADE;
+
+UPDATE newsletters SET email = lower(email) WHERE email <> lower(email);
+ALTER TABLE newsletters ADD CONSTRAINT newsletters_email_lowercase_check CHECK (email = lower(email));
+
+ALTER TABLE newsletters ALTER COLUMN created_at SET DEFAULT now();
+ALTER TABLE newsletters ALTER COLUMN created_at SET NOT NULL;
+
+-- When anything about the subscription last changed
+ALTER TABLE newsletters ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ;
+UPDATE newsletters SET updated_at = greatest(created_at, status_changed_at) WHERE updated_at IS NULL;
+ALTER TABLE newsletters ALTER COLUMN updated_at SET DEFAULT now();
+ALTER TABLE newsletters ALTER COLUMN updated_at SET NOT NULL;
+
+-- An update that sets updated_at itself, such as a merge carrying it over,
+-- keeps its value
+CREATE OR REPLACE FUNCTION set_updated_at() RETURNS trigger AS $$
+BEGIN
+    IF NEW.updated_at IS NOT DISTINCT FROM OLD.updated_at THEN
+        NEW.updated_at := now();
+    END IF;
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS newsletters_set_updated_at ON newsletters;
+CREATE TRIGGER newsletters_set_updated_at BEFORE UPDATE ON newsletters
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
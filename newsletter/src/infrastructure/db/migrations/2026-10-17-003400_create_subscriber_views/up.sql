@@ -0,0 +1,30 @@
+-- Read model of the subscriber list: one narrow row per subscription, kept
+-- up to date by the outbox relay from subscription events, so listings and
+-- counts do not touch the tables the writes lock. `updated_at` is the time
+-- of the last event applied, which makes a replayed or late event a no-op.
+CREATE TABLE IF NOT EXISTS subscriber_views (
+    id         BIGSERIAL   PRIMARY KEY,
+    tenant_id  TEXT        NOT NULL DEFAULT current_setting('app.tenant_id', true)
+        CONSTRAINT subscriber_views_tenant_id_check CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$'),
+    email      TEXT        NOT NULL,
+    status     TEXT        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    CONSTRAINT subscriber_views_tenant_email_key UNIQUE (tenant_id, email)
+);
+
+CREATE INDEX IF NOT EXISTS subscriber_views_created_at_idx ON subscriber_views (tenant_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS subscriber_views_status_idx ON subscriber_views (tenant_id, status);
+
+ALTER TABLE subscriber_views ENABLE ROW LEVEL SECURITY;
+ALTER TABLE subscriber_views FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON subscriber_views
+    USING (tenant_id = current_setting('app.tenant_id', true) OR current_setting('app.tenant_id', true) = '*');
+
+-- Start from the subscriptions stored so far; events still in the outbox are
+-- older than these rows and skipped
+SELECT set_config('app.tenant_id', '*', true);
+INSERT INTO subscriber_views (tenant_id, email, status, created_at, updated_at)
+SELECT tenant_id, email, status, created_at, updated_at
+FROM newsletters
+ON CONFLICT (tenant_id, email) DO NOTHING;
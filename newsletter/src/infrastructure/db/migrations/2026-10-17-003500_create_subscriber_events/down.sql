@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS subscriber_events;
+DROP FUNCTION IF EXISTS subscriber_events_append_only();
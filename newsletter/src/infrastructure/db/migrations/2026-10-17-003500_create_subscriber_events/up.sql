@@ -0,0 +1,66 @@
+-- Append-only history of subscriptions: every event written to the outbox is
+-- recorded here too, in the same transaction, and kept after the outbox has
+-- published and purged it. The status of an address at any past time is the
+-- fold of its events up to then.
+CREATE TABLE IF NOT EXISTS subscriber_events (
+    id             BIGSERIAL   PRIMARY KEY,
+    tenant_id      TEXT        NOT NULL DEFAULT current_setting('app.tenant_id', true)
+        CONSTRAINT subscriber_events_tenant_id_check CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$'),
+    email          TEXT        NOT NULL,
+    -- Address the subscription moved from, for address changes and merges
+    previous_email TEXT,
+    event_type     TEXT        NOT NULL,
+    status         TEXT,
+    -- The event as published, before pseudonymization
+    payload        JSONB       NOT NULL,
+    occurred_at    TIMESTAMPTZ NOT NULL,
+    recorded_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS subscriber_events_email_idx ON subscriber_events (tenant_id, email, occurred_at, id);
+CREATE INDEX IF NOT EXISTS subscriber_events_previous_email_idx ON subscriber_events (tenant_id, previous_email)
+    WHERE previous_email IS NOT NULL;
+
+ALTER TABLE subscriber_events ENABLE ROW LEVEL SECURITY;
+ALTER TABLE subscriber_events FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON subscriber_events
+    USING (tenant_id = current_setting('app.tenant_id', true) OR current_setting('app.tenant_id', true) = '*');
+
+-- Only the addresses may be rewritten, which retention does when it
+-- anonymizes a subscription; what happened and when stays as recorded
+CREATE OR REPLACE FUNCTION subscriber_events_append_only() RETURNS trigger AS $$
+BEGIN
+    IF NEW.tenant_id IS DISTINCT FROM OLD.tenant_id
+        OR NEW.event_type IS DISTINCT FROM OLD.event_type
+        OR NEW.status IS DISTINCT FROM OLD.status
+        OR NEW.occurred_at IS DISTINCT FROM OLD.occurred_at
+        OR NEW.recorded_at IS DISTINCT FROM OLD.recorded_at THEN
+        RAISE EXCEPTION 'subscriber_events is append-only' USING ERRCODE = 'check_violation';
+    END IF;
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS subscriber_events_append_only ON subscriber_events;
+CREATE TRIGGER subscriber_events_append_only BEFORE UPDATE ON subscriber_events
+    FOR EACH ROW EXECUTE FUNCTION subscriber_events_append_only();
+
+-- History starts from the subscriptions stored so far: one status change
+-- each, at the time their status last changed
+SELECT set_config('app.tenant_id', '*', true);
+INSERT INTO subscriber_events (tenant_id, email, event_type, status, payload, occurred_at)
+SELECT
+    tenant_id,
+    email,
+    'newsletter.status_changed',
+    status,
+    jsonb_build_object(
+        'type', 'newsletter.status_changed',
+        'email', email,
+        'active', status = 'active',
+        'status', status,
+        'occurred_at', status_changed_at,
+        'tenant', tenant_id
+    ),
+    status_changed_at
+FROM newsletters;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS subscriber_events_occurred_at_idx;
+DROP INDEX IF EXISTS engagement_events_created_at_idx;
+DROP TABLE IF EXISTS analytics_exports;
@@ -0,0 +1,13 @@
+-- Days of each dataset already written to the analytics bucket. The export
+-- covers every tenant, so rows are not scoped to one.
+CREATE TABLE IF NOT EXISTS analytics_exports (
+    dataset     TEXT        NOT NULL,
+    day         DATE        NOT NULL,
+    rows        BIGINT      NOT NULL,
+    exported_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (dataset, day)
+);
+
+-- The export reads a day at a time
+CREATE INDEX IF NOT EXISTS engagement_events_created_at_idx ON engagement_events (created_at);
+CREATE INDEX IF NOT EXISTS subscriber_events_occurred_at_idx ON subscriber_events (occurred_at);
@@ -0,0 +1,22 @@
+-- Images and attachments uploaded for templates. The content lives in the
+-- asset store under storage_key; rows are never updated, only deleted by
+-- the collection of assets no template references.
+CREATE TABLE IF NOT EXISTS template_assets (
+    id           BIGSERIAL   PRIMARY KEY,
+    tenant_id    TEXT        NOT NULL DEFAULT current_setting('app.tenant_id', true)
+        CONSTRAINT template_assets_tenant_id_check CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$'),
+    name         TEXT        NOT NULL,
+    content_type TEXT        NOT NULL,
+    size_bytes   BIGINT      NOT NULL CHECK (size_bytes > 0),
+    sha256       TEXT        NOT NULL,
+    storage_key  TEXT        NOT NULL UNIQUE,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CONSTRAINT template_assets_name_key UNIQUE (tenant_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS template_assets_created_at_idx ON template_assets (created_at);
+
+ALTER TABLE template_assets ENABLE ROW LEVEL SECURITY;
+ALTER TABLE template_assets FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON template_assets
+    USING (tenant_id = current_setting('app.tenant_id', true) OR current_setting('app.tenant_id', true) = '*');
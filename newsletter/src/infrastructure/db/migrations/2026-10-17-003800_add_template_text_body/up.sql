@@ -0,0 +1,2 @@
+-- Handlebars source of the text/plain part; NULL derives it from the HTML
+ALTER TABLE templates ADD COLUMN IF NOT EXISTS text_body TEXT;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS dkim_keys;
+DROP TABLE IF EXISTS sending_domains;
@@ -0,0 +1,42 @@
+-- The domain a tenant's campaigns are sent from, at most one per tenant.
+-- verified_at is set once a DKIM key of the domain was found published.
+CREATE TABLE IF NOT EXISTS sending_domains (
+    id           BIGSERIAL   PRIMARY KEY,
+    tenant_id    TEXT        NOT NULL DEFAULT current_setting('app.tenant_id', true)
+        CONSTRAINT sending_domains_tenant_id_check CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$'),
+    domain       TEXT        NOT NULL,
+    from_address TEXT        NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    verified_at  TIMESTAMPTZ,
+    CONSTRAINT sending_domains_tenant_id_key UNIQUE (tenant_id)
+);
+
+ALTER TABLE sending_domains ENABLE ROW LEVEL SECURITY;
+ALTER TABLE sending_domains FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON sending_domains
+    USING (tenant_id = current_setting('app.tenant_id', true) OR current_setting('app.tenant_id', true) = '*');
+
+-- DKIM keys of a sending domain: the active one messages are signed with and,
+-- during a rotation, the pending one waiting for its DNS record. The private
+-- key is PKCS#1 PEM, the public key the base64 DER published as `p=`.
+CREATE TABLE IF NOT EXISTS dkim_keys (
+    id           BIGSERIAL   PRIMARY KEY,
+    tenant_id    TEXT        NOT NULL DEFAULT current_setting('app.tenant_id', true)
+        CONSTRAINT dkim_keys_tenant_id_check CHECK (tenant_id ~ '^[a-z0-9][a-z0-9-]{0,62}$'),
+    domain_id    BIGINT      NOT NULL REFERENCES sending_domains (id) ON DELETE CASCADE,
+    selector     TEXT        NOT NULL,
+    private_key  TEXT        NOT NULL,
+    public_key   TEXT        NOT NULL,
+    status       TEXT        NOT NULL CHECK (status IN ('pending', 'active')),
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+    activated_at TIMESTAMPTZ,
+    CONSTRAINT dkim_keys_selector_key UNIQUE (domain_id, selector)
+);
+
+-- One key of each status per domain
+CREATE UNIQUE INDEX IF NOT EXISTS dkim_keys_domain_status_idx ON dkim_keys (domain_id, status);
+
+ALTER TABLE dkim_keys ENABLE ROW LEVEL SECURITY;
+ALTER TABLE dkim_keys FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON dkim_keys
+    USING (tenant_id = current_setting('app.tenant_id', true) OR current_setting('app.tenant_id', true) = '*');
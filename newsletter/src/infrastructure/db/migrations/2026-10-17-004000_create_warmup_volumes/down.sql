@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS warmup_volumes;
+DROP TABLE IF EXISTS warmup_domains;
@@ -0,0 +1,18 @@
+-- Warm-up progress of the domains campaigns are sent from. A domain's
+-- reputation is shared by every tenant sending from it, the default
+-- EMAIL_FROM domain above all, so these tables are not tenant scoped.
+CREATE TABLE IF NOT EXISTS warmup_domains (
+    domain     TEXT        PRIMARY KEY,
+    -- Day 0 of the ramp: the first UTC day the domain sent a campaign
+    started_on DATE        NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Campaign sends per domain and UTC day, counted while the domain is on
+-- its ramp; reserved before a batch is sent, unused reservations returned
+CREATE TABLE IF NOT EXISTS warmup_volumes (
+    domain TEXT   NOT NULL REFERENCES warmup_domains (domain) ON DELETE CASCADE,
+    day    DATE   NOT NULL,
+    sent   BIGINT NOT NULL DEFAULT 0 CHECK (sent >= 0),
+    PRIMARY KEY (domain, day)
+);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS template_assets_last_referenced_at_idx;
+ALTER TABLE template_assets DROP COLUMN IF EXISTS sent_at;
+ALTER TABLE template_assets DROP COLUMN IF EXISTS last_referenced_at;
@@ -0,0 +1,27 @@
+-- Unreferenced assets are collected a grace period after a template or
+-- translation last stopped using them, and never once a campaign that
+-- showed them was sent, since the emails keep linking to them
+ALTER TABLE template_assets ADD COLUMN IF NOT EXISTS last_referenced_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE template_assets ADD COLUMN IF NOT EXISTS sent_at TIMESTAMPTZ;
+
+-- Assets shown by emails sent so far. Bodies are matched by name, which
+-- may keep an asset that was only mentioned in passing; that is the safe
+-- way to be wrong here.
+SELECT set_config('app.tenant_id', '*', true);
+UPDATE template_assets a
+SET sent_at = now()
+WHERE a.sent_at IS NULL
+  AND EXISTS (
+    SELECT 1
+    FROM campaigns c
+    JOIN templates t ON t.id = c.template_id
+    LEFT JOIN template_translations tr ON tr.template_id = t.id
+    WHERE c.tenant_id = a.tenant_id
+      AND EXISTS (SELECT 1 FROM campaign_deliveries d WHERE d.campaign_id = c.id AND d.status = 'sent')
+      AND (position(a.name IN t.body) > 0
+        OR position(a.name IN coalesce(t.text_body, '')) > 0
+        OR position(a.name IN coalesce(tr.body, '')) > 0)
+  );
+
+CREATE INDEX IF NOT EXISTS template_assets_last_referenced_at_idx ON template_assets (last_referenced_at)
+    WHERE sent_at IS NULL;
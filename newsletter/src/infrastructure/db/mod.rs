@@ -1,14 +1,38 @@
 pub mod db_schema;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
-use std::env;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-use diesel::pg::PgConnection;
-use diesel::Connection;
+use anyhow::Context;
+use diesel::migration::MigrationSource;
+use diesel::pg::{Pg, PgConnection};
+use diesel::result::DatabaseErrorKind;
+use diesel::{Connection, QueryResult, QueryableByName};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use diesel::sql_types::{Bool, Text};
+use diesel_async::scoped_futures::ScopedBoxFuture;
+use diesel_async::RunQueryDsl;
 use diesel_async::{
-	pooled_connection::{bb8::Pool, AsyncDieselConnectionManager},
-	AsyncPgConnection,
+	pooled_connection::{
+		bb8::{Pool, PooledConnection, RunError},
+		AsyncDieselConnectionManager, ManagerConfig, PoolError,
+	},
+	AnsiTransactionManager, AsyncConnection, AsyncPgConnection, TransactionManager,
 };
+use futures::FutureExt;
+use serde::Deserialize;
+use std::fmt;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use tracing::{info, warn};
+
+use crate::infrastructure::config::DatabaseSettings;
+use crate::infrastructure::tenant;
+use crate::service::newsletter::import::StoreLoad;
 
 /// Pool type (bb8 re-exported by `diesel_async`)
 pub type PgPool = Pool<AsyncPgConnection>;
@@ -17,25 +41,71 @@ pub type PgPool = Pool<AsyncPgConnection>;
 /// Your migrations live under `src/infrastructure/db/migrations`, so use that:
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("src/infrastructure/db/migrations");
 
+/// Pooled connection that does not borrow its pool
+pub type PgConnectionGuard = PooledConnection<'static, AsyncPgConnection>;
+
 /// Build a pool for `AsyncPgConnection`.
-pub async fn build_pool() -> anyhow::Result<PgPool> {
-	let url = env::var("DATABASE_URL").map_err(|e| anyhow::anyhow!("DATABASE_URL not set: {e}"))?;
-	let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(url);
-	let pool = Pool::builder().max_size(16).build(manager).await?;
+pub async fn build_pool(settings: &DatabaseSettings) -> anyhow::Result<PgPool> {
+	let mut config = ManagerConfig::default();
+	if let Some(timeout) = settings.statement_timeout() {
+		// A session setting rather than `SET LOCAL`, which would end with the
+		// transaction while most reads run outside of one
+		let statement = format!("SET statement_timeout = {}", timeout.as_millis());
+		config.custom_setup = Box::new(move |url| {
+			let statement = statement.clone();
+			async move {
+				let mut conn = AsyncPgConnection::establish(url).await?;
+				diesel::sql_query(statement)
+					.execute(&mut conn)
+					.await
+					.map_err(diesel::ConnectionError::CouldntSetupConfiguration)?;
+				Ok(conn)
+			}
+			.boxed()
+		});
+	}
+	let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new_with_config(&settings.url, config);
+	let pool = Pool::builder()
+		.max_size(settings.max_size)
+		.min_idle((settings.min_idle > 0).then_some(settings.min_idle))
+		.connection_timeout(settings.acquire_timeout())
+		.max_lifetime(settings.max_lifetime())
+		.idle_timeout(settings.idle_timeout())
+		.build(manager)
+		.await?;
 	Ok(pool)
 }
 
-/// Build a pool with a specific database URL (useful for testing).
-#[cfg(test)]
+/// Build a pool for the read replica, sized like the primary's, if one is configured.
+pub async fn build_replica_pool(settings: &DatabaseSettings) -> anyhow::Result<Option<PgPool>> {
+	let Some(url) = settings.replica_url.as_deref().filter(|url| !url.is_empty()) else {
+		return Ok(None);
+	};
+	let pool = build_pool(&DatabaseSettings {
+		url: url.to_string(),
+		..settings.clone()
+	})
+	.await?;
+	Ok(Some(pool))
+}
+
+/// Build a pool for `url` with the default pool settings (useful for testing).
 pub async fn build_pool_with_url(url: &str) -> anyhow::Result<PgPool> {
-	let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(url);
-	let pool = Pool::builder().max_size(16).build(manager).await?;
-	Ok(pool)
+	build_pool(&DatabaseSettings {
+		url: url.to_string(),
+		..DatabaseSettings::default()
+	})
+	.await
+}
+
+/// Run embedded migrations against the configured database.
+pub async fn run_migrations(settings: &DatabaseSettings) -> anyhow::Result<()> {
+	run_migrations_with_url(&settings.url).await
 }
 
 /// Run embedded migrations on a blocking thread with a sync PgConnection.
-pub async fn run_migrations() -> anyhow::Result<()> {
-	let url = env::var("DATABASE_URL").map_err(|e| anyhow::anyhow!("DATABASE_URL not set: {e}"))?;
+pub async fn run_migrations_with_url(url: &str) -> anyhow::Result<()> {
+	let url = url.to_string();
 
 	tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
 		let mut conn = PgConnection::establish(&url).map_err(anyhow::Error::new)?;
@@ -49,19 +119,524 @@ pub async fn run_migrations() -> anyhow::Result<()> {
 	Ok(())
 }
 
-/// Run migrations with a specific database URL (useful for testing).
-#[cfg(test)]
-pub async fn run_migrations_with_url(url: &str) -> anyhow::Result<()> {
+/// Which database the newsletter service keeps subscriptions in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackend {
+	/// Everything, with row-level security between tenants
+	#[default]
+	Postgres,
+	/// A single file, or `:memory:`, serving only the newsletter API;
+	/// needs the `sqlite` feature
+	Sqlite,
+}
+
+impl fmt::Display for DatabaseBackend {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			DatabaseBackend::Postgres => "postgres",
+			DatabaseBackend::Sqlite => "sqlite",
+		})
+	}
+}
+
+/// What the server does about migrations on startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MigrationsMode {
+	/// Refuse to start unless the schema is already current
+	Check,
+	/// Apply pending migrations, then check
+	Apply,
+	/// Neither apply nor check; the schema is someone else's concern
+	Skip,
+}
+
+impl fmt::Display for MigrationsMode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			MigrationsMode::Check => "check",
+			MigrationsMode::Apply => "apply",
+			MigrationsMode::Skip => "skip",
+		})
+	}
+}
+
+/// One migration built into this binary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedMigration {
+	/// Timestamp prefix, as diesel records it once run
+	pub version: String,
+	/// Directory name, version included
+	pub name: String,
+	/// Whether the database has run it
+	pub applied: bool,
+}
+
+/// How the database schema compares to the migrations built into this binary
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaStatus {
+	/// Every embedded migration, oldest first
+	pub migrations: Vec<EmbeddedMigration>,
+	/// Migrations the database ran that this build does not know, from a newer release
+	pub unknown: Vec<String>,
+}
+
+impl SchemaStatus {
+	/// Versions of the embedded migrations the database has not run, oldest first
+	pub fn pending(&self) -> Vec<&str> {
+		self.migrations
+			.iter()
+			.filter(|migration| !migration.applied)
+			.map(|migration| migration.version.as_str())
+			.collect()
+	}
+}
+
+/// Compare the migrations the database ran with the embedded ones.
+pub async fn schema_status(url: &str) -> anyhow::Result<SchemaStatus> {
 	let url = url.to_string();
-	
-	tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
+
+	tokio::task::spawn_blocking(move || -> anyhow::Result<SchemaStatus> {
 		let mut conn = PgConnection::establish(&url).map_err(anyhow::Error::new)?;
-		// This returns Result<_, Box<dyn Error + Send + Sync>> — map explicitly to anyhow
-		conn.run_pending_migrations(MIGRATIONS)
-			.map_err(|e| anyhow::anyhow!(e))?;
-		Ok(())
+		let applied: Vec<String> = conn
+			.applied_migrations()
+			.map_err(|e| anyhow::anyhow!(e))?
+			.iter()
+			.map(ToString::to_string)
+			.collect();
+		let mut migrations: Vec<EmbeddedMigration> = MigrationSource::<Pg>::migrations(&MIGRATIONS)
+			.map_err(|e| anyhow::anyhow!(e))?
+			.iter()
+			.map(|migration| {
+				let version = migration.name().version().to_string();
+				EmbeddedMigration {
+					applied: applied.contains(&version),
+					name: migration.name().to_string(),
+					version,
+				}
+			})
+			.collect();
+		migrations.sort_by(|a, b| a.version.cmp(&b.version));
+
+		let mut unknown: Vec<String> = applied
+			.into_iter()
+			.filter(|v| !migrations.iter().any(|migration| &migration.version == v))
+			.collect();
+		unknown.sort();
+		Ok(SchemaStatus { migrations, unknown })
 	})
-	.await??;
+	.await?
+}
+
+/// Open `min_idle` connections (at least one) up front and run a query on
+/// each, so an unreachable database fails startup rather than the first
+/// requests.
+pub async fn warm_up(pool: &PgPool, settings: &DatabaseSettings) -> anyhow::Result<()> {
+	let wanted = settings.min_idle.clamp(1, settings.max_size.max(1));
+	let mut connections = futures::future::try_join_all((0..wanted).map(|_| pool.get()))
+		.await
+		.with_context(|| {
+			format!(
+				"could not open {wanted} database connection(s) within {}ms",
+				settings.acquire_timeout_ms
+			)
+		})?;
+	for conn in &mut connections {
+		diesel::sql_query("SELECT 1")
+			.execute(conn)
+			.await
+			.context("database connection did not answer")?;
+	}
+	info!(connections = wanted, "Database pool warmed up");
+	Ok(())
+}
 
+/// Warm up the pool and make sure the schema is exactly what this build
+/// expects, with an error saying what to do otherwise.
+pub async fn startup_check(pool: &PgPool, settings: &DatabaseSettings) -> anyhow::Result<()> {
+	warm_up(pool, settings).await?;
+
+	let status = schema_status(&settings.url).await.context("could not read the applied migrations")?;
+	if !status.unknown.is_empty() {
+		anyhow::bail!(
+			"the database schema is newer than this build: unknown migrations {}; deploy a release that includes them",
+			status.unknown.join(", ")
+		);
+	}
+	let pending = status.pending();
+	if let [first, ..] = pending.as_slice() {
+		anyhow::bail!(
+			"the database schema is behind this build: {} pending migration(s) starting at {first}; run `newsletter-admin migrate` or start with MIGRATIONS_MODE=apply",
+			pending.len()
+		);
+	}
 	Ok(())
 }
+
+/// Snapshot of a pool, for health checks and metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolHealth {
+	/// Whether a connection could be checked out and answer a query
+	pub healthy: bool,
+	/// Open connections, busy or idle
+	pub connections: u32,
+	pub idle_connections: u32,
+}
+
+/// Check out a connection and run `SELECT 1` on it; checking out is bounded
+/// by the pool's acquire timeout
+pub async fn pool_health(pool: &PgPool) -> PoolHealth {
+	let healthy = match pool.get().await {
+		Ok(mut conn) => diesel::sql_query("SELECT 1").execute(&mut conn).await.is_ok(),
+		Err(_) => false,
+	};
+
+	let state = pool.state();
+	PoolHealth {
+		healthy,
+		connections: state.connections,
+		idle_connections: state.idle_connections,
+	}
+}
+
+/// Load of a pool as imports see it: saturated while every connection it
+/// may open is open and busy
+pub struct PoolLoad {
+	pool: PgPool,
+	max_size: u32,
+}
+
+impl PoolLoad {
+	pub fn new(pool: PgPool, max_size: u32) -> Self {
+		Self { pool, max_size }
+	}
+}
+
+impl StoreLoad for PoolLoad {
+	fn saturated(&self) -> bool {
+		let state = self.pool.state();
+		state.idle_connections == 0 && state.connections >= self.max_size
+	}
+}
+
+tokio::task_local! {
+	/// Connection of the unit of work the running task is in
+	static UNIT: Arc<Mutex<PgConnectionGuard>>;
+}
+
+/// A connection from [`tenant_connection`]: checked out for the one call,
+/// or the connection of the unit of work the call is part of
+pub enum TenantConnection {
+	Pooled(PgConnectionGuard),
+	Unit(OwnedMutexGuard<PgConnectionGuard>),
+}
+
+impl Deref for TenantConnection {
+	type Target = AsyncPgConnection;
+
+	fn deref(&self) -> &Self::Target {
+		match self {
+			TenantConnection::Pooled(conn) => conn,
+			TenantConnection::Unit(conn) => conn,
+		}
+	}
+}
+
+impl DerefMut for TenantConnection {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		match self {
+			TenantConnection::Pooled(conn) => conn,
+			TenantConnection::Unit(conn) => conn,
+		}
+	}
+}
+
+/// Check out a connection bound to the running task's tenant scope.
+///
+/// Tenant tables carry row-level security policies keyed on the
+/// `app.tenant_id` setting, which is set on every checkout since pooled
+/// connections keep it from their previous user. Repositories of tenant data
+/// must get their connections here; a connection that never had the setting
+/// sees no tenant rows at all. Inside [`unit_of_work`] this is the unit's
+/// connection, whatever `pool` is.
+pub async fn tenant_connection(pool: &PgPool) -> Result<TenantConnection, RunError> {
+	if let Ok(unit) = UNIT.try_with(Arc::clone) {
+		return Ok(TenantConnection::Unit(unit.lock_owned().await));
+	}
+	checkout(pool).await.map(TenantConnection::Pooled)
+}
+
+async fn checkout(pool: &PgPool) -> Result<PgConnectionGuard, RunError> {
+	let mut conn = pool.get_owned().await?;
+	diesel::sql_query("SELECT set_config('app.tenant_id', $1, false)")
+		.bind::<Text, _>(tenant::current().setting())
+		.execute(&mut conn)
+		.await
+		.map_err(|e| RunError::User(PoolError::QueryError(e)))?;
+	Ok(conn)
+}
+
+/// Whether the running task is inside [`unit_of_work`]
+pub fn in_unit_of_work() -> bool {
+	UNIT.try_with(|_| ()).is_ok()
+}
+
+/// Run `work` as one transaction: every [`tenant_connection`] it takes,
+/// for any repository, is the same connection in a transaction begun on
+/// `pool`, committed if `work` succeeds and rolled back if it fails.
+///
+/// Transactions the calls open themselves become savepoints. The calls run
+/// one at a time on the connection and in the tenant scope the unit began
+/// in. A unit inside another joins it. The unit does not follow
+/// `tokio::spawn`.
+pub async fn unit_of_work<T, E, F>(pool: &PgPool, work: F) -> Result<T, E>
+where
+	F: Future<Output = Result<T, E>>,
+	E: From<diesel::result::Error> + From<RunError>,
+{
+	if in_unit_of_work() {
+		return work.await;
+	}
+
+	let mut conn = checkout(pool).await?;
+	AnsiTransactionManager::begin_transaction(&mut *conn).await?;
+	let unit = Arc::new(Mutex::new(conn));
+	let result = UNIT.scope(unit.clone(), work).await;
+
+	// Waits for a statement abandoned by `work` to be cancelled
+	let mut conn = unit.lock().await;
+	match result {
+		Ok(value) => {
+			AnsiTransactionManager::commit_transaction(&mut **conn).await?;
+			Ok(value)
+		}
+		Err(e) => {
+			// A connection left in the transaction is not returned to the pool
+			if let Err(rollback) = AnsiTransactionManager::rollback_transaction(&mut **conn).await {
+				warn!(error = %rollback, "Failed to roll back a unit of work");
+			}
+			Err(e)
+		}
+	}
+}
+
+/// Run `work` in a read-only transaction, or in a savepoint of the
+/// transaction `conn` is already in, which sees what that one wrote
+pub async fn read_only<'a, T, F>(conn: &mut AsyncPgConnection, repeatable_read: bool, work: F) -> QueryResult<T>
+where
+	T: Send + 'a,
+	F: for<'r> FnOnce(&'r mut AsyncPgConnection) -> ScopedBoxFuture<'a, 'r, QueryResult<T>> + Send + 'a,
+{
+	if AnsiTransactionManager::transaction_manager_status_mut(conn).transaction_depth()?.is_some() {
+		return conn.transaction(work).await;
+	}
+	let mut transaction = conn.build_transaction().read_only();
+	if repeatable_read {
+		transaction = transaction.repeatable_read();
+	}
+	transaction.run(work).await
+}
+
+/// A checked-out connection whose statement is cancelled when the caller
+/// stops waiting for it.
+///
+/// Dropping a query future, as tonic does with the handler of a call whose
+/// client went away or whose deadline passed, only stops listening for the
+/// result: Postgres carries on until the statement finishes or runs into
+/// `statement_timeout`. A statement abandoned inside [`Cancellable::run`] is
+/// cancelled from a background task, which holds on to the connection until
+/// the cancel request is through so it cannot hit the next user's statement.
+pub struct Cancellable {
+	conn: Option<TenantConnection>,
+	running: bool,
+}
+
+impl Cancellable {
+	pub fn new(conn: TenantConnection) -> Self {
+		Self { conn: Some(conn), running: false }
+	}
+
+	/// Run `query` on the connection, cancelling it server-side if the
+	/// returned future is dropped before it completes
+	pub async fn run<'a, T, F>(&mut self, query: F) -> T
+	where
+		F: for<'r> FnOnce(&'r mut AsyncPgConnection) -> ScopedBoxFuture<'a, 'r, T> + Send + 'a,
+	{
+		let conn = self.conn.as_deref_mut().expect("the connection is only taken on drop");
+		self.running = true;
+		let output = query(conn).await;
+		self.running = false;
+		output
+	}
+}
+
+impl Deref for Cancellable {
+	type Target = AsyncPgConnection;
+
+	fn deref(&self) -> &Self::Target {
+		self.conn.as_deref().expect("the connection is only taken on drop")
+	}
+}
+
+impl DerefMut for Cancellable {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		self.conn.as_deref_mut().expect("the connection is only taken on drop")
+	}
+}
+
+impl Drop for Cancellable {
+	fn drop(&mut self) {
+		if !self.running {
+			return;
+		}
+		let Some(conn) = self.conn.take() else {
+			return;
+		};
+		// Outside a runtime the connection goes down with it
+		let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+			return;
+		};
+		let token = conn.cancel_token();
+		runtime.spawn(async move {
+			match token.cancel_query(tokio_postgres::NoTls).await {
+				Ok(()) => info!("Cancelled the statement of an abandoned call"),
+				Err(e) => warn!(error = %e, "Failed to cancel the statement of an abandoned call"),
+			}
+			drop(conn);
+		});
+	}
+}
+
+/// Where reads that tolerate replication lag go: the replica while it is
+/// healthy, the primary otherwise.
+///
+/// A replica that fails a checkout is skipped until [`ReadPool::check`]
+/// finds it answering again, so an outage costs at most one slow checkout
+/// per health check interval.
+#[derive(Clone)]
+pub struct ReadPool {
+	primary: PgPool,
+	replica: Option<(PgPool, Arc<AtomicBool>)>,
+}
+
+impl ReadPool {
+	/// Reads go to the primary
+	pub fn primary(primary: PgPool) -> Self {
+		Self { primary, replica: None }
+	}
+
+	/// Reads go to `replica` unless it is down
+	pub fn with_replica(primary: PgPool, replica: Option<PgPool>) -> Self {
+		Self {
+			primary,
+			replica: replica.map(|pool| (pool, Arc::new(AtomicBool::new(true)))),
+		}
+	}
+
+	/// Check out a tenant connection for a read, from the replica if it is
+	/// up and from the primary if not; inside a unit of work, the unit's
+	/// connection, so the unit reads what it wrote
+	pub async fn tenant_connection(&self) -> Result<TenantConnection, RunError> {
+		if let Some((replica, healthy)) = self.replica.as_ref().filter(|_| !in_unit_of_work()) {
+			// Spelled out: `RunQueryDsl::load` would shadow the method
+			if AtomicBool::load(healthy, Ordering::Relaxed) {
+				match tenant_connection(replica).await {
+					Ok(conn) => return Ok(conn),
+					Err(e) => {
+						warn!(error = %e, "Read replica is unavailable, reading from the primary");
+						healthy.store(false, Ordering::Relaxed);
+					}
+				}
+			}
+		}
+		tenant_connection(&self.primary).await
+	}
+
+	/// Probe the replica and route reads back to it once it answers;
+	/// `None` without a replica
+	pub async fn check(&self) -> Option<PoolHealth> {
+		let (replica, healthy) = self.replica.as_ref()?;
+		let health = pool_health(replica).await;
+		if healthy.swap(health.healthy, Ordering::Relaxed) != health.healthy {
+			if health.healthy {
+				info!("Read replica is back, routing reads to it");
+			} else {
+				warn!("Read replica is unhealthy, reading from the primary");
+			}
+		}
+		Some(health)
+	}
+}
+
+/// Whether a failed query or checkout may succeed if made again: the pool
+/// timed out or could not connect, the connection dropped, or Postgres gave
+/// up on a transaction over a serialization failure or deadlock
+pub fn is_transient(error: &(dyn std::error::Error + 'static)) -> bool {
+	if let Some(e) = error.downcast_ref::<RunError>() {
+		return match e {
+			RunError::TimedOut | RunError::User(PoolError::ConnectionError(_)) => true,
+			RunError::User(PoolError::QueryError(e)) => is_transient_query(e),
+		};
+	}
+	error
+		.downcast_ref::<diesel::result::Error>()
+		.is_some_and(is_transient_query)
+}
+
+/// Whether a failed query or checkout means Postgres cannot be reached: the
+/// pool timed out or could not connect, or the connection dropped
+pub fn is_outage(error: &(dyn std::error::Error + 'static)) -> bool {
+	if let Some(e) = error.downcast_ref::<RunError>() {
+		return match e {
+			RunError::TimedOut | RunError::User(PoolError::ConnectionError(_)) => true,
+			RunError::User(PoolError::QueryError(e)) => is_lost_connection(e),
+		};
+	}
+	error
+		.downcast_ref::<diesel::result::Error>()
+		.is_some_and(is_lost_connection)
+}
+
+fn is_lost_connection(error: &diesel::result::Error) -> bool {
+	matches!(
+		error,
+		diesel::result::Error::DatabaseError(
+			DatabaseErrorKind::ClosedConnection | DatabaseErrorKind::UnableToSendCommand,
+			_
+		)
+	)
+}
+
+fn is_transient_query(error: &diesel::result::Error) -> bool {
+	match error {
+		diesel::result::Error::DatabaseError(
+			DatabaseErrorKind::SerializationFailure
+			| DatabaseErrorKind::ClosedConnection
+			| DatabaseErrorKind::UnableToSendCommand,
+			_,
+		) => true,
+		// Postgres reports deadlocks (40P01) without a kind of their own
+		diesel::result::Error::DatabaseError(DatabaseErrorKind::Unknown, info) => {
+			info.message().starts_with("deadlock detected")
+		}
+		_ => false,
+	}
+}
+
+#[derive(QueryableByName)]
+struct RowSecurity {
+	#[diesel(sql_type = Bool)]
+	bypasses: bool,
+}
+
+/// Whether the role the pool connects as skips row-level security, which
+/// leaves tenants unisolated: superusers and `BYPASSRLS` roles ignore the
+/// policies even on tables that force them.
+pub async fn bypasses_row_security(pool: &PgPool) -> anyhow::Result<bool> {
+	let mut conn = pool.get().await?;
+	let row = diesel::sql_query("SELECT rolsuper OR rolbypassrls AS bypasses FROM pg_roles WHERE rolname = current_user")
+		.get_result::<RowSecurity>(&mut conn)
+		.await?;
+	Ok(row.bypasses)
+}
@@ -0,0 +1,21 @@
+//! SQLite database for running the service on a laptop without Postgres.
+
+use diesel::connection::SimpleConnection;
+use diesel::sqlite::SqliteConnection;
+use diesel::Connection;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+
+/// The newsletter tables, kept apart from the Postgres migrations since the
+/// two dialects differ too much to share them
+pub const SQLITE_MIGRATIONS: EmbeddedMigrations = embed_migrations!("src/infrastructure/db/sqlite_migrations");
+
+/// Open the database file at `path`, or a private in-memory one for
+/// `:memory:`, and bring its schema up to date.
+pub fn open(path: &str) -> anyhow::Result<SqliteConnection> {
+	let mut conn = SqliteConnection::establish(path)?;
+	// Foreign keys are off by default and the cascades depend on them
+	conn.batch_execute("PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL; PRAGMA busy_timeout = 5000;")?;
+	conn.run_pending_migrations(SQLITE_MIGRATIONS)
+		.map_err(|e| anyhow::anyhow!(e))?;
+	Ok(conn)
+}
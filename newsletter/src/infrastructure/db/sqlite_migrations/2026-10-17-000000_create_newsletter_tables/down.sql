@@ -0,0 +1,11 @@
+DROP TABLE IF EXISTS idempotency_keys;
+DROP TABLE IF EXISTS outbox;
+DROP TABLE IF EXISTS list_metrics_daily;
+DROP TABLE IF EXISTS consents;
+DROP TABLE IF EXISTS unsubscribe_events;
+DROP TABLE IF EXISTS attribute_definitions;
+DROP TABLE IF EXISTS subscriber_topics;
+DROP TABLE IF EXISTS topics;
+DROP TABLE IF EXISTS subscriber_tags;
+DROP TABLE IF EXISTS confirmation_tokens;
+DROP TABLE IF EXISTS newsletters;
@@ -0,0 +1,141 @@
+-- The newsletter tables of the Postgres schema as they stand after its
+-- migrations, folded into one for local development. Tenants are a plain
+-- column the repository filters on, timestamps are UTC text in a fixed
+-- width so they sort as they compare, and JSON is stored as text.
+CREATE TABLE newsletters (
+    id                INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id         TEXT    NOT NULL,
+    email             TEXT    NOT NULL,
+    status            TEXT    NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'active', 'unsubscribed', 'suppressed')),
+    status_changed_at TEXT    NOT NULL,
+    created_at        TEXT    NOT NULL,
+    attributes        TEXT    NOT NULL DEFAULT '{}',
+    locale            TEXT    NULL,
+    timezone          TEXT    NULL,
+    resubscribed_at   TEXT    NULL,
+    UNIQUE (tenant_id, email)
+);
+
+CREATE UNIQUE INDEX newsletters_email_lower_idx ON newsletters (tenant_id, lower(email));
+CREATE INDEX newsletters_status_idx ON newsletters (tenant_id, status);
+
+-- Folding the case of an address renames it in the tables below too
+CREATE TABLE confirmation_tokens (
+    token_id   TEXT NOT NULL PRIMARY KEY,
+    tenant_id  TEXT NOT NULL,
+    email      TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email)
+        ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX confirmation_tokens_email_idx ON confirmation_tokens (tenant_id, email);
+CREATE INDEX confirmation_tokens_expires_at_idx ON confirmation_tokens (expires_at);
+
+CREATE TABLE subscriber_tags (
+    tenant_id  TEXT NOT NULL,
+    email      TEXT NOT NULL,
+    tag        TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (tenant_id, email, tag),
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email)
+        ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX subscriber_tags_tag_idx ON subscriber_tags (tenant_id, tag);
+
+CREATE TABLE topics (
+    key                TEXT    PRIMARY KEY,
+    name               TEXT    NOT NULL,
+    description        TEXT    NOT NULL DEFAULT '',
+    default_subscribed BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+INSERT INTO topics (key, name, description) VALUES
+    ('product_updates', 'Product updates', 'New features and changes to the product'),
+    ('promotions', 'Promotions', 'Offers and discounts'),
+    ('weekly_digest', 'Weekly digest', 'A summary of the week, sent every Monday');
+
+-- Explicit choices only; a topic without a row falls back to its default_subscribed
+CREATE TABLE subscriber_topics (
+    tenant_id  TEXT    NOT NULL,
+    email      TEXT    NOT NULL,
+    topic      TEXT    NOT NULL REFERENCES topics (key) ON DELETE CASCADE,
+    subscribed BOOLEAN NOT NULL,
+    updated_at TEXT    NOT NULL,
+    PRIMARY KEY (tenant_id, email, topic),
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email)
+        ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE attribute_definitions (
+    key         TEXT PRIMARY KEY,
+    kind        TEXT NOT NULL CHECK (kind IN ('string', 'number', 'boolean')),
+    description TEXT NOT NULL DEFAULT ''
+);
+
+INSERT INTO attribute_definitions (key, kind, description) VALUES
+    ('first_name', 'string', 'Given name used in greetings'),
+    ('last_name', 'string', 'Family name'),
+    ('locale', 'string', 'Preferred language, e.g. en-US'),
+    ('signup_source', 'string', 'Where the subscriber signed up');
+
+-- No foreign keys here or on consents: both have to outlive the subscription
+CREATE TABLE unsubscribe_events (
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id  TEXT    NOT NULL,
+    email      TEXT    NOT NULL,
+    reason     TEXT    NULL,
+    comment    TEXT    NULL,
+    created_at TEXT    NOT NULL
+);
+
+CREATE INDEX unsubscribe_events_created_at_idx ON unsubscribe_events (tenant_id, created_at);
+
+CREATE TABLE consents (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id      TEXT    NOT NULL,
+    email          TEXT    NOT NULL,
+    action         TEXT    NOT NULL CHECK (action IN ('given', 'confirmed', 'withdrawn')),
+    policy_version TEXT    NULL,
+    source         TEXT    NULL,
+    ip_address     TEXT    NULL,
+    created_at     TEXT    NOT NULL
+);
+
+CREATE INDEX consents_email_idx ON consents (tenant_id, lower(email), id);
+
+CREATE TABLE list_metrics_daily (
+    tenant_id    TEXT    NOT NULL,
+    day          TEXT    NOT NULL,
+    subscribers  INTEGER NOT NULL,
+    confirms     INTEGER NOT NULL,
+    unsubscribes INTEGER NOT NULL,
+    net_growth   INTEGER NOT NULL,
+    PRIMARY KEY (tenant_id, day)
+);
+
+CREATE TABLE outbox (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    event_type   TEXT    NOT NULL,
+    payload      TEXT    NOT NULL,
+    attempts     INTEGER NOT NULL DEFAULT 0,
+    last_error   TEXT    NULL,
+    available_at TEXT    NOT NULL,
+    created_at   TEXT    NOT NULL,
+    sent_at      TEXT    NULL
+);
+
+CREATE INDEX outbox_pending_idx ON outbox (available_at, id) WHERE sent_at IS NULL;
+
+CREATE TABLE idempotency_keys (
+    key          TEXT NOT NULL,
+    operation    TEXT NOT NULL,
+    request_hash TEXT NOT NULL,
+    -- NULL while the first request is still being processed
+    response     TEXT NULL,
+    created_at   TEXT NOT NULL,
+    PRIMARY KEY (key, operation)
+);
@@ -0,0 +1,12 @@
+CREATE TABLE email_changes (
+    token_id   TEXT NOT NULL PRIMARY KEY,
+    tenant_id  TEXT NOT NULL,
+    email      TEXT NOT NULL,
+    new_email  TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX email_changes_email_idx ON email_changes (tenant_id, email);
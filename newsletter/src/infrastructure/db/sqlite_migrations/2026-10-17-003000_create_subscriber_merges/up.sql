@@ -0,0 +1,11 @@
+CREATE TABLE subscriber_merges (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id     TEXT NOT NULL,
+    email         TEXT NOT NULL,
+    merged_email  TEXT NOT NULL,
+    merged_status TEXT NOT NULL,
+    status        TEXT NOT NULL,
+    created_at    TEXT NOT NULL
+);
+
+CREATE INDEX subscriber_merges_email_idx ON subscriber_merges (tenant_id, email);
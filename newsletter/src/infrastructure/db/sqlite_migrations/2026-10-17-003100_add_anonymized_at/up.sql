@@ -0,0 +1,5 @@
+-- When retention replaced the address of an unsubscribed subscription with a
+-- placeholder; NULL while it still holds the real one
+ALTER TABLE newsletters ADD COLUMN anonymized_at TEXT NULL;
+
+CREATE INDEX newsletters_retention_idx ON newsletters (tenant_id, status_changed_at) WHERE status = 'unsubscribed';
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS topic_confirmations;
+ALTER TABLE topics DROP COLUMN requires_confirmation;
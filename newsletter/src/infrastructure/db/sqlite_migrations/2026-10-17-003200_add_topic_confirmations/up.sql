@@ -0,0 +1,16 @@
+-- Topics such as promotions in some markets need their own double opt-in:
+-- opting in takes a confirmed link even for an active subscriber
+ALTER TABLE topics ADD COLUMN requires_confirmation BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE topic_confirmations (
+    token_id   TEXT NOT NULL PRIMARY KEY,
+    tenant_id  TEXT NOT NULL,
+    email      TEXT NOT NULL,
+    topic      TEXT NOT NULL REFERENCES topics (key) ON DELETE CASCADE,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (tenant_id, email) REFERENCES newsletters (tenant_id, email)
+        ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX topic_confirmations_email_idx ON topic_confirmations (tenant_id, email);
@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS newsletters_set_updated_at;
+DROP TRIGGER IF EXISTS newsletters_insert_updated_at;
+ALTER TABLE newsletters DROP COLUMN updated_at;
+DROP TRIGGER IF EXISTS newsletters_email_lowercase_update;
+DROP TRIGGER IF EXISTS newsletters_email_lowercase_insert;
@@ -0,0 +1,37 @@
+-- The integrity checks of the Postgres migration of the same name. SQLite
+-- cannot add a CHECK to an existing table, so triggers refuse the rows.
+-- Pending address changes do not follow a rename on their own; checking
+-- their keys at commit lets both be folded.
+PRAGMA defer_foreign_keys = ON;
+UPDATE email_changes SET email = lower(email) WHERE email <> lower(email);
+UPDATE newsletters SET email = lower(email) WHERE email <> lower(email);
+
+CREATE TRIGGER newsletters_email_lowercase_insert BEFORE INSERT ON newsletters
+    WHEN NEW.email <> lower(NEW.email)
+BEGIN
+    SELECT RAISE(ABORT, 'CHECK constraint failed: newsletters_email_lowercase_check');
+END;
+
+CREATE TRIGGER newsletters_email_lowercase_update BEFORE UPDATE OF email ON newsletters
+    WHEN NEW.email <> lower(NEW.email)
+BEGIN
+    SELECT RAISE(ABORT, 'CHECK constraint failed: newsletters_email_lowercase_check');
+END;
+
+-- When anything about the subscription last changed, in the repository's
+-- timestamp format
+ALTER TABLE newsletters ADD COLUMN updated_at TEXT NULL;
+UPDATE newsletters SET updated_at = max(created_at, status_changed_at);
+
+CREATE TRIGGER newsletters_insert_updated_at AFTER INSERT ON newsletters
+    WHEN NEW.updated_at IS NULL
+BEGIN
+    UPDATE newsletters SET updated_at = NEW.created_at WHERE id = NEW.id;
+END;
+
+-- An update that sets updated_at itself keeps its value
+CREATE TRIGGER newsletters_set_updated_at AFTER UPDATE ON newsletters
+    WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE newsletters SET updated_at = strftime('%Y-%m-%dT%H:%M:%f000Z', 'now') WHERE id = NEW.id;
+END;
@@ -0,0 +1,45 @@
+-- The history table of the Postgres migration of the same name, with
+-- triggers in place of the plpgsql function
+CREATE TABLE subscriber_events (
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id      TEXT    NOT NULL,
+    email          TEXT    NOT NULL,
+    previous_email TEXT    NULL,
+    event_type     TEXT    NOT NULL,
+    status         TEXT    NULL,
+    payload        TEXT    NOT NULL,
+    occurred_at    TEXT    NOT NULL,
+    recorded_at    TEXT    NOT NULL
+);
+
+CREATE INDEX subscriber_events_email_idx ON subscriber_events (tenant_id, email, occurred_at, id);
+CREATE INDEX subscriber_events_previous_email_idx ON subscriber_events (tenant_id, previous_email)
+    WHERE previous_email IS NOT NULL;
+
+CREATE TRIGGER subscriber_events_append_only BEFORE UPDATE ON subscriber_events
+    WHEN NEW.tenant_id IS NOT OLD.tenant_id
+        OR NEW.event_type IS NOT OLD.event_type
+        OR NEW.status IS NOT OLD.status
+        OR NEW.occurred_at IS NOT OLD.occurred_at
+        OR NEW.recorded_at IS NOT OLD.recorded_at
+BEGIN
+    SELECT RAISE(ABORT, 'subscriber_events is append-only');
+END;
+
+INSERT INTO subscriber_events (tenant_id, email, event_type, status, payload, occurred_at, recorded_at)
+SELECT
+    tenant_id,
+    email,
+    'newsletter.status_changed',
+    status,
+    json_object(
+        'type', 'newsletter.status_changed',
+        'email', email,
+        'active', json(CASE WHEN status = 'active' THEN 'true' ELSE 'false' END),
+        'status', status,
+        'occurred_at', status_changed_at,
+        'tenant', tenant_id
+    ),
+    status_changed_at,
+    strftime('%Y-%m-%dT%H:%M:%f000Z', 'now')
+FROM newsletters;
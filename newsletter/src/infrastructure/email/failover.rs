@@ -0,0 +1,293 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use rand::Rng;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use super::{EmailMessage, MailError, MailSender};
+use crate::infrastructure::rpc::rate_limit::memory::InMemoryRateLimitStore;
+use crate::infrastructure::rpc::rate_limit::{Decision, Quota, RateLimitStore};
+
+/// Which provider a message is tried with first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionStrategy {
+    /// The first provider of the list; the others only take over
+    #[default]
+    Priority,
+    /// Each message starts one provider further down the list
+    RoundRobin,
+    /// Providers are picked at random in proportion to their weight
+    Weighted,
+}
+
+/// How providers are picked and when one is taken out of rotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailoverPolicy {
+    pub strategy: SelectionStrategy,
+    /// Failed sends in a row that take a provider out of rotation
+    pub failover_after: u32,
+    /// How long a provider stays out of rotation
+    pub cooldown: Duration,
+}
+
+impl Default for FailoverPolicy {
+    fn default() -> Self {
+        Self {
+            strategy: SelectionStrategy::Priority,
+            failover_after: 5,
+            cooldown: Duration::from_secs(300),
+        }
+    }
+}
+
+/// A configured provider and the limits it is held to
+pub struct Provider {
+    /// Name used in logs, unique among the providers
+    pub name: String,
+    pub sender: Arc<dyn MailSender>,
+    /// Share of messages it starts with under [`SelectionStrategy::Weighted`];
+    /// 0 only takes over from the others
+    pub weight: u32,
+    /// Messages per second it accepts
+    pub rate: Option<Quota>,
+    /// Messages it accepts per UTC day
+    pub daily_quota: Option<u64>,
+}
+
+/// Why a provider was passed over for a message
+enum Skip {
+    /// Out of rotation after failing repeatedly
+    Unhealthy,
+    /// The day's quota is used up
+    OverQuota,
+    /// Over its rate; a token frees up after the duration
+    Limited(Duration),
+}
+
+#[derive(Debug, Default)]
+struct Usage {
+    /// UTC day `sent` counts
+    day: Option<NaiveDate>,
+    sent: u64,
+    /// Failed sends since the last one that went through
+    failures: u32,
+    disabled_until: Option<Instant>,
+}
+
+struct Slot {
+    provider: Provider,
+    usage: Mutex<Usage>,
+}
+
+impl Slot {
+    fn usage(&self) -> std::sync::MutexGuard<'_, Usage> {
+        self.usage.lock().expect("email provider usage lock poisoned")
+    }
+
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.usage().disabled_until.is_none_or(|until| now >= until)
+    }
+
+    /// Count a send against the day's quota
+    fn reserve(&self, today: NaiveDate, now: Instant) -> Result<(), Skip> {
+        let mut usage = self.usage();
+        if usage.disabled_until.is_some_and(|until| now < until) {
+            return Err(Skip::Unhealthy);
+        }
+        if usage.day != Some(today) {
+            usage.day = Some(today);
+            usage.sent = 0;
+        }
+        if self.provider.daily_quota.is_some_and(|quota| usage.sent >= quota) {
+            return Err(Skip::OverQuota);
+        }
+        usage.sent += 1;
+        Ok(())
+    }
+
+    /// Give back a send that did not go out
+    fn refund(&self, today: NaiveDate) {
+        let mut usage = self.usage();
+        if usage.day == Some(today) {
+            usage.sent = usage.sent.saturating_sub(1);
+        }
+    }
+
+    fn succeeded(&self) {
+        self.usage().failures = 0;
+    }
+
+    fn failed(&self, policy: &FailoverPolicy, error: &MailError) {
+        let mut usage = self.usage();
+        usage.failures += 1;
+        if usage.failures >= policy.failover_after {
+            usage.failures = 0;
+            usage.disabled_until = Some(Instant::now() + policy.cooldown);
+            warn!(provider = %self.provider.name, failures = policy.failover_after, cooldown_secs = policy.cooldown.as_secs(), error = %error, "Email provider taken out of rotation");
+        }
+    }
+}
+
+/// Sends through the first of several providers that is available.
+///
+/// Providers over their rate or daily quota, or out of rotation after
+/// `failover_after` failed sends in a row, are passed over. A message that
+/// fails with one provider, transiently or for good, is tried with the next.
+/// A rejected message, such as one to an unknown recipient, is returned as is
+/// and says nothing about the provider's health. Rates, quotas and health are
+/// tracked in process memory, so they apply per instance.
+pub struct FailoverMailSender {
+    slots: Vec<Slot>,
+    policy: FailoverPolicy,
+    rates: InMemoryRateLimitStore,
+    /// First provider of the next message under round-robin
+    next: AtomicUsize,
+}
+
+impl FailoverMailSender {
+    pub fn new(providers: Vec<Provider>, policy: FailoverPolicy) -> Self {
+        Self {
+            slots: providers
+                .into_iter()
+                .map(|provider| Slot {
+                    provider,
+                    usage: Mutex::new(Usage::default()),
+                })
+                .collect(),
+            policy,
+            rates: InMemoryRateLimitStore::new(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Indexes of the providers in the order a message tries them
+    fn order(&self) -> Vec<usize> {
+        let count = self.slots.len();
+        if count == 0 {
+            return Vec::new();
+        }
+
+        match self.policy.strategy {
+            SelectionStrategy::Priority => (0..count).collect(),
+            SelectionStrategy::RoundRobin => {
+                let first = self.next.fetch_add(1, Ordering::Relaxed) % count;
+                (first..count).chain(0..first).collect()
+            }
+            SelectionStrategy::Weighted => {
+                let first = self.pick_weighted();
+                std::iter::once(first).chain((0..count).filter(|&i| i != first)).collect()
+            }
+        }
+    }
+
+    /// A provider in rotation picked in proportion to its weight; the first
+    /// one when none has weight left
+    fn pick_weighted(&self) -> usize {
+        let now = Instant::now();
+        let weights: Vec<u32> = self
+            .slots
+            .iter()
+            .map(|slot| if slot.is_healthy(now) { slot.provider.weight } else { 0 })
+            .collect();
+        let total: u64 = weights.iter().map(|&w| u64::from(w)).sum();
+        if total == 0 {
+            return 0;
+        }
+
+        let mut ticket = rand::thread_rng().gen_range(0..total);
+        for (index, &weight) in weights.iter().enumerate() {
+            if ticket < u64::from(weight) {
+                return index;
+            }
+            ticket -= u64::from(weight);
+        }
+        0
+    }
+
+    /// Take a provider's quota and rate for one message
+    async fn admit(&self, slot: &Slot, today: NaiveDate) -> Result<(), Skip> {
+        slot.reserve(today, Instant::now())?;
+        let Some(rate) = slot.provider.rate else {
+            return Ok(());
+        };
+        match self.rates.acquire(&slot.provider.name, rate).await {
+            Ok(Decision::Limited { retry_after }) => {
+                slot.refund(today);
+                Err(Skip::Limited(retry_after))
+            }
+            // The in-memory store never fails
+            Ok(Decision::Allowed) | Err(_) => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl MailSender for FailoverMailSender {
+    fn provider(&self) -> &'static str {
+        "failover"
+    }
+
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailError> {
+        let mut failed = vec![false; self.slots.len()];
+        let mut last_error = None;
+
+        loop {
+            // Shortest wait for a rate-limited provider the message has not failed with
+            let mut wait: Option<Duration> = None;
+
+            for index in self.order() {
+                if failed[index] {
+                    continue;
+                }
+                let slot = &self.slots[index];
+                let today = Utc::now().date_naive();
+                match self.admit(slot, today).await {
+                    Ok(()) => {}
+                    Err(Skip::Limited(after)) => {
+                        wait = Some(wait.map_or(after, |shortest| shortest.min(after)));
+                        continue;
+                    }
+                    Err(Skip::Unhealthy | Skip::OverQuota) => continue,
+                }
+
+                match slot.provider.sender.send(message).await {
+                    Ok(()) => {
+                        slot.succeeded();
+                        if last_error.is_some() {
+                            info!(provider = %slot.provider.name, "Email sent after failing over");
+                        }
+                        return Ok(());
+                    }
+                    Err(e @ MailError::Rejected(_)) => {
+                        slot.refund(today);
+                        return Err(e);
+                    }
+                    Err(e) => {
+                        slot.refund(today);
+                        slot.failed(&self.policy, &e);
+                        warn!(provider = %slot.provider.name, error = %e, "Email provider failed, trying the next one");
+                        failed[index] = true;
+                        // A transient failure lets the caller try again later
+                        if !matches!(last_error, Some(MailError::Transient(_))) {
+                            last_error = Some(e);
+                        }
+                    }
+                }
+            }
+
+            match wait {
+                Some(after) => tokio::time::sleep(after).await,
+                None => {
+                    return Err(last_error.unwrap_or_else(|| {
+                        MailError::Transient(anyhow::anyhow!("no email provider is available"))
+                    }))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use async_trait::async_trait;
+use tracing::info;
+
+use super::{EmailMessage, MailError, MailSender};
+use crate::infrastructure::logging;
+
+/// Development sender that only logs outgoing messages
+#[derive(Debug, Clone, Default)]
+pub struct LogMailSender;
+
+#[async_trait]
+impl MailSender for LogMailSender {
+    fn provider(&self) -> &'static str {
+        "log"
+    }
+
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailError> {
+        info!(to = %logging::email(&message.to), subject = %message.subject, bytes = message.html.len(), "Email not delivered (log provider)");
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+use std::{env, fmt, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::domain::campaign::sending_domain::SenderIdentity;
+use crate::infrastructure::config::EmailSettings;
+
+pub mod failover;
+pub mod log;
+#[cfg(feature = "ses")]
+pub mod ses;
+pub mod smtp;
+
+/// A single outgoing email
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub html: String,
+    pub text: Option<String>,
+    /// Extra header fields, such as `List-Unsubscribe`, in order
+    pub headers: Vec<(String, String)>,
+    /// The tenant's verified sending domain, sent from instead of `EMAIL_FROM`
+    /// and signed for where the provider leaves signing to us
+    pub sender: Option<SenderIdentity>,
+}
+
+/// Failure to hand a message over to the provider
+#[derive(Debug)]
+pub enum MailError {
+    /// Worth retrying: timeouts, throttling, 4xx SMTP replies
+    Transient(anyhow::Error),
+    /// The provider cannot send at all: bad credentials, a suspended account,
+    /// 5xx replies other than a refused recipient. Retrying will not help,
+    /// another provider may
+    Permanent(anyhow::Error),
+    /// The message itself was refused: an unknown or malformed recipient, an
+    /// invalid header. No provider will take it
+    Rejected(anyhow::Error),
+}
+
+impl fmt::Display for MailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MailError::Transient(e) => write!(f, "transient mail error: {e}"),
+            MailError::Permanent(e) => write!(f, "permanent mail error: {e}"),
+            MailError::Rejected(e) => write!(f, "message rejected: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for MailError {}
+
+/// Delivers emails through a provider
+#[async_trait]
+pub trait MailSender: Send + Sync {
+    /// Short provider name used in logs
+    fn provider(&self) -> &'static str;
+
+    /// Send a message once
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailError>;
+}
+
+/// Exponential backoff settings for [`RetryingMailSender`]
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// Retries transient failures of the wrapped sender with exponential backoff
+pub struct RetryingMailSender {
+    inner: Arc<dyn MailSender>,
+    policy: RetryPolicy,
+}
+
+impl RetryingMailSender {
+    pub fn new(inner: Arc<dyn MailSender>, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+}
+
+#[async_trait]
+impl MailSender for RetryingMailSender {
+    fn provider(&self) -> &'static str {
+        self.inner.provider()
+    }
+
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailError> {
+        let mut attempt = 1;
+        loop {
+            match self.inner.send(message).await {
+                Ok(()) => {
+                    info!(provider = self.provider(), attempt = attempt, "Email sent");
+                    return Ok(());
+                }
+                Err(MailError::Transient(e)) if attempt < self.policy.max_attempts => {
+                    let delay = self.policy.delay(attempt);
+                    warn!(provider = self.provider(), attempt = attempt, delay_ms = delay.as_millis() as u64, error = %e, "Transient mail error, retrying");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Kind of a provider listed under `email.providers`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    Smtp,
+    Ses,
+    Log,
+}
+
+/// Build the configured sender from the environment.
+///
+/// `EMAIL_PROVIDER` selects `smtp`, `ses` or `log` (default). Retries are
+/// tuned with `EMAIL_MAX_ATTEMPTS` and `EMAIL_RETRY_BASE_MS`.
+pub async fn sender_from_env() -> anyhow::Result<Arc<dyn MailSender>> {
+    let provider = env::var("EMAIL_PROVIDER").unwrap_or_else(|_| "log".to_string());
+
+    let sender: Arc<dyn MailSender> = match provider.as_str() {
+        "smtp" => Arc::new(smtp::SmtpMailSender::from_env()?),
+        #[cfg(feature = "ses")]
+        "ses" => Arc::new(ses::SesMailSender::from_env().await?),
+        #[cfg(not(feature = "ses"))]
+        "ses" => anyhow::bail!("EMAIL_PROVIDER=ses requires the `ses` feature"),
+        "log" => Arc::new(log::LogMailSender),
+        other => anyhow::bail!("unknown EMAIL_PROVIDER: {other}"),
+    };
+
+    let policy = retry_policy_from_env();
+    info!(provider = sender.provider(), max_attempts = policy.max_attempts, "Configured mail sender");
+
+    Ok(Arc::new(RetryingMailSender::new(sender, policy)))
+}
+
+/// Build a sender failing over between the providers of `email.providers`,
+/// each retried as tuned by `EMAIL_MAX_ATTEMPTS` and `EMAIL_RETRY_BASE_MS`;
+/// the one of [`sender_from_env`] when none are listed
+pub async fn sender_from_settings(settings: &EmailSettings) -> anyhow::Result<Arc<dyn MailSender>> {
+    if settings.providers.is_empty() {
+        return sender_from_env().await;
+    }
+
+    let policy = retry_policy_from_env();
+    let mut providers = Vec::with_capacity(settings.providers.len());
+    for provider in &settings.providers {
+        let sender: Arc<dyn MailSender> = match provider.kind {
+            ProviderKind::Smtp => Arc::new(smtp::SmtpMailSender::new(&provider.smtp()?)?),
+            #[cfg(feature = "ses")]
+            ProviderKind::Ses => Arc::new(ses::SesMailSender::new(provider.configuration_set.clone()).await?),
+            #[cfg(not(feature = "ses"))]
+            ProviderKind::Ses => anyhow::bail!("email provider {} requires the `ses` feature", provider.name),
+            ProviderKind::Log => Arc::new(log::LogMailSender),
+        };
+        providers.push(failover::Provider {
+            name: provider.name.clone(),
+            sender: Arc::new(RetryingMailSender::new(sender, policy)),
+            weight: provider.weight,
+            rate: provider.rate(),
+            daily_quota: provider.daily_quota,
+        });
+    }
+
+    let names: Vec<&str> = settings.providers.iter().map(|p| p.name.as_str()).collect();
+    info!(providers = ?names, strategy = ?settings.strategy, max_attempts = policy.max_attempts, "Configured failover mail sender");
+
+    Ok(Arc::new(failover::FailoverMailSender::new(providers, settings.failover_policy())))
+}
+
+/// Retries of each send, from `EMAIL_MAX_ATTEMPTS` and `EMAIL_RETRY_BASE_MS`
+fn retry_policy_from_env() -> RetryPolicy {
+    let defaults = RetryPolicy::default();
+    RetryPolicy {
+        max_attempts: env::var("EMAIL_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(defaults.max_attempts),
+        base_delay: env::var("EMAIL_RETRY_BASE_MS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.base_delay),
+        ..defaults
+    }
+}
+
+/// Sender address shared by all providers
+pub(crate) fn from_address() -> anyhow::Result<String> {
+    env::var("EMAIL_FROM").map_err(|e| anyhow::anyhow!("EMAIL_FROM not set: {e}"))
+}
+
+/// Domain of `EMAIL_FROM`, when it is set
+pub fn from_domain() -> Option<String> {
+    let address = from_address().ok()?;
+    // `Name <user@domain>` as well as a bare address
+    let address = address.trim().trim_end_matches('>');
+    address.rsplit_once('@').map(|(_, domain)| domain.to_ascii_lowercase())
+}
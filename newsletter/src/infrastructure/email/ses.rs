@@ -0,0 +1,110 @@
+use std::env;
+
+use async_trait::async_trait;
+use aws_sdk_sesv2::types::{Body, Content, Destination, EmailContent, Message, MessageHeader};
+use aws_sdk_sesv2::Client;
+
+use super::{from_address, EmailMessage, MailError, MailSender};
+
+/// AWS SES (v2 API) sender; credentials and region come from the standard AWS environment.
+///
+/// SES signs with the Easy DKIM keys of identities verified with it, so a
+/// tenant's sending domain only sets the From address here.
+pub struct SesMailSender {
+    client: Client,
+    from: String,
+    configuration_set: Option<String>,
+}
+
+impl SesMailSender {
+    /// Configure from the AWS environment plus `EMAIL_FROM` and optional `SES_CONFIGURATION_SET`
+    pub async fn from_env() -> anyhow::Result<Self> {
+        Self::new(env::var("SES_CONFIGURATION_SET").ok()).await
+    }
+
+    /// Configure from the AWS environment plus `EMAIL_FROM`
+    pub async fn new(configuration_set: Option<String>) -> anyhow::Result<Self> {
+        let config = aws_config::load_from_env().await;
+
+        Ok(Self {
+            client: Client::new(&config),
+            from: from_address()?,
+            configuration_set,
+        })
+    }
+
+    fn content(data: &str) -> Result<Content, MailError> {
+        Content::builder()
+            .data(data)
+            .charset("UTF-8")
+            .build()
+            .map_err(|e| MailError::Rejected(e.into()))
+    }
+}
+
+#[async_trait]
+impl MailSender for SesMailSender {
+    fn provider(&self) -> &'static str {
+        "ses"
+    }
+
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailError> {
+        let mut body = Body::builder().html(Self::content(&message.html)?);
+        if let Some(text) = &message.text {
+            body = body.text(Self::content(text)?);
+        }
+
+        let headers = message
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                MessageHeader::builder()
+                    .name(name)
+                    .value(value)
+                    .build()
+                    .map_err(|e| MailError::Rejected(e.into()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let content = EmailContent::builder()
+            .simple(
+                Message::builder()
+                    .subject(Self::content(&message.subject)?)
+                    .body(body.build())
+                    .set_headers(Some(headers).filter(|h| !h.is_empty()))
+                    .build(),
+            )
+            .build();
+
+        let result = self
+            .client
+            .send_email()
+            .from_email_address(message.sender.as_ref().map_or(&self.from, |identity| &identity.from))
+            .destination(Destination::builder().to_addresses(&message.to).build())
+            .content(content)
+            .set_configuration_set_name(self.configuration_set.clone())
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                let service_error = e.as_service_error();
+                let rejected = service_error.is_some_and(|err| err.is_message_rejected() || err.is_bad_request_exception());
+                let permanent = service_error.is_some_and(|err| {
+                    err.is_account_suspended_exception()
+                        || err.is_mail_from_domain_not_verified_exception()
+                        || err.is_not_found_exception()
+                });
+
+                if rejected {
+                    Err(MailError::Rejected(e.into()))
+                } else if permanent {
+                    Err(MailError::Permanent(e.into()))
+                } else {
+                    Err(MailError::Transient(e.into()))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::env;
+
+use async_trait::async_trait;
+use lettre::message::dkim::{DkimConfig, DkimSigningAlgorithm, DkimSigningKey};
+use lettre::message::header::{ContentType, HeaderName, HeaderValue};
+use lettre::message::{Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::response::{Category, Detail};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use super::{from_address, EmailMessage, MailError, MailSender};
+
+/// SMTP sender backed by lettre's pooled async transport; messages from a
+/// tenant's sending domain are DKIM-signed before they are handed over
+pub struct SmtpMailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+/// Connection settings of an SMTP relay
+#[derive(Clone, PartialEq, Eq)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// The port of the TLS mode when unset
+    pub port: Option<u16>,
+    /// `starttls`, `tls` or `none`
+    pub tls: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl SmtpConfig {
+    /// Read `SMTP_HOST`, `SMTP_PORT`, `SMTP_USERNAME`, `SMTP_PASSWORD` and
+    /// `SMTP_TLS` (`starttls` by default)
+    pub fn from_env() -> anyhow::Result<Self> {
+        Ok(Self {
+            host: env::var("SMTP_HOST").map_err(|e| anyhow::anyhow!("SMTP_HOST not set: {e}"))?,
+            port: env::var("SMTP_PORT").ok().and_then(|s| s.parse().ok()),
+            tls: env::var("SMTP_TLS").unwrap_or_else(|_| "starttls".to_string()),
+            username: env::var("SMTP_USERNAME").ok(),
+            password: env::var("SMTP_PASSWORD").ok(),
+        })
+    }
+}
+
+impl SmtpMailSender {
+    /// Configure from the `SMTP_*` variables
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::new(&SmtpConfig::from_env()?)
+    }
+
+    pub fn new(config: &SmtpConfig) -> anyhow::Result<Self> {
+        let host = &config.host;
+        let mut builder = match config.tls.as_str() {
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            other => anyhow::bail!("unknown SMTP TLS mode: {other}"),
+        };
+
+        if let Some(port) = config.port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: from_address()?.parse()?,
+        })
+    }
+
+    fn build_message(&self, message: &EmailMessage) -> Result<Message, MailError> {
+        let to: Mailbox = message
+            .to
+            .parse()
+            .map_err(|e: lettre::address::AddressError| MailError::Rejected(e.into()))?;
+
+        let html = SinglePart::builder()
+            .header(ContentType::TEXT_HTML)
+            .body(message.html.clone());
+        let body = match &message.text {
+            Some(text) => MultiPart::alternative()
+                .singlepart(
+                    SinglePart::builder()
+                        .header(ContentType::TEXT_PLAIN)
+                        .body(text.clone()),
+                )
+                .singlepart(html),
+            None => MultiPart::mixed().singlepart(html),
+        };
+
+        let from = match &message.sender {
+            Some(identity) => identity
+                .from
+                .parse()
+                .map_err(|e: lettre::address::AddressError| MailError::Rejected(e.into()))?,
+            None => self.from.clone(),
+        };
+
+        let mut builder = Message::builder().from(from).to(to).subject(&message.subject);
+        for (name, value) in &message.headers {
+            let name = HeaderName::new_from_ascii(name.clone()).map_err(|e| MailError::Rejected(e.into()))?;
+            builder = builder.raw_header(HeaderValue::new(name, value.clone()));
+        }
+
+        let mut email = builder
+            .multipart(body)
+            .map_err(|e| MailError::Rejected(e.into()))?;
+
+        if let Some(identity) = &message.sender {
+            let key = DkimSigningKey::new(&identity.private_key, DkimSigningAlgorithm::Rsa)
+                .map_err(|e| MailError::Rejected(anyhow::anyhow!("invalid DKIM key for {}: {e}", identity.domain)))?;
+            email.sign(&DkimConfig::default_config(
+                identity.selector.clone(),
+                identity.domain.clone(),
+                key,
+            ));
+        }
+        Ok(email)
+    }
+}
+
+#[async_trait]
+impl MailSender for SmtpMailSender {
+    fn provider(&self) -> &'static str {
+        "smtp"
+    }
+
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailError> {
+        let email = self.build_message(message)?;
+
+        match self.transport.send(email).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.is_permanent() && refuses_recipient(&e) => Err(MailError::Rejected(e.into())),
+            Err(e) if e.is_permanent() => Err(MailError::Permanent(e.into())),
+            Err(e) => Err(MailError::Transient(e.into())),
+        }
+    }
+}
+
+/// 550-553 replies refuse the recipient rather than the sender: no such
+/// mailbox, not local, full, or a name that is not allowed
+fn refuses_recipient(error: &lettre::transport::smtp::Error) -> bool {
+    error.status().is_some_and(|code| {
+        code.category == Category::MailSystem
+            && matches!(code.detail, Detail::Zero | Detail::One | Detail::Two | Detail::Three)
+    })
+}
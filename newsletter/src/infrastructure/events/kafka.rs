@@ -0,0 +1,156 @@
+use std::{env, time::Duration};
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::message::{Header, Message, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use tracing::warn;
+
+use super::{ClickConsumer, EventPublisher, DEFAULT_CLICK_EVENTS_GROUP, DEFAULT_CLICK_EVENTS_TOPIC};
+use crate::domain::newsletter::SubscriptionEvent;
+use crate::service::campaign::clicks::ClickRecorder;
+
+/// Pause before retrying a click that could not be stored, or a failed poll
+const CLICK_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Publishes events as JSON to a Kafka topic.
+///
+/// Records are keyed by email so all events of one subscriber land in the
+/// same partition and keep their order; the event type is also sent as the
+/// `event_type` header for consumers that filter without decoding.
+pub struct KafkaEventPublisher {
+    producer: FutureProducer,
+    topic: String,
+    send_timeout: Duration,
+}
+
+impl KafkaEventPublisher {
+    /// Configure from `KAFKA_BROKERS`, `KAFKA_TOPIC` (default `newsletter.events`)
+    /// and `KAFKA_SEND_TIMEOUT_MS` (default 5000).
+    pub fn from_env() -> anyhow::Result<Self> {
+        let brokers = env::var("KAFKA_BROKERS")
+            .map_err(|e| anyhow::anyhow!("KAFKA_BROKERS not set: {e}"))?;
+
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", &brokers)
+            .set("enable.idempotence", "true")
+            .set("acks", "all")
+            .create()
+            .context("failed to create Kafka producer")?;
+
+        Ok(Self {
+            producer,
+            topic: env::var("KAFKA_TOPIC").unwrap_or_else(|_| "newsletter.events".to_string()),
+            send_timeout: Duration::from_millis(
+                env::var("KAFKA_SEND_TIMEOUT_MS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(5000),
+            ),
+        })
+    }
+}
+
+#[async_trait]
+impl EventPublisher for KafkaEventPublisher {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    async fn publish(&self, event: &SubscriptionEvent) -> anyhow::Result<()> {
+        let payload = serde_json::to_vec(event)?;
+        let headers = OwnedHeaders::new().insert(Header {
+            key: "event_type",
+            value: Some(event.kind.as_str()),
+        });
+
+        let record = FutureRecord::to(&self.topic)
+            .key(&event.email)
+            .payload(&payload)
+            .headers(headers);
+
+        self.producer
+            .send(record, self.send_timeout)
+            .await
+            .map_err(|(e, _)| anyhow::anyhow!("failed to publish {} to Kafka: {e}", event.kind))?;
+
+        Ok(())
+    }
+}
+
+/// Reads click events from a Kafka topic as part of a consumer group.
+///
+/// Offsets are stored only after a click is recorded; one that cannot be
+/// stored is retried in place, which holds up its partition meanwhile.
+pub struct KafkaClickConsumer {
+    consumer: StreamConsumer,
+    topic: String,
+}
+
+impl KafkaClickConsumer {
+    /// Configure from `KAFKA_BROKERS`, `CLICK_EVENTS_TOPIC` (default
+    /// `shortlink.link.clicked`) and `CLICK_EVENTS_GROUP` (default
+    /// `marketing-newsletter`). A new group starts at the latest clicks.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let brokers = env::var("KAFKA_BROKERS")
+            .map_err(|e| anyhow::anyhow!("KAFKA_BROKERS not set: {e}"))?;
+        let topic = env::var("CLICK_EVENTS_TOPIC").unwrap_or_else(|_| DEFAULT_CLICK_EVENTS_TOPIC.to_string());
+        let group = env::var("CLICK_EVENTS_GROUP").unwrap_or_else(|_| DEFAULT_CLICK_EVENTS_GROUP.to_string());
+
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &brokers)
+            .set("group.id", &group)
+            .set("enable.auto.commit", "true")
+            .set("enable.auto.offset.store", "false")
+            .set("auto.offset.reset", "latest")
+            .create()
+            .context("failed to create Kafka consumer")?;
+        consumer
+            .subscribe(&[topic.as_str()])
+            .with_context(|| format!("failed to subscribe to Kafka topic {topic}"))?;
+
+        Ok(Self { consumer, topic })
+    }
+}
+
+#[async_trait]
+impl ClickConsumer for KafkaClickConsumer {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    async fn run(&self, recorder: Arc<ClickRecorder>, mut stopped: BoxFuture<'static, ()>) {
+        loop {
+            let message = tokio::select! {
+                _ = &mut stopped => return,
+                message = self.consumer.recv() => message,
+            };
+            let message = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!(topic = %self.topic, error = %e, "Failed to read click event");
+                    tokio::select! {
+                        _ = &mut stopped => return,
+                        _ = tokio::time::sleep(CLICK_RETRY_DELAY) => continue,
+                    }
+                }
+            };
+
+            while let Err(e) = recorder.handle(message.payload().unwrap_or_default()).await {
+                warn!(topic = %self.topic, offset = message.offset(), error = %e, "Failed to record click, retrying");
+                tokio::select! {
+                    _ = &mut stopped => return,
+                    _ = tokio::time::sleep(CLICK_RETRY_DELAY) => {}
+                }
+            }
+            if let Err(e) = self.consumer.store_offset_from_message(&message) {
+                warn!(topic = %self.topic, offset = message.offset(), error = %e, "Failed to store click event offset");
+            }
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use tracing::info;
+
+use super::EventPublisher;
+use crate::domain::newsletter::SubscriptionEvent;
+use crate::infrastructure::logging;
+
+/// Development publisher that only logs events
+#[derive(Debug, Clone, Default)]
+pub struct LogEventPublisher;
+
+#[async_trait]
+impl EventPublisher for LogEventPublisher {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    async fn publish(&self, event: &SubscriptionEvent) -> anyhow::Result<()> {
+        info!(event_type = %event.kind, email = %logging::email(&event.email), status = ?event.status, "Event not published (log publisher)");
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use std::{env, sync::Arc};
+
+use async_trait::async_trait;
+use futures::future::{join_all, BoxFuture};
+use tracing::info;
+
+use crate::domain::newsletter::SubscriptionEvent;
+use crate::service::campaign::clicks::ClickRecorder;
+
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod log;
+#[cfg(feature = "nats")]
+pub mod nats;
+
+/// Announces subscription lifecycle changes to downstream consumers
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    /// Short publisher name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Publish a single event
+    async fn publish(&self, event: &SubscriptionEvent) -> anyhow::Result<()>;
+}
+
+/// Forwards every event to all wrapped publishers
+pub struct FanoutPublisher {
+    publishers: Vec<Arc<dyn EventPublisher>>,
+}
+
+impl FanoutPublisher {
+    pub fn new(publishers: Vec<Arc<dyn EventPublisher>>) -> Self {
+        Self { publishers }
+    }
+}
+
+#[async_trait]
+impl EventPublisher for FanoutPublisher {
+    fn name(&self) -> &'static str {
+        "fanout"
+    }
+
+    /// One failing sink does not keep the event from the others; the errors
+    /// are reported together afterwards.
+    async fn publish(&self, event: &SubscriptionEvent) -> anyhow::Result<()> {
+        let results = join_all(self.publishers.iter().map(|p| p.publish(event))).await;
+
+        let errors: Vec<String> = self
+            .publishers
+            .iter()
+            .zip(results)
+            .filter_map(|(p, result)| result.err().map(|e| format!("{}: {e}", p.name())))
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(errors.join("; ")))
+        }
+    }
+}
+
+/// Build the configured publisher from the environment.
+///
+/// `EVENT_PUBLISHER` selects `kafka`, `nats` or `log` (default).
+pub async fn publisher_from_env() -> anyhow::Result<Arc<dyn EventPublisher>> {
+    let name = env::var("EVENT_PUBLISHER").unwrap_or_else(|_| "log".to_string());
+
+    let publisher: Arc<dyn EventPublisher> = match name.as_str() {
+        #[cfg(feature = "kafka")]
+        "kafka" => Arc::new(kafka::KafkaEventPublisher::from_env()?),
+        #[cfg(not(feature = "kafka"))]
+        "kafka" => anyhow::bail!("EVENT_PUBLISHER=kafka requires the `kafka` feature"),
+        #[cfg(feature = "nats")]
+        "nats" => Arc::new(nats::NatsEventPublisher::from_env().await?),
+        #[cfg(not(feature = "nats"))]
+        "nats" => anyhow::bail!("EVENT_PUBLISHER=nats requires the `nats` feature"),
+        "log" => Arc::new(log::LogEventPublisher),
+        other => anyhow::bail!("unknown EVENT_PUBLISHER: {other}"),
+    };
+
+    info!(publisher = publisher.name(), "Configured event publisher");
+
+    Ok(publisher)
+}
+
+/// Feeds the shortlink platform's click events to a [`ClickRecorder`]
+#[async_trait]
+pub trait ClickConsumer: Send + Sync {
+    /// Short consumer name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Consume events until `stopped` resolves. An event is acknowledged
+    /// only once recorded, so clicks are not lost across restarts.
+    async fn run(&self, recorder: Arc<ClickRecorder>, stopped: BoxFuture<'static, ()>);
+}
+
+/// Default topic or subject of the shortlink service's click events
+pub const DEFAULT_CLICK_EVENTS_TOPIC: &str = "shortlink.link.clicked";
+
+/// Default consumer group, or durable consumer name on NATS
+pub const DEFAULT_CLICK_EVENTS_GROUP: &str = "marketing-newsletter";
+
+/// Build the configured click consumer from the environment.
+///
+/// `CLICK_EVENTS_SOURCE` selects `kafka` or `nats`; unset or empty consumes
+/// nothing.
+pub async fn click_consumer_from_env() -> anyhow::Result<Option<Arc<dyn ClickConsumer>>> {
+    let name = env::var("CLICK_EVENTS_SOURCE").unwrap_or_default();
+
+    match name.as_str() {
+        "" => Ok(None),
+        #[cfg(feature = "kafka")]
+        "kafka" => Ok(Some(Arc::new(kafka::KafkaClickConsumer::from_env()?))),
+        #[cfg(not(feature = "kafka"))]
+        "kafka" => anyhow::bail!("CLICK_EVENTS_SOURCE=kafka requires the `kafka` feature"),
+        #[cfg(feature = "nats")]
+        "nats" => Ok(Some(Arc::new(nats::NatsClickConsumer::from_env().await?))),
+        #[cfg(not(feature = "nats"))]
+        "nats" => anyhow::bail!("CLICK_EVENTS_SOURCE=nats requires the `nats` feature"),
+        other => anyhow::bail!("unknown CLICK_EVENTS_SOURCE: {other}"),
+    }
+}
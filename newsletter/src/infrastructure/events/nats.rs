@@ -0,0 +1,199 @@
+use std::{env, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use async_nats::header::{HeaderMap, NATS_MESSAGE_ID};
+use async_nats::jetstream::consumer::{pull, PullConsumer};
+use async_nats::jetstream::{self, stream, AckKind};
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use tracing::{debug, info, warn};
+
+use super::{ClickConsumer, EventPublisher, DEFAULT_CLICK_EVENTS_GROUP, DEFAULT_CLICK_EVENTS_TOPIC};
+use crate::domain::newsletter::SubscriptionEvent;
+use crate::domain::tenant::DEFAULT_TENANT;
+use crate::service::campaign::clicks::ClickRecorder;
+
+/// How long JetStream waits before redelivering a click that could not be
+/// stored, and the pause after a failed pull
+const CLICK_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// JetStream context on `NATS_URL`
+async fn connect() -> anyhow::Result<jetstream::Context> {
+    let url = env::var("NATS_URL").map_err(|e| anyhow::anyhow!("NATS_URL not set: {e}"))?;
+    let client = async_nats::connect(&url)
+        .await
+        .with_context(|| format!("failed to connect to NATS at {url}"))?;
+    Ok(jetstream::new(client))
+}
+
+/// Publishes events as JSON to NATS JetStream.
+///
+/// Subjects follow `<prefix>.<tenant>.<event type>`, for example
+/// `marketing.default.newsletter.subscribed`, so consumers can filter with
+/// `marketing.*.newsletter.>` or narrow to one tenant. A publish only counts
+/// once the stream acknowledged it; the `Nats-Msg-Id` header lets JetStream
+/// drop the copies an outbox retry sends within its duplicate window.
+pub struct NatsEventPublisher {
+    jetstream: jetstream::Context,
+    prefix: String,
+}
+
+impl NatsEventPublisher {
+    /// Configure from `NATS_URL`, `NATS_SUBJECT_PREFIX` (default `marketing`)
+    /// and `NATS_ACK_TIMEOUT_MS` (default 5000). When `NATS_STREAM` is set,
+    /// a stream of that name capturing `<prefix>.>` is created if missing.
+    pub async fn from_env() -> anyhow::Result<Self> {
+        let prefix = env::var("NATS_SUBJECT_PREFIX").unwrap_or_else(|_| "marketing".to_string());
+        let ack_timeout = Duration::from_millis(
+            env::var("NATS_ACK_TIMEOUT_MS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5000),
+        );
+
+        let mut jetstream = connect().await?;
+        jetstream.set_timeout(ack_timeout);
+
+        if let Some(name) = env::var("NATS_STREAM").ok().filter(|name| !name.is_empty()) {
+            jetstream
+                .get_or_create_stream(stream::Config {
+                    name: name.clone(),
+                    subjects: vec![format!("{prefix}.>")],
+                    ..Default::default()
+                })
+                .await
+                .with_context(|| format!("failed to create JetStream stream {name}"))?;
+            info!(stream = %name, subjects = %format!("{prefix}.>"), "JetStream stream ready");
+        }
+
+        Ok(Self { jetstream, prefix })
+    }
+
+    fn subject(&self, event: &SubscriptionEvent) -> String {
+        let tenant = event.tenant.as_ref().map_or(DEFAULT_TENANT, |tenant| tenant.as_str());
+        format!("{}.{tenant}.{}", self.prefix, event.kind)
+    }
+}
+
+#[async_trait]
+impl EventPublisher for NatsEventPublisher {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    async fn publish(&self, event: &SubscriptionEvent) -> anyhow::Result<()> {
+        let subject = self.subject(event);
+        let payload = serde_json::to_vec(event)?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            NATS_MESSAGE_ID,
+            format!("{}:{}:{}", subject, event.email, event.occurred_at.timestamp_micros()).as_str(),
+        );
+        headers.insert("event_type", event.kind.as_str());
+
+        let ack = self
+            .jetstream
+            .publish_with_headers(subject.clone(), headers, payload.into())
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to publish {} to NATS: {e}", event.kind))?
+            .await
+            .map_err(|e| anyhow::anyhow!("JetStream did not acknowledge {} on {subject}: {e}", event.kind))?;
+
+        if ack.duplicate {
+            debug!(subject = %subject, stream = %ack.stream, "JetStream dropped a duplicate event");
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads click events through a durable JetStream pull consumer.
+///
+/// A click is acknowledged once recorded; one that cannot be stored is
+/// negatively acknowledged and redelivered after a pause.
+pub struct NatsClickConsumer {
+    consumer: PullConsumer,
+    subject: String,
+}
+
+impl NatsClickConsumer {
+    /// Configure from `NATS_URL`, `CLICK_EVENTS_STREAM` (the shortlink
+    /// service's existing stream), `CLICK_EVENTS_SUBJECT` (default
+    /// `shortlink.link.clicked`) and `CLICK_EVENTS_GROUP`, the durable
+    /// consumer name (default `marketing-newsletter`).
+    pub async fn from_env() -> anyhow::Result<Self> {
+        let stream = env::var("CLICK_EVENTS_STREAM")
+            .map_err(|e| anyhow::anyhow!("CLICK_EVENTS_STREAM not set: {e}"))?;
+        let subject = env::var("CLICK_EVENTS_SUBJECT").unwrap_or_else(|_| DEFAULT_CLICK_EVENTS_TOPIC.to_string());
+        let durable = env::var("CLICK_EVENTS_GROUP").unwrap_or_else(|_| DEFAULT_CLICK_EVENTS_GROUP.to_string());
+
+        let consumer = connect()
+            .await?
+            .get_stream(&stream)
+            .await
+            .with_context(|| format!("failed to find JetStream stream {stream}"))?
+            .get_or_create_consumer(
+                &durable,
+                pull::Config {
+                    durable_name: Some(durable.clone()),
+                    filter_subject: subject.clone(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .with_context(|| format!("failed to create JetStream consumer {durable}"))?;
+
+        Ok(Self { consumer, subject })
+    }
+}
+
+#[async_trait]
+impl ClickConsumer for NatsClickConsumer {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    async fn run(&self, recorder: Arc<ClickRecorder>, mut stopped: BoxFuture<'static, ()>) {
+        loop {
+            let mut messages = match self.consumer.messages().await {
+                Ok(messages) => messages,
+                Err(e) => {
+                    warn!(subject = %self.subject, error = %e, "Failed to pull click events");
+                    tokio::select! {
+                        _ = &mut stopped => return,
+                        _ = tokio::time::sleep(CLICK_RETRY_DELAY) => continue,
+                    }
+                }
+            };
+
+            loop {
+                let message = tokio::select! {
+                    _ = &mut stopped => return,
+                    message = messages.next() => message,
+                };
+                let message = match message {
+                    Some(Ok(message)) => message,
+                    Some(Err(e)) => {
+                        warn!(subject = %self.subject, error = %e, "Failed to read click event");
+                        continue;
+                    }
+                    // The pull ended; start a new one
+                    None => break,
+                };
+
+                let ack = match recorder.handle(&message.payload).await {
+                    Ok(()) => AckKind::Ack,
+                    Err(e) => {
+                        warn!(subject = %self.subject, error = %e, "Failed to record click, redelivering");
+                        AckKind::Nak(Some(CLICK_RETRY_DELAY))
+                    }
+                };
+                if let Err(e) = message.ack_with(ack).await {
+                    warn!(subject = %self.subject, error = %e, "Failed to acknowledge click event");
+                }
+            }
+        }
+    }
+}
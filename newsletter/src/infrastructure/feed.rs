@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use tracing::info;
+
+use crate::domain::campaign::digest::{DigestItem, SourceFormat};
+
+/// Longest wait for a content source to answer
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where digest entries come from
+#[async_trait]
+pub trait ContentSource: Send + Sync {
+    /// Every entry the source currently lists, in its own order
+    async fn fetch(&self, url: &str, format: SourceFormat) -> Result<Vec<DigestItem>>;
+}
+
+/// Body of a [`SourceFormat::Json`] source
+#[derive(Deserialize)]
+struct JsonItems {
+    items: Vec<DigestItem>,
+}
+
+/// Content source reading RSS, Atom and JSON over HTTP
+pub struct HttpContentSource {
+    client: reqwest::Client,
+}
+
+impl HttpContentSource {
+    pub fn new() -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .context("failed to build content source HTTP client")?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl ContentSource for HttpContentSource {
+    async fn fetch(&self, url: &str, format: SourceFormat) -> Result<Vec<DigestItem>> {
+        let body = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("failed to fetch {url}"))?
+            .bytes()
+            .await
+            .with_context(|| format!("failed to read {url}"))?;
+
+        let items = match format {
+            SourceFormat::Feed => parse_feed(&body).with_context(|| format!("{url} is not an RSS or Atom feed"))?,
+            SourceFormat::Json => {
+                serde_json::from_slice::<JsonItems>(&body)
+                    .with_context(|| format!("{url} did not answer with a JSON item list"))?
+                    .items
+            }
+        };
+        info!(url = url, format = %format, items = items.len(), "Fetched digest content");
+        Ok(items)
+    }
+}
+
+/// Entries of an RSS or Atom document; those without a link are dropped
+fn parse_feed(body: &[u8]) -> Result<Vec<DigestItem>> {
+    let feed = feed_rs::parser::parse(body)?;
+    Ok(feed
+        .entries
+        .into_iter()
+        .filter_map(|entry| {
+            let url = entry.links.first()?.href.clone();
+            Some(DigestItem {
+                title: entry.title.map(|title| title.content).unwrap_or_else(|| url.clone()),
+                url,
+                summary: entry.summary.map(|summary| summary.content),
+                published_at: entry.published.or(entry.updated),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,62 @@
+use http::{header, Method, StatusCode};
+use hyper::{Request, Response};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::http::Body;
+use crate::infrastructure::tenant;
+use crate::service::template::assets::AssetLinks;
+use crate::service::template::TemplateService;
+
+/// How long a client may reuse a redirect, well within the signed URL's TTL
+const REDIRECT_MAX_AGE_SECS: u32 = 300;
+
+/// Serves the links of [`AssetLinks`] at `/assets/:token` by redirecting to
+/// a freshly signed URL of the blob, so images in an email keep loading
+/// however long after sending it is opened.
+pub struct AssetHandler {
+    links: AssetLinks,
+    templates: Arc<dyn TemplateService>,
+}
+
+impl AssetHandler {
+    pub fn new(links: AssetLinks, templates: Arc<dyn TemplateService>) -> Self {
+        Self { links, templates }
+    }
+
+    /// Whether `path` is one of these routes
+    pub fn matches(path: &str) -> bool {
+        path.starts_with("/assets/")
+    }
+
+    pub async fn handle<B>(&self, req: Request<B>) -> Response<Body> {
+        if req.method() != Method::GET && req.method() != Method::HEAD {
+            return Self::status(StatusCode::METHOD_NOT_ALLOWED);
+        }
+        let token = req.uri().path().trim_start_matches("/assets/");
+        let Some((tenant, name)) = self.links.verify(token) else {
+            return Self::status(StatusCode::NOT_FOUND);
+        };
+
+        match tenant::scope(TenantScope::One(tenant.clone()), self.templates.get_asset(&name)).await {
+            Ok(Some(signed)) => Response::builder()
+                .status(StatusCode::FOUND)
+                .header(header::LOCATION, signed.url)
+                .header(header::CACHE_CONTROL, format!("private, max-age={REDIRECT_MAX_AGE_SECS}"))
+                .body(Body::default())
+                .unwrap_or_else(|_| Self::status(StatusCode::INTERNAL_SERVER_ERROR)),
+            Ok(None) => Self::status(StatusCode::NOT_FOUND),
+            Err(e) => {
+                error!(tenant = %tenant, name = %name, error = %e, "Failed to sign template asset URL");
+                Self::status(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+
+    fn status(status: StatusCode) -> Response<Body> {
+        let mut response = Response::new(Body::default());
+        *response.status_mut() = status;
+        response
+    }
+}
@@ -0,0 +1,70 @@
+use http::{Method, StatusCode};
+use http_body_util::{BodyExt, Limited};
+use hyper::body::Body as HttpBody;
+use hyper::{Request, Response};
+use std::sync::Arc;
+use tracing::{error, warn};
+
+use crate::domain::campaign::complaint::{ComplaintError, MAX_REPORT_BYTES};
+use crate::infrastructure::http::Body;
+use crate::infrastructure::metrics::MetricsSink;
+use crate::service::campaign::complaints::ComplaintProcessor;
+
+/// Accepts ARF complaint reports at `POST /complaints`, the raw message as
+/// the body, such as from a mail filter on the feedback-loop mailbox.
+///
+/// Answers 202 once the subscriber is suppressed and 422 for reports that
+/// cannot be used, so the caller knows not to send them again. Complaints
+/// are counted as `complaints`, tagged with their feedback type.
+pub struct ComplaintHandler {
+    processor: ComplaintProcessor,
+    metrics: Arc<dyn MetricsSink>,
+}
+
+impl ComplaintHandler {
+    pub fn new(processor: ComplaintProcessor, metrics: Arc<dyn MetricsSink>) -> Self {
+        Self { processor, metrics }
+    }
+
+    /// Whether `path` is this route
+    pub fn matches(path: &str) -> bool {
+        path == "/complaints"
+    }
+
+    pub async fn handle<B>(&self, req: Request<B>) -> Response<Body>
+    where
+        B: HttpBody,
+        B::Error: std::error::Error + Send + Sync + 'static,
+    {
+        if req.method() != Method::POST {
+            return Self::status(StatusCode::METHOD_NOT_ALLOWED);
+        }
+        let body = match Limited::new(req.into_body(), MAX_REPORT_BYTES).collect().await {
+            Ok(body) => body.to_bytes(),
+            Err(_) => return Self::status(StatusCode::PAYLOAD_TOO_LARGE),
+        };
+
+        match self.processor.process(&String::from_utf8_lossy(&body)).await {
+            Ok(complaint) => {
+                self.metrics.count("complaints", 1, &[("feedback_type", &complaint.feedback_type)]);
+                Self::status(StatusCode::ACCEPTED)
+            }
+            Err(e) => match e.downcast_ref::<ComplaintError>() {
+                Some(reason) => {
+                    warn!(error = %reason, "Refused complaint report");
+                    Self::status(StatusCode::UNPROCESSABLE_ENTITY)
+                }
+                None => {
+                    error!(error = %e, "Failed to process complaint report");
+                    Self::status(StatusCode::INTERNAL_SERVER_ERROR)
+                }
+            },
+        }
+    }
+
+    fn status(status: StatusCode) -> Response<Body> {
+        let mut response = Response::new(Body::default());
+        *response.status_mut() = status;
+        response
+    }
+}
@@ -0,0 +1,38 @@
+use bytes::Bytes;
+use http::{header, Method, StatusCode};
+use hyper::{Request, Response};
+use std::sync::Arc;
+
+use crate::infrastructure::http::Body;
+use crate::infrastructure::metrics::prometheus::{PrometheusSink, CONTENT_TYPE};
+
+/// Serves `GET /metrics` for Prometheus to scrape
+pub struct MetricsHandler {
+    sink: Arc<PrometheusSink>,
+}
+
+impl MetricsHandler {
+    pub fn new(sink: Arc<PrometheusSink>) -> Self {
+        Self { sink }
+    }
+
+    pub async fn handle<B>(&self, req: Request<B>) -> Response<Body> {
+        if req.method() != Method::GET && req.method() != Method::HEAD {
+            return Self::status(StatusCode::METHOD_NOT_ALLOWED);
+        }
+        if req.uri().path() != "/metrics" {
+            return Self::status(StatusCode::NOT_FOUND);
+        }
+
+        Response::builder()
+            .header(header::CONTENT_TYPE, CONTENT_TYPE)
+            .body(Body::new(Bytes::from(self.sink.render())))
+            .unwrap_or_else(|_| Self::status(StatusCode::INTERNAL_SERVER_ERROR))
+    }
+
+    fn status(status: StatusCode) -> Response<Body> {
+        let mut response = Response::new(Body::default());
+        *response.status_mut() = status;
+        response
+    }
+}
@@ -0,0 +1,57 @@
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+pub mod assets;
+pub mod complaints;
+pub mod metrics;
+pub mod tracking;
+pub mod unsubscribe;
+
+pub type Body = Full<Bytes>;
+
+/// Serve plain HTTP/1 on `addr`, answering every request with `handler`,
+/// until `shutdown` resolves
+pub async fn serve<H, F>(
+    addr: SocketAddr,
+    handler: H,
+    shutdown: impl Future<Output = ()>,
+) -> anyhow::Result<()>
+where
+    H: Fn(Request<Incoming>) -> F + Clone + Send + 'static,
+    F: Future<Output = Response<Body>> + Send + 'static,
+{
+    let listener = TcpListener::bind(addr).await?;
+    info!(%addr, "Starting HTTP server");
+    tokio::pin!(shutdown);
+
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => accepted?,
+            _ = &mut shutdown => return Ok(()),
+        };
+        let handler = handler.clone();
+
+        tokio::spawn(async move {
+            let service = service_fn(move |req| {
+                let response = handler(req);
+                async move { Ok::<_, Infallible>(response.await) }
+            });
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                warn!(%peer, error = %e, "HTTP connection failed");
+            }
+        });
+    }
+}
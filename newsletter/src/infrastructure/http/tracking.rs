@@ -0,0 +1,89 @@
+use bytes::Bytes;
+use http::{header, Method, StatusCode};
+use hyper::{Request, Response};
+use route_recognizer::Router;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::domain::campaign::engagement::{EngagementEvent, EngagementKind};
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::http::Body;
+use crate::infrastructure::tenant;
+use crate::service::campaign::tracking::TrackingLinks;
+use crate::service::campaign::CampaignService;
+
+/// Transparent 1x1 GIF
+const PIXEL: &[u8] = &[
+    0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0xff, 0xff, 0xff, 0x21, 0xf9, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x2c, 0x00, 0x00,
+    0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44, 0x01, 0x00, 0x3b,
+];
+
+/// Serves the open pixel and click redirects of [`TrackingLinks`]:
+/// `GET /open/:token` and `GET /click/:token`.
+///
+/// Events are stored in the background so a slow database never delays the
+/// pixel or the redirect.
+pub struct TrackingHandler {
+    links: TrackingLinks,
+    campaigns: Arc<dyn CampaignService>,
+    router: Router<EngagementKind>,
+}
+
+impl TrackingHandler {
+    pub fn new(links: TrackingLinks, campaigns: Arc<dyn CampaignService>) -> Self {
+        let mut router = Router::new();
+        router.add("/open/:token", EngagementKind::Open);
+        router.add("/click/:token", EngagementKind::Click);
+
+        Self { links, campaigns, router }
+    }
+
+    pub async fn handle<B>(&self, req: Request<B>) -> Response<Body> {
+        if req.method() != Method::GET && req.method() != Method::HEAD {
+            return Self::status(StatusCode::METHOD_NOT_ALLOWED);
+        }
+
+        let Ok(route) = self.router.recognize(req.uri().path()) else {
+            return Self::status(StatusCode::NOT_FOUND);
+        };
+        let kind = **route.handler();
+        let Some(event) = self.links.verify(&route.params()["token"], kind) else {
+            return Self::status(StatusCode::NOT_FOUND);
+        };
+
+        let response = match &event.url {
+            Some(url) => Response::builder()
+                .status(StatusCode::FOUND)
+                .header(header::LOCATION, url.as_str())
+                .header(header::CACHE_CONTROL, "no-store")
+                .body(Body::default()),
+            None => Response::builder()
+                .header(header::CONTENT_TYPE, "image/gif")
+                .header(header::CACHE_CONTROL, "no-store")
+                .body(Body::new(Bytes::from_static(PIXEL))),
+        };
+
+        self.record(event);
+        response.unwrap_or_else(|_| Self::status(StatusCode::INTERNAL_SERVER_ERROR))
+    }
+
+    /// Tracking links name only the campaign, so events are stored across
+    /// all tenants and take the campaign's
+    fn record(&self, event: EngagementEvent) {
+        let campaigns = self.campaigns.clone();
+        tokio::spawn(async move {
+            let (campaign_id, kind) = (event.campaign_id, event.kind.as_str());
+            match tenant::scope(TenantScope::All, campaigns.record_engagement(event)).await {
+                Ok(()) => info!(campaign_id = campaign_id, kind = kind, "Recorded engagement"),
+                Err(e) => error!(campaign_id = campaign_id, kind = kind, error = %e, "Failed to record engagement"),
+            }
+        });
+    }
+
+    fn status(status: StatusCode) -> Response<Body> {
+        let mut response = Response::new(Body::default());
+        *response.status_mut() = status;
+        response
+    }
+}
@@ -0,0 +1,112 @@
+use bytes::Bytes;
+use http::{header, Method, StatusCode};
+use http_body_util::{BodyExt, Limited};
+use hyper::body::Body as HttpBody;
+use hyper::{Request, Response};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::domain::newsletter::unsubscribe::UnsubscribeFeedback;
+use crate::domain::newsletter::EmailAddress;
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::http::Body;
+use crate::infrastructure::metrics::MetricsSink;
+use crate::infrastructure::{logging, tenant};
+use crate::service::campaign::unsubscribe::{UnsubscribeLinks, ONE_CLICK_BODY};
+use crate::service::newsletter::NewsletterService;
+
+/// Largest request body read; a one-click POST is a single form field
+const MAX_BODY_BYTES: usize = 1024;
+
+const CONFIRM_PAGE: &str = "<!doctype html><html><body>\
+    <p>Unsubscribe from this newsletter?</p>\
+    <form method=\"post\"><button type=\"submit\">Unsubscribe</button></form>\
+    </body></html>";
+
+const DONE_PAGE: &str = "<!doctype html><html><body>\
+    <p>You have been unsubscribed and will not receive further emails.</p>\
+    </body></html>";
+
+/// Serves the links of [`UnsubscribeLinks`] at `/unsubscribe/:token`.
+///
+/// GET only asks for confirmation, since link scanners follow every URL of
+/// an email; the page's form and mail clients honoring RFC 8058 POST, and
+/// the POST unsubscribes. Each unsubscribe is counted as `unsubscribes`,
+/// tagged `via:header` for one-click POSTs and `via:link` otherwise.
+pub struct UnsubscribeHandler {
+    links: UnsubscribeLinks,
+    newsletters: Arc<dyn NewsletterService>,
+    metrics: Arc<dyn MetricsSink>,
+}
+
+impl UnsubscribeHandler {
+    pub fn new(links: UnsubscribeLinks, newsletters: Arc<dyn NewsletterService>, metrics: Arc<dyn MetricsSink>) -> Self {
+        Self { links, newsletters, metrics }
+    }
+
+    /// Whether `path` is one of these routes
+    pub fn matches(path: &str) -> bool {
+        path.starts_with("/unsubscribe/")
+    }
+
+    pub async fn handle<B>(&self, req: Request<B>) -> Response<Body>
+    where
+        B: HttpBody,
+        B::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let token = req.uri().path().trim_start_matches("/unsubscribe/");
+        let Some(link) = self.links.verify(token) else {
+            return Self::status(StatusCode::NOT_FOUND);
+        };
+
+        let method = req.method().clone();
+        if method == Method::GET || method == Method::HEAD {
+            return Self::page(CONFIRM_PAGE);
+        }
+        if method != Method::POST {
+            return Self::status(StatusCode::METHOD_NOT_ALLOWED);
+        }
+
+        let body = match Limited::new(req.into_body(), MAX_BODY_BYTES).collect().await {
+            Ok(body) => body.to_bytes(),
+            Err(_) => return Self::status(StatusCode::PAYLOAD_TOO_LARGE),
+        };
+        let one_click = String::from_utf8_lossy(&body).split('&').any(|field| field == ONE_CLICK_BODY);
+        let via = if one_click { "header" } else { "link" };
+
+        let Ok(email) = EmailAddress::parse(&link.email) else {
+            return Self::status(StatusCode::NOT_FOUND);
+        };
+        let unsubscribe = self.newsletters.unsubscribe(&email, UnsubscribeFeedback::default());
+        match tenant::scope(TenantScope::One(link.tenant.clone()), unsubscribe).await {
+            Ok(unsubscribed) => {
+                info!(campaign_id = link.campaign_id, tenant = %link.tenant, email = %logging::email(&link.email), via = via, unsubscribed = unsubscribed, "Unsubscribed from campaign link");
+                self.metrics.count("unsubscribes", 1, &[("via", via)]);
+            }
+            Err(e) => {
+                error!(campaign_id = link.campaign_id, tenant = %link.tenant, email = %logging::email(&link.email), via = via, error = %e, "Failed to unsubscribe from campaign link");
+                return Self::status(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+
+        if one_click {
+            Self::status(StatusCode::OK)
+        } else {
+            Self::page(DONE_PAGE)
+        }
+    }
+
+    fn page(html: &'static str) -> Response<Body> {
+        Response::builder()
+            .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+            .header(header::CACHE_CONTROL, "no-store")
+            .body(Body::new(Bytes::from_static(html.as_bytes())))
+            .unwrap_or_else(|_| Self::status(StatusCode::INTERNAL_SERVER_ERROR))
+    }
+
+    fn status(status: StatusCode) -> Response<Body> {
+        let mut response = Response::new(Body::default());
+        *response.status_mut() = status;
+        response
+    }
+}
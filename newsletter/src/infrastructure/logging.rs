@@ -1,9 +1,26 @@
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 
-/// Initialize tracing with JSON formatting
+use sha2::{Digest, Sha256};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry};
+
+use crate::infrastructure::pseudonym::Pseudonymizer;
+
+/// Whether subscriber addresses are logged as they are
+static LOG_PII: AtomicBool = AtomicBool::new(true);
+
+/// Replaces subscriber addresses once installed, overriding `LOG_PII`
+static PSEUDONYMS: OnceLock<Pseudonymizer> = OnceLock::new();
+
+/// Swaps the filter of the subscriber installed by `init_tracing`
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Initialize tracing with JSON formatting, filtered by `RUST_LOG` until
+/// [`set_filter`] is called
 pub fn init_tracing() -> anyhow::Result<()> {
-    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    let (env_filter, handle) = reload::Layer::new(default_filter());
+    let _ = FILTER.set(handle);
 
     tracing_subscriber::registry()
         .with(env_filter)
@@ -15,18 +32,73 @@ pub fn init_tracing() -> anyhow::Result<()> {
                 .with_file(true)
                 .with_line_number(true)
                 .with_current_span(true)
-                .with_span_list(false),
+                // The outer `rpc` span carries the trace id of the call
+                .with_span_list(true),
         )
         .init();
 
     Ok(())
 }
 
-/// Extract trace ID from tonic request headers
-pub fn extract_trace_id_from_request<T>(request: &tonic::Request<T>) -> Option<String> {
-    request
-        .metadata()
-        .get("x-trace-id")
-        .and_then(|value| value.to_str().ok())
-        .map(|s| s.to_string())
-}
\ No newline at end of file
+/// `RUST_LOG`, or `info` when it is unset or invalid
+fn default_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
+}
+
+/// Parse `tracing` directives such as `info,newsletter=debug`
+pub fn parse_filter(directives: &str) -> anyhow::Result<EnvFilter> {
+    EnvFilter::try_new(directives).map_err(|e| anyhow::anyhow!("invalid log filter {directives:?}: {e}"))
+}
+
+/// Replace the log filter of the running process; `None` goes back to the
+/// one tracing started with
+pub fn set_filter(directives: Option<&str>) -> anyhow::Result<()> {
+    let filter = match directives {
+        Some(directives) => parse_filter(directives)?,
+        None => default_filter(),
+    };
+    if let Some(handle) = FILTER.get() {
+        handle.reload(filter)?;
+    }
+    Ok(())
+}
+
+/// Log subscriber addresses as they are, or only as digests
+pub fn set_log_pii(enabled: bool) {
+    LOG_PII.store(enabled, Ordering::Relaxed);
+}
+
+/// Log subscriber addresses as the pseudonyms published events and audit
+/// records carry, so the three can be matched up. The first call wins.
+pub fn set_pseudonyms(pseudonyms: Pseudonymizer) {
+    let _ = PSEUDONYMS.set(pseudonyms);
+}
+
+/// An email address for a log field. With pseudonyms installed it is written
+/// as one; otherwise, with `LOG_PII` off, as a short SHA-256 digest, which
+/// still ties together the lines about one subscriber without revealing who
+/// it is.
+pub fn email<T: fmt::Display>(value: T) -> RedactedEmail<T> {
+    RedactedEmail(value)
+}
+
+/// See [`email`]
+pub struct RedactedEmail<T>(T);
+
+impl<T: fmt::Display> fmt::Display for RedactedEmail<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(pseudonyms) = PSEUDONYMS.get() {
+            return f.write_str(&pseudonyms.pseudonym(&self.0.to_string()));
+        }
+        if LOG_PII.load(Ordering::Relaxed) {
+            return self.0.fmt(f);
+        }
+
+        let digest = Sha256::digest(self.0.to_string().as_bytes());
+        f.write_str("sha256:")?;
+        for byte in &digest[..8] {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use tracing::debug;
+
+use super::{MetricsSink, Tags};
+
+/// Default sink that only writes samples to the debug log
+#[derive(Debug, Clone, Default)]
+pub struct LogMetricsSink;
+
+impl MetricsSink for LogMetricsSink {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn count(&self, name: &str, value: u64, tags: Tags<'_>) {
+        debug!(metric = name, value, ?tags, "Counter not exported (log metrics)");
+    }
+
+    fn gauge(&self, name: &str, value: f64, tags: Tags<'_>) {
+        debug!(metric = name, value, ?tags, "Gauge not exported (log metrics)");
+    }
+}
@@ -0,0 +1,61 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+pub mod log;
+pub mod prometheus;
+pub mod statsd;
+
+/// Labels of one series, as `(name, value)` pairs
+pub type Tags<'a> = &'a [(&'a str, &'a str)];
+
+/// Receives the counters and gauges the service reports.
+///
+/// Names are dotted, such as `db.retries`; each backend adds the configured
+/// prefix and spells them its own way. Recording never blocks or fails: a
+/// backend that cannot keep up drops samples.
+pub trait MetricsSink: Send + Sync {
+    /// Short backend name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Add `value` to a counter
+    fn count(&self, name: &str, value: u64, tags: Tags<'_>);
+
+    /// Set a gauge to `value`
+    fn gauge(&self, name: &str, value: f64, tags: Tags<'_>);
+}
+
+/// Where metrics are sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsBackend {
+    /// Written to the debug log only
+    #[default]
+    Log,
+    /// Served for scraping at `/metrics`
+    Prometheus,
+    /// Sent over UDP to a StatsD agent; tags become part of the name
+    Statsd,
+    /// Sent over UDP to a Datadog agent, with tags
+    Dogstatsd,
+}
+
+impl fmt::Display for MetricsBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MetricsBackend::Log => "log",
+            MetricsBackend::Prometheus => "prometheus",
+            MetricsBackend::Statsd => "statsd",
+            MetricsBackend::Dogstatsd => "dogstatsd",
+        })
+    }
+}
+
+/// Whether `prefix` is a dotted name both StatsD and Prometheus accept,
+/// such as `newsletter` or `marketing.newsletter`
+pub fn is_valid_prefix(prefix: &str) -> bool {
+    prefix.split('.').all(|part| {
+        part.starts_with(|c: char| c.is_ascii_alphabetic())
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+use super::{MetricsSink, Tags};
+
+/// Content type of the text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Counter,
+    Gauge,
+}
+
+#[derive(Debug)]
+struct Family {
+    kind: Kind,
+    /// Values keyed by their rendered label set, `{key="value"}` or empty
+    series: BTreeMap<String, f64>,
+}
+
+/// Keeps the latest value of every series for Prometheus to scrape.
+///
+/// Counters accumulate what is reported, so they only reset when the process
+/// restarts, as Prometheus expects.
+pub struct PrometheusSink {
+    prefix: String,
+    families: Mutex<BTreeMap<String, Family>>,
+}
+
+impl PrometheusSink {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.replace('.', "_"),
+            families: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Every series in the text exposition format
+    pub fn render(&self) -> String {
+        let families = self.families.lock().expect("metrics lock poisoned");
+        let mut out = String::new();
+        for (name, family) in families.iter() {
+            let kind = match family.kind {
+                Kind::Counter => "counter",
+                Kind::Gauge => "gauge",
+            };
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            for (labels, value) in &family.series {
+                let _ = writeln!(out, "{name}{labels} {value}");
+            }
+        }
+        out
+    }
+
+    fn record(&self, name: &str, kind: Kind, tags: Tags<'_>, update: impl FnOnce(&mut f64)) {
+        let mut name = format!("{}_{}", self.prefix, name.replace('.', "_"));
+        if kind == Kind::Counter {
+            name.push_str("_total");
+        }
+
+        let mut families = self.families.lock().expect("metrics lock poisoned");
+        let family = families.entry(name).or_insert_with(|| Family {
+            kind,
+            series: BTreeMap::new(),
+        });
+        update(family.series.entry(labels(tags)).or_default());
+    }
+}
+
+impl MetricsSink for PrometheusSink {
+    fn name(&self) -> &'static str {
+        "prometheus"
+    }
+
+    fn count(&self, name: &str, value: u64, tags: Tags<'_>) {
+        self.record(name, Kind::Counter, tags, |total| *total += value as f64);
+    }
+
+    fn gauge(&self, name: &str, value: f64, tags: Tags<'_>) {
+        self.record(name, Kind::Gauge, tags, |current| *current = value);
+    }
+}
+
+fn labels(tags: Tags<'_>) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = tags
+        .iter()
+        .map(|(key, value)| {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            format!("{key}=\"{value}\"")
+        })
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
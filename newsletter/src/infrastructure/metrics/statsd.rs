@@ -0,0 +1,82 @@
+use std::fmt::Write;
+use std::io::ErrorKind;
+use std::net::UdpSocket;
+
+use tracing::debug;
+
+use super::{MetricsSink, Tags};
+
+/// Sends every sample as one UDP datagram to a StatsD or DogStatsD agent.
+///
+/// Plain StatsD has no tags, so their values are appended to the name
+/// (`db.pool.connections.replica`); DogStatsD sends them as `|#key:value`.
+/// A datagram the socket cannot take at once is dropped.
+pub struct StatsdSink {
+    socket: UdpSocket,
+    prefix: String,
+    tagged: bool,
+}
+
+impl StatsdSink {
+    /// Resolve the agent at `addr` (`host:port`) and bind a socket sending to it
+    pub async fn connect(addr: &str, prefix: &str, tagged: bool) -> anyhow::Result<Self> {
+        let agent = tokio::net::lookup_host(addr)
+            .await?
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("StatsD agent address {addr} did not resolve"))?;
+        let local = if agent.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(agent)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            prefix: prefix.to_string(),
+            tagged,
+        })
+    }
+
+    fn line(&self, name: &str, value: &str, kind: &str, tags: Tags<'_>) -> String {
+        let mut line = format!("{}.{name}", self.prefix);
+        if !self.tagged {
+            for (_, value) in tags {
+                let _ = write!(line, ".{value}");
+            }
+        }
+        let _ = write!(line, ":{value}|{kind}");
+        if self.tagged && !tags.is_empty() {
+            let tags: Vec<String> = tags.iter().map(|(key, value)| format!("{key}:{value}")).collect();
+            let _ = write!(line, "|#{}", tags.join(","));
+        }
+        line
+    }
+
+    fn send(&self, line: String) {
+        if let Err(e) = self.socket.send(line.as_bytes()) {
+            if e.kind() != ErrorKind::WouldBlock {
+                debug!(error = %e, "Failed to send a metric to the StatsD agent");
+            }
+        }
+    }
+}
+
+impl MetricsSink for StatsdSink {
+    fn name(&self) -> &'static str {
+        if self.tagged {
+            "dogstatsd"
+        } else {
+            "statsd"
+        }
+    }
+
+    fn count(&self, name: &str, value: u64, tags: Tags<'_>) {
+        // An agent reads a missing counter as zero
+        if value > 0 {
+            self.send(self.line(name, &value.to_string(), "c", tags));
+        }
+    }
+
+    fn gauge(&self, name: &str, value: f64, tags: Tags<'_>) {
+        self.send(self.line(name, &value.to_string(), "g", tags));
+    }
+}
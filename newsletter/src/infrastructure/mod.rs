@@ -1,3 +1,22 @@
+pub mod analytics;
+pub mod assets;
+pub mod build_info;
+pub mod cache;
+pub mod config;
 pub mod db;
+pub mod dkim;
+pub mod email;
+pub mod events;
+pub mod feed;
+pub mod http;
 pub mod rpc;
+pub mod shutdown;
 pub mod logging;
+pub mod metrics;
+pub mod pseudonym;
+pub mod reload;
+pub mod template;
+pub mod tenant;
+pub mod token;
+pub mod verification;
+pub mod webhook;
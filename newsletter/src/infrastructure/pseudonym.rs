@@ -0,0 +1,47 @@
+use std::fmt;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Prefix telling pseudonyms apart from addresses
+pub const PREFIX: &str = "p_";
+
+/// Replaces subscriber addresses with a keyed HMAC-SHA256 pseudonym.
+///
+/// The same address always maps to the same pseudonym, whatever its case, so
+/// records stay joinable; without the key there is no way back other than
+/// hashing every address in the newsletters table and comparing.
+#[derive(Clone)]
+pub struct Pseudonymizer {
+    key: Vec<u8>,
+}
+
+impl Pseudonymizer {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self { key: key.into() }
+    }
+
+    /// `p_` followed by the first 16 bytes of the MAC in hex
+    pub fn pseudonym(&self, email: &str) -> String {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(email.to_lowercase().as_bytes());
+        let digest = mac.finalize().into_bytes();
+
+        let mut pseudonym = String::with_capacity(PREFIX.len() + 32);
+        pseudonym.push_str(PREFIX);
+        for byte in &digest[..16] {
+            pseudonym.push_str(&format!("{byte:02x}"));
+        }
+        pseudonym
+    }
+}
+
+// Keeps the key out of debug output
+impl fmt::Debug for Pseudonymizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pseudonymizer").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,129 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use tracing::{error, info};
+
+use crate::infrastructure::config::Settings;
+use crate::infrastructure::rpc::rate_limit::RateLimitConfig;
+
+/// Applies settings that may change while the server runs.
+///
+/// A reload reads the settings again and publishes the log filter, the rate
+/// limits and a blocklist generation on watch channels; the tracing filter,
+/// the rate limit layer and the blocklist refresher each follow theirs.
+/// Settings that fail validation leave every channel as it was. Everything
+/// else, including where the blocklist is kept, still needs a restart.
+pub struct Reloader {
+    log_filter: watch::Sender<Option<String>>,
+    rate_limit: watch::Sender<Option<RateLimitConfig>>,
+    /// Bumped by every reload, so the blocklist is read again even when its
+    /// location is unchanged
+    blocklist: watch::Sender<u64>,
+}
+
+impl Reloader {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            log_filter: watch::Sender::new(settings.logging.filter().map(str::to_string)),
+            rate_limit: watch::Sender::new(settings.rate_limit.config()),
+            blocklist: watch::Sender::new(0),
+        }
+    }
+
+    pub fn log_filter(&self) -> watch::Receiver<Option<String>> {
+        self.log_filter.subscribe()
+    }
+
+    pub fn rate_limit(&self) -> watch::Receiver<Option<RateLimitConfig>> {
+        self.rate_limit.subscribe()
+    }
+
+    pub fn blocklist(&self) -> watch::Receiver<u64> {
+        self.blocklist.subscribe()
+    }
+
+    /// Replace the log filter until the next reload; `None` goes back to the
+    /// one tracing started with
+    pub fn set_log_filter(&self, filter: Option<String>) {
+        self.log_filter.send_replace(filter);
+        info!("Log filter changed at runtime");
+    }
+
+    /// Load the settings again and publish what changed
+    pub fn reload(&self) -> anyhow::Result<()> {
+        let settings = Settings::load()?;
+        self.apply(&settings);
+        Ok(())
+    }
+
+    /// Publish the reloadable parts of `settings`
+    pub fn apply(&self, settings: &Settings) {
+        let filter = settings.logging.filter().map(str::to_string);
+        let filter_changed = self.log_filter.send_if_modified(|current| replace(current, filter));
+        let limits_changed = self
+            .rate_limit
+            .send_if_modified(|current| replace(current, settings.rate_limit.config()));
+        self.blocklist.send_modify(|generation| *generation += 1);
+
+        info!(log_filter_changed = filter_changed, rate_limit_changed = limits_changed, "Reloaded settings");
+    }
+}
+
+fn replace<T: PartialEq>(current: &mut T, new: T) -> bool {
+    if *current == new {
+        return false;
+    }
+    *current = new;
+    true
+}
+
+/// Call `apply` with every new value of `channel` until `stopped` resolves
+pub async fn follow<T, F, Fut>(mut channel: watch::Receiver<T>, stopped: impl Future<Output = ()>, mut apply: F)
+where
+    T: Clone,
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    tokio::pin!(stopped);
+    loop {
+        tokio::select! {
+            _ = &mut stopped => return,
+            changed = channel.changed() => {
+                // The reloader is gone, so nothing will change anymore
+                if changed.is_err() {
+                    return;
+                }
+                let value = channel.borrow_and_update().clone();
+                apply(value).await;
+            }
+        }
+    }
+}
+
+/// Reload whenever the process gets SIGHUP, until `stopped` resolves
+#[cfg(unix)]
+pub async fn on_hangup(reloader: Arc<Reloader>, stopped: impl Future<Output = ()>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            error!(error = %e, "Failed to install SIGHUP handler, settings cannot be reloaded");
+            return;
+        }
+    };
+
+    tokio::pin!(stopped);
+    loop {
+        tokio::select! {
+            _ = &mut stopped => return,
+            Some(()) = hangup.recv() => {
+                info!("SIGHUP received, reloading settings");
+                if let Err(e) = reloader.reload() {
+                    error!(error = %e, "Failed to reload settings, keeping the current ones");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use tonic::transport::server::TcpConnectInfo;
+use tonic::Code;
+use tower::{Layer, Service};
+use tracing::{error, info, info_span, Instrument};
+
+use crate::infrastructure::build_info;
+
+/// Metadata carrying the caller's trace id
+pub const TRACE_ID_HEADER: &str = "x-trace-id";
+
+/// Tower layer logging one line per call once it is answered: method, gRPC
+/// status code, duration and peer.
+///
+/// The call runs in an `rpc` span carrying the trace id from
+/// [`TRACE_ID_HEADER`], or a fresh one, so everything logged while handling
+/// it can be tied together. Goes outermost, so calls turned away by auth,
+/// tenant resolution or rate limiting are logged too.
+#[derive(Clone, Default)]
+pub struct AccessLogLayer;
+
+impl AccessLogLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for AccessLogLayer {
+    type Service = AccessLogMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AccessLogMiddleware { inner }
+    }
+}
+
+/// Service produced by [`AccessLogLayer`]
+#[derive(Clone)]
+pub struct AccessLogMiddleware<S> {
+    inner: S,
+}
+
+impl<S, B> Service<http::Request<B>> for AccessLogMiddleware<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<tonic::body::Body>>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        // Use the instance that was polled ready and leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let trace_id = req
+            .headers()
+            .get(TRACE_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let peer = req
+            .extensions()
+            .get::<TcpConnectInfo>()
+            .and_then(|info| info.remote_addr())
+            .map(|addr| addr.to_string())
+            .unwrap_or_default();
+        let method = req.uri().path().to_string();
+        // Every event of the call lists this span, so logs name the release that wrote them
+        let span = info_span!(
+            "rpc",
+            method = %method,
+            trace_id = %trace_id,
+            peer = %peer,
+            git_sha = build_info::GIT_SHA
+        );
+
+        Box::pin(
+            async move {
+                let started = Instant::now();
+                let response = inner.call(req).await;
+                let duration_ms = started.elapsed().as_millis() as u64;
+
+                match &response {
+                    Ok(response) => {
+                        let code = status_code(response);
+                        if is_server_error(code) {
+                            error!(code = ?code, duration_ms, "Call failed");
+                        } else {
+                            info!(code = ?code, duration_ms, "Call handled");
+                        }
+                    }
+                    Err(_) => error!(duration_ms, "Call failed before a response"),
+                }
+                response
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// Failed calls are answered with the status in the headers; a successful
+/// one sends it in the trailers, after the body, so it reads as `Ok` here
+/// even for a stream that fails halfway through
+fn status_code<B>(response: &http::Response<B>) -> Code {
+    response
+        .headers()
+        .get("grpc-status")
+        .map(|value| Code::from_bytes(value.as_bytes()))
+        .unwrap_or(Code::Ok)
+}
+
+/// Codes that point at the service rather than the caller
+fn is_server_error(code: Code) -> bool {
+    matches!(code, Code::Unknown | Code::Internal | Code::Unavailable | Code::DataLoss)
+}
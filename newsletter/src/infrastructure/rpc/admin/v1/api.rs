@@ -0,0 +1,318 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, instrument};
+
+use crate::domain::newsletter::EmailAddress;
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::build_info;
+use crate::infrastructure::cache::Cache;
+use crate::infrastructure::db;
+use crate::infrastructure::logging;
+use crate::infrastructure::reload::Reloader;
+use crate::infrastructure::rpc::errors::ErrorReason;
+use crate::infrastructure::rpc::timestamp;
+use crate::infrastructure::rpc::validation::invalid_field;
+use crate::infrastructure::tenant;
+use crate::repository::outbox::OutboxRepository;
+use crate::service::delivery::{DeliveryMonitor, DestinationStats};
+use crate::service::newsletter::seed::{self, ActivityMix, SeedError, SeedPlan};
+use crate::service::newsletter::NewsletterService;
+
+use crate::infrastructure::rpc::admin::v1::proto::{
+    admin_service_server::AdminService, DeliveryStats, FlushCacheRequest, FlushCacheResponse, GetBuildInfoRequest,
+    GetBuildInfoResponse, GetDeliveryStatusRequest, GetDeliveryStatusResponse, GetMigrationStatusRequest, MergeSubscribersRequest,
+    MergeSubscribersResponse, OutboxStatus,
+    WebhookStatus, GetVersionRequest, GetVersionResponse, GetMigrationStatusResponse, Migration, PurgeExpiredPendingRequest, PurgeExpiredPendingResponse, RefreshBlocklistRequest,
+    RefreshBlocklistResponse, ReplayOutboxRequest, ReplayOutboxResponse, SeedSubscribersRequest, SeedSubscribersResponse,
+    SetLogLevelRequest, SetLogLevelResponse,
+};
+
+/// gRPC adapter for operations on the running server, so they need neither a
+/// shell in the pod nor a restart
+#[derive(Clone)]
+pub struct MyAdminService {
+    reloader: Arc<Reloader>,
+    cache: Option<Cache>,
+    outbox: Arc<dyn OutboxRepository>,
+    newsletters: Arc<dyn NewsletterService>,
+    database_url: String,
+    delivery: DeliveryMonitor,
+}
+
+impl MyAdminService {
+    pub fn new(
+        reloader: Arc<Reloader>,
+        cache: Option<Cache>,
+        outbox: Arc<dyn OutboxRepository>,
+        newsletters: Arc<dyn NewsletterService>,
+        database_url: String,
+        delivery: DeliveryMonitor,
+    ) -> Self {
+        Self {
+            reloader,
+            cache,
+            outbox,
+            newsletters,
+            database_url,
+            delivery,
+        }
+    }
+}
+
+fn count(value: usize) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+fn age_seconds(since: Option<DateTime<Utc>>, now: DateTime<Utc>) -> i64 {
+    since.map_or(0, |since| (now - since).num_seconds().max(0))
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl From<DestinationStats> for DeliveryStats {
+    fn from(stats: DestinationStats) -> Self {
+        let total = |value: u64| i64::try_from(value).unwrap_or(i64::MAX);
+        Self {
+            destination: stats.destination,
+            delivered: total(stats.counts.delivered),
+            failed: total(stats.counts.failed),
+            dead_lettered: total(stats.counts.dead_lettered),
+            success_rate: stats.counts.success_rate().unwrap_or_default(),
+            latency_p50_ms: stats.latency.map_or(0.0, |latency| millis(latency.p50)),
+            latency_p95_ms: stats.latency.map_or(0.0, |latency| millis(latency.p95)),
+            latency_max_ms: stats.latency.map_or(0.0, |latency| millis(latency.max)),
+            last_delivered_at: stats.last_delivered_at.map(timestamp::to_proto),
+            last_failed_at: stats.last_failed_at.map(timestamp::to_proto),
+            last_error: stats.last_error.unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl AdminService for MyAdminService {
+    #[instrument(skip(self), fields(filter = %req.get_ref().filter))]
+    async fn set_log_level(&self, req: Request<SetLogLevelRequest>) -> Result<Response<SetLogLevelResponse>, Status> {
+        let filter = req.into_inner().filter;
+        let filter = filter.trim();
+        let filter = if filter.is_empty() {
+            None
+        } else {
+            // Checked here so a typo fails the call instead of only being logged
+            logging::parse_filter(filter).map_err(|e| invalid_field("filter", e.to_string()))?;
+            Some(filter.to_string())
+        };
+
+        self.reloader.set_log_filter(filter);
+        Ok(Response::new(SetLogLevelResponse {}))
+    }
+
+    #[instrument(skip(self, _req))]
+    async fn flush_cache(&self, _req: Request<FlushCacheRequest>) -> Result<Response<FlushCacheResponse>, Status> {
+        let Some(cache) = &self.cache else {
+            return Ok(Response::new(FlushCacheResponse { entries: 0 }));
+        };
+
+        match cache.clear().await {
+            Ok(entries) => Ok(Response::new(FlushCacheResponse { entries: count(entries) })),
+            Err(e) => {
+                error!(operation = "flush_cache", error = %e, "Failed to clear cache");
+                Err(ErrorReason::Internal.status(format!("failed to clear cache: {e}")))
+            }
+        }
+    }
+
+    #[instrument(skip(self, req))]
+    async fn replay_outbox(&self, req: Request<ReplayOutboxRequest>) -> Result<Response<ReplayOutboxResponse>, Status> {
+        let since = req
+            .into_inner()
+            .since
+            .ok_or_else(|| invalid_field("since", "is required"))?;
+        let since = timestamp::from_proto("since", since)?;
+
+        match self.outbox.replay(since).await {
+            Ok(requeued) => {
+                info!(operation = "replay_outbox", since = %since, requeued = requeued, "Requeued outbox events");
+                Ok(Response::new(ReplayOutboxResponse { requeued: count(requeued) }))
+            }
+            Err(e) => {
+                error!(operation = "replay_outbox", error = %e, "Failed to replay outbox events");
+                Err(ErrorReason::Internal.status(format!("failed to replay outbox events: {e}")))
+            }
+        }
+    }
+
+    #[instrument(skip(self, _req))]
+    async fn purge_expired_pending(
+        &self,
+        _req: Request<PurgeExpiredPendingRequest>,
+    ) -> Result<Response<PurgeExpiredPendingResponse>, Status> {
+        // Every tenant, like the recurring job
+        match tenant::scope(TenantScope::All, self.newsletters.purge_expired_pending()).await {
+            Ok(purge) => {
+                info!(operation = "purge_expired_pending", deleted = purge.deleted, reverted = purge.reverted, "Purged expired pending subscriptions");
+                Ok(Response::new(PurgeExpiredPendingResponse {
+                    deleted: count(purge.deleted),
+                    reverted: count(purge.reverted),
+                }))
+            }
+            Err(e) => {
+                error!(operation = "purge_expired_pending", error = %e, "Failed to purge expired pending subscriptions");
+                Err(ErrorReason::Internal.status(format!("failed to purge expired pending subscriptions: {e}")))
+            }
+        }
+    }
+
+    #[instrument(skip(self, _req))]
+    async fn refresh_blocklist(
+        &self,
+        _req: Request<RefreshBlocklistRequest>,
+    ) -> Result<Response<RefreshBlocklistResponse>, Status> {
+        let domains = self.newsletters.refresh_disposable_domains().await?;
+        info!(operation = "refresh_blocklist", domains = domains, "Reloaded the disposable domain list");
+        Ok(Response::new(RefreshBlocklistResponse { domains: count(domains) }))
+    }
+
+    async fn get_build_info(&self, _req: Request<GetBuildInfoRequest>) -> Result<Response<GetBuildInfoResponse>, Status> {
+        Ok(Response::new(GetBuildInfoResponse {
+            version: build_info::VERSION.to_string(),
+            git_commit: build_info::GIT_SHA.to_string(),
+            features: build_info::features().into_iter().map(str::to_string).collect(),
+        }))
+    }
+
+    async fn get_version(&self, _req: Request<GetVersionRequest>) -> Result<Response<GetVersionResponse>, Status> {
+        Ok(Response::new(GetVersionResponse {
+            version: build_info::VERSION.to_string(),
+            git_sha: build_info::GIT_SHA.to_string(),
+            built_at: build_info::built_at().map(timestamp::to_proto),
+        }))
+    }
+
+    #[instrument(skip(self, _req))]
+    async fn get_migration_status(
+        &self,
+        _req: Request<GetMigrationStatusRequest>,
+    ) -> Result<Response<GetMigrationStatusResponse>, Status> {
+        match db::schema_status(&self.database_url).await {
+            Ok(status) => Ok(Response::new(GetMigrationStatusResponse {
+                pending: count(status.pending().len()),
+                migrations: status
+                    .migrations
+                    .into_iter()
+                    .map(|migration| Migration {
+                        version: migration.version,
+                        name: migration.name,
+                        applied: migration.applied,
+                    })
+                    .collect(),
+                unknown: status.unknown,
+            })),
+            Err(e) => {
+                error!(operation = "get_migration_status", error = %e, "Failed to read the applied migrations");
+                Err(ErrorReason::Internal.status(format!("failed to read the applied migrations: {e}")))
+            }
+        }
+    }
+
+    #[instrument(skip(self, req), fields(count = req.get_ref().count))]
+    async fn seed_subscribers(
+        &self,
+        req: Request<SeedSubscribersRequest>,
+    ) -> Result<Response<SeedSubscribersResponse>, Status> {
+        let req = req.into_inner();
+        let mut mix = ActivityMix {
+            active: req.active_weight,
+            unsubscribed: req.unsubscribed_weight,
+            suppressed: req.suppressed_weight,
+        };
+        if mix == (ActivityMix { active: 0, unsubscribed: 0, suppressed: 0 }) {
+            mix = ActivityMix::default();
+        }
+        let plan = SeedPlan::new(usize::try_from(req.count).unwrap_or_default())
+            .with_domains(req.domains)
+            .with_mix(mix)
+            .with_seed(req.seed);
+        plan.validate().map_err(|e| match e {
+            SeedError::Count(_) => invalid_field("count", e.to_string()),
+            SeedError::EmptyMix => invalid_field("active_weight", e.to_string()),
+            SeedError::Domain(_) => invalid_field("domains", e.to_string()),
+        })?;
+
+        match seed::seed(self.newsletters.as_ref(), &plan).await {
+            Ok(summary) => {
+                info!(operation = "seed_subscribers", created = summary.created, active = summary.active, unsubscribed = summary.unsubscribed, suppressed = summary.suppressed, "Seeded subscribers");
+                Ok(Response::new(SeedSubscribersResponse {
+                    created: count(summary.created),
+                    active: count(summary.active),
+                    unsubscribed: count(summary.unsubscribed),
+                    suppressed: count(summary.suppressed),
+                }))
+            }
+            Err(e) => {
+                error!(operation = "seed_subscribers", error = %e, "Failed to seed subscribers");
+                Err(ErrorReason::Internal.status(format!("failed to seed subscribers: {e}")))
+            }
+        }
+    }
+
+    #[instrument(skip(self, _req))]
+    async fn get_delivery_status(
+        &self,
+        _req: Request<GetDeliveryStatusRequest>,
+    ) -> Result<Response<GetDeliveryStatusResponse>, Status> {
+        let status = match self.delivery.status().await {
+            Ok(status) => status,
+            Err(e) => {
+                error!(operation = "get_delivery_status", error = %e, "Failed to read the delivery backlog");
+                return Err(ErrorReason::Internal.status(format!("failed to read the delivery backlog: {e}")));
+            }
+        };
+        let now = Utc::now();
+        Ok(Response::new(GetDeliveryStatusResponse {
+            outbox: Some(OutboxStatus {
+                pending: status.outbox.pending,
+                failing: status.outbox.failing,
+                oldest_pending_at: status.outbox.oldest_pending_at.map(timestamp::to_proto),
+                oldest_pending_age_seconds: age_seconds(status.outbox.oldest_pending_at, now),
+                publishers: status.publishers.into_iter().map(DeliveryStats::from).collect(),
+            }),
+            webhooks: status
+                .webhooks
+                .into_iter()
+                .map(|webhook| WebhookStatus {
+                    oldest_pending_age_seconds: age_seconds(webhook.backlog.oldest_pending_at, now),
+                    oldest_pending_at: webhook.backlog.oldest_pending_at.map(timestamp::to_proto),
+                    url: webhook.backlog.url,
+                    pending: webhook.backlog.pending,
+                    dead_letters: webhook.backlog.dead_letters,
+                    delivery: webhook.stats.map(DeliveryStats::from),
+                })
+                .collect(),
+        }))
+    }
+
+    #[instrument(skip(self, req), fields(email = %logging::email(&req.get_ref().email), merged_email = %logging::email(&req.get_ref().merged_email)))]
+    async fn merge_subscribers(
+        &self,
+        req: Request<MergeSubscribersRequest>,
+    ) -> Result<Response<MergeSubscribersResponse>, Status> {
+        let req = req.into_inner();
+        let email = EmailAddress::parse(&req.email).map_err(|e| invalid_field("email", e.to_string()))?;
+        let merged_email =
+            EmailAddress::parse(&req.merged_email).map_err(|e| invalid_field("merged_email", e.to_string()))?;
+
+        let merge = self.newsletters.merge_subscribers(&email, &merged_email).await?;
+        Ok(Response::new(MergeSubscribersResponse {
+            email: merge.email,
+            merged_email: merge.merged_email,
+            merged_status: merge.merged_status.as_str().to_string(),
+            status: merge.status.as_str().to_string(),
+            merged_at: Some(timestamp::to_proto(merge.merged_at)),
+        }))
+    }
+}
@@ -0,0 +1,166 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tower::{Layer, Service};
+use tracing::{error, info};
+
+use crate::domain::auth::Scope;
+use crate::infrastructure::rpc::errors::ErrorReason;
+use crate::service::auth::AuthService;
+
+/// Methods a read-only key may call; every other method needs an admin key,
+/// apart from the rest of those under [`OPERATOR_PREFIX`]. None of them may
+/// change anything, since the published service config retries them.
+pub(crate) const READ_METHODS: &[&str] = &[
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/Get",
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/List",
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/Search",
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/ListByTag",
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/ListUnsubscribeReasons",
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/GetStats",
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/GetGrowthTimeSeries",
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/ListConsents",
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/GetPreferences",
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/ListAttributeDefinitions",
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/GetAttributes",
+    "/infrastructure.rpc.campaign.v1.CampaignService/List",
+    "/infrastructure.rpc.campaign.v1.CampaignService/GetEngagement",
+    "/infrastructure.rpc.campaign.v1.CampaignService/ListLinkEngagement",
+    "/infrastructure.rpc.campaign.v1.CampaignService/GetDomainStats",
+    "/infrastructure.rpc.campaign.v1.CampaignService/EstimateAudience",
+    "/infrastructure.rpc.campaign.v1.CampaignService/GetSendingDomain",
+    "/infrastructure.rpc.template.v1.TemplateService/Get",
+    "/infrastructure.rpc.template.v1.TemplateService/List",
+    "/infrastructure.rpc.template.v1.TemplateService/Render",
+    "/infrastructure.rpc.template.v1.TemplateService/ValidateTemplate",
+    "/infrastructure.rpc.template.v1.TemplateService/ListTranslations",
+    "/infrastructure.rpc.template.v1.TemplateService/GetAsset",
+    // Lets deploy checks confirm the release without an operator key
+    "/infrastructure.rpc.admin.v1.AdminService/GetVersion",
+];
+
+/// Services reachable without a key
+const PUBLIC_PREFIXES: &[&str] = &["/grpc.reflection.", "/grpc.health."];
+
+/// Runtime operations on the process itself, which only operator keys may call
+const OPERATOR_PREFIX: &str = "/infrastructure.rpc.admin.";
+
+/// Scope needed to call the method at `path`, or `None` for public methods
+pub fn required_scope(path: &str) -> Option<Scope> {
+    if PUBLIC_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+        return None;
+    }
+
+    if READ_METHODS.contains(&path) {
+        Some(Scope::Read)
+    } else if path.starts_with(OPERATOR_PREFIX) {
+        Some(Scope::Operator)
+    } else {
+        Some(Scope::Admin)
+    }
+}
+
+/// Take the key from `authorization: Bearer <key>`
+fn bearer_token<B>(req: &http::Request<B>) -> Option<String> {
+    let value = req.headers().get(http::header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then(|| token.to_string())
+}
+
+/// Tower layer authenticating every call with an API key and enforcing the
+/// scope of the called method. The authenticated
+/// [`ApiKey`](crate::domain::auth::ApiKey) is added to the request extensions.
+#[derive(Clone)]
+pub struct AuthLayer {
+    service: Arc<dyn AuthService>,
+}
+
+impl AuthLayer {
+    pub fn new(service: Arc<dyn AuthService>) -> Self {
+        Self { service }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthMiddleware {
+            inner,
+            service: self.service.clone(),
+        }
+    }
+}
+
+/// Service produced by [`AuthLayer`]
+#[derive(Clone)]
+pub struct AuthMiddleware<S> {
+    inner: S,
+    service: Arc<dyn AuthService>,
+}
+
+impl<S, B> Service<http::Request<B>> for AuthMiddleware<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<tonic::body::Body>>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        // Use the instance that was polled ready and leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let service = self.service.clone();
+
+        let path = req.uri().path().to_string();
+        let token = bearer_token(&req);
+
+        Box::pin(async move {
+            let Some(required) = required_scope(&path) else {
+                return inner.call(req).await;
+            };
+
+            let Some(token) = token else {
+                return Ok(ErrorReason::ApiKeyMissing.status("missing bearer api key").into_http());
+            };
+
+            let api_key = match service.authenticate(&token).await {
+                Ok(Some(api_key)) => api_key,
+                Ok(None) => {
+                    info!(method = %path, "Rejected unknown api key");
+                    return Ok(ErrorReason::ApiKeyInvalid.status("invalid api key").into_http());
+                }
+                Err(e) => {
+                    error!(method = %path, error = %e, "Failed to authenticate api key");
+                    return Ok(ErrorReason::AuthUnavailable.status("authentication is unavailable").into_http());
+                }
+            };
+
+            if !api_key.scope.allows(required) {
+                info!(method = %path, key = %api_key.name, scope = %api_key.scope, "Rejected api key without the required scope");
+                return Ok(ErrorReason::ScopeInsufficient.status(format!(
+                    "api key scope {} does not allow {path}",
+                    api_key.scope
+                ))
+                .into_http());
+            }
+
+            req.extensions_mut().insert(api_key);
+            inner.call(req).await
+        })
+    }
+}
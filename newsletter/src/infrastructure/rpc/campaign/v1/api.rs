@@ -0,0 +1,515 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, instrument};
+
+use crate::domain::campaign::audience::AudienceFilter;
+use crate::domain::campaign::sending_domain::{self as sending, NewSendingDomain, SendingDomainError};
+use crate::domain::campaign::test_send::TestSend;
+use crate::domain::campaign::{self as domain, CampaignError};
+use crate::domain::locale;
+use crate::domain::newsletter::Tag;
+use crate::domain::pagination::PageRequest;
+use crate::infrastructure::rpc::errors::ErrorReason;
+use crate::infrastructure::rpc::{json, timestamp};
+use crate::infrastructure::rpc::validation::{invalid_field, parse_page_token};
+use crate::service::campaign::sending_domains::SendingDomainService;
+use crate::service::campaign::CampaignService as CampaignServiceTrait;
+
+use crate::infrastructure::rpc::campaign::v1::proto::{
+    campaign_service_server::CampaignService, Campaign, CampaignStatus, CancelRequest,
+    CancelResponse, CreateRequest, CreateResponse, CreateSendingDomainRequest, CreateSendingDomainResponse,
+    DeleteSendingDomainRequest, DeleteSendingDomainResponse, DkimKeyStatus, DnsRecord, DomainStats, Engagement,
+    EstimateAudienceRequest, EstimateAudienceResponse, GetDomainStatsRequest, GetDomainStatsResponse, GetEngagementRequest,
+    GetEngagementResponse, GetSendingDomainRequest, GetSendingDomainResponse, LinkEngagement, ListLinkEngagementRequest,
+    ListLinkEngagementResponse, ListRequest, ListResponse, LocalSendHours, RotateDkimKeyRequest, RotateDkimKeyResponse,
+    ScheduleRequest, ScheduleResponse, SendTestEmailRequest, SendTestEmailResponse, SendWindow, SendingDomain,
+    UpdateRequest, UpdateResponse, VerifySendingDomainRequest, VerifySendingDomainResponse,
+};
+
+#[derive(Clone)]
+pub struct MyCampaignService<S: CampaignServiceTrait> {
+    service: Arc<S>,
+    sending_domains: Arc<dyn SendingDomainService>,
+}
+
+impl<S: CampaignServiceTrait> MyCampaignService<S> {
+    pub fn new(service: Arc<S>, sending_domains: Arc<dyn SendingDomainService>) -> Self {
+        Self { service, sending_domains }
+    }
+
+    fn to_proto(c: domain::Campaign) -> Campaign {
+        let status = match c.status {
+            domain::CampaignStatus::Draft => CampaignStatus::Draft,
+            domain::CampaignStatus::Scheduled => CampaignStatus::Scheduled,
+            domain::CampaignStatus::Sending => CampaignStatus::Sending,
+            domain::CampaignStatus::Sent => CampaignStatus::Sent,
+            domain::CampaignStatus::Cancelled => CampaignStatus::Cancelled,
+        };
+
+        Campaign {
+            id: c.id,
+            name: c.name,
+            subject: c.subject,
+            template_id: c.template_id,
+            status: status.into(),
+            send_window: c.send_window.map(|w| SendWindow {
+                start: Some(timestamp::to_proto(w.start)),
+                end: Some(timestamp::to_proto(w.end)),
+            }),
+            created_at: Some(timestamp::to_proto(c.created_at)),
+            updated_at: Some(timestamp::to_proto(c.updated_at)),
+            version: c.version,
+            local_send_hours: c.local_send_hours.map(|h| LocalSendHours {
+                start_hour: h.start,
+                end_hour: h.end,
+            }),
+        }
+    }
+
+    fn parse_local_send_hours(hours: Option<LocalSendHours>) -> Result<Option<domain::LocalSendHours>, Status> {
+        hours
+            .map(|h| domain::LocalSendHours::new(h.start_hour, h.end_hour))
+            .transpose()
+            .map_err(|e| invalid_field("local_send_hours", e.to_string()))
+    }
+
+    fn parse_send_window(window: Option<SendWindow>) -> Result<domain::SendWindow, Status> {
+        let window = window.ok_or_else(|| invalid_field("send_window", "is required"))?;
+        let start = window
+            .start
+            .ok_or_else(|| invalid_field("send_window.start", "is required"))?;
+        let end = window
+            .end
+            .ok_or_else(|| invalid_field("send_window.end", "is required"))?;
+
+        domain::SendWindow::new(
+            timestamp::from_proto("send_window.start", start)?,
+            timestamp::from_proto("send_window.end", end)?,
+        )
+        .map_err(|e| invalid_field("send_window", e.to_string()))
+    }
+
+    /// Map domain rule violations to precise status codes; anything else is internal.
+    fn to_status(operation: &str, e: anyhow::Error) -> Status {
+        match e.downcast_ref::<CampaignError>() {
+            Some(CampaignError::Validation(message)) => ErrorReason::InvalidRequest.status(message.clone()),
+            Some(err @ CampaignError::InvalidTransition { .. }) => {
+                ErrorReason::InvalidTransition.status(err.to_string())
+            }
+            Some(err @ CampaignError::VersionMismatch { .. }) => ErrorReason::VersionMismatch.status(err.to_string()),
+            Some(err @ CampaignError::RecipientNotAllowed(_)) => ErrorReason::RecipientNotAllowed.status(err.to_string()),
+            Some(err @ CampaignError::TemplateNotFound(_)) => ErrorReason::TemplateNotFound.status(err.to_string()),
+            None => ErrorReason::Internal.status(format!("service error ({operation}): {e}")),
+        }
+    }
+
+    /// Turn a request's segment conditions into a filter; empty ones match everyone
+    fn parse_audience(req: EstimateAudienceRequest) -> Result<AudienceFilter, Status> {
+        let EstimateAudienceRequest { tags, topics, active_within_days, domain } = req;
+        let tags = tags
+            .iter()
+            .map(|tag| Tag::parse(tag).map_err(|e| invalid_field("tags", e.to_string())))
+            .collect::<Result<_, _>>()?;
+        let topics = topics
+            .iter()
+            .map(|topic| match topic.trim() {
+                "" => Err(invalid_field("topics", "topic cannot be empty")),
+                topic => Ok(topic.to_string()),
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut filter = AudienceFilter { tags, topics, ..AudienceFilter::default() };
+        if active_within_days != 0 {
+            filter = filter
+                .engaged_within(i64::from(active_within_days), chrono::Utc::now())
+                .map_err(|e| invalid_field("active_within_days", e.to_string()))?;
+        }
+        if !domain.trim().is_empty() {
+            filter = filter.at_domain(&domain).map_err(|e| invalid_field("domain", e.to_string()))?;
+        }
+        Ok(filter)
+    }
+
+    /// The domain with its DNS records; private keys stay in the service
+    fn sending_domain_to_proto(d: sending::SendingDomain) -> SendingDomain {
+        let dns_records = d
+            .dns_records()
+            .into_iter()
+            .map(|record| DnsRecord {
+                name: record.name,
+                r#type: record.kind.to_string(),
+                value: record.value,
+                status: match record.status {
+                    sending::DkimKeyStatus::Pending => DkimKeyStatus::Pending,
+                    sending::DkimKeyStatus::Active => DkimKeyStatus::Active,
+                }
+                .into(),
+            })
+            .collect();
+
+        SendingDomain {
+            domain: d.domain,
+            from_address: d.from_address,
+            created_at: Some(timestamp::to_proto(d.created_at)),
+            verified_at: d.verified_at.map(timestamp::to_proto),
+            dns_records,
+        }
+    }
+
+    fn sending_domain_status(operation: &str, e: anyhow::Error) -> Status {
+        match e.downcast_ref::<SendingDomainError>() {
+            Some(SendingDomainError::Validation(message)) => ErrorReason::InvalidRequest.status(message.clone()),
+            Some(err @ SendingDomainError::AlreadyExists(_)) => ErrorReason::Conflict.status(err.to_string()),
+            Some(err @ SendingDomainError::NotFound) => ErrorReason::SendingDomainNotFound.status(err.to_string()),
+            None => ErrorReason::Internal.status(format!("service error ({operation}): {e}")),
+        }
+    }
+
+    fn found(id: i64, campaign: Option<domain::Campaign>) -> Result<Campaign, Status> {
+        campaign
+            .map(Self::to_proto)
+            .ok_or_else(|| ErrorReason::CampaignNotFound.status(format!("campaign {id} not found")))
+    }
+}
+
+#[async_trait]
+impl<S: CampaignServiceTrait + 'static> CampaignService for MyCampaignService<S> {
+    #[instrument(skip(self), fields(name = %req.get_ref().name))]
+    async fn create(&self, req: Request<CreateRequest>) -> Result<Response<CreateResponse>, Status> {
+        let CreateRequest {
+            name,
+            subject,
+            template_id,
+            local_send_hours,
+        } = req.into_inner();
+        let campaign = domain::NewCampaign {
+            local_send_hours: Self::parse_local_send_hours(local_send_hours)?,
+            ..domain::NewCampaign::new(name, subject, template_id)
+        };
+
+        match self.service.create_campaign(campaign).await {
+            Ok(campaign) => {
+                info!(operation = "create", crud_operation = "CREATE", entity = "campaign", id = campaign.id, "Successfully created campaign");
+                Ok(Response::new(CreateResponse {
+                    campaign: Some(Self::to_proto(campaign)),
+                }))
+            }
+            Err(e) => {
+                error!(operation = "create", crud_operation = "CREATE", entity = "campaign", error = %e, "Failed to create campaign");
+                Err(Self::to_status("create_campaign", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(id = req.get_ref().id))]
+    async fn update(&self, req: Request<UpdateRequest>) -> Result<Response<UpdateResponse>, Status> {
+        let UpdateRequest {
+            id,
+            name,
+            subject,
+            template_id,
+            expected_version,
+            local_send_hours,
+            clear_local_send_hours,
+        } = req.into_inner();
+        if clear_local_send_hours && local_send_hours.is_some() {
+            return Err(invalid_field("clear_local_send_hours", "cannot be combined with local_send_hours"));
+        }
+        let local_send_hours = match Self::parse_local_send_hours(local_send_hours)? {
+            Some(hours) => Some(Some(hours)),
+            None => clear_local_send_hours.then_some(None),
+        };
+
+        let update = domain::CampaignUpdate {
+            name,
+            subject,
+            template_id,
+            local_send_hours,
+            expected_version,
+        };
+        match self.service.update_campaign(id, update).await {
+            Ok(campaign) => Ok(Response::new(UpdateResponse {
+                campaign: Some(Self::found(id, campaign)?),
+            })),
+            Err(e) => {
+                error!(operation = "update", crud_operation = "UPDATE", entity = "campaign", id = id, error = %e, "Failed to update campaign");
+                Err(Self::to_status("update_campaign", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(id = req.get_ref().id))]
+    async fn schedule(&self, req: Request<ScheduleRequest>) -> Result<Response<ScheduleResponse>, Status> {
+        let ScheduleRequest { id, send_window } = req.into_inner();
+        let window = Self::parse_send_window(send_window)?;
+
+        match self.service.schedule_campaign(id, window).await {
+            Ok(campaign) => Ok(Response::new(ScheduleResponse {
+                campaign: Some(Self::found(id, campaign)?),
+            })),
+            Err(e) => {
+                error!(operation = "schedule", crud_operation = "UPDATE", entity = "campaign", id = id, error = %e, "Failed to schedule campaign");
+                Err(Self::to_status("schedule_campaign", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(page_size = req.get_ref().page_size))]
+    async fn list(&self, req: Request<ListRequest>) -> Result<Response<ListResponse>, Status> {
+        let ListRequest { page_size, page_token } = req.into_inner();
+        let page = PageRequest::new(i64::from(page_size), parse_page_token(&page_token)?);
+
+        let page = match self.service.list_campaigns(page).await {
+            Ok(page) => page,
+            Err(e) => {
+                error!(operation = "list", crud_operation = "READ", entity = "campaign", error = %e, "Failed to retrieve campaign list");
+                return Err(Self::to_status("list_campaigns", e));
+            }
+        };
+
+        Ok(Response::new(ListResponse {
+            campaigns: page.items.into_iter().map(Self::to_proto).collect(),
+            next_page_token: page.next_cursor.map(|c| c.to_string()).unwrap_or_default(),
+        }))
+    }
+
+    #[instrument(skip(self), fields(id = req.get_ref().id))]
+    async fn cancel(&self, req: Request<CancelRequest>) -> Result<Response<CancelResponse>, Status> {
+        let id = req.into_inner().id;
+
+        match self.service.cancel_campaign(id).await {
+            Ok(campaign) => Ok(Response::new(CancelResponse {
+                campaign: Some(Self::found(id, campaign)?),
+            })),
+            Err(e) => {
+                error!(operation = "cancel", crud_operation = "UPDATE", entity = "campaign", id = id, error = %e, "Failed to cancel campaign");
+                Err(Self::to_status("cancel_campaign", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(id = req.get_ref().id))]
+    async fn get_engagement(&self, req: Request<GetEngagementRequest>) -> Result<Response<GetEngagementResponse>, Status> {
+        let id = req.into_inner().id;
+
+        let stats = match self.service.campaign_engagement(id).await {
+            Ok(Some(stats)) => stats,
+            Ok(None) => return Err(ErrorReason::CampaignNotFound.status(format!("campaign {id} not found"))),
+            Err(e) => {
+                error!(operation = "get_engagement", crud_operation = "READ", entity = "campaign", id = id, error = %e, "Failed to get campaign engagement");
+                return Err(Self::to_status("campaign_engagement", e));
+            }
+        };
+
+        Ok(Response::new(GetEngagementResponse {
+            engagement: Some(Engagement {
+                sent: stats.sent,
+                opens: stats.opens,
+                unique_opens: stats.unique_opens,
+                clicks: stats.clicks,
+                unique_clicks: stats.unique_clicks,
+                open_rate: stats.open_rate(),
+                click_rate: stats.click_rate(),
+                complaints: stats.complaints,
+                complaint_rate: stats.complaint_rate(),
+            }),
+        }))
+    }
+
+    #[instrument(skip(self), fields(id = req.get_ref().id))]
+    async fn list_link_engagement(&self, req: Request<ListLinkEngagementRequest>) -> Result<Response<ListLinkEngagementResponse>, Status> {
+        let id = req.into_inner().id;
+
+        let links = match self.service.link_engagement(id).await {
+            Ok(Some(links)) => links,
+            Ok(None) => return Err(ErrorReason::CampaignNotFound.status(format!("campaign {id} not found"))),
+            Err(e) => {
+                error!(operation = "list_link_engagement", crud_operation = "READ", entity = "campaign", id = id, error = %e, "Failed to list link engagement");
+                return Err(Self::to_status("link_engagement", e));
+            }
+        };
+
+        Ok(Response::new(ListLinkEngagementResponse {
+            links: links
+                .into_iter()
+                .map(|link| LinkEngagement {
+                    url: link.url,
+                    clicks: link.clicks,
+                    unique_clicks: link.unique_clicks,
+                })
+                .collect(),
+        }))
+    }
+
+    #[instrument(skip(self))]
+    async fn get_domain_stats(&self, req: Request<GetDomainStatsRequest>) -> Result<Response<GetDomainStatsResponse>, Status> {
+        let GetDomainStatsRequest { page_size, page_token } = req.into_inner();
+        let page = PageRequest::new(i64::from(page_size), parse_page_token(&page_token)?);
+
+        let page = match self.service.domain_stats(page).await {
+            Ok(page) => page,
+            Err(e) => {
+                error!(operation = "get_domain_stats", crud_operation = "READ", entity = "campaign", error = %e, "Failed to get domain stats");
+                return Err(Self::to_status("domain_stats", e));
+            }
+        };
+
+        Ok(Response::new(GetDomainStatsResponse {
+            domains: page
+                .items
+                .into_iter()
+                .map(|stats| DomainStats {
+                    bounce_rate: stats.bounce_rate(),
+                    open_rate: stats.open_rate(),
+                    domain: stats.domain,
+                    subscribers: stats.subscribers,
+                    sent: stats.sent,
+                    bounced: stats.bounced,
+                    unique_opens: stats.unique_opens,
+                })
+                .collect(),
+            next_page_token: page.next_cursor.map(|c| c.to_string()).unwrap_or_default(),
+        }))
+    }
+
+    #[instrument(skip(self))]
+    async fn estimate_audience(&self, req: Request<EstimateAudienceRequest>) -> Result<Response<EstimateAudienceResponse>, Status> {
+        let filter = Self::parse_audience(req.into_inner())?;
+
+        let estimate = match self.service.estimate_audience(filter).await {
+            Ok(estimate) => estimate,
+            Err(e) => {
+                error!(operation = "estimate_audience", crud_operation = "READ", entity = "campaign", error = %e, "Failed to estimate audience");
+                return Err(Self::to_status("estimate_audience", e));
+            }
+        };
+
+        Ok(Response::new(EstimateAudienceResponse {
+            recipients: estimate.recipients,
+            sample: estimate.sample,
+        }))
+    }
+
+    #[instrument(skip(self, req), fields(id = req.get_ref().id, recipients = req.get_ref().recipients.len()))]
+    async fn send_test_email(&self, req: Request<SendTestEmailRequest>) -> Result<Response<SendTestEmailResponse>, Status> {
+        let SendTestEmailRequest {
+            id,
+            recipients,
+            email,
+            attributes,
+            locale,
+        } = req.into_inner();
+        let locale = Some(locale.trim()).filter(|l| !l.is_empty());
+        if locale.is_some_and(|l| !locale::is_valid(l)) {
+            return Err(invalid_field("locale", "must be a BCP 47 tag such as en-US"));
+        }
+        let test = TestSend {
+            recipients: recipients.into_iter().map(|r| r.trim().to_string()).collect(),
+            email: Some(email.trim().to_string()).filter(|e| !e.is_empty()),
+            attributes: attributes
+                .unwrap_or_default()
+                .fields
+                .into_iter()
+                .map(|(key, value)| (key, json::value_to_json(value)))
+                .collect(),
+            locale: locale.map(str::to_string),
+        };
+
+        match self.service.send_test_email(id, test).await {
+            Ok(Some(outcome)) => {
+                info!(operation = "send_test_email", entity = "campaign", campaign_id = id, sent = outcome.sent_to.len(), "Successfully sent campaign test email");
+                Ok(Response::new(SendTestEmailResponse {
+                    subject: outcome.subject,
+                    html: outcome.html,
+                    locale: outcome.locale.unwrap_or_default(),
+                    sent_to: outcome.sent_to,
+                }))
+            }
+            Ok(None) => Err(ErrorReason::CampaignNotFound.status(format!("campaign {id} not found"))),
+            Err(e) => {
+                error!(operation = "send_test_email", entity = "campaign", campaign_id = id, error = %e, "Failed to send campaign test email");
+                Err(Self::to_status("send_test_email", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(domain = %req.get_ref().domain))]
+    async fn create_sending_domain(&self, req: Request<CreateSendingDomainRequest>) -> Result<Response<CreateSendingDomainResponse>, Status> {
+        let CreateSendingDomainRequest { domain, from_address } = req.into_inner();
+        let domain = NewSendingDomain::parse(&domain, &from_address)
+            .map_err(|e| ErrorReason::InvalidRequest.status(e.to_string()))?;
+
+        match self.sending_domains.create_sending_domain(domain).await {
+            Ok(domain) => {
+                info!(operation = "create_sending_domain", crud_operation = "CREATE", entity = "sending_domain", domain = %domain.domain, "Successfully created sending domain");
+                Ok(Response::new(CreateSendingDomainResponse {
+                    sending_domain: Some(Self::sending_domain_to_proto(domain)),
+                }))
+            }
+            Err(e) => {
+                error!(operation = "create_sending_domain", crud_operation = "CREATE", entity = "sending_domain", error = %e, "Failed to create sending domain");
+                Err(Self::sending_domain_status("create_sending_domain", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self, _req))]
+    async fn get_sending_domain(&self, _req: Request<GetSendingDomainRequest>) -> Result<Response<GetSendingDomainResponse>, Status> {
+        match self.sending_domains.get_sending_domain().await {
+            Ok(Some(domain)) => Ok(Response::new(GetSendingDomainResponse {
+                sending_domain: Some(Self::sending_domain_to_proto(domain)),
+            })),
+            Ok(None) => Err(Self::sending_domain_status("get_sending_domain", SendingDomainError::NotFound.into())),
+            Err(e) => {
+                error!(operation = "get_sending_domain", crud_operation = "READ", entity = "sending_domain", error = %e, "Failed to get sending domain");
+                Err(Self::sending_domain_status("get_sending_domain", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self, _req))]
+    async fn verify_sending_domain(&self, _req: Request<VerifySendingDomainRequest>) -> Result<Response<VerifySendingDomainResponse>, Status> {
+        match self.sending_domains.verify_sending_domain().await {
+            Ok(verification) => Ok(Response::new(VerifySendingDomainResponse {
+                sending_domain: Some(Self::sending_domain_to_proto(verification.domain)),
+                verified: verification.verified,
+            })),
+            Err(e) => {
+                error!(operation = "verify_sending_domain", crud_operation = "UPDATE", entity = "sending_domain", error = %e, "Failed to verify sending domain");
+                Err(Self::sending_domain_status("verify_sending_domain", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self, _req))]
+    async fn rotate_dkim_key(&self, _req: Request<RotateDkimKeyRequest>) -> Result<Response<RotateDkimKeyResponse>, Status> {
+        match self.sending_domains.rotate_dkim_key().await {
+            Ok(domain) => {
+                info!(operation = "rotate_dkim_key", crud_operation = "CREATE", entity = "dkim_key", domain = %domain.domain, "Successfully rotated DKIM key");
+                Ok(Response::new(RotateDkimKeyResponse {
+                    sending_domain: Some(Self::sending_domain_to_proto(domain)),
+                }))
+            }
+            Err(e) => {
+                error!(operation = "rotate_dkim_key", crud_operation = "CREATE", entity = "dkim_key", error = %e, "Failed to rotate DKIM key");
+                Err(Self::sending_domain_status("rotate_dkim_key", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self, _req))]
+    async fn delete_sending_domain(&self, _req: Request<DeleteSendingDomainRequest>) -> Result<Response<DeleteSendingDomainResponse>, Status> {
+        match self.sending_domains.delete_sending_domain().await {
+            Ok(true) => {
+                info!(operation = "delete_sending_domain", crud_operation = "DELETE", entity = "sending_domain", "Successfully deleted sending domain");
+                Ok(Response::new(DeleteSendingDomainResponse {}))
+            }
+            Ok(false) => Err(Self::sending_domain_status("delete_sending_domain", SendingDomainError::NotFound.into())),
+            Err(e) => {
+                error!(operation = "delete_sending_domain", crud_operation = "DELETE", entity = "sending_domain", error = %e, "Failed to delete sending domain");
+                Err(Self::sending_domain_status("delete_sending_domain", e))
+            }
+        }
+    }
+}
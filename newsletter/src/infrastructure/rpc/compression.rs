@@ -0,0 +1,33 @@
+use prost::Message;
+use serde::Deserialize;
+use tonic::codec::CompressionEncoding;
+use tonic::Response;
+
+/// Message compression the newsletter service accepts and answers with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Codec {
+    Gzip,
+    Zstd,
+}
+
+impl Codec {
+    pub fn encoding(self) -> CompressionEncoding {
+        match self {
+            Codec::Gzip => CompressionEncoding::Gzip,
+            Codec::Zstd => CompressionEncoding::Zstd,
+        }
+    }
+}
+
+/// Send `message` uncompressed when it is shorter than `min_bytes`; the
+/// codec's framing would outweigh the saving. Larger replies are compressed
+/// when the client asked for a codec the server enables.
+pub fn reply<T: Message>(message: T, min_bytes: usize) -> Response<T> {
+    let small = message.encoded_len() < min_bytes;
+    let mut response = Response::new(message);
+    if small {
+        response.disable_compression();
+    }
+    response
+}
@@ -0,0 +1,157 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tonic::Status;
+use tower::{Layer, Service};
+
+/// Metadata carrying the client's deadline, relative to when the call was sent
+pub const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// Calls cut off at their deadline since the last [`Deadlines::take_counts`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeadlineCounts {
+    /// Calls that ran into the deadline the client set
+    pub client: u64,
+    /// Calls that ran into the server's maximum
+    pub server: u64,
+}
+
+/// Counts calls cut off at their deadline
+#[derive(Clone, Default)]
+pub struct Deadlines {
+    client: Arc<AtomicU64>,
+    server: Arc<AtomicU64>,
+}
+
+impl Deadlines {
+    /// Counters since the previous call, which resets them
+    pub fn take_counts(&self) -> DeadlineCounts {
+        DeadlineCounts {
+            client: self.client.swap(0, Ordering::Relaxed),
+            server: self.server.swap(0, Ordering::Relaxed),
+        }
+    }
+
+    fn hit(&self, by_client: bool) {
+        let counter = if by_client { &self.client } else { &self.server };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Tower layer answering `DEADLINE_EXCEEDED` once a call runs past the
+/// client's `grpc-timeout` or the server's maximum, whichever comes first.
+///
+/// The handler is dropped at that point, and with it any query it was
+/// waiting on, which Postgres then cancels. Goes outside auth, tenant
+/// resolution and rate limiting so their lookups count against the deadline.
+#[derive(Clone)]
+pub struct DeadlineLayer {
+    max: Option<Duration>,
+    deadlines: Deadlines,
+}
+
+impl DeadlineLayer {
+    pub fn new(max: Option<Duration>, deadlines: Deadlines) -> Self {
+        Self { max, deadlines }
+    }
+}
+
+impl<S> Layer<S> for DeadlineLayer {
+    type Service = DeadlineMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DeadlineMiddleware {
+            inner,
+            max: self.max,
+            deadlines: self.deadlines.clone(),
+        }
+    }
+}
+
+/// Service produced by [`DeadlineLayer`]
+#[derive(Clone)]
+pub struct DeadlineMiddleware<S> {
+    inner: S,
+    max: Option<Duration>,
+    deadlines: Deadlines,
+}
+
+impl<S, B> Service<http::Request<B>> for DeadlineMiddleware<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<tonic::body::Body>>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        // Use the instance that was polled ready and leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let requested = req
+            .headers()
+            .get(GRPC_TIMEOUT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_timeout);
+        let (timeout, by_client) = match (requested, self.max) {
+            (Some(requested), Some(max)) if requested > max => (Some(max), false),
+            (Some(requested), _) => (Some(requested), true),
+            (None, max) => (max, false),
+        };
+        let deadlines = self.deadlines.clone();
+
+        Box::pin(async move {
+            let Some(timeout) = timeout else {
+                return inner.call(req).await;
+            };
+            match tokio::time::timeout(timeout, inner.call(req)).await {
+                Ok(response) => response,
+                Err(_) => {
+                    deadlines.hit(by_client);
+                    let whose = if by_client { "client" } else { "server" };
+                    Ok(Status::deadline_exceeded(format!(
+                        "call exceeded the {whose} deadline of {}ms",
+                        timeout.as_millis()
+                    ))
+                    .into_http())
+                }
+            }
+        })
+    }
+}
+
+/// A `grpc-timeout` value: up to eight digits and a unit, `H`ours,
+/// `M`inutes, `S`econds, `m`illis, `u`micros or `n`anos
+pub fn parse_timeout(value: &str) -> Option<Duration> {
+    if value.len() < 2 || value.len() > 9 {
+        return None;
+    }
+    let (digits, unit) = value.split_at(value.len() - 1);
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let amount: u64 = digits.parse().ok()?;
+    match unit {
+        "H" => Some(Duration::from_secs(amount * 60 * 60)),
+        "M" => Some(Duration::from_secs(amount * 60)),
+        "S" => Some(Duration::from_secs(amount)),
+        "m" => Some(Duration::from_millis(amount)),
+        "u" => Some(Duration::from_micros(amount)),
+        "n" => Some(Duration::from_nanos(amount)),
+        _ => None,
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use tonic::{Code, Status};
+use tonic_types::{ErrorDetails, StatusExt};
+
+/// Domain of every `google.rpc.ErrorInfo` the service returns
+pub const ERROR_DOMAIN: &str = "newsletter.shortlink.best";
+
+/// Machine-readable cause of a failed call, sent as the `reason` of a
+/// `google.rpc.ErrorInfo` detail so clients can branch without parsing the
+/// message. Each reason always comes with the same status code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorReason {
+    /// A field is malformed; a `BadRequest` detail names it when known
+    InvalidRequest,
+    SubscriptionNotFound,
+    ConfirmationTokenInvalid,
+    AlreadySubscribed,
+    /// The change collides with stored data, such as a defined attribute
+    Conflict,
+    AddressSuppressed,
+    CampaignNotFound,
+    TemplateNotFound,
+    AssetNotFound,
+    /// No asset store is configured, so assets cannot be uploaded or fetched
+    AssetStorageDisabled,
+    SendingDomainNotFound,
+    /// A test email was addressed outside the allow-listed internal addresses
+    RecipientNotAllowed,
+    /// The campaign or subscription status does not allow the operation
+    InvalidTransition,
+    /// The resource changed since the version the caller expected
+    VersionMismatch,
+    IdempotencyKeyInvalid,
+    IdempotencyKeyReused,
+    IdempotencyInProgress,
+    /// Comes with a `RetryInfo` detail
+    RateLimited,
+    /// The store could not keep up with an import; comes with a `RetryInfo`
+    /// detail and the records imported as `records_committed`
+    ImportOverloaded,
+    ApiKeyMissing,
+    ApiKeyInvalid,
+    AuthUnavailable,
+    /// The database keeps failing; comes with a `RetryInfo` detail
+    DatabaseUnavailable,
+    ScopeInsufficient,
+    TenantInvalid,
+    TenantMismatch,
+    Internal,
+}
+
+impl ErrorReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorReason::InvalidRequest => "INVALID_REQUEST",
+            ErrorReason::SubscriptionNotFound => "SUBSCRIPTION_NOT_FOUND",
+            ErrorReason::ConfirmationTokenInvalid => "CONFIRMATION_TOKEN_INVALID",
+            ErrorReason::AlreadySubscribed => "ALREADY_SUBSCRIBED",
+            ErrorReason::Conflict => "CONFLICT",
+            ErrorReason::AddressSuppressed => "ADDRESS_SUPPRESSED",
+            ErrorReason::CampaignNotFound => "CAMPAIGN_NOT_FOUND",
+            ErrorReason::TemplateNotFound => "TEMPLATE_NOT_FOUND",
+            ErrorReason::AssetNotFound => "ASSET_NOT_FOUND",
+            ErrorReason::AssetStorageDisabled => "ASSET_STORAGE_DISABLED",
+            ErrorReason::SendingDomainNotFound => "SENDING_DOMAIN_NOT_FOUND",
+            ErrorReason::RecipientNotAllowed => "RECIPIENT_NOT_ALLOWED",
+            ErrorReason::InvalidTransition => "INVALID_TRANSITION",
+            ErrorReason::VersionMismatch => "VERSION_MISMATCH",
+            ErrorReason::IdempotencyKeyInvalid => "IDEMPOTENCY_KEY_INVALID",
+            ErrorReason::IdempotencyKeyReused => "IDEMPOTENCY_KEY_REUSED",
+            ErrorReason::IdempotencyInProgress => "IDEMPOTENCY_IN_PROGRESS",
+            ErrorReason::RateLimited => "RATE_LIMITED",
+            ErrorReason::ImportOverloaded => "IMPORT_OVERLOADED",
+            ErrorReason::ApiKeyMissing => "API_KEY_MISSING",
+            ErrorReason::ApiKeyInvalid => "API_KEY_INVALID",
+            ErrorReason::AuthUnavailable => "AUTH_UNAVAILABLE",
+            ErrorReason::DatabaseUnavailable => "DATABASE_UNAVAILABLE",
+            ErrorReason::ScopeInsufficient => "SCOPE_INSUFFICIENT",
+            ErrorReason::TenantInvalid => "TENANT_INVALID",
+            ErrorReason::TenantMismatch => "TENANT_MISMATCH",
+            ErrorReason::Internal => "INTERNAL",
+        }
+    }
+
+    pub fn code(self) -> Code {
+        match self {
+            ErrorReason::InvalidRequest | ErrorReason::IdempotencyKeyInvalid | ErrorReason::TenantInvalid => {
+                Code::InvalidArgument
+            }
+            ErrorReason::SubscriptionNotFound
+            | ErrorReason::ConfirmationTokenInvalid
+            | ErrorReason::CampaignNotFound
+            | ErrorReason::TemplateNotFound
+            | ErrorReason::AssetNotFound
+            | ErrorReason::SendingDomainNotFound => Code::NotFound,
+            ErrorReason::AlreadySubscribed | ErrorReason::Conflict => Code::AlreadyExists,
+            ErrorReason::AddressSuppressed
+            | ErrorReason::InvalidTransition
+            | ErrorReason::IdempotencyKeyReused
+            | ErrorReason::AssetStorageDisabled => Code::FailedPrecondition,
+            ErrorReason::VersionMismatch | ErrorReason::IdempotencyInProgress => Code::Aborted,
+            ErrorReason::RateLimited | ErrorReason::ImportOverloaded => Code::ResourceExhausted,
+            ErrorReason::ApiKeyMissing | ErrorReason::ApiKeyInvalid => Code::Unauthenticated,
+            ErrorReason::AuthUnavailable | ErrorReason::DatabaseUnavailable => Code::Unavailable,
+            ErrorReason::ScopeInsufficient | ErrorReason::TenantMismatch | ErrorReason::RecipientNotAllowed => {
+                Code::PermissionDenied
+            }
+            ErrorReason::Internal => Code::Internal,
+        }
+    }
+
+    /// Details holding only this reason's `ErrorInfo`, for callers adding more
+    pub fn details(self) -> ErrorDetails {
+        ErrorDetails::with_error_info(self.as_str(), ERROR_DOMAIN, HashMap::new())
+    }
+
+    /// Status with this reason's code and `ErrorInfo`
+    pub fn status(self, message: impl Into<String>) -> Status {
+        self.status_with(message, self.details())
+    }
+
+    /// Status with this reason's code and the given details, which should
+    /// start from [`ErrorReason::details`]
+    pub fn status_with(self, message: impl Into<String>, details: ErrorDetails) -> Status {
+        Status::with_error_details(self.code(), message, details)
+    }
+}
@@ -0,0 +1,38 @@
+use prost::Message;
+use sha2::{Digest, Sha256};
+use tonic::Status;
+
+use crate::domain::idempotency::IdempotencyError;
+use crate::infrastructure::rpc::errors::ErrorReason;
+
+/// Metadata header carrying the client's idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "x-idempotency-key";
+
+/// Read the idempotency key sent with a request, if any
+pub fn key_from_request<T>(request: &tonic::Request<T>) -> Option<String> {
+    request
+        .metadata()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+/// Hex SHA-256 of the encoded request, used to spot a key reused for another request
+pub fn request_hash<M: Message>(message: &M) -> String {
+    Sha256::digest(message.encode_to_vec())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Map idempotency conflicts to status codes, or `None` for other errors
+pub fn to_status(e: &anyhow::Error) -> Option<Status> {
+    e.downcast_ref::<IdempotencyError>().map(|err| {
+        let reason = match err {
+            IdempotencyError::InvalidKey => ErrorReason::IdempotencyKeyInvalid,
+            IdempotencyError::Mismatch => ErrorReason::IdempotencyKeyReused,
+            IdempotencyError::InProgress => ErrorReason::IdempotencyInProgress,
+        };
+        reason.status(err.to_string())
+    })
+}
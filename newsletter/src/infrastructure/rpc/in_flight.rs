@@ -0,0 +1,93 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tower::{Layer, Service};
+
+/// Number of calls currently being handled
+#[derive(Clone, Default)]
+pub struct InFlight {
+    count: Arc<AtomicUsize>,
+}
+
+impl InFlight {
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    /// Count a call until the guard is dropped
+    fn enter(&self) -> InFlightGuard {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard {
+            count: self.count.clone(),
+        }
+    }
+}
+
+struct InFlightGuard {
+    count: Arc<AtomicUsize>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Tower layer counting the calls in flight, so shutdown can tell how many it
+/// drained and how many it cut off
+#[derive(Clone)]
+pub struct InFlightLayer {
+    in_flight: InFlight,
+}
+
+impl InFlightLayer {
+    pub fn new(in_flight: InFlight) -> Self {
+        Self { in_flight }
+    }
+}
+
+impl<S> Layer<S> for InFlightLayer {
+    type Service = InFlightMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        InFlightMiddleware {
+            inner,
+            in_flight: self.in_flight.clone(),
+        }
+    }
+}
+
+/// Service produced by [`InFlightLayer`]
+#[derive(Clone)]
+pub struct InFlightMiddleware<S> {
+    inner: S,
+    in_flight: InFlight,
+}
+
+impl<S, B> Service<http::Request<B>> for InFlightMiddleware<S>
+where
+    S: Service<http::Request<B>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let guard = self.in_flight.enter();
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let response = future.await;
+            drop(guard);
+            response
+        })
+    }
+}
@@ -1 +1,18 @@
-pub mod newsletter;
\ No newline at end of file
+pub mod access_log;
+pub mod admin;
+pub mod auth;
+pub mod campaign;
+pub mod compression;
+pub mod deadline;
+pub mod errors;
+pub mod idempotency;
+pub mod in_flight;
+pub mod json;
+pub mod newsletter;
+pub mod rate_limit;
+pub mod service_config;
+pub mod template;
+pub mod tenant;
+pub mod timestamp;
+pub mod tls;
+pub mod validation;
@@ -1 +1,38 @@
-pub mod v1;
\ No newline at end of file
+use tonic::{Request, Status};
+
+use crate::domain::newsletter::error::NewsletterError;
+use crate::infrastructure::rpc::errors::ErrorReason;
+use crate::infrastructure::rpc::idempotency;
+
+pub mod v1;
+pub mod v2;
+
+/// Address of the client behind a request, recorded with its consent. With
+/// `trust_forwarded_for` the first `x-forwarded-for` entry wins over the peer.
+pub(crate) fn client_ip<T>(req: &Request<T>, trust_forwarded_for: bool) -> Option<String> {
+    if trust_forwarded_for {
+        let forwarded = req
+            .metadata()
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .and_then(|v| v.trim().parse::<std::net::IpAddr>().ok());
+        if let Some(ip) = forwarded {
+            return Some(ip.to_string());
+        }
+    }
+
+    req.remote_addr().map(|addr| addr.ip().to_string())
+}
+
+/// Status of a call that went through the idempotency guard or another
+/// `anyhow` collaborator: its own conflicts first, then newsletter errors
+pub(crate) fn to_status(operation: &str, e: anyhow::Error) -> Status {
+    if let Some(status) = idempotency::to_status(&e) {
+        return status;
+    }
+    match e.downcast::<NewsletterError>() {
+        Ok(e) => Status::from(e),
+        Err(e) => ErrorReason::Internal.status(format!("service error ({operation}): {e}")),
+    }
+}
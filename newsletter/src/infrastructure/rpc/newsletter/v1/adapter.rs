@@ -0,0 +1,18 @@
+//! v1 calls served by the v2 handler
+
+use crate::infrastructure::rpc::newsletter::v1::proto::SubscribeRequest;
+use crate::infrastructure::rpc::newsletter::v2::proto as v2;
+
+impl From<SubscribeRequest> for v2::SubscribeRequest {
+    fn from(req: SubscribeRequest) -> Self {
+        v2::SubscribeRequest {
+            email: req.email,
+            source: req.source,
+            locale: req.locale,
+            topics: Vec::new(),
+            consent: Some(v2::Consent {
+                policy_version: req.consent_version,
+            }),
+        }
+    }
+}
@@ -1,201 +1,1246 @@
 use async_trait::async_trait;
-use tonic::{Request, Response, Status};
-use tracing::{info, error, instrument, Span};
+use chrono::{NaiveDate, Utc};
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{info, error, instrument};
+use std::collections::HashMap;
 use std::sync::Arc;
+use tonic_types::ErrorDetails;
 
-use crate::infrastructure::logging;
+use crate::domain::newsletter::attributes::{
+    AttributeDefinition as DomainAttributeDefinition, AttributeType as DomainAttributeType, Attributes,
+};
+use crate::domain::newsletter::consent::{self as consent, ConsentContext};
+use crate::domain::newsletter::error::NewsletterError;
+use crate::domain::newsletter::growth::{self, GrowthRange};
+use crate::domain::newsletter::lifecycle;
+use crate::domain::newsletter::mask::{NewsletterMask, PartialNewsletter};
+use crate::domain::newsletter::preferences::{
+    TopicPreference as DomainTopicPreference, TopicSubscription as DomainTopicSubscription,
+};
+use crate::domain::newsletter::preview::RejectedEntry;
+use crate::domain::newsletter::query::{NewsletterFilter, NewsletterOrder, NewsletterQuery};
+use crate::domain::newsletter::search::SearchQuery;
+use crate::domain::newsletter::unsubscribe::{self as unsubscribe, UnsubscribeFeedback, UnsubscribeEventFilter};
+use crate::domain::newsletter::{EmailAddress, Tag};
+use crate::domain::pagination::PageRequest;
+use crate::infrastructure::{logging, tenant};
+use crate::infrastructure::rpc::errors::{ErrorReason, ERROR_DOMAIN};
+use crate::infrastructure::rpc::newsletter::v2::api as v2;
+use crate::infrastructure::rpc::newsletter::{client_ip, to_status};
+use crate::infrastructure::rpc::validation::{invalid_field, parse_page_token, validate};
+use crate::infrastructure::rpc::{compression, idempotency, json, timestamp};
+use crate::service::idempotency::IdempotencyGuard;
+use crate::service::newsletter::import::{self as import, ImportError as ImportFailure, ImportThrottle, SubscriberImport};
 use crate::service::newsletter::NewsletterService as NewsletterServiceTrait;
 
 use crate::infrastructure::rpc::newsletter::v1::proto::{
-    newsletter_service_server::NewsletterService, DeleteRequest, GetRequest, GetResponse,
-    ListResponse, Newsletter, SubscribeRequest, UnSubscribeRequest, UpdateStatusRequest,
+    newsletter_service_server::NewsletterService, ActiveFilter, AttributeDefinition, AttributeType, ChangeEmailRequest,
+    ChangeEmailResponse, ConfirmEmailChangeRequest, ConfirmEmailChangeResponse, ConfirmRequest, ConfirmTopicRequest,
+    ConfirmTopicResponse,
+    DefineAttributeRequest, DefineAttributeResponse, GetAttributesRequest, GetAttributesResponse,
+    ListAttributeDefinitionsRequest, ListAttributeDefinitionsResponse, SetAttributesRequest, SetAttributesResponse, SetLocaleRequest, SetLocaleResponse, SetTimezoneRequest, SetTimezoneResponse, ConfirmResponse, DeleteRequest,
+    ExportSubscriberDataRequest, ExportSubscriberDataResponse, GetSubscriberHistoryRequest, GetSubscriberHistoryResponse, GetPreferencesRequest, GetPreferencesResponse, GetRequest, GetResponse, ImportError,
+    ImportFormat, ImportSubscribersRequest, ImportSubscribersResponse, ListByTagRequest, ListFilter, ListRequest, ListResponse,
+    ListConsentsRequest, ListConsentsResponse, Consent, ConsentAction, GetStatsRequest, GetStatsResponse,
+    GetGrowthTimeSeriesRequest, GetGrowthTimeSeriesResponse, Granularity, GrowthPoint,
+    ListUnsubscribeReasonsRequest, ListUnsubscribeReasonsResponse, Newsletter, PendingConfirmation,
+    ReasonCount, RefreshDisposableDomainsRequest, RefreshDisposableDomainsResponse, SearchRequest, SearchResponse, SearchResult, SetPreferencesRequest, SetPreferencesResponse, SubscribeRequest, SubscriberEvent, SubscriberMerge, SubscriberTag, Subscription, TagSubscribersRequest, TagSubscribersResponse, UnSubscribeRequest,
+    Topic, TopicSubscription, UnsubscribeEvent, UnsubscribeReason, UntagSubscribersRequest, UntagSubscribersResponse,
+    SubscriptionStatus, UpdateStatusRequest, UpdateStatusResponse, DeleteResponse, BulkError,
 };
 
+/// The single mapping from newsletter failures to gRPC codes and error
+/// reasons; only store failures surface as `internal`
+impl From<NewsletterError> for Status {
+    fn from(e: NewsletterError) -> Self {
+        if let NewsletterError::Unavailable(retry_after) = e {
+            let mut details = ErrorReason::DatabaseUnavailable.details();
+            details.set_retry_info(Some(retry_after));
+            return ErrorReason::DatabaseUnavailable.status_with(e.to_string(), details);
+        }
+        let reason = match e {
+            NewsletterError::NotFound(_) => ErrorReason::SubscriptionNotFound,
+            NewsletterError::AlreadySubscribed(_) => ErrorReason::AlreadySubscribed,
+            NewsletterError::Conflict(_) => ErrorReason::Conflict,
+            NewsletterError::Suppressed(_) => ErrorReason::AddressSuppressed,
+            NewsletterError::Validation(_) => ErrorReason::InvalidRequest,
+            NewsletterError::InvalidTransition(_) => ErrorReason::InvalidTransition,
+            NewsletterError::Database(_) => ErrorReason::Internal,
+            NewsletterError::Unavailable(_) => ErrorReason::DatabaseUnavailable,
+        };
+        reason.status(e.to_string())
+    }
+}
+
+/// gRPC adapter over the newsletter service; every call goes through the
+/// service layer so its validation and side effects apply on the RPC path.
+///
+/// Addresses, tags and batch sizes are checked first, so a bad request fails
+/// with field-level `BadRequest` details without reaching the database.
+///
+/// Subscribe and UnSubscribe honour an `x-idempotency-key` header: a retried
+/// call with the same key gets the first result back instead of running again.
+/// Subscribe is served by the v2 handler.
 #[derive(Clone)]
-pub struct MyNewsletterService<S: NewsletterServiceTrait> {
-    service: Arc<S>,
+pub struct MyNewsletterService {
+    service: Arc<dyn NewsletterServiceTrait>,
+    idempotency: IdempotencyGuard,
+    subscriptions: v2::MyNewsletterService,
+    /// Fail Subscribe of an active address with ALREADY_EXISTS and UnSubscribe
+    /// of an unknown one with NOT_FOUND; both succeed silently otherwise
+    strict_status_codes: bool,
+    /// Take the IP recorded with a consent from `x-forwarded-for`
+    trust_forwarded_for: bool,
+    /// Replies shorter than this are sent uncompressed
+    compression_min_bytes: usize,
+    /// Paces the batches of ImportSubscribers; unbounded without
+    import_throttle: Option<ImportThrottle>,
 }
 
-impl<S: NewsletterServiceTrait> MyNewsletterService<S> {
-    pub fn new(service: Arc<S>) -> Self {
-        Self { service }
+impl MyNewsletterService {
+    pub fn new(service: Arc<dyn NewsletterServiceTrait>, idempotency: IdempotencyGuard) -> Self {
+        Self {
+            subscriptions: v2::MyNewsletterService::new(service.clone(), idempotency.clone()),
+            service,
+            idempotency,
+            strict_status_codes: false,
+            trust_forwarded_for: false,
+            compression_min_bytes: 0,
+            import_throttle: None,
+        }
+    }
+
+    pub fn with_import_throttle(mut self, throttle: ImportThrottle) -> Self {
+        self.import_throttle = Some(throttle);
+        self
+    }
+
+    pub fn with_strict_status_codes(mut self, strict: bool) -> Self {
+        self.strict_status_codes = strict;
+        self.subscriptions = self.subscriptions.with_strict_status_codes(strict);
+        self
+    }
+
+    pub fn with_trust_forwarded_for(mut self, trust: bool) -> Self {
+        self.trust_forwarded_for = trust;
+        self.subscriptions = self.subscriptions.with_trust_forwarded_for(trust);
+        self
+    }
+
+    pub fn with_compression_min_bytes(mut self, min_bytes: usize) -> Self {
+        self.compression_min_bytes = min_bytes;
+        self
+    }
+
+    fn reply<T: prost::Message>(&self, message: T) -> Response<T> {
+        compression::reply(message, self.compression_min_bytes)
     }
 
     fn to_proto(n: crate::domain::newsletter::Newsletter) -> Newsletter {
         Newsletter {
             field_mask: None,
+            active: n.is_active(),
+            status: Self::status_to_proto(Some(n.status)),
             email: n.email,
-            active: n.active,
+            created_at: None,
+            locale: String::new(),
+            timezone: String::new(),
+            resubscribed_at: None,
+        }
+    }
+
+    /// Resolve a request's `read_mask`; an unset mask selects every field.
+    fn parse_read_mask(mask: Option<prost_types::FieldMask>) -> Result<NewsletterMask, Status> {
+        let paths = mask.map(|mask| mask.paths).unwrap_or_default();
+        NewsletterMask::from_paths(&paths).map_err(|e| invalid_field("read_mask", e.to_string()))
+    }
+
+    fn parse_query(filter: Option<ListFilter>, order_by: &str) -> Result<NewsletterQuery, Status> {
+        let filter = filter.unwrap_or_default();
+        let invalid = |field: &'static str| {
+            move |e: crate::domain::newsletter::query::InvalidQuery| invalid_field(field, e.to_string())
+        };
+
+        let active = match ActiveFilter::try_from(filter.active) {
+            Ok(ActiveFilter::Unspecified) => None,
+            Ok(ActiveFilter::Active) => Some(true),
+            Ok(ActiveFilter::Inactive) => Some(false),
+            Err(_) => return Err(invalid_field("filter.active", format!("unknown value {}", filter.active))),
+        };
+
+        Ok(NewsletterQuery {
+            filter: NewsletterFilter {
+                active,
+                status: Self::parse_status("filter.status", filter.status)?,
+                email_prefix: NewsletterFilter::email_prefix(&filter.email_prefix).map_err(invalid("filter.email_prefix"))?,
+                email_domain: NewsletterFilter::email_domain(&filter.email_domain).map_err(invalid("filter.email_domain"))?,
+                created_since: filter.created_since.map(|t| timestamp::from_proto("created_since", t)).transpose()?,
+                created_until: filter.created_until.map(|t| timestamp::from_proto("created_until", t)).transpose()?,
+                attributes: Self::attributes_from_proto(filter.attributes),
+            },
+            order: NewsletterOrder::parse(order_by).map_err(invalid("order_by"))?,
+        })
+    }
+
+    fn attributes_from_proto(attributes: Option<prost_types::Struct>) -> Attributes {
+        match json::struct_to_json(attributes.unwrap_or_default()) {
+            serde_json::Value::Object(attributes) => attributes,
+            _ => Attributes::new(),
+        }
+    }
+
+    fn attribute_definition_to_proto(d: DomainAttributeDefinition) -> AttributeDefinition {
+        let kind = match d.kind {
+            DomainAttributeType::String => AttributeType::String,
+            DomainAttributeType::Number => AttributeType::Number,
+            DomainAttributeType::Boolean => AttributeType::Boolean,
+        };
+        AttributeDefinition {
+            key: d.key,
+            kind: kind as i32,
+            description: d.description,
+        }
+    }
+
+    fn topics_to_proto(topics: Vec<DomainTopicSubscription>) -> Vec<TopicSubscription> {
+        topics
+            .into_iter()
+            .map(|t| TopicSubscription {
+                topic: Some(Topic {
+                    key: t.topic.key,
+                    name: t.topic.name,
+                    description: t.topic.description,
+                    requires_confirmation: t.topic.requires_confirmation,
+                }),
+                subscribed: t.subscribed,
+            })
+            .collect()
+    }
+
+    fn partial_to_proto(n: PartialNewsletter, mask: NewsletterMask) -> Newsletter {
+        Newsletter {
+            field_mask: Some(prost_types::FieldMask { paths: mask.paths() }),
+            email: n.email.unwrap_or_default(),
+            active: n.active.unwrap_or_default(),
+            status: Self::status_to_proto(n.status),
+            created_at: n.created_at.map(timestamp::to_proto),
+            locale: n.locale.unwrap_or_default(),
+            timezone: n.timezone.unwrap_or_default(),
+            resubscribed_at: n.resubscribed_at.map(timestamp::to_proto),
+        }
+    }
+
+    /// Reject malformed addresses before they reach the service or the database.
+    fn parse_email(field: &str, value: &str) -> Result<EmailAddress, Status> {
+        EmailAddress::parse(value).map_err(|e| invalid_field(field, e.to_string()))
+    }
+
+    fn parse_emails(field: &str, values: Vec<String>) -> Result<Vec<EmailAddress>, Status> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| Self::parse_email(&format!("{field}[{i}]"), v))
+            .collect()
+    }
+
+    /// The valid addresses of a dry run, and the malformed ones as errors
+    fn partition_emails(values: Vec<String>) -> (Vec<EmailAddress>, Vec<BulkError>) {
+        let mut emails = Vec::with_capacity(values.len());
+        let mut errors = Vec::new();
+        for value in values {
+            match EmailAddress::parse(&value) {
+                Ok(email) => emails.push(email),
+                Err(e) => errors.push(BulkError {
+                    email: value,
+                    reason: e.to_string(),
+                }),
+            }
+        }
+        (emails, errors)
+    }
+
+    fn bulk_errors(invalid: Vec<BulkError>, rejected: Vec<RejectedEntry>) -> Vec<BulkError> {
+        invalid
+            .into_iter()
+            .chain(rejected.into_iter().map(|entry| BulkError {
+                email: entry.email,
+                reason: entry.reason,
+            }))
+            .collect()
+    }
+
+    fn parse_tag(field: &str, value: &str) -> Result<Tag, Status> {
+        Tag::parse(value).map_err(|e| invalid_field(field, e.to_string()))
+    }
+
+    /// UNSPECIFIED means "no reason"; values this build does not know are rejected.
+    fn parse_reason(value: i32) -> Result<Option<unsubscribe::UnsubscribeReason>, Status> {
+        match UnsubscribeReason::try_from(value) {
+            Ok(UnsubscribeReason::Unspecified) => Ok(None),
+            Ok(UnsubscribeReason::TooFrequent) => Ok(Some(unsubscribe::UnsubscribeReason::TooFrequent)),
+            Ok(UnsubscribeReason::NotRelevant) => Ok(Some(unsubscribe::UnsubscribeReason::NotRelevant)),
+            Ok(UnsubscribeReason::NeverSubscribed) => {
+                Ok(Some(unsubscribe::UnsubscribeReason::NeverSubscribed))
+            }
+            Ok(UnsubscribeReason::Other) => Ok(Some(unsubscribe::UnsubscribeReason::Other)),
+            Err(_) => Err(invalid_field("reason", format!("unknown unsubscribe reason {value}"))),
+        }
+    }
+
+    fn parse_date(field: &str, value: &str) -> Result<NaiveDate, Status> {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| invalid_field(field, "must be a date as YYYY-MM-DD"))
+    }
+
+    fn parse_granularity(value: i32) -> Result<growth::Granularity, Status> {
+        match Granularity::try_from(value) {
+            Ok(Granularity::Unspecified | Granularity::Day) => Ok(growth::Granularity::Day),
+            Ok(Granularity::Week) => Ok(growth::Granularity::Week),
+            Ok(Granularity::Month) => Ok(growth::Granularity::Month),
+            Err(_) => Err(invalid_field("granularity", format!("unknown granularity {value}"))),
+        }
+    }
+
+    fn parse_import_format(value: i32) -> Result<Option<import::ImportFormat>, Status> {
+        match ImportFormat::try_from(value) {
+            Ok(ImportFormat::Unspecified) => Ok(None),
+            Ok(ImportFormat::Csv) => Ok(Some(import::ImportFormat::Csv)),
+            Ok(ImportFormat::Ndjson) => Ok(Some(import::ImportFormat::Ndjson)),
+            Err(_) => Err(invalid_field("format", format!("unknown import format {value}"))),
+        }
+    }
+
+    /// A malformed upload is a caller error; anything else maps as the service's errors do.
+    fn import_status(e: anyhow::Error) -> Status {
+        match e.downcast_ref::<ImportFailure>() {
+            Some(err @ ImportFailure::Overloaded { committed, retry_after }) => {
+                let mut details = ErrorDetails::with_error_info(
+                    ErrorReason::ImportOverloaded.as_str(),
+                    ERROR_DOMAIN,
+                    HashMap::from([("records_committed".to_string(), committed.to_string())]),
+                );
+                details.set_retry_info(Some(*retry_after));
+                ErrorReason::ImportOverloaded.status_with(err.to_string(), details)
+            }
+            Some(err) => invalid_field("chunk", err.to_string()),
+            None => to_status("import_subscribers", e),
+        }
+    }
+
+    /// UNSPECIFIED means "not set"; values this build does not know are rejected.
+    fn parse_status(field: &str, value: i32) -> Result<Option<lifecycle::SubscriptionStatus>, Status> {
+        match SubscriptionStatus::try_from(value) {
+            Ok(SubscriptionStatus::Unspecified) => Ok(None),
+            Ok(SubscriptionStatus::Pending) => Ok(Some(lifecycle::SubscriptionStatus::Pending)),
+            Ok(SubscriptionStatus::Active) => Ok(Some(lifecycle::SubscriptionStatus::Active)),
+            Ok(SubscriptionStatus::Unsubscribed) => Ok(Some(lifecycle::SubscriptionStatus::Unsubscribed)),
+            Ok(SubscriptionStatus::Suppressed) => Ok(Some(lifecycle::SubscriptionStatus::Suppressed)),
+            Err(_) => Err(invalid_field(field, format!("unknown subscription status {value}"))),
+        }
+    }
+
+    fn status_to_proto(status: Option<lifecycle::SubscriptionStatus>) -> i32 {
+        let status = match status {
+            None => SubscriptionStatus::Unspecified,
+            Some(lifecycle::SubscriptionStatus::Pending) => SubscriptionStatus::Pending,
+            Some(lifecycle::SubscriptionStatus::Active) => SubscriptionStatus::Active,
+            Some(lifecycle::SubscriptionStatus::Unsubscribed) => SubscriptionStatus::Unsubscribed,
+            Some(lifecycle::SubscriptionStatus::Suppressed) => SubscriptionStatus::Suppressed,
+        };
+        status.into()
+    }
+
+    fn reason_to_proto(reason: Option<unsubscribe::UnsubscribeReason>) -> i32 {
+        let reason = match reason {
+            None => UnsubscribeReason::Unspecified,
+            Some(unsubscribe::UnsubscribeReason::TooFrequent) => UnsubscribeReason::TooFrequent,
+            Some(unsubscribe::UnsubscribeReason::NotRelevant) => UnsubscribeReason::NotRelevant,
+            Some(unsubscribe::UnsubscribeReason::NeverSubscribed) => UnsubscribeReason::NeverSubscribed,
+            Some(unsubscribe::UnsubscribeReason::Other) => UnsubscribeReason::Other,
+        };
+        reason.into()
+    }
+
+    fn consent_to_proto(c: consent::ConsentRecord) -> Consent {
+        let action = match c.action {
+            consent::ConsentAction::Given => ConsentAction::Given,
+            consent::ConsentAction::Confirmed => ConsentAction::Confirmed,
+            consent::ConsentAction::Withdrawn => ConsentAction::Withdrawn,
+        };
+        Consent {
+            email: c.email,
+            action: action.into(),
+            policy_version: c.policy_version.unwrap_or_default(),
+            source: c.source.unwrap_or_default(),
+            ip_address: c.ip_address.unwrap_or_default(),
+            created_at: Some(timestamp::to_proto(c.created_at)),
+        }
+    }
+
+    fn unsubscribe_event_to_proto(e: unsubscribe::UnsubscribeEvent) -> UnsubscribeEvent {
+        UnsubscribeEvent {
+            email: e.email,
+            reason: Self::reason_to_proto(e.reason),
+            comment: e.comment.unwrap_or_default(),
+            created_at: Some(timestamp::to_proto(e.created_at)),
         }
     }
 }
 
 #[async_trait]
-impl<S: NewsletterServiceTrait + 'static> NewsletterService for MyNewsletterService<S> {
-    #[instrument(skip(self), fields(email = %req.get_ref().email, trace_id))]
+impl NewsletterService for MyNewsletterService {
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
     async fn get(&self, req: Request<GetRequest>) -> Result<Response<GetResponse>, Status> {
-        // Set trace_id from header or generate new one
-        let trace_id = if let Some(trace_id) = logging::extract_trace_id_from_request(&req) {
-            trace_id
-        } else {
-            uuid::Uuid::new_v4().to_string()
-        };
-        Span::current().record("trace_id", &trace_id);
+        validate(req.get_ref())?;
         
-        let email = req.into_inner().email;
-
-        info!(operation = "get", crud_operation = "READ", entity = "newsletter", email = %email, "Starting get operation");
+        let GetRequest { email, read_mask } = req.into_inner();
+        let email = Self::parse_email("email", &email)?;
+        let mask = Self::parse_read_mask(read_mask)?;
 
-        let active = match self.service.get_subscription_status(&email).await {
-            Ok(status) => {
-                info!(operation = "get", crud_operation = "READ", entity = "newsletter", email = %email, active = status, "Successfully retrieved subscription status");
-                status
-            }
+        let newsletter = match self.service.get_newsletter(&email, mask).await {
+            Ok(newsletter) => newsletter,
             Err(e) => {
-                error!(operation = "get", crud_operation = "READ", entity = "newsletter", email = %email, error = %e, "Failed to retrieve subscription status");
-                return Err(Status::internal(format!("service error (get_subscription_status): {e}")));
+                error!(operation = "get", crud_operation = "READ", entity = "newsletter", email = %logging::email(&email), error = %e, "Failed to retrieve newsletter");
+                return Err(Status::from(e));
             }
         };
 
-        info!(operation = "get", email = %email, active = active, "Get operation completed");
+        // An unknown address reads as an inactive subscription, as before masks
+        let newsletter = newsletter.unwrap_or_else(|| PartialNewsletter {
+            email: mask.email.then(|| email.as_str().to_string()),
+            active: mask.active.then_some(false),
+            status: None,
+            created_at: None,
+            locale: None,
+            timezone: None,
+            resubscribed_at: None,
+        });
 
-        Ok(Response::new(GetResponse { email, active }))
+        Ok(self.reply(GetResponse {
+            email: newsletter.email.unwrap_or_default(),
+            active: newsletter.active.unwrap_or_default(),
+            created_at: newsletter.created_at.map(timestamp::to_proto),
+            field_mask: Some(prost_types::FieldMask { paths: mask.paths() }),
+            status: Self::status_to_proto(newsletter.status),
+            locale: newsletter.locale.unwrap_or_default(),
+            timezone: newsletter.timezone.unwrap_or_default(),
+            resubscribed_at: newsletter.resubscribed_at.map(timestamp::to_proto),
+        }))
     }
 
-    #[instrument(skip(self), fields(email = %req.get_ref().email, trace_id))]
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
     async fn subscribe(&self, req: Request<SubscribeRequest>) -> Result<Response<()>, Status> {
-        // Set trace_id from header or generate new one
-        let trace_id = if let Some(trace_id) = logging::extract_trace_id_from_request(&req) {
-            trace_id
-        } else {
-            uuid::Uuid::new_v4().to_string()
-        };
-        Span::current().record("trace_id", &trace_id);
-        
-        let email = req.into_inner().email;
+        // Checked here so violations name the v1 fields
+        validate(req.get_ref())?;
+
+        self.subscriptions.sign_up(req.map(Into::into)).await?;
+        Ok(self.reply(()))
+    }
+
+    #[instrument(skip_all)]
+    async fn confirm(&self, req: Request<ConfirmRequest>) -> Result<Response<ConfirmResponse>, Status> {
+        let consent = ConsentContext::new(None, None, client_ip(&req, self.trust_forwarded_for));
+        let token = req.into_inner().token;
+
+        match self.service.confirm(&token, consent).await {
+            Ok(Some(email)) => {
+                info!(operation = "confirm", crud_operation = "UPDATE", entity = "newsletter", email = %logging::email(&email), "Successfully confirmed newsletter subscription");
+                Ok(self.reply(ConfirmResponse { email }))
+            }
+            Ok(None) => {
+                info!(operation = "confirm", crud_operation = "UPDATE", entity = "newsletter", "Rejected invalid or expired confirmation token");
+                Err(ErrorReason::ConfirmationTokenInvalid.status("confirmation token is invalid or expired"))
+            }
+            Err(e) => {
+                error!(operation = "confirm", crud_operation = "UPDATE", entity = "newsletter", error = %e, "Failed to confirm newsletter subscription");
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
+    async fn change_email(&self, req: Request<ChangeEmailRequest>) -> Result<Response<ChangeEmailResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let ChangeEmailRequest { email, new_email } = req.into_inner();
+        let email = Self::parse_email("email", &email)?;
+        let new_email = Self::parse_email("new_email", &new_email)?;
+
+        match self.service.request_email_change(&email, &new_email).await {
+            Ok(pending) => {
+                info!(operation = "change_email", crud_operation = "UPDATE", entity = "newsletter", email = %logging::email(&email), new_email = %logging::email(&new_email), "Sent confirmation of the new address");
+                Ok(self.reply(ChangeEmailResponse {
+                    expires_at: Some(timestamp::to_proto(pending.expires_at)),
+                }))
+            }
+            Err(e) => {
+                error!(operation = "change_email", crud_operation = "UPDATE", entity = "newsletter", email = %logging::email(&email), error = %e, "Failed to request email change");
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[instrument(skip_all)]
+    async fn confirm_email_change(
+        &self,
+        req: Request<ConfirmEmailChangeRequest>,
+    ) -> Result<Response<ConfirmEmailChangeResponse>, Status> {
+        let consent = ConsentContext::new(None, None, client_ip(&req, self.trust_forwarded_for));
+        let token = req.into_inner().token;
+
+        match self.service.confirm_email_change(&token, consent).await {
+            Ok(Some(change)) => {
+                info!(operation = "confirm_email_change", crud_operation = "UPDATE", entity = "newsletter", email = %logging::email(&change.to), previous_email = %logging::email(&change.from), "Successfully changed newsletter address");
+                Ok(self.reply(ConfirmEmailChangeResponse {
+                    previous_email: change.from,
+                    email: change.to,
+                }))
+            }
+            Ok(None) => {
+                info!(operation = "confirm_email_change", crud_operation = "UPDATE", entity = "newsletter", "Rejected invalid or expired email change token");
+                Err(ErrorReason::ConfirmationTokenInvalid.status("email change token is invalid or expired"))
+            }
+            Err(e) => {
+                error!(operation = "confirm_email_change", crud_operation = "UPDATE", entity = "newsletter", error = %e, "Failed to change newsletter address");
+                Err(Status::from(e))
+            }
+        }
+    }
 
-        info!(operation = "subscribe", crud_operation = "CREATE", entity = "newsletter", email = %email, "Starting subscribe operation");
+    #[instrument(skip_all)]
+    async fn confirm_topic(&self, req: Request<ConfirmTopicRequest>) -> Result<Response<ConfirmTopicResponse>, Status> {
+        let token = req.into_inner().token;
 
-        match self.service.subscribe(&email).await {
-            Ok(_) => {
-                info!(operation = "subscribe", crud_operation = "CREATE", entity = "newsletter", email = %email, "Successfully subscribed to newsletter");
-                Ok(Response::new(()))
+        match self.service.confirm_topic(&token).await {
+            Ok(Some(confirmed)) => {
+                info!(operation = "confirm_topic", crud_operation = "UPDATE", entity = "subscriber_topic", email = %logging::email(&confirmed.email), topic = %confirmed.topic, "Successfully confirmed topic opt-in");
+                Ok(self.reply(ConfirmTopicResponse {
+                    email: confirmed.email,
+                    topic: confirmed.topic,
+                }))
+            }
+            Ok(None) => {
+                info!(operation = "confirm_topic", crud_operation = "UPDATE", entity = "subscriber_topic", "Rejected invalid or expired topic confirmation token");
+                Err(ErrorReason::ConfirmationTokenInvalid.status("topic confirmation token is invalid or expired"))
             }
             Err(e) => {
-                error!(operation = "subscribe", crud_operation = "CREATE", entity = "newsletter", email = %email, error = %e, "Failed to subscribe to newsletter");
-                Err(Status::internal(format!("service error (subscribe): {e}")))
+                error!(operation = "confirm_topic", crud_operation = "UPDATE", entity = "subscriber_topic", error = %e, "Failed to confirm topic opt-in");
+                Err(Status::from(e))
             }
         }
     }
 
-    #[instrument(skip(self), fields(email = %req.get_ref().email, trace_id))]
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
     async fn un_subscribe(&self, req: Request<UnSubscribeRequest>) -> Result<Response<()>, Status> {
-        // Set trace_id from header or generate new one
-        let trace_id = if let Some(trace_id) = logging::extract_trace_id_from_request(&req) {
-            trace_id
-        } else {
-            uuid::Uuid::new_v4().to_string()
-        };
-        Span::current().record("trace_id", &trace_id);
+        validate(req.get_ref())?;
         
-        let email = req.into_inner().email;
+        let idempotency_key = idempotency::key_from_request(&req);
+        let request_hash = idempotency::request_hash(req.get_ref());
+        let UnSubscribeRequest { email, reason, comment } = req.into_inner();
+        let email = Self::parse_email("email", &email)?;
+        let feedback = UnsubscribeFeedback::new(Self::parse_reason(reason)?, Some(comment));
 
-        info!(operation = "unsubscribe", crud_operation = "DELETE", entity = "newsletter", email = %email, "Starting unsubscribe operation");
+        let result = self
+            .idempotency
+            .execute(idempotency_key.as_deref(), "unsubscribe", &request_hash, || async {
+                Ok(self.service.unsubscribe(&email, feedback).await?)
+            })
+            .await;
 
-        match self.service.unsubscribe(&email).await {
-            Ok(_) => {
-                info!(operation = "unsubscribe", crud_operation = "DELETE", entity = "newsletter", email = %email, "Successfully unsubscribed from newsletter");
-                Ok(Response::new(()))
+        match result {
+            Ok(false) if self.strict_status_codes => {
+                info!(operation = "unsubscribe", crud_operation = "DELETE", entity = "newsletter", email = %logging::email(&email), "Rejected unsubscribe of an unknown address");
+                Err(NewsletterError::NotFound(format!("{email} is not subscribed")).into())
+            }
+            Ok(existed) => {
+                info!(operation = "unsubscribe", crud_operation = "DELETE", entity = "newsletter", email = %logging::email(&email), existed = existed, "Successfully unsubscribed from newsletter");
+                Ok(self.reply(()))
             }
             Err(e) => {
-                error!(operation = "unsubscribe", crud_operation = "DELETE", entity = "newsletter", email = %email, error = %e, "Failed to unsubscribe from newsletter");
-                Err(Status::internal(format!("service error (unsubscribe): {e}")))
+                error!(operation = "unsubscribe", crud_operation = "DELETE", entity = "newsletter", email = %logging::email(&email), error = %e, "Failed to unsubscribe from newsletter");
+                Err(to_status("unsubscribe", e))
             }
         }
     }
 
-    #[instrument(skip(self), fields(trace_id))]
-    async fn list(&self, req: Request<()>) -> Result<Response<ListResponse>, Status> {
-        // Set trace_id from header or generate new one
-        let trace_id = if let Some(trace_id) = logging::extract_trace_id_from_request(&req) {
-            trace_id
-        } else {
-            uuid::Uuid::new_v4().to_string()
-        };
-        Span::current().record("trace_id", &trace_id);
+    #[instrument(skip_all)]
+    async fn import_subscribers(
+        &self,
+        req: Request<Streaming<ImportSubscribersRequest>>,
+    ) -> Result<Response<ImportSubscribersResponse>, Status> {
+        let mut stream = req.into_inner();
+        let mut import: Option<SubscriberImport> = None;
 
-        info!(operation = "list", crud_operation = "READ", entity = "newsletter", "Starting list operation");
+        while let Some(message) = stream.message().await? {
+            let format = Self::parse_import_format(message.format)?;
+            let import = match (&mut import, format) {
+                (Some(import), Some(format)) if import.format() != format => {
+                    return Err(invalid_field("format", "cannot change during an import"));
+                }
+                (Some(import), _) => import,
+                (None, Some(format)) => {
+                    let mut new = SubscriberImport::new(format).with_dry_run(message.dry_run);
+                    if let Some(throttle) = &self.import_throttle {
+                        new = new.with_throttle(throttle.clone());
+                    }
+                    import.insert(new)
+                }
+                (None, None) => {
+                    return Err(invalid_field("format", "is required in the first chunk"));
+                }
+            };
 
-        let items = match self.service.list_newsletters().await {
-            Ok(items) => {
-                info!(operation = "list", crud_operation = "READ", entity = "newsletter", count = items.len(), "Successfully retrieved newsletter list");
-                items
+            if let Err(e) = import.push(self.service.as_ref(), &message.chunk).await {
+                error!(operation = "import_subscribers", crud_operation = "CREATE", entity = "newsletter", error = %e, "Failed to import newsletters");
+                return Err(Self::import_status(e));
             }
+        }
+
+        let Some(import) = import else {
+            return Err(invalid_field("chunk", "the upload is empty"));
+        };
+        let dry_run = import.is_dry_run();
+
+        let summary = match import.finish(self.service.as_ref()).await {
+            Ok(summary) => summary,
+            Err(e) => {
+                error!(operation = "import_subscribers", crud_operation = "CREATE", entity = "newsletter", error = %e, "Failed to import newsletters");
+                return Err(Self::import_status(e));
+            }
+        };
+
+        info!(operation = "import_subscribers", crud_operation = "CREATE", entity = "newsletter", dry_run, imported = summary.imported, skipped = summary.skipped, invalid = summary.invalid, "Successfully imported newsletters");
+
+        Ok(self.reply(ImportSubscribersResponse {
+            imported: summary.imported as i64,
+            skipped: summary.skipped as i64,
+            invalid: summary.invalid as i64,
+            errors: summary
+                .errors
+                .into_iter()
+                .map(|row| ImportError {
+                    record: row.record as i64,
+                    reason: row.reason,
+                })
+                .collect(),
+        }))
+    }
+
+    #[instrument(skip(self), fields(page_size = req.get_ref().page_size))]
+    async fn list(&self, req: Request<ListRequest>) -> Result<Response<ListResponse>, Status> {
+        let ListRequest { page_size, page_token, read_mask, filter, order_by } = req.into_inner();
+        let page = PageRequest::new(i64::from(page_size), parse_page_token(&page_token)?);
+        let mask = Self::parse_read_mask(read_mask)?;
+        let query = Self::parse_query(filter, &order_by)?;
+
+        let page = match self.service.list_newsletters_masked(&query, page, mask).await {
+            Ok(page) => page,
             Err(e) => {
                 error!(operation = "list", crud_operation = "READ", entity = "newsletter", error = %e, "Failed to retrieve newsletter list");
-                return Err(Status::internal(format!("service error (list_newsletters): {e}")));
+                return Err(Status::from(e));
             }
         };
 
-        let newsletters: Vec<Newsletter> = items.into_iter().map(Self::to_proto).collect();
+        let newsletters: Vec<Newsletter> = page
+            .items
+            .into_iter()
+            .map(|n| Self::partial_to_proto(n, mask))
+            .collect();
+        let next_page_token = page.next_cursor.map(|c| c.to_string()).unwrap_or_default();
 
-        Ok(Response::new(ListResponse { newsletters }))
+        Ok(self.reply(ListResponse {
+            newsletters,
+            next_page_token,
+        }))
     }
 
-    #[instrument(skip(self), fields(emails = ?req.get_ref().emails, active = req.get_ref().active, trace_id))]
+    #[instrument(skip(self), fields(count = req.get_ref().emails.len(), active = req.get_ref().active, status = req.get_ref().status))]
     async fn update_status(
         &self,
         req: Request<UpdateStatusRequest>,
-    ) -> Result<Response<()>, Status> {
-        // Set trace_id from header or generate new one
-        let trace_id = if let Some(trace_id) = logging::extract_trace_id_from_request(&req) {
-            trace_id
+    ) -> Result<Response<UpdateStatusResponse>, Status> {
+        validate(req.get_ref())?;
+        
+        let UpdateStatusRequest { emails, active, status, dry_run } = req.into_inner();
+        // Callers that predate `status` only ever set the flag
+        let status = Self::parse_status("status", status)?.unwrap_or(if active {
+            lifecycle::SubscriptionStatus::Active
         } else {
-            uuid::Uuid::new_v4().to_string()
-        };
-        Span::current().record("trace_id", &trace_id);
+            lifecycle::SubscriptionStatus::Unsubscribed
+        });
+
+        if dry_run {
+            let (emails, invalid) = Self::partition_emails(emails);
+            let preview = match self.service.preview_status_update(emails, status).await {
+                Ok(preview) => preview,
+                Err(e) => {
+                    error!(operation = "update_status", crud_operation = "READ", entity = "newsletter", status = %status, error = %e, "Failed to preview bulk update status operation");
+                    return Err(Status::from(e));
+                }
+            };
+
+            info!(operation = "update_status", crud_operation = "READ", entity = "newsletter", changed = preview.changed, created = preview.created, rejected = preview.rejected.len(), status = %status, "Successfully previewed bulk update status operation");
+
+            return Ok(self.reply(UpdateStatusResponse {
+                changed: preview.changed as i64,
+                unchanged: preview.unchanged as i64,
+                created: preview.created as i64,
+                not_found: preview.not_found as i64,
+                errors: Self::bulk_errors(invalid, preview.rejected),
+            }));
+        }
+
+        let emails = Self::parse_emails("emails", emails)?;
+
+        let operation = if status.is_active() { "UPDATE_ACTIVATE" } else { "UPDATE_DEACTIVATE" };
+
+        match self.service.update_subscription_status(emails.clone(), status).await {
+            Ok(_) => Ok(self.reply(UpdateStatusResponse::default())),
+            Err(e) => {
+                error!(operation = "update_status", crud_operation = operation, entity = "newsletter", count = emails.len(), status = %status, error = %e, "Failed to complete bulk update status operation");
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(count = req.get_ref().emails.len()))]
+    async fn delete(&self, req: Request<DeleteRequest>) -> Result<Response<DeleteResponse>, Status> {
+        validate(req.get_ref())?;
         
-        let UpdateStatusRequest { emails, active } = req.into_inner();
+        let DeleteRequest { emails, dry_run, .. } = req.into_inner();
+
+        if dry_run {
+            let (emails, invalid) = Self::partition_emails(emails);
+            let preview = match self.service.preview_delete(emails).await {
+                Ok(preview) => preview,
+                Err(e) => {
+                    error!(operation = "delete", crud_operation = "READ", entity = "newsletter", error = %e, "Failed to preview bulk delete operation");
+                    return Err(Status::from(e));
+                }
+            };
+
+            info!(operation = "delete", crud_operation = "READ", entity = "newsletter", deleted = preview.changed, not_found = preview.not_found, "Successfully previewed bulk delete operation");
 
-        let operation = if active { "UPDATE_ACTIVATE" } else { "UPDATE_DEACTIVATE" };
+            return Ok(self.reply(DeleteResponse {
+                deleted: preview.changed as i64,
+                not_found: preview.not_found as i64,
+                errors: Self::bulk_errors(invalid, preview.rejected),
+            }));
+        }
 
-        info!(operation = "update_status", crud_operation = operation, entity = "newsletter", count = emails.len(), active = active, "Starting bulk update status operation");
+        let emails = Self::parse_emails("emails", emails)?;
 
-        match self.service.update_subscription_status(emails.clone(), active).await {
-            Ok(_) => {
-                info!(operation = "update_status", crud_operation = operation, entity = "newsletter", count = emails.len(), active = active, "Successfully completed bulk update status operation");
-                Ok(Response::new(()))
+        match self.service.delete_subscriptions(emails.clone()).await {
+            Ok(_) => Ok(self.reply(DeleteResponse::default())),
+            Err(e) => {
+                error!(operation = "delete", crud_operation = "DELETE", entity = "newsletter", count = emails.len(), error = %e, "Failed to complete bulk delete operation");
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(tag = %req.get_ref().tag, count = req.get_ref().emails.len()))]
+    async fn tag_subscribers(
+        &self,
+        req: Request<TagSubscribersRequest>,
+    ) -> Result<Response<TagSubscribersResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let TagSubscribersRequest { emails, tag } = req.into_inner();
+        let tag = Self::parse_tag("tag", &tag)?;
+        let emails = Self::parse_emails("emails", emails)?;
+
+        match self.service.tag_subscribers(emails, &tag).await {
+            Ok(tagged) => {
+                info!(operation = "tag_subscribers", crud_operation = "CREATE", entity = "subscriber_tag", tag = %tag, tagged = tagged, "Successfully tagged newsletters");
+                Ok(self.reply(TagSubscribersResponse {
+                    tagged: tagged as i64,
+                }))
             }
             Err(e) => {
-                error!(operation = "update_status", crud_operation = operation, entity = "newsletter", count = emails.len(), active = active, error = %e, "Failed to complete bulk update status operation");
-                Err(Status::internal(format!("service error (update_subscription_status): {e}")))
+                error!(operation = "tag_subscribers", crud_operation = "CREATE", entity = "subscriber_tag", tag = %tag, error = %e, "Failed to tag newsletters");
+                Err(Status::from(e))
             }
         }
     }
 
-    #[instrument(skip(self), fields(emails = ?req.get_ref().emails, trace_id))]
-    async fn delete(&self, req: Request<DeleteRequest>) -> Result<Response<()>, Status> {
-        // Set trace_id from header or generate new one
-        let trace_id = if let Some(trace_id) = logging::extract_trace_id_from_request(&req) {
-            trace_id
-        } else {
-            uuid::Uuid::new_v4().to_string()
+    #[instrument(skip(self), fields(tag = %req.get_ref().tag, count = req.get_ref().emails.len()))]
+    async fn untag_subscribers(
+        &self,
+        req: Request<UntagSubscribersRequest>,
+    ) -> Result<Response<UntagSubscribersResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let UntagSubscribersRequest { emails, tag } = req.into_inner();
+        let tag = Self::parse_tag("tag", &tag)?;
+        let emails = Self::parse_emails("emails", emails)?;
+
+        match self.service.untag_subscribers(emails, &tag).await {
+            Ok(untagged) => {
+                info!(operation = "untag_subscribers", crud_operation = "DELETE", entity = "subscriber_tag", tag = %tag, untagged = untagged, "Successfully untagged newsletters");
+                Ok(self.reply(UntagSubscribersResponse {
+                    untagged: untagged as i64,
+                }))
+            }
+            Err(e) => {
+                error!(operation = "untag_subscribers", crud_operation = "DELETE", entity = "subscriber_tag", tag = %tag, error = %e, "Failed to untag newsletters");
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(page_size = req.get_ref().page_size))]
+    async fn search(&self, req: Request<SearchRequest>) -> Result<Response<SearchResponse>, Status> {
+        let SearchRequest { query, page_size, page_token } = req.into_inner();
+        let query = SearchQuery::parse(&query).map_err(|e| invalid_field("query", e.to_string()))?;
+        let page = PageRequest::new(i64::from(page_size), parse_page_token(&page_token)?);
+
+        let page = match self.service.search(&query, page).await {
+            Ok(page) => page,
+            Err(e) => {
+                error!(operation = "search", crud_operation = "READ", entity = "newsletter", error = %e, "Failed to search newsletters");
+                return Err(Status::from(e));
+            }
         };
-        Span::current().record("trace_id", &trace_id);
-        
-        let emails = req.into_inner().emails;
 
-        info!(operation = "delete", crud_operation = "DELETE", entity = "newsletter", count = emails.len(), "Starting bulk delete operation");
+        Ok(self.reply(SearchResponse {
+            results: page
+                .items
+                .into_iter()
+                .map(|hit| SearchResult { newsletter: Some(Self::to_proto(hit.newsletter)), score: hit.score })
+                .collect(),
+            next_page_token: page.next_cursor.map(|c| c.to_string()).unwrap_or_default(),
+        }))
+    }
 
-        match self.service.delete_subscriptions(emails.clone()).await {
-            Ok(_) => {
-                info!(operation = "delete", crud_operation = "DELETE", entity = "newsletter", count = emails.len(), "Successfully completed bulk delete operation");
-                Ok(Response::new(()))
+    #[instrument(skip(self), fields(tag = %req.get_ref().tag, page_size = req.get_ref().page_size))]
+    async fn list_by_tag(&self, req: Request<ListByTagRequest>) -> Result<Response<ListResponse>, Status> {
+        let ListByTagRequest { tag, page_size, page_token } = req.into_inner();
+        let tag = Self::parse_tag("tag", &tag)?;
+        let page = PageRequest::new(i64::from(page_size), parse_page_token(&page_token)?);
+
+        let page = match self.service.list_by_tag(&tag, page).await {
+            Ok(page) => page,
+            Err(e) => {
+                error!(operation = "list_by_tag", crud_operation = "READ", entity = "newsletter", tag = %tag, error = %e, "Failed to retrieve tagged newsletters");
+                return Err(Status::from(e));
             }
+        };
+
+        Ok(self.reply(ListResponse {
+            newsletters: page.items.into_iter().map(Self::to_proto).collect(),
+            next_page_token: page.next_cursor.map(|c| c.to_string()).unwrap_or_default(),
+        }))
+    }
+
+    #[instrument(skip(self), fields(page_size = req.get_ref().page_size))]
+    async fn list_unsubscribe_reasons(
+        &self,
+        req: Request<ListUnsubscribeReasonsRequest>,
+    ) -> Result<Response<ListUnsubscribeReasonsResponse>, Status> {
+        let ListUnsubscribeReasonsRequest { reason, since, until, page_size, page_token } = req.into_inner();
+        let filter = UnsubscribeEventFilter {
+            reason: Self::parse_reason(reason)?,
+            since: since.map(|t| timestamp::from_proto("since", t)).transpose()?,
+            until: until.map(|t| timestamp::from_proto("until", t)).transpose()?,
+        };
+        let page = PageRequest::new(i64::from(page_size), parse_page_token(&page_token)?);
+
+        let (page, counts) = match self.service.list_unsubscribe_reasons(filter, page).await {
+            Ok(result) => result,
             Err(e) => {
-                error!(operation = "delete", crud_operation = "DELETE", entity = "newsletter", count = emails.len(), error = %e, "Failed to complete bulk delete operation");
-                Err(Status::internal(format!("service error (delete_subscriptions): {e}")))
+                error!(operation = "list_unsubscribe_reasons", crud_operation = "READ", entity = "unsubscribe_event", error = %e, "Failed to retrieve unsubscribe reasons");
+                return Err(Status::from(e));
+            }
+        };
+
+        Ok(self.reply(ListUnsubscribeReasonsResponse {
+            events: page.items.into_iter().map(Self::unsubscribe_event_to_proto).collect(),
+            counts: counts
+                .into_iter()
+                .map(|c| ReasonCount {
+                    reason: Self::reason_to_proto(c.reason),
+                    count: c.count,
+                })
+                .collect(),
+            next_page_token: page.next_cursor.map(|c| c.to_string()).unwrap_or_default(),
+        }))
+    }
+
+    #[instrument(skip_all)]
+    async fn get_stats(&self, _req: Request<GetStatsRequest>) -> Result<Response<GetStatsResponse>, Status> {
+        let tenant = tenant::current();
+
+        let stats = match self.service.stats().await {
+            Ok(stats) => stats,
+            Err(e) => {
+                error!(operation = "get_stats", crud_operation = "READ", entity = "newsletter", tenant = %tenant, error = %e, "Failed to count subscriptions");
+                return Err(Status::from(e));
+            }
+        };
+
+        Ok(self.reply(GetStatsResponse {
+            tenant_id: tenant.to_string(),
+            active: stats.active,
+            inactive: stats.inactive,
+            unsubscribed: stats.unsubscribed,
+            suppressed: stats.suppressed,
+        }))
+    }
+
+    #[instrument(skip_all)]
+    async fn get_growth_time_series(
+        &self,
+        req: Request<GetGrowthTimeSeriesRequest>,
+    ) -> Result<Response<GetGrowthTimeSeriesResponse>, Status> {
+        let GetGrowthTimeSeriesRequest { start_date, end_date, granularity } = req.into_inner();
+        let range = GrowthRange::new(
+            Self::parse_date("start_date", &start_date)?,
+            Self::parse_date("end_date", &end_date)?,
+            Self::parse_granularity(granularity)?,
+        )?;
+
+        let points = match self.service.growth_time_series(range).await {
+            Ok(points) => points,
+            Err(e) => {
+                error!(operation = "get_growth_time_series", crud_operation = "READ", entity = "list_metrics", error = %e, "Failed to retrieve growth time series");
+                return Err(Status::from(e));
+            }
+        };
+
+        Ok(self.reply(GetGrowthTimeSeriesResponse {
+            points: points
+                .into_iter()
+                .map(|p| GrowthPoint {
+                    start_date: p.start.to_string(),
+                    subscribers: p.subscribers,
+                    confirms: p.confirms,
+                    unsubscribes: p.unsubscribes,
+                    net_growth: p.net_growth,
+                })
+                .collect(),
+        }))
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
+    async fn list_consents(&self, req: Request<ListConsentsRequest>) -> Result<Response<ListConsentsResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let ListConsentsRequest { email, page_size, page_token } = req.into_inner();
+        let email = Self::parse_email("email", &email)?;
+        let page = PageRequest::new(i64::from(page_size), parse_page_token(&page_token)?);
+
+        let page = match self.service.list_consents(&email, page).await {
+            Ok(page) => page,
+            Err(e) => {
+                error!(operation = "list_consents", crud_operation = "READ", entity = "consent", email = %logging::email(&email), error = %e, "Failed to retrieve consents");
+                return Err(Status::from(e));
+            }
+        };
+
+        Ok(self.reply(ListConsentsResponse {
+            consents: page.items.into_iter().map(Self::consent_to_proto).collect(),
+            next_page_token: page.next_cursor.map(|c| c.to_string()).unwrap_or_default(),
+        }))
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
+    async fn export_subscriber_data(
+        &self,
+        req: Request<ExportSubscriberDataRequest>,
+    ) -> Result<Response<ExportSubscriberDataResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let email = Self::parse_email("email", &req.into_inner().email)?;
+
+        let export = match self.service.export_subscriber_data(&email).await {
+            Ok(export) => export,
+            Err(e) => {
+                error!(operation = "export_subscriber_data", crud_operation = "READ", entity = "newsletter", email = %logging::email(&email), error = %e, "Failed to export subscriber data");
+                return Err(Status::from(e));
+            }
+        };
+
+        Ok(self.reply(ExportSubscriberDataResponse {
+            email: export.email,
+            subscription: export.subscription.map(|s| Subscription {
+                active: s.status.is_active(),
+                status: Self::status_to_proto(Some(s.status)),
+                created_at: Some(timestamp::to_proto(s.created_at)),
+                attributes: Some(json::json_to_struct(s.attributes)),
+                locale: s.locale.unwrap_or_default(),
+                timezone: s.timezone.unwrap_or_default(),
+                resubscribed_at: s.resubscribed_at.map(timestamp::to_proto),
+            }),
+            tags: export
+                .tags
+                .into_iter()
+                .map(|t| SubscriberTag {
+                    tag: t.tag,
+                    created_at: Some(timestamp::to_proto(t.created_at)),
+                })
+                .collect(),
+            pending_confirmations: export
+                .pending_confirmations
+                .into_iter()
+                .map(|p| PendingConfirmation {
+                    created_at: Some(timestamp::to_proto(p.created_at)),
+                    expires_at: Some(timestamp::to_proto(p.expires_at)),
+                })
+                .collect(),
+            unsubscribes: export
+                .unsubscribes
+                .into_iter()
+                .map(Self::unsubscribe_event_to_proto)
+                .collect(),
+            merges: export
+                .merges
+                .into_iter()
+                .map(|m| SubscriberMerge {
+                    email: m.email,
+                    merged_email: m.merged_email,
+                    merged_status: Self::status_to_proto(Some(m.merged_status)),
+                    status: Self::status_to_proto(Some(m.status)),
+                    merged_at: Some(timestamp::to_proto(m.merged_at)),
+                })
+                .collect(),
+        }))
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
+    async fn get_subscriber_history(
+        &self,
+        req: Request<GetSubscriberHistoryRequest>,
+    ) -> Result<Response<GetSubscriberHistoryResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let GetSubscriberHistoryRequest { email, at } = req.into_inner();
+        let email = Self::parse_email("email", &email)?;
+        let at = match at {
+            Some(at) => timestamp::from_proto("at", at)?,
+            None => Utc::now(),
+        };
+
+        let history = match self.service.subscriber_history(&email, at).await {
+            Ok(history) => history,
+            Err(e) => {
+                error!(operation = "get_subscriber_history", crud_operation = "READ", entity = "subscriber_event", email = %logging::email(&email), error = %e, "Failed to replay subscriber history");
+                return Err(Status::from(e));
+            }
+        };
+
+        Ok(self.reply(GetSubscriberHistoryResponse {
+            status: Self::status_to_proto(history.state.as_ref().map(|s| s.status)),
+            created_at: history.state.as_ref().map(|s| timestamp::to_proto(s.created_at)),
+            updated_at: history.state.as_ref().map(|s| timestamp::to_proto(s.updated_at)),
+            events: history
+                .events
+                .into_iter()
+                .map(|e| SubscriberEvent {
+                    r#type: e.kind.as_str().to_string(),
+                    email: e.email,
+                    status: Self::status_to_proto(e.status),
+                    previous_email: e.previous_email.unwrap_or_default(),
+                    occurred_at: Some(timestamp::to_proto(e.occurred_at)),
+                })
+                .collect(),
+        }))
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
+    async fn get_preferences(
+        &self,
+        req: Request<GetPreferencesRequest>,
+    ) -> Result<Response<GetPreferencesResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let email = Self::parse_email("email", &req.into_inner().email)?;
+
+        match self.service.get_preferences(&email).await {
+            Ok(topics) => Ok(self.reply(GetPreferencesResponse {
+                topics: Self::topics_to_proto(topics),
+            })),
+            Err(e) => {
+                error!(operation = "get_preferences", crud_operation = "READ", entity = "subscriber_topic", email = %logging::email(&email), error = %e, "Failed to retrieve topic preferences");
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email), count = req.get_ref().preferences.len()))]
+    async fn set_preferences(
+        &self,
+        req: Request<SetPreferencesRequest>,
+    ) -> Result<Response<SetPreferencesResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let SetPreferencesRequest { email, preferences } = req.into_inner();
+        let email = Self::parse_email("email", &email)?;
+        let preferences: Vec<DomainTopicPreference> = preferences
+            .into_iter()
+            .map(|p| DomainTopicPreference {
+                topic: p.topic,
+                subscribed: p.subscribed,
+            })
+            .collect();
+
+        match self.service.set_preferences(&email, preferences).await {
+            Ok(update) => Ok(self.reply(SetPreferencesResponse {
+                topics: Self::topics_to_proto(update.preferences),
+                awaiting_confirmation: update.awaiting_confirmation,
+            })),
+            Err(e) => {
+                error!(operation = "set_preferences", crud_operation = "UPDATE", entity = "subscriber_topic", email = %logging::email(&email), error = %e, "Failed to update topic preferences");
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[instrument(skip_all)]
+    async fn list_attribute_definitions(
+        &self,
+        _req: Request<ListAttributeDefinitionsRequest>,
+    ) -> Result<Response<ListAttributeDefinitionsResponse>, Status> {
+
+        match self.service.list_attribute_definitions().await {
+            Ok(definitions) => Ok(self.reply(ListAttributeDefinitionsResponse {
+                definitions: definitions.into_iter().map(Self::attribute_definition_to_proto).collect(),
+            })),
+            Err(e) => {
+                error!(operation = "list_attribute_definitions", crud_operation = "READ", entity = "attribute_definition", error = %e, "Failed to retrieve attribute definitions");
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[instrument(skip_all)]
+    async fn define_attribute(
+        &self,
+        req: Request<DefineAttributeRequest>,
+    ) -> Result<Response<DefineAttributeResponse>, Status> {
+        let definition = req
+            .into_inner()
+            .definition
+            .ok_or_else(|| invalid_field("definition", "is required"))?;
+        let kind = match AttributeType::try_from(definition.kind) {
+            Ok(AttributeType::String) => DomainAttributeType::String,
+            Ok(AttributeType::Number) => DomainAttributeType::Number,
+            Ok(AttributeType::Boolean) => DomainAttributeType::Boolean,
+            Ok(AttributeType::Unspecified) | Err(_) => {
+                return Err(invalid_field("definition.kind", "must be string, number or boolean"));
+            }
+        };
+        let definition = DomainAttributeDefinition::new(&definition.key, kind, &definition.description)
+            .map_err(|e| invalid_field("definition", e.to_string()))?;
+
+        match self.service.define_attribute(definition).await {
+            Ok(definition) => Ok(self.reply(DefineAttributeResponse {
+                definition: Some(Self::attribute_definition_to_proto(definition)),
+            })),
+            Err(e) => {
+                error!(operation = "define_attribute", crud_operation = "CREATE", entity = "attribute_definition", error = %e, "Failed to define attribute");
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
+    async fn get_attributes(
+        &self,
+        req: Request<GetAttributesRequest>,
+    ) -> Result<Response<GetAttributesResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let email = Self::parse_email("email", &req.into_inner().email)?;
+
+        match self.service.get_attributes(&email).await {
+            Ok(attributes) => Ok(self.reply(GetAttributesResponse {
+                attributes: Some(json::json_to_struct(attributes)),
+            })),
+            Err(e) => {
+                error!(operation = "get_attributes", crud_operation = "READ", entity = "newsletter", email = %logging::email(&email), error = %e, "Failed to retrieve attributes");
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
+    async fn set_attributes(
+        &self,
+        req: Request<SetAttributesRequest>,
+    ) -> Result<Response<SetAttributesResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let SetAttributesRequest { email, attributes } = req.into_inner();
+        let email = Self::parse_email("email", &email)?;
+        let changes = Self::attributes_from_proto(attributes);
+
+        match self.service.set_attributes(&email, changes).await {
+            Ok(attributes) => Ok(self.reply(SetAttributesResponse {
+                attributes: Some(json::json_to_struct(attributes)),
+            })),
+            Err(e) => {
+                error!(operation = "set_attributes", crud_operation = "UPDATE", entity = "newsletter", email = %logging::email(&email), error = %e, "Failed to update attributes");
+                Err(Status::from(e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
+    async fn set_locale(&self, req: Request<SetLocaleRequest>) -> Result<Response<SetLocaleResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let SetLocaleRequest { email, locale } = req.into_inner();
+        let email = Self::parse_email("email", &email)?;
+        let locale = Some(locale.trim()).filter(|l| !l.is_empty());
+
+        match self.service.set_locale(&email, locale).await {
+            Ok(locale) => {
+                info!(operation = "set_locale", crud_operation = "UPDATE", entity = "newsletter", email = %logging::email(&email), locale = locale.as_deref().unwrap_or_default(), "Successfully updated locale");
+                Ok(self.reply(SetLocaleResponse {
+                    locale: locale.unwrap_or_default(),
+                }))
+            }
+            Err(e) => {
+                error!(operation = "set_locale", crud_operation = "UPDATE", entity = "newsletter", email = %logging::email(&email), error = %e, "Failed to update locale");
+                Err(Status::from(e))
             }
         }
     }
-}
\ No newline at end of file
+
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
+    async fn set_timezone(&self, req: Request<SetTimezoneRequest>) -> Result<Response<SetTimezoneResponse>, Status> {
+        validate(req.get_ref())?;
+
+        let SetTimezoneRequest { email, timezone } = req.into_inner();
+        let email = Self::parse_email("email", &email)?;
+        let timezone = Some(timezone.trim()).filter(|t| !t.is_empty());
+
+        match self.service.set_timezone(&email, timezone).await {
+            Ok(()) => {
+                info!(operation = "set_timezone", crud_operation = "UPDATE", entity = "newsletter", email = %logging::email(&email), timezone = timezone.unwrap_or_default(), "Successfully updated timezone");
+                Ok(self.reply(SetTimezoneResponse {}))
+            }
+            Err(e) => {
+                error!(operation = "set_timezone", crud_operation = "UPDATE", entity = "newsletter", email = %logging::email(&email), error = %e, "Failed to update timezone");
+                Err(Status::from(e))
+            }
+        }
+    }
+    #[instrument(skip_all)]
+    async fn refresh_disposable_domains(
+        &self,
+        _req: Request<RefreshDisposableDomainsRequest>,
+    ) -> Result<Response<RefreshDisposableDomainsResponse>, Status> {
+
+        match self.service.refresh_disposable_domains().await {
+            Ok(domains) => {
+                info!(operation = "refresh_disposable_domains", crud_operation = "UPDATE", entity = "disposable_domains", domains = domains, "Successfully refreshed disposable domains");
+                Ok(self.reply(RefreshDisposableDomainsResponse { domains: domains as i64 }))
+            }
+            Err(e) => {
+                error!(operation = "refresh_disposable_domains", crud_operation = "UPDATE", entity = "disposable_domains", error = %e, "Failed to refresh disposable domains");
+                Err(Status::from(e))
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
+mod adapter;
 pub mod api;
+mod validate;
 
 pub mod proto {
     #![allow(dead_code)]
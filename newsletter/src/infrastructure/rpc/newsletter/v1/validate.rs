@@ -0,0 +1,130 @@
+use crate::infrastructure::rpc::newsletter::v1::proto::{
+    ChangeEmailRequest, DeleteRequest, ExportSubscriberDataRequest, GetAttributesRequest, GetSubscriberHistoryRequest, GetPreferencesRequest, GetRequest,
+    ListConsentsRequest, SetAttributesRequest, SetLocaleRequest, SetPreferencesRequest, SetTimezoneRequest, SubscribeRequest, TagSubscribersRequest, UnSubscribeRequest,
+    UntagSubscribersRequest, UpdateStatusRequest,
+};
+use crate::domain::newsletter::consent::MAX_CONSENT_LABEL_LEN;
+use crate::infrastructure::rpc::validation::{Validate, Violations, MAX_BATCH_SIZE};
+
+impl Validate for GetRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+    }
+}
+
+impl Validate for SubscribeRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+        for (field, value) in [("consent_version", &self.consent_version), ("source", &self.source)] {
+            if value.trim().chars().count() > MAX_CONSENT_LABEL_LEN {
+                violations.add(field, format!("must be at most {MAX_CONSENT_LABEL_LEN} characters"));
+            }
+        }
+        violations.locale("locale", self.locale.trim());
+    }
+}
+
+impl Validate for UnSubscribeRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+    }
+}
+
+impl Validate for ChangeEmailRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+        violations.email("new_email", &self.new_email);
+    }
+}
+
+impl Validate for ExportSubscriberDataRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+    }
+}
+
+impl Validate for GetSubscriberHistoryRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+    }
+}
+
+impl Validate for ListConsentsRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+    }
+}
+
+impl Validate for GetPreferencesRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+    }
+}
+
+impl Validate for SetPreferencesRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+    }
+}
+
+impl Validate for GetAttributesRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+    }
+}
+
+impl Validate for SetAttributesRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+    }
+}
+
+impl Validate for SetLocaleRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+        violations.locale("locale", self.locale.trim());
+    }
+}
+
+impl Validate for SetTimezoneRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+        violations.timezone("timezone", self.timezone.trim());
+    }
+}
+
+// A dry run lists malformed addresses in its report rather than failing
+
+impl Validate for UpdateStatusRequest {
+    fn validate(&self, violations: &mut Violations) {
+        if self.dry_run {
+            violations.batch("emails", &self.emails, MAX_BATCH_SIZE);
+        } else {
+            violations.emails("emails", &self.emails, MAX_BATCH_SIZE);
+        }
+    }
+}
+
+impl Validate for DeleteRequest {
+    fn validate(&self, violations: &mut Violations) {
+        if self.dry_run {
+            violations.batch("emails", &self.emails, MAX_BATCH_SIZE);
+        } else {
+            violations.emails("emails", &self.emails, MAX_BATCH_SIZE);
+        }
+    }
+}
+
+impl Validate for TagSubscribersRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.emails("emails", &self.emails, MAX_BATCH_SIZE);
+        violations.tag("tag", &self.tag);
+    }
+}
+
+impl Validate for UntagSubscribersRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.emails("emails", &self.emails, MAX_BATCH_SIZE);
+        violations.tag("tag", &self.tag);
+    }
+}
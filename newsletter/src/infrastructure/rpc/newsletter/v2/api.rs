@@ -0,0 +1,170 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use tracing::{error, info, instrument};
+
+use crate::domain::newsletter::consent::ConsentContext;
+use crate::domain::newsletter::error::NewsletterError;
+use crate::domain::newsletter::lifecycle;
+use crate::domain::newsletter::mask::NewsletterMask;
+use crate::domain::newsletter::signup::{SignupDetails, SOURCE_ATTRIBUTE};
+use crate::domain::newsletter::EmailAddress;
+use crate::infrastructure::logging;
+use crate::infrastructure::rpc::newsletter::{client_ip, to_status};
+use crate::infrastructure::rpc::validation::{invalid_field, validate};
+use crate::infrastructure::rpc::{idempotency, timestamp};
+use crate::service::idempotency::IdempotencyGuard;
+use crate::service::newsletter::{NewsletterService as NewsletterServiceTrait, SubscribeOutcome};
+
+use crate::infrastructure::rpc::newsletter::v2::proto::{
+    newsletter_service_server::NewsletterService, SubscribeRequest, SubscribeResponse, Subscription,
+    SubscriptionStatus,
+};
+
+/// gRPC adapter for the v2 newsletter API.
+///
+/// Subscribe is implemented here for both versions: the v1 handler turns its
+/// request into a v2 one and drops the response. It honours the same
+/// `x-idempotency-key` header as v1.
+#[derive(Clone)]
+pub struct MyNewsletterService {
+    service: Arc<dyn NewsletterServiceTrait>,
+    idempotency: IdempotencyGuard,
+    /// Fail Subscribe of an active address with ALREADY_EXISTS
+    strict_status_codes: bool,
+    /// Take the IP recorded with a consent from `x-forwarded-for`
+    trust_forwarded_for: bool,
+}
+
+impl MyNewsletterService {
+    pub fn new(service: Arc<dyn NewsletterServiceTrait>, idempotency: IdempotencyGuard) -> Self {
+        Self {
+            service,
+            idempotency,
+            strict_status_codes: false,
+            trust_forwarded_for: false,
+        }
+    }
+
+    pub fn with_strict_status_codes(mut self, strict: bool) -> Self {
+        self.strict_status_codes = strict;
+        self
+    }
+
+    pub fn with_trust_forwarded_for(mut self, trust: bool) -> Self {
+        self.trust_forwarded_for = trust;
+        self
+    }
+
+    /// Store a signup; returns the address and, when a confirmation was sent,
+    /// when its link expires
+    pub(crate) async fn sign_up(
+        &self,
+        req: Request<SubscribeRequest>,
+    ) -> Result<(EmailAddress, Option<DateTime<Utc>>), Status> {
+        validate(req.get_ref())?;
+
+        let idempotency_key = idempotency::key_from_request(&req);
+        let request_hash = idempotency::request_hash(req.get_ref());
+        let ip_address = client_ip(&req, self.trust_forwarded_for);
+        let SubscribeRequest { email, source, locale, topics, consent } = req.into_inner();
+        let email = EmailAddress::parse(&email).map_err(|e| invalid_field("email", e.to_string()))?;
+        let consent = ConsentContext::new(
+            consent.map(|c| c.policy_version),
+            Some(source),
+            ip_address,
+        );
+        let details = SignupDetails {
+            locale: Some(locale.trim().to_string()).filter(|l| !l.is_empty()),
+            source: consent.source.clone(),
+            topics: topics.into_iter().map(|t| t.trim().to_string()).collect(),
+        };
+
+        let result = self
+            .idempotency
+            .execute(idempotency_key.as_deref(), "sign_up", &request_hash, || async {
+                let outcome = self.service.sign_up(&email, consent.clone(), details.clone()).await?;
+                Ok(match outcome {
+                    SubscribeOutcome::PendingConfirmation { expires_at, .. } => Some(expires_at),
+                    SubscribeOutcome::AlreadyActive if self.strict_status_codes => {
+                        // Failing releases the key, so a retry is rejected the same way
+                        info!(operation = "subscribe", crud_operation = "CREATE", entity = "newsletter", email = %logging::email(&email), "Rejected subscribe of an active address");
+                        return Err(NewsletterError::AlreadySubscribed(email.to_string()).into());
+                    }
+                    SubscribeOutcome::AlreadyActive | SubscribeOutcome::Resubscribed => None,
+                })
+            })
+            .await;
+
+        match result {
+            Ok(expires_at) => {
+                info!(operation = "subscribe", crud_operation = "CREATE", entity = "newsletter", email = %logging::email(&email), pending = expires_at.is_some(), "Successfully subscribed to newsletter");
+                Ok((email, expires_at))
+            }
+            Err(e) => {
+                error!(operation = "subscribe", crud_operation = "CREATE", entity = "newsletter", email = %logging::email(&email), error = %e, "Failed to subscribe to newsletter");
+                Err(to_status("subscribe", e))
+            }
+        }
+    }
+
+    /// Read back the subscription a signup left behind
+    async fn subscription(
+        &self,
+        email: &EmailAddress,
+        confirmation_expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Subscription, Status> {
+        let newsletter = self
+            .service
+            .get_newsletter(email, NewsletterMask::ALL)
+            .await?
+            .ok_or_else(|| Status::from(NewsletterError::NotFound(format!("{email} is not subscribed"))))?;
+        let attributes = self.service.get_attributes(email).await?;
+        let topics = self.service.get_preferences(email).await?;
+
+        let source = match attributes.get(SOURCE_ATTRIBUTE) {
+            Some(Value::String(value)) => value.clone(),
+            _ => String::new(),
+        };
+        Ok(Subscription {
+            email: newsletter.email.unwrap_or_else(|| email.to_string()),
+            status: Self::status_to_proto(newsletter.status),
+            locale: newsletter.locale.unwrap_or_default(),
+            source,
+            topics: topics
+                .into_iter()
+                .filter(|t| t.subscribed)
+                .map(|t| t.topic.key)
+                .collect(),
+            created_at: newsletter.created_at.map(timestamp::to_proto),
+            resubscribed_at: newsletter.resubscribed_at.map(timestamp::to_proto),
+            confirmation_expires_at: confirmation_expires_at.map(timestamp::to_proto),
+        })
+    }
+
+    fn status_to_proto(status: Option<lifecycle::SubscriptionStatus>) -> i32 {
+        let status = match status {
+            None => SubscriptionStatus::Unspecified,
+            Some(lifecycle::SubscriptionStatus::Pending) => SubscriptionStatus::Pending,
+            Some(lifecycle::SubscriptionStatus::Active) => SubscriptionStatus::Active,
+            Some(lifecycle::SubscriptionStatus::Unsubscribed) => SubscriptionStatus::Unsubscribed,
+            Some(lifecycle::SubscriptionStatus::Suppressed) => SubscriptionStatus::Suppressed,
+        };
+        status.into()
+    }
+}
+
+#[async_trait]
+impl NewsletterService for MyNewsletterService {
+    #[instrument(skip(self), fields(email = %logging::email(&req.get_ref().email)))]
+    async fn subscribe(&self, req: Request<SubscribeRequest>) -> Result<Response<SubscribeResponse>, Status> {
+        let (email, confirmation_expires_at) = self.sign_up(req).await?;
+        let subscription = self.subscription(&email, confirmation_expires_at).await?;
+
+        Ok(Response::new(SubscribeResponse {
+            subscription: Some(subscription),
+        }))
+    }
+}
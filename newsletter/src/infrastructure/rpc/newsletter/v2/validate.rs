@@ -0,0 +1,30 @@
+use crate::domain::newsletter::consent::MAX_CONSENT_LABEL_LEN;
+use crate::infrastructure::rpc::newsletter::v2::proto::SubscribeRequest;
+use crate::infrastructure::rpc::validation::{Validate, Violations};
+
+/// More topics than any catalogue holds
+const MAX_TOPICS: usize = 100;
+
+impl Validate for SubscribeRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.email("email", &self.email);
+
+        let policy_version = self.consent.as_ref().map(|c| c.policy_version.as_str()).unwrap_or_default();
+        for (field, value) in [("consent.policy_version", policy_version), ("source", &self.source)] {
+            if value.trim().chars().count() > MAX_CONSENT_LABEL_LEN {
+                violations.add(field, format!("must be at most {MAX_CONSENT_LABEL_LEN} characters"));
+            }
+        }
+
+        violations.locale("locale", self.locale.trim());
+
+        if self.topics.len() > MAX_TOPICS {
+            violations.add("topics", format!("must hold at most {MAX_TOPICS} topics"));
+        }
+        for (i, topic) in self.topics.iter().enumerate() {
+            if topic.trim().is_empty() {
+                violations.add(format!("topics[{i}]"), "must not be empty");
+            }
+        }
+    }
+}
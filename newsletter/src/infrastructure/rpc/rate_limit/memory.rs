@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use super::{Decision, Quota, RateLimitStore};
+
+/// Buckets kept before full (idle) ones are swept
+const SWEEP_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, quota: Quota, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * quota.per_second).min(f64::from(quota.burst));
+        self.updated = now;
+    }
+}
+
+/// Token buckets held in process memory; limits apply per instance
+#[derive(Debug, Default)]
+pub struct InMemoryRateLimitStore {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl InMemoryRateLimitStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for InMemoryRateLimitStore {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    async fn acquire(&self, key: &str, quota: Quota) -> anyhow::Result<Decision> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().expect("rate limit store lock poisoned");
+
+        if buckets.len() >= SWEEP_THRESHOLD && !buckets.contains_key(key) {
+            // A bucket that refilled completely behaves exactly like a missing one
+            let full_after = Duration::from_secs_f64(f64::from(quota.burst) / quota.per_second);
+            buckets.retain(|_, b| now.saturating_duration_since(b.updated) < full_after);
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: f64::from(quota.burst),
+            updated: now,
+        });
+        bucket.refill(quota, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(Decision::Allowed);
+        }
+
+        Ok(Decision::Limited {
+            retry_after: Duration::from_secs_f64((1.0 - bucket.tokens) / quota.per_second),
+        })
+    }
+}
@@ -0,0 +1,238 @@
+use std::env;
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tonic::metadata::MetadataValue;
+use tonic::transport::server::TcpConnectInfo;
+use tokio::sync::watch;
+use tonic::Status;
+use tower::{Layer, Service};
+use tracing::{info, warn};
+
+use crate::domain::auth::ApiKey;
+use crate::infrastructure::rpc::errors::ErrorReason;
+
+pub mod memory;
+#[cfg(feature = "redis")]
+pub mod redis;
+
+/// Sustained rate and burst size of a token bucket
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quota {
+    /// Tokens added per second
+    pub per_second: f64,
+    /// Bucket capacity, i.e. requests allowed at once after an idle period
+    pub burst: u32,
+}
+
+/// Whether a request may proceed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Allowed,
+    Limited { retry_after: Duration },
+}
+
+/// Storage for token buckets, shared by every request of a client
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Take one token from the bucket under `key`
+    async fn acquire(&self, key: &str, quota: Quota) -> anyhow::Result<Decision>;
+}
+
+/// Per-client limits. Every request is counted against the peer IP before
+/// its API key is checked, so a flood of bad keys never reaches the key
+/// lookup; authenticated requests are counted against their key as well.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    /// Enforced by [`RateLimitLayer::per_ip`], outside the auth layer
+    pub per_ip: Option<Quota>,
+    /// Enforced by [`RateLimitLayer::per_key`], inside the auth layer
+    pub per_key: Option<Quota>,
+    /// Take the client IP from the first `x-forwarded-for` entry; only safe
+    /// behind a proxy that overwrites the header
+    pub trust_forwarded: bool,
+}
+
+/// Pick the token bucket store from `RATE_LIMIT_STORE` (memory | redis)
+pub async fn store_from_env() -> anyhow::Result<Arc<dyn RateLimitStore>> {
+    let name = env::var("RATE_LIMIT_STORE").unwrap_or_else(|_| "memory".to_string());
+
+    let store: Arc<dyn RateLimitStore> = match name.as_str() {
+        #[cfg(feature = "redis")]
+        "redis" => Arc::new(redis::RedisRateLimitStore::from_env().await?),
+        #[cfg(not(feature = "redis"))]
+        "redis" => anyhow::bail!("RATE_LIMIT_STORE=redis requires the `redis` feature"),
+        "memory" => Arc::new(memory::InMemoryRateLimitStore::new()),
+        other => anyhow::bail!("unknown RATE_LIMIT_STORE: {other}"),
+    };
+
+    info!(store = store.name(), "Configured rate limit store");
+
+    Ok(store)
+}
+
+/// Tower layer rejecting requests over their client's quota with
+/// `RESOURCE_EXHAUSTED` and a `retry-after` header (whole seconds).
+///
+/// The limits are read from a watch channel on every request, so new ones
+/// apply at once; with none, every request passes.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limiter: Arc<Limiter>,
+}
+
+impl RateLimitLayer {
+    /// Counts every request against its peer IP; must sit outside the auth
+    /// layer, so it runs before the key is looked up
+    pub fn per_ip(config: watch::Receiver<Option<RateLimitConfig>>, store: Arc<dyn RateLimitStore>) -> Self {
+        Self::new(config, store, Scope::Ip)
+    }
+
+    /// Counts authenticated requests against their API key; must sit inside
+    /// the auth layer to see the key
+    pub fn per_key(config: watch::Receiver<Option<RateLimitConfig>>, store: Arc<dyn RateLimitStore>) -> Self {
+        Self::new(config, store, Scope::Key)
+    }
+
+    fn new(config: watch::Receiver<Option<RateLimitConfig>>, store: Arc<dyn RateLimitStore>, scope: Scope) -> Self {
+        Self {
+            limiter: Arc::new(Limiter { config, store, scope }),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+/// What a layer counts requests against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Ip,
+    Key,
+}
+
+struct Limiter {
+    config: watch::Receiver<Option<RateLimitConfig>>,
+    store: Arc<dyn RateLimitStore>,
+    scope: Scope,
+}
+
+impl Limiter {
+    /// The bucket and quota that apply to a request, if any
+    fn bucket<B>(&self, req: &http::Request<B>) -> Option<(String, Quota)> {
+        let config = self.config.borrow();
+        let config = config.as_ref()?;
+        match self.scope {
+            Scope::Key => {
+                let api_key = req.extensions().get::<ApiKey>()?;
+                config.per_key.map(|quota| (format!("key:{}", api_key.id), quota))
+            }
+            Scope::Ip => {
+                let quota = config.per_ip?;
+                let ip = Self::client_ip(req, config.trust_forwarded)?;
+                Some((format!("ip:{ip}"), quota))
+            }
+        }
+    }
+
+    fn client_ip<B>(req: &http::Request<B>, trust_forwarded: bool) -> Option<IpAddr> {
+        if trust_forwarded {
+            let forwarded = req
+                .headers()
+                .get("x-forwarded-for")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.split(',').next())
+                .and_then(|v| v.trim().parse().ok());
+            if forwarded.is_some() {
+                return forwarded;
+            }
+        }
+
+        req.extensions()
+            .get::<TcpConnectInfo>()
+            .and_then(|info| info.remote_addr())
+            .map(|addr| addr.ip())
+    }
+
+    /// `Some(status)` when the request must be rejected
+    async fn check(&self, bucket: &str, quota: Quota) -> Option<Status> {
+        match self.store.acquire(bucket, quota).await {
+            Ok(Decision::Allowed) => None,
+            Ok(Decision::Limited { retry_after }) => {
+                let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+                info!(bucket = bucket, retry_after_ms = retry_after.as_millis() as u64, "Rate limit exceeded");
+
+                let mut details = ErrorReason::RateLimited.details();
+                details.set_retry_info(Some(retry_after));
+                let mut status = ErrorReason::RateLimited.status_with("rate limit exceeded", details);
+                status
+                    .metadata_mut()
+                    .insert("retry-after", MetadataValue::from(seconds.max(1)));
+                Some(status)
+            }
+            Err(e) => {
+                // An unavailable store must not take the API down with it
+                warn!(store = self.store.name(), error = %e, "Rate limit store failed, allowing request");
+                None
+            }
+        }
+    }
+}
+
+/// Service produced by [`RateLimitLayer`]
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<Limiter>,
+}
+
+impl<S, B> Service<http::Request<B>> for RateLimitService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<tonic::body::Body>>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        // Use the instance that was polled ready and leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+        let bucket = limiter.bucket(&req);
+
+        Box::pin(async move {
+            if let Some((bucket, quota)) = bucket {
+                if let Some(status) = limiter.check(&bucket, quota).await {
+                    return Ok(status.into_http());
+                }
+            }
+            inner.call(req).await
+        })
+    }
+}
@@ -0,0 +1,86 @@
+use std::env;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use redis::aio::ConnectionManager;
+use redis::Script;
+
+use super::{Decision, Quota, RateLimitStore};
+
+/// Refill and take a token atomically, using the Redis clock so every
+/// instance agrees on elapsed time. Returns `{allowed, wait_ms}`.
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local rate = tonumber(ARGV[1])
+local burst = tonumber(ARGV[2])
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(state[1]) or burst
+local ts = tonumber(state[2]) or now
+tokens = math.min(burst, tokens + math.max(0, now - ts) * rate / 1000)
+
+local allowed = 0
+local wait = 0
+if tokens >= 1 then
+  tokens = tokens - 1
+  allowed = 1
+else
+  wait = math.ceil((1 - tokens) * 1000 / rate)
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil(burst * 1000 / rate) + 1000)
+return {allowed, wait}
+"#;
+
+/// Token buckets shared by every instance through Redis
+pub struct RedisRateLimitStore {
+    connection: ConnectionManager,
+    script: Script,
+    prefix: String,
+}
+
+impl RedisRateLimitStore {
+    /// Connect to `REDIS_URL`; bucket keys are prefixed with
+    /// `RATE_LIMIT_REDIS_PREFIX` (default `newsletter:ratelimit:`)
+    pub async fn from_env() -> anyhow::Result<Self> {
+        let url = env::var("REDIS_URL").map_err(|e| anyhow::anyhow!("REDIS_URL not set: {e}"))?;
+        let prefix = env::var("RATE_LIMIT_REDIS_PREFIX")
+            .unwrap_or_else(|_| "newsletter:ratelimit:".to_string());
+
+        let connection = redis::Client::open(url)?.get_connection_manager().await?;
+
+        Ok(Self {
+            connection,
+            script: Script::new(TOKEN_BUCKET_SCRIPT),
+            prefix,
+        })
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    async fn acquire(&self, key: &str, quota: Quota) -> anyhow::Result<Decision> {
+        let mut connection = self.connection.clone();
+        let (allowed, wait_ms): (i64, i64) = self
+            .script
+            .key(format!("{}{key}", self.prefix))
+            .arg(quota.per_second)
+            .arg(quota.burst)
+            .invoke_async(&mut connection)
+            .await?;
+
+        if allowed == 1 {
+            return Ok(Decision::Allowed);
+        }
+
+        Ok(Decision::Limited {
+            retry_after: Duration::from_millis(wait_ms.max(0) as u64),
+        })
+    }
+}
@@ -0,0 +1,68 @@
+use serde_json::{json, Value};
+
+use crate::infrastructure::rpc::auth::READ_METHODS;
+
+/// Services whose calls wait for a ready connection instead of failing fast
+const SERVICES: &[&str] = &[
+    "infrastructure.rpc.newsletter.v1.NewsletterService",
+    "infrastructure.rpc.newsletter.v2.NewsletterService",
+    "infrastructure.rpc.campaign.v1.CampaignService",
+    "infrastructure.rpc.template.v1.TemplateService",
+    "infrastructure.rpc.admin.v1.AdminService",
+];
+
+/// Methods without side effects that need more than a read key
+const OTHER_READ_METHODS: &[&str] = &[
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/ExportSubscriberData",
+    "/infrastructure.rpc.newsletter.v1.NewsletterService/GetSubscriberHistory",
+    "/infrastructure.rpc.admin.v1.AdminService/GetBuildInfo",
+    "/infrastructure.rpc.admin.v1.AdminService/GetMigrationStatus",
+    "/infrastructure.rpc.admin.v1.AdminService/GetDeliveryStatus",
+];
+
+/// Attempts of a read, the first one included
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Methods a client may retry or hedge: they only read, so running one
+/// twice answers the same as running it once. Paths as in `/service/Method`.
+pub fn read_methods() -> impl Iterator<Item = &'static str> {
+    READ_METHODS.iter().chain(OTHER_READ_METHODS).copied()
+}
+
+/// The gRPC service config for clients of this server, published as
+/// `service_config.json` next to the protos. Every call waits for a ready
+/// connection, and reads are retried on `UNAVAILABLE`, which the server
+/// answers while shutting down or with the database breaker open. Clients
+/// wanting hedging can swap the reads' `retryPolicy` for a `hedgingPolicy`.
+pub fn service_config() -> Value {
+    let reads: Vec<Value> = read_methods()
+        .filter_map(|path| path.trim_start_matches('/').split_once('/'))
+        .map(|(service, method)| json!({ "service": service, "method": method }))
+        .collect();
+    let services: Vec<Value> = SERVICES.iter().map(|service| json!({ "service": service })).collect();
+
+    json!({
+        "methodConfig": [
+            {
+                "name": reads,
+                "waitForReady": true,
+                "retryPolicy": {
+                    "maxAttempts": MAX_ATTEMPTS,
+                    "initialBackoff": "0.1s",
+                    "maxBackoff": "1s",
+                    "backoffMultiplier": 2,
+                    "retryableStatusCodes": ["UNAVAILABLE"],
+                },
+            },
+            {
+                "name": services,
+                "waitForReady": true,
+            },
+        ],
+        // Clients stop retrying while calls keep failing, so an outage is not multiplied
+        "retryThrottling": {
+            "maxTokens": 10,
+            "tokenRatio": 0.1,
+        },
+    })
+}
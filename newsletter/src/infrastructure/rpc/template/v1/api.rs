@@ -0,0 +1,409 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{error, info, instrument};
+
+use crate::domain::locale;
+use crate::domain::pagination::PageRequest;
+use crate::domain::template::asset::{self, NewAsset, TemplateAsset as DomainAsset};
+use crate::domain::template::lint::{IssueKind, TemplateDraft, TemplateIssue as DomainIssue};
+use crate::domain::template::translation::TemplateTranslation as DomainTranslation;
+use crate::domain::template::{self as domain, TemplateError};
+use crate::infrastructure::rpc::errors::ErrorReason;
+use crate::infrastructure::rpc::validation::{invalid_field, parse_page_token};
+use crate::infrastructure::rpc::{json, timestamp};
+use crate::service::template::TemplateService as TemplateServiceTrait;
+
+use crate::infrastructure::rpc::template::v1::proto::{
+    template_service_server::TemplateService, upload_asset_request::Part, CreateRequest,
+    CreateResponse, DeleteRequest, DeleteTranslationRequest, GetAssetRequest, GetAssetResponse,
+    GetRequest, GetResponse, ListRequest, ListResponse, ListTranslationsRequest,
+    ListTranslationsResponse, PutTranslationRequest, PutTranslationResponse, RenderRequest,
+    RenderResponse, Template, TemplateAsset, TemplateFormat, TemplateIssue, TemplateIssueKind,
+    TemplateTranslation, UpdateRequest, UpdateResponse, UploadAssetRequest, UploadAssetResponse,
+    UploadTranslationsRequest, UploadTranslationsResponse, ValidateTemplateRequest,
+    ValidateTemplateResponse,
+};
+
+#[derive(Clone)]
+pub struct MyTemplateService<S: TemplateServiceTrait> {
+    service: Arc<S>,
+}
+
+impl<S: TemplateServiceTrait> MyTemplateService<S> {
+    pub fn new(service: Arc<S>) -> Self {
+        Self { service }
+    }
+
+    fn to_proto(t: domain::Template) -> Template {
+        let format = match t.format {
+            domain::TemplateFormat::Handlebars => TemplateFormat::Handlebars,
+            domain::TemplateFormat::Mjml => TemplateFormat::Mjml,
+        };
+
+        Template {
+            id: t.id,
+            name: t.name,
+            format: format.into(),
+            body: t.body,
+            required_variables: t.required_variables,
+            created_at: Some(timestamp::to_proto(t.created_at)),
+            updated_at: Some(timestamp::to_proto(t.updated_at)),
+            version: t.version,
+            text_body: t.text_body.unwrap_or_default(),
+        }
+    }
+
+    fn translation_to_proto(t: DomainTranslation) -> TemplateTranslation {
+        TemplateTranslation {
+            template_id: t.template_id,
+            locale: t.locale,
+            body: t.body,
+            created_at: Some(timestamp::to_proto(t.created_at)),
+            updated_at: Some(timestamp::to_proto(t.updated_at)),
+        }
+    }
+
+    fn asset_to_proto(a: DomainAsset) -> TemplateAsset {
+        TemplateAsset {
+            id: a.id,
+            name: a.name,
+            content_type: a.content_type,
+            size_bytes: a.size_bytes,
+            sha256: a.sha256,
+            created_at: Some(timestamp::to_proto(a.created_at)),
+        }
+    }
+
+    fn issue_to_proto(issue: DomainIssue) -> TemplateIssue {
+        let kind = match issue.kind {
+            IssueKind::Syntax => TemplateIssueKind::Syntax,
+            IssueKind::UnknownVariable => TemplateIssueKind::UnknownVariable,
+            IssueKind::Render => TemplateIssueKind::Render,
+            IssueKind::Clipped => TemplateIssueKind::Clipped,
+        };
+
+        TemplateIssue {
+            kind: kind.into(),
+            message: issue.message,
+            variable: issue.variable.unwrap_or_default(),
+        }
+    }
+
+    fn parse_format(value: i32) -> Result<domain::TemplateFormat, Status> {
+        match TemplateFormat::try_from(value) {
+            Ok(TemplateFormat::Handlebars) => Ok(domain::TemplateFormat::Handlebars),
+            Ok(TemplateFormat::Mjml) => Ok(domain::TemplateFormat::Mjml),
+            _ => Err(invalid_field("format", "must be HANDLEBARS or MJML")),
+        }
+    }
+
+    /// Template problems are caller errors; anything else is internal.
+    fn to_status(operation: &str, e: anyhow::Error) -> Status {
+        match e.downcast_ref::<TemplateError>() {
+            Some(err @ TemplateError::VersionMismatch { .. }) => ErrorReason::VersionMismatch.status(err.to_string()),
+            Some(err @ TemplateError::AssetExists(_)) => ErrorReason::Conflict.status(err.to_string()),
+            Some(err @ TemplateError::AssetStorageDisabled) => ErrorReason::AssetStorageDisabled.status(err.to_string()),
+            Some(err) => ErrorReason::InvalidRequest.status(err.to_string()),
+            None => ErrorReason::Internal.status(format!("service error ({operation}): {e}")),
+        }
+    }
+
+    fn found(id: i64, template: Option<domain::Template>) -> Result<Template, Status> {
+        template
+            .map(Self::to_proto)
+            .ok_or_else(|| ErrorReason::TemplateNotFound.status(format!("template {id} not found")))
+    }
+}
+
+#[async_trait]
+impl<S: TemplateServiceTrait + 'static> TemplateService for MyTemplateService<S> {
+    #[instrument(skip(self), fields(name = %req.get_ref().name))]
+    async fn create(&self, req: Request<CreateRequest>) -> Result<Response<CreateResponse>, Status> {
+        let CreateRequest { name, format, body, required_variables, text_body } = req.into_inner();
+        let template = domain::NewTemplate {
+            name,
+            format: Self::parse_format(format)?,
+            body,
+            text_body,
+            required_variables,
+        };
+
+        match self.service.create_template(template).await {
+            Ok(template) => {
+                info!(operation = "create", crud_operation = "CREATE", entity = "template", id = template.id, "Successfully created template");
+                Ok(Response::new(CreateResponse {
+                    template: Some(Self::to_proto(template)),
+                }))
+            }
+            Err(e) => {
+                error!(operation = "create", crud_operation = "CREATE", entity = "template", error = %e, "Failed to create template");
+                Err(Self::to_status("create_template", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(id = req.get_ref().id))]
+    async fn get(&self, req: Request<GetRequest>) -> Result<Response<GetResponse>, Status> {
+        let id = req.into_inner().id;
+
+        match self.service.get_template(id).await {
+            Ok(template) => Ok(Response::new(GetResponse {
+                template: Some(Self::found(id, template)?),
+            })),
+            Err(e) => {
+                error!(operation = "get", crud_operation = "READ", entity = "template", id = id, error = %e, "Failed to retrieve template");
+                Err(Self::to_status("get_template", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(id = req.get_ref().id))]
+    async fn update(&self, req: Request<UpdateRequest>) -> Result<Response<UpdateResponse>, Status> {
+        let UpdateRequest {
+            id,
+            name,
+            format,
+            body,
+            required_variables,
+            expected_version,
+            text_body,
+        } = req.into_inner();
+        let update = domain::TemplateUpdate {
+            name,
+            format: format.map(Self::parse_format).transpose()?,
+            body,
+            text_body: text_body.map(|text_body| Some(text_body).filter(|t| !t.is_empty())),
+            required_variables: required_variables.map(|v| v.names),
+            expected_version,
+        };
+
+        match self.service.update_template(id, update).await {
+            Ok(template) => Ok(Response::new(UpdateResponse {
+                template: Some(Self::found(id, template)?),
+            })),
+            Err(e) => {
+                error!(operation = "update", crud_operation = "UPDATE", entity = "template", id = id, error = %e, "Failed to update template");
+                Err(Self::to_status("update_template", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(id = req.get_ref().id))]
+    async fn delete(&self, req: Request<DeleteRequest>) -> Result<Response<()>, Status> {
+        let id = req.into_inner().id;
+
+        match self.service.delete_template(id).await {
+            Ok(true) => Ok(Response::new(())),
+            Ok(false) => Err(ErrorReason::TemplateNotFound.status(format!("template {id} not found"))),
+            Err(e) => {
+                error!(operation = "delete", crud_operation = "DELETE", entity = "template", id = id, error = %e, "Failed to delete template");
+                Err(Self::to_status("delete_template", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(page_size = req.get_ref().page_size))]
+    async fn list(&self, req: Request<ListRequest>) -> Result<Response<ListResponse>, Status> {
+        let ListRequest { page_size, page_token } = req.into_inner();
+        let page = PageRequest::new(i64::from(page_size), parse_page_token(&page_token)?);
+
+        let page = match self.service.list_templates(page).await {
+            Ok(page) => page,
+            Err(e) => {
+                error!(operation = "list", crud_operation = "READ", entity = "template", error = %e, "Failed to retrieve template list");
+                return Err(Self::to_status("list_templates", e));
+            }
+        };
+
+        Ok(Response::new(ListResponse {
+            templates: page.items.into_iter().map(Self::to_proto).collect(),
+            next_page_token: page.next_cursor.map(|c| c.to_string()).unwrap_or_default(),
+        }))
+    }
+
+    #[instrument(skip(self), fields(template_id = req.get_ref().template_id))]
+    async fn render(&self, req: Request<RenderRequest>) -> Result<Response<RenderResponse>, Status> {
+        let RenderRequest { template_id, context, locale } = req.into_inner();
+        let context = json::struct_to_json(context.unwrap_or_default());
+        let locale = Some(locale.trim()).filter(|l| !l.is_empty());
+        if locale.is_some_and(|l| !locale::is_valid(l)) {
+            return Err(invalid_field("locale", "must be a BCP 47 tag such as en-US"));
+        }
+
+        match self.service.render(template_id, &context, locale).await {
+            Ok(Some(rendered)) => Ok(Response::new(RenderResponse {
+                html: rendered.html,
+                locale: rendered.locale.unwrap_or_default(),
+                text: rendered.text,
+            })),
+            Ok(None) => Err(ErrorReason::TemplateNotFound.status(format!("template {template_id} not found"))),
+            Err(e) => {
+                error!(operation = "render", entity = "template", template_id = template_id, error = %e, "Failed to render template");
+                Err(Self::to_status("render", e))
+            }
+        }
+    }
+
+    #[instrument(skip_all)]
+    async fn validate_template(
+        &self,
+        req: Request<ValidateTemplateRequest>,
+    ) -> Result<Response<ValidateTemplateResponse>, Status> {
+        let ValidateTemplateRequest { format, body, required_variables, sample, text_body } = req.into_inner();
+        let draft = TemplateDraft {
+            format: Self::parse_format(format)?,
+            body,
+            text_body: Some(text_body).filter(|t| !t.is_empty()),
+            required_variables,
+        };
+        let sample = sample.map(json::struct_to_json);
+
+        match self.service.validate_template(draft, sample).await {
+            Ok(report) => {
+                let valid = report.is_valid();
+                let html = report.html.unwrap_or_default();
+                Ok(Response::new(ValidateTemplateResponse {
+                    valid,
+                    issues: report.issues.into_iter().map(Self::issue_to_proto).collect(),
+                    html_bytes: html.len() as i64,
+                    html,
+                    text: report.text.unwrap_or_default(),
+                }))
+            }
+            Err(e) => {
+                error!(operation = "validate_template", entity = "template", error = %e, "Failed to validate template");
+                Err(Self::to_status("validate_template", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self, req), fields(template_id = req.get_ref().template_id, locale = %req.get_ref().locale))]
+    async fn put_translation(&self, req: Request<PutTranslationRequest>) -> Result<Response<PutTranslationResponse>, Status> {
+        let PutTranslationRequest { template_id, locale, body } = req.into_inner();
+
+        match self.service.put_translation(template_id, &locale, &body).await {
+            Ok(Some(translation)) => {
+                info!(operation = "put_translation", crud_operation = "UPDATE", entity = "template", template_id = template_id, locale = %translation.locale, "Successfully saved template translation");
+                Ok(Response::new(PutTranslationResponse {
+                    translation: Some(Self::translation_to_proto(translation)),
+                }))
+            }
+            Ok(None) => Err(ErrorReason::TemplateNotFound.status(format!("template {template_id} not found"))),
+            Err(e) => {
+                error!(operation = "put_translation", crud_operation = "UPDATE", entity = "template", template_id = template_id, error = %e, "Failed to save template translation");
+                Err(Self::to_status("put_translation", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self, req), fields(template_id = req.get_ref().template_id, count = req.get_ref().translations.len()))]
+    async fn upload_translations(
+        &self,
+        req: Request<UploadTranslationsRequest>,
+    ) -> Result<Response<UploadTranslationsResponse>, Status> {
+        let UploadTranslationsRequest { template_id, translations, replace } = req.into_inner();
+        let count = translations.len();
+
+        match self.service.upload_translations(template_id, translations, replace).await {
+            Ok(Some(translations)) => {
+                info!(operation = "upload_translations", crud_operation = "UPDATE", entity = "template", template_id = template_id, uploaded = count, replace = replace, "Successfully uploaded template translations");
+                Ok(Response::new(UploadTranslationsResponse {
+                    translations: translations.into_iter().map(Self::translation_to_proto).collect(),
+                }))
+            }
+            Ok(None) => Err(ErrorReason::TemplateNotFound.status(format!("template {template_id} not found"))),
+            Err(e) => {
+                error!(operation = "upload_translations", crud_operation = "UPDATE", entity = "template", template_id = template_id, error = %e, "Failed to upload template translations");
+                Err(Self::to_status("upload_translations", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(template_id = req.get_ref().template_id, locale = %req.get_ref().locale))]
+    async fn delete_translation(&self, req: Request<DeleteTranslationRequest>) -> Result<Response<()>, Status> {
+        let DeleteTranslationRequest { template_id, locale } = req.into_inner();
+
+        match self.service.delete_translation(template_id, &locale).await {
+            Ok(true) => Ok(Response::new(())),
+            Ok(false) => Err(ErrorReason::TemplateNotFound.status(format!("template {template_id} has no {locale} translation"))),
+            Err(e) => {
+                error!(operation = "delete_translation", crud_operation = "DELETE", entity = "template", template_id = template_id, error = %e, "Failed to delete template translation");
+                Err(Self::to_status("delete_translation", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(template_id = req.get_ref().template_id))]
+    async fn list_translations(&self, req: Request<ListTranslationsRequest>) -> Result<Response<ListTranslationsResponse>, Status> {
+        let template_id = req.into_inner().template_id;
+
+        match self.service.list_translations(template_id).await {
+            Ok(translations) => Ok(Response::new(ListTranslationsResponse {
+                translations: translations.into_iter().map(Self::translation_to_proto).collect(),
+            })),
+            Err(e) => {
+                error!(operation = "list_translations", crud_operation = "READ", entity = "template", template_id = template_id, error = %e, "Failed to retrieve template translations");
+                Err(Self::to_status("list_translations", e))
+            }
+        }
+    }
+
+    #[instrument(skip_all)]
+    async fn upload_asset(
+        &self,
+        req: Request<Streaming<UploadAssetRequest>>,
+    ) -> Result<Response<UploadAssetResponse>, Status> {
+        let mut stream = req.into_inner();
+        let Some(Part::Metadata(metadata)) = stream.message().await?.and_then(|m| m.part) else {
+            return Err(invalid_field("metadata", "is required in the first message"));
+        };
+        let new_asset = NewAsset {
+            name: metadata.name,
+            content_type: metadata.content_type,
+        };
+
+        let mut content = Vec::new();
+        while let Some(message) = stream.message().await? {
+            let Some(Part::Chunk(chunk)) = message.part else {
+                return Err(invalid_field("metadata", "is only allowed in the first message"));
+            };
+            // Rejected as it arrives rather than after buffering all of it
+            if content.len() + chunk.len() > asset::MAX_ASSET_BYTES {
+                return Err(invalid_field("chunk", format!("asset is larger than {} bytes", asset::MAX_ASSET_BYTES)));
+            }
+            content.extend_from_slice(&chunk);
+        }
+
+        match self.service.upload_asset(new_asset, content).await {
+            Ok(asset) => {
+                info!(operation = "upload_asset", crud_operation = "CREATE", entity = "template_asset", id = asset.id, name = %asset.name, size_bytes = asset.size_bytes, "Successfully uploaded template asset");
+                Ok(Response::new(UploadAssetResponse {
+                    asset: Some(Self::asset_to_proto(asset)),
+                }))
+            }
+            Err(e) => {
+                error!(operation = "upload_asset", crud_operation = "CREATE", entity = "template_asset", error = %e, "Failed to upload template asset");
+                Err(Self::to_status("upload_asset", e))
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(name = %req.get_ref().name))]
+    async fn get_asset(&self, req: Request<GetAssetRequest>) -> Result<Response<GetAssetResponse>, Status> {
+        let name = req.into_inner().name;
+
+        match self.service.get_asset(&name).await {
+            Ok(Some(signed)) => Ok(Response::new(GetAssetResponse {
+                asset: Some(Self::asset_to_proto(signed.asset)),
+                url: signed.url,
+                expires_at: Some(timestamp::to_proto(signed.expires_at)),
+            })),
+            Ok(None) => Err(ErrorReason::AssetNotFound.status(format!("asset {name:?} not found"))),
+            Err(e) => {
+                error!(operation = "get_asset", crud_operation = "READ", entity = "template_asset", name = %name, error = %e, "Failed to retrieve template asset");
+                Err(Self::to_status("get_asset", e))
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tonic::Status;
+use tower::{Layer, Service};
+use tracing::info;
+
+use crate::domain::auth::ApiKey;
+use crate::domain::tenant::{TenantId, TenantScope};
+use crate::infrastructure::rpc::errors::ErrorReason;
+use crate::infrastructure::tenant;
+
+/// Metadata naming the tenant a call works on
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// Tenant of a call: the one its API key is bound to, otherwise the one named
+/// in [`TENANT_HEADER`], otherwise the default tenant. A bound key naming
+/// another tenant in the header is refused rather than silently narrowed.
+pub fn resolve_tenant(api_key: Option<&ApiKey>, header: Option<&http::HeaderValue>) -> Result<TenantId, Status> {
+    let requested = header
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| TenantId::parse(value).ok())
+                .ok_or_else(|| ErrorReason::TenantInvalid.status(format!("{TENANT_HEADER} is not a valid tenant id")))
+        })
+        .transpose()?;
+
+    match (api_key.and_then(|key| key.tenant.clone()), requested) {
+        (Some(bound), Some(requested)) if bound != requested => Err(ErrorReason::TenantMismatch.status(format!(
+            "api key is bound to tenant {bound} and cannot act for {requested}"
+        ))),
+        (Some(bound), _) => Ok(bound),
+        (None, requested) => Ok(requested.unwrap_or_default()),
+    }
+}
+
+/// Tower layer running every call in its tenant's scope, so repositories only
+/// see that tenant's rows. Goes inside [`AuthLayer`](super::auth::AuthLayer),
+/// whose authenticated key it reads.
+#[derive(Clone, Default)]
+pub struct TenantLayer;
+
+impl TenantLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for TenantLayer {
+    type Service = TenantMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TenantMiddleware { inner }
+    }
+}
+
+/// Service produced by [`TenantLayer`]
+#[derive(Clone)]
+pub struct TenantMiddleware<S> {
+    inner: S,
+}
+
+impl<S, B> Service<http::Request<B>> for TenantMiddleware<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<tonic::body::Body>>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        // Use the instance that was polled ready and leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let resolved = resolve_tenant(req.extensions().get::<ApiKey>(), req.headers().get(TENANT_HEADER));
+
+        Box::pin(async move {
+            let tenant = match resolved {
+                Ok(tenant) => tenant,
+                Err(status) => {
+                    info!(method = %req.uri().path(), error = %status.message(), "Rejected call for tenant");
+                    return Ok(status.into_http());
+                }
+            };
+
+            tenant::scope(TenantScope::One(tenant), async move { inner.call(req).await }).await
+        })
+    }
+}
@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use prost_types::Timestamp;
+use tonic::Status;
+
+use crate::infrastructure::rpc::validation::invalid_field;
+
+/// Convert a domain timestamp into its protobuf representation
+pub fn to_proto(value: DateTime<Utc>) -> Timestamp {
+    Timestamp {
+        seconds: value.timestamp(),
+        nanos: value.timestamp_subsec_nanos() as i32,
+    }
+}
+
+/// Convert a protobuf timestamp into a domain timestamp
+pub fn from_proto(field: &str, value: Timestamp) -> Result<DateTime<Utc>, Status> {
+    let nanos = u32::try_from(value.nanos)
+        .map_err(|_| invalid_field(field, "has negative nanos"))?;
+
+    DateTime::from_timestamp(value.seconds, nanos)
+        .ok_or_else(|| invalid_field(field, "is out of range"))
+}
@@ -0,0 +1,28 @@
+use std::fs;
+
+use anyhow::Context;
+use tonic::transport::{Certificate, Identity, ServerTlsConfig};
+
+use crate::infrastructure::config::TlsSettings;
+
+/// Server TLS from the configured PEM files; `None` serves plaintext, for
+/// deployments where the mesh terminates TLS
+pub fn server_tls_config(settings: &TlsSettings) -> anyhow::Result<Option<ServerTlsConfig>> {
+    let Some((cert_path, key_path)) = settings.identity() else {
+        return Ok(None);
+    };
+
+    let identity = Identity::from_pem(read(cert_path)?, read(key_path)?);
+    let mut config = ServerTlsConfig::new().identity(identity);
+
+    // mTLS: only clients with a certificate signed by this CA get through
+    if let Some(ca_path) = settings.client_ca() {
+        config = config.client_ca_root(Certificate::from_pem(read(ca_path)?));
+    }
+
+    Ok(Some(config))
+}
+
+fn read(path: &str) -> anyhow::Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("failed to read {path}"))
+}
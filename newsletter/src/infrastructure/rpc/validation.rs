@@ -0,0 +1,125 @@
+use tonic::Status;
+use tonic_types::FieldViolation;
+
+use crate::domain::{locale, timezone};
+use crate::domain::newsletter::{EmailAddress, Tag, MAX_ADDRESS_LEN};
+use crate::infrastructure::rpc::errors::ErrorReason;
+
+/// Most addresses a bulk call may carry
+pub const MAX_BATCH_SIZE: usize = 10_000;
+
+/// Violations reported back; a batch of bad addresses stops being listed here
+/// so the details still fit in the response trailers
+const MAX_REPORTED: usize = 20;
+
+/// Request messages that check their own fields before the handler touches
+/// the service or the database
+pub trait Validate {
+    fn validate(&self, violations: &mut Violations);
+}
+
+/// Check a request, failing with every violation found
+pub fn validate<T: Validate>(request: &T) -> Result<(), Status> {
+    let mut violations = Violations::default();
+    request.validate(&mut violations);
+    violations.into_result()
+}
+
+/// A request rejected over one field, for checks made while decoding it
+pub fn invalid_field(field: &str, description: impl Into<String>) -> Status {
+    let mut violations = Violations::default();
+    violations.add(field, description);
+    violations.into_result().expect_err("a violation was added")
+}
+
+/// Page tokens are the opaque string form of the keyset cursor; empty asks
+/// for the first page
+pub fn parse_page_token(token: &str) -> Result<Option<i64>, Status> {
+    if token.is_empty() {
+        return Ok(None);
+    }
+
+    token
+        .parse::<i64>()
+        .map(Some)
+        .map_err(|_| invalid_field("page_token", "is not a token from a previous page"))
+}
+
+/// Field violations of one request, returned as `INVALID_ARGUMENT` with a
+/// `google.rpc.BadRequest` detail next to the `INVALID_REQUEST` reason
+#[derive(Debug, Default)]
+pub struct Violations {
+    fields: Vec<FieldViolation>,
+    total: usize,
+}
+
+impl Violations {
+    pub fn add(&mut self, field: impl Into<String>, description: impl Into<String>) {
+        self.total += 1;
+        if self.fields.len() < MAX_REPORTED {
+            self.fields.push(FieldViolation::new(field, description));
+        }
+    }
+
+    /// A mailbox address; the length is checked first so oversized input is never parsed
+    pub fn email(&mut self, field: &str, value: &str) {
+        if value.trim().len() > MAX_ADDRESS_LEN {
+            self.add(field, format!("must be at most {MAX_ADDRESS_LEN} bytes"));
+        } else if let Err(e) = EmailAddress::parse(value) {
+            self.add(field, e.to_string());
+        }
+    }
+
+    /// A list of addresses holding at most `max` entries
+    pub fn emails(&mut self, field: &str, values: &[String], max: usize) {
+        if !self.batch(field, values, max) {
+            return;
+        }
+        for (i, value) in values.iter().enumerate() {
+            self.email(&format!("{field}[{i}]"), value);
+        }
+    }
+
+    /// Only the size of a list of addresses; whether it was within `max`
+    pub fn batch(&mut self, field: &str, values: &[String], max: usize) -> bool {
+        if values.len() > max {
+            self.add(field, format!("must hold at most {max} addresses, got {}", values.len()));
+            return false;
+        }
+        true
+    }
+
+    pub fn tag(&mut self, field: &str, value: &str) {
+        if let Err(e) = Tag::parse(value) {
+            self.add(field, e.to_string());
+        }
+    }
+
+    /// A BCP 47 tag; empty passes, for fields where it means "none"
+    pub fn locale(&mut self, field: &str, value: &str) {
+        if !value.is_empty() && !locale::is_valid(value) {
+            self.add(field, format!("must be a BCP 47 tag of at most {} characters, such as en-US", locale::MAX_LEN));
+        }
+    }
+
+    /// An IANA zone name; empty passes, for fields where it means "none"
+    pub fn timezone(&mut self, field: &str, value: &str) {
+        if !value.is_empty() && !timezone::is_valid(value) {
+            self.add(field, "must be an IANA timezone such as Europe/Berlin");
+        }
+    }
+
+    pub fn into_result(self) -> Result<(), Status> {
+        let Some(first) = self.fields.first() else {
+            return Ok(());
+        };
+
+        let mut message = format!("{}: {}", first.field, first.description);
+        if self.total > 1 {
+            message.push_str(&format!(" (and {} more)", self.total - 1));
+        }
+        let mut details = ErrorReason::InvalidRequest.details();
+        details.set_bad_request(self.fields);
+        Err(ErrorReason::InvalidRequest.status_with(message, details))
+    }
+}
@@ -0,0 +1,110 @@
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::task::{JoinHandle, JoinSet};
+use tokio::time::{timeout_at, Instant, MissedTickBehavior};
+use tracing::{info, warn};
+
+use crate::infrastructure::rpc::in_flight::InFlight;
+
+/// How long in-flight work gets to finish once shutdown starts
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Coordinates a graceful shutdown.
+///
+/// Once it starts, the gRPC server stops accepting calls and the background
+/// loops stop starting new iterations. Calls and iterations already running
+/// get up to the drain timeout to finish; whatever is left is aborted. An
+/// aborted job keeps its lease and is picked up again after a restart.
+pub struct Shutdown {
+    signal: watch::Sender<bool>,
+    tasks: JoinSet<()>,
+    requests: InFlight,
+    timeout: Duration,
+}
+
+impl Shutdown {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            signal: watch::Sender::new(false),
+            tasks: JoinSet::new(),
+            requests: InFlight::default(),
+            timeout,
+        }
+    }
+
+    /// Counter of gRPC calls in flight, fed by an `InFlightLayer`
+    pub fn requests(&self) -> InFlight {
+        self.requests.clone()
+    }
+
+    /// Resolves once shutdown has started
+    pub fn started(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut signal = self.signal.subscribe();
+        async move {
+            // An error means the sender is gone, which only happens on shutdown too
+            let _ = signal.wait_for(|started| *started).await;
+        }
+    }
+
+    /// Run `task` every `every` until shutdown starts
+    pub fn every<F, Fut>(&mut self, every: Duration, mut task: F)
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let started = self.started();
+        self.tasks.spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            tokio::pin!(started);
+            loop {
+                tokio::select! {
+                    _ = &mut started => return,
+                    _ = interval.tick() => task().await,
+                }
+            }
+        });
+    }
+
+    /// Run a task that returns on its own once [`Self::started`] resolves
+    pub fn spawn(&mut self, task: impl Future<Output = ()> + Send + 'static) {
+        self.tasks.spawn(task);
+    }
+
+    /// Start shutting down and wait for the server and background tasks,
+    /// aborting whatever is still running when the drain timeout runs out
+    pub async fn drain<T: Send + 'static>(mut self, mut server: JoinHandle<T>) -> Option<T> {
+        self.signal.send_replace(true);
+
+        let deadline = Instant::now() + self.timeout;
+        let requests = self.requests.count();
+        let tasks = self.tasks.len();
+        info!(requests = requests, tasks = tasks, timeout_secs = self.timeout.as_secs(), "Draining in-flight work");
+
+        let output = match timeout_at(deadline, &mut server).await {
+            Ok(joined) => joined.ok(),
+            Err(_) => {
+                server.abort();
+                None
+            }
+        };
+        let aborted_requests = self.requests.count();
+
+        let mut drained_tasks = 0;
+        while let Ok(Some(_)) = timeout_at(deadline, self.tasks.join_next()).await {
+            drained_tasks += 1;
+        }
+        let aborted_tasks = self.tasks.len();
+        self.tasks.abort_all();
+
+        let drained_requests = requests.saturating_sub(aborted_requests);
+        if aborted_requests + aborted_tasks > 0 {
+            warn!(drained_requests = drained_requests, aborted_requests = aborted_requests, drained_tasks = drained_tasks, aborted_tasks = aborted_tasks, "Drain timeout reached, aborted in-flight work");
+        } else {
+            info!(drained_requests = drained_requests, aborted_requests = 0, drained_tasks = drained_tasks, aborted_tasks = 0, "Drained in-flight work");
+        }
+        output
+    }
+}
@@ -0,0 +1,191 @@
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderErrorReason,
+};
+use mrml::prelude::render::RenderOptions;
+use serde_json::Value;
+
+use crate::domain::template::{RenderedTemplate, Template, TemplateError, TemplateFormat};
+use crate::infrastructure::tenant;
+use crate::service::template::assets::AssetLinks;
+
+/// Column plaintext is wrapped at, as mail clients expect
+const TEXT_WIDTH: usize = 78;
+
+/// Most unknown variables looked for in one body
+const MAX_UNKNOWN_VARIABLES: usize = 50;
+
+/// `{{asset "logo.png"}}`: the public link of the current tenant's asset
+struct AssetHelper {
+    links: AssetLinks,
+}
+
+impl HelperDef for AssetHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let name = h
+            .param(0)
+            .and_then(|param| param.value().as_str())
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("asset", 0))?;
+        let tenant = tenant::current().tenant().cloned().unwrap_or_default();
+
+        out.write(&self.links.url(&tenant, name))?;
+        Ok(())
+    }
+}
+
+/// Renders stored templates: Handlebars substitution first, then MJML
+/// compilation for MJML templates. The plaintext part comes from the
+/// template's own text body when it has one, or from the HTML otherwise.
+#[derive(Clone)]
+pub struct TemplateEngine {
+    handlebars: Handlebars<'static>,
+    /// Renders text bodies, which must not be HTML-escaped
+    text: Handlebars<'static>,
+}
+
+impl Default for TemplateEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TemplateEngine {
+    pub fn new() -> Self {
+        let mut handlebars = Handlebars::new();
+        // Fail on unknown variables instead of silently rendering blanks
+        handlebars.set_strict_mode(true);
+        let mut text = handlebars.clone();
+        text.register_escape_fn(handlebars::no_escape);
+
+        Self { handlebars, text }
+    }
+
+    /// Render `{{asset "name"}}` as the link of the asset; without this the
+    /// helper is unknown and rendering a template that uses it fails
+    pub fn with_assets(mut self, links: AssetLinks) -> Self {
+        self.handlebars
+            .register_helper("asset", Box::new(AssetHelper { links: links.clone() }));
+        self.text.register_helper("asset", Box::new(AssetHelper { links }));
+        self
+    }
+
+    /// Check that a text body compiles without rendering it
+    pub fn validate_text(&self, text_body: &str) -> Result<(), TemplateError> {
+        handlebars::Template::compile(text_body)
+            .map(|_| ())
+            .map_err(|e| TemplateError::Render(format!("text body: {e}")))
+    }
+
+    /// Check that a template body compiles without rendering it
+    pub fn validate(&self, format: TemplateFormat, body: &str) -> Result<(), TemplateError> {
+        handlebars::Template::compile(body).map_err(|e| TemplateError::Render(e.to_string()))?;
+
+        // Placeholders are plain text to MJML, so the raw body must already parse
+        if format == TemplateFormat::Mjml {
+            mrml::parse(body).map_err(|e| TemplateError::Render(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Render a template against a JSON object context
+    pub fn render(
+        &self,
+        template: &Template,
+        context: &serde_json::Value,
+    ) -> Result<RenderedTemplate, TemplateError> {
+        let missing = template.missing_variables(context);
+        if !missing.is_empty() {
+            return Err(TemplateError::MissingVariables(missing));
+        }
+
+        let html = self.render_body(template.format, &template.body, context)?;
+        let text = self.render_text(template.text_body.as_deref(), &html, context)?;
+
+        Ok(RenderedTemplate { html, text, locale: None })
+    }
+
+    /// Render a body of `format` to HTML without checking required variables
+    pub fn render_body(&self, format: TemplateFormat, body: &str, context: &Value) -> Result<String, TemplateError> {
+        let output = self
+            .handlebars
+            .render_template(body, context)
+            .map_err(|e| TemplateError::Render(e.to_string()))?;
+
+        match format {
+            TemplateFormat::Handlebars => Ok(output),
+            TemplateFormat::Mjml => mrml::parse(&output)
+                .map_err(|e| TemplateError::Render(e.to_string()))?
+                .element
+                .render(&RenderOptions::default())
+                .map_err(|e| TemplateError::Render(e.to_string())),
+        }
+    }
+
+    /// The text/plain part to go with `html`: `text_body` rendered when
+    /// given, else `html` converted to plaintext
+    pub fn render_text(&self, text_body: Option<&str>, html: &str, context: &Value) -> Result<String, TemplateError> {
+        match text_body {
+            Some(text_body) => self
+                .text
+                .render_template(text_body, context)
+                .map_err(|e| TemplateError::Render(format!("text body: {e}"))),
+            None => Self::plaintext(html),
+        }
+    }
+
+    /// Paths `body` reads that `context` lacks, in the order rendering
+    /// reaches them. Strict mode stops at the first one, so each is filled
+    /// with a placeholder in `context` and the render retried. A path that
+    /// cannot be filled from the root, such as one relative to an `#each`
+    /// item, is reported and ends the search.
+    pub fn unknown_variables(&self, body: &str, context: &mut Value) -> Vec<String> {
+        let mut unknown = Vec::new();
+        while unknown.len() < MAX_UNKNOWN_VARIABLES {
+            let Err(e) = self.handlebars.render_template(body, context) else {
+                break;
+            };
+            let RenderErrorReason::MissingVariable(Some(path)) = e.reason() else {
+                break;
+            };
+            if unknown.contains(path) {
+                break;
+            }
+
+            unknown.push(path.clone());
+            if !fill(context, path) {
+                break;
+            }
+        }
+        unknown
+    }
+
+    /// Plaintext rendering of `html` for the text/plain part of a message
+    pub fn plaintext(html: &str) -> Result<String, TemplateError> {
+        html2text::from_read(html.as_bytes(), TEXT_WIDTH).map_err(|e| TemplateError::Render(e.to_string()))
+    }
+}
+
+/// Set the variable at `path` to a placeholder, creating the objects on the
+/// way; returns false if the path runs through a value that is not an object
+fn fill(context: &mut Value, path: &str) -> bool {
+    let mut value = context;
+    for segment in path.split(['.', '/']).filter(|s| !s.is_empty() && *s != "this") {
+        let Some(object) = value.as_object_mut() else {
+            return false;
+        };
+        value = object.entry(segment).or_insert(Value::Null);
+    }
+    if !value.is_null() {
+        return false;
+    }
+
+    *value = Value::String(format!("[{path}]"));
+    true
+}
@@ -0,0 +1,21 @@
+use std::future::Future;
+
+use crate::domain::tenant::TenantScope;
+
+tokio::task_local! {
+    static CURRENT: TenantScope;
+}
+
+/// Run `work` with `scope` as the tenant of every repository call it makes.
+///
+/// The gRPC tenant layer wraps each request in the caller's tenant and the
+/// job runner each job in the tenant that queued it. The scope does not
+/// follow `tokio::spawn`; spawned work has to be scoped again.
+pub async fn scope<F: Future>(scope: TenantScope, work: F) -> F::Output {
+    CURRENT.scope(scope, work).await
+}
+
+/// Tenant scope of the running task; the default tenant outside any scope
+pub fn current() -> TenantScope {
+    CURRENT.try_with(TenantScope::clone).unwrap_or_default()
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use hickory_resolver::error::{ResolveError, ResolveErrorKind};
+use hickory_resolver::proto::op::ResponseCode;
+use hickory_resolver::TokioAsyncResolver;
+
+use crate::domain::newsletter::verification::DisposableDomains;
+use crate::infrastructure::config::VerificationSettings;
+use crate::service::campaign::sending_domains::TxtLookup;
+use crate::service::newsletter::verification::{AddressVerifier, DomainListSource, MailDomainResolver};
+
+/// Longest wait for one DNS answer; subscribe waits on it
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Longest wait for a remote blocklist
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Build the address verifier the settings ask for, with its blocklist
+/// loaded; `None` when no check is enabled
+pub async fn from_settings(settings: &VerificationSettings) -> Result<Option<Arc<AddressVerifier>>> {
+    let mut verifier = AddressVerifier::new();
+    let mut enabled = false;
+
+    if let Some(location) = settings.disposable_domains() {
+        verifier = verifier.with_disposable_domains(Arc::new(DomainListLocation::new(location)?));
+        enabled = true;
+    }
+    if settings.mx_lookup {
+        verifier = verifier.with_mx_lookup(Arc::new(DnsMailDomainResolver::from_system_conf()?));
+        enabled = true;
+    }
+    if !enabled {
+        return Ok(None);
+    }
+
+    if settings.disposable_domains().is_some() {
+        verifier.refresh().await.context("failed to load the disposable domain list")?;
+    }
+    Ok(Some(Arc::new(verifier)))
+}
+
+/// MX and TXT lookups through the system's resolvers
+pub struct DnsMailDomainResolver {
+    resolver: TokioAsyncResolver,
+}
+
+impl DnsMailDomainResolver {
+    pub fn from_system_conf() -> Result<Self> {
+        let (config, mut options) =
+            hickory_resolver::system_conf::read_system_conf().context("failed to read the system DNS configuration")?;
+        options.timeout = LOOKUP_TIMEOUT;
+        options.attempts = 1;
+        Ok(Self {
+            resolver: TokioAsyncResolver::tokio(config, options),
+        })
+    }
+}
+
+/// The lookup found nothing, and whether that is because the name does not exist
+fn no_records(e: &ResolveError) -> Option<bool> {
+    match e.kind() {
+        ResolveErrorKind::NoRecordsFound { response_code, .. } => Some(*response_code == ResponseCode::NXDomain),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl MailDomainResolver for DnsMailDomainResolver {
+    /// Falls back to address records when there is no MX, as senders do
+    async fn accepts_mail(&self, domain: &str) -> Result<bool> {
+        // Fully qualified, so search domains are not tried
+        let name = format!("{}.", domain.trim_end_matches('.'));
+
+        match self.resolver.mx_lookup(name.as_str()).await {
+            // RFC 7505: a lone MX of "." says the domain takes no mail
+            Ok(mx) => Ok(!mx.iter().all(|record| record.exchange().is_root())),
+            Err(e) => match no_records(&e) {
+                Some(true) => Ok(false),
+                Some(false) => match self.resolver.lookup_ip(name.as_str()).await {
+                    Ok(_) => Ok(true),
+                    Err(e) if no_records(&e).is_some() => Ok(false),
+                    Err(e) => Err(e.into()),
+                },
+                None => Err(e.into()),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl TxtLookup for DnsMailDomainResolver {
+    async fn txt_records(&self, name: &str) -> Result<Vec<String>> {
+        let name = format!("{}.", name.trim_end_matches('.'));
+
+        match self.resolver.txt_lookup(name.as_str()).await {
+            // A record longer than 255 bytes is split into strings that are
+            // read back joined
+            Ok(txt) => Ok(txt
+                .iter()
+                .map(|record| {
+                    record
+                        .txt_data()
+                        .iter()
+                        .map(|part| String::from_utf8_lossy(part))
+                        .collect::<String>()
+                })
+                .collect()),
+            Err(e) if no_records(&e).is_some() => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Blocklist kept in a local file or at an `http(s)://` URL
+pub enum DomainListLocation {
+    File(String),
+    Url { url: String, client: reqwest::Client },
+}
+
+impl DomainListLocation {
+    pub fn new(location: &str) -> Result<Self> {
+        if location.starts_with("http://") || location.starts_with("https://") {
+            let client = reqwest::Client::builder()
+                .timeout(DOWNLOAD_TIMEOUT)
+                .build()
+                .context("failed to build blocklist HTTP client")?;
+            Ok(DomainListLocation::Url {
+                url: location.to_string(),
+                client,
+            })
+        } else {
+            Ok(DomainListLocation::File(location.to_string()))
+        }
+    }
+}
+
+#[async_trait]
+impl DomainListSource for DomainListLocation {
+    async fn load(&self) -> Result<DisposableDomains> {
+        let list = match self {
+            DomainListLocation::File(path) => tokio::fs::read_to_string(path)
+                .await
+                .with_context(|| format!("failed to read {path}"))?,
+            DomainListLocation::Url { url, client } => client
+                .get(url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .with_context(|| format!("failed to fetch {url}"))?
+                .text()
+                .await
+                .with_context(|| format!("failed to read {url}"))?,
+        };
+        Ok(DisposableDomains::parse(&list))
+    }
+}
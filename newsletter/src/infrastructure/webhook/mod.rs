@@ -0,0 +1,247 @@
+use std::{env, sync::Arc, time::{Duration, Instant}};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use reqwest::StatusCode;
+use sha2::Sha256;
+use tracing::{error, info, warn};
+
+use crate::domain::jobs::{DeliverWebhook, Job, JobKind, NewJob};
+use crate::domain::newsletter::SubscriptionEvent;
+use crate::domain::webhook::DeadLetter;
+use crate::infrastructure::events::EventPublisher;
+use crate::repository::jobs::JobRepository;
+use crate::repository::webhook::WebhookDeadLetterRepository;
+use crate::service::delivery::DeliveryTracker;
+use crate::service::jobs::JobHandler;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Name of the event carried by the request, e.g. `newsletter.subscribed`
+pub const EVENT_HEADER: &str = "x-webhook-event";
+/// Unix seconds at signing time; part of the signed content to stop replays
+pub const TIMESTAMP_HEADER: &str = "x-webhook-timestamp";
+/// `sha256=<hex>` HMAC of `"{timestamp}.{body}"` keyed with the shared secret
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+
+/// Endpoints and delivery settings for webhook notifications
+#[derive(Clone)]
+pub struct WebhookConfig {
+    pub urls: Vec<String>,
+    pub secret: Vec<u8>,
+    pub timeout: Duration,
+    /// Deliveries per event and endpoint before it is dead-lettered
+    pub max_attempts: u32,
+}
+
+impl WebhookConfig {
+    /// Read `WEBHOOK_URLS` (comma-separated), `WEBHOOK_SECRET`,
+    /// `WEBHOOK_TIMEOUT_SECS` and `WEBHOOK_MAX_ATTEMPTS`.
+    ///
+    /// Returns `None` when no URL is configured.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let urls: Vec<String> = env::var("WEBHOOK_URLS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        if urls.is_empty() {
+            return Ok(None);
+        }
+
+        let secret = env::var("WEBHOOK_SECRET")
+            .map_err(|e| anyhow::anyhow!("WEBHOOK_SECRET not set: {e}"))?;
+
+        Ok(Some(Self {
+            urls,
+            secret: secret.into_bytes(),
+            timeout: Duration::from_secs(
+                env::var("WEBHOOK_TIMEOUT_SECS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(10),
+            ),
+            max_attempts: env::var("WEBHOOK_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+        }))
+    }
+}
+
+struct Inner {
+    client: reqwest::Client,
+    config: WebhookConfig,
+    dead_letters: Arc<dyn WebhookDeadLetterRepository>,
+    jobs: Arc<dyn JobRepository>,
+    tracker: Arc<DeliveryTracker>,
+}
+
+/// Delivery of subscription events to the configured endpoints.
+///
+/// Each endpoint gets its own `deliver_webhook` job, which this dispatcher
+/// also runs: transient failures (network errors, 429, 5xx) are retried by
+/// the job runner with backoff, and deliveries that still fail are written to
+/// the dead-letter table instead of being dropped.
+#[derive(Clone)]
+pub struct WebhookDispatcher {
+    inner: Arc<Inner>,
+}
+
+enum DeliveryError {
+    Transient(String),
+    Permanent(String),
+}
+
+impl WebhookDispatcher {
+    pub fn new(
+        config: WebhookConfig,
+        dead_letters: Arc<dyn WebhookDeadLetterRepository>,
+        jobs: Arc<dyn JobRepository>,
+    ) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .context("failed to build webhook HTTP client")?;
+
+        info!(endpoints = config.urls.len(), max_attempts = config.max_attempts, "Configured webhook dispatcher");
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                client,
+                config,
+                dead_letters,
+                jobs,
+                tracker: Arc::new(DeliveryTracker::new()),
+            }),
+        })
+    }
+
+    /// Delivery attempts by endpoint
+    pub fn tracker(&self) -> Arc<DeliveryTracker> {
+        self.inner.tracker.clone()
+    }
+
+    /// Queue a delivery of the event to every endpoint
+    pub async fn dispatch(&self, event: &SubscriptionEvent) -> anyhow::Result<()> {
+        let payload = serde_json::to_value(event).context("failed to serialize webhook payload")?;
+        let max_attempts = i32::try_from(self.inner.config.max_attempts).unwrap_or(i32::MAX);
+
+        for url in &self.inner.config.urls {
+            let job = NewJob::new(
+                JobKind::DeliverWebhook,
+                &DeliverWebhook {
+                    url: url.clone(),
+                    event_type: event.kind.as_str().to_string(),
+                    event: payload.clone(),
+                },
+            )?
+            .max_attempts(max_attempts);
+            self.inner.jobs.enqueue(&job).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventPublisher for WebhookDispatcher {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn publish(&self, event: &SubscriptionEvent) -> anyhow::Result<()> {
+        self.dispatch(event).await
+    }
+}
+
+#[async_trait]
+impl JobHandler for WebhookDispatcher {
+    /// Transient failures are handed back to the runner to retry until the
+    /// last attempt; after that, and for permanent failures, the delivery is
+    /// dead-lettered and the job finishes.
+    async fn run(&self, job: &Job) -> anyhow::Result<()> {
+        let DeliverWebhook { url, event_type, event } = job.payload()?;
+        let attempt = job.attempts + 1;
+
+        let started = Instant::now();
+        let delivered = self.inner.deliver(&url, &event_type, &event.to_string()).await;
+        let latency = started.elapsed();
+        if let Err(DeliveryError::Transient(e) | DeliveryError::Permanent(e)) = &delivered {
+            self.inner.tracker.failed(&url, latency, e);
+        }
+        let last_error = match delivered {
+            Ok(()) => {
+                self.inner.tracker.delivered(&url, latency);
+                info!(url = %url, event_type = %event_type, attempt = attempt, "Webhook delivered");
+                return Ok(());
+            }
+            Err(DeliveryError::Transient(e)) if !job.is_last_attempt() => {
+                warn!(url = %url, event_type = %event_type, attempt = attempt, error = %e, "Webhook delivery failed, retrying");
+                return Err(anyhow::anyhow!(e));
+            }
+            Err(DeliveryError::Transient(e) | DeliveryError::Permanent(e)) => e,
+        };
+
+        error!(url = %url, event_type = %event_type, attempts = attempt, error = %last_error, "Webhook delivery failed, moving to dead letters");
+
+        let dead_letter = DeadLetter {
+            url,
+            event_type,
+            payload: event,
+            attempts: u32::try_from(attempt).unwrap_or(0),
+            last_error,
+        };
+        self.inner.tracker.dead_lettered(&dead_letter.url);
+        self.inner.dead_letters.record(&dead_letter).await
+    }
+}
+
+impl Inner {
+    async fn deliver(&self, url: &str, event_type: &str, body: &str) -> Result<(), DeliveryError> {
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+
+        let response = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event_type)
+            .header(TIMESTAMP_HEADER, &timestamp)
+            .header(SIGNATURE_HEADER, self.sign(&timestamp, body))
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| DeliveryError::Transient(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else if status == StatusCode::TOO_MANY_REQUESTS
+            || status == StatusCode::REQUEST_TIMEOUT
+            || status.is_server_error()
+        {
+            Err(DeliveryError::Transient(format!("endpoint responded with {status}")))
+        } else {
+            Err(DeliveryError::Permanent(format!("endpoint responded with {status}")))
+        }
+    }
+
+    fn sign(&self, timestamp: &str, body: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(&self.config.secret)
+            .expect("HMAC accepts keys of any length");
+        mac.update(timestamp.as_bytes());
+        mac.update(b".");
+        mac.update(body.as_bytes());
+
+        let signature: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        format!("sha256={signature}")
+    }
+}
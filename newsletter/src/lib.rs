@@ -4,5 +4,4 @@ pub mod repository;
 pub mod service;
 
 // Re-export commonly used items for easier testing access
-#[cfg(test)]
 pub use infrastructure::db::{build_pool_with_url, run_migrations_with_url, PgPool};
\ No newline at end of file
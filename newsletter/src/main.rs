@@ -1,21 +1,144 @@
-use std::{env, net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tonic::transport::Server;
+use tonic_health::ServingStatus;
 use tonic_reflection::server::Builder as ReflBuilder;
 
-use infrastructure::db::{build_pool, run_migrations, PgPool};
-use infrastructure::rpc::newsletter::v1::proto::newsletter_service_server::NewsletterServiceServer;
-use infrastructure::rpc::newsletter::v1::{api::MyNewsletterService, proto};
-use infrastructure::logging;
+use newsletter::infrastructure::build_info;
+use newsletter::infrastructure::cache;
+use newsletter::infrastructure::verification;
+use newsletter::infrastructure::config::{DigestSettings, ReengagementSettings, Settings};
+use newsletter::infrastructure::db::{
+    build_pool, build_replica_pool, bypasses_row_security, pool_health, run_migrations, startup_check, warm_up,
+    DatabaseBackend, MigrationsMode, PgPool, PoolHealth, PoolLoad, ReadPool,
+};
+use newsletter::infrastructure::rpc::newsletter::v1::proto::newsletter_service_server::NewsletterServiceServer;
+use newsletter::infrastructure::rpc::newsletter::v1::{api::MyNewsletterService, proto};
+use newsletter::infrastructure::rpc::newsletter::v2::proto::newsletter_service_server::NewsletterServiceServer as NewsletterServiceV2Server;
+use newsletter::infrastructure::rpc::newsletter::v2::{
+    api::MyNewsletterService as MyNewsletterServiceV2, proto as proto_v2,
+};
+use newsletter::infrastructure::rpc::campaign::v1::proto::campaign_service_server::CampaignServiceServer;
+use newsletter::infrastructure::rpc::campaign::v1::{
+    api::MyCampaignService, proto as campaign_proto,
+};
+use newsletter::infrastructure::rpc::admin::v1::proto::admin_service_server::AdminServiceServer;
+use newsletter::infrastructure::rpc::admin::v1::{
+    api::MyAdminService, proto as admin_proto,
+};
+use newsletter::infrastructure::rpc::access_log::AccessLogLayer;
+use newsletter::infrastructure::rpc::auth::AuthLayer;
+use newsletter::infrastructure::rpc::compression::Codec;
+use newsletter::infrastructure::rpc::rate_limit::{self, RateLimitLayer};
+use newsletter::infrastructure::rpc::tls::server_tls_config;
+use newsletter::infrastructure::rpc::template::v1::proto::template_service_server::TemplateServiceServer;
+use newsletter::infrastructure::rpc::template::v1::{
+    api::MyTemplateService, proto as template_proto,
+};
+use newsletter::infrastructure::template::TemplateEngine;
+use newsletter::infrastructure::logging;
+use newsletter::infrastructure::reload::{self, Reloader};
+use newsletter::infrastructure::rpc::deadline::{DeadlineLayer, Deadlines};
+use newsletter::infrastructure::rpc::in_flight::InFlightLayer;
+use newsletter::infrastructure::rpc::tenant::TenantLayer;
+use newsletter::infrastructure::pseudonym::Pseudonymizer;
+use newsletter::infrastructure::shutdown::Shutdown;
 
-use repository::newsletter::postgres::PostgresNewsletterRepository;
-use service::newsletter::DefaultNewsletterService;
+use newsletter::domain::auth::{self, Scope};
+use newsletter::domain::campaign::test_send::TestRecipients;
+use newsletter::repository::api_key::postgres::PostgresApiKeyRepository;
+use newsletter::repository::api_key::ApiKeyRepository;
+use newsletter::repository::campaign::postgres::PostgresCampaignRepository;
+use newsletter::repository::idempotency::postgres::PostgresIdempotencyRepository;
+#[cfg(feature = "sqlite")]
+use newsletter::repository::jobs::memory::InMemoryJobRepository;
+use newsletter::repository::jobs::postgres::PostgresJobRepository;
+use newsletter::repository::breaker::{BreakerState, CircuitBreaker};
+use newsletter::repository::newsletter::breaker::CircuitBreakingNewsletterRepository;
+use newsletter::repository::newsletter::postgres::PostgresNewsletterRepository;
+use newsletter::repository::newsletter::retry::RetryingNewsletterRepository;
+#[cfg(feature = "sqlite")]
+use newsletter::repository::newsletter::sqlite::SqliteNewsletterRepository;
+use newsletter::repository::outbox::postgres::PostgresOutboxRepository;
+use newsletter::repository::retry::Retrier;
+use newsletter::repository::analytics::postgres::PostgresAnalyticsRepository;
+use newsletter::repository::subscriber_view::postgres::PostgresSubscriberViewRepository;
+use newsletter::repository::subscriber_view::SubscriberViewRepository;
+use newsletter::repository::sending_domain::postgres::PostgresSendingDomainRepository;
+use newsletter::repository::template::postgres::PostgresTemplateRepository;
+use newsletter::repository::warmup::postgres::PostgresWarmupRepository;
+use newsletter::repository::webhook::postgres::PostgresWebhookDeadLetterRepository;
+use newsletter::infrastructure::email;
+use newsletter::infrastructure::http::{self as http_server, assets::AssetHandler, complaints::ComplaintHandler, metrics::MetricsHandler, tracking::TrackingHandler, unsubscribe::UnsubscribeHandler};
+use newsletter::infrastructure::metrics::log::LogMetricsSink;
+use newsletter::infrastructure::metrics::prometheus::PrometheusSink;
+use newsletter::infrastructure::metrics::statsd::StatsdSink;
+use newsletter::infrastructure::metrics::{MetricsBackend, MetricsSink};
+use newsletter::infrastructure::analytics;
+use newsletter::infrastructure::assets;
+use newsletter::infrastructure::events::{self, EventPublisher, FanoutPublisher};
+use newsletter::infrastructure::feed::HttpContentSource;
+use newsletter::infrastructure::token::TokenSigner;
+use newsletter::infrastructure::webhook::{WebhookConfig, WebhookDispatcher};
+use newsletter::service::auth::{self as auth_service, DefaultAuthService};
+use newsletter::domain::jobs::JobKind;
+use newsletter::service::campaign::sender::CampaignSender;
+use newsletter::service::campaign::clicks::ClickRecorder;
+use newsletter::service::campaign::complaints::ComplaintProcessor;
+use newsletter::service::campaign::tracking::TrackingLinks;
+use newsletter::service::campaign::unsubscribe::UnsubscribeLinks;
+use newsletter::service::campaign::digest::DigestScheduler;
+use newsletter::service::campaign::reengagement::ReengagementScheduler;
+use newsletter::service::campaign::sending_domains::DefaultSendingDomainService;
+use newsletter::service::campaign::warmup::WarmupLimiter;
+use newsletter::service::campaign::{CampaignDispatcher, DefaultCampaignService};
+use newsletter::service::idempotency::IdempotencyGuard;
+use newsletter::service::jobs::JobRunner;
+use newsletter::service::delivery::{DeliveryMonitor, DeliveryStatus, DestinationStats};
+use newsletter::service::analytics::AnalyticsExporter;
+use newsletter::service::outbox::OutboxRelay;
+use newsletter::service::template::assets::AssetLinks;
+use newsletter::service::template::jobs::CollectAssets;
+use newsletter::service::template::DefaultTemplateService;
+use newsletter::service::newsletter::import::ImportThrottle;
+use newsletter::service::newsletter::jobs::{ApplyRetention, ConfirmationMailer, ExpirePending, RollupGrowth};
+use newsletter::service::newsletter::{ConfirmationConfig, DefaultNewsletterService, NewsletterService};
 
-use tracing::info;
+use tracing::{error, info, warn};
 
-mod domain;
-mod infrastructure;
-mod repository;
-mod service;
+/// How often expired pending subscriptions are purged
+const CONFIRMATION_PURGE_INTERVAL: chrono::Duration = chrono::Duration::minutes(15);
+
+/// How often the growth rollup looks for a finished day without a snapshot;
+/// the first run after midnight UTC records it
+const GROWTH_ROLLUP_INTERVAL: chrono::Duration = chrono::Duration::hours(1);
+
+/// How often unsubscribed subscriptions past their retention are anonymized or deleted
+const RETENTION_INTERVAL: chrono::Duration = chrono::Duration::hours(1);
+
+/// How often the analytics export looks for finished days not yet written;
+/// the first run after midnight UTC and the grace writes the day before
+const ANALYTICS_EXPORT_INTERVAL: chrono::Duration = chrono::Duration::hours(1);
+
+/// How often template assets past their grace without a reference are deleted
+const ASSET_COLLECTION_INTERVAL: chrono::Duration = chrono::Duration::hours(6);
+
+/// How often idempotency keys past their TTL are purged
+const IDEMPOTENCY_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often published outbox messages past their retention are purged
+const OUTBOX_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often the database pool is checked for the health service
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often repository retry counts are reported
+const RETRY_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often calls cut off at their deadline are reported
+const DEADLINE_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the outbox and webhook backlogs are reported
+const DELIVERY_REPORT_INTERVAL: Duration = Duration::from_secs(30);
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -24,75 +147,928 @@ async fn main() -> anyhow::Result<()> {
 
     // ---------- JSON logging with trace-id (tracing) ----------
     logging::init_tracing()?;
+    info!(
+        version = build_info::VERSION,
+        git_sha = build_info::GIT_SHA,
+        built_at = build_info::BUILD_TIMESTAMP,
+        features = ?build_info::features(),
+        "Starting newsletter"
+    );
+
+    // ---------- Settings: config file + env overrides ----------
+    let settings = Settings::load()?;
+    logging::set_filter(settings.logging.filter())?;
+    logging::set_log_pii(settings.logging.pii);
+    let pseudonyms = settings.privacy.pseudonymizer();
+    if let Some(pseudonyms) = &pseudonyms {
+        logging::set_pseudonyms(pseudonyms.clone());
+    }
+    if settings.database.backend == DatabaseBackend::Sqlite {
+        return serve_sqlite(&settings, pseudonyms).await;
+    }
 
     // ---------- DB: pool + migrations ----------
-    let _pool: PgPool = build_pool().await?;
-    run_migrations().await?; // matches signature that reads DATABASE_URL internally
+    let pool: PgPool = build_pool(&settings.database).await?;
+    match settings.database.migrations_mode() {
+        MigrationsMode::Apply => {
+            run_migrations(&settings.database).await?;
+            startup_check(&pool, &settings.database).await?;
+        }
+        MigrationsMode::Check => startup_check(&pool, &settings.database).await?,
+        MigrationsMode::Skip => {
+            warm_up(&pool, &settings.database).await?;
+            warn!("Skipping the migration check, the schema may not match this build");
+        }
+    }
+    if bypasses_row_security(&pool).await? {
+        warn!("The database role bypasses row-level security, tenants are not isolated from each other");
+    }
+    let reads = ReadPool::with_replica(pool.clone(), build_replica_pool(&settings.database).await?);
 
     // ---------- Address ----------
-    let host = env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
-    let port: u16 = env::var("PORT")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(50051);
+    let host = settings.server.host.clone();
+    let port = settings.server.port;
     let addr: SocketAddr = format!("{}:{}", host, port).parse()?;
 
     // ---------- Reflection (v1) ----------
     // Requires FILE_DESCRIPTOR_SET exposed from proto module and build.rs generating it.
     let reflection = ReflBuilder::configure()
         .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(proto_v2::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(campaign_proto::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(template_proto::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(admin_proto::FILE_DESCRIPTOR_SET)
         .build_v1()?; // tonic-reflection 0.14 uses build_v1()/build_v1alpha()
 
     info!(message = "Starting gRPC server", %host, %port);
 
+    // ---------- Graceful shutdown ----------
+    // Background loops and the servers stop taking work once this starts
+    let mut shutdown = Shutdown::new(settings.shutdown.drain_timeout());
+
+    // ---------- Reload on SIGHUP ----------
+    // The log filter, rate limits and blocklist follow the settings file
+    let reloader = Arc::new(Reloader::new(&settings));
+    shutdown.spawn(reload::follow(reloader.log_filter(), shutdown.started(), |filter| async move {
+        if let Err(e) = logging::set_filter(filter.as_deref()) {
+            error!(error = %e, "Failed to apply the reloaded log filter");
+        }
+    }));
+    #[cfg(unix)]
+    shutdown.spawn(reload::on_hangup(reloader.clone(), shutdown.started()));
+
+    // ---------- Metrics ----------
+    // The periodic reports below also go to the configured backend
+    let metrics_settings = &settings.metrics;
+    let metrics: Arc<dyn MetricsSink> = match metrics_settings.backend {
+        MetricsBackend::Log => Arc::new(LogMetricsSink),
+        MetricsBackend::Prometheus => {
+            let sink = Arc::new(PrometheusSink::new(&metrics_settings.prefix));
+            let metrics_addr: SocketAddr = format!("{}:{}", host, metrics_settings.port).parse()?;
+            let handler = Arc::new(MetricsHandler::new(sink.clone()));
+            let stopped = shutdown.started();
+            shutdown.spawn(async move {
+                let serve = http_server::serve(
+                    metrics_addr,
+                    move |req| {
+                        let handler = handler.clone();
+                        async move { handler.handle(req).await }
+                    },
+                    stopped,
+                );
+                if let Err(e) = serve.await {
+                    error!(error = %e, "Metrics server stopped");
+                }
+            });
+            sink
+        }
+        MetricsBackend::Statsd | MetricsBackend::Dogstatsd => Arc::new(
+            StatsdSink::connect(
+                &metrics_settings.statsd_addr,
+                &metrics_settings.prefix,
+                metrics_settings.backend == MetricsBackend::Dogstatsd
+            )
+            .await?,
+        ),
+    };
+    info!(backend = metrics.name(), "Configured metrics");
+
     // ---------- Dependency Injection Setup ----------
-    let pool = build_pool().await?;
-    run_migrations().await?;
-    
     // Create repository with dependency injection
-    let repository = Arc::new(PostgresNewsletterRepository::new(pool.clone()));
+    let retrier = Arc::new(Retrier::new(settings.database.retry_policy()));
+    let mut postgres_repository =
+        PostgresNewsletterRepository::new(pool.clone()).with_reads(reads.clone());
+    if let Some(pseudonyms) = &pseudonyms {
+        postgres_repository = postgres_repository.with_pseudonyms(pseudonyms.clone());
+    }
+    let mut repository =
+        CircuitBreakingNewsletterRepository::new(RetryingNewsletterRepository::new(postgres_repository, retrier.clone()));
+    let breaker = settings.database.breaker_policy().map(|policy| Arc::new(CircuitBreaker::new(policy)));
+    if let Some(breaker) = &breaker {
+        repository = repository.with_breaker(breaker.clone());
+    }
+    let repository = Arc::new(repository);
+
+    let retry_metrics = metrics.clone();
+    let retry_breaker = breaker.clone();
+    shutdown.every(RETRY_REPORT_INTERVAL, move || {
+        let counts = retrier.take_counts();
+        retry_metrics.count("db.retries", counts.retries, &[]);
+        retry_metrics.count("db.retries.recovered", counts.recovered, &[]);
+        retry_metrics.count("db.retries.exhausted", counts.exhausted, &[]);
+        retry_metrics.count("db.retries.over_budget", counts.over_budget, &[]);
+        if let Some(breaker) = &retry_breaker {
+            let counts = breaker.take_counts();
+            retry_metrics.count("db.breaker.opened", counts.opened, &[]);
+            retry_metrics.count("db.breaker.rejected", counts.rejected, &[]);
+        }
+        async move {
+            if counts.retries > 0 || counts.exhausted > 0 || counts.over_budget > 0 {
+                warn!(
+                    retries = counts.retries,
+                    recovered = counts.recovered,
+                    exhausted = counts.exhausted,
+                    over_budget = counts.over_budget,
+                    "Retried transient database errors"
+                );
+            }
+        }
+    });
+    let jobs = Arc::new(PostgresJobRepository::new(pool.clone()));
     
+    // ---------- Double opt-in ----------
+    let confirmation = confirmation(&settings);
+
+    // ---------- Email ----------
+    let mailer = email::sender_from_settings(&settings.email).await?;
+
+    // ---------- Events: broker + webhooks ----------
+    let mut publishers: Vec<Arc<dyn EventPublisher>> = vec![events::publisher_from_env().await?];
+    let dead_letters = Arc::new(PostgresWebhookDeadLetterRepository::new(pool.clone()));
+    let webhooks = match WebhookConfig::from_env()? {
+        Some(config) => Some(Arc::new(WebhookDispatcher::new(
+            config,
+            dead_letters.clone(),
+            jobs.clone(),
+        )?)),
+        None => None,
+    };
+    if let Some(webhooks) = &webhooks {
+        publishers.push(webhooks.clone());
+    }
+    let event_publisher = Arc::new(FanoutPublisher::new(publishers));
+
+    // ---------- Outbox relay ----------
+    // Events are written with each subscription change and published from here
+    let outbox_retention = settings.outbox.retention();
+    let outbox = Arc::new(PostgresOutboxRepository::new(pool.clone()));
+    let mut relay = OutboxRelay::new(outbox.clone(), event_publisher)
+    .with_batch_size(settings.outbox.batch_size);
+    if let Some(pseudonyms) = &pseudonyms {
+        relay = relay.with_pseudonyms(pseudonyms.clone());
+    }
+    let views: Option<Arc<dyn SubscriberViewRepository>> = settings
+        .outbox
+        .read_model
+        .then(|| Arc::new(PostgresSubscriberViewRepository::new(pool.clone())) as _);
+    if let Some(views) = &views {
+        relay = relay.with_read_model(views.clone());
+    }
+    let mut delivery = DeliveryMonitor::new(outbox.clone(), relay.tracker());
+    if let Some(webhooks) = &webhooks {
+        delivery = delivery.with_webhooks(dead_letters, webhooks.tracker());
+    }
+
+    let delivery_task = delivery.clone();
+    let delivery_metrics = metrics.clone();
+    shutdown.every(DELIVERY_REPORT_INTERVAL, move || {
+        let delivery = delivery_task.clone();
+        let metrics = delivery_metrics.clone();
+        async move {
+            match delivery.status().await {
+                Ok(status) => report_delivery(metrics.as_ref(), &delivery, &status),
+                Err(e) => warn!(error = %e, "Failed to read the delivery backlog"),
+            }
+        }
+    });
+
+    let relay_task = relay.clone();
+    shutdown.every(settings.outbox.poll_interval(), move || {
+        let relay = relay_task.clone();
+        async move {
+            if let Err(e) = relay.drain().await {
+                error!(error = %e, "Failed to relay outbox messages");
+            }
+        }
+    });
+
+    shutdown.every(OUTBOX_PURGE_INTERVAL, move || {
+        let relay = relay.clone();
+        async move {
+            if let Err(e) = relay.purge_sent(outbox_retention).await {
+                error!(error = %e, "Failed to purge sent outbox messages");
+            }
+        }
+    });
+
     // Create service with dependency injection
-    let newsletter_service = Arc::new(DefaultNewsletterService::new(repository));
+    let mut newsletter_service =
+        DefaultNewsletterService::new(repository, confirmation.clone(), jobs.clone())
+            .with_normalization(settings.normalization.rules())
+            .with_retention(settings.retention.rules()?);
+    let cache = cache::from_settings(&settings.cache).await?;
+    if let Some(cache) = &cache {
+        newsletter_service = newsletter_service.with_cache(cache.clone());
+    }
+    if let Some(verifier) = verification::from_settings(&settings.verification).await? {
+        newsletter_service = newsletter_service.with_verifier(verifier);
+    }
+    if let Some(views) = views {
+        newsletter_service = newsletter_service.with_read_model(views);
+    }
+    let newsletter_service: Arc<dyn NewsletterService> = Arc::new(newsletter_service);
+    if settings.verification.disposable_domains().is_some() {
+        let service = newsletter_service.clone();
+        shutdown.spawn(reload::follow(reloader.blocklist(), shutdown.started(), move |_| {
+            let service = service.clone();
+            async move {
+                match service.refresh_disposable_domains().await {
+                    Ok(domains) => info!(domains = domains, "Reloaded the disposable domain list"),
+                    // The list loaded before stays in use
+                    Err(e) => error!(error = %e, "Failed to reload the disposable domain list"),
+                }
+            }
+        }));
+    }
     
+    // ---------- Idempotency keys ----------
+    let idempotency_guard = IdempotencyGuard::new(
+        Arc::new(PostgresIdempotencyRepository::new(pool.clone())),
+        settings.idempotency.ttl(),
+    );
+
+    let purge_guard = idempotency_guard.clone();
+    shutdown.every(IDEMPOTENCY_PURGE_INTERVAL, move || {
+        let guard = purge_guard.clone();
+        async move {
+            if let Err(e) = guard.purge_expired().await {
+                error!(error = %e, "Failed to purge expired idempotency keys");
+            }
+        }
+    });
+
+    // Imports wait for the pool instead of queueing their batches on it
+    let import_throttle = ImportThrottle::new(settings.import.max_batches_in_flight, settings.import.max_wait())
+        .with_load(Arc::new(PoolLoad::new(pool.clone(), settings.database.max_size)));
+
     // Create gRPC service with dependency injection
-    let grpc_service = MyNewsletterService::new(newsletter_service);
+    let grpc_service = MyNewsletterService::new(newsletter_service.clone(), idempotency_guard.clone())
+        .with_strict_status_codes(settings.server.strict_status_codes)
+        .with_trust_forwarded_for(settings.server.trust_forwarded_for)
+        .with_compression_min_bytes(settings.server.compression_min_bytes)
+        .with_import_throttle(import_throttle.clone());
+    let grpc_service_v2 = MyNewsletterServiceV2::new(newsletter_service.clone(), idempotency_guard)
+        .with_strict_status_codes(settings.server.strict_status_codes)
+        .with_trust_forwarded_for(settings.server.trust_forwarded_for);
 
-    // ---------- Graceful shutdown ----------
-    // Standard tonic + Tokio signal pattern.
-    let shutdown = async {
-        let ctrl_c = async {
-            tokio::signal::ctrl_c()
-                .await
-                .expect("failed to install Ctrl+C handler");
-        };
+    // Templates
+    // `{{asset "name"}}` renders a link served by the tracking server, so it
+    // only works with tracking enabled
+    let asset_links = settings
+        .tracking
+        .enabled()
+        .map(|(base_url, secret)| AssetLinks::new(TokenSigner::new(secret), base_url));
+    let template_engine = match &asset_links {
+        Some(links) => TemplateEngine::new().with_assets(links.clone()),
+        None => TemplateEngine::new(),
+    };
+    let asset_store = assets::store_from_env()?;
+    let template_repository = Arc::new(PostgresTemplateRepository::new(pool.clone()));
+    let mut template_service = DefaultTemplateService::new(template_repository.clone(), template_engine.clone())
+        .with_locale_fallbacks(settings.campaign.locale_fallbacks.clone())
+        .with_attribute_schema(newsletter_service.clone());
+    if let Some(store) = &asset_store {
+        info!(store = store.name(), "Configured template asset storage");
+        template_service = template_service.with_assets(store.clone());
+    }
+    let template_service = Arc::new(template_service);
 
-        #[cfg(unix)]
-        let sigterm = async {
-            use tokio::signal::unix::{signal, SignalKind};
-            let mut term =
-                signal(SignalKind::terminate()).expect("failed to install SIGTERM handler");
-            term.recv().await;
+    // Campaign management
+    let campaign_repository = Arc::new(PostgresCampaignRepository::new(pool.clone()));
+    let campaign_service = Arc::new(
+        DefaultCampaignService::new(campaign_repository.clone(), jobs.clone()).with_test_sends(
+            template_service.clone(),
+            mailer.clone(),
+            TestRecipients::new(settings.campaign.test_recipients.clone()),
+        ),
+    );
+    // Sending domains are verified by looking their DKIM records up
+    let sending_domain_repository = Arc::new(PostgresSendingDomainRepository::new(pool.clone()));
+    let sending_domain_service = Arc::new(DefaultSendingDomainService::new(
+        sending_domain_repository.clone(),
+        Arc::new(verification::DnsMailDomainResolver::from_system_conf()?),
+    ));
+    let campaign_grpc_service = MyCampaignService::new(campaign_service.clone(), sending_domain_service);
+
+    // ---------- Open and click tracking ----------
+    // Links in campaign emails point at TRACKING_URL, normally routed here by the shortlink gateway.
+    // The unsubscribe links of campaign emails, and complaint reports that carry them, are
+    // served alongside.
+    let tracking = settings
+        .tracking
+        .enabled()
+        .map(|(base_url, secret)| TrackingLinks::new(TokenSigner::new(secret), base_url));
+    let unsubscribe_links = settings
+        .tracking
+        .enabled()
+        .map(|(base_url, secret)| UnsubscribeLinks::new(TokenSigner::new(secret), base_url));
+    if let (Some(links), Some(unsubscribe), Some(asset_links)) = (&tracking, &unsubscribe_links, &asset_links) {
+        let tracking_addr: SocketAddr = format!("{}:{}", host, settings.tracking.port).parse()?;
+        let handler = Arc::new(TrackingHandler::new(links.clone(), campaign_service.clone()));
+        let complaints = Arc::new(ComplaintHandler::new(
+            ComplaintProcessor::new(unsubscribe.clone(), campaign_service.clone(), newsletter_service.clone()),
+            metrics.clone(),
+        ));
+        let unsubscribe = Arc::new(UnsubscribeHandler::new(unsubscribe.clone(), newsletter_service.clone(), metrics.clone()));
+        let assets = Arc::new(AssetHandler::new(asset_links.clone(), template_service.clone()));
+        let stopped = shutdown.started();
+        shutdown.spawn(async move {
+            let serve = http_server::serve(
+                tracking_addr,
+                move |req| {
+                    let (handler, unsubscribe, complaints, assets) =
+                        (handler.clone(), unsubscribe.clone(), complaints.clone(), assets.clone());
+                    async move {
+                        if UnsubscribeHandler::matches(req.uri().path()) {
+                            unsubscribe.handle(req).await
+                        } else if ComplaintHandler::matches(req.uri().path()) {
+                            complaints.handle(req).await
+                        } else if AssetHandler::matches(req.uri().path()) {
+                            assets.handle(req).await
+                        } else {
+                            handler.handle(req).await
+                        }
+                    }
+                },
+                stopped,
+            );
+            if let Err(e) = serve.await {
+                error!(error = %e, "Tracking server stopped");
+            }
+        });
+    }
+
+    // Clicks the shortlink service answered itself arrive as events instead
+    if let Some(consumer) = events::click_consumer_from_env().await? {
+        let Some(links) = &tracking else {
+            anyhow::bail!("CLICK_EVENTS_SOURCE requires tracking to be enabled");
         };
+        info!(consumer = consumer.name(), "Consuming click events");
+        let recorder = Arc::new(ClickRecorder::new(links.clone(), campaign_service.clone()));
+        let stopped = shutdown.started();
+        shutdown.spawn(async move { consumer.run(recorder, Box::pin(stopped)).await });
+    }
+
+    let template_grpc_service = MyTemplateService::new(template_service.clone());
+    let admin_grpc_service = MyAdminService::new(
+        reloader.clone(),
+        cache,
+        outbox,
+        newsletter_service.clone(),
+        settings.database.url.clone(),
+        delivery,
+    );
+
+    // ---------- Digests ----------
+    let digests = settings
+        .digests
+        .iter()
+        .map(DigestSettings::digest)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let digest_scheduler = Arc::new(DigestScheduler::new(
+        digests,
+        Arc::new(HttpContentSource::new()?),
+        campaign_service.clone(),
+        jobs.clone(),
+    ));
 
-        #[cfg(not(unix))]
-        let sigterm = std::future::pending::<()>();
+    // ---------- Re-engagement ----------
+    let flows = settings
+        .reengagement
+        .iter()
+        .map(ReengagementSettings::reengagement)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let reengagement_scheduler = Arc::new(ReengagementScheduler::new(
+        flows,
+        campaign_repository.clone(),
+        campaign_service,
+        newsletter_service.clone(),
+        jobs.clone(),
+    ));
 
-        tokio::select! {
-            _ = ctrl_c => {},
-            _ = sigterm => {},
+    // ---------- Background jobs ----------
+    // Confirmation mails, webhook deliveries, campaign sends, digests,
+    // re-engagement, pending expiry, the growth rollup, retention, the
+    // analytics export and template asset collection
+    let mut sender = CampaignSender::new(
+        campaign_repository.clone(),
+        template_repository,
+        template_engine,
+        mailer.clone(),
+        jobs.clone(),
+        settings.campaign.throttle(),
+    )
+    .with_locale_fallbacks(settings.campaign.locale_fallbacks.clone())
+    .with_sending_domains(sending_domain_repository);
+    if let Some(links) = tracking {
+        sender = sender.with_tracking(links);
+    }
+    if let Some(links) = unsubscribe_links {
+        sender = sender.with_unsubscribe(links);
+    }
+    if let Some(schedule) = settings.campaign.warmup() {
+        info!(days = settings.campaign.warmup.len(), "Warming up sending domains");
+        sender = sender.with_warmup(WarmupLimiter::new(
+            Arc::new(PostgresWarmupRepository::new(pool.clone())),
+            schedule,
+            settings.campaign.warmup_domains(),
+            email::from_domain(),
+        ));
+    }
+    let confirmation_mailer = Arc::new(ConfirmationMailer::new(confirmation, mailer.clone()));
+    let mut runner = JobRunner::new(jobs.clone())
+        .register(JobKind::SendConfirmation, confirmation_mailer.clone())
+        .register(JobKind::SendEmailChange, confirmation_mailer.clone())
+        .register(JobKind::SendTopicConfirmation, confirmation_mailer)
+        .register(
+            JobKind::DispatchCampaign,
+            Arc::new(CampaignDispatcher::new(campaign_repository, jobs.clone())),
+        )
+        .register(JobKind::SendCampaignBatch, Arc::new(sender))
+        .register(JobKind::AssembleDigest, digest_scheduler.clone())
+        .register(JobKind::RunReengagement, reengagement_scheduler.clone())
+        .register_recurring(
+            JobKind::ExpirePending,
+            CONFIRMATION_PURGE_INTERVAL,
+            Arc::new(ExpirePending::new(newsletter_service.clone())),
+        )
+        .register_recurring(
+            JobKind::RollupGrowth,
+            GROWTH_ROLLUP_INTERVAL,
+            Arc::new(RollupGrowth::new(newsletter_service.clone())),
+        )
+        .register_recurring(
+            JobKind::ApplyRetention,
+            RETENTION_INTERVAL,
+            Arc::new(ApplyRetention::new(newsletter_service.clone(), metrics.clone())),
+        );
+    if let Some(webhooks) = webhooks {
+        runner = runner.register(JobKind::DeliverWebhook, webhooks);
+    }
+    if let Some(sink) = analytics::sink_from_env()? {
+        info!(sink = sink.name(), "Configured analytics export");
+        let repository = PostgresAnalyticsRepository::new(pool.clone()).with_reads(reads.clone());
+        let mut exporter = AnalyticsExporter::new(Arc::new(repository), sink);
+        if let Some(pseudonyms) = pseudonyms {
+            exporter = exporter.with_pseudonyms(pseudonyms);
+        }
+        runner = runner.register_recurring(JobKind::ExportAnalytics, ANALYTICS_EXPORT_INTERVAL, Arc::new(exporter));
+    }
+    if asset_store.is_some() {
+        runner = runner.register_recurring(
+            JobKind::CollectAssets,
+            ASSET_COLLECTION_INTERVAL,
+            Arc::new(CollectAssets::new(template_service)),
+        );
+    }
+    runner = runner.with_batch_size(settings.jobs.batch_size);
+    runner.start().await?;
+    digest_scheduler.start().await?;
+    reengagement_scheduler.start().await?;
+
+    let job_metrics = metrics.clone();
+    shutdown.every(settings.jobs.poll_interval(), move || {
+        let runner = runner.clone();
+        let metrics = job_metrics.clone();
+        async move {
+            match runner.drain().await {
+                Ok(claimed) => metrics.count("jobs.claimed", claimed as u64, &[]),
+                Err(e) => error!(error = %e, "Failed to run background jobs"),
+            }
+        }
+    });
+
+
+    // ---------- Health ----------
+    // The whole server reports NOT_SERVING while the database cannot answer
+    // or the circuit breaker refuses to ask it;
+    // a replica that cannot only sends reads back to the primary
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    let health_pool = pool.clone();
+    let health_task = health_reporter.clone();
+    let health_metrics = metrics.clone();
+    shutdown.every(HEALTH_CHECK_INTERVAL, move || {
+        let import_throttle = import_throttle.clone();
+        let pool = health_pool.clone();
+        let reads = reads.clone();
+        let breaker = breaker.clone();
+        let reporter = health_task.clone();
+        let metrics = health_metrics.clone();
+        async move {
+            if let Some(replica) = reads.check().await {
+                report_pool(metrics.as_ref(), "replica", replica);
+            }
+            let health = pool_health(&pool).await;
+            report_pool(metrics.as_ref(), "primary", health);
+            metrics.gauge("import.batches_in_flight", import_throttle.in_flight() as f64, &[]);
+            let breaker_state = breaker.as_ref().map(|breaker| breaker.state());
+            if let Some(state) = breaker_state {
+                metrics.gauge("db.breaker.open", if state == BreakerState::Open { 1.0 } else { 0.0 }, &[]);
+            }
+            let status = if !health.healthy {
+                warn!(connections = health.connections, idle_connections = health.idle_connections, "Database pool is unhealthy");
+                ServingStatus::NotServing
+            } else if breaker_state == Some(BreakerState::Open) {
+                warn!("Database circuit breaker is open");
+                ServingStatus::NotServing
+            } else {
+                ServingStatus::Serving
+            };
+            reporter.set_service_status("", status).await;
+        }
+    });
+
+    // ---------- API key auth ----------
+    let auth = if settings.auth.enabled {
+        let api_keys = Arc::new(PostgresApiKeyRepository::new(pool.clone()));
+
+        // Lets a fresh deployment create its first key without touching the database
+        if let Some(key) = &settings.auth.bootstrap_admin_key {
+            if !key.is_empty() && api_keys.ensure("bootstrap", &auth::hash_key(key), Scope::Admin).await? {
+                info!("Stored bootstrap admin api key");
+            }
+        }
+        if let Some(key) = &settings.auth.bootstrap_operator_key {
+            if !key.is_empty() && api_keys.ensure("bootstrap-operator", &auth::hash_key(key), Scope::Operator).await? {
+                info!("Stored bootstrap operator api key");
+            }
         }
 
-        info!("Shutdown signal received, stopping gRPC server gracefully...");
+        Some(AuthLayer::new(Arc::new(DefaultAuthService::new(
+            api_keys,
+            auth_service::DEFAULT_CACHE_TTL,
+        ))))
+    } else {
+        warn!("AUTH_ENABLED=false, the gRPC API accepts unauthenticated calls");
+        None
     };
 
+    // ---------- Rate limiting ----------
+    // Installed even without limits, so a reload can add them
+    let rate_limit_store = rate_limit::store_from_env().await?;
+    let ip_rate_limit = RateLimitLayer::per_ip(reloader.rate_limit(), rate_limit_store.clone());
+    let key_rate_limit = RateLimitLayer::per_key(reloader.rate_limit(), rate_limit_store);
+
+    // ---------- Deadlines ----------
+    let deadlines = Deadlines::default();
+    let deadline = DeadlineLayer::new(settings.server.max_deadline(), deadlines.clone());
+    let deadline_metrics = metrics.clone();
+    shutdown.every(DEADLINE_REPORT_INTERVAL, move || {
+        let counts = deadlines.take_counts();
+        deadline_metrics.count("rpc.deadline_exceeded", counts.client, &[("by", "client")]);
+        deadline_metrics.count("rpc.deadline_exceeded", counts.server, &[("by", "server")]);
+        async move {
+            if counts.client > 0 || counts.server > 0 {
+                warn!(
+                    client = counts.client,
+                    server = counts.server,
+                    "Calls cut off at their deadline"
+                );
+            }
+        }
+    });
+
+    // ---------- Shutdown signal ----------
+    let signal = shutdown_signal();
+
+    // ---------- TLS ----------
+    let mut builder = Server::builder();
+    match server_tls_config(&settings.tls)? {
+        Some(tls) => {
+            builder = builder.tls_config(tls)?;
+            info!(mtls = settings.tls.client_ca().is_some(), "Serving gRPC over TLS");
+        }
+        None => warn!("No TLS certificate configured, serving gRPC in plaintext"),
+    }
+
     // ---------- Server ----------
-    Server::builder()
-        .add_service(reflection)
-        .add_service(NewsletterServiceServer::new(grpc_service))
-        .serve_with_shutdown(addr, shutdown)
-        .await?; // let anyhow convert tonic::transport::Error
+    let mut server = tokio::spawn(
+        builder
+            .layer(AccessLogLayer::new())
+            .layer(InFlightLayer::new(shutdown.requests()))
+            .layer(deadline)
+            // Before auth, so callers with bad keys are limited too
+            .layer(ip_rate_limit)
+            .layer(tower::util::option_layer(auth))
+            .layer(TenantLayer::new())
+            .layer(key_rate_limit)
+            .add_service(reflection)
+            .add_service(health_service)
+            .add_service(
+                compressed(NewsletterServiceServer::new(grpc_service), &settings.server.compression)
+                    .max_decoding_message_size(settings.messages.newsletter.max_receive_bytes)
+                    .max_encoding_message_size(settings.messages.newsletter.max_send_bytes)
+            )
+            .add_service(
+                NewsletterServiceV2Server::new(grpc_service_v2)
+                    .max_decoding_message_size(settings.messages.newsletter.max_receive_bytes)
+                    .max_encoding_message_size(settings.messages.newsletter.max_send_bytes)
+            )
+            .add_service(
+                CampaignServiceServer::new(campaign_grpc_service)
+                    .max_decoding_message_size(settings.messages.campaign.max_receive_bytes)
+                    .max_encoding_message_size(settings.messages.campaign.max_send_bytes)
+            )
+            .add_service(
+                TemplateServiceServer::new(template_grpc_service)
+                    .max_decoding_message_size(settings.messages.template.max_receive_bytes)
+                    .max_encoding_message_size(settings.messages.template.max_send_bytes)
+            )
+            .add_service(
+                AdminServiceServer::new(admin_grpc_service)
+                    .max_decoding_message_size(settings.messages.admin.max_receive_bytes)
+                    .max_encoding_message_size(settings.messages.admin.max_send_bytes)
+            )
+            .serve_with_shutdown(addr, shutdown.started()),
+    );
+
+    tokio::select! {
+        // The server only stops on its own when it fails
+        result = &mut server => {
+            result??; // let anyhow convert tonic::transport::Error
+        }
+        _ = signal => {
+            health_reporter.set_service_status("", ServingStatus::NotServing).await;
+            if let Some(result) = shutdown.drain(server).await {
+                result?;
+            }
+        }
+    }
+
+    info!("Server stopped");
+    Ok(())
+}
+
+/// Report a health check of the `role` pool, `primary` or `replica`
+fn report_pool(metrics: &dyn MetricsSink, role: &str, health: PoolHealth) {
+    let tags = [("pool", role)];
+    metrics.gauge("db.pool.healthy", if health.healthy { 1.0 } else { 0.0 }, &tags);
+    metrics.gauge("db.pool.connections", f64::from(health.connections), &tags);
+    metrics.gauge("db.pool.idle_connections", f64::from(health.idle_connections), &tags);
+}
+
+/// Report the outbox and webhook backlogs, and the deliveries since the last report
+fn report_delivery(metrics: &dyn MetricsSink, monitor: &DeliveryMonitor, status: &DeliveryStatus) {
+    let now = chrono::Utc::now();
+    let age = |since: Option<chrono::DateTime<chrono::Utc>>| {
+        since.map_or(0.0, |since| (now - since).num_milliseconds().max(0) as f64 / 1000.0)
+    };
+
+    metrics.gauge("outbox.pending", status.outbox.pending as f64, &[]);
+    metrics.gauge("outbox.failing", status.outbox.failing as f64, &[]);
+    metrics.gauge("outbox.oldest_pending_seconds", age(status.outbox.oldest_pending_at), &[]);
+    for (publisher, counts) in monitor.relay().take_counts() {
+        let tags = [("publisher", publisher.as_str())];
+        metrics.count("outbox.published", counts.delivered, &tags);
+        metrics.count("outbox.publish_failures", counts.failed, &tags);
+    }
+    for stats in &status.publishers {
+        report_latency(metrics, "outbox.publish_latency_ms", ("publisher", &stats.destination), stats);
+    }
+
+    for webhook in &status.webhooks {
+        let tags = [("url", webhook.backlog.url.as_str())];
+        metrics.gauge("webhook.pending", webhook.backlog.pending as f64, &tags);
+        metrics.gauge("webhook.oldest_pending_seconds", age(webhook.backlog.oldest_pending_at), &tags);
+        metrics.gauge("webhook.dead_letters", webhook.backlog.dead_letters as f64, &tags);
+        if let Some(stats) = &webhook.stats {
+            report_latency(metrics, "webhook.latency_ms", ("url", &stats.destination), stats);
+        }
+    }
+    if let Some(tracker) = monitor.webhooks() {
+        for (url, counts) in tracker.take_counts() {
+            let tags = [("url", url.as_str())];
+            metrics.count("webhook.delivered", counts.delivered, &tags);
+            metrics.count("webhook.failures", counts.failed, &tags);
+            metrics.count("webhook.dead_lettered", counts.dead_lettered, &tags);
+        }
+    }
+}
+
+/// Report the latency percentiles of one destination, tagged with `quantile`
+fn report_latency(metrics: &dyn MetricsSink, name: &str, tag: (&str, &str), stats: &DestinationStats) {
+    if let Some(latency) = stats.latency {
+        for (quantile, value) in [("p50", latency.p50), ("p95", latency.p95), ("max", latency.max)] {
+            metrics.gauge(name, value.as_secs_f64() * 1000.0, &[tag, ("quantile", quantile)]);
+        }
+    }
+}
+
+/// Enable `codecs` on the newsletter service in both directions
+fn compressed(
+    mut server: NewsletterServiceServer<MyNewsletterService>,
+    codecs: &[Codec],
+) -> NewsletterServiceServer<MyNewsletterService> {
+    for codec in codecs {
+        server = server.accept_compressed(codec.encoding()).send_compressed(codec.encoding());
+    }
+    server
+}
+
+fn confirmation(settings: &Settings) -> ConfirmationConfig {
+    ConfirmationConfig {
+        signer: TokenSigner::new(settings.confirmation.secret.clone()),
+        ttl: settings.confirmation.ttl(),
+        confirm_url: settings.confirmation.url.clone(),
+        email_change_url: settings.confirmation.email_change_url.clone(),
+        topic_url: settings.confirmation.topic_url.clone(),
+        required_on_resubscribe: settings.confirmation.required_on_resubscribe,
+        pending_retention: settings.confirmation.pending_retention(),
+    }
+}
+
+/// Resolves on Ctrl+C or SIGTERM; the standard tonic + Tokio signal pattern
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let sigterm = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut term =
+            signal(SignalKind::terminate()).expect("failed to install SIGTERM handler");
+        term.recv().await;
+    };
+
+    #[cfg(not(unix))]
+    let sigterm = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = sigterm => {},
+    }
+
+    info!("Shutdown signal received, stopping gRPC server gracefully...");
+}
+
+/// Serve the newsletter API, v1 and v2, from a SQLite database for local
+/// development. Campaigns, templates, the admin API and tracking keep their
+/// data in Postgres and are not served; jobs are held in memory.
+#[cfg(feature = "sqlite")]
+async fn serve_sqlite(settings: &Settings, pseudonyms: Option<Pseudonymizer>) -> anyhow::Result<()> {
+    let mut repository = SqliteNewsletterRepository::open(&settings.database.url)?;
+    if let Some(pseudonyms) = &pseudonyms {
+        repository = repository.with_pseudonyms(pseudonyms.clone());
+    }
+    let repository = Arc::new(repository);
+    warn!(path = %settings.database.url, "Serving the newsletter API from SQLite, for local development only");
+
+    let addr: SocketAddr = format!("{}:{}", settings.server.host, settings.server.port).parse()?;
+    let mut shutdown = Shutdown::new(settings.shutdown.drain_timeout());
+    let jobs = Arc::new(InMemoryJobRepository::new());
+    let confirmation = confirmation(settings);
+
+    // ---------- Outbox relay ----------
+    let outbox_retention = settings.outbox.retention();
+    let mut relay = OutboxRelay::new(repository.clone(), events::publisher_from_env().await?)
+        .with_batch_size(settings.outbox.batch_size);
+    if let Some(pseudonyms) = pseudonyms {
+        relay = relay.with_pseudonyms(pseudonyms);
+    }
+    if settings.outbox.read_model {
+        warn!("outbox.read_model (OUTBOX_READ_MODEL) needs Postgres; reading from the newsletters table");
+    }
+    let relay_task = relay.clone();
+    shutdown.every(settings.outbox.poll_interval(), move || {
+        let relay = relay_task.clone();
+        async move {
+            if let Err(e) = relay.drain().await {
+                error!(error = %e, "Failed to relay outbox messages");
+            }
+        }
+    });
+    shutdown.every(OUTBOX_PURGE_INTERVAL, move || {
+        let relay = relay.clone();
+        async move {
+            if let Err(e) = relay.purge_sent(outbox_retention).await {
+                error!(error = %e, "Failed to purge sent outbox messages");
+            }
+        }
+    });
+
+    // ---------- Services ----------
+    let newsletter_service: Arc<dyn NewsletterService> = Arc::new(
+        DefaultNewsletterService::new(repository.clone(), confirmation.clone(), jobs.clone())
+            .with_normalization(settings.normalization.rules())
+            .with_retention(settings.retention.rules()?),
+    );
+    let idempotency_guard = IdempotencyGuard::new(repository, settings.idempotency.ttl());
+    let grpc_service = MyNewsletterService::new(newsletter_service.clone(), idempotency_guard.clone())
+        .with_strict_status_codes(settings.server.strict_status_codes)
+        .with_trust_forwarded_for(settings.server.trust_forwarded_for)
+        .with_compression_min_bytes(settings.server.compression_min_bytes)
+        .with_import_throttle(ImportThrottle::new(
+            settings.import.max_batches_in_flight,
+            settings.import.max_wait(),
+        ));
+    let grpc_service_v2 = MyNewsletterServiceV2::new(newsletter_service.clone(), idempotency_guard)
+        .with_strict_status_codes(settings.server.strict_status_codes)
+        .with_trust_forwarded_for(settings.server.trust_forwarded_for);
+
+    // ---------- Background jobs ----------
+    let confirmation_mailer = Arc::new(ConfirmationMailer::new(confirmation, email::sender_from_settings(&settings.email).await?));
+    let runner = JobRunner::new(jobs)
+        .register(JobKind::SendConfirmation, confirmation_mailer.clone())
+        .register(JobKind::SendEmailChange, confirmation_mailer.clone())
+        .register(JobKind::SendTopicConfirmation, confirmation_mailer)
+        .register_recurring(
+            JobKind::ExpirePending,
+            CONFIRMATION_PURGE_INTERVAL,
+            Arc::new(ExpirePending::new(newsletter_service.clone())),
+        )
+        .register_recurring(
+            JobKind::RollupGrowth,
+            GROWTH_ROLLUP_INTERVAL,
+            Arc::new(RollupGrowth::new(newsletter_service.clone())),
+        )
+        .register_recurring(
+            JobKind::ApplyRetention,
+            RETENTION_INTERVAL,
+            Arc::new(ApplyRetention::new(newsletter_service, Arc::new(LogMetricsSink))),
+        )
+        .with_batch_size(settings.jobs.batch_size);
+    runner.start().await?;
+    shutdown.every(settings.jobs.poll_interval(), move || {
+        let runner = runner.clone();
+        async move {
+            if let Err(e) = runner.drain().await {
+                error!(error = %e, "Failed to run background jobs");
+            }
+        }
+    });
+
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    let reflection = ReflBuilder::configure()
+        .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(proto_v2::FILE_DESCRIPTOR_SET)
+        .build_v1()?;
+
+    let mut builder = Server::builder();
+    if let Some(tls) = server_tls_config(&settings.tls)? {
+        builder = builder.tls_config(tls)?;
+    }
+    info!(message = "Starting gRPC server", %addr);
+    let mut server = tokio::spawn(
+        builder
+            .layer(AccessLogLayer::new())
+            .layer(InFlightLayer::new(shutdown.requests()))
+            .layer(DeadlineLayer::new(settings.server.max_deadline(), Deadlines::default()))
+            .layer(TenantLayer::new())
+            .add_service(reflection)
+            .add_service(health_service)
+            .add_service(
+                compressed(NewsletterServiceServer::new(grpc_service), &settings.server.compression)
+                    .max_decoding_message_size(settings.messages.newsletter.max_receive_bytes)
+                    .max_encoding_message_size(settings.messages.newsletter.max_send_bytes)
+            )
+            .add_service(
+                NewsletterServiceV2Server::new(grpc_service_v2)
+                    .max_decoding_message_size(settings.messages.newsletter.max_receive_bytes)
+                    .max_encoding_message_size(settings.messages.newsletter.max_send_bytes)
+            )
+            .serve_with_shutdown(addr, shutdown.started()),
+    );
+
+    tokio::select! {
+        result = &mut server => {
+            result??;
+        }
+        _ = shutdown_signal() => {
+            health_reporter.set_service_status("", ServingStatus::NotServing).await;
+            if let Some(result) = shutdown.drain(server).await {
+                result?;
+            }
+        }
+    }
 
     info!("Server stopped");
     Ok(())
 }
+
+#[cfg(not(feature = "sqlite"))]
+async fn serve_sqlite(_settings: &Settings, _pseudonyms: Option<Pseudonymizer>) -> anyhow::Result<()> {
+    anyhow::bail!("database.backend (DATABASE_BACKEND) sqlite needs a build with the sqlite feature")
+}
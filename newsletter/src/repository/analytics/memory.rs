@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::domain::analytics::{Dataset, DailyRows, EngagementRecord, SubscriptionEventRecord};
+use crate::repository::analytics::AnalyticsRepository;
+
+#[derive(Debug, Default)]
+struct State {
+    engagement: Vec<EngagementRecord>,
+    subscription_events: Vec<SubscriptionEventRecord>,
+    /// Rows exported per dataset and day
+    exported: BTreeMap<(&'static str, NaiveDate), usize>,
+}
+
+/// AnalyticsRepository kept in process memory, for tests that run without
+/// Postgres; rows are added with `record_engagement` and
+/// `record_subscription_event` and are not scoped to tenants
+#[derive(Debug, Default)]
+pub struct InMemoryAnalyticsRepository {
+    state: Mutex<State>,
+}
+
+impl InMemoryAnalyticsRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("in-memory repository lock poisoned")
+    }
+
+    pub fn record_engagement(&self, record: EngagementRecord) {
+        self.state().engagement.push(record);
+    }
+
+    pub fn record_subscription_event(&self, record: SubscriptionEventRecord) {
+        self.state().subscription_events.push(record);
+    }
+}
+
+#[async_trait]
+impl AnalyticsRepository for InMemoryAnalyticsRepository {
+    async fn last_exported(&self, dataset: Dataset) -> Result<Option<NaiveDate>> {
+        Ok(self
+            .state()
+            .exported
+            .keys()
+            .filter(|(name, _)| *name == dataset.as_str())
+            .map(|(_, day)| *day)
+            .max())
+    }
+
+    async fn first_day(&self, dataset: Dataset) -> Result<Option<NaiveDate>> {
+        let state = self.state();
+        let oldest = match dataset {
+            Dataset::Engagement => state.engagement.iter().map(|r| r.occurred_at).min(),
+            Dataset::SubscriptionEvents => state.subscription_events.iter().map(|r| r.occurred_at).min(),
+        };
+        Ok(oldest.map(|at| at.date_naive()))
+    }
+
+    async fn rows(&self, dataset: Dataset, day: NaiveDate) -> Result<DailyRows> {
+        let state = self.state();
+        Ok(match dataset {
+            Dataset::Engagement => {
+                let mut rows: Vec<EngagementRecord> = state
+                    .engagement
+                    .iter()
+                    .filter(|r| r.occurred_at.date_naive() == day)
+                    .cloned()
+                    .collect();
+                rows.sort_by_key(|r| r.occurred_at);
+                DailyRows::Engagement(rows)
+            }
+            Dataset::SubscriptionEvents => {
+                let mut rows: Vec<SubscriptionEventRecord> = state
+                    .subscription_events
+                    .iter()
+                    .filter(|r| r.occurred_at.date_naive() == day)
+                    .cloned()
+                    .collect();
+                rows.sort_by_key(|r| r.occurred_at);
+                DailyRows::SubscriptionEvents(rows)
+            }
+        })
+    }
+
+    async fn mark_exported(&self, dataset: Dataset, day: NaiveDate, rows: usize) -> Result<()> {
+        self.state().exported.insert((dataset.as_str(), day), rows);
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use crate::domain::analytics::{Dataset, DailyRows};
+
+#[cfg(any(test, feature = "testing"))]
+pub mod memory;
+pub mod postgres;
+
+/// Repository trait for the analytics export: reads the rows of a finished
+/// day and remembers which days were written, so each is exported once
+#[async_trait]
+pub trait AnalyticsRepository: Send + Sync {
+    /// Latest day of `dataset` exported, if any
+    async fn last_exported(&self, dataset: Dataset) -> Result<Option<NaiveDate>>;
+
+    /// Day of the oldest row of `dataset`, if it has any
+    async fn first_day(&self, dataset: Dataset) -> Result<Option<NaiveDate>>;
+
+    /// Every row of `dataset` from the UTC day `day`, across the tenants in
+    /// scope, oldest first
+    async fn rows(&self, dataset: Dataset, day: NaiveDate) -> Result<DailyRows>;
+
+    /// Record that `day` of `dataset` was exported with `rows` rows
+    async fn mark_exported(&self, dataset: Dataset, day: NaiveDate, rows: usize) -> Result<()>;
+}
@@ -0,0 +1,246 @@
+use crate::domain::analytics::{Dataset, DailyRows, EngagementRecord, SubscriptionEventRecord};
+use crate::infrastructure::db::db_schema::{analytics_exports, engagement_events, subscriber_events};
+use crate::infrastructure::db::{tenant_connection, PgPool, ReadPool};
+use crate::repository::analytics::AnalyticsRepository;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use diesel::dsl::{max, min};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use tracing::{error, info, instrument};
+
+#[derive(Queryable)]
+struct EngagementRow {
+    pub tenant_id: String,
+    pub campaign_id: i64,
+    pub email: String,
+    pub kind: String,
+    pub url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Queryable)]
+struct HistoryRow {
+    pub tenant_id: String,
+    pub event_type: String,
+    pub email: String,
+    pub previous_email: Option<String>,
+    pub status: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = analytics_exports)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewExportRow<'a> {
+    pub dataset: &'a str,
+    pub day: NaiveDate,
+    pub rows: i64,
+    pub exported_at: DateTime<Utc>,
+}
+
+/// PostgreSQL implementation of the AnalyticsRepository trait
+#[derive(Clone)]
+pub struct PostgresAnalyticsRepository {
+    pool: PgPool,
+    reads: ReadPool,
+}
+
+impl PostgresAnalyticsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            reads: ReadPool::primary(pool.clone()),
+            pool,
+        }
+    }
+
+    /// Read the rows to export from `reads`, which may lag behind the
+    /// primary by less than the grace a finished day is given
+    pub fn with_reads(mut self, reads: ReadPool) -> Self {
+        self.reads = reads;
+        self
+    }
+}
+
+#[async_trait]
+impl AnalyticsRepository for PostgresAnalyticsRepository {
+    #[instrument(skip(self))]
+    async fn last_exported(&self, dataset: Dataset) -> Result<Option<NaiveDate>> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "analytics_exports_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match analytics_exports::table
+            .filter(analytics_exports::dataset.eq(dataset.as_str()))
+            .select(max(analytics_exports::day))
+            .first::<Option<NaiveDate>>(&mut conn)
+            .await
+        {
+            Ok(day) => Ok(day),
+            Err(e) => {
+                error!(entity = "analytics_exports_table", crud_operation = "READ", dataset = %dataset, error = %e, "Failed to read the last exported day");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn first_day(&self, dataset: Dataset) -> Result<Option<NaiveDate>> {
+        let mut conn = match self.reads.tenant_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "analytics_exports_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = match dataset {
+            Dataset::Engagement => {
+                engagement_events::table
+                    .select(min(engagement_events::created_at))
+                    .first::<Option<DateTime<Utc>>>(&mut conn)
+                    .await
+            }
+            Dataset::SubscriptionEvents => {
+                subscriber_events::table
+                    .select(min(subscriber_events::occurred_at))
+                    .first::<Option<DateTime<Utc>>>(&mut conn)
+                    .await
+            }
+        };
+
+        match result {
+            Ok(oldest) => Ok(oldest.map(|at| at.date_naive())),
+            Err(e) => {
+                error!(entity = "analytics_exports_table", crud_operation = "READ", dataset = %dataset, error = %e, "Failed to find the oldest row");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn rows(&self, dataset: Dataset, day: NaiveDate) -> Result<DailyRows> {
+        info!(entity = "analytics_exports_table", crud_operation = "READ", dataset = %dataset, day = %day, "Starting database export read operation");
+
+        let mut conn = match self.reads.tenant_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "analytics_exports_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let start = day.and_time(NaiveTime::MIN).and_utc();
+        let end = start + Duration::days(1);
+        let result = match dataset {
+            Dataset::Engagement => engagement_events::table
+                .filter(engagement_events::created_at.ge(start))
+                .filter(engagement_events::created_at.lt(end))
+                .order(engagement_events::id.asc())
+                .select((
+                    engagement_events::tenant_id,
+                    engagement_events::campaign_id,
+                    engagement_events::email,
+                    engagement_events::kind,
+                    engagement_events::url,
+                    engagement_events::created_at,
+                ))
+                .load::<EngagementRow>(&mut conn)
+                .await
+                .map(|rows| {
+                    DailyRows::Engagement(
+                        rows.into_iter()
+                            .map(|row| EngagementRecord {
+                                tenant: row.tenant_id,
+                                campaign_id: row.campaign_id,
+                                email: row.email,
+                                kind: row.kind,
+                                url: row.url,
+                                occurred_at: row.created_at,
+                            })
+                            .collect(),
+                    )
+                }),
+            Dataset::SubscriptionEvents => subscriber_events::table
+                .filter(subscriber_events::occurred_at.ge(start))
+                .filter(subscriber_events::occurred_at.lt(end))
+                .order((subscriber_events::occurred_at.asc(), subscriber_events::id.asc()))
+                .select((
+                    subscriber_events::tenant_id,
+                    subscriber_events::event_type,
+                    subscriber_events::email,
+                    subscriber_events::previous_email,
+                    subscriber_events::status,
+                    subscriber_events::occurred_at,
+                ))
+                .load::<HistoryRow>(&mut conn)
+                .await
+                .map(|rows| {
+                    DailyRows::SubscriptionEvents(
+                        rows.into_iter()
+                            .map(|row| SubscriptionEventRecord {
+                                tenant: row.tenant_id,
+                                event_type: row.event_type,
+                                email: row.email,
+                                previous_email: row.previous_email,
+                                status: row.status,
+                                occurred_at: row.occurred_at,
+                            })
+                            .collect(),
+                    )
+                }),
+        };
+
+        match result {
+            Ok(rows) => {
+                info!(entity = "analytics_exports_table", crud_operation = "READ", dataset = %dataset, day = %day, rows = rows.len(), "Successfully read rows to export");
+                Ok(rows)
+            }
+            Err(e) => {
+                error!(entity = "analytics_exports_table", crud_operation = "READ", dataset = %dataset, day = %day, error = %e, "Failed to read rows to export");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn mark_exported(&self, dataset: Dataset, day: NaiveDate, rows: usize) -> Result<()> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "analytics_exports_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let row = NewExportRow {
+            dataset: dataset.as_str(),
+            day,
+            rows: rows as i64,
+            exported_at: Utc::now(),
+        };
+        match diesel::insert_into(analytics_exports::table)
+            .values(&row)
+            .on_conflict((analytics_exports::dataset, analytics_exports::day))
+            .do_update()
+            .set((
+                analytics_exports::rows.eq(row.rows),
+                analytics_exports::exported_at.eq(row.exported_at),
+            ))
+            .execute(&mut conn)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!(entity = "analytics_exports_table", crud_operation = "CREATE", dataset = %dataset, day = %day, error = %e, "Failed to record the export");
+                Err(e.into())
+            }
+        }
+    }
+}
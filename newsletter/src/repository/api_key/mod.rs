@@ -0,0 +1,15 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use crate::domain::auth::{ApiKey, Scope};
+
+pub mod postgres;
+
+/// Repository trait for API keys
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    /// Get the non-revoked key stored under `key_hash`
+    async fn find_active(&self, key_hash: &str) -> Result<Option<ApiKey>>;
+
+    /// Store a key unless one with the same hash exists; returns whether it was added
+    async fn ensure(&self, name: &str, key_hash: &str, scope: Scope) -> Result<bool>;
+}
@@ -0,0 +1,125 @@
+use crate::domain::auth::{ApiKey, Scope};
+use crate::domain::tenant::TenantId;
+use crate::infrastructure::db::db_schema::api_keys;
+use crate::infrastructure::db::PgPool;
+use crate::repository::api_key::ApiKeyRepository;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use tracing::{error, info, instrument};
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = api_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct ApiKeyRow {
+    pub id: i64,
+    pub name: String,
+    pub scope: String,
+    pub tenant_id: Option<String>,
+}
+
+impl TryFrom<ApiKeyRow> for ApiKey {
+    type Error = anyhow::Error;
+
+    fn try_from(row: ApiKeyRow) -> Result<Self> {
+        let scope = Scope::parse(&row.scope)
+            .ok_or_else(|| anyhow::anyhow!("unknown api key scope in database: {}", row.scope))?;
+        let tenant = row
+            .tenant_id
+            .map(|tenant| TenantId::parse(&tenant))
+            .transpose()?;
+
+        Ok(ApiKey {
+            id: row.id,
+            name: row.name,
+            scope,
+            tenant,
+        })
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = api_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewApiKeyRow<'a> {
+    pub name: &'a str,
+    pub key_hash: &'a str,
+    pub scope: &'a str,
+}
+
+/// PostgreSQL implementation of the ApiKeyRepository trait
+#[derive(Clone)]
+pub struct PostgresApiKeyRepository {
+    pool: PgPool,
+}
+
+impl PostgresApiKeyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PostgresApiKeyRepository {
+    #[instrument(skip(self, key_hash))]
+    async fn find_active(&self, key_hash: &str) -> Result<Option<ApiKey>> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "api_keys_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let row = match api_keys::table
+            .filter(api_keys::key_hash.eq(key_hash))
+            .filter(api_keys::revoked_at.is_null())
+            .select(ApiKeyRow::as_select())
+            .first(&mut conn)
+            .await
+            .optional()
+        {
+            Ok(row) => row,
+            Err(e) => {
+                error!(entity = "api_keys_table", crud_operation = "READ", error = %e, "Failed to look up api key");
+                return Err(e.into());
+            }
+        };
+
+        row.map(ApiKey::try_from).transpose()
+    }
+
+    #[instrument(skip(self, key_hash), fields(name = %name, scope = %scope))]
+    async fn ensure(&self, name: &str, key_hash: &str, scope: Scope) -> Result<bool> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "api_keys_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::insert_into(api_keys::table)
+            .values(&NewApiKeyRow {
+                name,
+                key_hash,
+                scope: scope.as_str(),
+            })
+            .on_conflict(api_keys::key_hash)
+            .do_nothing()
+            .execute(&mut conn)
+            .await
+        {
+            Ok(inserted) => {
+                info!(entity = "api_keys_table", crud_operation = "CREATE", name = %name, inserted = inserted == 1, "Ensured api key");
+                Ok(inserted == 1)
+            }
+            Err(e) => {
+                error!(entity = "api_keys_table", crud_operation = "CREATE", name = %name, error = %e, "Failed to store api key");
+                Err(e.into())
+            }
+        }
+    }
+}
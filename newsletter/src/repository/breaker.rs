@@ -0,0 +1,203 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+/// Errors a [`CircuitBreaker`] can tell apart
+pub trait Outage: Sized {
+    /// Whether the store could not be reached at all: refused or dropped
+    /// connections, pool timeouts. Errors about the call itself, such as a
+    /// serialization failure, say nothing about the store's health.
+    fn is_outage(&self) -> bool;
+
+    /// The error of a call refused while the breaker is open
+    fn circuit_open(retry_after: Duration) -> Self;
+}
+
+/// When a [`CircuitBreaker`] opens and how long it stays open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerPolicy {
+    /// Most recent calls the failure rate is taken over
+    pub window: u32,
+    /// Calls the window needs before it may open the breaker
+    pub min_calls: u32,
+    /// Share of the window, in percent, that must have failed to open it
+    pub failure_percent: u32,
+    /// How long calls are refused before one is let through to test the store
+    pub open_for: Duration,
+}
+
+impl Default for BreakerPolicy {
+    fn default() -> Self {
+        Self {
+            window: 20,
+            min_calls: 10,
+            failure_percent: 50,
+            open_for: Duration::from_secs(5),
+        }
+    }
+}
+
+/// What a [`CircuitBreaker`] does with the next call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Calls go through
+    Closed,
+    /// Calls are refused
+    Open,
+    /// The next call goes through to test the store; the breaker closes if
+    /// it succeeds and opens again if it does not
+    HalfOpen,
+}
+
+/// Breaker counters since the last [`CircuitBreaker::take_counts`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BreakerCounts {
+    /// Times the breaker opened
+    pub opened: u64,
+    /// Calls refused while it was open
+    pub rejected: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Closed,
+    Open { until: Instant },
+    /// A trial call went through at `since`
+    Trial { since: Instant },
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: State,
+    /// Whether each recent call failed, oldest first
+    outcomes: VecDeque<bool>,
+}
+
+/// Stops calling a store that keeps failing.
+///
+/// Once enough of the recent calls failed with an [`Outage`], every call
+/// is refused at once with [`Outage::circuit_open`] instead of waiting on a
+/// pool timeout, which keeps callers responsive and spares the store a
+/// reconnect storm. After the policy's `open_for` one call is let through;
+/// its outcome closes the breaker or opens it again.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    policy: BreakerPolicy,
+    inner: Mutex<Inner>,
+    opened: AtomicU64,
+    rejected: AtomicU64,
+}
+
+impl CircuitBreaker {
+    pub fn new(policy: BreakerPolicy) -> Self {
+        Self {
+            policy,
+            inner: Mutex::new(Inner {
+                state: State::Closed,
+                outcomes: VecDeque::with_capacity(policy.window as usize),
+            }),
+            opened: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    pub fn policy(&self) -> BreakerPolicy {
+        self.policy
+    }
+
+    /// Run `call` unless the breaker is open, recording whether it failed
+    pub async fn run<T, E, Fut>(&self, operation: &'static str, call: Fut) -> Result<T, E>
+    where
+        E: Outage,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        if let Err(retry_after) = self.admit() {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return Err(E::circuit_open(retry_after));
+        }
+        let result = call.await;
+        self.record(operation, matches!(&result, Err(e) if e.is_outage()));
+        result
+    }
+
+    pub fn state(&self) -> BreakerState {
+        match self.inner().state {
+            State::Closed => BreakerState::Closed,
+            State::Open { until } if Instant::now() < until => BreakerState::Open,
+            State::Open { .. } | State::Trial { .. } => BreakerState::HalfOpen,
+        }
+    }
+
+    /// Counters since the previous call, which resets them
+    pub fn take_counts(&self) -> BreakerCounts {
+        BreakerCounts {
+            opened: self.opened.swap(0, Ordering::Relaxed),
+            rejected: self.rejected.swap(0, Ordering::Relaxed),
+        }
+    }
+
+    fn inner(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().expect("circuit breaker lock poisoned")
+    }
+
+    /// Let a call through, or say how long until one will be
+    fn admit(&self) -> Result<(), Duration> {
+        let mut inner = self.inner();
+        let now = Instant::now();
+        match inner.state {
+            State::Closed => Ok(()),
+            State::Open { until } if now < until => Err(until - now),
+            // A trial that never reports back, say because its call was
+            // cancelled, gives way to another after `open_for`
+            State::Trial { since } if now < since + self.policy.open_for => Err(since + self.policy.open_for - now),
+            State::Open { .. } | State::Trial { .. } => {
+                inner.state = State::Trial { since: now };
+                Ok(())
+            }
+        }
+    }
+
+    fn record(&self, operation: &'static str, failed: bool) {
+        let mut inner = self.inner();
+        match inner.state {
+            State::Trial { .. } if failed => self.open(&mut inner, operation),
+            State::Trial { .. } => {
+                inner.state = State::Closed;
+                inner.outcomes.clear();
+                info!(operation = operation, "Database answered again, circuit breaker closed");
+            }
+            State::Closed => {
+                if inner.outcomes.len() >= self.policy.window as usize {
+                    inner.outcomes.pop_front();
+                }
+                inner.outcomes.push_back(failed);
+                let calls = inner.outcomes.len() as u64;
+                let failures = inner.outcomes.iter().filter(|failed| **failed).count() as u64;
+                if calls >= u64::from(self.policy.min_calls)
+                    && failures * 100 >= u64::from(self.policy.failure_percent) * calls
+                {
+                    self.open(&mut inner, operation);
+                }
+            }
+            // Calls let through before it opened
+            State::Open { .. } => {}
+        }
+    }
+
+    fn open(&self, inner: &mut Inner, operation: &'static str) {
+        inner.state = State::Open {
+            until: Instant::now() + self.policy.open_for,
+        };
+        inner.outcomes.clear();
+        self.opened.fetch_add(1, Ordering::Relaxed);
+        warn!(
+            operation = operation,
+            open_for_ms = self.policy.open_for.as_millis() as u64,
+            "Database keeps failing, circuit breaker opened"
+        );
+    }
+}
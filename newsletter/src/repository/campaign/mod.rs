@@ -0,0 +1,80 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use crate::domain::campaign::audience::{AudienceEstimate, AudienceFilter};
+use crate::domain::campaign::complaint::Complaint;
+use crate::domain::campaign::delivery::{DeliveryCounts, DeliveryResult, Recipient};
+use crate::domain::campaign::engagement::{DomainStats, EngagementEvent, EngagementStats, LinkEngagement};
+use crate::domain::campaign::reengagement::SegmentMember;
+use crate::domain::campaign::{Campaign, NewCampaign};
+use crate::domain::pagination::{Page, PageRequest};
+
+pub mod postgres;
+
+/// Repository trait for campaign persistence
+#[async_trait]
+pub trait CampaignRepository: Send + Sync {
+    /// Persist a new draft campaign
+    async fn create(&self, campaign: &NewCampaign) -> Result<Campaign>;
+
+    /// Get a campaign by id
+    async fn get(&self, id: i64) -> Result<Option<Campaign>>;
+
+    /// Save the mutable fields of an existing campaign
+    async fn save(&self, campaign: &Campaign) -> Result<Campaign>;
+
+    /// Get a page of campaigns, newest first
+    async fn list(&self, page: PageRequest) -> Result<Page<Campaign>>;
+
+    /// Save a campaign that has started sending together with a pending
+    /// delivery for every active subscriber receiving its topic and carrying
+    /// its tag, held until the campaign's local send hours where it has them;
+    /// returns the number of recipients
+    async fn start_sending(&self, campaign: &Campaign) -> Result<i64>;
+
+    /// Take up to `limit` pending deliveries that are due, and deliveries whose sender has
+    /// held them longer than `lease`. Addresses that are no longer active are
+    /// marked skipped instead of being returned.
+    async fn claim_deliveries(&self, campaign_id: i64, limit: i64, lease: Duration) -> Result<Vec<Recipient>>;
+
+    /// Record the outcome of sending to claimed recipients
+    async fn record_deliveries(&self, campaign_id: i64, results: &[(String, DeliveryResult)]) -> Result<()>;
+
+    /// Count a campaign's deliveries per status
+    async fn delivery_counts(&self, campaign_id: i64) -> Result<DeliveryCounts>;
+
+    /// The earliest time a pending delivery of a campaign may be claimed,
+    /// which is in the past when one is due already; `None` when none is
+    /// pending
+    async fn next_delivery_at(&self, campaign_id: i64) -> Result<Option<DateTime<Utc>>>;
+
+    /// Store an open or click by a recipient
+    async fn record_engagement(&self, event: &EngagementEvent) -> Result<()>;
+
+    /// Count a campaign's opens and clicks against its sent deliveries
+    async fn engagement_stats(&self, campaign_id: i64) -> Result<EngagementStats>;
+
+    /// Store a spam complaint about a campaign; returns false when the
+    /// recipient already complained about it or the campaign is gone
+    async fn record_complaint(&self, complaint: &Complaint) -> Result<bool>;
+
+    /// Count clicks per link of a campaign, most clicked first
+    async fn link_engagement(&self, campaign_id: i64) -> Result<Vec<LinkEngagement>>;
+
+    /// Get a page of subscriber, bounce and open counts per email domain,
+    /// most subscribers first; the cursor is the number of domains skipped
+    async fn domain_stats(&self, page: PageRequest) -> Result<Page<DomainStats>>;
+
+    /// Count the active subscribers matching `filter` and take the first
+    /// `sample` of their addresses, in one query
+    async fn estimate_audience(&self, filter: &AudienceFilter, sample: i64) -> Result<AudienceEstimate>;
+
+    /// Addresses active since before `since` that were sent a campaign
+    /// after it but have not opened or clicked anything since, leaving out
+    /// those tagged `segment`
+    async fn find_unengaged(&self, since: DateTime<Utc>, segment: &str) -> Result<Vec<String>>;
+
+    /// Every subscriber tagged `segment`, with whether they are still active
+    /// and engaged since being tagged
+    async fn segment_members(&self, segment: &str) -> Result<Vec<SegmentMember>>;
+}
@@ -0,0 +1,1044 @@
+use crate::domain::campaign::audience::{AudienceEstimate, AudienceFilter};
+use crate::domain::campaign::complaint::Complaint;
+use crate::domain::campaign::delivery::{DeliveryCounts, DeliveryResult, DeliveryStatus, Recipient};
+use crate::domain::campaign::engagement::{DomainStats, EngagementEvent, EngagementKind, EngagementStats, LinkEngagement};
+use crate::domain::campaign::reengagement::SegmentMember;
+use crate::domain::campaign::{Campaign, CampaignError, CampaignStatus, LocalSendHours, NewCampaign, SendWindow};
+use crate::domain::pagination::{Page, PageRequest};
+use crate::domain::timezone;
+use crate::infrastructure::db::db_schema::{
+    campaign_complaints, campaign_deliveries, campaigns, engagement_events, newsletters, subscriber_tags, subscriber_topics, topics,
+};
+use crate::infrastructure::db::{tenant_connection, PgPool};
+use crate::repository::campaign::CampaignRepository;
+use crate::repository::newsletter::postgres::attributes_from_json;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use diesel::dsl::{count, count_star, exists, not, sql};
+use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Bool, Nullable, Text, Timestamptz};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use serde_json::Value;
+use tracing::{error, info, instrument};
+
+#[derive(QueryableByName)]
+struct DomainStatsRow {
+    #[diesel(sql_type = Text)]
+    domain: String,
+    #[diesel(sql_type = BigInt)]
+    subscribers: i64,
+    #[diesel(sql_type = BigInt)]
+    sent: i64,
+    #[diesel(sql_type = BigInt)]
+    bounced: i64,
+    #[diesel(sql_type = BigInt)]
+    unique_opens: i64,
+}
+
+impl From<DomainStatsRow> for DomainStats {
+    fn from(row: DomainStatsRow) -> Self {
+        DomainStats {
+            domain: row.domain,
+            subscribers: row.subscribers,
+            sent: row.sent,
+            bounced: row.bounced,
+            unique_opens: row.unique_opens,
+        }
+    }
+}
+
+/// Per-domain counts over subscriptions, deliveries and opens. Row-level
+/// security keeps each CTE to the caller's tenant.
+const DOMAIN_STATS_QUERY: &str = "
+    WITH subscribers AS (
+        SELECT lower(split_part(email, '@', 2)) AS domain, count(*) FILTER (WHERE active) AS subscribers
+        FROM newsletters
+        GROUP BY 1
+    ), deliveries AS (
+        SELECT lower(split_part(email, '@', 2)) AS domain,
+               count(*) FILTER (WHERE status = 'sent') AS sent,
+               count(*) FILTER (WHERE status = 'failed') AS bounced
+        FROM campaign_deliveries
+        GROUP BY 1
+    ), opens AS (
+        SELECT lower(split_part(email, '@', 2)) AS domain, count(DISTINCT (campaign_id, email)) AS unique_opens
+        FROM engagement_events
+        WHERE kind = 'open'
+        GROUP BY 1
+    )
+    SELECT domain,
+           coalesce(subscribers, 0) AS subscribers,
+           coalesce(sent, 0) AS sent,
+           coalesce(bounced, 0) AS bounced,
+           coalesce(unique_opens, 0) AS unique_opens
+    FROM subscribers
+    FULL JOIN deliveries USING (domain)
+    FULL JOIN opens USING (domain)
+    ORDER BY subscribers DESC, domain
+    LIMIT $1 OFFSET $2";
+
+/// A complaint about campaign `$1`, kept once per recipient and skipped
+/// when the campaign was deleted since it was sent
+const RECORD_COMPLAINT_QUERY: &str = "
+    INSERT INTO campaign_complaints (campaign_id, email, feedback_type, user_agent)
+    SELECT id, $2, $3, $4 FROM campaigns WHERE id = $1
+    ON CONFLICT (campaign_id, email) DO NOTHING";
+
+#[derive(QueryableByName)]
+struct AudienceRow {
+    #[diesel(sql_type = BigInt)]
+    recipients: i64,
+    #[diesel(sql_type = Array<Text>)]
+    sample: Vec<String>,
+}
+
+/// Active subscribers carrying every tag in `$1`, receiving every topic in
+/// `$2` (chosen, or by the topic's default when they never chose), engaged
+/// since `$3` and at domain `$4`, where set; counted, with the first `$5`
+/// addresses. A topic nobody defined is received by no one.
+const ESTIMATE_AUDIENCE_QUERY: &str = "
+    WITH audience AS (
+        SELECT n.email
+        FROM newsletters n
+        WHERE n.active
+          AND NOT EXISTS (
+              SELECT 1 FROM unnest($1::text[]) AS wanted(tag)
+              WHERE NOT EXISTS (
+                  SELECT 1 FROM subscriber_tags t
+                  WHERE t.email = n.email AND t.tag = wanted.tag
+              )
+          )
+          AND NOT EXISTS (
+              SELECT 1 FROM unnest($2::text[]) AS wanted(topic)
+              LEFT JOIN subscriber_topics s ON s.email = n.email AND s.topic = wanted.topic
+              LEFT JOIN topics tp ON tp.key = wanted.topic
+              WHERE NOT coalesce(s.subscribed, tp.default_subscribed AND NOT tp.requires_confirmation, false)
+          )
+          AND ($3::timestamptz IS NULL OR EXISTS (
+              SELECT 1 FROM engagement_events e
+              WHERE e.email = n.email AND e.created_at >= $3
+          ))
+          AND ($4::text IS NULL OR lower(split_part(n.email, '@', 2)) = $4)
+    )
+    SELECT (SELECT count(*) FROM audience) AS recipients,
+           ARRAY(SELECT email FROM audience ORDER BY email LIMIT $5) AS sample";
+
+#[derive(QueryableByName)]
+struct EmailRow {
+    #[diesel(sql_type = Text)]
+    email: String,
+}
+
+/// Subscribers mailed since `$1` who have stayed silent since, skipping
+/// those who only became active after it and members of the segment `$2`
+const UNENGAGED_QUERY: &str = "
+    SELECT n.email
+    FROM newsletters n
+    WHERE n.status = 'active'
+      AND n.status_changed_at <= $1
+      AND EXISTS (
+          SELECT 1 FROM campaign_deliveries d
+          WHERE d.email = n.email AND d.status = 'sent' AND d.sent_at >= $1
+      )
+      AND NOT EXISTS (
+          SELECT 1 FROM engagement_events e
+          WHERE e.email = n.email AND e.created_at >= $1
+      )
+      AND NOT EXISTS (
+          SELECT 1 FROM subscriber_tags t
+          WHERE t.email = n.email AND t.tag = $2
+      )
+    ORDER BY n.email";
+
+#[derive(QueryableByName)]
+struct SegmentMemberRow {
+    #[diesel(sql_type = Text)]
+    email: String,
+    #[diesel(sql_type = Timestamptz)]
+    joined_at: DateTime<Utc>,
+    #[diesel(sql_type = Bool)]
+    active: bool,
+    #[diesel(sql_type = Bool)]
+    engaged: bool,
+}
+
+impl From<SegmentMemberRow> for SegmentMember {
+    fn from(row: SegmentMemberRow) -> Self {
+        SegmentMember {
+            email: row.email,
+            joined_at: row.joined_at,
+            active: row.active,
+            engaged: row.engaged,
+        }
+    }
+}
+
+const SEGMENT_MEMBERS_QUERY: &str = "
+    SELECT t.email,
+           t.created_at AS joined_at,
+           n.status = 'active' AS active,
+           EXISTS (
+               SELECT 1 FROM engagement_events e
+               WHERE e.email = t.email AND e.created_at >= t.created_at
+           ) AS engaged
+    FROM subscriber_tags t
+    JOIN newsletters n ON n.email = t.email
+    WHERE t.tag = $1
+    ORDER BY t.email";
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = campaigns)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct CampaignRow {
+    pub id: i64,
+    pub name: String,
+    pub subject: String,
+    pub template_id: i64,
+    pub status: String,
+    pub send_window_start: Option<DateTime<Utc>>,
+    pub send_window_end: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub topic: Option<String>,
+    pub variables: Value,
+    pub tag: Option<String>,
+    pub version: i64,
+    pub local_send_start_hour: Option<i32>,
+    pub local_send_end_hour: Option<i32>,
+}
+
+impl TryFrom<CampaignRow> for Campaign {
+    type Error = anyhow::Error;
+
+    fn try_from(row: CampaignRow) -> Result<Self> {
+        let status = CampaignStatus::parse(&row.status)
+            .ok_or_else(|| anyhow::anyhow!("unknown campaign status in database: {}", row.status))?;
+        let send_window = match (row.send_window_start, row.send_window_end) {
+            (Some(start), Some(end)) => Some(SendWindow { start, end }),
+            _ => None,
+        };
+        let local_send_hours = match (row.local_send_start_hour, row.local_send_end_hour) {
+            (Some(start), Some(end)) => Some(LocalSendHours {
+                start: u32::try_from(start)?,
+                end: u32::try_from(end)?,
+            }),
+            _ => None,
+        };
+
+        Ok(Campaign {
+            id: row.id,
+            name: row.name,
+            subject: row.subject,
+            template_id: row.template_id,
+            status,
+            send_window,
+            topic: row.topic,
+            tag: row.tag,
+            variables: row.variables,
+            local_send_hours,
+            version: row.version,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = campaigns)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewCampaignRow<'a> {
+    pub name: &'a str,
+    pub subject: &'a str,
+    pub template_id: i64,
+    pub topic: Option<&'a str>,
+    pub tag: Option<&'a str>,
+    pub variables: &'a Value,
+    pub local_send_start_hour: Option<i32>,
+    pub local_send_end_hour: Option<i32>,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = campaigns)]
+#[diesel(treat_none_as_null = true)]
+struct CampaignChangeset<'a> {
+    pub name: &'a str,
+    pub subject: &'a str,
+    pub template_id: i64,
+    pub status: &'a str,
+    pub send_window_start: Option<DateTime<Utc>>,
+    pub send_window_end: Option<DateTime<Utc>>,
+    pub local_send_start_hour: Option<i32>,
+    pub local_send_end_hour: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'a> CampaignChangeset<'a> {
+    fn of(campaign: &'a Campaign) -> Self {
+        Self {
+            name: &campaign.name,
+            subject: &campaign.subject,
+            template_id: campaign.template_id,
+            status: campaign.status.as_str(),
+            send_window_start: campaign.send_window.map(|w| w.start),
+            send_window_end: campaign.send_window.map(|w| w.end),
+            local_send_start_hour: campaign.local_send_hours.map(|h| h.start as i32),
+            local_send_end_hour: campaign.local_send_hours.map(|h| h.end as i32),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = engagement_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewEngagementRow<'a> {
+    pub campaign_id: i64,
+    pub email: &'a str,
+    pub kind: &'a str,
+    pub url: Option<&'a str>,
+}
+
+/// Why a save of `campaign` matched no row: another save bumped the version
+async fn version_mismatch(conn: &mut AsyncPgConnection, campaign: &Campaign) -> anyhow::Error {
+    match campaigns::table
+        .find(campaign.id)
+        .select(campaigns::version)
+        .first::<i64>(conn)
+        .await
+    {
+        Ok(actual) => CampaignError::VersionMismatch {
+            expected: campaign.version,
+            actual,
+        }
+        .into(),
+        Err(e) => e.into(),
+    }
+}
+
+/// Hold the deliveries of a campaign until `hours` open in each recipient's
+/// timezone. Recipients sharing a zone share the time, so it takes one update
+/// per zone; those without one, or inside their hours, are left due at once.
+async fn hold_for_local_hours(
+    conn: &mut AsyncPgConnection,
+    campaign_id: i64,
+    hours: LocalSendHours,
+    now: DateTime<Utc>,
+) -> QueryResult<()> {
+    let recipients = campaign_deliveries::table
+        .filter(campaign_deliveries::campaign_id.eq(campaign_id))
+        .select(campaign_deliveries::email);
+    let zones: Vec<String> = newsletters::table
+        .filter(newsletters::email.eq_any(recipients))
+        .filter(newsletters::timezone.is_not_null())
+        .select(newsletters::timezone.assume_not_null())
+        .distinct()
+        .load(conn)
+        .await?;
+
+    for zone in zones {
+        let Some(not_before) = timezone::parse(&zone).and_then(|tz| hours.delay_until(now, tz)) else {
+            continue;
+        };
+        let in_zone = newsletters::table
+            .filter(newsletters::timezone.eq(&zone))
+            .select(newsletters::email);
+        diesel::update(
+            campaign_deliveries::table
+                .filter(campaign_deliveries::campaign_id.eq(campaign_id))
+                .filter(campaign_deliveries::email.eq_any(in_zone)),
+        )
+        .set(campaign_deliveries::not_before.eq(not_before))
+        .execute(conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// PostgreSQL implementation of the CampaignRepository trait
+#[derive(Clone)]
+pub struct PostgresCampaignRepository {
+    pool: PgPool,
+}
+
+impl PostgresCampaignRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CampaignRepository for PostgresCampaignRepository {
+    #[instrument(skip(self, campaign), fields(name = %campaign.name))]
+    async fn create(&self, campaign: &NewCampaign) -> Result<Campaign> {
+        info!(entity = "campaign_table", crud_operation = "CREATE", "Starting database create operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "campaign_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::insert_into(campaigns::table)
+            .values(&NewCampaignRow {
+                name: &campaign.name,
+                subject: &campaign.subject,
+                template_id: campaign.template_id,
+                topic: campaign.topic.as_deref(),
+                tag: campaign.tag.as_deref(),
+                variables: &campaign.variables,
+                local_send_start_hour: campaign.local_send_hours.map(|h| h.start as i32),
+                local_send_end_hour: campaign.local_send_hours.map(|h| h.end as i32),
+            })
+            .returning(CampaignRow::as_returning())
+            .get_result(&mut conn)
+            .await
+        {
+            Ok(row) => {
+                info!(entity = "campaign_table", crud_operation = "CREATE", id = row.id, "Successfully created campaign");
+                row.try_into()
+            }
+            Err(e) => {
+                error!(entity = "campaign_table", crud_operation = "CREATE", error = %e, "Failed to create campaign");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn get(&self, id: i64) -> Result<Option<Campaign>> {
+        info!(entity = "campaign_table", crud_operation = "READ", id = id, "Starting database get operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "campaign_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match campaigns::table
+            .find(id)
+            .select(CampaignRow::as_select())
+            .first(&mut conn)
+            .await
+            .optional()
+        {
+            Ok(row) => {
+                info!(entity = "campaign_table", crud_operation = "READ", id = id, found = row.is_some(), "Successfully retrieved campaign");
+                row.map(Campaign::try_from).transpose()
+            }
+            Err(e) => {
+                error!(entity = "campaign_table", crud_operation = "READ", id = id, error = %e, "Failed to retrieve campaign");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, campaign), fields(id = campaign.id, status = %campaign.status))]
+    async fn save(&self, campaign: &Campaign) -> Result<Campaign> {
+        info!(entity = "campaign_table", crud_operation = "UPDATE", id = campaign.id, "Starting database save operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "campaign_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::update(campaigns::table.find(campaign.id).filter(campaigns::version.eq(campaign.version)))
+            .set((&CampaignChangeset::of(campaign), campaigns::version.eq(campaigns::version + 1)))
+            .returning(CampaignRow::as_returning())
+            .get_result(&mut conn)
+            .await
+            .optional()
+        {
+            Ok(Some(row)) => {
+                info!(entity = "campaign_table", crud_operation = "UPDATE", id = campaign.id, "Successfully saved campaign");
+                row.try_into()
+            }
+            Ok(None) => {
+                let e = version_mismatch(&mut conn, campaign).await;
+                error!(entity = "campaign_table", crud_operation = "UPDATE", id = campaign.id, error = %e, "Campaign changed since it was read");
+                Err(e)
+            }
+            Err(e) => {
+                error!(entity = "campaign_table", crud_operation = "UPDATE", id = campaign.id, error = %e, "Failed to save campaign");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(limit = page.limit, after = ?page.after))]
+    async fn list(&self, page: PageRequest) -> Result<Page<Campaign>> {
+        info!(entity = "campaign_table", crud_operation = "READ", limit = page.limit, "Starting database list operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "campaign_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let mut query = campaigns::table
+            .select(CampaignRow::as_select())
+            .order(campaigns::id.desc())
+            .limit(page.limit + 1)
+            .into_boxed();
+
+        if let Some(after) = page.after {
+            query = query.filter(campaigns::id.lt(after));
+        }
+
+        let mut rows: Vec<CampaignRow> = match query.load(&mut conn).await {
+            Ok(rows) => {
+                info!(entity = "campaign_table", crud_operation = "READ", rows_count = rows.len(), "Successfully retrieved campaigns");
+                rows
+            }
+            Err(e) => {
+                error!(entity = "campaign_table", crud_operation = "READ", error = %e, "Failed to retrieve campaigns");
+                return Err(e.into());
+            }
+        };
+
+        let has_more = rows.len() as i64 > page.limit;
+        rows.truncate(page.limit as usize);
+        let next_cursor = if has_more { rows.last().map(|r| r.id) } else { None };
+
+        Ok(Page {
+            items: rows
+                .into_iter()
+                .map(Campaign::try_from)
+                .collect::<Result<Vec<_>>>()?,
+            next_cursor,
+        })
+    }
+
+    #[instrument(skip(self, campaign), fields(id = campaign.id))]
+    async fn start_sending(&self, campaign: &Campaign) -> Result<i64> {
+        info!(entity = "campaign_deliveries_table", crud_operation = "CREATE", id = campaign.id, "Starting database start_sending operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "campaign_deliveries_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let result = conn
+            .transaction::<_, anyhow::Error, _>(|conn| {
+                async move {
+                    let saved = diesel::update(campaigns::table.find(campaign.id).filter(campaigns::version.eq(campaign.version)))
+                        .set((&CampaignChangeset::of(campaign), campaigns::version.eq(campaigns::version + 1)))
+                        .execute(conn)
+                        .await?;
+                    if saved == 0 {
+                        return Err(version_mismatch(conn, campaign).await);
+                    }
+
+                    let mut audience = newsletters::table.filter(newsletters::active.eq(true)).into_boxed();
+                    if let Some(topic) = &campaign.topic {
+                        let chosen = subscriber_topics::table.filter(subscriber_topics::topic.eq(topic));
+                        let opted_in = chosen.filter(subscriber_topics::subscribed.eq(true)).select(subscriber_topics::email);
+                        let by_default = topics::table
+                            .filter(topics::key.eq(topic))
+                            .filter(topics::default_subscribed.eq(true))
+                            .filter(topics::requires_confirmation.eq(false));
+                        audience = audience.filter(
+                            newsletters::email
+                                .eq_any(opted_in)
+                                .or(not(newsletters::email.eq_any(chosen.select(subscriber_topics::email)))
+                                    .and(exists(by_default))),
+                        );
+                    }
+                    if let Some(tag) = &campaign.tag {
+                        let tagged = subscriber_tags::table.filter(subscriber_tags::tag.eq(tag)).select(subscriber_tags::email);
+                        audience = audience.filter(newsletters::email.eq_any(tagged));
+                    }
+
+                    // Addresses already there come from an earlier, interrupted start
+                    diesel::insert_into(campaign_deliveries::table)
+                        .values(audience.select((campaign.id.into_sql::<BigInt>(), newsletters::email)))
+                        .into_columns((campaign_deliveries::campaign_id, campaign_deliveries::email))
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+                    if let Some(hours) = campaign.local_send_hours {
+                        hold_for_local_hours(conn, campaign.id, hours, Utc::now()).await?;
+                    }
+
+                    Ok(campaign_deliveries::table
+                        .filter(campaign_deliveries::campaign_id.eq(campaign.id))
+                        .select(count_star())
+                        .first::<i64>(conn)
+                        .await?)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(recipients) => {
+                info!(entity = "campaign_deliveries_table", crud_operation = "CREATE", id = campaign.id, recipients = recipients, "Successfully started sending campaign");
+                Ok(recipients)
+            }
+            Err(e) => {
+                error!(entity = "campaign_deliveries_table", crud_operation = "CREATE", id = campaign.id, error = %e, "Failed to start sending campaign");
+                Err(e)
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn claim_deliveries(&self, campaign_id: i64, limit: i64, lease: Duration) -> Result<Vec<Recipient>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "campaign_deliveries_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let now = Utc::now();
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let claimed: Vec<(String, i32)> = campaign_deliveries::table
+                        .filter(campaign_deliveries::campaign_id.eq(campaign_id))
+                        .filter(
+                            campaign_deliveries::status
+                                .eq(DeliveryStatus::Pending.as_str())
+                                .and(
+                                    campaign_deliveries::not_before
+                                        .is_null()
+                                        .or(campaign_deliveries::not_before.le(now)),
+                                )
+                                .or(
+                                campaign_deliveries::status
+                                    .eq(DeliveryStatus::Sending.as_str())
+                                    .and(campaign_deliveries::claimed_at.lt(now - lease)),
+                            ),
+                        )
+                        .order(campaign_deliveries::email.asc())
+                        .limit(limit)
+                        .select((campaign_deliveries::email, campaign_deliveries::attempts))
+                        .for_update()
+                        .skip_locked()
+                        .load(conn)
+                        .await?;
+
+                    if claimed.is_empty() {
+                        return Ok((Vec::new(), 0));
+                    }
+
+                    let emails: Vec<&str> = claimed.iter().map(|(email, _)| email.as_str()).collect();
+                    let active: Vec<(String, Value, Option<String>)> = newsletters::table
+                        .filter(newsletters::email.eq_any(&emails))
+                        .filter(newsletters::active.eq(true))
+                        .select((newsletters::email, newsletters::attributes, newsletters::locale))
+                        .load(conn)
+                        .await?;
+
+                    let mut recipients = Vec::with_capacity(claimed.len());
+                    let mut gone = Vec::new();
+                    for (email, attempts) in claimed {
+                        match active.iter().find(|(e, _, _)| *e == email) {
+                            Some((_, attributes, locale)) => {
+                                recipients.push((email, attempts, attributes.clone(), locale.clone()))
+                            }
+                            None => gone.push(email),
+                        }
+                    }
+
+                    let skipped = diesel::update(
+                        campaign_deliveries::table
+                            .filter(campaign_deliveries::campaign_id.eq(campaign_id))
+                            .filter(campaign_deliveries::email.eq_any(&gone)),
+                    )
+                    .set((
+                        campaign_deliveries::status.eq(DeliveryStatus::Skipped.as_str()),
+                        campaign_deliveries::claimed_at.eq(None::<DateTime<Utc>>),
+                    ))
+                    .execute(conn)
+                    .await?;
+
+                    let emails: Vec<&str> = recipients.iter().map(|(email, _, _, _)| email.as_str()).collect();
+                    diesel::update(
+                        campaign_deliveries::table
+                            .filter(campaign_deliveries::campaign_id.eq(campaign_id))
+                            .filter(campaign_deliveries::email.eq_any(&emails)),
+                    )
+                    .set((
+                        campaign_deliveries::status.eq(DeliveryStatus::Sending.as_str()),
+                        campaign_deliveries::claimed_at.eq(now),
+                    ))
+                    .execute(conn)
+                    .await?;
+
+                    Ok((recipients, skipped))
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        let (rows, skipped) = match result {
+            Ok(claimed) => claimed,
+            Err(e) => {
+                error!(entity = "campaign_deliveries_table", crud_operation = "UPDATE", campaign_id = campaign_id, error = %e, "Failed to claim campaign deliveries");
+                return Err(e.into());
+            }
+        };
+
+        info!(entity = "campaign_deliveries_table", crud_operation = "UPDATE", campaign_id = campaign_id, claimed = rows.len(), skipped = skipped, "Claimed campaign deliveries");
+
+        rows.into_iter()
+            .map(|(email, attempts, attributes, locale)| {
+                Ok(Recipient {
+                    email,
+                    attributes: attributes_from_json(attributes)?,
+                    locale,
+                    attempts,
+                })
+            })
+            .collect()
+    }
+
+    #[instrument(skip(self, results), fields(count = results.len()))]
+    async fn record_deliveries(&self, campaign_id: i64, results: &[(String, DeliveryResult)]) -> Result<()> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "campaign_deliveries_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let now = Utc::now();
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    for (email, outcome) in results {
+                        let target = campaign_deliveries::table
+                            .filter(campaign_deliveries::campaign_id.eq(campaign_id))
+                            .filter(campaign_deliveries::email.eq(email));
+
+                        let (status, error) = match outcome {
+                            DeliveryResult::Sent => {
+                                diesel::update(target)
+                                    .set((
+                                        campaign_deliveries::status.eq(DeliveryStatus::Sent.as_str()),
+                                        campaign_deliveries::claimed_at.eq(None::<DateTime<Utc>>),
+                                        campaign_deliveries::sent_at.eq(now),
+                                    ))
+                                    .execute(conn)
+                                    .await?;
+                                continue;
+                            }
+                            DeliveryResult::Retry(error) => (DeliveryStatus::Pending, error),
+                            DeliveryResult::Failed(error) => (DeliveryStatus::Failed, error),
+                        };
+
+                        diesel::update(target)
+                            .set((
+                                campaign_deliveries::status.eq(status.as_str()),
+                                campaign_deliveries::attempts.eq(campaign_deliveries::attempts + 1),
+                                campaign_deliveries::last_error.eq(error),
+                                campaign_deliveries::claimed_at.eq(None::<DateTime<Utc>>),
+                            ))
+                            .execute(conn)
+                            .await?;
+                    }
+                    Ok(())
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                error!(entity = "campaign_deliveries_table", crud_operation = "UPDATE", campaign_id = campaign_id, error = %e, "Failed to record campaign deliveries");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn delivery_counts(&self, campaign_id: i64) -> Result<DeliveryCounts> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "campaign_deliveries_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let rows: Vec<(String, i64)> = match campaign_deliveries::table
+            .filter(campaign_deliveries::campaign_id.eq(campaign_id))
+            .group_by(campaign_deliveries::status)
+            .select((campaign_deliveries::status, count_star()))
+            .load(&mut conn)
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(entity = "campaign_deliveries_table", crud_operation = "READ", campaign_id = campaign_id, error = %e, "Failed to count campaign deliveries");
+                return Err(e.into());
+            }
+        };
+
+        let mut counts = DeliveryCounts::default();
+        for (status, count) in rows {
+            match DeliveryStatus::parse(&status) {
+                Some(DeliveryStatus::Pending) => counts.pending = count,
+                Some(DeliveryStatus::Sending) => counts.sending = count,
+                Some(DeliveryStatus::Sent) => counts.sent = count,
+                Some(DeliveryStatus::Failed) => counts.failed = count,
+                Some(DeliveryStatus::Skipped) => counts.skipped = count,
+                None => anyhow::bail!("unknown delivery status in database: {status}"),
+            }
+        }
+        Ok(counts)
+    }
+
+    #[instrument(skip(self))]
+    async fn next_delivery_at(&self, campaign_id: i64) -> Result<Option<DateTime<Utc>>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "campaign_deliveries_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        // Deliveries that were never held have been due since they were created
+        match campaign_deliveries::table
+            .filter(campaign_deliveries::campaign_id.eq(campaign_id))
+            .filter(campaign_deliveries::status.eq(DeliveryStatus::Pending.as_str()))
+            .select(sql::<Nullable<Timestamptz>>("min(coalesce(not_before, created_at))"))
+            .first::<Option<DateTime<Utc>>>(&mut conn)
+            .await
+        {
+            Ok(next) => Ok(next),
+            Err(e) => {
+                error!(entity = "campaign_deliveries_table", crud_operation = "READ", campaign_id = campaign_id, error = %e, "Failed to find the next campaign delivery");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, event), fields(campaign_id = event.campaign_id, kind = event.kind.as_str()))]
+    async fn record_engagement(&self, event: &EngagementEvent) -> Result<()> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "engagement_events_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::insert_into(engagement_events::table)
+            .values(&NewEngagementRow {
+                campaign_id: event.campaign_id,
+                email: &event.email,
+                kind: event.kind.as_str(),
+                url: event.url.as_deref(),
+            })
+            .execute(&mut conn)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!(entity = "engagement_events_table", crud_operation = "CREATE", error = %e, "Failed to record engagement event");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn engagement_stats(&self, campaign_id: i64) -> Result<EngagementStats> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "engagement_events_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let sent = campaign_deliveries::table
+            .filter(campaign_deliveries::campaign_id.eq(campaign_id))
+            .filter(campaign_deliveries::status.eq(DeliveryStatus::Sent.as_str()))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .await;
+        let rows = engagement_events::table
+            .filter(engagement_events::campaign_id.eq(campaign_id))
+            .group_by(engagement_events::kind)
+            .select((engagement_events::kind, count_star(), count(engagement_events::email).aggregate_distinct()))
+            .load::<(String, i64, i64)>(&mut conn)
+            .await;
+        let complaints = campaign_complaints::table
+            .filter(campaign_complaints::campaign_id.eq(campaign_id))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .await;
+
+        let (sent, rows, complaints) = match (sent, rows, complaints) {
+            (Ok(sent), Ok(rows), Ok(complaints)) => (sent, rows, complaints),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                error!(entity = "engagement_events_table", crud_operation = "READ", campaign_id = campaign_id, error = %e, "Failed to count campaign engagement");
+                return Err(e.into());
+            }
+        };
+
+        let mut stats = EngagementStats { sent, complaints, ..Default::default() };
+        for (kind, total, unique) in rows {
+            match EngagementKind::parse(&kind) {
+                Some(EngagementKind::Open) => (stats.opens, stats.unique_opens) = (total, unique),
+                Some(EngagementKind::Click) => (stats.clicks, stats.unique_clicks) = (total, unique),
+                None => anyhow::bail!("unknown engagement kind in database: {kind}"),
+            }
+        }
+        Ok(stats)
+    }
+
+    #[instrument(skip(self, complaint), fields(campaign_id = complaint.campaign_id))]
+    async fn record_complaint(&self, complaint: &Complaint) -> Result<bool> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "campaign_complaints_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::sql_query(RECORD_COMPLAINT_QUERY)
+            .bind::<BigInt, _>(complaint.campaign_id)
+            .bind::<Text, _>(&complaint.email)
+            .bind::<Text, _>(&complaint.feedback_type)
+            .bind::<Nullable<Text>, _>(complaint.user_agent.as_deref())
+            .execute(&mut conn)
+            .await
+        {
+            Ok(inserted) => {
+                info!(entity = "campaign_complaints_table", crud_operation = "CREATE", campaign_id = complaint.campaign_id, recorded = inserted > 0, "Successfully recorded complaint");
+                Ok(inserted > 0)
+            }
+            Err(e) => {
+                error!(entity = "campaign_complaints_table", crud_operation = "CREATE", campaign_id = complaint.campaign_id, error = %e, "Failed to record complaint");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn link_engagement(&self, campaign_id: i64) -> Result<Vec<LinkEngagement>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "engagement_events_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let rows: Vec<(Option<String>, i64, i64)> = match engagement_events::table
+            .filter(engagement_events::campaign_id.eq(campaign_id))
+            .filter(engagement_events::kind.eq(EngagementKind::Click.as_str()))
+            .filter(engagement_events::url.is_not_null())
+            .group_by(engagement_events::url)
+            .select((engagement_events::url, count_star(), count(engagement_events::email).aggregate_distinct()))
+            .order((count_star().desc(), engagement_events::url.asc()))
+            .load(&mut conn)
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(entity = "engagement_events_table", crud_operation = "READ", campaign_id = campaign_id, error = %e, "Failed to count link clicks");
+                return Err(e.into());
+            }
+        };
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(url, clicks, unique_clicks)| {
+                url.map(|url| LinkEngagement { url, clicks, unique_clicks })
+            })
+            .collect())
+    }
+
+    #[instrument(skip(self))]
+    async fn domain_stats(&self, page: PageRequest) -> Result<Page<DomainStats>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "campaign_deliveries_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let offset = page.after.unwrap_or(0).max(0);
+        let mut rows: Vec<DomainStatsRow> = match diesel::sql_query(DOMAIN_STATS_QUERY)
+            .bind::<BigInt, _>(page.limit + 1)
+            .bind::<BigInt, _>(offset)
+            .load(&mut conn)
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(entity = "campaign_deliveries_table", crud_operation = "READ", error = %e, "Failed to aggregate domain stats");
+                return Err(e.into());
+            }
+        };
+
+        let next_cursor = if rows.len() as i64 > page.limit {
+            rows.truncate(page.limit as usize);
+            Some(offset + page.limit)
+        } else {
+            None
+        };
+        Ok(Page {
+            items: rows.into_iter().map(DomainStats::from).collect(),
+            next_cursor,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn estimate_audience(&self, filter: &AudienceFilter, sample: i64) -> Result<AudienceEstimate> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let tags: Vec<&str> = filter.tags.iter().map(|tag| tag.as_str()).collect();
+        match diesel::sql_query(ESTIMATE_AUDIENCE_QUERY)
+            .bind::<Array<Text>, _>(tags)
+            .bind::<Array<Text>, _>(&filter.topics)
+            .bind::<Nullable<Timestamptz>, _>(filter.engaged_since)
+            .bind::<Nullable<Text>, _>(filter.domain.as_deref())
+            .bind::<BigInt, _>(sample)
+            .get_result::<AudienceRow>(&mut conn)
+            .await
+        {
+            Ok(row) => {
+                info!(entity = "newsletter_table", crud_operation = "READ", recipients = row.recipients, "Successfully estimated audience");
+                Ok(AudienceEstimate { recipients: row.recipients, sample: row.sample })
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to estimate audience");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(segment = %segment))]
+    async fn find_unengaged(&self, since: DateTime<Utc>, segment: &str) -> Result<Vec<String>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::sql_query(UNENGAGED_QUERY)
+            .bind::<Timestamptz, _>(since)
+            .bind::<Text, _>(segment)
+            .load::<EmailRow>(&mut conn)
+            .await
+        {
+            Ok(rows) => {
+                info!(entity = "newsletter_table", crud_operation = "READ", rows_count = rows.len(), "Successfully found unengaged subscribers");
+                Ok(rows.into_iter().map(|row| row.email).collect())
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to find unengaged subscribers");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(segment = %segment))]
+    async fn segment_members(&self, segment: &str) -> Result<Vec<SegmentMember>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "subscriber_tags_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::sql_query(SEGMENT_MEMBERS_QUERY)
+            .bind::<Text, _>(segment)
+            .load::<SegmentMemberRow>(&mut conn)
+            .await
+        {
+            Ok(rows) => {
+                info!(entity = "subscriber_tags_table", crud_operation = "READ", rows_count = rows.len(), "Successfully retrieved segment members");
+                Ok(rows.into_iter().map(SegmentMember::from).collect())
+            }
+            Err(e) => {
+                error!(entity = "subscriber_tags_table", crud_operation = "READ", error = %e, "Failed to retrieve segment members");
+                Err(e.into())
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use crate::domain::idempotency::IdempotencyRecord;
+
+pub mod postgres;
+
+/// Outcome of claiming an idempotency key
+#[derive(Debug, Clone, PartialEq)]
+pub enum Claim {
+    /// The key was free and now belongs to the caller
+    Acquired,
+    /// The key is held by an earlier request
+    Existing(IdempotencyRecord),
+}
+
+/// Repository trait for processed idempotency keys
+#[async_trait]
+pub trait IdempotencyRepository: Send + Sync {
+    /// Reserve a key for an operation; keys created before `expired_before` are
+    /// treated as free and replaced
+    async fn claim(
+        &self,
+        key: &str,
+        operation: &str,
+        request_hash: &str,
+        expired_before: DateTime<Utc>,
+    ) -> Result<Claim>;
+
+    /// Store the result of the request that claimed the key
+    async fn complete(&self, key: &str, operation: &str, response: &Value) -> Result<()>;
+
+    /// Drop a claim whose request failed, so the client may retry with the same key
+    async fn release(&self, key: &str, operation: &str) -> Result<()>;
+
+    /// Remove keys created before `expired_before`
+    async fn purge_expired(&self, expired_before: DateTime<Utc>) -> Result<usize>;
+}
@@ -0,0 +1,176 @@
+use crate::domain::idempotency::IdempotencyRecord;
+use crate::infrastructure::db::db_schema::idempotency_keys;
+use crate::infrastructure::db::PgPool;
+use crate::repository::idempotency::{Claim, IdempotencyRepository};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use serde_json::Value;
+use tracing::{error, info, instrument};
+
+#[derive(Insertable)]
+#[diesel(table_name = idempotency_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewIdempotencyKey<'a> {
+    pub key: &'a str,
+    pub operation: &'a str,
+    pub request_hash: &'a str,
+}
+
+/// PostgreSQL implementation of the IdempotencyRepository trait
+#[derive(Clone)]
+pub struct PostgresIdempotencyRepository {
+    pool: PgPool,
+}
+
+impl PostgresIdempotencyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for PostgresIdempotencyRepository {
+    #[instrument(skip(self, key, request_hash), fields(operation = %operation))]
+    async fn claim(
+        &self,
+        key: &str,
+        operation: &str,
+        request_hash: &str,
+        expired_before: DateTime<Utc>,
+    ) -> Result<Claim> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "idempotency_keys_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let entry = idempotency_keys::table
+                        .filter(idempotency_keys::key.eq(key))
+                        .filter(idempotency_keys::operation.eq(operation));
+
+                    diesel::delete(entry.filter(idempotency_keys::created_at.lt(expired_before)))
+                        .execute(conn)
+                        .await?;
+
+                    let inserted = diesel::insert_into(idempotency_keys::table)
+                        .values(&NewIdempotencyKey { key, operation, request_hash })
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+
+                    if inserted == 1 {
+                        return Ok(Claim::Acquired);
+                    }
+
+                    let (request_hash, response) = entry
+                        .select((idempotency_keys::request_hash, idempotency_keys::response))
+                        .first::<(String, Option<Value>)>(conn)
+                        .await?;
+
+                    Ok(Claim::Existing(IdempotencyRecord { request_hash, response }))
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(claim) => {
+                info!(entity = "idempotency_keys_table", crud_operation = "CREATE", acquired = matches!(claim, Claim::Acquired), "Claimed idempotency key");
+                Ok(claim)
+            }
+            Err(e) => {
+                error!(entity = "idempotency_keys_table", crud_operation = "CREATE", error = %e, "Failed to claim idempotency key");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, key, response), fields(operation = %operation))]
+    async fn complete(&self, key: &str, operation: &str, response: &Value) -> Result<()> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "idempotency_keys_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::update(
+            idempotency_keys::table
+                .filter(idempotency_keys::key.eq(key))
+                .filter(idempotency_keys::operation.eq(operation)),
+        )
+        .set(idempotency_keys::response.eq(response))
+        .execute(&mut conn)
+        .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!(entity = "idempotency_keys_table", crud_operation = "UPDATE", error = %e, "Failed to store idempotent response");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, key), fields(operation = %operation))]
+    async fn release(&self, key: &str, operation: &str) -> Result<()> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "idempotency_keys_table", crud_operation = "DELETE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::delete(
+            idempotency_keys::table
+                .filter(idempotency_keys::key.eq(key))
+                .filter(idempotency_keys::operation.eq(operation))
+                .filter(idempotency_keys::response.is_null()),
+        )
+        .execute(&mut conn)
+        .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!(entity = "idempotency_keys_table", crud_operation = "DELETE", error = %e, "Failed to release idempotency key");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn purge_expired(&self, expired_before: DateTime<Utc>) -> Result<usize> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "idempotency_keys_table", crud_operation = "DELETE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::delete(idempotency_keys::table.filter(idempotency_keys::created_at.lt(expired_before)))
+            .execute(&mut conn)
+            .await
+        {
+            Ok(purged) => {
+                info!(entity = "idempotency_keys_table", crud_operation = "DELETE", purged = purged, "Purged expired idempotency keys");
+                Ok(purged)
+            }
+            Err(e) => {
+                error!(entity = "idempotency_keys_table", crud_operation = "DELETE", error = %e, "Failed to purge expired idempotency keys");
+                Err(e.into())
+            }
+        }
+    }
+}
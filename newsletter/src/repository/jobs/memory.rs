@@ -0,0 +1,153 @@
+use std::sync::Mutex;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::domain::jobs::{Job, NewJob};
+use crate::infrastructure::tenant;
+use crate::repository::jobs::JobRepository;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pending,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone)]
+struct Row {
+    job: Job,
+    unique_key: Option<String>,
+    status: Status,
+    run_at: DateTime<Utc>,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_id: i64,
+    /// Kept in insertion order, so ids ascend
+    rows: Vec<Row>,
+}
+
+/// JobRepository kept in process memory, for tests that run without Postgres
+#[derive(Debug, Default)]
+pub struct InMemoryJobRepository {
+    state: Mutex<State>,
+}
+
+impl InMemoryJobRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("in-memory repository lock poisoned")
+    }
+
+    fn update(&self, id: i64, change: impl FnOnce(&mut Row)) {
+        if let Some(row) = self.state().rows.iter_mut().find(|r| r.job.id == id) {
+            change(row);
+        }
+    }
+
+    /// Jobs not yet done or failed
+    pub fn pending(&self) -> Vec<Job> {
+        self.state()
+            .rows
+            .iter()
+            .filter(|r| r.status == Status::Pending)
+            .map(|r| r.job.clone())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl JobRepository for InMemoryJobRepository {
+    async fn enqueue(&self, job: &NewJob) -> Result<Option<i64>> {
+        let mut state = self.state();
+        if let Some(key) = &job.unique_key {
+            if state
+                .rows
+                .iter()
+                .any(|r| r.status == Status::Pending && r.unique_key.as_ref() == Some(key))
+            {
+                return Ok(None);
+            }
+        }
+
+        state.next_id += 1;
+        let id = state.next_id;
+        state.rows.push(Row {
+            job: Job {
+                id,
+                kind: job.kind,
+                payload: job.payload.clone(),
+                attempts: 0,
+                max_attempts: job.max_attempts,
+                tenant: tenant::current(),
+            },
+            unique_key: job.unique_key.clone(),
+            status: Status::Pending,
+            run_at: job.run_at,
+            last_error: None,
+        });
+        Ok(Some(id))
+    }
+
+    async fn claim(&self, limit: i64, lease: Duration) -> Result<Vec<Job>> {
+        let mut state = self.state();
+        let now = Utc::now();
+
+        let mut due: Vec<&mut Row> = state
+            .rows
+            .iter_mut()
+            .filter(|r| r.status == Status::Pending && r.run_at <= now)
+            .collect();
+        due.sort_by_key(|r| (r.run_at, r.job.id));
+        due.truncate(limit.max(0) as usize);
+
+        let mut claimed: Vec<Job> = due
+            .into_iter()
+            .map(|r| {
+                r.run_at = now + lease;
+                r.job.clone()
+            })
+            .collect();
+        claimed.sort_by_key(|j| j.id);
+        Ok(claimed)
+    }
+
+    async fn complete(&self, id: i64) -> Result<()> {
+        self.update(id, |r| r.status = Status::Done);
+        Ok(())
+    }
+
+    async fn retry(&self, id: i64, error: &str, run_at: DateTime<Utc>) -> Result<()> {
+        self.update(id, |r| {
+            r.job.attempts += 1;
+            r.last_error = Some(error.to_string());
+            r.run_at = run_at;
+        });
+        Ok(())
+    }
+
+    async fn fail(&self, id: i64, error: &str) -> Result<()> {
+        self.update(id, |r| {
+            r.job.attempts += 1;
+            r.last_error = Some(error.to_string());
+            r.status = Status::Failed;
+        });
+        Ok(())
+    }
+
+    async fn reschedule(&self, id: i64, run_at: DateTime<Utc>) -> Result<()> {
+        self.update(id, |r| {
+            r.job.attempts = 0;
+            r.last_error = None;
+            r.run_at = run_at;
+        });
+        Ok(())
+    }
+}
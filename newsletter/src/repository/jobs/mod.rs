@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::domain::jobs::{Job, NewJob};
+
+#[cfg(any(test, feature = "testing", feature = "sqlite"))]
+pub mod memory;
+pub mod postgres;
+
+/// Repository trait for the background job queue
+#[async_trait]
+pub trait JobRepository: Send + Sync {
+    /// Queue a job; returns its id, or `None` if a pending job already holds its unique key
+    async fn enqueue(&self, job: &NewJob) -> Result<Option<i64>>;
+
+    /// Take up to `limit` due jobs, oldest first, hiding them from other
+    /// workers for `lease`
+    async fn claim(&self, limit: i64, lease: Duration) -> Result<Vec<Job>>;
+
+    /// Record that a job finished
+    async fn complete(&self, id: i64) -> Result<()>;
+
+    /// Record a failed attempt and when to try again
+    async fn retry(&self, id: i64, error: &str, run_at: DateTime<Utc>) -> Result<()>;
+
+    /// Record a failure that is not retried
+    async fn fail(&self, id: i64, error: &str) -> Result<()>;
+
+    /// Run a recurring job again at `run_at`, resetting its attempts
+    async fn reschedule(&self, id: i64, run_at: DateTime<Utc>) -> Result<()>;
+}
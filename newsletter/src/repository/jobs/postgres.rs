@@ -0,0 +1,235 @@
+use crate::domain::jobs::{Job, JobKind, NewJob};
+use crate::domain::tenant::{TenantId, TenantScope};
+use crate::infrastructure::db::db_schema::jobs;
+use crate::infrastructure::db::{tenant_connection, PgPool};
+use crate::infrastructure::tenant;
+use crate::repository::jobs::JobRepository;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use serde_json::Value;
+use tracing::{error, info, instrument};
+
+const PENDING: &str = "pending";
+const DONE: &str = "done";
+const FAILED: &str = "failed";
+
+#[derive(Insertable)]
+#[diesel(table_name = jobs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewJobRow<'a> {
+    pub kind: &'a str,
+    pub payload: &'a Value,
+    pub unique_key: Option<&'a str>,
+    pub max_attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub tenant_id: Option<&'a str>,
+}
+
+type ClaimedRow = (i64, String, Value, i32, i32, Option<String>);
+
+/// PostgreSQL implementation of the JobRepository trait
+#[derive(Clone)]
+pub struct PostgresJobRepository {
+    pool: PgPool,
+}
+
+impl PostgresJobRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Apply a change to one job, logging failures under `action`
+    async fn finish<C>(&self, id: i64, action: &'static str, changes: C) -> Result<()>
+    where
+        C: diesel::query_builder::AsChangeset<Target = jobs::table> + Send,
+        C::Changeset: diesel::query_builder::QueryFragment<diesel::pg::Pg> + Send,
+    {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "jobs_table", crud_operation = "UPDATE", id = id, error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::update(jobs::table.find(id))
+            .set(changes)
+            .execute(&mut conn)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!(entity = "jobs_table", crud_operation = "UPDATE", id = id, action = action, error = %e, "Failed to update job");
+                Err(e.into())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl JobRepository for PostgresJobRepository {
+    /// Inside a unit of work the job is queued on the unit's connection, so
+    /// it only runs if the unit commits
+    #[instrument(skip(self, job), fields(kind = %job.kind, run_at = %job.run_at))]
+    async fn enqueue(&self, job: &NewJob) -> Result<Option<i64>> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "jobs_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let scope = tenant::current();
+        match diesel::insert_into(jobs::table)
+            .values(&NewJobRow {
+                kind: job.kind.as_str(),
+                payload: &job.payload,
+                unique_key: job.unique_key.as_deref(),
+                max_attempts: job.max_attempts,
+                run_at: job.run_at,
+                tenant_id: scope.tenant().map(TenantId::as_str),
+            })
+            .on_conflict_do_nothing()
+            .returning(jobs::id)
+            .get_result::<i64>(&mut conn)
+            .await
+            .optional()
+        {
+            Ok(id) => {
+                info!(entity = "jobs_table", crud_operation = "CREATE", kind = %job.kind, id = ?id, "Enqueued job");
+                Ok(id)
+            }
+            Err(e) => {
+                error!(entity = "jobs_table", crud_operation = "CREATE", kind = %job.kind, error = %e, "Failed to enqueue job");
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Jobs locked by another worker are skipped rather than waited on, and
+    /// the lease keeps them hidden after this transaction commits
+    #[instrument(skip(self))]
+    async fn claim(&self, limit: i64, lease: Duration) -> Result<Vec<Job>> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "jobs_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let now = Utc::now();
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let ids: Vec<i64> = jobs::table
+                        .filter(jobs::status.eq(PENDING))
+                        .filter(jobs::run_at.le(now))
+                        .order((jobs::run_at.asc(), jobs::id.asc()))
+                        .limit(limit)
+                        .select(jobs::id)
+                        .for_update()
+                        .skip_locked()
+                        .load(conn)
+                        .await?;
+
+                    if ids.is_empty() {
+                        return Ok(Vec::new());
+                    }
+
+                    diesel::update(jobs::table.filter(jobs::id.eq_any(&ids)))
+                        .set(jobs::run_at.eq(now + lease))
+                        .returning((jobs::id, jobs::kind, jobs::payload, jobs::attempts, jobs::max_attempts, jobs::tenant_id))
+                        .get_results::<ClaimedRow>(conn)
+                        .await
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        let mut rows = match result {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(entity = "jobs_table", crud_operation = "UPDATE", error = %e, "Failed to claim jobs");
+                return Err(e.into());
+            }
+        };
+        rows.sort_by_key(|(id, ..)| *id);
+
+        let jobs: Vec<Job> = rows
+            .into_iter()
+            .filter_map(|(id, kind, payload, attempts, max_attempts, tenant_id)| {
+                let Some(kind) = JobKind::parse(&kind) else {
+                    // Left leased; written by a newer release this worker does not know about
+                    error!(entity = "jobs_table", id = id, kind = %kind, "Skipping job of unknown kind");
+                    return None;
+                };
+                let tenant = match tenant_id.as_deref().map(TenantId::parse) {
+                    None => TenantScope::All,
+                    Some(Ok(tenant)) => TenantScope::One(tenant),
+                    Some(Err(e)) => {
+                        error!(entity = "jobs_table", id = id, tenant = ?tenant_id, error = %e, "Skipping job of invalid tenant");
+                        return None;
+                    }
+                };
+                Some(Job { id, kind, payload, attempts, max_attempts, tenant })
+            })
+            .collect();
+
+        if !jobs.is_empty() {
+            info!(entity = "jobs_table", crud_operation = "UPDATE", count = jobs.len(), "Claimed jobs");
+        }
+        Ok(jobs)
+    }
+
+    #[instrument(skip(self))]
+    async fn complete(&self, id: i64) -> Result<()> {
+        self.finish(id, "complete", (jobs::status.eq(DONE), jobs::finished_at.eq(Utc::now())))
+            .await
+    }
+
+    #[instrument(skip(self, last_error), fields(run_at = %run_at))]
+    async fn retry(&self, id: i64, last_error: &str, run_at: DateTime<Utc>) -> Result<()> {
+        self.finish(
+            id,
+            "retry",
+            (
+                jobs::attempts.eq(jobs::attempts + 1),
+                jobs::last_error.eq(last_error),
+                jobs::run_at.eq(run_at),
+            ),
+        )
+        .await
+    }
+
+    #[instrument(skip(self, last_error))]
+    async fn fail(&self, id: i64, last_error: &str) -> Result<()> {
+        self.finish(
+            id,
+            "fail",
+            (
+                jobs::status.eq(FAILED),
+                jobs::attempts.eq(jobs::attempts + 1),
+                jobs::last_error.eq(last_error),
+                jobs::finished_at.eq(Utc::now()),
+            ),
+        )
+        .await
+    }
+
+    #[instrument(skip(self), fields(run_at = %run_at))]
+    async fn reschedule(&self, id: i64, run_at: DateTime<Utc>) -> Result<()> {
+        self.finish(
+            id,
+            "reschedule",
+            (jobs::attempts.eq(0), jobs::last_error.eq(None::<String>), jobs::run_at.eq(run_at)),
+        )
+        .await
+    }
+}
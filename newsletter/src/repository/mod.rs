@@ -1 +1,14 @@
+pub mod analytics;
+pub mod api_key;
+pub mod breaker;
+pub mod campaign;
+pub mod idempotency;
+pub mod jobs;
 pub mod newsletter;
+pub mod outbox;
+pub mod retry;
+pub mod sending_domain;
+pub mod subscriber_view;
+pub mod template;
+pub mod warmup;
+pub mod webhook;
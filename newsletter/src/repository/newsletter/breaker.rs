@@ -0,0 +1,303 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::domain::newsletter::attributes::{AttributeDefinition, Attributes};
+use crate::domain::newsletter::consent::{ConsentContext, ConsentRecord};
+use crate::domain::newsletter::email_change::EmailChange;
+use crate::domain::newsletter::error::{NewsletterError, Result};
+use crate::domain::newsletter::export::SubscriberExport;
+use crate::domain::newsletter::growth::DailyMetrics;
+use crate::domain::newsletter::lifecycle::{PendingPurge, SubscriptionStatus};
+use crate::domain::newsletter::mask::{NewsletterMask, PartialNewsletter};
+use crate::domain::newsletter::merge::SubscriberMerge;
+use crate::domain::newsletter::preferences::{Topic, TopicConfirmation, TopicPreference, TopicSubscription};
+use crate::domain::newsletter::query::NewsletterQuery;
+use crate::domain::newsletter::retention::{RetentionAction, RetentionPurge};
+use crate::domain::newsletter::search::{SearchHit, SearchQuery};
+use crate::domain::newsletter::stats::SubscriberStats;
+use crate::domain::newsletter::unsubscribe::{
+    ReasonCount, UnsubscribeEvent, UnsubscribeEventFilter, UnsubscribeFeedback,
+};
+use crate::domain::newsletter::{Newsletter, SubscriptionEvent};
+use crate::domain::pagination::{Page, PageRequest};
+use crate::domain::tenant::TenantId;
+use crate::infrastructure::db;
+use crate::repository::breaker::{CircuitBreaker, Outage};
+use crate::repository::newsletter::NewsletterRepository;
+
+impl Outage for NewsletterError {
+    fn is_outage(&self) -> bool {
+        match self {
+            NewsletterError::Database(e) => db::is_outage(e.as_ref()),
+            _ => false,
+        }
+    }
+
+    fn circuit_open(retry_after: Duration) -> Self {
+        NewsletterError::Unavailable(retry_after)
+    }
+}
+
+/// Repository refusing calls with [`NewsletterError::Unavailable`] while
+/// the breaker is open, instead of letting each one wait out the pool's
+/// acquire timeout during an outage. Wrap it around the retrying
+/// repository, so a call counts once however often it was retried.
+/// Without [`with_breaker`](Self::with_breaker) calls go straight through.
+pub struct CircuitBreakingNewsletterRepository<R> {
+    inner: R,
+    breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl<R: NewsletterRepository> CircuitBreakingNewsletterRepository<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, breaker: None }
+    }
+
+    pub fn with_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = Some(breaker);
+        self
+    }
+
+    async fn run<T>(&self, operation: &'static str, call: impl Future<Output = Result<T>>) -> Result<T> {
+        match &self.breaker {
+            Some(breaker) => breaker.run(operation, call).await,
+            None => call.await,
+        }
+    }
+}
+
+#[async_trait]
+impl<R: NewsletterRepository> NewsletterRepository for CircuitBreakingNewsletterRepository<R> {
+    async fn list(&self, page: PageRequest) -> Result<Page<Newsletter>> {
+        self.run("list", self.inner.list(page)).await
+    }
+
+    async fn list_masked(
+        &self,
+        query: &NewsletterQuery,
+        page: PageRequest,
+        mask: NewsletterMask,
+    ) -> Result<Page<PartialNewsletter>> {
+        self.run("list_masked", self.inner.list_masked(query, page, mask)).await
+    }
+
+    async fn get_masked(&self, email: &str, mask: NewsletterMask) -> Result<Option<PartialNewsletter>> {
+        self.run("get_masked", self.inner.get_masked(email, mask)).await
+    }
+
+    async fn add(&self, email: &str) -> Result<bool> {
+        self.run("add", self.inner.add(email)).await
+    }
+
+    async fn delete(&self, email: &str) -> Result<bool> {
+        self.run("delete", self.inner.delete(email)).await
+    }
+
+    async fn unsubscribe(&self, email: &str, feedback: &UnsubscribeFeedback) -> Result<bool> {
+        self.run("unsubscribe", self.inner.unsubscribe(email, feedback)).await
+    }
+
+    async fn list_unsubscribe_events(
+        &self,
+        filter: UnsubscribeEventFilter,
+        page: PageRequest,
+    ) -> Result<Page<UnsubscribeEvent>> {
+        self.run("list_unsubscribe_events", self.inner.list_unsubscribe_events(filter, page)).await
+    }
+
+    async fn count_unsubscribe_reasons(&self, filter: UnsubscribeEventFilter) -> Result<Vec<ReasonCount>> {
+        self.run("count_unsubscribe_reasons", self.inner.count_unsubscribe_reasons(filter)).await
+    }
+
+    async fn list_consents(&self, email: &str, page: PageRequest) -> Result<Page<ConsentRecord>> {
+        self.run("list_consents", self.inner.list_consents(email, page)).await
+    }
+
+    async fn list_subscriber_events(&self, email: &str, until: DateTime<Utc>) -> Result<Vec<SubscriptionEvent>> {
+        self.run("list_subscriber_events", self.inner.list_subscriber_events(email, until)).await
+    }
+
+    async fn stats(&self) -> Result<SubscriberStats> {
+        self.run("stats", self.inner.stats()).await
+    }
+
+    async fn record_daily_metrics(&self, day: NaiveDate) -> Result<usize> {
+        self.run("record_daily_metrics", self.inner.record_daily_metrics(day)).await
+    }
+
+    async fn list_daily_metrics(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyMetrics>> {
+        self.run("list_daily_metrics", self.inner.list_daily_metrics(from, to)).await
+    }
+
+    async fn search(&self, query: &SearchQuery, page: PageRequest) -> Result<Page<SearchHit>> {
+        self.run("search", self.inner.search(query, page)).await
+    }
+
+    async fn list_tenants(&self) -> Result<Vec<TenantId>> {
+        self.run("list_tenants", self.inner.list_tenants()).await
+    }
+
+    async fn add_many(&self, emails: &[String]) -> Result<usize> {
+        self.run("add_many", self.inner.add_many(emails)).await
+    }
+
+    async fn set_status_many(&self, emails: &[String], status: SubscriptionStatus) -> Result<usize> {
+        self.run("set_status_many", self.inner.set_status_many(emails, status)).await
+    }
+
+    async fn delete_many(&self, emails: &[String]) -> Result<usize> {
+        self.run("delete_many", self.inner.delete_many(emails)).await
+    }
+
+    async fn get_many(&self, emails: &[String]) -> Result<Vec<Newsletter>> {
+        self.run("get_many", self.inner.get_many(emails)).await
+    }
+
+    async fn get_by_email(&self, email: &str) -> Result<Option<Newsletter>> {
+        self.run("get_by_email", self.inner.get_by_email(email)).await
+    }
+
+    async fn add_pending(
+        &self,
+        email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+        consent: &ConsentContext,
+    ) -> Result<bool> {
+        self.run("add_pending", self.inner.add_pending(email, token_id, expires_at, consent)).await
+    }
+
+    async fn resubscribe(&self, email: &str, consent: &ConsentContext) -> Result<bool> {
+        self.run("resubscribe", self.inner.resubscribe(email, consent)).await
+    }
+
+    async fn confirm(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<String>> {
+        self.run("confirm", self.inner.confirm(token_id, now, consent)).await
+    }
+
+    async fn request_email_change(
+        &self,
+        email: &str,
+        new_email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        self.run(
+            "request_email_change",
+            self.inner.request_email_change(email, new_email, token_id, expires_at),
+        )
+        .await
+    }
+
+    async fn change_email(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<EmailChange>> {
+        self.run("change_email", self.inner.change_email(token_id, now, consent)).await
+    }
+
+    async fn request_topic_confirmation(
+        &self,
+        email: &str,
+        topic: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        self.run(
+            "request_topic_confirmation",
+            self.inner.request_topic_confirmation(email, topic, token_id, expires_at),
+        )
+        .await
+    }
+
+    async fn confirm_topic(&self, token_id: Uuid, now: DateTime<Utc>) -> Result<Option<TopicConfirmation>> {
+        self.run("confirm_topic", self.inner.confirm_topic(token_id, now)).await
+    }
+
+    async fn purge_expired_pending(&self, expired_by: DateTime<Utc>) -> Result<PendingPurge> {
+        self.run("purge_expired_pending", self.inner.purge_expired_pending(expired_by)).await
+    }
+
+    async fn apply_retention(&self, unsubscribed_before: DateTime<Utc>, action: RetentionAction) -> Result<RetentionPurge> {
+        self.run("apply_retention", self.inner.apply_retention(unsubscribed_before, action)).await
+    }
+
+    async fn merge_case_duplicates(&self) -> Result<usize> {
+        self.run("merge_case_duplicates", self.inner.merge_case_duplicates()).await
+    }
+
+    async fn merge_subscribers(&self, email: &str, merged_email: &str) -> Result<Option<SubscriberMerge>> {
+        self.run("merge_subscribers", self.inner.merge_subscribers(email, merged_email)).await
+    }
+
+    async fn tag(&self, emails: &[String], tag: &str) -> Result<usize> {
+        self.run("tag", self.inner.tag(emails, tag)).await
+    }
+
+    async fn untag(&self, emails: &[String], tag: &str) -> Result<usize> {
+        self.run("untag", self.inner.untag(emails, tag)).await
+    }
+
+    async fn list_by_tag(&self, tag: &str, page: PageRequest) -> Result<Page<Newsletter>> {
+        self.run("list_by_tag", self.inner.list_by_tag(tag, page)).await
+    }
+
+    async fn get_preferences(&self, email: &str) -> Result<Option<Vec<TopicSubscription>>> {
+        self.run("get_preferences", self.inner.get_preferences(email)).await
+    }
+
+    async fn set_preferences(&self, email: &str, preferences: &[TopicPreference]) -> Result<bool> {
+        self.run("set_preferences", self.inner.set_preferences(email, preferences)).await
+    }
+
+    async fn list_topics(&self) -> Result<Vec<Topic>> {
+        self.run("list_topics", self.inner.list_topics()).await
+    }
+
+    async fn set_topic_requires_confirmation(&self, topic: &str, required: bool) -> Result<bool> {
+        self.run(
+            "set_topic_requires_confirmation",
+            self.inner.set_topic_requires_confirmation(topic, required),
+        )
+        .await
+    }
+
+    async fn list_attribute_definitions(&self) -> Result<Vec<AttributeDefinition>> {
+        self.run("list_attribute_definitions", self.inner.list_attribute_definitions()).await
+    }
+
+    async fn define_attribute(&self, definition: &AttributeDefinition) -> Result<bool> {
+        self.run("define_attribute", self.inner.define_attribute(definition)).await
+    }
+
+    async fn get_attributes(&self, email: &str) -> Result<Option<Attributes>> {
+        self.run("get_attributes", self.inner.get_attributes(email)).await
+    }
+
+    async fn set_attributes(&self, email: &str, changes: &Attributes) -> Result<Option<Attributes>> {
+        self.run("set_attributes", self.inner.set_attributes(email, changes)).await
+    }
+
+    async fn set_locale(&self, email: &str, locale: Option<&str>) -> Result<bool> {
+        self.run("set_locale", self.inner.set_locale(email, locale)).await
+    }
+
+    async fn set_timezone(&self, email: &str, timezone: Option<&str>) -> Result<bool> {
+        self.run("set_timezone", self.inner.set_timezone(email, timezone)).await
+    }
+
+    async fn export(&self, email: &str) -> Result<SubscriberExport> {
+        self.run("export", self.inner.export(email)).await
+    }
+
+    /// Counted by the calls `work` makes, not as a call of its own
+    async fn with_transaction<T, F>(&self, work: F) -> Result<T>
+    where
+        T: Send,
+        F: Future<Output = Result<T>> + Send,
+    {
+        self.inner.with_transaction(work).await
+    }
+}
@@ -0,0 +1,1431 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::domain::newsletter::attributes::{AttributeDefinition, AttributeType, Attributes};
+use crate::domain::newsletter::consent::{ConsentAction, ConsentContext, ConsentRecord};
+use crate::domain::newsletter::email_change::{self, EmailChange};
+use crate::domain::newsletter::error::{NewsletterError, Result};
+use crate::domain::newsletter::export::{
+    PendingConfirmation, SubscriberExport, SubscriptionRecord, TagRecord,
+};
+use crate::domain::newsletter::growth::DailyMetrics;
+use crate::domain::newsletter::lifecycle::{PendingPurge, SubscriptionStatus};
+use crate::domain::newsletter::mask::{NewsletterMask, PartialNewsletter};
+use crate::domain::newsletter::merge::{MergedFields, SubscriberMerge};
+use crate::domain::newsletter::preferences::{
+    PreferencesError, Topic, TopicConfirmation, TopicPreference, TopicSubscription,
+};
+use crate::domain::newsletter::query::NewsletterQuery;
+use crate::domain::newsletter::retention::{self, RetentionAction, RetentionPurge};
+use crate::domain::newsletter::search::{Rank, SearchHit, SearchQuery};
+use crate::domain::newsletter::stats::SubscriberStats;
+use crate::domain::newsletter::unsubscribe::{
+    ReasonCount, UnsubscribeEvent, UnsubscribeEventFilter, UnsubscribeFeedback,
+};
+use crate::domain::newsletter::{Newsletter, SubscriptionEvent, SubscriptionEventKind};
+use crate::domain::outbox::{OutboxBacklog, OutboxMessage};
+use crate::domain::pagination::{Page, PageRequest, StaleCursor};
+use crate::domain::tenant::{TenantId, TenantScope};
+use crate::infrastructure::pseudonym::Pseudonymizer;
+use crate::infrastructure::tenant;
+use crate::repository::newsletter::NewsletterRepository;
+use crate::repository::outbox::OutboxRepository;
+
+#[derive(Debug, Clone)]
+struct Row {
+    id: i64,
+    email: String,
+    status: SubscriptionStatus,
+    created_at: DateTime<Utc>,
+    attributes: Attributes,
+    locale: Option<String>,
+    timezone: Option<String>,
+    resubscribed_at: Option<DateTime<Utc>>,
+    status_changed_at: DateTime<Utc>,
+    anonymized_at: Option<DateTime<Utc>>,
+}
+
+impl Row {
+    fn set_status(&mut self, status: SubscriptionStatus) {
+        if self.status != status {
+            self.status = status;
+            self.status_changed_at = Utc::now();
+        }
+    }
+
+    fn fields(&self) -> MergedFields {
+        MergedFields {
+            status: self.status,
+            created_at: self.created_at,
+            attributes: self.attributes.clone(),
+            locale: self.locale.clone(),
+            timezone: self.timezone.clone(),
+            resubscribed_at: self.resubscribed_at,
+        }
+    }
+
+    fn sort_key(&self) -> (&str, DateTime<Utc>, i64) {
+        (&self.email, self.created_at, self.id)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OutboxEntry {
+    message: OutboxMessage,
+    available_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    sent_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    email: String,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct PendingEmailChange {
+    email: String,
+    new_email: String,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct PendingTopicConfirmation {
+    email: String,
+    topic: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Attributes seeded by the `add_newsletter_attributes` migration
+const SEEDED_ATTRIBUTES: &[(&str, &str)] = &[
+    ("first_name", "Given name used in greetings"),
+    ("last_name", "Family name"),
+    ("locale", "Preferred language, e.g. en-US"),
+    ("signup_source", "Where the subscriber signed up"),
+];
+
+/// Topics seeded by the `create_topics` migration
+const SEEDED_TOPICS: &[(&str, &str, &str)] = &[
+    ("product_updates", "Product updates", "New features and changes to the product"),
+    ("promotions", "Promotions", "Offers and discounts"),
+    ("weekly_digest", "Weekly digest", "A summary of the week, sent every Monday"),
+];
+
+/// Rows of one tenant
+#[derive(Debug, Clone, Default)]
+struct State {
+    next_id: i64,
+    /// Kept in insertion order, so ids ascend
+    rows: Vec<Row>,
+    tokens: HashMap<Uuid, Token>,
+    email_changes: HashMap<Uuid, PendingEmailChange>,
+    topic_confirmations: HashMap<Uuid, PendingTopicConfirmation>,
+    /// Tag assignments with the time they were made
+    tags: HashMap<(String, String), DateTime<Utc>>,
+    next_event_id: i64,
+    /// Kept in insertion order, so ids ascend
+    unsubscribes: Vec<UnsubscribeEvent>,
+    next_consent_id: i64,
+    /// Kept in insertion order, so ids ascend
+    consents: Vec<ConsentRecord>,
+    /// Explicit topic choices keyed by (email, topic)
+    topic_choices: HashMap<(String, String), bool>,
+    /// Snapshots of the nightly rollup
+    metrics: BTreeMap<NaiveDate, DailyMetrics>,
+    /// Audit trail of merged subscriptions, under their audit addresses
+    merges: Vec<SubscriberMerge>,
+    /// Every event enqueued, oldest first, kept after the outbox is purged
+    subscriber_events: Vec<SubscriptionEvent>,
+}
+
+/// Tables every tenant shares, as in Postgres
+#[derive(Debug, Clone)]
+struct Shared {
+    /// Ordered by key
+    topics: Vec<Topic>,
+    /// Ordered by key
+    attribute_definitions: Vec<AttributeDefinition>,
+    next_outbox_id: i64,
+    /// Kept in insertion order, so ids ascend
+    outbox: Vec<OutboxEntry>,
+}
+
+impl Default for Shared {
+    fn default() -> Self {
+        Self {
+            topics: SEEDED_TOPICS
+                .iter()
+                .map(|(key, name, description)| Topic {
+                    key: key.to_string(),
+                    name: name.to_string(),
+                    description: description.to_string(),
+                    default_subscribed: true,
+                    requires_confirmation: false,
+                })
+                .collect(),
+            attribute_definitions: SEEDED_ATTRIBUTES
+                .iter()
+                .map(|(key, description)| AttributeDefinition {
+                    key: key.to_string(),
+                    kind: AttributeType::String,
+                    description: description.to_string(),
+                })
+                .collect(),
+            next_outbox_id: 0,
+            outbox: Vec::new(),
+        }
+    }
+}
+
+impl State {
+    /// Case-insensitive, like the `lower(email)` index
+    fn find(&self, email: &str) -> Option<&Row> {
+        self.rows.iter().find(|r| r.email.to_lowercase() == email.to_lowercase())
+    }
+
+    /// Insert unless the email exists; returns whether a row was added
+    fn insert(&mut self, email: &str, status: SubscriptionStatus) -> bool {
+        if self.find(email).is_some() {
+            return false;
+        }
+
+        self.next_id += 1;
+        self.rows.push(Row {
+            id: self.next_id,
+            email: email.to_string(),
+            status,
+            created_at: Utc::now(),
+            attributes: Attributes::new(),
+            locale: None,
+            timezone: None,
+            resubscribed_at: None,
+            status_changed_at: Utc::now(),
+            anonymized_at: None,
+        });
+        true
+    }
+
+    /// Delete rows matching `remove` together with their tokens and tags, like
+    /// the `ON DELETE CASCADE` foreign keys do in Postgres
+    fn remove_where(&mut self, remove: impl Fn(&Row) -> bool) -> usize {
+        let (removed, kept): (Vec<Row>, Vec<Row>) = self.rows.drain(..).partition(|r| remove(r));
+        self.rows = kept;
+
+        let removed: HashSet<String> = removed.into_iter().map(|r| r.email).collect();
+        self.tokens.retain(|_, token| !removed.contains(&token.email));
+        self.email_changes.retain(|_, change| !removed.contains(&change.email));
+        self.topic_confirmations
+            .retain(|_, confirmation| !removed.contains(&confirmation.email));
+        self.tags.retain(|(email, _), _| !removed.contains(email));
+        self.topic_choices.retain(|(email, _), _| !removed.contains(email));
+        removed.len()
+    }
+
+    /// Store the subscription of `from` under `to`, along with its tokens,
+    /// tags and topic choices, like the `ON UPDATE CASCADE` foreign keys do in
+    /// Postgres
+    fn rename(&mut self, from: &str, to: &str) {
+        for row in self.rows.iter_mut().filter(|r| r.email == from) {
+            row.email = to.to_string();
+        }
+        for token in self.tokens.values_mut().filter(|token| token.email == from) {
+            token.email = to.to_string();
+        }
+        for confirmation in self.topic_confirmations.values_mut().filter(|c| c.email == from) {
+            confirmation.email = to.to_string();
+        }
+        rekey(&mut self.tags, from, to);
+        rekey(&mut self.topic_choices, from, to);
+    }
+
+    /// Credit the consents and unsubscribes recorded for `from`, under the
+    /// address or its `from_audit` form, to `to_audit`
+    fn move_history(&mut self, from: &str, from_audit: &str, to_audit: &str) {
+        for consent in self
+            .consents
+            .iter_mut()
+            .filter(|c| c.email.to_lowercase() == from.to_lowercase() || c.email == from_audit)
+        {
+            consent.email = to_audit.to_string();
+        }
+        for event in self
+            .unsubscribes
+            .iter_mut()
+            .filter(|e| e.email == from || e.email == from_audit)
+        {
+            event.email = to_audit.to_string();
+        }
+    }
+
+    fn record_consent(&mut self, email: &str, action: ConsentAction, context: &ConsentContext) {
+        self.next_consent_id += 1;
+        self.consents.push(ConsentRecord {
+            id: self.next_consent_id,
+            email: email.to_string(),
+            action,
+            policy_version: context.policy_version.clone(),
+            source: context.source.clone(),
+            ip_address: context.ip_address.clone(),
+            created_at: Utc::now(),
+        });
+    }
+
+    fn purge_expired(&mut self, expired_by: DateTime<Utc>) -> PendingPurge {
+        // Address changes and topic opt-ins nobody confirmed leave the
+        // subscription as it is
+        self.email_changes.retain(|_, change| change.expires_at > expired_by);
+        self.topic_confirmations
+            .retain(|_, confirmation| confirmation.expires_at > expired_by);
+        let mut expired = HashSet::new();
+        self.tokens.retain(|_, token| {
+            let keep = token.expires_at > expired_by;
+            if !keep {
+                expired.insert(token.email.clone());
+            }
+            keep
+        });
+
+        let pending: HashSet<String> = self.tokens.values().map(|token| token.email.clone()).collect();
+        let lapsed =
+            |r: &Row| expired.contains(&r.email) && r.status == SubscriptionStatus::Pending && !pending.contains(&r.email);
+
+        let mut reverted = 0;
+        for row in self.rows.iter_mut().filter(|r| lapsed(r) && r.resubscribed_at.is_some()) {
+            row.set_status(SubscriptionStatus::Unsubscribed);
+            reverted += 1;
+        }
+        PendingPurge {
+            deleted: self.remove_where(lapsed),
+            reverted,
+        }
+    }
+
+    /// Snapshot `day` unless it has one, or the tenant has neither
+    /// subscriptions nor activity that day
+    fn record_metrics(&mut self, day: NaiveDate) -> bool {
+        if self.metrics.contains_key(&day) {
+            return false;
+        }
+        let on_day = |at: DateTime<Utc>| at.date_naive() == day;
+        let confirms = self
+            .consents
+            .iter()
+            .filter(|c| c.action == ConsentAction::Confirmed && on_day(c.created_at))
+            .count() as i64;
+        let unsubscribes = self.unsubscribes.iter().filter(|e| on_day(e.created_at)).count() as i64;
+        if self.rows.is_empty() && confirms == 0 && unsubscribes == 0 {
+            return false;
+        }
+
+        let subscribers = self.rows.iter().filter(|r| r.status == SubscriptionStatus::Active).count() as i64;
+        self.metrics.insert(
+            day,
+            DailyMetrics {
+                day,
+                subscribers,
+                confirms,
+                unsubscribes,
+                net_growth: confirms - unsubscribes,
+            },
+        );
+        true
+    }
+
+    fn project(row: &Row, mask: NewsletterMask) -> PartialNewsletter {
+        PartialNewsletter {
+            email: mask.email.then(|| row.email.clone()),
+            active: mask.active.then_some(row.status.is_active()),
+            status: mask.status.then_some(row.status),
+            created_at: mask.created_at.then_some(row.created_at),
+            locale: row.locale.clone().filter(|_| mask.locale),
+            timezone: row.timezone.clone().filter(|_| mask.timezone),
+            resubscribed_at: row.resubscribed_at.filter(|_| mask.resubscribed_at),
+        }
+    }
+
+    fn unsubscribes_matching(&self, filter: UnsubscribeEventFilter) -> impl DoubleEndedIterator<Item = &UnsubscribeEvent> {
+        self.unsubscribes.iter().filter(move |e| {
+            filter.reason.is_none_or(|reason| e.reason == Some(reason))
+                && filter.since.is_none_or(|since| e.created_at >= since)
+                && filter.until.is_none_or(|until| e.created_at < until)
+        })
+    }
+
+    fn page(&self, page: PageRequest, filter: impl Fn(&Row) -> bool) -> Page<Newsletter> {
+        let mut rows: Vec<&Row> = self
+            .rows
+            .iter()
+            .rev()
+            .filter(|r| page.after.is_none_or(|after| r.id < after))
+            .filter(|r| filter(r))
+            .take(page.limit as usize + 1)
+            .collect();
+
+        let has_more = rows.len() as i64 > page.limit;
+        rows.truncate(page.limit as usize);
+        let next_cursor = if has_more { rows.last().map(|r| r.id) } else { None };
+
+        Page {
+            items: rows
+                .into_iter()
+                .map(|r| Newsletter {
+                    email: r.email.clone(),
+                    status: r.status,
+                })
+                .collect(),
+            next_cursor,
+        }
+    }
+}
+
+/// Move the entries of `from` in a map keyed by (email, name) to `to`
+fn rekey<V>(map: &mut HashMap<(String, String), V>, from: &str, to: &str) {
+    let keys: Vec<(String, String)> = map.keys().filter(|(email, _)| email == from).cloned().collect();
+    for key in keys {
+        if let Some(value) = map.remove(&key) {
+            map.insert((to.to_string(), key.1), value);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Store {
+    shared: Shared,
+    tenants: HashMap<TenantId, State>,
+}
+
+/// The store locked with the current tenant's rows in view, standing in for
+/// the row-level security policies. `TenantScope::All` sees the default
+/// tenant, except where a method sweeps every tenant itself.
+struct TenantState<'a> {
+    store: MutexGuard<'a, Store>,
+    tenant: TenantId,
+}
+
+impl TenantState<'_> {
+    fn shared(&self) -> &Shared {
+        &self.store.shared
+    }
+
+    /// Queue an event stamped with the tenant and record it in the history,
+    /// like the Postgres outbox does
+    fn enqueue(&mut self, event: SubscriptionEvent) {
+        let event = SubscriptionEvent {
+            tenant: Some(self.tenant.clone()),
+            ..event
+        };
+        self.subscriber_events.push(event.clone());
+        let shared = &mut self.store.shared;
+        shared.next_outbox_id += 1;
+        shared.outbox.push(OutboxEntry {
+            message: OutboxMessage {
+                id: shared.next_outbox_id,
+                event,
+                attempts: 0,
+            },
+            available_at: Utc::now(),
+            created_at: Utc::now(),
+            sent_at: None,
+        });
+    }
+}
+
+impl Deref for TenantState<'_> {
+    type Target = State;
+
+    fn deref(&self) -> &State {
+        &self.store.tenants[&self.tenant]
+    }
+}
+
+impl DerefMut for TenantState<'_> {
+    fn deref_mut(&mut self) -> &mut State {
+        self.store.tenants.get_mut(&self.tenant).expect("tenant state created on lock")
+    }
+}
+
+/// NewsletterRepository kept in process memory.
+///
+/// Mirrors the Postgres semantics closely enough for service-level tests and
+/// the cucumber suites to run without a database.
+#[derive(Debug, Default)]
+pub struct InMemoryNewsletterRepository {
+    store: Mutex<Store>,
+    pseudonyms: Option<Pseudonymizer>,
+}
+
+impl InMemoryNewsletterRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep consent and unsubscribe records under pseudonyms, as the
+    /// Postgres repository does
+    pub fn with_pseudonyms(mut self, pseudonyms: Pseudonymizer) -> Self {
+        self.pseudonyms = Some(pseudonyms);
+        self
+    }
+
+    fn audit_email<'e>(&self, email: &'e str) -> Cow<'e, str> {
+        match &self.pseudonyms {
+            Some(pseudonyms) => Cow::Owned(pseudonyms.pseudonym(email)),
+            None => Cow::Borrowed(email),
+        }
+    }
+
+    fn store(&self) -> MutexGuard<'_, Store> {
+        self.store.lock().expect("in-memory repository lock poisoned")
+    }
+
+    fn state(&self) -> TenantState<'_> {
+        let tenant = tenant::current().tenant().cloned().unwrap_or_default();
+        let mut store = self.store();
+        store.tenants.entry(tenant.clone()).or_default();
+        TenantState { store, tenant }
+    }
+}
+
+#[async_trait]
+impl NewsletterRepository for InMemoryNewsletterRepository {
+    async fn list(&self, page: PageRequest) -> Result<Page<Newsletter>> {
+        Ok(self.state().page(page, |_| true))
+    }
+
+    async fn list_masked(
+        &self,
+        query: &NewsletterQuery,
+        page: PageRequest,
+        mask: NewsletterMask,
+    ) -> Result<Page<PartialNewsletter>> {
+        let state = self.state();
+        let cursor = match page.after {
+            Some(after) => match state.rows.iter().find(|r| r.id == after) {
+                Some(row) => Some(row.sort_key()),
+                None => return Err(StaleCursor(after).into()),
+            },
+            None => None,
+        };
+
+        let mut rows: Vec<&Row> = state
+            .rows
+            .iter()
+            .filter(|r| query.filter.matches(&r.email, r.status, r.created_at, &r.attributes))
+            .filter(|r| cursor.is_none_or(|c| query.order.compare(r.sort_key(), c).is_gt()))
+            .collect();
+        rows.sort_by(|a, b| query.order.compare(a.sort_key(), b.sort_key()));
+        rows.truncate(page.limit as usize + 1);
+
+        let has_more = rows.len() as i64 > page.limit;
+        rows.truncate(page.limit as usize);
+        let next_cursor = if has_more { rows.last().map(|r| r.id) } else { None };
+
+        Ok(Page {
+            items: rows.into_iter().map(|r| State::project(r, mask)).collect(),
+            next_cursor,
+        })
+    }
+
+    async fn search(&self, query: &SearchQuery, page: PageRequest) -> Result<Page<SearchHit>> {
+        let state = self.state();
+        let mut hits: Vec<(Rank, &Row)> = state
+            .rows
+            .iter()
+            .filter_map(|r| Rank::of(&r.email, query).map(|rank| (rank, r)))
+            .collect();
+        hits.sort_by(|(a, ra), (b, rb)| a.cmp_best_first(b).then_with(|| ra.email.cmp(&rb.email)));
+
+        let offset = page.after.unwrap_or(0).max(0);
+        let mut hits: Vec<_> = hits.into_iter().skip(offset as usize).take(page.limit as usize + 1).collect();
+        let has_more = hits.len() as i64 > page.limit;
+        hits.truncate(page.limit as usize);
+
+        Ok(Page {
+            items: hits
+                .into_iter()
+                .map(|(rank, r)| SearchHit {
+                    newsletter: Newsletter { email: r.email.clone(), status: r.status },
+                    score: rank.score,
+                })
+                .collect(),
+            next_cursor: has_more.then_some(offset + page.limit),
+        })
+    }
+
+    async fn get_masked(&self, email: &str, mask: NewsletterMask) -> Result<Option<PartialNewsletter>> {
+        Ok(self.state().find(email).map(|r| State::project(r, mask)))
+    }
+
+    async fn add(&self, email: &str) -> Result<bool> {
+        let mut state = self.state();
+        let inserted = state.insert(email, SubscriptionStatus::Active);
+        if inserted {
+            state.enqueue(SubscriptionEvent::status_changed(email, SubscriptionStatus::Active));
+        }
+        Ok(inserted)
+    }
+
+    async fn delete(&self, email: &str) -> Result<bool> {
+        let mut state = self.state();
+        let deleted = state.remove_where(|r| r.email == email) > 0;
+        if deleted {
+            state.enqueue(SubscriptionEvent::now(SubscriptionEventKind::Unsubscribed, email));
+        }
+        Ok(deleted)
+    }
+
+    async fn add_many(&self, emails: &[String]) -> Result<usize> {
+        let mut state = self.state();
+        let mut added = 0;
+        for email in emails {
+            if state.insert(email, SubscriptionStatus::Active) {
+                state.enqueue(SubscriptionEvent::status_changed(email.as_str(), SubscriptionStatus::Active));
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    async fn set_status_many(&self, emails: &[String], status: SubscriptionStatus) -> Result<usize> {
+        let mut state = self.state();
+        for row in state.rows.iter().filter(|r| emails.contains(&r.email)) {
+            row.status
+                .transition(status)
+                .map_err(|e| NewsletterError::InvalidTransition(format!("{}: {e}", row.email)))?;
+        }
+
+        let mut changed = Vec::new();
+        for row in state.rows.iter_mut().filter(|r| emails.contains(&r.email) && r.status != status) {
+            row.set_status(status);
+            changed.push(row.email.clone());
+        }
+        for email in &changed {
+            state.enqueue(SubscriptionEvent::status_changed(email.as_str(), status));
+        }
+        Ok(changed.len())
+    }
+
+    async fn delete_many(&self, emails: &[String]) -> Result<usize> {
+        let mut state = self.state();
+        let deleted: Vec<String> = state
+            .rows
+            .iter()
+            .filter(|r| emails.contains(&r.email))
+            .map(|r| r.email.clone())
+            .collect();
+
+        state.remove_where(|r| emails.contains(&r.email));
+        for email in &deleted {
+            state.enqueue(SubscriptionEvent::now(SubscriptionEventKind::Unsubscribed, email.as_str()));
+        }
+        Ok(deleted.len())
+    }
+
+    async fn get_many(&self, emails: &[String]) -> Result<Vec<Newsletter>> {
+        Ok(self
+            .state()
+            .rows
+            .iter()
+            .filter(|r| emails.contains(&r.email))
+            .map(|r| Newsletter {
+                email: r.email.clone(),
+                status: r.status,
+            })
+            .collect())
+    }
+
+    async fn get_by_email(&self, email: &str) -> Result<Option<Newsletter>> {
+        Ok(self.state().find(email).map(|r| Newsletter {
+            email: r.email.clone(),
+            status: r.status,
+        }))
+    }
+
+    async fn add_pending(
+        &self,
+        email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+        consent: &ConsentContext,
+    ) -> Result<bool> {
+        let mut state = self.state();
+        if state
+            .find(email)
+            .is_some_and(|row| matches!(row.status, SubscriptionStatus::Active | SubscriptionStatus::Suppressed))
+        {
+            return Ok(false);
+        }
+        let mut returning = false;
+        if !state.insert(email, SubscriptionStatus::Pending) {
+            if let Some(row) = state.rows.iter_mut().find(|r| r.email == email) {
+                if row.status == SubscriptionStatus::Unsubscribed {
+                    row.set_status(SubscriptionStatus::Pending);
+                    row.resubscribed_at = Some(Utc::now());
+                    returning = true;
+                }
+            }
+        }
+        state.tokens.insert(
+            token_id,
+            Token {
+                email: email.to_string(),
+                expires_at,
+                created_at: Utc::now(),
+            },
+        );
+        state.record_consent(&self.audit_email(email), ConsentAction::Given, consent);
+        state.enqueue(if returning {
+            SubscriptionEvent::resubscribed(email, SubscriptionStatus::Pending)
+        } else {
+            SubscriptionEvent::now(SubscriptionEventKind::Subscribed, email)
+        });
+        Ok(true)
+    }
+
+    async fn resubscribe(&self, email: &str, consent: &ConsentContext) -> Result<bool> {
+        let mut state = self.state();
+        let Some(row) = state
+            .rows
+            .iter_mut()
+            .find(|r| r.email == email && r.status == SubscriptionStatus::Unsubscribed)
+        else {
+            return Ok(false);
+        };
+
+        row.set_status(SubscriptionStatus::Active);
+        row.resubscribed_at = Some(Utc::now());
+        state.record_consent(&self.audit_email(email), ConsentAction::Given, consent);
+        state.enqueue(SubscriptionEvent::resubscribed(email, SubscriptionStatus::Active));
+        Ok(true)
+    }
+
+    async fn confirm(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<String>> {
+        let mut state = self.state();
+
+        let email = match state.tokens.remove(&token_id) {
+            Some(token) if token.expires_at > now => token.email,
+            _ => return Ok(None),
+        };
+
+        if let Some(row) = state
+            .rows
+            .iter_mut()
+            .find(|r| r.email == email && r.status == SubscriptionStatus::Pending)
+        {
+            row.set_status(SubscriptionStatus::Active);
+        }
+        state.tokens.retain(|_, token| token.email != email);
+        state.record_consent(&self.audit_email(&email), ConsentAction::Confirmed, consent);
+        state.enqueue(SubscriptionEvent::now(SubscriptionEventKind::Confirmed, email.as_str()));
+        Ok(Some(email))
+    }
+
+    async fn request_email_change(
+        &self,
+        email: &str,
+        new_email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let mut state = self.state();
+        if !state.rows.iter().any(|r| r.email == email && r.status == SubscriptionStatus::Active) {
+            return Ok(false);
+        }
+        if let Some(row) = state.find(new_email) {
+            return Err(email_change::taken(new_email, row.status));
+        }
+
+        state.email_changes.insert(
+            token_id,
+            PendingEmailChange {
+                email: email.to_string(),
+                new_email: new_email.to_string(),
+                expires_at,
+            },
+        );
+        Ok(true)
+    }
+
+    async fn change_email(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<EmailChange>> {
+        let mut state = self.state();
+        let pending = match state.email_changes.get(&token_id) {
+            Some(pending) if pending.expires_at > now => pending.clone(),
+            _ => return Ok(None),
+        };
+        // Leaves the token in place, as the rolled back transaction does in Postgres
+        if let Some(row) = state.find(&pending.new_email) {
+            return Err(email_change::taken(&pending.new_email, row.status));
+        }
+
+        state.email_changes.remove(&token_id);
+        if !state
+            .rows
+            .iter()
+            .any(|r| r.email == pending.email && r.status == SubscriptionStatus::Active)
+        {
+            return Ok(None);
+        }
+
+        let change = EmailChange {
+            from: pending.email,
+            to: pending.new_email,
+        };
+        // Other changes still waiting are moot once the address is gone
+        state.email_changes.retain(|_, other| other.email != change.from);
+        state.rename(&change.from, &change.to);
+
+        let to_audit = self.audit_email(&change.to).into_owned();
+        state.move_history(&change.from, &self.audit_email(&change.from), &to_audit);
+        state.record_consent(&to_audit, ConsentAction::Confirmed, consent);
+        state.enqueue(SubscriptionEvent::email_changed(&change));
+        Ok(Some(change))
+    }
+
+    async fn request_topic_confirmation(
+        &self,
+        email: &str,
+        topic: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let mut state = self.state();
+        if !state.rows.iter().any(|r| r.email == email && r.status == SubscriptionStatus::Active) {
+            return Ok(false);
+        }
+
+        state.topic_confirmations.insert(
+            token_id,
+            PendingTopicConfirmation {
+                email: email.to_string(),
+                topic: topic.to_string(),
+                expires_at,
+            },
+        );
+        Ok(true)
+    }
+
+    async fn confirm_topic(&self, token_id: Uuid, now: DateTime<Utc>) -> Result<Option<TopicConfirmation>> {
+        let mut state = self.state();
+        let Some(pending) = state.topic_confirmations.remove(&token_id) else {
+            return Ok(None);
+        };
+        if pending.expires_at <= now {
+            return Ok(None);
+        }
+
+        state
+            .topic_choices
+            .insert((pending.email.clone(), pending.topic.clone()), true);
+        Ok(Some(TopicConfirmation {
+            email: pending.email,
+            topic: pending.topic,
+        }))
+    }
+
+    async fn purge_expired_pending(&self, expired_by: DateTime<Utc>) -> Result<PendingPurge> {
+        match tenant::current() {
+            TenantScope::All => {
+                let mut total = PendingPurge::default();
+                for state in self.store().tenants.values_mut() {
+                    let purge = state.purge_expired(expired_by);
+                    total.deleted += purge.deleted;
+                    total.reverted += purge.reverted;
+                }
+                Ok(total)
+            }
+            TenantScope::One(_) => Ok(self.state().purge_expired(expired_by)),
+        }
+    }
+
+    async fn apply_retention(&self, unsubscribed_before: DateTime<Utc>, action: RetentionAction) -> Result<RetentionPurge> {
+        let mut state = self.state();
+        let expired: Vec<(i64, String)> = state
+            .rows
+            .iter()
+            .filter(|r| r.status == SubscriptionStatus::Unsubscribed && r.status_changed_at < unsubscribed_before)
+            // Anonymized rows have nothing left to anonymize
+            .filter(|r| action == RetentionAction::Delete || r.anonymized_at.is_none())
+            .map(|r| (r.id, r.email.clone()))
+            .collect();
+
+        let mut purge = RetentionPurge::default();
+        for (id, email) in expired {
+            let audit = self.audit_email(&email).into_owned();
+            match action {
+                RetentionAction::Anonymize => {
+                    let anonymized = retention::anonymized_email(id);
+                    let anonymized_audit = self.audit_email(&anonymized).into_owned();
+                    state.tokens.retain(|_, token| token.email != email);
+                    state.email_changes.retain(|_, change| change.email != email);
+                    state.topic_confirmations.retain(|_, c| c.email != email);
+                    state.tags.retain(|(e, _), _| *e != email);
+                    state.topic_choices.retain(|(e, _), _| *e != email);
+                    if let Some(row) = state.rows.iter_mut().find(|r| r.id == id) {
+                        row.email = anonymized.clone();
+                        row.attributes = Attributes::new();
+                        row.locale = None;
+                        row.timezone = None;
+                        row.anonymized_at = Some(Utc::now());
+                    }
+                    state.move_history(&email, &audit, &anonymized_audit);
+                    for event in state.subscriber_events.iter_mut() {
+                        if event.email == email {
+                            event.email = anonymized.clone();
+                        }
+                        if event.previous_email.as_deref() == Some(email.as_str()) {
+                            event.previous_email = Some(anonymized.clone());
+                        }
+                    }
+                    for merge in state.merges.iter_mut() {
+                        if merge.email == audit {
+                            merge.email = anonymized_audit.clone();
+                        }
+                        if merge.merged_email == audit {
+                            merge.merged_email = anonymized_audit.clone();
+                        }
+                    }
+                    purge.anonymized += 1;
+                }
+                RetentionAction::Delete => {
+                    purge.deleted += state.remove_where(|r| r.id == id);
+                    state
+                        .consents
+                        .retain(|c| c.email.to_lowercase() != email.to_lowercase() && c.email != audit);
+                    state.unsubscribes.retain(|e| e.email != email && e.email != audit);
+                    state.merges.retain(|m| m.email != audit && m.merged_email != audit);
+                    state.subscriber_events.retain(|e| e.email != email);
+                    for event in state.subscriber_events.iter_mut() {
+                        if event.previous_email.as_deref() == Some(email.as_str()) {
+                            event.previous_email = None;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(purge)
+    }
+
+    async fn merge_case_duplicates(&self) -> Result<usize> {
+        // `find` ignores case, so no two rows here differ only by case and
+        // folding comes down to renaming
+        let mut state = self.state();
+        let mut folded = 0;
+        for i in 0..state.rows.len() {
+            let from = state.rows[i].email.clone();
+            let to = from.to_lowercase();
+            if from == to {
+                continue;
+            }
+
+            state.rows[i].email = to.clone();
+            let rename = |email: String| if email == from { to.clone() } else { email };
+            state.tags = std::mem::take(&mut state.tags)
+                .into_iter()
+                .map(|((email, tag), at)| ((rename(email), tag), at))
+                .collect();
+            state.topic_choices = std::mem::take(&mut state.topic_choices)
+                .into_iter()
+                .map(|((email, topic), subscribed)| ((rename(email), topic), subscribed))
+                .collect();
+            for token in state.tokens.values_mut().filter(|token| token.email == from) {
+                token.email = to.clone();
+            }
+            for confirmation in state.topic_confirmations.values_mut().filter(|c| c.email == from) {
+                confirmation.email = to.clone();
+            }
+            folded += 1;
+        }
+        Ok(folded)
+    }
+
+    async fn merge_subscribers(&self, email: &str, merged_email: &str) -> Result<Option<SubscriberMerge>> {
+        let mut state = self.state();
+        let (Some(kept), Some(merged)) = (state.find(email).cloned(), state.find(merged_email).cloned()) else {
+            return Ok(None);
+        };
+
+        let fields = kept.fields().merge(merged.fields());
+        if let Some(row) = state.rows.iter_mut().find(|r| r.id == kept.id) {
+            row.set_status(fields.status);
+            row.created_at = fields.created_at;
+            row.attributes = fields.attributes;
+            row.locale = fields.locale;
+            row.timezone = fields.timezone;
+            row.resubscribed_at = fields.resubscribed_at;
+        }
+
+        // Tags and topic choices already made under the kept address win
+        let tags: Vec<(String, DateTime<Utc>)> = state
+            .tags
+            .iter()
+            .filter(|((e, _), _)| *e == merged.email)
+            .map(|((_, tag), at)| (tag.clone(), *at))
+            .collect();
+        for (tag, at) in tags {
+            state.tags.entry((kept.email.clone(), tag)).or_insert(at);
+        }
+        let choices: Vec<(String, bool)> = state
+            .topic_choices
+            .iter()
+            .filter(|((e, _), _)| *e == merged.email)
+            .map(|((_, topic), subscribed)| (topic.clone(), *subscribed))
+            .collect();
+        for (topic, subscribed) in choices {
+            state.topic_choices.entry((kept.email.clone(), topic)).or_insert(subscribed);
+        }
+        state.remove_where(|r| r.id == merged.id);
+
+        let kept_audit = self.audit_email(&kept.email).into_owned();
+        let merged_audit = self.audit_email(&merged.email).into_owned();
+        state.move_history(&merged.email, &merged_audit, &kept_audit);
+
+        let merge = SubscriberMerge {
+            email: kept.email,
+            merged_email: merged.email,
+            merged_status: merged.status,
+            status: fields.status,
+            merged_at: Utc::now(),
+        };
+        state.merges.push(SubscriberMerge {
+            email: kept_audit,
+            merged_email: merged_audit,
+            ..merge.clone()
+        });
+        state.enqueue(SubscriptionEvent::merged(&merge));
+        Ok(Some(merge))
+    }
+
+    async fn tag(&self, emails: &[String], tag: &str) -> Result<usize> {
+        let mut state = self.state();
+        let known: Vec<String> = emails
+            .iter()
+            .filter(|email| state.find(email).is_some())
+            .cloned()
+            .collect();
+
+        Ok(known
+            .into_iter()
+            .filter(|email| {
+                let key = (email.clone(), tag.to_string());
+                if state.tags.contains_key(&key) {
+                    return false;
+                }
+                state.tags.insert(key, Utc::now());
+                true
+            })
+            .count())
+    }
+
+    async fn untag(&self, emails: &[String], tag: &str) -> Result<usize> {
+        let mut state = self.state();
+        Ok(emails
+            .iter()
+            .filter(|email| state.tags.remove(&((*email).clone(), tag.to_string())).is_some())
+            .count())
+    }
+
+    async fn list_by_tag(&self, tag: &str, page: PageRequest) -> Result<Page<Newsletter>> {
+        let state = self.state();
+        Ok(state.page(page, |r| state.tags.contains_key(&(r.email.clone(), tag.to_string()))))
+    }
+
+    async fn unsubscribe(&self, email: &str, feedback: &UnsubscribeFeedback) -> Result<bool> {
+        let mut state = self.state();
+        match state.rows.iter_mut().find(|r| r.email == email) {
+            Some(row) if matches!(row.status, SubscriptionStatus::Pending | SubscriptionStatus::Active) => {
+                row.set_status(SubscriptionStatus::Unsubscribed);
+            }
+            _ => return Ok(false),
+        }
+        state.tokens.retain(|_, token| token.email != email);
+
+        let audit_email = self.audit_email(email);
+        state.next_event_id += 1;
+        let event = UnsubscribeEvent {
+            id: state.next_event_id,
+            email: audit_email.to_string(),
+            reason: feedback.reason,
+            comment: feedback.comment.clone(),
+            created_at: Utc::now(),
+        };
+        state.unsubscribes.push(event);
+        state.record_consent(&audit_email, ConsentAction::Withdrawn, &ConsentContext::default());
+        state.enqueue(SubscriptionEvent::now(SubscriptionEventKind::Unsubscribed, email));
+        Ok(true)
+    }
+
+    async fn list_unsubscribe_events(
+        &self,
+        filter: UnsubscribeEventFilter,
+        page: PageRequest,
+    ) -> Result<Page<UnsubscribeEvent>> {
+        let state = self.state();
+        let mut events: Vec<UnsubscribeEvent> = state
+            .unsubscribes_matching(filter)
+            .rev()
+            .filter(|e| page.after.is_none_or(|after| e.id < after))
+            .take(page.limit as usize + 1)
+            .cloned()
+            .collect();
+
+        let has_more = events.len() as i64 > page.limit;
+        events.truncate(page.limit as usize);
+        let next_cursor = if has_more { events.last().map(|e| e.id) } else { None };
+
+        Ok(Page { items: events, next_cursor })
+    }
+
+    async fn count_unsubscribe_reasons(&self, filter: UnsubscribeEventFilter) -> Result<Vec<ReasonCount>> {
+        let state = self.state();
+        let mut counts: Vec<ReasonCount> = Vec::new();
+        for event in state.unsubscribes_matching(filter) {
+            match counts.iter_mut().find(|c| c.reason == event.reason) {
+                Some(count) => count.count += 1,
+                None => counts.push(ReasonCount { reason: event.reason, count: 1 }),
+            }
+        }
+        Ok(counts)
+    }
+
+    async fn list_consents(&self, email: &str, page: PageRequest) -> Result<Page<ConsentRecord>> {
+        let audit_email = self.audit_email(email);
+        let state = self.state();
+        let mut consents: Vec<ConsentRecord> = state
+            .consents
+            .iter()
+            .rev()
+            .filter(|c| c.email.to_lowercase() == email.to_lowercase() || c.email == audit_email)
+            .filter(|c| page.after.is_none_or(|after| c.id < after))
+            .take(page.limit as usize + 1)
+            .map(|c| ConsentRecord {
+                email: email.to_string(),
+                ..c.clone()
+            })
+            .collect();
+
+        let has_more = consents.len() as i64 > page.limit;
+        consents.truncate(page.limit as usize);
+        let next_cursor = if has_more { consents.last().map(|c| c.id) } else { None };
+
+        Ok(Page { items: consents, next_cursor })
+    }
+
+    async fn list_subscriber_events(&self, email: &str, until: DateTime<Utc>) -> Result<Vec<SubscriptionEvent>> {
+        let state = self.state();
+        let mut events: Vec<SubscriptionEvent> = state
+            .subscriber_events
+            .iter()
+            .filter(|e| e.email == email || e.previous_email.as_deref() == Some(email))
+            .filter(|e| e.occurred_at <= until)
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| e.occurred_at);
+        Ok(events)
+    }
+
+    async fn stats(&self) -> Result<SubscriberStats> {
+        let state = self.state();
+        let count = |status| state.rows.iter().filter(|r| r.status == status).count() as i64;
+        Ok(SubscriberStats {
+            active: count(SubscriptionStatus::Active),
+            inactive: count(SubscriptionStatus::Pending),
+            unsubscribed: state.unsubscribes.len() as i64,
+            suppressed: count(SubscriptionStatus::Suppressed),
+        })
+    }
+
+    async fn record_daily_metrics(&self, day: NaiveDate) -> Result<usize> {
+        match tenant::current() {
+            TenantScope::All => {
+                let mut recorded = 0;
+                for state in self.store().tenants.values_mut() {
+                    recorded += usize::from(state.record_metrics(day));
+                }
+                Ok(recorded)
+            }
+            TenantScope::One(_) => Ok(usize::from(self.state().record_metrics(day))),
+        }
+    }
+
+    async fn list_daily_metrics(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyMetrics>> {
+        Ok(self.state().metrics.range(from..=to).map(|(_, metrics)| *metrics).collect())
+    }
+
+    async fn list_tenants(&self) -> Result<Vec<TenantId>> {
+        let store = self.store();
+        let mut tenants: Vec<TenantId> = store
+            .tenants
+            .iter()
+            .filter(|(_, state)| !state.rows.is_empty())
+            .map(|(tenant, _)| tenant.clone())
+            .filter(|tenant| match tenant::current() {
+                TenantScope::One(current) => *tenant == current,
+                TenantScope::All => true,
+            })
+            .collect();
+        tenants.sort();
+        Ok(tenants)
+    }
+
+    async fn list_topics(&self) -> Result<Vec<Topic>> {
+        Ok(self.store().shared.topics.clone())
+    }
+
+    async fn set_topic_requires_confirmation(&self, topic: &str, required: bool) -> Result<bool> {
+        let mut store = self.store();
+        let Some(topic) = store.shared.topics.iter_mut().find(|t| t.key == topic) else {
+            return Ok(false);
+        };
+        topic.requires_confirmation = required;
+        Ok(true)
+    }
+
+    async fn list_attribute_definitions(&self) -> Result<Vec<AttributeDefinition>> {
+        Ok(self.store().shared.attribute_definitions.clone())
+    }
+
+    async fn define_attribute(&self, definition: &AttributeDefinition) -> Result<bool> {
+        let mut store = self.store();
+        let definitions = &mut store.shared.attribute_definitions;
+        if definitions.iter().any(|d| d.key == definition.key) {
+            return Ok(false);
+        }
+
+        definitions.push(definition.clone());
+        definitions.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(true)
+    }
+
+    async fn get_attributes(&self, email: &str) -> Result<Option<Attributes>> {
+        Ok(self.state().find(email).map(|r| r.attributes.clone()))
+    }
+
+    async fn set_attributes(&self, email: &str, changes: &Attributes) -> Result<Option<Attributes>> {
+        let mut state = self.state();
+        let Some(row) = state.rows.iter_mut().find(|r| r.email == email) else {
+            return Ok(None);
+        };
+
+        for (key, value) in changes {
+            if value.is_null() {
+                row.attributes.remove(key);
+            } else {
+                row.attributes.insert(key.clone(), value.clone());
+            }
+        }
+        Ok(Some(row.attributes.clone()))
+    }
+
+    async fn set_locale(&self, email: &str, locale: Option<&str>) -> Result<bool> {
+        let mut state = self.state();
+        let Some(row) = state.rows.iter_mut().find(|r| r.email == email) else {
+            return Ok(false);
+        };
+
+        row.locale = locale.map(str::to_string);
+        Ok(true)
+    }
+
+    async fn set_timezone(&self, email: &str, timezone: Option<&str>) -> Result<bool> {
+        let mut state = self.state();
+        let Some(row) = state.rows.iter_mut().find(|r| r.email == email) else {
+            return Ok(false);
+        };
+
+        row.timezone = timezone.map(str::to_string);
+        Ok(true)
+    }
+
+    async fn get_preferences(&self, email: &str) -> Result<Option<Vec<TopicSubscription>>> {
+        let state = self.state();
+        if state.find(email).is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            state
+                .shared()
+                .topics
+                .iter()
+                .map(|topic| {
+                    let choice = state
+                        .topic_choices
+                        .get(&(email.to_string(), topic.key.clone()))
+                        .copied();
+                    TopicSubscription::resolve(topic.clone(), choice)
+                })
+                .collect(),
+        ))
+    }
+
+    async fn set_preferences(&self, email: &str, preferences: &[TopicPreference]) -> Result<bool> {
+        let mut state = self.state();
+        if state.find(email).is_none() {
+            return Ok(false);
+        }
+        if let Some(unknown) = preferences
+            .iter()
+            .find(|p| !state.shared().topics.iter().any(|t| t.key == p.topic))
+        {
+            return Err(PreferencesError::UnknownTopic(unknown.topic.clone()).into());
+        }
+
+        for preference in preferences {
+            state
+                .topic_choices
+                .insert((email.to_string(), preference.topic.clone()), preference.subscribed);
+        }
+        Ok(true)
+    }
+
+    async fn export(&self, email: &str) -> Result<SubscriberExport> {
+        let audit_email = self.audit_email(email);
+        let state = self.state();
+
+        let mut tags: Vec<TagRecord> = state
+            .tags
+            .iter()
+            .filter(|((e, _), _)| e == email)
+            .map(|((_, tag), created_at)| TagRecord {
+                tag: tag.clone(),
+                created_at: *created_at,
+            })
+            .collect();
+        tags.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+        let mut pending_confirmations: Vec<PendingConfirmation> = state
+            .tokens
+            .values()
+            .filter(|token| token.email == email)
+            .map(|token| PendingConfirmation {
+                created_at: token.created_at,
+                expires_at: token.expires_at,
+            })
+            .collect();
+        pending_confirmations.sort_by_key(|p| p.created_at);
+
+        Ok(SubscriberExport {
+            email: email.to_string(),
+            subscription: state.find(email).map(|r| SubscriptionRecord {
+                status: r.status,
+                created_at: r.created_at,
+                attributes: r.attributes.clone(),
+                locale: r.locale.clone(),
+                timezone: r.timezone.clone(),
+                resubscribed_at: r.resubscribed_at,
+            }),
+            tags,
+            pending_confirmations,
+            unsubscribes: state
+                .unsubscribes
+                .iter()
+                .filter(|e| e.email == email || e.email == audit_email)
+                .map(|e| UnsubscribeEvent {
+                    email: email.to_string(),
+                    ..e.clone()
+                })
+                .collect(),
+            merges: state
+                .merges
+                .iter()
+                .filter(|m| m.email == audit_email || m.merged_email == audit_email)
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// Puts the store back as it was when `work` fails. Not isolated like a
+    /// database transaction: what other tasks change meanwhile is put back too.
+    async fn with_transaction<T, F>(&self, work: F) -> Result<T>
+    where
+        T: Send,
+        F: Future<Output = Result<T>> + Send,
+    {
+        let snapshot = self.store().clone();
+        let result = work.await;
+        if result.is_err() {
+            *self.store() = snapshot;
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for InMemoryNewsletterRepository {
+    async fn claim(&self, limit: i64, lease: Duration) -> anyhow::Result<Vec<OutboxMessage>> {
+        let mut store = self.store();
+        let now = Utc::now();
+
+        let mut due: Vec<&mut OutboxEntry> = store
+            .shared
+            .outbox
+            .iter_mut()
+            .filter(|e| e.sent_at.is_none() && e.available_at <= now)
+            .collect();
+        due.sort_by_key(|e| (e.available_at, e.message.id));
+        due.truncate(limit.max(0) as usize);
+
+        let mut claimed: Vec<OutboxMessage> = due
+            .into_iter()
+            .map(|e| {
+                e.available_at = now + lease;
+                e.message.clone()
+            })
+            .collect();
+        claimed.sort_by_key(|m| m.id);
+        Ok(claimed)
+    }
+
+    async fn mark_sent(&self, ids: &[i64]) -> anyhow::Result<()> {
+        let now = Utc::now();
+        for entry in self.store().shared.outbox.iter_mut().filter(|e| ids.contains(&e.message.id)) {
+            entry.sent_at = Some(now);
+        }
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: i64, _error: &str, retry_at: DateTime<Utc>) -> anyhow::Result<()> {
+        if let Some(entry) = self.store().shared.outbox.iter_mut().find(|e| e.message.id == id) {
+            entry.message.attempts += 1;
+            entry.available_at = retry_at;
+        }
+        Ok(())
+    }
+
+    async fn purge_sent(&self, before: DateTime<Utc>) -> anyhow::Result<usize> {
+        let mut store = self.store();
+        let count = store.shared.outbox.len();
+        store.shared.outbox.retain(|e| e.sent_at.is_none_or(|sent_at| sent_at >= before));
+        Ok(count - store.shared.outbox.len())
+    }
+
+    async fn replay(&self, since: DateTime<Utc>) -> anyhow::Result<usize> {
+        let now = Utc::now();
+        let mut replayed = 0;
+        for entry in self.store().shared.outbox.iter_mut().filter(|e| e.sent_at.is_some_and(|sent_at| sent_at >= since)) {
+            entry.sent_at = None;
+            entry.message.attempts = 0;
+            entry.available_at = now;
+            replayed += 1;
+        }
+        Ok(replayed)
+    }
+
+    async fn backlog(&self) -> anyhow::Result<OutboxBacklog> {
+        let store = self.store();
+        let pending: Vec<&OutboxEntry> = store.shared.outbox.iter().filter(|e| e.sent_at.is_none()).collect();
+        Ok(OutboxBacklog {
+            pending: pending.len() as i64,
+            failing: pending.iter().filter(|e| e.message.attempts > 0).count() as i64,
+            oldest_pending_at: pending.iter().map(|e| e.created_at).min(),
+        })
+    }
+}
@@ -1,21 +1,266 @@
+use std::future::Future;
+
 use async_trait::async_trait;
-use anyhow::Result;
-use crate::domain::newsletter::Newsletter;
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+use crate::domain::newsletter::attributes::{AttributeDefinition, Attributes};
+use crate::domain::newsletter::consent::{ConsentContext, ConsentRecord};
+use crate::domain::newsletter::email_change::EmailChange;
+use crate::domain::newsletter::error::Result;
+use crate::domain::newsletter::export::SubscriberExport;
+use crate::domain::newsletter::growth::DailyMetrics;
+use crate::domain::newsletter::lifecycle::{PendingPurge, SubscriptionStatus};
+use crate::domain::newsletter::mask::{NewsletterMask, PartialNewsletter};
+use crate::domain::newsletter::merge::SubscriberMerge;
+use crate::domain::newsletter::preferences::{Topic, TopicConfirmation, TopicPreference, TopicSubscription};
+use crate::domain::newsletter::query::NewsletterQuery;
+use crate::domain::newsletter::retention::{RetentionAction, RetentionPurge};
+use crate::domain::newsletter::search::{SearchHit, SearchQuery};
+use crate::domain::newsletter::stats::SubscriberStats;
+use crate::domain::newsletter::unsubscribe::{
+    ReasonCount, UnsubscribeEvent, UnsubscribeEventFilter, UnsubscribeFeedback,
+};
+use crate::domain::newsletter::{Newsletter, SubscriptionEvent};
+use crate::domain::pagination::{Page, PageRequest};
+use crate::domain::tenant::TenantId;
 
+pub mod breaker;
+#[cfg(any(test, feature = "testing"))]
+pub mod memory;
 pub mod postgres;
+pub mod retry;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
 /// Repository trait for newsletter operations
 #[async_trait]
 pub trait NewsletterRepository: Send + Sync {
-    /// Get all newsletters
-    async fn list(&self) -> Result<Vec<Newsletter>>;
+    /// Get a page of newsletters, newest first
+    async fn list(&self, page: PageRequest) -> Result<Page<Newsletter>>;
+
+    /// Get a page of the newsletters matching `query` in its order, loading only
+    /// the masked fields. Fails with a `NewsletterError::Validation` if the cursor's row is gone.
+    async fn list_masked(
+        &self,
+        query: &NewsletterQuery,
+        page: PageRequest,
+        mask: NewsletterMask,
+    ) -> Result<Page<PartialNewsletter>>;
+
+    /// Get a page of subscriptions whose address contains `query` or is
+    /// similar to it, best match first; the cursor is an offset
+    async fn search(&self, query: &SearchQuery, page: PageRequest) -> Result<Page<SearchHit>>;
+
+    /// Get a newsletter by email, ignoring case, loading only the masked fields
+    async fn get_masked(&self, email: &str, mask: NewsletterMask) -> Result<Option<PartialNewsletter>>;
     
-    /// Add a new newsletter subscription
-    async fn add(&self, email: &str) -> Result<()>;
+    /// Add a new active newsletter subscription, recording a
+    /// `newsletter.status_changed` event; returns whether a row was inserted
+    /// (`false` if the address already had one)
+    async fn add(&self, email: &str) -> Result<bool>;
     
-    /// Delete a newsletter subscription
-    async fn delete(&self, email: &str) -> Result<()>;
+    /// Delete a newsletter subscription, recording a `newsletter.unsubscribed`
+    /// event; returns whether it existed
+    async fn delete(&self, email: &str) -> Result<bool>;
+
+    /// Mark a pending or active subscription unsubscribed, dropping its
+    /// confirmation tokens, and record why it was cancelled along with the
+    /// withdrawn consent, atomically; returns whether it was subscribed
+    /// (nothing is recorded otherwise)
+    async fn unsubscribe(&self, email: &str, feedback: &UnsubscribeFeedback) -> Result<bool>;
+
+    /// Get a page of recorded unsubscribes, newest first
+    async fn list_unsubscribe_events(
+        &self,
+        filter: UnsubscribeEventFilter,
+        page: PageRequest,
+    ) -> Result<Page<UnsubscribeEvent>>;
+
+    /// Count recorded unsubscribes per reason
+    async fn count_unsubscribe_reasons(&self, filter: UnsubscribeEventFilter) -> Result<Vec<ReasonCount>>;
+
+    /// Get a page of the consent steps recorded for an email, ignoring case, newest first
+    async fn list_consents(&self, email: &str, page: PageRequest) -> Result<Page<ConsentRecord>>;
+
+    /// Recorded events naming `email`, as its address or as the one it moved
+    /// from, up to `until`, oldest first
+    async fn list_subscriber_events(&self, email: &str, until: DateTime<Utc>) -> Result<Vec<SubscriptionEvent>>;
+
+    /// Count the subscriptions and unsubscribes of the current tenant
+    async fn stats(&self) -> Result<SubscriberStats>;
+
+    /// Snapshot `day` into the daily metrics of every tenant in scope that
+    /// has none for it yet, counting subscribers as they stand now; returns
+    /// how many snapshots were written
+    async fn record_daily_metrics(&self, day: NaiveDate) -> Result<usize>;
+
+    /// Recorded snapshots from `from` to `to`, both included, oldest first
+    async fn list_daily_metrics(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyMetrics>>;
+
+    /// Tenants holding at least one subscription, ordered by id; only the
+    /// current one unless running under `TenantScope::All`
+    async fn list_tenants(&self) -> Result<Vec<TenantId>>;
     
-    /// Get a newsletter by email (optional - for future use)
+    /// Add many active subscriptions in one transaction, skipping existing ones
+    /// and recording a `newsletter.status_changed` event for each new one;
+    /// returns the number of rows inserted
+    async fn add_many(&self, emails: &[String]) -> Result<usize>;
+
+    /// Move many subscriptions to `status`; returns the number that changed.
+    /// Fails with `NewsletterError::InvalidTransition`, changing nothing, if
+    /// the lifecycle forbids the move for any of them.
+    async fn set_status_many(&self, emails: &[String], status: SubscriptionStatus) -> Result<usize>;
+
+    /// Delete many subscriptions; returns the number of rows deleted
+    async fn delete_many(&self, emails: &[String]) -> Result<usize>;
+
+    /// Get the subscriptions stored under exactly these addresses, as the
+    /// bulk operations match them
+    async fn get_many(&self, emails: &[String]) -> Result<Vec<Newsletter>>;
+
+    /// Get a newsletter by email, ignoring case
     async fn get_by_email(&self, email: &str) -> Result<Option<Newsletter>>;
+
+    /// Add a pending subscription awaiting confirmation together with its
+    /// token and the given consent, reopening an unsubscribed one as a
+    /// resubscription; returns `false`, storing nothing, if the address is
+    /// active or suppressed
+    async fn add_pending(
+        &self,
+        email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+        consent: &ConsentContext,
+    ) -> Result<bool>;
+
+    /// Activate an unsubscribed subscription without confirmation, recording
+    /// the resubscription and the given consent; returns `false`, storing
+    /// nothing, if the address is not unsubscribed
+    async fn resubscribe(&self, email: &str, consent: &ConsentContext) -> Result<bool>;
+
+    /// Activate the pending subscription owning a non-expired token and
+    /// record the confirmed consent; returns its email
+    async fn confirm(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<String>>;
+
+    /// Record a move of the active subscription of `email` to `new_email`
+    /// that waits for the new address to confirm it; returns `false`, storing
+    /// nothing, if `email` has no active subscription. Fails with
+    /// `NewsletterError::AlreadySubscribed`, or `Suppressed`, if `new_email`
+    /// has a subscription of its own.
+    async fn request_email_change(
+        &self,
+        email: &str,
+        new_email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool>;
+
+    /// Move the subscription owning a non-expired change token to the new
+    /// address together with its tags, topic choices, consents and
+    /// unsubscribe history, and record the consent confirmed there; returns
+    /// both addresses, or `None` if the token is unknown or expired or the
+    /// subscription is no longer active. Fails like `request_email_change`
+    /// if the new address subscribed in the meantime.
+    async fn change_email(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<EmailChange>>;
+
+    /// Record an opt-in of the active subscription of `email` to `topic` that
+    /// waits for the subscriber to follow the link sent to them; returns
+    /// `false`, storing nothing, if `email` has no active subscription
+    async fn request_topic_confirmation(
+        &self,
+        email: &str,
+        topic: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool>;
+
+    /// Opt the subscription owning a non-expired topic token in to its topic;
+    /// returns the address and topic, or `None` if the token is unknown or
+    /// expired
+    async fn confirm_topic(&self, token_id: Uuid, now: DateTime<Utc>) -> Result<Option<TopicConfirmation>>;
+
+    /// Drop tokens that expired by `expired_by` and the unconfirmed
+    /// subscriptions left without one; unconfirmed resubscriptions go back to
+    /// unsubscribed instead, and unconfirmed address changes and topic
+    /// opt-ins leave the subscription as it is
+    async fn purge_expired_pending(&self, expired_by: DateTime<Utc>) -> Result<PendingPurge>;
+
+    /// Anonymize or delete, as `action` says, the subscriptions that
+    /// unsubscribed before `unsubscribed_before`. Anonymizing renames one to
+    /// `retention::anonymized_email`, drops its attributes, locale, timezone,
+    /// tags, topic choices and tokens, and re-points its history to the new
+    /// name; it is not touched again. Deleting removes it along with its
+    /// consent, unsubscribe, engagement and merge history.
+    async fn apply_retention(&self, unsubscribed_before: DateTime<Utc>, action: RetentionAction) -> Result<RetentionPurge>;
+
+    /// Rewrite addresses stored with uppercase letters in lowercase, merging
+    /// each into the subscription that already holds the lowercase form along
+    /// with its tags, topic choices and pending tokens; returns how many rows
+    /// were folded
+    async fn merge_case_duplicates(&self) -> Result<usize>;
+
+    /// Fold the subscription of `merged_email` into the one of `email`, both
+    /// ignoring case: their fields by `MergedFields::merge`, the tags and
+    /// topic choices `email` lacks, and the consent, unsubscribe and
+    /// engagement history of `merged_email`, recording the merge in the audit
+    /// trail; returns `None`, changing nothing, unless both have a
+    /// subscription
+    async fn merge_subscribers(&self, email: &str, merged_email: &str) -> Result<Option<SubscriberMerge>>;
+
+    /// Attach a tag to the known subscriptions among `emails`; returns the number newly tagged
+    async fn tag(&self, emails: &[String], tag: &str) -> Result<usize>;
+
+    /// Remove a tag from subscriptions; returns the number untagged
+    async fn untag(&self, emails: &[String], tag: &str) -> Result<usize>;
+
+    /// Get a page of newsletters carrying a tag, newest first
+    async fn list_by_tag(&self, tag: &str, page: PageRequest) -> Result<Page<Newsletter>>;
+
+    /// Every topic with whether the subscriber receives it; `None` if the email
+    /// has no subscription
+    async fn get_preferences(&self, email: &str) -> Result<Option<Vec<TopicSubscription>>>;
+
+    /// Store topic choices atomically; returns whether the email has a
+    /// subscription (nothing is stored otherwise). Fails with
+    /// `NewsletterError::Validation` before storing anything if a topic does not exist.
+    async fn set_preferences(&self, email: &str, preferences: &[TopicPreference]) -> Result<bool>;
+
+    /// Every topic readers can choose, ordered by key
+    async fn list_topics(&self) -> Result<Vec<Topic>>;
+
+    /// Set whether opting in to `topic` takes a confirmed link; returns
+    /// `false` if no topic has that key
+    async fn set_topic_requires_confirmation(&self, topic: &str, required: bool) -> Result<bool>;
+
+    /// The attribute schema registry, ordered by key
+    async fn list_attribute_definitions(&self) -> Result<Vec<AttributeDefinition>>;
+
+    /// Register an attribute; returns false if the key is already defined
+    async fn define_attribute(&self, definition: &AttributeDefinition) -> Result<bool>;
+
+    /// Get the attributes of a subscription; `None` if the email has none
+    async fn get_attributes(&self, email: &str) -> Result<Option<Attributes>>;
+
+    /// Merge attribute changes into a subscription, removing keys set to `null`,
+    /// and return the result; `None` if the email has no subscription
+    async fn set_attributes(&self, email: &str, changes: &Attributes) -> Result<Option<Attributes>>;
+
+    /// Set or clear the preferred locale of a subscription; returns false if
+    /// the email has none
+    async fn set_locale(&self, email: &str, locale: Option<&str>) -> Result<bool>;
+
+    /// Set or clear the timezone of a subscription; returns false if the
+    /// email has none
+    async fn set_timezone(&self, email: &str, timezone: Option<&str>) -> Result<bool>;
+
+    /// Collect everything stored for an email address
+    async fn export(&self, email: &str) -> Result<SubscriberExport>;
+
+    /// Run `work` as one transaction: the calls it makes on this repository,
+    /// and on the other repositories of the same database, commit together
+    /// when it succeeds and are rolled back when it fails
+    async fn with_transaction<T, F>(&self, work: F) -> Result<T>
+    where
+        T: Send,
+        F: Future<Output = Result<T>> + Send;
 }
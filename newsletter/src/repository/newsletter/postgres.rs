@@ -1,57 +1,795 @@
-use crate::domain::newsletter::Newsletter;
-use crate::infrastructure::db::db_schema::newsletters;
-use crate::infrastructure::db::PgPool;
+use crate::domain::newsletter::attributes::{AttributeDefinition, AttributeType, Attributes};
+use crate::domain::newsletter::consent::{ConsentAction, ConsentContext, ConsentRecord};
+use crate::domain::newsletter::email_change::{self, EmailChange};
+use crate::domain::newsletter::error::{NewsletterError, Result};
+use crate::domain::newsletter::export::{
+    PendingConfirmation, SubscriberExport, SubscriptionRecord, TagRecord,
+};
+use crate::domain::newsletter::growth::DailyMetrics;
+use crate::domain::newsletter::history;
+use crate::domain::newsletter::lifecycle::{PendingPurge, SubscriptionStatus};
+use crate::domain::newsletter::mask::{NewsletterMask, PartialNewsletter};
+use crate::domain::newsletter::merge::{MergedFields, SubscriberMerge};
+use crate::domain::newsletter::preferences::{
+    PreferencesError, Topic, TopicConfirmation, TopicPreference, TopicSubscription,
+};
+use crate::domain::newsletter::query::{NewsletterFilter, NewsletterQuery, SortField};
+use crate::domain::newsletter::retention::{self, RetentionAction, RetentionPurge};
+use crate::domain::newsletter::search::{SearchHit, SearchQuery};
+use crate::domain::newsletter::stats::SubscriberStats;
+use crate::domain::newsletter::unsubscribe::{
+    ReasonCount, UnsubscribeEvent, UnsubscribeEventFilter, UnsubscribeFeedback, UnsubscribeReason,
+};
+use crate::domain::newsletter::{Newsletter, SubscriptionEvent, SubscriptionEventKind};
+use crate::domain::pagination::{Page, PageRequest, StaleCursor};
+use crate::domain::tenant::TenantId;
+use crate::infrastructure::db::db_schema::{
+    attribute_definitions, campaign_complaints, campaign_deliveries, confirmation_tokens, consents, email_changes, engagement_events, list_metrics_daily, newsletters, subscriber_events,
+    subscriber_merges, subscriber_tags, subscriber_topics, topic_confirmations, topics, unsubscribe_events,
+};
+use crate::infrastructure::db::{self, tenant_connection, Cancellable, PgPool, ReadPool};
+use crate::infrastructure::logging;
+use crate::infrastructure::pseudonym::Pseudonymizer;
 use crate::repository::newsletter::NewsletterRepository;
+use crate::repository::outbox::postgres::enqueue;
+
+use std::borrow::Cow;
+use std::future::Future;
 
-use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use diesel::dsl::{exists, not};
 use diesel::prelude::*;
+use diesel::dsl::sql;
+use diesel::expression::SqlLiteral;
+use diesel::sql_types::{Array, BigInt, Date, Double, Nullable, Text, Timestamptz};
 use diesel::SelectableHelper;
-use diesel_async::RunQueryDsl;
+use diesel::result::DatabaseErrorKind;
+use diesel::declare_sql_function;
+use diesel_async::pooled_connection::bb8::RunError;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use std::collections::BTreeMap;
+use uuid::Uuid;
 use tracing::{info, error, instrument};
 
+#[declare_sql_function]
+extern "SQL" {
+    /// Case folding of the `newsletters_email_lower_idx` index; addresses
+    /// in `newsletters` are lowercase already, so only other tables need it
+    fn lower(value: Text) -> Text;
+}
+
+impl From<diesel::result::Error> for NewsletterError {
+    fn from(e: diesel::result::Error) -> Self {
+        match e {
+            diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, info) => {
+                NewsletterError::Conflict(info.message().to_string())
+            }
+            // Such as an address that is not lowercase
+            diesel::result::Error::DatabaseError(DatabaseErrorKind::CheckViolation, info) => {
+                NewsletterError::Validation(info.message().to_string())
+            }
+            e => NewsletterError::database(e),
+        }
+    }
+}
+
+impl From<RunError> for NewsletterError {
+    fn from(e: RunError) -> Self {
+        NewsletterError::database(e)
+    }
+}
+
 #[derive(Debug, Clone, Queryable, Selectable)]
 #[diesel(table_name = newsletters)]
 #[diesel(check_for_backend(diesel::pg::Pg))] // optional: extra compile-time checks
 struct NewsletterRow {
-    #[allow(dead_code)]
     pub id: i64,
     pub email: String,
-    pub active: bool,
+    pub status: String,
     #[allow(dead_code)]
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// A subscription as read when folding the case variants of an address or
+/// merging two subscribers
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = newsletters)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct CaseVariantRow {
+    email: String,
+    status: String,
+    created_at: DateTime<Utc>,
+    attributes: serde_json::Value,
+    locale: Option<String>,
+    timezone: Option<String>,
+    resubscribed_at: Option<DateTime<Utc>>,
+}
+
+impl CaseVariantRow {
+    /// Statuses folded by `SubscriptionStatus::merge`, created with the
+    /// oldest, resubscribed with the latest, and attributes, locale and
+    /// timezone already set winning over those of later rows
+    fn merge(mut self, rows: impl IntoIterator<Item = Self>) -> Result<Self> {
+        for row in rows {
+            self.status = parse_status(&self.status)?.merge(parse_status(&row.status)?).as_str().to_string();
+            self.created_at = self.created_at.min(row.created_at);
+            self.locale = self.locale.or(row.locale);
+            self.timezone = self.timezone.or(row.timezone);
+            self.resubscribed_at = self.resubscribed_at.max(row.resubscribed_at);
+            if let (serde_json::Value::Object(into), serde_json::Value::Object(from)) =
+                (&mut self.attributes, row.attributes)
+            {
+                for (key, value) in from {
+                    into.entry(key).or_insert(value);
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    fn fields(&self) -> Result<MergedFields> {
+        Ok(MergedFields {
+            status: parse_status(&self.status)?,
+            created_at: self.created_at,
+            attributes: attributes_from_json(self.attributes.clone())?,
+            locale: self.locale.clone(),
+            timezone: self.timezone.clone(),
+            resubscribed_at: self.resubscribed_at,
+        })
+    }
+}
+
 #[derive(Insertable)]
 #[diesel(table_name = newsletters)]
 #[diesel(check_for_backend(diesel::pg::Pg))] // optional
 struct NewNewsletter<'a> {
     pub email: &'a str,
-    pub active: bool,
+    pub status: &'a str,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = confirmation_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewConfirmationToken<'a> {
+    pub token_id: Uuid,
+    pub email: &'a str,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = email_changes)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewEmailChange<'a> {
+    pub token_id: Uuid,
+    pub email: &'a str,
+    pub new_email: &'a str,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = topic_confirmations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewTopicConfirmation<'a> {
+    pub token_id: Uuid,
+    pub email: &'a str,
+    pub topic: &'a str,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = subscriber_merges)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewSubscriberMerge<'a> {
+    pub email: &'a str,
+    pub merged_email: &'a str,
+    pub merged_status: &'a str,
+    pub status: &'a str,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = subscriber_merges)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct SubscriberMergeRow {
+    pub email: String,
+    pub merged_email: String,
+    pub merged_status: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<SubscriberMergeRow> for SubscriberMerge {
+    type Error = NewsletterError;
+
+    fn try_from(row: SubscriberMergeRow) -> Result<Self> {
+        Ok(SubscriberMerge {
+            email: row.email,
+            merged_email: row.merged_email,
+            merged_status: parse_status(&row.merged_status)?,
+            status: parse_status(&row.status)?,
+            merged_at: row.created_at,
+        })
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = unsubscribe_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewUnsubscribeEvent<'a> {
+    pub email: &'a str,
+    pub reason: Option<&'a str>,
+    pub comment: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = unsubscribe_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct UnsubscribeEventRow {
+    pub id: i64,
+    pub email: String,
+    pub reason: Option<String>,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = consents)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewConsent<'a> {
+    pub email: &'a str,
+    pub action: &'a str,
+    pub policy_version: Option<&'a str>,
+    pub source: Option<&'a str>,
+    pub ip_address: Option<&'a str>,
+}
+
+impl<'a> NewConsent<'a> {
+    fn new(email: &'a str, action: ConsentAction, context: &'a ConsentContext) -> Self {
+        Self {
+            email,
+            action: action.as_str(),
+            policy_version: context.policy_version.as_deref(),
+            source: context.source.as_deref(),
+            ip_address: context.ip_address.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = consents)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct ConsentRow {
+    pub id: i64,
+    pub email: String,
+    pub action: String,
+    pub policy_version: Option<String>,
+    pub source: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = list_metrics_daily)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct DailyMetricsRow {
+    pub day: NaiveDate,
+    pub subscribers: i64,
+    pub confirms: i64,
+    pub unsubscribes: i64,
+    pub net_growth: i64,
+}
+
+impl From<DailyMetricsRow> for DailyMetrics {
+    fn from(row: DailyMetricsRow) -> Self {
+        Self {
+            day: row.day,
+            subscribers: row.subscribers,
+            confirms: row.confirms,
+            unsubscribes: row.unsubscribes,
+            net_growth: row.net_growth,
+        }
+    }
+}
+
+/// Snapshot of day `$1`, whose activity falls in `[$2, $3)`, for every
+/// tenant visible to the connection that has subscriptions or activity
+const RECORD_DAILY_METRICS_QUERY: &str = "
+    WITH subscribers AS (
+        SELECT tenant_id, count(*) FILTER (WHERE status = 'active') AS n
+        FROM newsletters
+        GROUP BY tenant_id
+    ), confirms AS (
+        SELECT tenant_id, count(*) AS n
+        FROM consents
+        WHERE action = 'confirmed' AND created_at >= $2 AND created_at < $3
+        GROUP BY tenant_id
+    ), unsubscribes AS (
+        SELECT tenant_id, count(*) AS n
+        FROM unsubscribe_events
+        WHERE created_at >= $2 AND created_at < $3
+        GROUP BY tenant_id
+    ), tenants AS (
+        SELECT tenant_id FROM subscribers
+        UNION SELECT tenant_id FROM confirms
+        UNION SELECT tenant_id FROM unsubscribes
+    )
+    INSERT INTO list_metrics_daily (tenant_id, day, subscribers, confirms, unsubscribes, net_growth)
+    SELECT t.tenant_id, $1,
+           coalesce(s.n, 0), coalesce(c.n, 0), coalesce(u.n, 0),
+           coalesce(c.n, 0) - coalesce(u.n, 0)
+    FROM tenants t
+    LEFT JOIN subscribers s USING (tenant_id)
+    LEFT JOIN confirms c USING (tenant_id)
+    LEFT JOIN unsubscribes u USING (tenant_id)
+    ON CONFLICT (tenant_id, day) DO NOTHING";
+
+/// Credit the complaints about `$2` to `$1`, except for campaigns `$1`
+/// complained about already; those are left to be dropped
+const MOVE_COMPLAINTS_QUERY: &str = "
+    UPDATE campaign_complaints c SET email = $1
+    WHERE c.email = $2
+      AND NOT EXISTS (
+          SELECT 1 FROM campaign_complaints k WHERE k.campaign_id = c.campaign_id AND k.email = $1
+      )";
+
+/// Rewrite `$1` to `$2` in the subscriber history, in the columns and in
+/// the payloads, keeping what happened and when
+const ANONYMIZE_HISTORY_QUERY: &str = "
+    UPDATE subscriber_events SET
+        email = CASE WHEN email = $1 THEN $2 ELSE email END,
+        previous_email = CASE WHEN previous_email = $1 THEN $2 ELSE previous_email END,
+        payload = payload || jsonb_strip_nulls(jsonb_build_object(
+            'email', CASE WHEN email = $1 THEN $2 END,
+            'previous_email', CASE WHEN previous_email = $1 THEN $2 END
+        ))
+    WHERE email = $1 OR previous_email = $1";
+
+/// Forget the addresses in `$1` as where subscriptions moved from, once
+/// their own events are deleted
+const FORGET_PREVIOUS_EMAILS_QUERY: &str = "
+    UPDATE subscriber_events SET previous_email = NULL, payload = payload - 'previous_email'
+    WHERE previous_email = ANY($1)";
+
+/// A subscription found by `SEARCH_QUERY`, with its trigram similarity
+#[derive(Debug, QueryableByName)]
+struct SearchRow {
+    #[diesel(sql_type = Text)]
+    email: String,
+    #[diesel(sql_type = Text)]
+    status: String,
+    #[diesel(sql_type = Double)]
+    score: f64,
+}
+
+/// Subscriptions whose address is trigram-similar to `$1` or matches the
+/// LIKE pattern `$2`, ranked as `Rank::cmp_best_first` does; `$3` rows from
+/// offset `$4`. `%` uses the `newsletters_email_trgm_idx` index.
+const SEARCH_QUERY: &str = "
+    SELECT email, status, similarity(lower(email), $1)::float8 AS score
+    FROM newsletters
+    WHERE lower(email) % $1 OR lower(email) LIKE $2
+    ORDER BY lower(email) = $1 DESC,
+             lower(email) LIKE $2 DESC,
+             score DESC,
+             levenshtein(left(lower(email), 255), left($1, 255)),
+             email
+    LIMIT $3 OFFSET $4";
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = attribute_definitions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct AttributeDefinitionRow {
+    pub key: String,
+    pub kind: String,
+    pub description: String,
+}
+
+impl TryFrom<AttributeDefinitionRow> for AttributeDefinition {
+    type Error = NewsletterError;
+
+    fn try_from(row: AttributeDefinitionRow) -> Result<Self> {
+        let kind = AttributeType::parse(&row.kind)
+            .ok_or_else(|| NewsletterError::database(format!("unknown attribute type in database: {}", row.kind)))?;
+
+        Ok(AttributeDefinition {
+            key: row.key,
+            kind,
+            description: row.description,
+        })
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = attribute_definitions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewAttributeDefinitionRow<'a> {
+    pub key: &'a str,
+    pub kind: &'a str,
+    pub description: &'a str,
+}
+
+/// Read a JSONB attributes column, which the schema keeps an object
+pub(crate) fn attributes_from_json(value: serde_json::Value) -> Result<Attributes> {
+    match value {
+        serde_json::Value::Object(attributes) => Ok(attributes),
+        other => Err(NewsletterError::database(format!("attributes in database are not an object: {other}"))),
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = topics)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct TopicRow {
+    pub key: String,
+    pub name: String,
+    pub description: String,
+    pub default_subscribed: bool,
+    pub requires_confirmation: bool,
+}
+
+impl From<TopicRow> for Topic {
+    fn from(row: TopicRow) -> Self {
+        Topic {
+            key: row.key,
+            name: row.name,
+            description: row.description,
+            default_subscribed: row.default_subscribed,
+            requires_confirmation: row.requires_confirmation,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = subscriber_topics)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewSubscriberTopicRow<'a> {
+    pub email: &'a str,
+    pub topic: &'a str,
+    pub subscribed: bool,
+}
+
+pub(crate) fn parse_status(value: &str) -> Result<SubscriptionStatus> {
+    SubscriptionStatus::parse(value)
+        .ok_or_else(|| NewsletterError::database(format!("unknown subscription status in database: {value}")))
+}
+
+pub(super) fn parse_reason(value: Option<String>) -> Result<Option<UnsubscribeReason>> {
+    value
+        .map(|v| {
+            UnsubscribeReason::parse(&v)
+                .ok_or_else(|| NewsletterError::database(format!("unknown unsubscribe reason in database: {v}")))
+        })
+        .transpose()
+}
+
+impl TryFrom<UnsubscribeEventRow> for UnsubscribeEvent {
+    type Error = NewsletterError;
+
+    fn try_from(row: UnsubscribeEventRow) -> Result<Self> {
+        Ok(UnsubscribeEvent {
+            id: row.id,
+            email: row.email,
+            reason: parse_reason(row.reason)?,
+            comment: row.comment,
+            created_at: row.created_at,
+        })
+    }
+}
+
+impl TryFrom<ConsentRow> for ConsentRecord {
+    type Error = NewsletterError;
+
+    fn try_from(row: ConsentRow) -> Result<Self> {
+        let action = ConsentAction::parse(&row.action)
+            .ok_or_else(|| NewsletterError::database(format!("unknown consent action in database: {}", row.action)))?;
+
+        Ok(ConsentRecord {
+            id: row.id,
+            email: row.email,
+            action,
+            policy_version: row.policy_version,
+            source: row.source,
+            ip_address: row.ip_address,
+            created_at: row.created_at,
+        })
+    }
+}
+
+/// Apply the reason and time window of a filter to an unsubscribe_events query
+fn filter_unsubscribe_events<'a>(
+    mut query: unsubscribe_events::BoxedQuery<'a, diesel::pg::Pg>,
+    filter: UnsubscribeEventFilter,
+) -> unsubscribe_events::BoxedQuery<'a, diesel::pg::Pg> {
+    if let Some(reason) = filter.reason {
+        query = query.filter(unsubscribe_events::reason.eq(reason.as_str()));
+    }
+    if let Some(since) = filter.since {
+        query = query.filter(unsubscribe_events::created_at.ge(since));
+    }
+    if let Some(until) = filter.until {
+        query = query.filter(unsubscribe_events::created_at.lt(until));
+    }
+    query
+}
+
+/// Rows per multi-row INSERT, keeping bind parameters well under Postgres' 65535 limit
+const INSERT_CHUNK_SIZE: usize = 10_000;
+
+/// Turn `limit + 1` keyset rows into a page, using the extra row as the "has more" marker
+fn into_page(mut rows: Vec<NewsletterRow>, limit: i64) -> Result<Page<Newsletter>> {
+    let has_more = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+    let next_cursor = if has_more { rows.last().map(|r| r.id) } else { None };
+
+    Ok(Page {
+        items: rows
+            .into_iter()
+            .map(|r| {
+                Ok(Newsletter {
+                    status: parse_status(&r.status)?,
+                    email: r.email,
+                })
+            })
+            .collect::<Result<_>>()?,
+        next_cursor,
+    })
+}
+
+/// Status of the subscription `email` holds, ignoring case, if it has one
+async fn taken_status(conn: &mut AsyncPgConnection, email: &str) -> Result<Option<SubscriptionStatus>> {
+    let status: Option<String> = newsletters::table
+        .filter(newsletters::email.eq(email.to_lowercase()))
+        .select(newsletters::status)
+        .first(conn)
+        .await
+        .optional()?;
+    status.as_deref().map(parse_status).transpose()
+}
+
+/// Escape LIKE wildcards so user input only ever matches literally
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn filter_newsletters<'a>(
+    mut query: newsletters::BoxedQuery<'a, diesel::pg::Pg, MaskedSqlRow>,
+    filter: &NewsletterFilter,
+) -> newsletters::BoxedQuery<'a, diesel::pg::Pg, MaskedSqlRow> {
+    if let Some(active) = filter.active {
+        query = query.filter(newsletters::active.eq(active));
+    }
+    if let Some(status) = filter.status {
+        query = query.filter(newsletters::status.eq(status.as_str()));
+    }
+    if let Some(prefix) = &filter.email_prefix {
+        query = query.filter(newsletters::email.like(format!("{}%", escape_like(prefix))));
+    }
+    if let Some(domain) = &filter.email_domain {
+        query = query.filter(newsletters::email.like(format!("%@{}", escape_like(domain))));
+    }
+    if let Some(since) = filter.created_since {
+        query = query.filter(newsletters::created_at.ge(since));
+    }
+    if let Some(until) = filter.created_until {
+        query = query.filter(newsletters::created_at.lt(until));
+    }
+    if !filter.attributes.is_empty() {
+        query = query.filter(newsletters::attributes.contains(serde_json::Value::Object(filter.attributes.clone())));
+    }
+    query
+}
+
+type MaskedSqlRow = (
+    diesel::sql_types::BigInt,
+    Nullable<Text>,
+    Nullable<Text>,
+    Nullable<Timestamptz>,
+    Nullable<Text>,
+    Nullable<Text>,
+    Nullable<Timestamptz>,
+);
+
+type MaskedColumns = (
+    SqlLiteral<Nullable<Text>>,
+    SqlLiteral<Nullable<Text>>,
+    SqlLiteral<Nullable<Timestamptz>>,
+    SqlLiteral<Nullable<Text>>,
+    SqlLiteral<Nullable<Text>>,
+    SqlLiteral<Nullable<Timestamptz>>,
+);
+
+/// Columns for a masked select; unselected fields are read as NULL literals so
+/// Postgres never ships them. Only these fixed fragments ever reach the SQL.
+fn masked_columns(mask: NewsletterMask) -> MaskedColumns {
+    (
+        sql(if mask.email { "newsletters.email" } else { "NULL::text" }),
+        // The active flag is derived from the status
+        sql(if mask.active || mask.status { "newsletters.status" } else { "NULL::text" }),
+        sql(if mask.created_at { "newsletters.created_at" } else { "NULL::timestamptz" }),
+        sql(if mask.locale { "newsletters.locale" } else { "NULL::text" }),
+        sql(if mask.timezone { "newsletters.timezone" } else { "NULL::text" }),
+        sql(if mask.resubscribed_at { "newsletters.resubscribed_at" } else { "NULL::timestamptz" }),
+    )
+}
+
+type MaskedRow = (
+    i64,
+    Option<String>,
+    Option<String>,
+    Option<DateTime<Utc>>,
+    Option<String>,
+    Option<String>,
+    Option<DateTime<Utc>>,
+);
+
+fn partial(mask: NewsletterMask) -> impl Fn(MaskedRow) -> Result<PartialNewsletter> {
+    move |(_, email, status, created_at, locale, timezone, resubscribed_at)| {
+        let status = status.as_deref().map(parse_status).transpose()?;
+        Ok(PartialNewsletter {
+            email,
+            active: status.filter(|_| mask.active).map(|s| s.is_active()),
+            status: status.filter(|_| mask.status),
+            created_at,
+            locale,
+            timezone,
+            resubscribed_at,
+        })
+    }
 }
 
+/// The subscription columns of an export
+type ExportRow = (
+    String,
+    DateTime<Utc>,
+    serde_json::Value,
+    Option<String>,
+    Option<String>,
+    Option<DateTime<Utc>>,
+);
+
 /// PostgreSQL implementation of the NewsletterRepository trait
 #[derive(Clone)]
 pub struct PostgresNewsletterRepository {
     pool: PgPool,
+    /// Listings, lookups by email and stats; everything else uses `pool`
+    reads: ReadPool,
+    pseudonyms: Option<Pseudonymizer>,
 }
 
 impl PostgresNewsletterRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            reads: ReadPool::primary(pool.clone()),
+            pool,
+            pseudonyms: None,
+        }
+    }
+
+    /// Serve listings, lookups by email and stats from `reads`, which may
+    /// lag behind the primary
+    pub fn with_reads(mut self, reads: ReadPool) -> Self {
+        self.reads = reads;
+        self
+    }
+
+    /// Write consent and unsubscribe records under the subscriber's pseudonym,
+    /// leaving the newsletters table the only place holding the address.
+    /// Records written before are still found by the address.
+    pub fn with_pseudonyms(mut self, pseudonyms: Pseudonymizer) -> Self {
+        self.pseudonyms = Some(pseudonyms);
+        self
+    }
+
+    /// How `email` is written to the consent and unsubscribe records
+    fn audit_email<'e>(&self, email: &'e str) -> Cow<'e, str> {
+        match &self.pseudonyms {
+            Some(pseudonyms) => Cow::Owned(pseudonyms.pseudonym(email)),
+            None => Cow::Borrowed(email),
+        }
+    }
+
+    /// Replay the subscriber history of the tenants in scope and set the
+    /// status of the stored subscriptions it disagrees with, as of their last
+    /// event. Subscriptions without history are left alone, and no events are
+    /// written. Returns the number of subscriptions corrected.
+    #[instrument(skip(self))]
+    pub async fn rebuild_snapshots(&self) -> Result<usize> {
+        info!(entity = "newsletter_table", crud_operation = "UPDATE", "Starting database rebuild_snapshots operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, NewsletterError, _>(|conn| {
+                async move {
+                    let tenants: Vec<String> = subscriber_events::table
+                        .select(subscriber_events::tenant_id)
+                        .distinct()
+                        .load(conn)
+                        .await?;
+
+                    let mut corrected = 0;
+                    for tenant in tenants {
+                        let events = subscriber_events::table
+                            .filter(subscriber_events::tenant_id.eq(&tenant))
+                            .order((subscriber_events::occurred_at.asc(), subscriber_events::id.asc()))
+                            .select(subscriber_events::payload)
+                            .load::<serde_json::Value>(conn)
+                            .await?
+                            .into_iter()
+                            .map(|payload| serde_json::from_value(payload).map_err(NewsletterError::database))
+                            .collect::<Result<Vec<SubscriptionEvent>>>()?;
+                        let replayed = history::replay(&events);
+
+                        let stored: Vec<(String, String)> = newsletters::table
+                            .filter(newsletters::tenant_id.eq(&tenant))
+                            .select((newsletters::email, newsletters::status))
+                            .for_update()
+                            .load(conn)
+                            .await?;
+                        for (email, status) in stored {
+                            let Some(view) = replayed.get(&email) else {
+                                continue;
+                            };
+                            if parse_status(&status)? == view.status {
+                                continue;
+                            }
+                            corrected += diesel::update(
+                                newsletters::table
+                                    .filter(newsletters::tenant_id.eq(&tenant))
+                                    .filter(newsletters::email.eq(&email)),
+                            )
+                            .set((
+                                newsletters::status.eq(view.status.as_str()),
+                                newsletters::status_changed_at.eq(view.updated_at),
+                            ))
+                            .execute(conn)
+                            .await?;
+                        }
+                    }
+                    Ok(corrected)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(corrected) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", rows_affected = corrected, "Successfully rebuilt newsletter snapshots from the subscriber history");
+                Ok(corrected)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to rebuild newsletter snapshots from the subscriber history");
+                Err(e)
+            }
+        }
     }
 }
 
 #[async_trait]
 impl NewsletterRepository for PostgresNewsletterRepository {
-    #[instrument(skip(self))]
-    async fn list(&self) -> Result<Vec<Newsletter>> {
-        info!(entity = "newsletter_table", crud_operation = "READ", "Starting database list operation");
+    #[instrument(skip(self), fields(limit = page.limit, after = ?page.after))]
+    async fn list(&self, page: PageRequest) -> Result<Page<Newsletter>> {
+        info!(entity = "newsletter_table", crud_operation = "READ", limit = page.limit, after = ?page.after, "Starting database list operation");
 
-        let mut conn = match self.pool.get().await {
+        let mut conn = match self.reads.tenant_connection().await {
             Ok(conn) => {
                 info!(entity = "newsletter_table", "Successfully acquired database connection");
-                conn
+                Cancellable::new(conn)
             }
             Err(e) => {
                 error!(entity = "newsletter_table", error = %e, "Failed to acquire database connection");
@@ -59,12 +797,19 @@ impl NewsletterRepository for PostgresNewsletterRepository {
             }
         };
 
-        let rows: Vec<NewsletterRow> = match newsletters::table
+        // Keyset pagination: walk the primary key downwards and fetch one extra
+        // row to find out whether another page exists.
+        let mut query = newsletters::table
             .select(NewsletterRow::as_select())
             .order(newsletters::id.desc())
-            .load(&mut conn)
-            .await
-        {
+            .limit(page.limit + 1)
+            .into_boxed();
+
+        if let Some(after) = page.after {
+            query = query.filter(newsletters::id.lt(after));
+        }
+
+        let rows: Vec<NewsletterRow> = match conn.run(|conn| query.load(conn).scope_boxed()).await {
             Ok(rows) => {
                 info!(entity = "newsletter_table", crud_operation = "READ", rows_count = rows.len(), "Successfully retrieved newsletters from database");
                 rows
@@ -75,139 +820,2527 @@ impl NewsletterRepository for PostgresNewsletterRepository {
             }
         };
 
-        Ok(rows
-            .into_iter()
-            .map(|r| Newsletter {
-                email: r.email,
-                active: r.active,
-            })
-            .collect())
+        into_page(rows, page.limit)
     }
 
-    #[instrument(skip(self), fields(email = %email))]
-    async fn add(&self, email: &str) -> Result<()> {
-        info!(entity = "newsletter_table", crud_operation = "CREATE", email = %email, "Starting database add operation");
+    #[instrument(skip(self), fields(limit = page.limit, after = ?page.after))]
+    async fn list_masked(
+        &self,
+        query: &NewsletterQuery,
+        page: PageRequest,
+        mask: NewsletterMask,
+    ) -> Result<Page<PartialNewsletter>> {
+        info!(entity = "newsletter_table", crud_operation = "READ", limit = page.limit, after = ?page.after, mask = ?mask, filter = ?query.filter, order = ?query.order, "Starting database list_masked operation");
 
-        let mut conn = match self.pool.get().await {
-            Ok(conn) => {
-                info!(entity = "newsletter_table", email = %email, "Successfully acquired database connection");
-                conn
-            }
+        let mut conn = match self.reads.tenant_connection().await {
+            Ok(conn) => Cancellable::new(conn),
             Err(e) => {
-                error!(entity = "newsletter_table", crud_operation = "CREATE", email = %email, error = %e, "Failed to acquire database connection");
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
                 return Err(e.into());
             }
         };
 
-        match diesel::insert_into(newsletters::table)
-            .values(&NewNewsletter {
-                email,
-                active: true,
-            })
-            .on_conflict(newsletters::email)
-            .do_nothing()
-            .execute(&mut conn)
-            .await
-        {
-            Ok(_) => {
-                info!(entity = "newsletter_table", crud_operation = "CREATE", email = %email, "Successfully added newsletter to database");
-                Ok(())
-            }
+        // The cursor is an id; its row supplies the sort key the next page starts after
+        let cursor = match page.after {
+            Some(after) => match newsletters::table
+                .filter(newsletters::id.eq(after))
+                .select((newsletters::email, newsletters::created_at))
+                .first::<(String, DateTime<Utc>)>(&mut conn)
+                .await
+                .optional()
+            {
+                Ok(Some((email, created_at))) => Some((after, email, created_at)),
+                Ok(None) => return Err(StaleCursor(after).into()),
+                Err(e) => {
+                    error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to look up page cursor");
+                    return Err(e.into());
+                }
+            },
+            None => None,
+        };
+
+        // The id is always loaded for the keyset cursor
+        let (email, status, created_at, locale, timezone, resubscribed_at) = masked_columns(mask);
+        let mut rows_query = filter_newsletters(
+            newsletters::table
+                .select((newsletters::id, email, status, created_at, locale, timezone, resubscribed_at))
+                .limit(page.limit + 1)
+                .into_boxed(),
+            &query.filter,
+        );
+
+        rows_query = match (query.order.field, query.order.descending) {
+            (SortField::CreatedAt, true) => rows_query.order((newsletters::created_at.desc(), newsletters::id.desc())),
+            (SortField::CreatedAt, false) => rows_query.order((newsletters::created_at.asc(), newsletters::id.asc())),
+            (SortField::Email, true) => rows_query.order((newsletters::email.desc(), newsletters::id.desc())),
+            (SortField::Email, false) => rows_query.order((newsletters::email.asc(), newsletters::id.asc())),
+        };
+
+        if let Some((id, email, created_at)) = cursor {
+            rows_query = match (query.order.field, query.order.descending) {
+                (SortField::CreatedAt, true) => rows_query.filter(
+                    newsletters::created_at.lt(created_at)
+                        .or(newsletters::created_at.eq(created_at).and(newsletters::id.lt(id))),
+                ),
+                (SortField::CreatedAt, false) => rows_query.filter(
+                    newsletters::created_at.gt(created_at)
+                        .or(newsletters::created_at.eq(created_at).and(newsletters::id.gt(id))),
+                ),
+                (SortField::Email, true) => rows_query.filter(
+                    newsletters::email.lt(email.clone())
+                        .or(newsletters::email.eq(email).and(newsletters::id.lt(id))),
+                ),
+                (SortField::Email, false) => rows_query.filter(
+                    newsletters::email.gt(email.clone())
+                        .or(newsletters::email.eq(email).and(newsletters::id.gt(id))),
+                ),
+            };
+        }
+
+        let mut rows: Vec<MaskedRow> = match conn.run(|conn| rows_query.load(conn).scope_boxed()).await {
+            Ok(rows) => rows,
             Err(e) => {
-                error!(entity = "newsletter_table", crud_operation = "CREATE", email = %email, error = %e, "Failed to add newsletter to database");
-                Err(e.into())
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to retrieve newsletters from database");
+                return Err(e.into());
             }
-        }
+        };
+
+        let has_more = rows.len() as i64 > page.limit;
+        rows.truncate(page.limit as usize);
+        let next_cursor = if has_more { rows.last().map(|r| r.0) } else { None };
+
+        info!(entity = "newsletter_table", crud_operation = "READ", rows_count = rows.len(), "Successfully retrieved newsletters from database");
+
+        Ok(Page {
+            items: rows.into_iter().map(partial(mask)).collect::<Result<_>>()?,
+            next_cursor,
+        })
     }
 
-    #[instrument(skip(self), fields(email = %email))]
-    async fn delete(&self, email: &str) -> Result<()> {
-        info!(entity = "newsletter_table", crud_operation = "DELETE", email = %email, "Starting database delete operation");
+    #[instrument(skip(self), fields(limit = page.limit, after = ?page.after))]
+    async fn search(&self, query: &SearchQuery, page: PageRequest) -> Result<Page<SearchHit>> {
+        info!(entity = "newsletter_table", crud_operation = "READ", limit = page.limit, after = ?page.after, "Starting database search operation");
 
-        let mut conn = match self.pool.get().await {
-            Ok(conn) => {
-                info!(entity = "newsletter_table", email = %email, "Successfully acquired database connection");
-                conn
+        let mut conn = match self.reads.tenant_connection().await {
+            Ok(conn) => Cancellable::new(conn),
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let offset = page.after.unwrap_or(0).max(0);
+        let search = diesel::sql_query(SEARCH_QUERY)
+            .bind::<Text, _>(query.as_str().to_string())
+            .bind::<Text, _>(format!("%{}%", escape_like(query.as_str())))
+            .bind::<BigInt, _>(page.limit + 1)
+            .bind::<BigInt, _>(offset);
+
+        let mut rows: Vec<SearchRow> = match conn.run(|conn| search.load(conn).scope_boxed()).await {
+            Ok(rows) => {
+                info!(entity = "newsletter_table", crud_operation = "READ", rows_count = rows.len(), "Successfully searched newsletters");
+                rows
             }
             Err(e) => {
-                error!(entity = "newsletter_table", crud_operation = "DELETE", email = %email, error = %e, "Failed to acquire database connection");
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to search newsletters");
                 return Err(e.into());
             }
         };
 
-        match diesel::delete(newsletters::table.filter(newsletters::email.eq(email)))
-            .execute(&mut conn)
+        let has_more = rows.len() as i64 > page.limit;
+        rows.truncate(page.limit as usize);
+        Ok(Page {
+            items: rows
+                .into_iter()
+                .map(|r| {
+                    Ok(SearchHit {
+                        newsletter: Newsletter { status: parse_status(&r.status)?, email: r.email },
+                        score: r.score,
+                    })
+                })
+                .collect::<Result<_>>()?,
+            next_cursor: has_more.then_some(offset + page.limit),
+        })
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email)))]
+    async fn get_masked(&self, email: &str, mask: NewsletterMask) -> Result<Option<PartialNewsletter>> {
+        let mut conn = match self.reads.tenant_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let (email_column, status, created_at, locale, timezone, resubscribed_at) = masked_columns(mask);
+        match newsletters::table
+            .filter(newsletters::email.eq(email.to_lowercase()))
+            .select((newsletters::id, email_column, status, created_at, locale, timezone, resubscribed_at))
+            .first::<MaskedRow>(&mut conn)
             .await
+            .optional()
         {
-            Ok(rows_affected) => {
-                info!(entity = "newsletter_table", crud_operation = "DELETE", email = %email, rows_affected = rows_affected, "Successfully deleted newsletter from database");
-                Ok(())
-            }
+            Ok(row) => row.map(partial(mask)).transpose(),
             Err(e) => {
-                error!(entity = "newsletter_table", crud_operation = "DELETE", email = %email, error = %e, "Failed to delete newsletter from database");
+                error!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to retrieve newsletter by email");
                 Err(e.into())
             }
         }
     }
 
-    #[instrument(skip(self), fields(email = %email))]
-    async fn get_by_email(&self, email: &str) -> Result<Option<Newsletter>> {
-        info!(entity = "newsletter_table", crud_operation = "READ", email = %email, "Starting database get_by_email operation");
+    #[instrument(skip(self), fields(email = %logging::email(&email)))]
+    async fn add(&self, email: &str) -> Result<bool> {
+        info!(entity = "newsletter_table", crud_operation = "CREATE", email = %logging::email(&email), "Starting database add operation");
 
-        let mut conn = match self.pool.get().await {
+        let mut conn = match tenant_connection(&self.pool).await {
             Ok(conn) => {
-                info!(entity = "newsletter_table", email = %email, "Successfully acquired database connection");
+                info!(entity = "newsletter_table", email = %logging::email(&email), "Successfully acquired database connection");
                 conn
             }
             Err(e) => {
-                error!(entity = "newsletter_table", crud_operation = "READ", email = %email, error = %e, "Failed to acquire database connection");
+                error!(entity = "newsletter_table", crud_operation = "CREATE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
                 return Err(e.into());
             }
         };
 
-        match newsletters::table
-            .filter(newsletters::email.eq(email))
-            .select(NewsletterRow::as_select())
-            .first(&mut conn)
-            .await
-            .optional()
-        {
-            Ok(row) => {
-                let found = row.is_some();
-                info!(entity = "newsletter_table", crud_operation = "READ", email = %email, found = found, "Successfully retrieved newsletter by email");
-                Ok(row.map(|r| Newsletter {
-                    email: r.email,
-                    active: r.active,
-                }))
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let inserted = diesel::insert_into(newsletters::table)
+                        .values(&NewNewsletter {
+                            email,
+                            status: SubscriptionStatus::Active.as_str(),
+                        })
+                        .on_conflict((newsletters::tenant_id, newsletters::email))
+                        .do_nothing()
+                        .execute(conn)
+                        .await?;
+                    if inserted > 0 {
+                        enqueue(conn, &[SubscriptionEvent::status_changed(email, SubscriptionStatus::Active)]).await?;
+                    }
+                    Ok(inserted)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(rows_affected) => {
+                info!(entity = "newsletter_table", crud_operation = "CREATE", email = %logging::email(&email), rows_affected = rows_affected, "Successfully added newsletter to database");
+                Ok(rows_affected > 0)
             }
             Err(e) => {
-                error!(entity = "newsletter_table", crud_operation = "READ", email = %email, error = %e, "Failed to retrieve newsletter by email");
+                error!(entity = "newsletter_table", crud_operation = "CREATE", email = %logging::email(&email), error = %e, "Failed to add newsletter to database");
                 Err(e.into())
             }
         }
     }
-}
 
-// Legacy functions - kept for backward compatibility if needed
-// The trait-based implementation above should be used instead
+    #[instrument(skip(self), fields(email = %logging::email(&email)))]
+    async fn delete(&self, email: &str) -> Result<bool> {
+        info!(entity = "newsletter_table", crud_operation = "DELETE", email = %logging::email(&email), "Starting database delete operation");
 
-#[allow(dead_code)]
-#[instrument(skip(pool))]
-pub async fn list(pool: &PgPool) -> Result<Vec<Newsletter>> {
-    let repository = PostgresNewsletterRepository::new(pool.clone());
-    repository.list().await
-}
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => {
+                info!(entity = "newsletter_table", email = %logging::email(&email), "Successfully acquired database connection");
+                conn
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "DELETE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let deleted = diesel::delete(newsletters::table.filter(newsletters::email.eq(email)))
+                        .execute(conn)
+                        .await?;
+                    if deleted > 0 {
+                        enqueue(conn, &[SubscriptionEvent::now(SubscriptionEventKind::Unsubscribed, email)]).await?;
+                    }
+                    Ok(deleted)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(rows_affected) => {
+                info!(entity = "newsletter_table", crud_operation = "DELETE", email = %logging::email(&email), rows_affected = rows_affected, "Successfully deleted newsletter from database");
+                Ok(rows_affected > 0)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "DELETE", email = %logging::email(&email), error = %e, "Failed to delete newsletter from database");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, feedback), fields(email = %logging::email(&email), reason = ?feedback.reason))]
+    async fn unsubscribe(&self, email: &str, feedback: &UnsubscribeFeedback) -> Result<bool> {
+        info!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), "Starting database unsubscribe operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let updated = diesel::update(
+                        newsletters::table
+                            .filter(newsletters::email.eq(email))
+                            .filter(newsletters::status.eq_any([
+                                SubscriptionStatus::Pending.as_str(),
+                                SubscriptionStatus::Active.as_str(),
+                            ])),
+                    )
+                    .set((
+                        newsletters::status.eq(SubscriptionStatus::Unsubscribed.as_str()),
+                        newsletters::status_changed_at.eq(diesel::dsl::now),
+                    ))
+                    .execute(conn)
+                    .await?;
+
+                    if updated == 0 {
+                        return Ok(false);
+                    }
+
+                    // A confirmation mail still in the inbox must not undo this
+                    diesel::delete(confirmation_tokens::table.filter(confirmation_tokens::email.eq(email)))
+                        .execute(conn)
+                        .await?;
+
+                    let audit_email = self.audit_email(email);
+                    diesel::insert_into(unsubscribe_events::table)
+                        .values(&NewUnsubscribeEvent {
+                            email: &audit_email,
+                            reason: feedback.reason.map(|r| r.as_str()),
+                            comment: feedback.comment.as_deref(),
+                        })
+                        .execute(conn)
+                        .await?;
+
+                    diesel::insert_into(consents::table)
+                        .values(&NewConsent::new(&audit_email, ConsentAction::Withdrawn, &ConsentContext::default()))
+                        .execute(conn)
+                        .await?;
+
+                    enqueue(conn, &[SubscriptionEvent::now(SubscriptionEventKind::Unsubscribed, email)]).await?;
+
+                    Ok(true)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(existed) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), existed = existed, "Successfully processed unsubscribe");
+                Ok(existed)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to unsubscribe newsletter");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(limit = page.limit, after = ?page.after))]
+    async fn list_unsubscribe_events(
+        &self,
+        filter: UnsubscribeEventFilter,
+        page: PageRequest,
+    ) -> Result<Page<UnsubscribeEvent>> {
+        info!(entity = "unsubscribe_events_table", crud_operation = "READ", limit = page.limit, after = ?page.after, "Starting database list_unsubscribe_events operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => Cancellable::new(conn),
+            Err(e) => {
+                error!(entity = "unsubscribe_events_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let mut query = filter_unsubscribe_events(unsubscribe_events::table.into_boxed(), filter)
+            .select(UnsubscribeEventRow::as_select())
+            .order(unsubscribe_events::id.desc())
+            .limit(page.limit + 1);
+
+        if let Some(after) = page.after {
+            query = query.filter(unsubscribe_events::id.lt(after));
+        }
+
+        let mut rows: Vec<UnsubscribeEventRow> = match conn.run(|conn| query.load(conn).scope_boxed()).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(entity = "unsubscribe_events_table", crud_operation = "READ", error = %e, "Failed to retrieve unsubscribe events from database");
+                return Err(e.into());
+            }
+        };
+
+        let has_more = rows.len() as i64 > page.limit;
+        rows.truncate(page.limit as usize);
+        let next_cursor = if has_more { rows.last().map(|r| r.id) } else { None };
+
+        info!(entity = "unsubscribe_events_table", crud_operation = "READ", rows_count = rows.len(), "Successfully retrieved unsubscribe events from database");
+
+        Ok(Page {
+            items: rows
+                .into_iter()
+                .map(UnsubscribeEvent::try_from)
+                .collect::<Result<_>>()?,
+            next_cursor,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn count_unsubscribe_reasons(&self, filter: UnsubscribeEventFilter) -> Result<Vec<ReasonCount>> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "unsubscribe_events_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        // GROUP BY has to come before boxing, so the filter is applied by hand here
+        let mut query = unsubscribe_events::table
+            .group_by(unsubscribe_events::reason)
+            .select((unsubscribe_events::reason, diesel::dsl::count_star()))
+            .into_boxed();
+
+        if let Some(reason) = filter.reason {
+            query = query.filter(unsubscribe_events::reason.eq(reason.as_str()));
+        }
+        if let Some(since) = filter.since {
+            query = query.filter(unsubscribe_events::created_at.ge(since));
+        }
+        if let Some(until) = filter.until {
+            query = query.filter(unsubscribe_events::created_at.lt(until));
+        }
+
+        let rows: Vec<(Option<String>, i64)> = match query.load(&mut conn).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(entity = "unsubscribe_events_table", crud_operation = "READ", error = %e, "Failed to count unsubscribe reasons");
+                return Err(e.into());
+            }
+        };
+
+        rows.into_iter()
+            .map(|(reason, count)| Ok(ReasonCount { reason: parse_reason(reason)?, count }))
+            .collect()
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email), limit = page.limit, after = ?page.after))]
+    async fn list_consents(&self, email: &str, page: PageRequest) -> Result<Page<ConsentRecord>> {
+        info!(entity = "consents_table", crud_operation = "READ", email = %logging::email(&email), limit = page.limit, after = ?page.after, "Starting database list_consents operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "consents_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let audit_email = self.audit_email(email);
+        let mut query = consents::table
+            .filter(lower(consents::email).eq(lower(email)).or(consents::email.eq(audit_email.as_ref())))
+            .select(ConsentRow::as_select())
+            .order(consents::id.desc())
+            .limit(page.limit + 1)
+            .into_boxed();
+
+        if let Some(after) = page.after {
+            query = query.filter(consents::id.lt(after));
+        }
+
+        let mut rows: Vec<ConsentRow> = match query.load(&mut conn).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(entity = "consents_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to retrieve consents from database");
+                return Err(e.into());
+            }
+        };
+
+        let has_more = rows.len() as i64 > page.limit;
+        rows.truncate(page.limit as usize);
+        let next_cursor = if has_more { rows.last().map(|r| r.id) } else { None };
+
+        info!(entity = "consents_table", crud_operation = "READ", email = %logging::email(&email), rows_count = rows.len(), "Successfully retrieved consents from database");
+
+        Ok(Page {
+            items: rows
+                .into_iter()
+                .map(|row| {
+                    ConsentRecord::try_from(row).map(|record| ConsentRecord {
+                        email: email.to_string(),
+                        ..record
+                    })
+                })
+                .collect::<Result<_>>()?,
+            next_cursor,
+        })
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email), until = %until))]
+    async fn list_subscriber_events(&self, email: &str, until: DateTime<Utc>) -> Result<Vec<SubscriptionEvent>> {
+        info!(entity = "subscriber_events_table", crud_operation = "READ", email = %logging::email(&email), until = %until, "Starting database list_subscriber_events operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "subscriber_events_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let payloads: Vec<serde_json::Value> = match subscriber_events::table
+            .filter(subscriber_events::email.eq(email).or(subscriber_events::previous_email.eq(email)))
+            .filter(subscriber_events::occurred_at.le(until))
+            .order((subscriber_events::occurred_at.asc(), subscriber_events::id.asc()))
+            .select(subscriber_events::payload)
+            .load(&mut conn)
+            .await
+        {
+            Ok(payloads) => payloads,
+            Err(e) => {
+                error!(entity = "subscriber_events_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to retrieve subscriber events from database");
+                return Err(e.into());
+            }
+        };
+
+        info!(entity = "subscriber_events_table", crud_operation = "READ", email = %logging::email(&email), rows_count = payloads.len(), "Successfully retrieved subscriber events from database");
+
+        payloads
+            .into_iter()
+            .map(|payload| serde_json::from_value(payload).map_err(NewsletterError::database))
+            .collect()
+    }
+
+    #[instrument(skip(self))]
+    async fn stats(&self) -> Result<SubscriberStats> {
+        info!(entity = "newsletter_table", crud_operation = "READ", "Starting database stats operation");
+
+        let mut conn = match self.reads.tenant_connection().await {
+            Ok(conn) => Cancellable::new(conn),
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        // Read only, so a retried or hedged GetStats cannot change anything
+        let result = conn
+            .run(|conn| {
+                db::read_only(conn, false, |conn| {
+                    async move {
+                        let by_status = newsletters::table
+                            .group_by(newsletters::status)
+                            .select((newsletters::status, diesel::dsl::count_star()))
+                            .load::<(String, i64)>(conn)
+                            .await?;
+                        let unsubscribed = unsubscribe_events::table
+                            .count()
+                            .get_result::<i64>(conn)
+                            .await?;
+                        Ok((by_status, unsubscribed))
+                    }
+                    .scope_boxed()
+                })
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok((by_status, unsubscribed)) => {
+                let mut stats = SubscriberStats {
+                    unsubscribed,
+                    ..SubscriberStats::default()
+                };
+                // Unsubscribed rows are left out; `unsubscribed` counts events
+                for (status, count) in by_status {
+                    match parse_status(&status)? {
+                        SubscriptionStatus::Active => stats.active = count,
+                        SubscriptionStatus::Pending => stats.inactive = count,
+                        SubscriptionStatus::Suppressed => stats.suppressed = count,
+                        SubscriptionStatus::Unsubscribed => {}
+                    }
+                }
+                info!(entity = "newsletter_table", crud_operation = "READ", active = stats.active, inactive = stats.inactive, suppressed = stats.suppressed, "Successfully counted subscriptions");
+                Ok(stats)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to count subscriptions");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn record_daily_metrics(&self, day: NaiveDate) -> Result<usize> {
+        info!(entity = "list_metrics_daily_table", crud_operation = "CREATE", "Starting database record_daily_metrics operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "list_metrics_daily_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let start = day.and_time(NaiveTime::MIN).and_utc();
+        match diesel::sql_query(RECORD_DAILY_METRICS_QUERY)
+            .bind::<Date, _>(day)
+            .bind::<Timestamptz, _>(start)
+            .bind::<Timestamptz, _>(start + Duration::days(1))
+            .execute(&mut conn)
+            .await
+        {
+            Ok(recorded) => {
+                info!(entity = "list_metrics_daily_table", crud_operation = "CREATE", day = %day, recorded = recorded, "Successfully recorded daily metrics");
+                Ok(recorded)
+            }
+            Err(e) => {
+                error!(entity = "list_metrics_daily_table", crud_operation = "CREATE", day = %day, error = %e, "Failed to record daily metrics");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list_daily_metrics(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyMetrics>> {
+        info!(entity = "list_metrics_daily_table", crud_operation = "READ", "Starting database list_daily_metrics operation");
+
+        let mut conn = match self.reads.tenant_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "list_metrics_daily_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match list_metrics_daily::table
+            .filter(list_metrics_daily::day.between(from, to))
+            .order(list_metrics_daily::day.asc())
+            .select(DailyMetricsRow::as_select())
+            .load::<DailyMetricsRow>(&mut conn)
+            .await
+        {
+            Ok(rows) => {
+                info!(entity = "list_metrics_daily_table", crud_operation = "READ", rows_count = rows.len(), "Successfully retrieved daily metrics");
+                Ok(rows.into_iter().map(DailyMetrics::from).collect())
+            }
+            Err(e) => {
+                error!(entity = "list_metrics_daily_table", crud_operation = "READ", error = %e, "Failed to retrieve daily metrics");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list_tenants(&self) -> Result<Vec<TenantId>> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let tenants: Vec<String> = match newsletters::table
+            .select(newsletters::tenant_id)
+            .distinct()
+            .order(newsletters::tenant_id)
+            .load(&mut conn)
+            .await
+        {
+            Ok(tenants) => tenants,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to list tenants");
+                return Err(e.into());
+            }
+        };
+
+        tenants
+            .into_iter()
+            .map(|tenant| {
+                TenantId::parse(&tenant)
+                    .map_err(|_| NewsletterError::database(format!("invalid tenant in database: {tenant}")))
+            })
+            .collect()
+    }
+
+    #[instrument(skip(self, emails), fields(count = emails.len()))]
+    async fn add_many(&self, emails: &[String]) -> Result<usize> {
+        info!(entity = "newsletter_table", crud_operation = "CREATE", count = emails.len(), "Starting database add_many operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let rows: Vec<NewNewsletter> = emails
+            .iter()
+            .map(|email| NewNewsletter {
+                email,
+                status: SubscriptionStatus::Active.as_str(),
+            })
+            .collect();
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let mut inserted = 0;
+                    for chunk in rows.chunks(INSERT_CHUNK_SIZE) {
+                        let added: Vec<String> = diesel::insert_into(newsletters::table)
+                            .values(chunk)
+                            .on_conflict((newsletters::tenant_id, newsletters::email))
+                            .do_nothing()
+                            .returning(newsletters::email)
+                            .get_results(conn)
+                            .await?;
+                        let events: Vec<SubscriptionEvent> = added
+                            .iter()
+                            .map(|email| SubscriptionEvent::status_changed(email.as_str(), SubscriptionStatus::Active))
+                            .collect();
+                        enqueue(conn, &events).await?;
+                        inserted += added.len();
+                    }
+                    Ok(inserted)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(rows_affected) => {
+                info!(entity = "newsletter_table", crud_operation = "CREATE", rows_affected = rows_affected, "Successfully added newsletters to database");
+                Ok(rows_affected)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "CREATE", error = %e, "Failed to add newsletters to database");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, emails), fields(count = emails.len(), status = %status))]
+    async fn set_status_many(&self, emails: &[String], status: SubscriptionStatus) -> Result<usize> {
+        info!(entity = "newsletter_table", crud_operation = "UPDATE", count = emails.len(), status = %status, "Starting database set_status_many operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, NewsletterError, _>(|conn| {
+                async move {
+                    // Locked, so the statuses checked are the ones replaced
+                    let current: Vec<(String, String)> = newsletters::table
+                        .filter(newsletters::email.eq_any(emails))
+                        .select((newsletters::email, newsletters::status))
+                        .for_update()
+                        .load(conn)
+                        .await?;
+
+                    let mut changed = Vec::new();
+                    for (email, from) in current {
+                        let from = parse_status(&from)?;
+                        from.transition(status)
+                            .map_err(|e| NewsletterError::InvalidTransition(format!("{email}: {e}")))?;
+                        if from != status {
+                            changed.push(email);
+                        }
+                    }
+
+                    diesel::update(newsletters::table.filter(newsletters::email.eq_any(&changed)))
+                        .set((
+                            newsletters::status.eq(status.as_str()),
+                            newsletters::status_changed_at.eq(diesel::dsl::now),
+                        ))
+                        .execute(conn)
+                        .await?;
+
+                    let events: Vec<SubscriptionEvent> = changed
+                        .iter()
+                        .map(|email| SubscriptionEvent::status_changed(email.as_str(), status))
+                        .collect();
+                    enqueue(conn, &events).await?;
+
+                    Ok(changed.len())
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(rows_affected) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", rows_affected = rows_affected, "Successfully updated newsletter status in database");
+                Ok(rows_affected)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to update newsletter status in database");
+                Err(e)
+            }
+        }
+    }
+
+    #[instrument(skip(self, emails), fields(count = emails.len()))]
+    async fn delete_many(&self, emails: &[String]) -> Result<usize> {
+        info!(entity = "newsletter_table", crud_operation = "DELETE", count = emails.len(), "Starting database delete_many operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "DELETE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let deleted: Vec<String> = diesel::delete(newsletters::table.filter(newsletters::email.eq_any(emails)))
+                        .returning(newsletters::email)
+                        .get_results(conn)
+                        .await?;
+
+                    let events: Vec<SubscriptionEvent> = deleted
+                        .iter()
+                        .map(|email| SubscriptionEvent::now(SubscriptionEventKind::Unsubscribed, email.as_str()))
+                        .collect();
+                    enqueue(conn, &events).await?;
+
+                    Ok(deleted.len())
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(rows_affected) => {
+                info!(entity = "newsletter_table", crud_operation = "DELETE", rows_affected = rows_affected, "Successfully deleted newsletters from database");
+                Ok(rows_affected)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "DELETE", error = %e, "Failed to delete newsletters from database");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, emails), fields(count = emails.len()))]
+    async fn get_many(&self, emails: &[String]) -> Result<Vec<Newsletter>> {
+        info!(entity = "newsletter_table", crud_operation = "READ", count = emails.len(), "Starting database get_many operation");
+
+        let mut conn = match self.reads.tenant_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match newsletters::table
+            .filter(newsletters::email.eq_any(emails))
+            .select(NewsletterRow::as_select())
+            .load(&mut conn)
+            .await
+        {
+            Ok(rows) => {
+                info!(entity = "newsletter_table", crud_operation = "READ", count = rows.len(), "Successfully retrieved newsletters by email");
+                rows.into_iter()
+                    .map(|r| {
+                        Ok(Newsletter {
+                            status: parse_status(&r.status)?,
+                            email: r.email,
+                        })
+                    })
+                    .collect()
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to retrieve newsletters by email");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email)))]
+    async fn get_by_email(&self, email: &str) -> Result<Option<Newsletter>> {
+        info!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), "Starting database get_by_email operation");
+
+        let mut conn = match self.reads.tenant_connection().await {
+            Ok(conn) => {
+                info!(entity = "newsletter_table", email = %logging::email(&email), "Successfully acquired database connection");
+                conn
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match newsletters::table
+            .filter(newsletters::email.eq(email.to_lowercase()))
+            .select(NewsletterRow::as_select())
+            .first(&mut conn)
+            .await
+            .optional()
+        {
+            Ok(row) => {
+                let found = row.is_some();
+                info!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), found = found, "Successfully retrieved newsletter by email");
+                row.map(|r| {
+                    Ok(Newsletter {
+                        status: parse_status(&r.status)?,
+                        email: r.email,
+                    })
+                })
+                .transpose()
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to retrieve newsletter by email");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, consent), fields(email = %logging::email(&email), token_id = %token_id))]
+    async fn add_pending(
+        &self,
+        email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+        consent: &ConsentContext,
+    ) -> Result<bool> {
+        info!(entity = "newsletter_table", crud_operation = "CREATE", email = %logging::email(&email), "Starting database add_pending operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "CREATE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let settled = diesel::select(exists(
+                        newsletters::table
+                            .filter(newsletters::email.eq(email))
+                            .filter(newsletters::status.eq_any([
+                                SubscriptionStatus::Active.as_str(),
+                                SubscriptionStatus::Suppressed.as_str(),
+                            ])),
+                    ))
+                    .get_result::<bool>(conn)
+                    .await?;
+                    if settled {
+                        return Ok(false);
+                    }
+
+                    diesel::insert_into(newsletters::table)
+                        .values(&NewNewsletter {
+                            email,
+                            status: SubscriptionStatus::Pending.as_str(),
+                        })
+                        .on_conflict((newsletters::tenant_id, newsletters::email))
+                        .do_nothing()
+                        .execute(conn)
+                        .await?;
+
+                    // Signing up again after unsubscribing starts over as pending
+                    let returning = diesel::update(
+                        newsletters::table
+                            .filter(newsletters::email.eq(email))
+                            .filter(newsletters::status.eq(SubscriptionStatus::Unsubscribed.as_str())),
+                    )
+                    .set((
+                        newsletters::status.eq(SubscriptionStatus::Pending.as_str()),
+                        newsletters::status_changed_at.eq(diesel::dsl::now),
+                        newsletters::resubscribed_at.eq(Some(Utc::now())),
+                    ))
+                    .execute(conn)
+                    .await?
+                        > 0;
+
+                    diesel::insert_into(confirmation_tokens::table)
+                        .values(&NewConfirmationToken {
+                            token_id,
+                            email,
+                            expires_at,
+                        })
+                        .execute(conn)
+                        .await?;
+
+                    diesel::insert_into(consents::table)
+                        .values(&NewConsent::new(&self.audit_email(email), ConsentAction::Given, consent))
+                        .execute(conn)
+                        .await?;
+
+                    let event = if returning {
+                        SubscriptionEvent::resubscribed(email, SubscriptionStatus::Pending)
+                    } else {
+                        SubscriptionEvent::now(SubscriptionEventKind::Subscribed, email)
+                    };
+                    enqueue(conn, &[event]).await?;
+
+                    Ok(true)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(stored) => {
+                info!(entity = "newsletter_table", crud_operation = "CREATE", email = %logging::email(&email), stored = stored, "Successfully added pending newsletter to database");
+                Ok(stored)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "CREATE", email = %logging::email(&email), error = %e, "Failed to add pending newsletter to database");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, consent), fields(email = %logging::email(&email)))]
+    async fn resubscribe(&self, email: &str, consent: &ConsentContext) -> Result<bool> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let updated = diesel::update(
+                        newsletters::table
+                            .filter(newsletters::email.eq(email))
+                            .filter(newsletters::status.eq(SubscriptionStatus::Unsubscribed.as_str())),
+                    )
+                    .set((
+                        newsletters::status.eq(SubscriptionStatus::Active.as_str()),
+                        newsletters::status_changed_at.eq(diesel::dsl::now),
+                        newsletters::resubscribed_at.eq(Some(Utc::now())),
+                    ))
+                    .execute(conn)
+                    .await?;
+
+                    if updated == 0 {
+                        return Ok(false);
+                    }
+
+                    diesel::insert_into(consents::table)
+                        .values(&NewConsent::new(&self.audit_email(email), ConsentAction::Given, consent))
+                        .execute(conn)
+                        .await?;
+
+                    enqueue(conn, &[SubscriptionEvent::resubscribed(email, SubscriptionStatus::Active)]).await?;
+
+                    Ok(true)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(resubscribed) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), resubscribed = resubscribed, "Reactivated unsubscribed newsletter");
+                Ok(resubscribed)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to reactivate unsubscribed newsletter");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, consent), fields(token_id = %token_id))]
+    async fn confirm(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<String>> {
+        info!(entity = "newsletter_table", crud_operation = "UPDATE", "Starting database confirm operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let email: Option<String> = diesel::delete(
+                        confirmation_tokens::table
+                            .filter(confirmation_tokens::token_id.eq(token_id))
+                            .filter(confirmation_tokens::expires_at.gt(now)),
+                    )
+                    .returning(confirmation_tokens::email)
+                    .get_result(conn)
+                    .await
+                    .optional()?;
+
+                    if let Some(email) = &email {
+                        diesel::update(
+                            newsletters::table
+                                .filter(newsletters::email.eq(email))
+                                .filter(newsletters::status.eq(SubscriptionStatus::Pending.as_str())),
+                        )
+                        .set((
+                            newsletters::status.eq(SubscriptionStatus::Active.as_str()),
+                            newsletters::status_changed_at.eq(diesel::dsl::now),
+                        ))
+                        .execute(conn)
+                        .await?;
+
+                        // Any other outstanding tokens for this address are now moot
+                        diesel::delete(confirmation_tokens::table.filter(confirmation_tokens::email.eq(email)))
+                            .execute(conn)
+                            .await?;
+
+                        diesel::insert_into(consents::table)
+                            .values(&NewConsent::new(&self.audit_email(email), ConsentAction::Confirmed, consent))
+                            .execute(conn)
+                            .await?;
+
+                        enqueue(conn, &[SubscriptionEvent::now(SubscriptionEventKind::Confirmed, email.as_str())]).await?;
+                    }
+
+                    Ok(email)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(email) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", confirmed = email.is_some(), "Successfully processed confirmation token");
+                Ok(email)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to confirm newsletter");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email), new_email = %logging::email(&new_email), token_id = %token_id))]
+    async fn request_email_change(
+        &self,
+        email: &str,
+        new_email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        info!(entity = "email_changes_table", crud_operation = "CREATE", email = %logging::email(&email), "Starting database request_email_change operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "email_changes_table", crud_operation = "CREATE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, NewsletterError, _>(|conn| {
+                async move {
+                    let active = diesel::select(exists(
+                        newsletters::table
+                            .filter(newsletters::email.eq(email))
+                            .filter(newsletters::status.eq(SubscriptionStatus::Active.as_str())),
+                    ))
+                    .get_result::<bool>(conn)
+                    .await?;
+                    if !active {
+                        return Ok(false);
+                    }
+                    if let Some(status) = taken_status(conn, new_email).await? {
+                        return Err(email_change::taken(new_email, status));
+                    }
+
+                    diesel::insert_into(email_changes::table)
+                        .values(&NewEmailChange {
+                            token_id,
+                            email,
+                            new_email,
+                            expires_at,
+                        })
+                        .execute(conn)
+                        .await?;
+                    Ok(true)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(stored) => {
+                info!(entity = "email_changes_table", crud_operation = "CREATE", email = %logging::email(&email), stored = stored, "Successfully recorded email change");
+                Ok(stored)
+            }
+            Err(e) => {
+                error!(entity = "email_changes_table", crud_operation = "CREATE", email = %logging::email(&email), error = %e, "Failed to record email change");
+                Err(e)
+            }
+        }
+    }
+
+    #[instrument(skip(self, consent), fields(token_id = %token_id))]
+    async fn change_email(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<EmailChange>> {
+        info!(entity = "newsletter_table", crud_operation = "UPDATE", "Starting database change_email operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, NewsletterError, _>(|conn| {
+                async move {
+                    let pending: Option<(String, String)> = diesel::delete(
+                        email_changes::table
+                            .filter(email_changes::token_id.eq(token_id))
+                            .filter(email_changes::expires_at.gt(now)),
+                    )
+                    .returning((email_changes::email, email_changes::new_email))
+                    .get_result(conn)
+                    .await
+                    .optional()?;
+                    let Some((from, to)) = pending else {
+                        return Ok(None);
+                    };
+                    if let Some(status) = taken_status(conn, &to).await? {
+                        return Err(email_change::taken(&to, status));
+                    }
+
+                    // Other changes still waiting are moot once the address is gone
+                    diesel::delete(email_changes::table.filter(email_changes::email.eq(&from)))
+                        .execute(conn)
+                        .await?;
+
+                    // Tokens, tags and topic choices follow through ON UPDATE CASCADE
+                    let moved = diesel::update(
+                        newsletters::table
+                            .filter(newsletters::email.eq(&from))
+                            .filter(newsletters::status.eq(SubscriptionStatus::Active.as_str())),
+                    )
+                    .set(newsletters::email.eq(&to))
+                    .execute(conn)
+                    .await?;
+                    if moved == 0 {
+                        return Ok(None);
+                    }
+
+                    let from_audit = self.audit_email(&from);
+                    let to_audit = self.audit_email(&to);
+                    diesel::update(
+                        consents::table
+                            .filter(lower(consents::email).eq(lower(&from)).or(consents::email.eq(from_audit.as_ref()))),
+                    )
+                    .set(consents::email.eq(to_audit.as_ref()))
+                    .execute(conn)
+                    .await?;
+                    diesel::update(
+                        unsubscribe_events::table.filter(unsubscribe_events::email.eq_any([from.as_str(), from_audit.as_ref()])),
+                    )
+                    .set(unsubscribe_events::email.eq(to_audit.as_ref()))
+                    .execute(conn)
+                    .await?;
+
+                    diesel::insert_into(consents::table)
+                        .values(&NewConsent::new(&to_audit, ConsentAction::Confirmed, consent))
+                        .execute(conn)
+                        .await?;
+
+                    let change = EmailChange { from, to };
+                    enqueue(conn, &[SubscriptionEvent::email_changed(&change)]).await?;
+                    Ok(Some(change))
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(change) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", changed = change.is_some(), "Successfully processed email change token");
+                Ok(change)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to change email");
+                Err(e)
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email), topic = %topic, token_id = %token_id))]
+    async fn request_topic_confirmation(
+        &self,
+        email: &str,
+        topic: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        info!(entity = "topic_confirmations_table", crud_operation = "CREATE", email = %logging::email(&email), "Starting database request_topic_confirmation operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "topic_confirmations_table", crud_operation = "CREATE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        // Inserts only while the subscription is active, in one statement
+        let result = diesel::insert_into(topic_confirmations::table)
+            .values(
+                newsletters::table
+                    .filter(newsletters::email.eq(email))
+                    .filter(newsletters::status.eq(SubscriptionStatus::Active.as_str()))
+                    .select((
+                        token_id.into_sql::<diesel::sql_types::Uuid>(),
+                        newsletters::email,
+                        topic.into_sql::<diesel::sql_types::Text>(),
+                        expires_at.into_sql::<diesel::sql_types::Timestamptz>(),
+                    )),
+            )
+            .into_columns((
+                topic_confirmations::token_id,
+                topic_confirmations::email,
+                topic_confirmations::topic,
+                topic_confirmations::expires_at,
+            ))
+            .execute(&mut conn)
+            .await;
+
+        match result {
+            Ok(stored) => {
+                info!(entity = "topic_confirmations_table", crud_operation = "CREATE", email = %logging::email(&email), stored = stored > 0, "Successfully recorded topic confirmation");
+                Ok(stored > 0)
+            }
+            Err(e) => {
+                error!(entity = "topic_confirmations_table", crud_operation = "CREATE", email = %logging::email(&email), error = %e, "Failed to record topic confirmation");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(token_id = %token_id))]
+    async fn confirm_topic(&self, token_id: Uuid, now: DateTime<Utc>) -> Result<Option<TopicConfirmation>> {
+        info!(entity = "subscriber_topics_table", crud_operation = "UPDATE", "Starting database confirm_topic operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "subscriber_topics_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let pending: Option<(String, String)> = diesel::delete(
+                        topic_confirmations::table
+                            .filter(topic_confirmations::token_id.eq(token_id))
+                            .filter(topic_confirmations::expires_at.gt(now)),
+                    )
+                    .returning((topic_confirmations::email, topic_confirmations::topic))
+                    .get_result(conn)
+                    .await
+                    .optional()?;
+                    let Some((email, topic)) = pending else {
+                        return Ok(None);
+                    };
+
+                    diesel::insert_into(subscriber_topics::table)
+                        .values(&NewSubscriberTopicRow {
+                            email: &email,
+                            topic: &topic,
+                            subscribed: true,
+                        })
+                        .on_conflict((subscriber_topics::tenant_id, subscriber_topics::email, subscriber_topics::topic))
+                        .do_update()
+                        .set((
+                            subscriber_topics::subscribed.eq(true),
+                            subscriber_topics::updated_at.eq(diesel::dsl::now),
+                        ))
+                        .execute(conn)
+                        .await?;
+                    Ok(Some(TopicConfirmation { email, topic }))
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(confirmed) => {
+                info!(entity = "subscriber_topics_table", crud_operation = "UPDATE", confirmed = confirmed.is_some(), "Successfully processed topic confirmation token");
+                Ok(confirmed)
+            }
+            Err(e) => {
+                error!(entity = "subscriber_topics_table", crud_operation = "UPDATE", error = %e, "Failed to confirm topic");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn purge_expired_pending(&self, expired_by: DateTime<Utc>) -> Result<PendingPurge> {
+        info!(entity = "newsletter_table", crud_operation = "DELETE", "Starting database purge_expired_pending operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "DELETE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    // Address changes and topic opt-ins nobody confirmed leave the
+                    // subscription as it is
+                    diesel::delete(email_changes::table.filter(email_changes::expires_at.le(expired_by)))
+                        .execute(conn)
+                        .await?;
+                    diesel::delete(topic_confirmations::table.filter(topic_confirmations::expires_at.le(expired_by)))
+                        .execute(conn)
+                        .await?;
+
+                    let emails: Vec<String> = diesel::delete(
+                        confirmation_tokens::table.filter(confirmation_tokens::expires_at.le(expired_by)),
+                    )
+                    .returning(confirmation_tokens::email)
+                    .get_results(conn)
+                    .await?;
+
+                    if emails.is_empty() {
+                        return Ok(PendingPurge::default());
+                    }
+
+                    let lapsed = newsletters::table
+                        .filter(newsletters::email.eq_any(&emails))
+                        .filter(newsletters::status.eq(SubscriptionStatus::Pending.as_str()))
+                        .filter(not(exists(
+                            confirmation_tokens::table.filter(confirmation_tokens::email.eq(newsletters::email)),
+                        )));
+
+                    // An unconfirmed resubscription keeps the history it had
+                    let reverted = diesel::update(lapsed.clone().filter(newsletters::resubscribed_at.is_not_null()))
+                        .set((
+                            newsletters::status.eq(SubscriptionStatus::Unsubscribed.as_str()),
+                            newsletters::status_changed_at.eq(diesel::dsl::now),
+                        ))
+                        .execute(conn)
+                        .await?;
+
+                    let deleted = diesel::delete(lapsed).execute(conn).await?;
+                    Ok(PendingPurge { deleted, reverted })
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(purge) => {
+                info!(entity = "newsletter_table", crud_operation = "DELETE", deleted = purge.deleted, reverted = purge.reverted, "Successfully purged expired pending newsletters");
+                Ok(purge)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "DELETE", error = %e, "Failed to purge expired pending newsletters");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(action = %action))]
+    async fn apply_retention(&self, unsubscribed_before: DateTime<Utc>, action: RetentionAction) -> Result<RetentionPurge> {
+        info!(entity = "newsletter_table", crud_operation = "UPDATE", action = %action, unsubscribed_before = %unsubscribed_before, "Starting database apply_retention operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let expired = newsletters::table
+                        .filter(newsletters::status.eq(SubscriptionStatus::Unsubscribed.as_str()))
+                        .filter(newsletters::status_changed_at.lt(unsubscribed_before))
+                        .select((newsletters::id, newsletters::email));
+                    let rows: Vec<(i64, String)> = match action {
+                        // Anonymized rows have nothing left to anonymize
+                        RetentionAction::Anonymize => {
+                            expired
+                                .filter(newsletters::anonymized_at.is_null())
+                                .for_update()
+                                .load(conn)
+                                .await?
+                        }
+                        RetentionAction::Delete => expired.for_update().load(conn).await?,
+                    };
+
+                    match action {
+                        RetentionAction::Anonymize => {
+                            for (id, email) in &rows {
+                                let anonymized = retention::anonymized_email(*id);
+                                let audit = self.audit_email(email);
+                                let anonymized_audit = self.audit_email(&anonymized);
+
+                                diesel::delete(subscriber_tags::table.filter(subscriber_tags::email.eq(email)))
+                                    .execute(conn)
+                                    .await?;
+                                diesel::delete(subscriber_topics::table.filter(subscriber_topics::email.eq(email)))
+                                    .execute(conn)
+                                    .await?;
+                                diesel::delete(confirmation_tokens::table.filter(confirmation_tokens::email.eq(email)))
+                                    .execute(conn)
+                                    .await?;
+                                diesel::delete(email_changes::table.filter(email_changes::email.eq(email)))
+                                    .execute(conn)
+                                    .await?;
+                                diesel::delete(topic_confirmations::table.filter(topic_confirmations::email.eq(email)))
+                                    .execute(conn)
+                                    .await?;
+                                diesel::update(newsletters::table.filter(newsletters::id.eq(*id)))
+                                    .set((
+                                        newsletters::email.eq(&anonymized),
+                                        newsletters::attributes.eq(serde_json::json!({})),
+                                        newsletters::locale.eq(None::<String>),
+                                        newsletters::timezone.eq(None::<String>),
+                                        newsletters::anonymized_at.eq(diesel::dsl::now),
+                                    ))
+                                    .execute(conn)
+                                    .await?;
+
+                                diesel::update(
+                                    consents::table
+                                        .filter(lower(consents::email).eq(lower(email)).or(consents::email.eq(audit.as_ref()))),
+                                )
+                                .set(consents::email.eq(anonymized_audit.as_ref()))
+                                .execute(conn)
+                                .await?;
+                                diesel::update(
+                                    unsubscribe_events::table
+                                        .filter(unsubscribe_events::email.eq_any([email.as_str(), audit.as_ref()])),
+                                )
+                                .set(unsubscribe_events::email.eq(anonymized_audit.as_ref()))
+                                .execute(conn)
+                                .await?;
+                                diesel::update(subscriber_merges::table.filter(subscriber_merges::email.eq(audit.as_ref())))
+                                    .set(subscriber_merges::email.eq(anonymized_audit.as_ref()))
+                                    .execute(conn)
+                                    .await?;
+                                diesel::update(
+                                    subscriber_merges::table.filter(subscriber_merges::merged_email.eq(audit.as_ref())),
+                                )
+                                .set(subscriber_merges::merged_email.eq(anonymized_audit.as_ref()))
+                                .execute(conn)
+                                .await?;
+
+                                // Campaign statistics keep counting the address
+                                diesel::update(engagement_events::table.filter(engagement_events::email.eq(email)))
+                                    .set(engagement_events::email.eq(&anonymized))
+                                    .execute(conn)
+                                    .await?;
+                                diesel::update(campaign_complaints::table.filter(campaign_complaints::email.eq(email)))
+                                    .set(campaign_complaints::email.eq(&anonymized))
+                                    .execute(conn)
+                                    .await?;
+                                diesel::update(campaign_deliveries::table.filter(campaign_deliveries::email.eq(email)))
+                                    .set(campaign_deliveries::email.eq(&anonymized))
+                                    .execute(conn)
+                                    .await?;
+                                diesel::sql_query(ANONYMIZE_HISTORY_QUERY)
+                                    .bind::<Text, _>(email)
+                                    .bind::<Text, _>(&anonymized)
+                                    .execute(conn)
+                                    .await?;
+                            }
+                            Ok(RetentionPurge {
+                                anonymized: rows.len(),
+                                deleted: 0,
+                            })
+                        }
+                        RetentionAction::Delete => {
+                            let ids: Vec<i64> = rows.iter().map(|(id, _)| *id).collect();
+                            let emails: Vec<String> = rows.into_iter().map(|(_, email)| email).collect();
+                            let lowered: Vec<String> = emails.iter().map(|email| email.to_lowercase()).collect();
+                            let audits: Vec<String> = emails.iter().map(|email| self.audit_email(email).into_owned()).collect();
+
+                            // Removes its tokens, address changes, tags and topic choices with it
+                            let deleted = diesel::delete(newsletters::table.filter(newsletters::id.eq_any(&ids)))
+                                .execute(conn)
+                                .await?;
+                            diesel::delete(
+                                consents::table
+                                    .filter(lower(consents::email).eq_any(&lowered).or(consents::email.eq_any(&audits))),
+                            )
+                            .execute(conn)
+                            .await?;
+                            diesel::delete(
+                                unsubscribe_events::table
+                                    .filter(unsubscribe_events::email.eq_any(&emails).or(unsubscribe_events::email.eq_any(&audits))),
+                            )
+                            .execute(conn)
+                            .await?;
+                            diesel::delete(
+                                subscriber_merges::table
+                                    .filter(subscriber_merges::email.eq_any(&audits).or(subscriber_merges::merged_email.eq_any(&audits))),
+                            )
+                            .execute(conn)
+                            .await?;
+                            diesel::delete(engagement_events::table.filter(engagement_events::email.eq_any(&emails)))
+                                .execute(conn)
+                                .await?;
+                            diesel::delete(campaign_complaints::table.filter(campaign_complaints::email.eq_any(&emails)))
+                                .execute(conn)
+                                .await?;
+                            diesel::delete(campaign_deliveries::table.filter(campaign_deliveries::email.eq_any(&emails)))
+                                .execute(conn)
+                                .await?;
+                            diesel::delete(subscriber_events::table.filter(subscriber_events::email.eq_any(&emails)))
+                                .execute(conn)
+                                .await?;
+                            diesel::sql_query(FORGET_PREVIOUS_EMAILS_QUERY)
+                                .bind::<Array<Text>, _>(&emails)
+                                .execute(conn)
+                                .await?;
+                            Ok(RetentionPurge { anonymized: 0, deleted })
+                        }
+                    }
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(purge) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", anonymized = purge.anonymized, deleted = purge.deleted, "Successfully applied retention to newsletters");
+                Ok(purge)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to apply retention to newsletters");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn merge_case_duplicates(&self) -> Result<usize> {
+        info!(entity = "newsletter_table", crud_operation = "UPDATE", "Starting database merge_case_duplicates operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, NewsletterError, _>(|conn| {
+                async move {
+                    let mixed: Vec<CaseVariantRow> = newsletters::table
+                        .filter(newsletters::email.ne(lower(newsletters::email)))
+                        .select(CaseVariantRow::as_select())
+                        .order(newsletters::id.asc())
+                        .load(conn)
+                        .await?;
+
+                    let mut groups: BTreeMap<String, Vec<CaseVariantRow>> = BTreeMap::new();
+                    for row in mixed {
+                        groups.entry(row.email.to_lowercase()).or_default().push(row);
+                    }
+
+                    let mut folded = 0;
+                    for (email, variants) in groups {
+                        let existing: Option<CaseVariantRow> = newsletters::table
+                            .filter(newsletters::email.eq(&email))
+                            .select(CaseVariantRow::as_select())
+                            .first(conn)
+                            .await
+                            .optional()?;
+                        let old: Vec<&str> = variants.iter().map(|row| row.email.as_str()).collect();
+                        let merged = existing
+                            .unwrap_or_else(|| variants[0].clone())
+                            .merge(variants.iter().cloned())?;
+
+                        diesel::insert_into(newsletters::table)
+                            .values((
+                                newsletters::email.eq(&email),
+                                newsletters::status.eq(&merged.status),
+                                newsletters::created_at.eq(merged.created_at),
+                                newsletters::attributes.eq(&merged.attributes),
+                                newsletters::locale.eq(&merged.locale),
+                                newsletters::timezone.eq(&merged.timezone),
+                                newsletters::resubscribed_at.eq(merged.resubscribed_at),
+                            ))
+                            .on_conflict((newsletters::tenant_id, newsletters::email))
+                            .do_update()
+                            .set((
+                                newsletters::status.eq(diesel::upsert::excluded(newsletters::status)),
+                                newsletters::created_at.eq(diesel::upsert::excluded(newsletters::created_at)),
+                                newsletters::attributes.eq(diesel::upsert::excluded(newsletters::attributes)),
+                                newsletters::locale.eq(diesel::upsert::excluded(newsletters::locale)),
+                                newsletters::timezone.eq(diesel::upsert::excluded(newsletters::timezone)),
+                                newsletters::resubscribed_at.eq(diesel::upsert::excluded(newsletters::resubscribed_at)),
+                            ))
+                            .execute(conn)
+                            .await?;
+
+                        diesel::update(confirmation_tokens::table.filter(confirmation_tokens::email.eq_any(&old)))
+                            .set(confirmation_tokens::email.eq(&email))
+                            .execute(conn)
+                            .await?;
+
+                        // Choices already made under the lowercase address win
+                        let tags: Vec<(String, DateTime<Utc>)> = subscriber_tags::table
+                            .filter(subscriber_tags::email.eq_any(&old))
+                            .select((subscriber_tags::tag, subscriber_tags::created_at))
+                            .load(conn)
+                            .await?;
+                        let tags: Vec<_> = tags
+                            .into_iter()
+                            .map(|(tag, created_at)| {
+                                (
+                                    subscriber_tags::email.eq(email.as_str()),
+                                    subscriber_tags::tag.eq(tag),
+                                    subscriber_tags::created_at.eq(created_at),
+                                )
+                            })
+                            .collect();
+                        diesel::insert_into(subscriber_tags::table)
+                            .values(&tags)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .await?;
+
+                        let choices: Vec<(String, bool, DateTime<Utc>)> = subscriber_topics::table
+                            .filter(subscriber_topics::email.eq_any(&old))
+                            .select((subscriber_topics::topic, subscriber_topics::subscribed, subscriber_topics::updated_at))
+                            .load(conn)
+                            .await?;
+                        let choices: Vec<_> = choices
+                            .into_iter()
+                            .map(|(topic, subscribed, updated_at)| {
+                                (
+                                    subscriber_topics::email.eq(email.as_str()),
+                                    subscriber_topics::topic.eq(topic),
+                                    subscriber_topics::subscribed.eq(subscribed),
+                                    subscriber_topics::updated_at.eq(updated_at),
+                                )
+                            })
+                            .collect();
+                        diesel::insert_into(subscriber_topics::table)
+                            .values(&choices)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .await?;
+
+                        // Removes the variants' own tags and topic choices with them
+                        folded += diesel::delete(newsletters::table.filter(newsletters::email.eq_any(&old)))
+                            .execute(conn)
+                            .await?;
+                        info!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), variants = old.len(), "Merged case variants of an address");
+                    }
+                    Ok(folded)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(rows_affected) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", rows_affected = rows_affected, "Successfully merged case duplicates");
+                Ok(rows_affected)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", error = %e, "Failed to merge case duplicates");
+                Err(e)
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email), merged_email = %logging::email(&merged_email)))]
+    async fn merge_subscribers(&self, email: &str, merged_email: &str) -> Result<Option<SubscriberMerge>> {
+        info!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), "Starting database merge_subscribers operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
 
-#[allow(dead_code)]
-#[instrument(skip(pool), fields(email = %email))]
-pub async fn add(pool: &PgPool, email: &str) -> Result<()> {
-    let repository = PostgresNewsletterRepository::new(pool.clone());
-    repository.add(email).await
-}
+        let result = conn
+            .transaction::<_, NewsletterError, _>(|conn| {
+                async move {
+                    // Locked, so the fields merged are the ones replaced
+                    let rows: Vec<CaseVariantRow> = newsletters::table
+                        .filter(newsletters::email.eq_any([email.to_lowercase(), merged_email.to_lowercase()]))
+                        .select(CaseVariantRow::as_select())
+                        .for_update()
+                        .load(conn)
+                        .await?;
+                    let find = |address: &str| rows.iter().find(|row| row.email == address.to_lowercase());
+                    let (Some(kept), Some(merged)) = (find(email), find(merged_email)) else {
+                        return Ok(None);
+                    };
+                    let fields = kept.fields()?.merge(merged.fields()?);
+
+                    diesel::update(newsletters::table.filter(newsletters::email.eq(&kept.email)))
+                        .set((
+                            newsletters::status.eq(fields.status.as_str()),
+                            newsletters::created_at.eq(fields.created_at),
+                            newsletters::attributes.eq(serde_json::Value::Object(fields.attributes)),
+                            newsletters::locale.eq(&fields.locale),
+                            newsletters::timezone.eq(&fields.timezone),
+                            newsletters::resubscribed_at.eq(fields.resubscribed_at),
+                        ))
+                        .execute(conn)
+                        .await?;
+                    if fields.status != parse_status(&kept.status)? {
+                        diesel::update(newsletters::table.filter(newsletters::email.eq(&kept.email)))
+                            .set(newsletters::status_changed_at.eq(diesel::dsl::now))
+                            .execute(conn)
+                            .await?;
+                    }
+
+                    // Tags and topic choices already made under the kept address win
+                    let tags: Vec<(String, DateTime<Utc>)> = subscriber_tags::table
+                        .filter(subscriber_tags::email.eq(&merged.email))
+                        .select((subscriber_tags::tag, subscriber_tags::created_at))
+                        .load(conn)
+                        .await?;
+                    let tags: Vec<_> = tags
+                        .into_iter()
+                        .map(|(tag, created_at)| {
+                            (
+                                subscriber_tags::email.eq(kept.email.as_str()),
+                                subscriber_tags::tag.eq(tag),
+                                subscriber_tags::created_at.eq(created_at),
+                            )
+                        })
+                        .collect();
+                    diesel::insert_into(subscriber_tags::table)
+                        .values(&tags)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+
+                    let choices: Vec<(String, bool, DateTime<Utc>)> = subscriber_topics::table
+                        .filter(subscriber_topics::email.eq(&merged.email))
+                        .select((subscriber_topics::topic, subscriber_topics::subscribed, subscriber_topics::updated_at))
+                        .load(conn)
+                        .await?;
+                    let choices: Vec<_> = choices
+                        .into_iter()
+                        .map(|(topic, subscribed, updated_at)| {
+                            (
+                                subscriber_topics::email.eq(kept.email.as_str()),
+                                subscriber_topics::topic.eq(topic),
+                                subscriber_topics::subscribed.eq(subscribed),
+                                subscriber_topics::updated_at.eq(updated_at),
+                            )
+                        })
+                        .collect();
+                    diesel::insert_into(subscriber_topics::table)
+                        .values(&choices)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+
+                    // Removes its tokens, address changes, tags and topic choices with it
+                    diesel::delete(newsletters::table.filter(newsletters::email.eq(&merged.email)))
+                        .execute(conn)
+                        .await?;
+
+                    let kept_audit = self.audit_email(&kept.email);
+                    let merged_audit = self.audit_email(&merged.email);
+                    diesel::update(
+                        consents::table.filter(
+                            lower(consents::email)
+                                .eq(lower(&merged.email))
+                                .or(consents::email.eq(merged_audit.as_ref())),
+                        ),
+                    )
+                    .set(consents::email.eq(kept_audit.as_ref()))
+                    .execute(conn)
+                    .await?;
+                    diesel::update(
+                        unsubscribe_events::table
+                            .filter(unsubscribe_events::email.eq_any([merged.email.as_str(), merged_audit.as_ref()])),
+                    )
+                    .set(unsubscribe_events::email.eq(kept_audit.as_ref()))
+                    .execute(conn)
+                    .await?;
+
+                    // Opens, clicks and complaints count towards the kept address
+                    diesel::update(engagement_events::table.filter(engagement_events::email.eq(&merged.email)))
+                        .set(engagement_events::email.eq(&kept.email))
+                        .execute(conn)
+                        .await?;
+                    diesel::sql_query(MOVE_COMPLAINTS_QUERY)
+                        .bind::<Text, _>(&kept.email)
+                        .bind::<Text, _>(&merged.email)
+                        .execute(conn)
+                        .await?;
+                    diesel::delete(campaign_complaints::table.filter(campaign_complaints::email.eq(&merged.email)))
+                        .execute(conn)
+                        .await?;
+
+                    let merge = SubscriberMerge {
+                        email: kept.email.clone(),
+                        merged_email: merged.email.clone(),
+                        merged_status: parse_status(&merged.status)?,
+                        status: fields.status,
+                        merged_at: Utc::now(),
+                    };
+                    diesel::insert_into(subscriber_merges::table)
+                        .values(&NewSubscriberMerge {
+                            email: &kept_audit,
+                            merged_email: &merged_audit,
+                            merged_status: merge.merged_status.as_str(),
+                            status: merge.status.as_str(),
+                            created_at: merge.merged_at,
+                        })
+                        .execute(conn)
+                        .await?;
+                    enqueue(conn, &[SubscriptionEvent::merged(&merge)]).await?;
+                    Ok(Some(merge))
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(merge) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), merged = merge.is_some(), "Successfully merged subscribers");
+                Ok(merge)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to merge subscribers");
+                Err(e)
+            }
+        }
+    }
+
+    #[instrument(skip(self, emails), fields(count = emails.len(), tag = %tag))]
+    async fn tag(&self, emails: &[String], tag: &str) -> Result<usize> {
+        info!(entity = "subscriber_tags_table", crud_operation = "CREATE", count = emails.len(), tag = %tag, "Starting database tag operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "subscriber_tags_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        // INSERT ... SELECT skips addresses that are not subscribed instead of
+        // tripping the foreign key.
+        let known = newsletters::table
+            .filter(newsletters::email.eq_any(emails))
+            .select((newsletters::email, tag.into_sql::<diesel::sql_types::Text>()));
+
+        match diesel::insert_into(subscriber_tags::table)
+            .values(known)
+            .into_columns((subscriber_tags::email, subscriber_tags::tag))
+            .on_conflict_do_nothing()
+            .execute(&mut conn)
+            .await
+        {
+            Ok(rows_affected) => {
+                info!(entity = "subscriber_tags_table", crud_operation = "CREATE", rows_affected = rows_affected, "Successfully tagged newsletters");
+                Ok(rows_affected)
+            }
+            Err(e) => {
+                error!(entity = "subscriber_tags_table", crud_operation = "CREATE", error = %e, "Failed to tag newsletters");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, emails), fields(count = emails.len(), tag = %tag))]
+    async fn untag(&self, emails: &[String], tag: &str) -> Result<usize> {
+        info!(entity = "subscriber_tags_table", crud_operation = "DELETE", count = emails.len(), tag = %tag, "Starting database untag operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "subscriber_tags_table", crud_operation = "DELETE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::delete(
+            subscriber_tags::table
+                .filter(subscriber_tags::tag.eq(tag))
+                .filter(subscriber_tags::email.eq_any(emails)),
+        )
+        .execute(&mut conn)
+        .await
+        {
+            Ok(rows_affected) => {
+                info!(entity = "subscriber_tags_table", crud_operation = "DELETE", rows_affected = rows_affected, "Successfully untagged newsletters");
+                Ok(rows_affected)
+            }
+            Err(e) => {
+                error!(entity = "subscriber_tags_table", crud_operation = "DELETE", error = %e, "Failed to untag newsletters");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(tag = %tag, limit = page.limit, after = ?page.after))]
+    async fn list_by_tag(&self, tag: &str, page: PageRequest) -> Result<Page<Newsletter>> {
+        info!(entity = "newsletter_table", crud_operation = "READ", tag = %tag, limit = page.limit, after = ?page.after, "Starting database list_by_tag operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => Cancellable::new(conn),
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let tagged = subscriber_tags::table
+            .filter(subscriber_tags::tag.eq(tag))
+            .select(subscriber_tags::email);
+
+        let mut query = newsletters::table
+            .filter(newsletters::email.eq_any(tagged))
+            .select(NewsletterRow::as_select())
+            .order(newsletters::id.desc())
+            .limit(page.limit + 1)
+            .into_boxed();
+
+        if let Some(after) = page.after {
+            query = query.filter(newsletters::id.lt(after));
+        }
+
+        match conn.run(|conn| query.load::<NewsletterRow>(conn).scope_boxed()).await {
+            Ok(rows) => {
+                info!(entity = "newsletter_table", crud_operation = "READ", rows_count = rows.len(), "Successfully retrieved tagged newsletters from database");
+                into_page(rows, page.limit)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", error = %e, "Failed to retrieve tagged newsletters from database");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list_topics(&self) -> Result<Vec<Topic>> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "topics_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match topics::table
+            .select(TopicRow::as_select())
+            .order(topics::key.asc())
+            .load::<TopicRow>(&mut conn)
+            .await
+        {
+            Ok(rows) => Ok(rows.into_iter().map(Topic::from).collect()),
+            Err(e) => {
+                error!(entity = "topics_table", crud_operation = "READ", error = %e, "Failed to retrieve topics");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn set_topic_requires_confirmation(&self, topic: &str, required: bool) -> Result<bool> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "topics_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::update(topics::table.filter(topics::key.eq(topic)))
+            .set(topics::requires_confirmation.eq(required))
+            .execute(&mut conn)
+            .await
+        {
+            Ok(updated) => {
+                info!(entity = "topics_table", crud_operation = "UPDATE", topic = %topic, requires_confirmation = required, found = updated > 0, "Successfully updated topic");
+                Ok(updated > 0)
+            }
+            Err(e) => {
+                error!(entity = "topics_table", crud_operation = "UPDATE", topic = %topic, error = %e, "Failed to update topic");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list_attribute_definitions(&self) -> Result<Vec<AttributeDefinition>> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "attribute_definitions_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match attribute_definitions::table
+            .select(AttributeDefinitionRow::as_select())
+            .order(attribute_definitions::key.asc())
+            .load(&mut conn)
+            .await
+        {
+            Ok(rows) => rows.into_iter().map(AttributeDefinition::try_from).collect(),
+            Err(e) => {
+                error!(entity = "attribute_definitions_table", crud_operation = "READ", error = %e, "Failed to retrieve attribute definitions");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, definition), fields(key = %definition.key, kind = %definition.kind))]
+    async fn define_attribute(&self, definition: &AttributeDefinition) -> Result<bool> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "attribute_definitions_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::insert_into(attribute_definitions::table)
+            .values(&NewAttributeDefinitionRow {
+                key: &definition.key,
+                kind: definition.kind.as_str(),
+                description: &definition.description,
+            })
+            .on_conflict(attribute_definitions::key)
+            .do_nothing()
+            .execute(&mut conn)
+            .await
+        {
+            Ok(inserted) => {
+                info!(entity = "attribute_definitions_table", crud_operation = "CREATE", key = %definition.key, inserted = inserted == 1, "Defined attribute");
+                Ok(inserted == 1)
+            }
+            Err(e) => {
+                error!(entity = "attribute_definitions_table", crud_operation = "CREATE", key = %definition.key, error = %e, "Failed to define attribute");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email)))]
+    async fn get_attributes(&self, email: &str) -> Result<Option<Attributes>> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match newsletters::table
+            .filter(newsletters::email.eq(email))
+            .select(newsletters::attributes)
+            .first::<serde_json::Value>(&mut conn)
+            .await
+            .optional()
+        {
+            Ok(attributes) => attributes.map(attributes_from_json).transpose(),
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to retrieve attributes");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, changes), fields(email = %logging::email(&email), count = changes.len()))]
+    async fn set_attributes(&self, email: &str, changes: &Attributes) -> Result<Option<Attributes>> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let (removed, set): (Vec<_>, Vec<_>) = changes.iter().partition(|(_, value)| value.is_null());
+        let removed: Vec<String> = removed.into_iter().map(|(key, _)| key.clone()).collect();
+        let set = serde_json::Value::Object(
+            set.into_iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        );
+
+        // A single UPDATE, so concurrent changes to other keys are not lost
+        match diesel::update(newsletters::table.filter(newsletters::email.eq(email)))
+            .set(newsletters::attributes.eq(newsletters::attributes.concat(set).remove(removed)))
+            .returning(newsletters::attributes)
+            .get_result::<serde_json::Value>(&mut conn)
+            .await
+            .optional()
+        {
+            Ok(attributes) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), found = attributes.is_some(), "Updated attributes");
+                attributes.map(attributes_from_json).transpose()
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to update attributes");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email)))]
+    async fn set_locale(&self, email: &str, locale: Option<&str>) -> Result<bool> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::update(newsletters::table.filter(newsletters::email.eq(email)))
+            .set(newsletters::locale.eq(locale))
+            .execute(&mut conn)
+            .await
+        {
+            Ok(rows_affected) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), found = rows_affected > 0, "Updated locale");
+                Ok(rows_affected > 0)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to update locale");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email)))]
+    async fn set_timezone(&self, email: &str, timezone: Option<&str>) -> Result<bool> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::update(newsletters::table.filter(newsletters::email.eq(email)))
+            .set(newsletters::timezone.eq(timezone))
+            .execute(&mut conn)
+            .await
+        {
+            Ok(rows_affected) => {
+                info!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), found = rows_affected > 0, "Updated timezone");
+                Ok(rows_affected > 0)
+            }
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to update timezone");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email)))]
+    async fn get_preferences(&self, email: &str) -> Result<Option<Vec<TopicSubscription>>> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "subscriber_topics_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = db::read_only(&mut conn, false, |conn| {
+            async move {
+                let subscribed = diesel::select(exists(newsletters::table.filter(newsletters::email.eq(email))))
+                    .get_result::<bool>(conn)
+                    .await?;
+                if !subscribed {
+                    return Ok(None);
+                }
+
+                let rows = topics::table
+                    .left_join(
+                        subscriber_topics::table.on(subscriber_topics::topic
+                            .eq(topics::key)
+                            .and(subscriber_topics::email.eq(email))),
+                    )
+                    .select((TopicRow::as_select(), subscriber_topics::subscribed.nullable()))
+                    .order(topics::key.asc())
+                    .load::<(TopicRow, Option<bool>)>(conn)
+                    .await?;
+                Ok(Some(rows))
+            }
+            .scope_boxed()
+        })
+        .await;
+
+        match result {
+            Ok(rows) => {
+                info!(entity = "subscriber_topics_table", crud_operation = "READ", email = %logging::email(&email), found = rows.is_some(), "Successfully retrieved topic preferences");
+                Ok(rows.map(|rows| {
+                    rows.into_iter()
+                        .map(|(topic, choice)| TopicSubscription::resolve(topic.into(), choice))
+                        .collect()
+                }))
+            }
+            Err(e) => {
+                error!(entity = "subscriber_topics_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to retrieve topic preferences");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, preferences), fields(email = %logging::email(&email), count = preferences.len()))]
+    async fn set_preferences(&self, email: &str, preferences: &[TopicPreference]) -> Result<bool> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "subscriber_topics_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let keys: Vec<&str> = preferences.iter().map(|p| p.topic.as_str()).collect();
+        let rows: Vec<NewSubscriberTopicRow> = preferences
+            .iter()
+            .map(|p| NewSubscriberTopicRow {
+                email,
+                topic: &p.topic,
+                subscribed: p.subscribed,
+            })
+            .collect();
+
+        // Checks run before any write, so an early return leaves nothing behind
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let subscribed = diesel::select(exists(newsletters::table.filter(newsletters::email.eq(email))))
+                        .get_result::<bool>(conn)
+                        .await?;
+                    if !subscribed {
+                        return Ok(Ok(false));
+                    }
+
+                    let known: Vec<String> = topics::table
+                        .filter(topics::key.eq_any(&keys))
+                        .select(topics::key)
+                        .load(conn)
+                        .await?;
+                    if let Some(unknown) = keys.iter().find(|key| !known.iter().any(|k| k == *key)) {
+                        return Ok(Err(PreferencesError::UnknownTopic(unknown.to_string())));
+                    }
+
+                    if !rows.is_empty() {
+                        diesel::insert_into(subscriber_topics::table)
+                            .values(&rows)
+                            .on_conflict((subscriber_topics::tenant_id, subscriber_topics::email, subscriber_topics::topic))
+                            .do_update()
+                            .set((
+                                subscriber_topics::subscribed.eq(diesel::upsert::excluded(subscriber_topics::subscribed)),
+                                subscriber_topics::updated_at.eq(diesel::dsl::now),
+                            ))
+                            .execute(conn)
+                            .await?;
+                    }
+                    Ok(Ok(true))
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(Ok(subscribed)) => {
+                info!(entity = "subscriber_topics_table", crud_operation = "UPDATE", email = %logging::email(&email), subscribed = subscribed, "Successfully stored topic preferences");
+                Ok(subscribed)
+            }
+            Ok(Err(e)) => {
+                info!(entity = "subscriber_topics_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Rejected topic preferences");
+                Err(e.into())
+            }
+            Err(e) => {
+                error!(entity = "subscriber_topics_table", crud_operation = "UPDATE", email = %logging::email(&email), error = %e, "Failed to store topic preferences");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(email = %logging::email(&email)))]
+    async fn export(&self, email: &str) -> Result<SubscriberExport> {
+        info!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), "Starting database export operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => Cancellable::new(conn),
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let audit_email = self.audit_email(email);
+
+        // One snapshot, so the parts of the export agree with each other
+        let result = conn
+            .run(|conn| {
+                async move {
+                    db::read_only(conn, true, |conn| {
+                        async move {
+                            let subscription = newsletters::table
+                                .filter(newsletters::email.eq(email))
+                                .select((
+                                    newsletters::status,
+                                    newsletters::created_at,
+                                    newsletters::attributes,
+                                    newsletters::locale,
+                                    newsletters::timezone,
+                                    newsletters::resubscribed_at,
+                                ))
+                                .first::<ExportRow>(conn)
+                                .await
+                                .optional()?;
 
-#[allow(dead_code)]
-#[instrument(skip(pool), fields(email = %email))]
-pub async fn delete(pool: &PgPool, email: &str) -> Result<()> {
-    let repository = PostgresNewsletterRepository::new(pool.clone());
-    repository.delete(email).await
+                            let tags = subscriber_tags::table
+                                .filter(subscriber_tags::email.eq(email))
+                                .select((subscriber_tags::tag, subscriber_tags::created_at))
+                                .order(subscriber_tags::tag.asc())
+                                .load::<(String, DateTime<Utc>)>(conn)
+                                .await?;
+
+                            let pending = confirmation_tokens::table
+                                .filter(confirmation_tokens::email.eq(email))
+                                .select((confirmation_tokens::created_at, confirmation_tokens::expires_at))
+                                .order(confirmation_tokens::created_at.asc())
+                                .load::<(DateTime<Utc>, DateTime<Utc>)>(conn)
+                                .await?;
+
+                            let unsubscribes = unsubscribe_events::table
+                                .filter(unsubscribe_events::email.eq_any([email, audit_email.as_ref()]))
+                                .select(UnsubscribeEventRow::as_select())
+                                .order(unsubscribe_events::id.asc())
+                                .load(conn)
+                                .await?;
+
+                            let merges = subscriber_merges::table
+                                .filter(
+                                    subscriber_merges::email
+                                        .eq(audit_email.as_ref())
+                                        .or(subscriber_merges::merged_email.eq(audit_email.as_ref())),
+                                )
+                                .select(SubscriberMergeRow::as_select())
+                                .order(subscriber_merges::id.asc())
+                                .load(conn)
+                                .await?;
+
+                            Ok((subscription, tags, pending, unsubscribes, merges))
+                        }
+                        .scope_boxed()
+                    })
+                    .await
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        let (subscription, tags, pending, unsubscribes, merges) = match result {
+            Ok(parts) => parts,
+            Err(e) => {
+                error!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), error = %e, "Failed to export subscriber data");
+                return Err(e.into());
+            }
+        };
+
+        info!(entity = "newsletter_table", crud_operation = "READ", email = %logging::email(&email), found = subscription.is_some(), "Successfully exported subscriber data");
+
+        Ok(SubscriberExport {
+            email: email.to_string(),
+            subscription: subscription
+                .map(|(status, created_at, attributes, locale, timezone, resubscribed_at)| -> Result<_> {
+                    Ok(SubscriptionRecord {
+                        status: parse_status(&status)?,
+                        created_at,
+                        attributes: attributes_from_json(attributes)?,
+                        locale,
+                        timezone,
+                        resubscribed_at,
+                    })
+                })
+                .transpose()?,
+            tags: tags
+                .into_iter()
+                .map(|(tag, created_at)| TagRecord { tag, created_at })
+                .collect(),
+            pending_confirmations: pending
+                .into_iter()
+                .map(|(created_at, expires_at)| PendingConfirmation { created_at, expires_at })
+                .collect(),
+            unsubscribes: unsubscribes
+                .into_iter()
+                .map(|row| {
+                    UnsubscribeEvent::try_from(row).map(|event| UnsubscribeEvent {
+                        email: email.to_string(),
+                        ..event
+                    })
+                })
+                .collect::<Result<_>>()?,
+            merges: merges.into_iter().map(SubscriberMerge::try_from).collect::<Result<_>>()?,
+        })
+    }
+
+    /// On the primary, whose connection the calls of `work`, the job queue's
+    /// included, share through [`db::unit_of_work`]
+    #[instrument(skip(self, work))]
+    async fn with_transaction<T, F>(&self, work: F) -> Result<T>
+    where
+        T: Send,
+        F: Future<Output = Result<T>> + Send,
+    {
+        let result = db::unit_of_work(&self.pool, work).await;
+        if let Err(e) = &result {
+            info!(entity = "newsletter_table", error = %e, "Rolled back unit of work");
+        }
+        result
+    }
 }
@@ -0,0 +1,288 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::domain::newsletter::attributes::{AttributeDefinition, Attributes};
+use crate::domain::newsletter::consent::{ConsentContext, ConsentRecord};
+use crate::domain::newsletter::email_change::EmailChange;
+use crate::domain::newsletter::error::{NewsletterError, Result};
+use crate::domain::newsletter::export::SubscriberExport;
+use crate::domain::newsletter::growth::DailyMetrics;
+use crate::domain::newsletter::lifecycle::{PendingPurge, SubscriptionStatus};
+use crate::domain::newsletter::mask::{NewsletterMask, PartialNewsletter};
+use crate::domain::newsletter::merge::SubscriberMerge;
+use crate::domain::newsletter::preferences::{Topic, TopicConfirmation, TopicPreference, TopicSubscription};
+use crate::domain::newsletter::query::NewsletterQuery;
+use crate::domain::newsletter::retention::{RetentionAction, RetentionPurge};
+use crate::domain::newsletter::search::{SearchHit, SearchQuery};
+use crate::domain::newsletter::stats::SubscriberStats;
+use crate::domain::newsletter::unsubscribe::{
+    ReasonCount, UnsubscribeEvent, UnsubscribeEventFilter, UnsubscribeFeedback,
+};
+use crate::domain::newsletter::{Newsletter, SubscriptionEvent};
+use crate::domain::pagination::{Page, PageRequest};
+use crate::domain::tenant::TenantId;
+use crate::infrastructure::db;
+use crate::repository::newsletter::NewsletterRepository;
+use crate::repository::retry::{Retrier, Transient};
+
+impl Transient for NewsletterError {
+    fn is_transient(&self) -> bool {
+        match self {
+            NewsletterError::Database(e) => db::is_transient(e.as_ref()),
+            _ => false,
+        }
+    }
+}
+
+/// Repository retrying transient database errors of the calls that are safe
+/// to repeat: every read, and the writes that set state rather than add to
+/// it. Inserts, deletes and token confirmation run once, since a retry after
+/// a lost commit would report a different outcome.
+pub struct RetryingNewsletterRepository<R> {
+    inner: R,
+    retrier: Arc<Retrier>,
+}
+
+impl<R: NewsletterRepository> RetryingNewsletterRepository<R> {
+    pub fn new(inner: R, retrier: Arc<Retrier>) -> Self {
+        Self { inner, retrier }
+    }
+}
+
+#[async_trait]
+impl<R: NewsletterRepository> NewsletterRepository for RetryingNewsletterRepository<R> {
+    async fn list(&self, page: PageRequest) -> Result<Page<Newsletter>> {
+        self.retrier.run("list", || self.inner.list(page)).await
+    }
+
+    async fn list_masked(
+        &self,
+        query: &NewsletterQuery,
+        page: PageRequest,
+        mask: NewsletterMask,
+    ) -> Result<Page<PartialNewsletter>> {
+        self.retrier.run("list_masked", || self.inner.list_masked(query, page, mask)).await
+    }
+
+    async fn get_masked(&self, email: &str, mask: NewsletterMask) -> Result<Option<PartialNewsletter>> {
+        self.retrier.run("get_masked", || self.inner.get_masked(email, mask)).await
+    }
+
+    async fn add(&self, email: &str) -> Result<bool> {
+        self.inner.add(email).await
+    }
+
+    async fn delete(&self, email: &str) -> Result<bool> {
+        self.inner.delete(email).await
+    }
+
+    async fn unsubscribe(&self, email: &str, feedback: &UnsubscribeFeedback) -> Result<bool> {
+        self.inner.unsubscribe(email, feedback).await
+    }
+
+    async fn list_unsubscribe_events(
+        &self,
+        filter: UnsubscribeEventFilter,
+        page: PageRequest,
+    ) -> Result<Page<UnsubscribeEvent>> {
+        self.retrier
+            .run("list_unsubscribe_events", || self.inner.list_unsubscribe_events(filter, page))
+            .await
+    }
+
+    async fn count_unsubscribe_reasons(&self, filter: UnsubscribeEventFilter) -> Result<Vec<ReasonCount>> {
+        self.retrier
+            .run("count_unsubscribe_reasons", || self.inner.count_unsubscribe_reasons(filter))
+            .await
+    }
+
+    async fn list_consents(&self, email: &str, page: PageRequest) -> Result<Page<ConsentRecord>> {
+        self.retrier.run("list_consents", || self.inner.list_consents(email, page)).await
+    }
+
+    async fn list_subscriber_events(&self, email: &str, until: DateTime<Utc>) -> Result<Vec<SubscriptionEvent>> {
+        self.retrier
+            .run("list_subscriber_events", || self.inner.list_subscriber_events(email, until))
+            .await
+    }
+
+    async fn stats(&self) -> Result<SubscriberStats> {
+        self.retrier.run("stats", || self.inner.stats()).await
+    }
+
+    async fn record_daily_metrics(&self, day: NaiveDate) -> Result<usize> {
+        self.inner.record_daily_metrics(day).await
+    }
+
+    async fn list_daily_metrics(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyMetrics>> {
+        self.retrier.run("list_daily_metrics", || self.inner.list_daily_metrics(from, to)).await
+    }
+
+    async fn search(&self, query: &SearchQuery, page: PageRequest) -> Result<Page<SearchHit>> {
+        self.retrier.run("search", || self.inner.search(query, page)).await
+    }
+
+    async fn list_tenants(&self) -> Result<Vec<TenantId>> {
+        self.retrier.run("list_tenants", || self.inner.list_tenants()).await
+    }
+
+    async fn add_many(&self, emails: &[String]) -> Result<usize> {
+        self.inner.add_many(emails).await
+    }
+
+    async fn set_status_many(&self, emails: &[String], status: SubscriptionStatus) -> Result<usize> {
+        self.retrier.run("set_status_many", || self.inner.set_status_many(emails, status)).await
+    }
+
+    async fn delete_many(&self, emails: &[String]) -> Result<usize> {
+        self.inner.delete_many(emails).await
+    }
+
+    async fn get_many(&self, emails: &[String]) -> Result<Vec<Newsletter>> {
+        self.retrier.run("get_many", || self.inner.get_many(emails)).await
+    }
+
+    async fn get_by_email(&self, email: &str) -> Result<Option<Newsletter>> {
+        self.retrier.run("get_by_email", || self.inner.get_by_email(email)).await
+    }
+
+    async fn add_pending(
+        &self,
+        email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+        consent: &ConsentContext,
+    ) -> Result<bool> {
+        self.inner.add_pending(email, token_id, expires_at, consent).await
+    }
+
+    async fn resubscribe(&self, email: &str, consent: &ConsentContext) -> Result<bool> {
+        self.inner.resubscribe(email, consent).await
+    }
+
+    async fn confirm(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<String>> {
+        self.inner.confirm(token_id, now, consent).await
+    }
+
+    async fn request_email_change(
+        &self,
+        email: &str,
+        new_email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        self.inner.request_email_change(email, new_email, token_id, expires_at).await
+    }
+
+    async fn change_email(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<EmailChange>> {
+        self.inner.change_email(token_id, now, consent).await
+    }
+
+    async fn request_topic_confirmation(
+        &self,
+        email: &str,
+        topic: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        self.inner.request_topic_confirmation(email, topic, token_id, expires_at).await
+    }
+
+    async fn confirm_topic(&self, token_id: Uuid, now: DateTime<Utc>) -> Result<Option<TopicConfirmation>> {
+        self.inner.confirm_topic(token_id, now).await
+    }
+
+    async fn purge_expired_pending(&self, expired_by: DateTime<Utc>) -> Result<PendingPurge> {
+        self.inner.purge_expired_pending(expired_by).await
+    }
+
+    async fn apply_retention(&self, unsubscribed_before: DateTime<Utc>, action: RetentionAction) -> Result<RetentionPurge> {
+        self.inner.apply_retention(unsubscribed_before, action).await
+    }
+
+    async fn merge_case_duplicates(&self) -> Result<usize> {
+        self.inner.merge_case_duplicates().await
+    }
+
+    async fn merge_subscribers(&self, email: &str, merged_email: &str) -> Result<Option<SubscriberMerge>> {
+        self.inner.merge_subscribers(email, merged_email).await
+    }
+
+    async fn tag(&self, emails: &[String], tag: &str) -> Result<usize> {
+        self.inner.tag(emails, tag).await
+    }
+
+    async fn untag(&self, emails: &[String], tag: &str) -> Result<usize> {
+        self.inner.untag(emails, tag).await
+    }
+
+    async fn list_by_tag(&self, tag: &str, page: PageRequest) -> Result<Page<Newsletter>> {
+        self.retrier.run("list_by_tag", || self.inner.list_by_tag(tag, page)).await
+    }
+
+    async fn get_preferences(&self, email: &str) -> Result<Option<Vec<TopicSubscription>>> {
+        self.retrier.run("get_preferences", || self.inner.get_preferences(email)).await
+    }
+
+    async fn set_preferences(&self, email: &str, preferences: &[TopicPreference]) -> Result<bool> {
+        self.retrier
+            .run("set_preferences", || self.inner.set_preferences(email, preferences))
+            .await
+    }
+
+    async fn list_topics(&self) -> Result<Vec<Topic>> {
+        self.retrier.run("list_topics", || self.inner.list_topics()).await
+    }
+
+    async fn set_topic_requires_confirmation(&self, topic: &str, required: bool) -> Result<bool> {
+        self.retrier
+            .run("set_topic_requires_confirmation", || {
+                self.inner.set_topic_requires_confirmation(topic, required)
+            })
+            .await
+    }
+
+    async fn list_attribute_definitions(&self) -> Result<Vec<AttributeDefinition>> {
+        self.retrier
+            .run("list_attribute_definitions", || self.inner.list_attribute_definitions())
+            .await
+    }
+
+    async fn define_attribute(&self, definition: &AttributeDefinition) -> Result<bool> {
+        self.inner.define_attribute(definition).await
+    }
+
+    async fn get_attributes(&self, email: &str) -> Result<Option<Attributes>> {
+        self.retrier.run("get_attributes", || self.inner.get_attributes(email)).await
+    }
+
+    async fn set_attributes(&self, email: &str, changes: &Attributes) -> Result<Option<Attributes>> {
+        self.inner.set_attributes(email, changes).await
+    }
+
+    async fn set_locale(&self, email: &str, locale: Option<&str>) -> Result<bool> {
+        self.retrier.run("set_locale", || self.inner.set_locale(email, locale)).await
+    }
+
+    async fn set_timezone(&self, email: &str, timezone: Option<&str>) -> Result<bool> {
+        self.retrier.run("set_timezone", || self.inner.set_timezone(email, timezone)).await
+    }
+
+    async fn export(&self, email: &str) -> Result<SubscriberExport> {
+        self.retrier.run("export", || self.inner.export(email)).await
+    }
+
+    /// Not retried: `work` can only run once, and the calls it makes are
+    /// not retried either
+    async fn with_transaction<T, F>(&self, work: F) -> Result<T>
+    where
+        T: Send,
+        F: Future<Output = Result<T>> + Send,
+    {
+        self.inner.with_transaction(work).await
+    }
+}
@@ -0,0 +1,2391 @@
+use crate::domain::idempotency::IdempotencyRecord;
+use crate::domain::newsletter::attributes::{AttributeDefinition, AttributeType, Attributes};
+use crate::domain::newsletter::consent::{ConsentAction, ConsentContext, ConsentRecord};
+use crate::domain::newsletter::email_change::{self, EmailChange};
+use crate::domain::newsletter::error::{NewsletterError, Result};
+use crate::domain::newsletter::export::{
+    PendingConfirmation, SubscriberExport, SubscriptionRecord, TagRecord,
+};
+use crate::domain::newsletter::growth::DailyMetrics;
+use crate::domain::newsletter::lifecycle::{PendingPurge, SubscriptionStatus};
+use crate::domain::newsletter::mask::{NewsletterMask, PartialNewsletter};
+use crate::domain::newsletter::merge::{MergedFields, SubscriberMerge};
+use crate::domain::newsletter::preferences::{
+    PreferencesError, Topic, TopicConfirmation, TopicPreference, TopicSubscription,
+};
+use crate::domain::newsletter::query::{NewsletterFilter, NewsletterQuery, SortField};
+use crate::domain::newsletter::retention::{self, RetentionAction, RetentionPurge};
+use crate::domain::newsletter::search::{Rank, SearchHit, SearchQuery};
+use crate::domain::newsletter::stats::SubscriberStats;
+use crate::domain::newsletter::unsubscribe::{
+    ReasonCount, UnsubscribeEvent, UnsubscribeEventFilter, UnsubscribeFeedback,
+};
+use crate::domain::newsletter::{Newsletter, SubscriptionEvent, SubscriptionEventKind};
+use crate::domain::outbox::{OutboxBacklog, OutboxMessage};
+use crate::domain::pagination::{Page, PageRequest, StaleCursor};
+use crate::domain::tenant::{TenantId, TenantScope};
+use crate::infrastructure::db::sqlite;
+use crate::infrastructure::pseudonym::Pseudonymizer;
+use crate::infrastructure::tenant;
+use crate::repository::idempotency::{Claim, IdempotencyRepository};
+use crate::repository::newsletter::postgres::{attributes_from_json, parse_reason, parse_status};
+use crate::repository::newsletter::NewsletterRepository;
+use crate::repository::outbox::OutboxRepository;
+
+use std::borrow::Cow;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use diesel::connection::{AnsiTransactionManager, TransactionManager};
+use diesel::prelude::*;
+use diesel::query_builder::{BoxedSqlQuery, SqlQuery};
+use diesel::sql_types::{BigInt, Bool, Integer, Nullable, Text};
+use diesel::sqlite::{Sqlite, SqliteConnection};
+use serde_json::Value;
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// Timestamps are stored as UTC text of a fixed width, so comparing and
+/// sorting the text agrees with comparing the times
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.6fZ";
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.format(TIMESTAMP_FORMAT).to_string()
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, TIMESTAMP_FORMAT)
+        .map(|at| at.and_utc())
+        .map_err(|e| NewsletterError::database(format!("invalid timestamp in database: {value}: {e}")))
+}
+
+fn parse_optional_timestamp(value: Option<&str>) -> Result<Option<DateTime<Utc>>> {
+    value.map(parse_timestamp).transpose()
+}
+
+fn parse_attributes(value: &str) -> Result<Attributes> {
+    attributes_from_json(serde_json::from_str(value).map_err(NewsletterError::database)?)
+}
+
+const NEWSLETTER_COLUMNS: &str = "id, email, status, created_at, attributes, locale, timezone, resubscribed_at";
+
+#[derive(Debug, QueryableByName)]
+struct NewsletterRow {
+    #[diesel(sql_type = BigInt)]
+    id: i64,
+    #[diesel(sql_type = Text)]
+    email: String,
+    #[diesel(sql_type = Text)]
+    status: String,
+    #[diesel(sql_type = Text)]
+    created_at: String,
+    #[diesel(sql_type = Text)]
+    attributes: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    locale: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    timezone: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    resubscribed_at: Option<String>,
+}
+
+impl NewsletterRow {
+    fn newsletter(self) -> Result<Newsletter> {
+        Ok(Newsletter {
+            status: parse_status(&self.status)?,
+            email: self.email,
+        })
+    }
+
+    fn partial(self, mask: NewsletterMask) -> Result<PartialNewsletter> {
+        let status = parse_status(&self.status)?;
+        Ok(PartialNewsletter {
+            active: mask.active.then_some(status.is_active()),
+            status: mask.status.then_some(status),
+            created_at: if mask.created_at { Some(parse_timestamp(&self.created_at)?) } else { None },
+            resubscribed_at: if mask.resubscribed_at {
+                parse_optional_timestamp(self.resubscribed_at.as_deref())?
+            } else {
+                None
+            },
+            email: mask.email.then_some(self.email),
+            locale: self.locale.filter(|_| mask.locale),
+            timezone: self.timezone.filter(|_| mask.timezone),
+        })
+    }
+
+    fn fields(&self) -> Result<MergedFields> {
+        Ok(MergedFields {
+            status: parse_status(&self.status)?,
+            created_at: parse_timestamp(&self.created_at)?,
+            attributes: parse_attributes(&self.attributes)?,
+            locale: self.locale.clone(),
+            timezone: self.timezone.clone(),
+            resubscribed_at: parse_optional_timestamp(self.resubscribed_at.as_deref())?,
+        })
+    }
+
+    fn record(self) -> Result<SubscriptionRecord> {
+        Ok(SubscriptionRecord {
+            status: parse_status(&self.status)?,
+            created_at: parse_timestamp(&self.created_at)?,
+            attributes: parse_attributes(&self.attributes)?,
+            resubscribed_at: parse_optional_timestamp(self.resubscribed_at.as_deref())?,
+            locale: self.locale,
+            timezone: self.timezone,
+        })
+    }
+}
+
+#[derive(Debug, QueryableByName)]
+struct CursorRow {
+    #[diesel(sql_type = Text)]
+    email: String,
+    #[diesel(sql_type = Text)]
+    created_at: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct StatusRow {
+    #[diesel(sql_type = Text)]
+    email: String,
+    #[diesel(sql_type = Text)]
+    status: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct EmailRow {
+    #[diesel(sql_type = Text)]
+    email: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct PayloadRow {
+    #[diesel(sql_type = Text)]
+    payload: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct IdEmailRow {
+    #[diesel(sql_type = BigInt)]
+    id: i64,
+    #[diesel(sql_type = Text)]
+    email: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct SubscriberMergeRow {
+    #[diesel(sql_type = Text)]
+    email: String,
+    #[diesel(sql_type = Text)]
+    merged_email: String,
+    #[diesel(sql_type = Text)]
+    merged_status: String,
+    #[diesel(sql_type = Text)]
+    status: String,
+    #[diesel(sql_type = Text)]
+    created_at: String,
+}
+
+impl TryFrom<SubscriberMergeRow> for SubscriberMerge {
+    type Error = NewsletterError;
+
+    fn try_from(row: SubscriberMergeRow) -> Result<Self> {
+        Ok(SubscriberMerge {
+            merged_status: parse_status(&row.merged_status)?,
+            status: parse_status(&row.status)?,
+            merged_at: parse_timestamp(&row.created_at)?,
+            email: row.email,
+            merged_email: row.merged_email,
+        })
+    }
+}
+
+#[derive(Debug, QueryableByName)]
+struct EmailChangeRow {
+    #[diesel(sql_type = Text)]
+    email: String,
+    #[diesel(sql_type = Text)]
+    new_email: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct TopicConfirmationRow {
+    #[diesel(sql_type = Text)]
+    email: String,
+    #[diesel(sql_type = Text)]
+    topic: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct TenantEmailRow {
+    #[diesel(sql_type = Text)]
+    tenant_id: String,
+    #[diesel(sql_type = Text)]
+    email: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct TenantRow {
+    #[diesel(sql_type = Text)]
+    tenant_id: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct CountRow {
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+#[derive(Debug, QueryableByName)]
+struct StatusCountRow {
+    #[diesel(sql_type = Text)]
+    status: String,
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+#[derive(Debug, QueryableByName)]
+struct ReasonCountRow {
+    #[diesel(sql_type = Nullable<Text>)]
+    reason: Option<String>,
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+#[derive(Debug, QueryableByName)]
+struct UnsubscribeEventRow {
+    #[diesel(sql_type = BigInt)]
+    id: i64,
+    #[diesel(sql_type = Text)]
+    email: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    reason: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    comment: Option<String>,
+    #[diesel(sql_type = Text)]
+    created_at: String,
+}
+
+impl TryFrom<UnsubscribeEventRow> for UnsubscribeEvent {
+    type Error = NewsletterError;
+
+    fn try_from(row: UnsubscribeEventRow) -> Result<Self> {
+        Ok(UnsubscribeEvent {
+            id: row.id,
+            email: row.email,
+            reason: parse_reason(row.reason)?,
+            comment: row.comment,
+            created_at: parse_timestamp(&row.created_at)?,
+        })
+    }
+}
+
+#[derive(Debug, QueryableByName)]
+struct ConsentRow {
+    #[diesel(sql_type = BigInt)]
+    id: i64,
+    #[diesel(sql_type = Text)]
+    email: String,
+    #[diesel(sql_type = Text)]
+    action: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    policy_version: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    source: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    ip_address: Option<String>,
+    #[diesel(sql_type = Text)]
+    created_at: String,
+}
+
+impl TryFrom<ConsentRow> for ConsentRecord {
+    type Error = NewsletterError;
+
+    fn try_from(row: ConsentRow) -> Result<Self> {
+        let action = ConsentAction::parse(&row.action)
+            .ok_or_else(|| NewsletterError::database(format!("unknown consent action in database: {}", row.action)))?;
+
+        Ok(ConsentRecord {
+            id: row.id,
+            email: row.email,
+            action,
+            policy_version: row.policy_version,
+            source: row.source,
+            ip_address: row.ip_address,
+            created_at: parse_timestamp(&row.created_at)?,
+        })
+    }
+}
+
+#[derive(Debug, QueryableByName)]
+struct DailyMetricsRow {
+    #[diesel(sql_type = Text)]
+    day: String,
+    #[diesel(sql_type = BigInt)]
+    subscribers: i64,
+    #[diesel(sql_type = BigInt)]
+    confirms: i64,
+    #[diesel(sql_type = BigInt)]
+    unsubscribes: i64,
+    #[diesel(sql_type = BigInt)]
+    net_growth: i64,
+}
+
+impl TryFrom<DailyMetricsRow> for DailyMetrics {
+    type Error = NewsletterError;
+
+    fn try_from(row: DailyMetricsRow) -> Result<Self> {
+        let day = NaiveDate::parse_from_str(&row.day, DATE_FORMAT)
+            .map_err(|e| NewsletterError::database(format!("invalid day in database: {}: {e}", row.day)))?;
+
+        Ok(DailyMetrics {
+            day,
+            subscribers: row.subscribers,
+            confirms: row.confirms,
+            unsubscribes: row.unsubscribes,
+            net_growth: row.net_growth,
+        })
+    }
+}
+
+#[derive(Debug, QueryableByName)]
+struct TopicRow {
+    #[diesel(sql_type = Text)]
+    key: String,
+    #[diesel(sql_type = Text)]
+    name: String,
+    #[diesel(sql_type = Text)]
+    description: String,
+    #[diesel(sql_type = Bool)]
+    default_subscribed: bool,
+    #[diesel(sql_type = Bool)]
+    requires_confirmation: bool,
+}
+
+impl From<TopicRow> for Topic {
+    fn from(row: TopicRow) -> Self {
+        Topic {
+            key: row.key,
+            name: row.name,
+            description: row.description,
+            default_subscribed: row.default_subscribed,
+            requires_confirmation: row.requires_confirmation,
+        }
+    }
+}
+
+/// A topic with the subscriber's explicit choice, if any
+#[derive(Debug, QueryableByName)]
+struct TopicChoiceRow {
+    #[diesel(embed)]
+    topic: TopicRow,
+    #[diesel(sql_type = Nullable<Bool>)]
+    subscribed: Option<bool>,
+}
+
+#[derive(Debug, QueryableByName)]
+struct AttributeDefinitionRow {
+    #[diesel(sql_type = Text)]
+    key: String,
+    #[diesel(sql_type = Text)]
+    kind: String,
+    #[diesel(sql_type = Text)]
+    description: String,
+}
+
+impl TryFrom<AttributeDefinitionRow> for AttributeDefinition {
+    type Error = NewsletterError;
+
+    fn try_from(row: AttributeDefinitionRow) -> Result<Self> {
+        let kind = AttributeType::parse(&row.kind)
+            .ok_or_else(|| NewsletterError::database(format!("unknown attribute type in database: {}", row.kind)))?;
+
+        Ok(AttributeDefinition {
+            key: row.key,
+            kind,
+            description: row.description,
+        })
+    }
+}
+
+#[derive(Debug, QueryableByName)]
+struct TagRow {
+    #[diesel(sql_type = Text)]
+    tag: String,
+    #[diesel(sql_type = Text)]
+    created_at: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct TokenRow {
+    #[diesel(sql_type = Text)]
+    created_at: String,
+    #[diesel(sql_type = Text)]
+    expires_at: String,
+}
+
+#[derive(Debug, QueryableByName)]
+struct OutboxRow {
+    #[diesel(sql_type = BigInt)]
+    id: i64,
+    #[diesel(sql_type = Text)]
+    payload: String,
+    #[diesel(sql_type = Integer)]
+    attempts: i32,
+}
+
+#[derive(Debug, QueryableByName)]
+struct OutboxBacklogRow {
+    #[diesel(sql_type = BigInt)]
+    pending: i64,
+    #[diesel(sql_type = BigInt)]
+    failing: i64,
+    #[diesel(sql_type = Nullable<Text>)]
+    oldest_pending_at: Option<String>,
+}
+
+#[derive(Debug, QueryableByName)]
+struct IdempotencyRow {
+    #[diesel(sql_type = Text)]
+    request_hash: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    response: Option<String>,
+}
+
+/// Snapshot of day `$2`, whose activity falls in `[$3, $4)`, for the tenant
+/// `$1`, or every tenant if NULL, that has subscriptions or activity
+const RECORD_DAILY_METRICS_QUERY: &str = "
+    WITH scope AS (
+        SELECT ?1 AS tenant_id
+    ), subscribers AS (
+        SELECT tenant_id, count(*) FILTER (WHERE status = 'active') AS n
+        FROM newsletters
+        WHERE (SELECT tenant_id FROM scope) IS NULL OR tenant_id = (SELECT tenant_id FROM scope)
+        GROUP BY tenant_id
+    ), confirms AS (
+        SELECT tenant_id, count(*) AS n
+        FROM consents
+        WHERE action = 'confirmed' AND created_at >= ?3 AND created_at < ?4
+          AND ((SELECT tenant_id FROM scope) IS NULL OR tenant_id = (SELECT tenant_id FROM scope))
+        GROUP BY tenant_id
+    ), unsubscribes AS (
+        SELECT tenant_id, count(*) AS n
+        FROM unsubscribe_events
+        WHERE created_at >= ?3 AND created_at < ?4
+          AND ((SELECT tenant_id FROM scope) IS NULL OR tenant_id = (SELECT tenant_id FROM scope))
+        GROUP BY tenant_id
+    ), tenants AS (
+        SELECT tenant_id FROM subscribers
+        UNION SELECT tenant_id FROM confirms
+        UNION SELECT tenant_id FROM unsubscribes
+    )
+    INSERT INTO list_metrics_daily (tenant_id, day, subscribers, confirms, unsubscribes, net_growth)
+    SELECT t.tenant_id, ?2,
+           coalesce(s.n, 0), coalesce(c.n, 0), coalesce(u.n, 0),
+           coalesce(c.n, 0) - coalesce(u.n, 0)
+    FROM tenants t
+    LEFT JOIN subscribers s USING (tenant_id)
+    LEFT JOIN confirms c USING (tenant_id)
+    LEFT JOIN unsubscribes u USING (tenant_id)
+    WHERE true
+    ON CONFLICT (tenant_id, day) DO NOTHING";
+
+/// Take the due outbox messages `$3` at a time, oldest first, leasing them until `$1`
+const CLAIM_OUTBOX_QUERY: &str = "
+    UPDATE outbox SET available_at = ?
+    WHERE id IN (
+        SELECT id FROM outbox
+        WHERE sent_at IS NULL AND available_at <= ?
+        ORDER BY available_at, id
+        LIMIT ?
+    )
+    RETURNING id, payload, attempts";
+
+/// Turn `limit + 1` keyset rows into a page, using the extra row as the "has more" marker
+fn keyset_page<R, T>(
+    mut rows: Vec<R>,
+    limit: i64,
+    id: impl Fn(&R) -> i64,
+    item: impl Fn(R) -> Result<T>,
+) -> Result<Page<T>> {
+    let has_more = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+    let next_cursor = if has_more { rows.last().map(id) } else { None };
+
+    Ok(Page {
+        items: rows.into_iter().map(item).collect::<Result<_>>()?,
+        next_cursor,
+    })
+}
+
+/// Tenant whose rows a call works on. There is no row-level security here,
+/// so every query names it; `TenantScope::All` works on the default tenant
+/// except where a method sweeps every tenant itself.
+fn tenant_of(scope: &TenantScope) -> TenantId {
+    scope.tenant().cloned().unwrap_or_default()
+}
+
+/// Tenant a sweeping method is limited to; `None` sweeps all of them
+fn swept_tenant(scope: &TenantScope) -> Option<String> {
+    scope.tenant().map(|tenant| tenant.as_str().to_string())
+}
+
+fn filter_newsletters(
+    mut query: BoxedSqlQuery<'static, Sqlite, SqlQuery>,
+    filter: &NewsletterFilter,
+) -> BoxedSqlQuery<'static, Sqlite, SqlQuery> {
+    if let Some(active) = filter.active {
+        query = query.sql(" AND (status = 'active') = ?").bind::<Bool, _>(active);
+    }
+    if let Some(status) = filter.status {
+        query = query.sql(" AND status = ?").bind::<Text, _>(status.as_str());
+    }
+    // LIKE ignores case in SQLite, so the address is cut to the length of the fragment instead
+    if let Some(prefix) = &filter.email_prefix {
+        query = query
+            .sql(" AND substr(email, 1, length(?)) = ?")
+            .bind::<Text, _>(prefix.clone())
+            .bind::<Text, _>(prefix.clone());
+    }
+    if let Some(domain) = &filter.email_domain {
+        let suffix = format!("@{domain}");
+        query = query
+            .sql(" AND substr(email, -length(?)) = ?")
+            .bind::<Text, _>(suffix.clone())
+            .bind::<Text, _>(suffix);
+    }
+    if let Some(since) = filter.created_since {
+        query = query.sql(" AND created_at >= ?").bind::<Text, _>(timestamp(since));
+    }
+    if let Some(until) = filter.created_until {
+        query = query.sql(" AND created_at < ?").bind::<Text, _>(timestamp(until));
+    }
+    // `->` yields minified JSON, so values only match with the same type
+    for (key, value) in &filter.attributes {
+        query = query
+            .sql(" AND attributes -> ? = json(?)")
+            .bind::<Text, _>(format!("$.\"{}\"", key.replace('"', "\\\"")))
+            .bind::<Text, _>(value.to_string());
+    }
+    query
+}
+
+/// Subscription stored under `email`, ignoring case
+fn find(conn: &mut SqliteConnection, tenant: &TenantId, email: &str) -> QueryResult<Option<NewsletterRow>> {
+    diesel::sql_query(format!(
+        "SELECT {NEWSLETTER_COLUMNS} FROM newsletters WHERE tenant_id = ? AND lower(email) = lower(?)"
+    ))
+    .bind::<Text, _>(tenant.as_str())
+    .bind::<Text, _>(email)
+    .get_result(conn)
+    .optional()
+}
+
+/// Subscription stored under exactly `email`
+fn find_exact(conn: &mut SqliteConnection, tenant: &TenantId, email: &str) -> QueryResult<Option<NewsletterRow>> {
+    diesel::sql_query(format!(
+        "SELECT {NEWSLETTER_COLUMNS} FROM newsletters WHERE tenant_id = ? AND email = ?"
+    ))
+    .bind::<Text, _>(tenant.as_str())
+    .bind::<Text, _>(email)
+    .get_result(conn)
+    .optional()
+}
+
+/// Insert unless the address exists in any case; returns whether a row was added
+fn insert_newsletter(
+    conn: &mut SqliteConnection,
+    tenant: &TenantId,
+    email: &str,
+    status: SubscriptionStatus,
+) -> QueryResult<bool> {
+    let now = timestamp(Utc::now());
+    let inserted = diesel::sql_query(
+        "INSERT INTO newsletters (tenant_id, email, status, status_changed_at, created_at)
+         VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT DO NOTHING",
+    )
+    .bind::<Text, _>(tenant.as_str())
+    .bind::<Text, _>(email)
+    .bind::<Text, _>(status.as_str())
+    .bind::<Text, _>(&now)
+    .bind::<Text, _>(&now)
+    .execute(conn)?;
+    Ok(inserted > 0)
+}
+
+/// Move the subscription under `email` from one of `from` to `to`; returns whether it did
+fn move_status(
+    conn: &mut SqliteConnection,
+    tenant: &TenantId,
+    email: &str,
+    from: &[SubscriptionStatus],
+    to: SubscriptionStatus,
+    resubscribed: bool,
+) -> QueryResult<bool> {
+    let from: Vec<&str> = from.iter().map(SubscriptionStatus::as_str).collect();
+    let now = timestamp(Utc::now());
+    let updated = diesel::sql_query(
+        "UPDATE newsletters
+         SET status = ?, status_changed_at = ?,
+             resubscribed_at = CASE WHEN ? THEN ? ELSE resubscribed_at END
+         WHERE tenant_id = ? AND email = ? AND status IN (SELECT value FROM json_each(?))",
+    )
+    .bind::<Text, _>(to.as_str())
+    .bind::<Text, _>(&now)
+    .bind::<Bool, _>(resubscribed)
+    .bind::<Text, _>(&now)
+    .bind::<Text, _>(tenant.as_str())
+    .bind::<Text, _>(email)
+    .bind::<Text, _>(serde_json::to_string(&from).unwrap_or_default())
+    .execute(conn)?;
+    Ok(updated > 0)
+}
+
+fn record_consent(
+    conn: &mut SqliteConnection,
+    tenant: &TenantId,
+    email: &str,
+    action: ConsentAction,
+    context: &ConsentContext,
+) -> QueryResult<()> {
+    diesel::sql_query(
+        "INSERT INTO consents (tenant_id, email, action, policy_version, source, ip_address, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind::<Text, _>(tenant.as_str())
+    .bind::<Text, _>(email)
+    .bind::<Text, _>(action.as_str())
+    .bind::<Nullable<Text>, _>(context.policy_version.as_deref())
+    .bind::<Nullable<Text>, _>(context.source.as_deref())
+    .bind::<Nullable<Text>, _>(context.ip_address.as_deref())
+    .bind::<Text, _>(timestamp(Utc::now()))
+    .execute(conn)?;
+    Ok(())
+}
+
+fn delete_tokens(conn: &mut SqliteConnection, tenant: &TenantId, email: &str) -> QueryResult<usize> {
+    diesel::sql_query("DELETE FROM confirmation_tokens WHERE tenant_id = ? AND email = ?")
+        .bind::<Text, _>(tenant.as_str())
+        .bind::<Text, _>(email)
+        .execute(conn)
+}
+
+/// Write events stamped with the tenant to the outbox and to the subscriber
+/// history, inside the transaction of the change they describe
+fn enqueue(conn: &mut SqliteConnection, tenant: &TenantId, events: &[SubscriptionEvent]) -> Result<()> {
+    let now = timestamp(Utc::now());
+    for event in events {
+        let event = SubscriptionEvent {
+            tenant: Some(tenant.clone()),
+            ..event.clone()
+        };
+        let payload = serde_json::to_string(&event).map_err(NewsletterError::database)?;
+        diesel::sql_query("INSERT INTO outbox (event_type, payload, available_at, created_at) VALUES (?, ?, ?, ?)")
+            .bind::<Text, _>(event.kind.as_str())
+            .bind::<Text, _>(&payload)
+            .bind::<Text, _>(&now)
+            .bind::<Text, _>(&now)
+            .execute(conn)?;
+        diesel::sql_query(
+            "INSERT INTO subscriber_events (tenant_id, email, previous_email, event_type, status, payload, occurred_at, recorded_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind::<Text, _>(tenant.as_str())
+        .bind::<Text, _>(&event.email)
+        .bind::<Nullable<Text>, _>(event.previous_email.as_deref())
+        .bind::<Text, _>(event.kind.as_str())
+        .bind::<Nullable<Text>, _>(event.status.as_ref().map(|status| status.as_str()))
+        .bind::<Text, _>(&payload)
+        .bind::<Text, _>(timestamp(event.occurred_at))
+        .bind::<Text, _>(&now)
+        .execute(conn)?;
+    }
+    Ok(())
+}
+
+/// NewsletterRepository on a single SQLite connection, for running the
+/// service and the cucumber suites without Postgres.
+///
+/// Each call runs in an immediate transaction on a blocking thread, so calls
+/// are serialized. The newsletter service's outbox and idempotency keys live
+/// in the same database.
+#[derive(Clone)]
+pub struct SqliteNewsletterRepository {
+    conn: Arc<Mutex<SqliteConnection>>,
+    /// Held by a unit of work, or by a call outside one, so other calls
+    /// cannot run inside the transaction of a unit
+    units: Arc<tokio::sync::Mutex<()>>,
+    pseudonyms: Option<Pseudonymizer>,
+}
+
+impl SqliteNewsletterRepository {
+    /// Open the database at `path`, `:memory:` for a private in-memory one,
+    /// applying the SQLite migrations
+    pub fn open(path: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            conn: Arc::new(Mutex::new(sqlite::open(path)?)),
+            units: Arc::default(),
+            pseudonyms: None,
+        })
+    }
+
+    /// Write consent and unsubscribe records under the subscriber's
+    /// pseudonym, as the Postgres repository does
+    pub fn with_pseudonyms(mut self, pseudonyms: Pseudonymizer) -> Self {
+        self.pseudonyms = Some(pseudonyms);
+        self
+    }
+
+    fn audit_email<'e>(&self, email: &'e str) -> Cow<'e, str> {
+        match &self.pseudonyms {
+            Some(pseudonyms) => Cow::Owned(pseudonyms.pseudonym(email)),
+            None => Cow::Borrowed(email),
+        }
+    }
+
+    /// Run `work` in a transaction on a blocking thread, in the tenant scope
+    /// of the calling task, which does not follow it there. Inside a unit of
+    /// work the transaction is a savepoint of the unit's.
+    async fn run<T, F>(&self, entity: &'static str, crud_operation: &'static str, work: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut SqliteConnection, &TenantScope) -> Result<T> + Send + 'static,
+    {
+        let in_unit = UNIT.try_with(|_| ()).is_ok();
+        let _unit = if in_unit { None } else { Some(self.units.lock().await) };
+        let scope = tenant::current();
+        let result = self
+            .blocking(move |conn| {
+                if in_unit {
+                    return conn.transaction(|conn| work(conn, &scope));
+                }
+                close_abandoned_unit(conn);
+                conn.immediate_transaction(|conn| work(conn, &scope))
+            })
+            .await;
+
+        if let Err(e) = &result {
+            error!(entity = entity, crud_operation = crud_operation, error = %e, "Failed to run SQLite query");
+        }
+        result
+    }
+
+    /// Run `work` on the connection on a blocking thread
+    async fn blocking<T, F>(&self, work: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut SqliteConnection) -> Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn.lock().expect("SQLite connection lock poisoned");
+            work(&mut conn)
+        })
+        .await
+        .map_err(NewsletterError::database)
+        .and_then(|result| result)
+    }
+}
+
+tokio::task_local! {
+    /// Set while the running task is in a unit of work
+    static UNIT: ();
+}
+
+/// Roll back the transaction of a unit of work whose future was dropped
+/// before it finished, which would otherwise stay open for the next caller
+fn close_abandoned_unit(conn: &mut SqliteConnection) {
+    let depth = AnsiTransactionManager::transaction_manager_status_mut(conn).transaction_depth();
+    if !matches!(depth, Ok(None)) {
+        match AnsiTransactionManager::rollback_transaction(conn) {
+            Ok(()) => info!("Rolled back an abandoned unit of work"),
+            Err(e) => error!(error = %e, "Failed to roll back an abandoned unit of work"),
+        }
+    }
+}
+
+#[async_trait]
+impl NewsletterRepository for SqliteNewsletterRepository {
+    async fn list(&self, page: PageRequest) -> Result<Page<Newsletter>> {
+        self.run("newsletter_table", "READ", move |conn, scope| {
+            let rows: Vec<NewsletterRow> = diesel::sql_query(format!(
+                "SELECT {NEWSLETTER_COLUMNS} FROM newsletters
+                 WHERE tenant_id = ? AND (? IS NULL OR id < ?)
+                 ORDER BY id DESC LIMIT ?"
+            ))
+            .bind::<Text, _>(tenant_of(scope).as_str())
+            .bind::<Nullable<BigInt>, _>(page.after)
+            .bind::<Nullable<BigInt>, _>(page.after)
+            .bind::<BigInt, _>(page.limit + 1)
+            .load(conn)?;
+
+            keyset_page(rows, page.limit, |r| r.id, NewsletterRow::newsletter)
+        })
+        .await
+    }
+
+    async fn list_masked(
+        &self,
+        query: &NewsletterQuery,
+        page: PageRequest,
+        mask: NewsletterMask,
+    ) -> Result<Page<PartialNewsletter>> {
+        let query = query.clone();
+        self.run("newsletter_table", "READ", move |conn, scope| {
+            let tenant = tenant_of(scope);
+
+            // The cursor is an id; its row supplies the sort key the next page starts after
+            let cursor = match page.after {
+                Some(after) => {
+                    let row: Option<CursorRow> =
+                        diesel::sql_query("SELECT email, created_at FROM newsletters WHERE tenant_id = ? AND id = ?")
+                            .bind::<Text, _>(tenant.as_str())
+                            .bind::<BigInt, _>(after)
+                            .get_result(conn)
+                            .optional()?;
+                    match row {
+                        Some(row) => Some((after, row)),
+                        None => return Err(StaleCursor(after).into()),
+                    }
+                }
+                None => None,
+            };
+
+            let mut rows_query = filter_newsletters(
+                diesel::sql_query(format!("SELECT {NEWSLETTER_COLUMNS} FROM newsletters WHERE tenant_id = ?"))
+                    .into_boxed()
+                    .bind::<Text, _>(tenant.as_str().to_string()),
+                &query.filter,
+            );
+
+            let column = match query.order.field {
+                SortField::CreatedAt => "created_at",
+                SortField::Email => "email",
+            };
+            let (compare, direction) = if query.order.descending { ("<", "DESC") } else { (">", "ASC") };
+            if let Some((id, row)) = cursor {
+                let key = match query.order.field {
+                    SortField::CreatedAt => row.created_at,
+                    SortField::Email => row.email,
+                };
+                rows_query = rows_query
+                    .sql(format!(" AND ({column} {compare} ? OR ({column} = ? AND id {compare} ?))"))
+                    .bind::<Text, _>(key.clone())
+                    .bind::<Text, _>(key)
+                    .bind::<BigInt, _>(id);
+            }
+            let rows: Vec<NewsletterRow> = rows_query
+                .sql(format!(" ORDER BY {column} {direction}, id {direction} LIMIT ?"))
+                .bind::<BigInt, _>(page.limit + 1)
+                .load(conn)?;
+
+            keyset_page(rows, page.limit, |r| r.id, |r| r.partial(mask))
+        })
+        .await
+    }
+
+    async fn search(&self, query: &SearchQuery, page: PageRequest) -> Result<Page<SearchHit>> {
+        let query = query.clone();
+        self.run("newsletter_table", "READ", move |conn, scope| {
+            // Without pg_trgm every address is ranked here, as the domain defines it
+            let rows: Vec<StatusRow> = diesel::sql_query("SELECT email, status FROM newsletters WHERE tenant_id = ?")
+                .bind::<Text, _>(tenant_of(scope).as_str())
+                .load(conn)?;
+
+            let mut hits: Vec<(Rank, StatusRow)> = rows
+                .into_iter()
+                .filter_map(|r| Rank::of(&r.email, &query).map(|rank| (rank, r)))
+                .collect();
+            hits.sort_by(|(a, ra), (b, rb)| a.cmp_best_first(b).then_with(|| ra.email.cmp(&rb.email)));
+
+            let offset = page.after.unwrap_or(0).max(0);
+            let mut hits: Vec<_> = hits.into_iter().skip(offset as usize).take(page.limit as usize + 1).collect();
+            let has_more = hits.len() as i64 > page.limit;
+            hits.truncate(page.limit as usize);
+
+            Ok(Page {
+                items: hits
+                    .into_iter()
+                    .map(|(rank, r)| {
+                        Ok(SearchHit {
+                            newsletter: Newsletter { status: parse_status(&r.status)?, email: r.email },
+                            score: rank.score,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+                next_cursor: has_more.then_some(offset + page.limit),
+            })
+        })
+        .await
+    }
+
+    async fn get_masked(&self, email: &str, mask: NewsletterMask) -> Result<Option<PartialNewsletter>> {
+        let email = email.to_string();
+        self.run("newsletter_table", "READ", move |conn, scope| {
+            find(conn, &tenant_of(scope), &email)?.map(|row| row.partial(mask)).transpose()
+        })
+        .await
+    }
+
+    async fn add(&self, email: &str) -> Result<bool> {
+        let email = email.to_string();
+        self.run("newsletter_table", "CREATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let inserted = insert_newsletter(conn, &tenant, &email, SubscriptionStatus::Active)?;
+            if inserted {
+                enqueue(conn, &tenant, &[SubscriptionEvent::status_changed(email, SubscriptionStatus::Active)])?;
+            }
+            Ok(inserted)
+        })
+        .await
+    }
+
+    async fn delete(&self, email: &str) -> Result<bool> {
+        let email = email.to_string();
+        self.run("newsletter_table", "DELETE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let deleted = diesel::sql_query("DELETE FROM newsletters WHERE tenant_id = ? AND email = ?")
+                .bind::<Text, _>(tenant.as_str())
+                .bind::<Text, _>(&email)
+                .execute(conn)?;
+            if deleted > 0 {
+                enqueue(conn, &tenant, &[SubscriptionEvent::now(SubscriptionEventKind::Unsubscribed, email)])?;
+            }
+            Ok(deleted > 0)
+        })
+        .await
+    }
+
+    async fn unsubscribe(&self, email: &str, feedback: &UnsubscribeFeedback) -> Result<bool> {
+        let audit_email = self.audit_email(email).into_owned();
+        let email = email.to_string();
+        let feedback = feedback.clone();
+        self.run("newsletter_table", "UPDATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let unsubscribed = move_status(
+                conn,
+                &tenant,
+                &email,
+                &[SubscriptionStatus::Pending, SubscriptionStatus::Active],
+                SubscriptionStatus::Unsubscribed,
+                false,
+            )?;
+            if !unsubscribed {
+                return Ok(false);
+            }
+
+            // A confirmation mail still in the inbox must not undo this
+            delete_tokens(conn, &tenant, &email)?;
+
+            diesel::sql_query(
+                "INSERT INTO unsubscribe_events (tenant_id, email, reason, comment, created_at) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&audit_email)
+            .bind::<Nullable<Text>, _>(feedback.reason.map(|r| r.as_str()))
+            .bind::<Nullable<Text>, _>(feedback.comment.as_deref())
+            .bind::<Text, _>(timestamp(Utc::now()))
+            .execute(conn)?;
+
+            record_consent(conn, &tenant, &audit_email, ConsentAction::Withdrawn, &ConsentContext::default())?;
+            enqueue(conn, &tenant, &[SubscriptionEvent::now(SubscriptionEventKind::Unsubscribed, email.as_str())])?;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn list_unsubscribe_events(
+        &self,
+        filter: UnsubscribeEventFilter,
+        page: PageRequest,
+    ) -> Result<Page<UnsubscribeEvent>> {
+        self.run("unsubscribe_events_table", "READ", move |conn, scope| {
+            let reason = filter.reason.map(|r| r.as_str());
+            let (since, until) = (filter.since.map(timestamp), filter.until.map(timestamp));
+            let rows: Vec<UnsubscribeEventRow> = diesel::sql_query(
+                "SELECT id, email, reason, comment, created_at FROM unsubscribe_events
+                 WHERE tenant_id = ?
+                   AND (? IS NULL OR reason = ?)
+                   AND (? IS NULL OR created_at >= ?)
+                   AND (? IS NULL OR created_at < ?)
+                   AND (? IS NULL OR id < ?)
+                 ORDER BY id DESC LIMIT ?",
+            )
+            .bind::<Text, _>(tenant_of(scope).as_str())
+            .bind::<Nullable<Text>, _>(reason)
+            .bind::<Nullable<Text>, _>(reason)
+            .bind::<Nullable<Text>, _>(since.as_deref())
+            .bind::<Nullable<Text>, _>(since.as_deref())
+            .bind::<Nullable<Text>, _>(until.as_deref())
+            .bind::<Nullable<Text>, _>(until.as_deref())
+            .bind::<Nullable<BigInt>, _>(page.after)
+            .bind::<Nullable<BigInt>, _>(page.after)
+            .bind::<BigInt, _>(page.limit + 1)
+            .load(conn)?;
+
+            keyset_page(rows, page.limit, |r| r.id, UnsubscribeEvent::try_from)
+        })
+        .await
+    }
+
+    async fn count_unsubscribe_reasons(&self, filter: UnsubscribeEventFilter) -> Result<Vec<ReasonCount>> {
+        self.run("unsubscribe_events_table", "READ", move |conn, scope| {
+            let reason = filter.reason.map(|r| r.as_str());
+            let (since, until) = (filter.since.map(timestamp), filter.until.map(timestamp));
+            let rows: Vec<ReasonCountRow> = diesel::sql_query(
+                "SELECT reason, count(*) AS count FROM unsubscribe_events
+                 WHERE tenant_id = ?
+                   AND (? IS NULL OR reason = ?)
+                   AND (? IS NULL OR created_at >= ?)
+                   AND (? IS NULL OR created_at < ?)
+                 GROUP BY reason
+                 ORDER BY min(id)",
+            )
+            .bind::<Text, _>(tenant_of(scope).as_str())
+            .bind::<Nullable<Text>, _>(reason)
+            .bind::<Nullable<Text>, _>(reason)
+            .bind::<Nullable<Text>, _>(since.as_deref())
+            .bind::<Nullable<Text>, _>(since.as_deref())
+            .bind::<Nullable<Text>, _>(until.as_deref())
+            .bind::<Nullable<Text>, _>(until.as_deref())
+            .load(conn)?;
+
+            rows.into_iter()
+                .map(|row| {
+                    Ok(ReasonCount {
+                        reason: parse_reason(row.reason)?,
+                        count: row.count,
+                    })
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn list_consents(&self, email: &str, page: PageRequest) -> Result<Page<ConsentRecord>> {
+        let audit_email = self.audit_email(email).into_owned();
+        let email = email.to_string();
+        self.run("consents_table", "READ", move |conn, scope| {
+            let rows: Vec<ConsentRow> = diesel::sql_query(
+                "SELECT id, email, action, policy_version, source, ip_address, created_at FROM consents
+                 WHERE tenant_id = ? AND (lower(email) = lower(?) OR email = ?) AND (? IS NULL OR id < ?)
+                 ORDER BY id DESC LIMIT ?",
+            )
+            .bind::<Text, _>(tenant_of(scope).as_str())
+            .bind::<Text, _>(&email)
+            .bind::<Text, _>(&audit_email)
+            .bind::<Nullable<BigInt>, _>(page.after)
+            .bind::<Nullable<BigInt>, _>(page.after)
+            .bind::<BigInt, _>(page.limit + 1)
+            .load(conn)?;
+
+            // Pseudonymized records are shown under the address asked for
+            keyset_page(rows, page.limit, |r| r.id, |r| {
+                Ok(ConsentRecord {
+                    email: email.clone(),
+                    ..ConsentRecord::try_from(r)?
+                })
+            })
+        })
+        .await
+    }
+
+    async fn list_subscriber_events(&self, email: &str, until: DateTime<Utc>) -> Result<Vec<SubscriptionEvent>> {
+        let email = email.to_string();
+        self.run("subscriber_events_table", "READ", move |conn, scope| {
+            let rows: Vec<PayloadRow> = diesel::sql_query(
+                "SELECT payload FROM subscriber_events
+                 WHERE tenant_id = ? AND (email = ? OR previous_email = ?) AND occurred_at <= ?
+                 ORDER BY occurred_at, id",
+            )
+            .bind::<Text, _>(tenant_of(scope).as_str())
+            .bind::<Text, _>(&email)
+            .bind::<Text, _>(&email)
+            .bind::<Text, _>(timestamp(until))
+            .load(conn)?;
+            rows.into_iter()
+                .map(|row| serde_json::from_str(&row.payload).map_err(NewsletterError::database))
+                .collect()
+        })
+        .await
+    }
+
+    async fn stats(&self) -> Result<SubscriberStats> {
+        self.run("newsletter_table", "READ", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let by_status: Vec<StatusCountRow> =
+                diesel::sql_query("SELECT status, count(*) AS count FROM newsletters WHERE tenant_id = ? GROUP BY status")
+                    .bind::<Text, _>(tenant.as_str())
+                    .load(conn)?;
+            let unsubscribed: CountRow =
+                diesel::sql_query("SELECT count(*) AS count FROM unsubscribe_events WHERE tenant_id = ?")
+                    .bind::<Text, _>(tenant.as_str())
+                    .get_result(conn)?;
+
+            let mut stats = SubscriberStats {
+                unsubscribed: unsubscribed.count,
+                ..SubscriberStats::default()
+            };
+            // Unsubscribed rows are left out; `unsubscribed` counts events
+            for row in by_status {
+                match parse_status(&row.status)? {
+                    SubscriptionStatus::Active => stats.active = row.count,
+                    SubscriptionStatus::Pending => stats.inactive = row.count,
+                    SubscriptionStatus::Suppressed => stats.suppressed = row.count,
+                    SubscriptionStatus::Unsubscribed => {}
+                }
+            }
+            Ok(stats)
+        })
+        .await
+    }
+
+    async fn record_daily_metrics(&self, day: NaiveDate) -> Result<usize> {
+        self.run("list_metrics_daily_table", "CREATE", move |conn, scope| {
+            let start = day.and_time(NaiveTime::MIN).and_utc();
+            let recorded = diesel::sql_query(RECORD_DAILY_METRICS_QUERY)
+                .bind::<Nullable<Text>, _>(swept_tenant(scope))
+                .bind::<Text, _>(day.format(DATE_FORMAT).to_string())
+                .bind::<Text, _>(timestamp(start))
+                .bind::<Text, _>(timestamp(start + Duration::days(1)))
+                .execute(conn)?;
+            info!(entity = "list_metrics_daily_table", crud_operation = "CREATE", day = %day, recorded = recorded, "Successfully recorded daily metrics");
+            Ok(recorded)
+        })
+        .await
+    }
+
+    async fn list_daily_metrics(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyMetrics>> {
+        self.run("list_metrics_daily_table", "READ", move |conn, scope| {
+            let rows: Vec<DailyMetricsRow> = diesel::sql_query(
+                "SELECT day, subscribers, confirms, unsubscribes, net_growth FROM list_metrics_daily
+                 WHERE tenant_id = ? AND day BETWEEN ? AND ?
+                 ORDER BY day",
+            )
+            .bind::<Text, _>(tenant_of(scope).as_str())
+            .bind::<Text, _>(from.format(DATE_FORMAT).to_string())
+            .bind::<Text, _>(to.format(DATE_FORMAT).to_string())
+            .load(conn)?;
+
+            rows.into_iter().map(DailyMetrics::try_from).collect()
+        })
+        .await
+    }
+
+    async fn list_tenants(&self) -> Result<Vec<TenantId>> {
+        self.run("newsletter_table", "READ", move |conn, scope| {
+            let tenant = swept_tenant(scope);
+            let rows: Vec<TenantRow> = diesel::sql_query(
+                "SELECT DISTINCT tenant_id FROM newsletters WHERE ? IS NULL OR tenant_id = ? ORDER BY tenant_id",
+            )
+            .bind::<Nullable<Text>, _>(tenant.as_deref())
+            .bind::<Nullable<Text>, _>(tenant.as_deref())
+            .load(conn)?;
+
+            rows.into_iter()
+                .map(|row| {
+                    TenantId::parse(&row.tenant_id)
+                        .map_err(|_| NewsletterError::database(format!("invalid tenant in database: {}", row.tenant_id)))
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn add_many(&self, emails: &[String]) -> Result<usize> {
+        let emails = emails.to_vec();
+        self.run("newsletter_table", "CREATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let mut events = Vec::new();
+            for email in &emails {
+                if insert_newsletter(conn, &tenant, email, SubscriptionStatus::Active)? {
+                    events.push(SubscriptionEvent::status_changed(email.as_str(), SubscriptionStatus::Active));
+                }
+            }
+            enqueue(conn, &tenant, &events)?;
+            Ok(events.len())
+        })
+        .await
+    }
+
+    async fn set_status_many(&self, emails: &[String], status: SubscriptionStatus) -> Result<usize> {
+        let emails = emails.to_vec();
+        self.run("newsletter_table", "UPDATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let mut current = Vec::new();
+            for email in &emails {
+                if let Some(row) = find_exact(conn, &tenant, email)? {
+                    let from = parse_status(&row.status)?;
+                    from.transition(status)
+                        .map_err(|e| NewsletterError::InvalidTransition(format!("{}: {e}", row.email)))?;
+                    current.push((row.email, from));
+                }
+            }
+
+            let mut events = Vec::new();
+            for (email, from) in current.into_iter().filter(|(_, from)| *from != status) {
+                move_status(conn, &tenant, &email, &[from], status, false)?;
+                events.push(SubscriptionEvent::status_changed(email, status));
+            }
+            enqueue(conn, &tenant, &events)?;
+            Ok(events.len())
+        })
+        .await
+    }
+
+    async fn delete_many(&self, emails: &[String]) -> Result<usize> {
+        let emails = emails.to_vec();
+        self.run("newsletter_table", "DELETE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let mut events = Vec::new();
+            for email in &emails {
+                let deleted = diesel::sql_query("DELETE FROM newsletters WHERE tenant_id = ? AND email = ?")
+                    .bind::<Text, _>(tenant.as_str())
+                    .bind::<Text, _>(email)
+                    .execute(conn)?;
+                if deleted > 0 {
+                    events.push(SubscriptionEvent::now(SubscriptionEventKind::Unsubscribed, email.as_str()));
+                }
+            }
+            enqueue(conn, &tenant, &events)?;
+            Ok(events.len())
+        })
+        .await
+    }
+
+    async fn get_many(&self, emails: &[String]) -> Result<Vec<Newsletter>> {
+        let emails = emails.to_vec();
+        self.run("newsletter_table", "READ", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let mut rows = Vec::new();
+            for email in &emails {
+                rows.extend(find_exact(conn, &tenant, email)?);
+            }
+            rows.sort_by_key(|r| r.id);
+            rows.dedup_by_key(|r| r.id);
+            rows.into_iter().map(NewsletterRow::newsletter).collect()
+        })
+        .await
+    }
+
+    async fn get_by_email(&self, email: &str) -> Result<Option<Newsletter>> {
+        let email = email.to_string();
+        self.run("newsletter_table", "READ", move |conn, scope| {
+            find(conn, &tenant_of(scope), &email)?.map(NewsletterRow::newsletter).transpose()
+        })
+        .await
+    }
+
+    async fn add_pending(
+        &self,
+        email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+        consent: &ConsentContext,
+    ) -> Result<bool> {
+        let audit_email = self.audit_email(email).into_owned();
+        let email = email.to_string();
+        let consent = consent.clone();
+        self.run("newsletter_table", "CREATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            if let Some(row) = find_exact(conn, &tenant, &email)? {
+                if matches!(parse_status(&row.status)?, SubscriptionStatus::Active | SubscriptionStatus::Suppressed) {
+                    return Ok(false);
+                }
+            }
+
+            insert_newsletter(conn, &tenant, &email, SubscriptionStatus::Pending)?;
+            // Signing up again after unsubscribing starts over as pending
+            let returning = move_status(
+                conn,
+                &tenant,
+                &email,
+                &[SubscriptionStatus::Unsubscribed],
+                SubscriptionStatus::Pending,
+                true,
+            )?;
+
+            let now = timestamp(Utc::now());
+            diesel::sql_query(
+                "INSERT INTO confirmation_tokens (token_id, tenant_id, email, expires_at, created_at) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind::<Text, _>(token_id.to_string())
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&email)
+            .bind::<Text, _>(timestamp(expires_at))
+            .bind::<Text, _>(&now)
+            .execute(conn)?;
+
+            record_consent(conn, &tenant, &audit_email, ConsentAction::Given, &consent)?;
+            let event = if returning {
+                SubscriptionEvent::resubscribed(email.as_str(), SubscriptionStatus::Pending)
+            } else {
+                SubscriptionEvent::now(SubscriptionEventKind::Subscribed, email.as_str())
+            };
+            enqueue(conn, &tenant, &[event])?;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn resubscribe(&self, email: &str, consent: &ConsentContext) -> Result<bool> {
+        let audit_email = self.audit_email(email).into_owned();
+        let email = email.to_string();
+        let consent = consent.clone();
+        self.run("newsletter_table", "UPDATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let resubscribed = move_status(
+                conn,
+                &tenant,
+                &email,
+                &[SubscriptionStatus::Unsubscribed],
+                SubscriptionStatus::Active,
+                true,
+            )?;
+            if !resubscribed {
+                return Ok(false);
+            }
+
+            record_consent(conn, &tenant, &audit_email, ConsentAction::Given, &consent)?;
+            enqueue(conn, &tenant, &[SubscriptionEvent::resubscribed(email.as_str(), SubscriptionStatus::Active)])?;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn confirm(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<String>> {
+        let pseudonyms = self.pseudonyms.clone();
+        let consent = consent.clone();
+        self.run("newsletter_table", "UPDATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let email: Option<EmailRow> = diesel::sql_query(
+                "DELETE FROM confirmation_tokens WHERE tenant_id = ? AND token_id = ? AND expires_at > ? RETURNING email",
+            )
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(token_id.to_string())
+            .bind::<Text, _>(timestamp(now))
+            .get_result(conn)
+            .optional()?;
+            let Some(EmailRow { email }) = email else {
+                return Ok(None);
+            };
+
+            move_status(conn, &tenant, &email, &[SubscriptionStatus::Pending], SubscriptionStatus::Active, false)?;
+            // Any other outstanding tokens for this address are now moot
+            delete_tokens(conn, &tenant, &email)?;
+
+            let audit_email = match &pseudonyms {
+                Some(pseudonyms) => pseudonyms.pseudonym(&email),
+                None => email.clone(),
+            };
+            record_consent(conn, &tenant, &audit_email, ConsentAction::Confirmed, &consent)?;
+            enqueue(conn, &tenant, &[SubscriptionEvent::now(SubscriptionEventKind::Confirmed, email.as_str())])?;
+            Ok(Some(email))
+        })
+        .await
+    }
+
+    async fn request_email_change(
+        &self,
+        email: &str,
+        new_email: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let email = email.to_string();
+        let new_email = new_email.to_string();
+        self.run("email_changes_table", "CREATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let active = match find_exact(conn, &tenant, &email)? {
+                Some(row) => parse_status(&row.status)? == SubscriptionStatus::Active,
+                None => false,
+            };
+            if !active {
+                return Ok(false);
+            }
+            if let Some(row) = find(conn, &tenant, &new_email)? {
+                return Err(email_change::taken(&new_email, parse_status(&row.status)?));
+            }
+
+            diesel::sql_query(
+                "INSERT INTO email_changes (token_id, tenant_id, email, new_email, expires_at, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind::<Text, _>(token_id.to_string())
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&email)
+            .bind::<Text, _>(&new_email)
+            .bind::<Text, _>(timestamp(expires_at))
+            .bind::<Text, _>(timestamp(Utc::now()))
+            .execute(conn)?;
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn change_email(&self, token_id: Uuid, now: DateTime<Utc>, consent: &ConsentContext) -> Result<Option<EmailChange>> {
+        let pseudonyms = self.pseudonyms.clone();
+        let consent = consent.clone();
+        self.run("newsletter_table", "UPDATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let pending: Option<EmailChangeRow> = diesel::sql_query(
+                "DELETE FROM email_changes WHERE tenant_id = ? AND token_id = ? AND expires_at > ?
+                 RETURNING email, new_email",
+            )
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(token_id.to_string())
+            .bind::<Text, _>(timestamp(now))
+            .get_result(conn)
+            .optional()?;
+            let Some(EmailChangeRow { email: from, new_email: to }) = pending else {
+                return Ok(None);
+            };
+            if let Some(row) = find(conn, &tenant, &to)? {
+                return Err(email_change::taken(&to, parse_status(&row.status)?));
+            }
+
+            // Other changes still waiting are moot once the address is gone
+            diesel::sql_query("DELETE FROM email_changes WHERE tenant_id = ? AND email = ?")
+                .bind::<Text, _>(tenant.as_str())
+                .bind::<Text, _>(&from)
+                .execute(conn)?;
+            // The foreign keys carry the rename to tokens, tags and topic choices
+            let moved = diesel::sql_query(
+                "UPDATE newsletters SET email = ? WHERE tenant_id = ? AND email = ? AND status = 'active'",
+            )
+            .bind::<Text, _>(&to)
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&from)
+            .execute(conn)?;
+            if moved == 0 {
+                return Ok(None);
+            }
+
+            let (from_audit, to_audit) = match &pseudonyms {
+                Some(pseudonyms) => (pseudonyms.pseudonym(&from), pseudonyms.pseudonym(&to)),
+                None => (from.clone(), to.clone()),
+            };
+            diesel::sql_query(
+                "UPDATE consents SET email = ? WHERE tenant_id = ? AND (lower(email) = lower(?) OR email = ?)",
+            )
+            .bind::<Text, _>(&to_audit)
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&from)
+            .bind::<Text, _>(&from_audit)
+            .execute(conn)?;
+            diesel::sql_query("UPDATE unsubscribe_events SET email = ? WHERE tenant_id = ? AND email IN (?, ?)")
+                .bind::<Text, _>(&to_audit)
+                .bind::<Text, _>(tenant.as_str())
+                .bind::<Text, _>(&from)
+                .bind::<Text, _>(&from_audit)
+                .execute(conn)?;
+
+            record_consent(conn, &tenant, &to_audit, ConsentAction::Confirmed, &consent)?;
+            let change = EmailChange { from, to };
+            enqueue(conn, &tenant, &[SubscriptionEvent::email_changed(&change)])?;
+            Ok(Some(change))
+        })
+        .await
+    }
+
+    async fn request_topic_confirmation(
+        &self,
+        email: &str,
+        topic: &str,
+        token_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let email = email.to_string();
+        let topic = topic.to_string();
+        self.run("topic_confirmations_table", "CREATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let inserted = diesel::sql_query(
+                "INSERT INTO topic_confirmations (token_id, tenant_id, email, topic, expires_at, created_at)
+                 SELECT ?, tenant_id, email, ?, ?, ? FROM newsletters
+                 WHERE tenant_id = ? AND email = ? AND status = 'active'",
+            )
+            .bind::<Text, _>(token_id.to_string())
+            .bind::<Text, _>(&topic)
+            .bind::<Text, _>(timestamp(expires_at))
+            .bind::<Text, _>(timestamp(Utc::now()))
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&email)
+            .execute(conn)?;
+            Ok(inserted > 0)
+        })
+        .await
+    }
+
+    async fn confirm_topic(&self, token_id: Uuid, now: DateTime<Utc>) -> Result<Option<TopicConfirmation>> {
+        self.run("subscriber_topics_table", "UPDATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let pending: Option<TopicConfirmationRow> = diesel::sql_query(
+                "DELETE FROM topic_confirmations WHERE tenant_id = ? AND token_id = ? AND expires_at > ?
+                 RETURNING email, topic",
+            )
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(token_id.to_string())
+            .bind::<Text, _>(timestamp(now))
+            .get_result(conn)
+            .optional()?;
+            let Some(TopicConfirmationRow { email, topic }) = pending else {
+                return Ok(None);
+            };
+
+            diesel::sql_query(
+                "INSERT INTO subscriber_topics (tenant_id, email, topic, subscribed, updated_at)
+                 VALUES (?, ?, ?, TRUE, ?)
+                 ON CONFLICT (tenant_id, email, topic)
+                 DO UPDATE SET subscribed = TRUE, updated_at = excluded.updated_at",
+            )
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&email)
+            .bind::<Text, _>(&topic)
+            .bind::<Text, _>(timestamp(Utc::now()))
+            .execute(conn)?;
+            Ok(Some(TopicConfirmation { email, topic }))
+        })
+        .await
+    }
+
+    async fn purge_expired_pending(&self, expired_by: DateTime<Utc>) -> Result<PendingPurge> {
+        self.run("newsletter_table", "DELETE", move |conn, scope| {
+            let tenant = swept_tenant(scope);
+            // Address changes and topic opt-ins nobody confirmed leave the
+            // subscription as it is
+            for table in ["email_changes", "topic_confirmations"] {
+                diesel::sql_query(format!(
+                    "DELETE FROM {table} WHERE (? IS NULL OR tenant_id = ?) AND expires_at <= ?"
+                ))
+                .bind::<Nullable<Text>, _>(tenant.as_deref())
+                .bind::<Nullable<Text>, _>(tenant.as_deref())
+                .bind::<Text, _>(timestamp(expired_by))
+                .execute(conn)?;
+            }
+            let mut expired: Vec<TenantEmailRow> = diesel::sql_query(
+                "DELETE FROM confirmation_tokens WHERE (? IS NULL OR tenant_id = ?) AND expires_at <= ?
+                 RETURNING tenant_id, email",
+            )
+            .bind::<Nullable<Text>, _>(tenant.as_deref())
+            .bind::<Nullable<Text>, _>(tenant.as_deref())
+            .bind::<Text, _>(timestamp(expired_by))
+            .load(conn)?;
+            expired.sort_by(|a, b| (&a.tenant_id, &a.email).cmp(&(&b.tenant_id, &b.email)));
+            expired.dedup_by(|a, b| a.tenant_id == b.tenant_id && a.email == b.email);
+
+            let lapsed = "tenant_id = ? AND email = ? AND status = 'pending' AND NOT EXISTS (
+                SELECT 1 FROM confirmation_tokens t
+                WHERE t.tenant_id = newsletters.tenant_id AND t.email = newsletters.email
+            )";
+            let mut purge = PendingPurge::default();
+            for row in &expired {
+                // An unconfirmed resubscription keeps the history it had
+                purge.reverted += diesel::sql_query(format!(
+                    "UPDATE newsletters SET status = 'unsubscribed', status_changed_at = ?
+                     WHERE {lapsed} AND resubscribed_at IS NOT NULL"
+                ))
+                .bind::<Text, _>(timestamp(Utc::now()))
+                .bind::<Text, _>(&row.tenant_id)
+                .bind::<Text, _>(&row.email)
+                .execute(conn)?;
+
+                purge.deleted += diesel::sql_query(format!("DELETE FROM newsletters WHERE {lapsed}"))
+                    .bind::<Text, _>(&row.tenant_id)
+                    .bind::<Text, _>(&row.email)
+                    .execute(conn)?;
+            }
+            Ok(purge)
+        })
+        .await
+    }
+
+    async fn apply_retention(&self, unsubscribed_before: DateTime<Utc>, action: RetentionAction) -> Result<RetentionPurge> {
+        let pseudonyms = self.pseudonyms.clone();
+        self.run("newsletter_table", "UPDATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let audit = |email: &str| match &pseudonyms {
+                Some(pseudonyms) => pseudonyms.pseudonym(email),
+                None => email.to_string(),
+            };
+            // Anonymized rows have nothing left to anonymize
+            let rows: Vec<IdEmailRow> = diesel::sql_query(
+                "SELECT id, email FROM newsletters
+                 WHERE tenant_id = ? AND status = 'unsubscribed' AND status_changed_at < ?
+                   AND (? OR anonymized_at IS NULL)",
+            )
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(timestamp(unsubscribed_before))
+            .bind::<Bool, _>(action == RetentionAction::Delete)
+            .load(conn)?;
+
+            let mut purge = RetentionPurge::default();
+            for row in &rows {
+                let email_audit = audit(&row.email);
+                match action {
+                    RetentionAction::Anonymize => {
+                        let anonymized = retention::anonymized_email(row.id);
+                        let anonymized_audit = audit(&anonymized);
+                        // The rename would carry them over through the foreign keys
+                        for table in [
+                            "subscriber_tags",
+                            "subscriber_topics",
+                            "confirmation_tokens",
+                            "email_changes",
+                            "topic_confirmations",
+                        ] {
+                            diesel::sql_query(format!("DELETE FROM {table} WHERE tenant_id = ? AND email = ?"))
+                                .bind::<Text, _>(tenant.as_str())
+                                .bind::<Text, _>(&row.email)
+                                .execute(conn)?;
+                        }
+                        diesel::sql_query(
+                            "UPDATE newsletters
+                             SET email = ?, attributes = '{}', locale = NULL, timezone = NULL, anonymized_at = ?
+                             WHERE id = ?",
+                        )
+                        .bind::<Text, _>(&anonymized)
+                        .bind::<Text, _>(timestamp(Utc::now()))
+                        .bind::<BigInt, _>(row.id)
+                        .execute(conn)?;
+                        diesel::sql_query(
+                            "UPDATE consents SET email = ? WHERE tenant_id = ? AND (lower(email) = lower(?) OR email = ?)",
+                        )
+                        .bind::<Text, _>(&anonymized_audit)
+                        .bind::<Text, _>(tenant.as_str())
+                        .bind::<Text, _>(&row.email)
+                        .bind::<Text, _>(&email_audit)
+                        .execute(conn)?;
+                        diesel::sql_query("UPDATE unsubscribe_events SET email = ? WHERE tenant_id = ? AND email IN (?, ?)")
+                            .bind::<Text, _>(&anonymized_audit)
+                            .bind::<Text, _>(tenant.as_str())
+                            .bind::<Text, _>(&row.email)
+                            .bind::<Text, _>(&email_audit)
+                            .execute(conn)?;
+                        for column in ["email", "merged_email"] {
+                            diesel::sql_query(format!(
+                                "UPDATE subscriber_merges SET {column} = ? WHERE tenant_id = ? AND {column} = ?"
+                            ))
+                            .bind::<Text, _>(&anonymized_audit)
+                            .bind::<Text, _>(tenant.as_str())
+                            .bind::<Text, _>(&email_audit)
+                            .execute(conn)?;
+                        }
+                        // What happened and when stays in the history, under the new address
+                        for column in ["email", "previous_email"] {
+                            diesel::sql_query(format!(
+                                "UPDATE subscriber_events SET {column} = ?1, payload = json_set(payload, '$.{column}', ?1)
+                                 WHERE tenant_id = ?2 AND {column} = ?3"
+                            ))
+                            .bind::<Text, _>(&anonymized)
+                            .bind::<Text, _>(tenant.as_str())
+                            .bind::<Text, _>(&row.email)
+                            .execute(conn)?;
+                        }
+                        purge.anonymized += 1;
+                    }
+                    RetentionAction::Delete => {
+                        // The foreign keys remove its tokens, address changes, tags and topic choices
+                        purge.deleted += diesel::sql_query("DELETE FROM newsletters WHERE id = ?")
+                            .bind::<BigInt, _>(row.id)
+                            .execute(conn)?;
+                        diesel::sql_query(
+                            "DELETE FROM consents WHERE tenant_id = ? AND (lower(email) = lower(?) OR email = ?)",
+                        )
+                        .bind::<Text, _>(tenant.as_str())
+                        .bind::<Text, _>(&row.email)
+                        .bind::<Text, _>(&email_audit)
+                        .execute(conn)?;
+                        diesel::sql_query("DELETE FROM unsubscribe_events WHERE tenant_id = ? AND email IN (?, ?)")
+                            .bind::<Text, _>(tenant.as_str())
+                            .bind::<Text, _>(&row.email)
+                            .bind::<Text, _>(&email_audit)
+                            .execute(conn)?;
+                        diesel::sql_query(
+                            "DELETE FROM subscriber_merges WHERE tenant_id = ? AND (email = ? OR merged_email = ?)",
+                        )
+                        .bind::<Text, _>(tenant.as_str())
+                        .bind::<Text, _>(&email_audit)
+                        .bind::<Text, _>(&email_audit)
+                        .execute(conn)?;
+                        diesel::sql_query("DELETE FROM subscriber_events WHERE tenant_id = ? AND email = ?")
+                            .bind::<Text, _>(tenant.as_str())
+                            .bind::<Text, _>(&row.email)
+                            .execute(conn)?;
+                        diesel::sql_query(
+                            "UPDATE subscriber_events SET previous_email = NULL, payload = json_remove(payload, '$.previous_email')
+                             WHERE tenant_id = ? AND previous_email = ?",
+                        )
+                        .bind::<Text, _>(tenant.as_str())
+                        .bind::<Text, _>(&row.email)
+                        .execute(conn)?;
+                    }
+                }
+            }
+            Ok(purge)
+        })
+        .await
+    }
+
+    async fn merge_case_duplicates(&self) -> Result<usize> {
+        self.run("newsletter_table", "UPDATE", move |conn, scope| {
+            // The unique index on lower(email) keeps case variants from
+            // coexisting, so folding comes down to renaming, and the foreign
+            // keys carry the rename to tags, topic choices and tokens
+            let folded = diesel::sql_query(
+                "UPDATE newsletters SET email = lower(email) WHERE tenant_id = ? AND email <> lower(email)",
+            )
+            .bind::<Text, _>(tenant_of(scope).as_str())
+            .execute(conn)?;
+            Ok(folded)
+        })
+        .await
+    }
+
+    async fn merge_subscribers(&self, email: &str, merged_email: &str) -> Result<Option<SubscriberMerge>> {
+        let pseudonyms = self.pseudonyms.clone();
+        let email = email.to_string();
+        let merged_email = merged_email.to_string();
+        self.run("newsletter_table", "UPDATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let (Some(kept), Some(merged)) = (find(conn, &tenant, &email)?, find(conn, &tenant, &merged_email)?) else {
+                return Ok(None);
+            };
+            let fields = kept.fields()?.merge(merged.fields()?);
+
+            diesel::sql_query(
+                "UPDATE newsletters
+                 SET status = ?, created_at = ?, attributes = ?, locale = ?, timezone = ?, resubscribed_at = ?,
+                     status_changed_at = CASE WHEN status = ? THEN status_changed_at ELSE ? END
+                 WHERE id = ?",
+            )
+            .bind::<Text, _>(fields.status.as_str())
+            .bind::<Text, _>(timestamp(fields.created_at))
+            .bind::<Text, _>(serde_json::Value::Object(fields.attributes).to_string())
+            .bind::<Nullable<Text>, _>(&fields.locale)
+            .bind::<Nullable<Text>, _>(&fields.timezone)
+            .bind::<Nullable<Text>, _>(fields.resubscribed_at.map(timestamp))
+            .bind::<Text, _>(fields.status.as_str())
+            .bind::<Text, _>(timestamp(Utc::now()))
+            .bind::<BigInt, _>(kept.id)
+            .execute(conn)?;
+
+            // Tags and topic choices already made under the kept address win
+            diesel::sql_query(
+                "INSERT INTO subscriber_tags (tenant_id, email, tag, created_at)
+                 SELECT tenant_id, ?, tag, created_at FROM subscriber_tags WHERE tenant_id = ? AND email = ?
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind::<Text, _>(&kept.email)
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&merged.email)
+            .execute(conn)?;
+            diesel::sql_query(
+                "INSERT INTO subscriber_topics (tenant_id, email, topic, subscribed, updated_at)
+                 SELECT tenant_id, ?, topic, subscribed, updated_at FROM subscriber_topics WHERE tenant_id = ? AND email = ?
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind::<Text, _>(&kept.email)
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&merged.email)
+            .execute(conn)?;
+
+            // The foreign keys remove its tokens, address changes, tags and topic choices
+            diesel::sql_query("DELETE FROM newsletters WHERE id = ?")
+                .bind::<BigInt, _>(merged.id)
+                .execute(conn)?;
+
+            let (kept_audit, merged_audit) = match &pseudonyms {
+                Some(pseudonyms) => (pseudonyms.pseudonym(&kept.email), pseudonyms.pseudonym(&merged.email)),
+                None => (kept.email.clone(), merged.email.clone()),
+            };
+            diesel::sql_query(
+                "UPDATE consents SET email = ? WHERE tenant_id = ? AND (lower(email) = lower(?) OR email = ?)",
+            )
+            .bind::<Text, _>(&kept_audit)
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&merged.email)
+            .bind::<Text, _>(&merged_audit)
+            .execute(conn)?;
+            diesel::sql_query("UPDATE unsubscribe_events SET email = ? WHERE tenant_id = ? AND email IN (?, ?)")
+                .bind::<Text, _>(&kept_audit)
+                .bind::<Text, _>(tenant.as_str())
+                .bind::<Text, _>(&merged.email)
+                .bind::<Text, _>(&merged_audit)
+                .execute(conn)?;
+
+            let merge = SubscriberMerge {
+                merged_status: parse_status(&merged.status)?,
+                status: fields.status,
+                merged_at: Utc::now(),
+                email: kept.email,
+                merged_email: merged.email,
+            };
+            diesel::sql_query(
+                "INSERT INTO subscriber_merges (tenant_id, email, merged_email, merged_status, status, created_at)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&kept_audit)
+            .bind::<Text, _>(&merged_audit)
+            .bind::<Text, _>(merge.merged_status.as_str())
+            .bind::<Text, _>(merge.status.as_str())
+            .bind::<Text, _>(timestamp(merge.merged_at))
+            .execute(conn)?;
+            enqueue(conn, &tenant, &[SubscriptionEvent::merged(&merge)])?;
+            Ok(Some(merge))
+        })
+        .await
+    }
+
+    async fn tag(&self, emails: &[String], tag: &str) -> Result<usize> {
+        let emails = emails.to_vec();
+        let tag = tag.to_string();
+        self.run("subscriber_tags_table", "CREATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let now = timestamp(Utc::now());
+            let mut tagged = 0;
+            for email in &emails {
+                tagged += diesel::sql_query(
+                    "INSERT INTO subscriber_tags (tenant_id, email, tag, created_at)
+                     SELECT tenant_id, email, ?, ? FROM newsletters
+                     WHERE tenant_id = ? AND lower(email) = lower(?)
+                     ON CONFLICT DO NOTHING",
+                )
+                .bind::<Text, _>(&tag)
+                .bind::<Text, _>(&now)
+                .bind::<Text, _>(tenant.as_str())
+                .bind::<Text, _>(email)
+                .execute(conn)?;
+            }
+            Ok(tagged)
+        })
+        .await
+    }
+
+    async fn untag(&self, emails: &[String], tag: &str) -> Result<usize> {
+        let emails = emails.to_vec();
+        let tag = tag.to_string();
+        self.run("subscriber_tags_table", "DELETE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let mut untagged = 0;
+            for email in &emails {
+                untagged += diesel::sql_query("DELETE FROM subscriber_tags WHERE tenant_id = ? AND email = ? AND tag = ?")
+                    .bind::<Text, _>(tenant.as_str())
+                    .bind::<Text, _>(email)
+                    .bind::<Text, _>(&tag)
+                    .execute(conn)?;
+            }
+            Ok(untagged)
+        })
+        .await
+    }
+
+    async fn list_by_tag(&self, tag: &str, page: PageRequest) -> Result<Page<Newsletter>> {
+        let tag = tag.to_string();
+        self.run("newsletter_table", "READ", move |conn, scope| {
+            let rows: Vec<NewsletterRow> = diesel::sql_query(format!(
+                "SELECT {NEWSLETTER_COLUMNS} FROM newsletters n
+                 WHERE n.tenant_id = ?
+                   AND EXISTS (
+                       SELECT 1 FROM subscriber_tags t
+                       WHERE t.tenant_id = n.tenant_id AND t.email = n.email AND t.tag = ?
+                   )
+                   AND (? IS NULL OR n.id < ?)
+                 ORDER BY n.id DESC LIMIT ?"
+            ))
+            .bind::<Text, _>(tenant_of(scope).as_str())
+            .bind::<Text, _>(&tag)
+            .bind::<Nullable<BigInt>, _>(page.after)
+            .bind::<Nullable<BigInt>, _>(page.after)
+            .bind::<BigInt, _>(page.limit + 1)
+            .load(conn)?;
+
+            keyset_page(rows, page.limit, |r| r.id, NewsletterRow::newsletter)
+        })
+        .await
+    }
+
+    async fn get_preferences(&self, email: &str) -> Result<Option<Vec<TopicSubscription>>> {
+        let email = email.to_string();
+        self.run("subscriber_topics_table", "READ", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let Some(row) = find(conn, &tenant, &email)? else {
+                return Ok(None);
+            };
+
+            let choices: Vec<TopicChoiceRow> = diesel::sql_query(
+                "SELECT t.key, t.name, t.description, t.default_subscribed, t.requires_confirmation, s.subscribed
+                 FROM topics t
+                 LEFT JOIN subscriber_topics s ON s.topic = t.key AND s.tenant_id = ? AND s.email = ?
+                 ORDER BY t.key",
+            )
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&row.email)
+            .load(conn)?;
+
+            Ok(Some(
+                choices
+                    .into_iter()
+                    .map(|choice| TopicSubscription::resolve(choice.topic.into(), choice.subscribed))
+                    .collect(),
+            ))
+        })
+        .await
+    }
+
+    async fn set_preferences(&self, email: &str, preferences: &[TopicPreference]) -> Result<bool> {
+        let email = email.to_string();
+        let preferences = preferences.to_vec();
+        self.run("subscriber_topics_table", "UPDATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let Some(row) = find(conn, &tenant, &email)? else {
+                return Ok(false);
+            };
+
+            let topics: Vec<TopicRow> =
+                diesel::sql_query("SELECT key, name, description, default_subscribed, requires_confirmation FROM topics")
+                    .load(conn)?;
+            if let Some(unknown) = preferences.iter().find(|p| !topics.iter().any(|t| t.key == p.topic)) {
+                return Err(PreferencesError::UnknownTopic(unknown.topic.clone()).into());
+            }
+
+            let now = timestamp(Utc::now());
+            for preference in &preferences {
+                diesel::sql_query(
+                    "INSERT INTO subscriber_topics (tenant_id, email, topic, subscribed, updated_at)
+                     VALUES (?, ?, ?, ?, ?)
+                     ON CONFLICT (tenant_id, email, topic)
+                     DO UPDATE SET subscribed = excluded.subscribed, updated_at = excluded.updated_at",
+                )
+                .bind::<Text, _>(tenant.as_str())
+                .bind::<Text, _>(&row.email)
+                .bind::<Text, _>(&preference.topic)
+                .bind::<Bool, _>(preference.subscribed)
+                .bind::<Text, _>(&now)
+                .execute(conn)?;
+            }
+            Ok(true)
+        })
+        .await
+    }
+
+    async fn list_topics(&self) -> Result<Vec<Topic>> {
+        self.run("topics_table", "READ", move |conn, _| {
+            let rows: Vec<TopicRow> =
+                diesel::sql_query(
+                    "SELECT key, name, description, default_subscribed, requires_confirmation FROM topics ORDER BY key",
+                )
+                .load(conn)?;
+            Ok(rows.into_iter().map(Topic::from).collect())
+        })
+        .await
+    }
+
+    async fn set_topic_requires_confirmation(&self, topic: &str, required: bool) -> Result<bool> {
+        let topic = topic.to_string();
+        self.run("topics_table", "UPDATE", move |conn, _| {
+            let updated = diesel::sql_query("UPDATE topics SET requires_confirmation = ? WHERE key = ?")
+                .bind::<Bool, _>(required)
+                .bind::<Text, _>(&topic)
+                .execute(conn)?;
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    async fn list_attribute_definitions(&self) -> Result<Vec<AttributeDefinition>> {
+        self.run("attribute_definitions_table", "READ", move |conn, _| {
+            let rows: Vec<AttributeDefinitionRow> =
+                diesel::sql_query("SELECT key, kind, description FROM attribute_definitions ORDER BY key").load(conn)?;
+            rows.into_iter().map(AttributeDefinition::try_from).collect()
+        })
+        .await
+    }
+
+    async fn define_attribute(&self, definition: &AttributeDefinition) -> Result<bool> {
+        let definition = definition.clone();
+        self.run("attribute_definitions_table", "CREATE", move |conn, _| {
+            let inserted = diesel::sql_query(
+                "INSERT INTO attribute_definitions (key, kind, description) VALUES (?, ?, ?) ON CONFLICT (key) DO NOTHING",
+            )
+            .bind::<Text, _>(&definition.key)
+            .bind::<Text, _>(definition.kind.as_str())
+            .bind::<Text, _>(&definition.description)
+            .execute(conn)?;
+            Ok(inserted > 0)
+        })
+        .await
+    }
+
+    async fn get_attributes(&self, email: &str) -> Result<Option<Attributes>> {
+        let email = email.to_string();
+        self.run("newsletter_table", "READ", move |conn, scope| {
+            find(conn, &tenant_of(scope), &email)?
+                .map(|row| parse_attributes(&row.attributes))
+                .transpose()
+        })
+        .await
+    }
+
+    async fn set_attributes(&self, email: &str, changes: &Attributes) -> Result<Option<Attributes>> {
+        let email = email.to_string();
+        let changes = changes.clone();
+        self.run("newsletter_table", "UPDATE", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let Some(row) = find_exact(conn, &tenant, &email)? else {
+                return Ok(None);
+            };
+
+            let mut attributes = parse_attributes(&row.attributes)?;
+            for (key, value) in changes {
+                if value.is_null() {
+                    attributes.remove(&key);
+                } else {
+                    attributes.insert(key, value);
+                }
+            }
+
+            diesel::sql_query("UPDATE newsletters SET attributes = ? WHERE id = ?")
+                .bind::<Text, _>(Value::Object(attributes.clone()).to_string())
+                .bind::<BigInt, _>(row.id)
+                .execute(conn)?;
+            Ok(Some(attributes))
+        })
+        .await
+    }
+
+    async fn set_locale(&self, email: &str, locale: Option<&str>) -> Result<bool> {
+        let email = email.to_string();
+        let locale = locale.map(str::to_string);
+        self.run("newsletter_table", "UPDATE", move |conn, scope| {
+            let updated = diesel::sql_query("UPDATE newsletters SET locale = ? WHERE tenant_id = ? AND email = ?")
+                .bind::<Nullable<Text>, _>(locale)
+                .bind::<Text, _>(tenant_of(scope).as_str())
+                .bind::<Text, _>(&email)
+                .execute(conn)?;
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    async fn set_timezone(&self, email: &str, timezone: Option<&str>) -> Result<bool> {
+        let email = email.to_string();
+        let timezone = timezone.map(str::to_string);
+        self.run("newsletter_table", "UPDATE", move |conn, scope| {
+            let updated = diesel::sql_query("UPDATE newsletters SET timezone = ? WHERE tenant_id = ? AND email = ?")
+                .bind::<Nullable<Text>, _>(timezone)
+                .bind::<Text, _>(tenant_of(scope).as_str())
+                .bind::<Text, _>(&email)
+                .execute(conn)?;
+            Ok(updated > 0)
+        })
+        .await
+    }
+
+    async fn export(&self, email: &str) -> Result<SubscriberExport> {
+        let audit_email = self.audit_email(email).into_owned();
+        let email = email.to_string();
+        self.run("newsletter_table", "READ", move |conn, scope| {
+            let tenant = tenant_of(scope);
+            let subscription = find(conn, &tenant, &email)?.map(NewsletterRow::record).transpose()?;
+
+            let tags: Vec<TagRow> =
+                diesel::sql_query("SELECT tag, created_at FROM subscriber_tags WHERE tenant_id = ? AND email = ? ORDER BY tag")
+                    .bind::<Text, _>(tenant.as_str())
+                    .bind::<Text, _>(&email)
+                    .load(conn)?;
+            let tokens: Vec<TokenRow> = diesel::sql_query(
+                "SELECT created_at, expires_at FROM confirmation_tokens WHERE tenant_id = ? AND email = ? ORDER BY created_at",
+            )
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&email)
+            .load(conn)?;
+            let unsubscribes: Vec<UnsubscribeEventRow> = diesel::sql_query(
+                "SELECT id, email, reason, comment, created_at FROM unsubscribe_events
+                 WHERE tenant_id = ? AND (email = ? OR email = ?)
+                 ORDER BY id",
+            )
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&email)
+            .bind::<Text, _>(&audit_email)
+            .load(conn)?;
+            let merges: Vec<SubscriberMergeRow> = diesel::sql_query(
+                "SELECT email, merged_email, merged_status, status, created_at FROM subscriber_merges
+                 WHERE tenant_id = ? AND (email = ? OR merged_email = ?)
+                 ORDER BY id",
+            )
+            .bind::<Text, _>(tenant.as_str())
+            .bind::<Text, _>(&audit_email)
+            .bind::<Text, _>(&audit_email)
+            .load(conn)?;
+
+            Ok(SubscriberExport {
+                subscription,
+                tags: tags
+                    .into_iter()
+                    .map(|row| {
+                        Ok(TagRecord {
+                            created_at: parse_timestamp(&row.created_at)?,
+                            tag: row.tag,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+                pending_confirmations: tokens
+                    .into_iter()
+                    .map(|row| {
+                        Ok(PendingConfirmation {
+                            created_at: parse_timestamp(&row.created_at)?,
+                            expires_at: parse_timestamp(&row.expires_at)?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+                unsubscribes: unsubscribes
+                    .into_iter()
+                    .map(|row| {
+                        Ok(UnsubscribeEvent {
+                            email: email.clone(),
+                            ..UnsubscribeEvent::try_from(row)?
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+                merges: merges.into_iter().map(SubscriberMerge::try_from).collect::<Result<_>>()?,
+                email,
+            })
+        })
+        .await
+    }
+
+    /// One `BEGIN IMMEDIATE` transaction, during which calls from outside
+    /// the unit wait
+    async fn with_transaction<T, F>(&self, work: F) -> Result<T>
+    where
+        T: Send,
+        F: Future<Output = Result<T>> + Send,
+    {
+        if UNIT.try_with(|_| ()).is_ok() {
+            return work.await;
+        }
+
+        let _unit = self.units.lock().await;
+        self.blocking(|conn| {
+            close_abandoned_unit(conn);
+            Ok(AnsiTransactionManager::begin_transaction_sql(conn, "BEGIN IMMEDIATE")?)
+        })
+        .await?;
+        let result = UNIT.scope((), work).await;
+        let committed = result.is_ok();
+        let finished = self
+            .blocking(move |conn| {
+                if committed {
+                    Ok(AnsiTransactionManager::commit_transaction(conn)?)
+                } else {
+                    Ok(AnsiTransactionManager::rollback_transaction(conn)?)
+                }
+            })
+            .await;
+        if let Err(e) = &finished {
+            error!(entity = "newsletter_table", error = %e, "Failed to finish unit of work");
+        }
+        let value = result?;
+        finished?;
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for SqliteNewsletterRepository {
+    async fn claim(&self, limit: i64, lease: Duration) -> anyhow::Result<Vec<OutboxMessage>> {
+        let messages = self
+            .run("outbox_table", "UPDATE", move |conn, _| {
+                let now = Utc::now();
+                let mut rows: Vec<OutboxRow> = diesel::sql_query(CLAIM_OUTBOX_QUERY)
+                    .bind::<Text, _>(timestamp(now + lease))
+                    .bind::<Text, _>(timestamp(now))
+                    .bind::<BigInt, _>(limit)
+                    .load(conn)?;
+                rows.sort_by_key(|row| row.id);
+
+                Ok(rows
+                    .into_iter()
+                    .filter_map(|row| match serde_json::from_str(&row.payload) {
+                        Ok(event) => Some(OutboxMessage { id: row.id, event, attempts: row.attempts }),
+                        Err(e) => {
+                            // Left leased; it comes back after the lease and is logged again
+                            error!(entity = "outbox_table", id = row.id, error = %e, "Skipping outbox message with an unreadable payload");
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .await?;
+        Ok(messages)
+    }
+
+    async fn mark_sent(&self, ids: &[i64]) -> anyhow::Result<()> {
+        let ids = ids.to_vec();
+        self.run("outbox_table", "UPDATE", move |conn, _| {
+            let now = timestamp(Utc::now());
+            for id in ids {
+                diesel::sql_query("UPDATE outbox SET sent_at = ? WHERE id = ?")
+                    .bind::<Text, _>(&now)
+                    .bind::<BigInt, _>(id)
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: i64, last_error: &str, retry_at: DateTime<Utc>) -> anyhow::Result<()> {
+        let last_error = last_error.to_string();
+        self.run("outbox_table", "UPDATE", move |conn, _| {
+            diesel::sql_query("UPDATE outbox SET attempts = attempts + 1, last_error = ?, available_at = ? WHERE id = ?")
+                .bind::<Text, _>(&last_error)
+                .bind::<Text, _>(timestamp(retry_at))
+                .bind::<BigInt, _>(id)
+                .execute(conn)?;
+            Ok(())
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn purge_sent(&self, before: DateTime<Utc>) -> anyhow::Result<usize> {
+        let purged = self
+            .run("outbox_table", "DELETE", move |conn, _| {
+                Ok(diesel::sql_query("DELETE FROM outbox WHERE sent_at < ?")
+                    .bind::<Text, _>(timestamp(before))
+                    .execute(conn)?)
+            })
+            .await?;
+        Ok(purged)
+    }
+
+    async fn replay(&self, since: DateTime<Utc>) -> anyhow::Result<usize> {
+        let replayed = self
+            .run("outbox_table", "UPDATE", move |conn, _| {
+                Ok(diesel::sql_query(
+                    "UPDATE outbox SET sent_at = NULL, attempts = 0, last_error = NULL, available_at = ? WHERE sent_at >= ?",
+                )
+                .bind::<Text, _>(timestamp(Utc::now()))
+                .bind::<Text, _>(timestamp(since))
+                .execute(conn)?)
+            })
+            .await?;
+        Ok(replayed)
+    }
+
+    async fn backlog(&self) -> anyhow::Result<OutboxBacklog> {
+        let backlog = self
+            .run("outbox_table", "READ", move |conn, _| {
+                let row: OutboxBacklogRow = diesel::sql_query(
+                    "SELECT count(*) AS pending, coalesce(sum(attempts > 0), 0) AS failing, min(created_at) AS oldest_pending_at
+                     FROM outbox WHERE sent_at IS NULL",
+                )
+                .get_result(conn)?;
+                Ok(OutboxBacklog {
+                    pending: row.pending,
+                    failing: row.failing,
+                    oldest_pending_at: parse_optional_timestamp(row.oldest_pending_at.as_deref())?,
+                })
+            })
+            .await?;
+        Ok(backlog)
+    }
+}
+
+#[async_trait]
+impl IdempotencyRepository for SqliteNewsletterRepository {
+    async fn claim(
+        &self,
+        key: &str,
+        operation: &str,
+        request_hash: &str,
+        expired_before: DateTime<Utc>,
+    ) -> anyhow::Result<Claim> {
+        let (key, operation, request_hash) = (key.to_string(), operation.to_string(), request_hash.to_string());
+        let claim = self
+            .run("idempotency_keys_table", "CREATE", move |conn, _| {
+                diesel::sql_query("DELETE FROM idempotency_keys WHERE key = ? AND operation = ? AND created_at < ?")
+                    .bind::<Text, _>(&key)
+                    .bind::<Text, _>(&operation)
+                    .bind::<Text, _>(timestamp(expired_before))
+                    .execute(conn)?;
+
+                let inserted = diesel::sql_query(
+                    "INSERT INTO idempotency_keys (key, operation, request_hash, created_at) VALUES (?, ?, ?, ?)
+                     ON CONFLICT (key, operation) DO NOTHING",
+                )
+                .bind::<Text, _>(&key)
+                .bind::<Text, _>(&operation)
+                .bind::<Text, _>(&request_hash)
+                .bind::<Text, _>(timestamp(Utc::now()))
+                .execute(conn)?;
+                if inserted == 1 {
+                    return Ok(Claim::Acquired);
+                }
+
+                let row: IdempotencyRow =
+                    diesel::sql_query("SELECT request_hash, response FROM idempotency_keys WHERE key = ? AND operation = ?")
+                        .bind::<Text, _>(&key)
+                        .bind::<Text, _>(&operation)
+                        .get_result(conn)?;
+                let response = row
+                    .response
+                    .map(|response| serde_json::from_str(&response))
+                    .transpose()
+                    .map_err(NewsletterError::database)?;
+                Ok(Claim::Existing(IdempotencyRecord {
+                    request_hash: row.request_hash,
+                    response,
+                }))
+            })
+            .await?;
+        Ok(claim)
+    }
+
+    async fn complete(&self, key: &str, operation: &str, response: &Value) -> anyhow::Result<()> {
+        let (key, operation, response) = (key.to_string(), operation.to_string(), response.to_string());
+        self.run("idempotency_keys_table", "UPDATE", move |conn, _| {
+            diesel::sql_query("UPDATE idempotency_keys SET response = ? WHERE key = ? AND operation = ?")
+                .bind::<Text, _>(&response)
+                .bind::<Text, _>(&key)
+                .bind::<Text, _>(&operation)
+                .execute(conn)?;
+            Ok(())
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn release(&self, key: &str, operation: &str) -> anyhow::Result<()> {
+        let (key, operation) = (key.to_string(), operation.to_string());
+        self.run("idempotency_keys_table", "DELETE", move |conn, _| {
+            diesel::sql_query("DELETE FROM idempotency_keys WHERE key = ? AND operation = ? AND response IS NULL")
+                .bind::<Text, _>(&key)
+                .bind::<Text, _>(&operation)
+                .execute(conn)?;
+            Ok(())
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn purge_expired(&self, expired_before: DateTime<Utc>) -> anyhow::Result<usize> {
+        let purged = self
+            .run("idempotency_keys_table", "DELETE", move |conn, _| {
+                Ok(diesel::sql_query("DELETE FROM idempotency_keys WHERE created_at < ?")
+                    .bind::<Text, _>(timestamp(expired_before))
+                    .execute(conn)?)
+            })
+            .await?;
+        Ok(purged)
+    }
+}
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::domain::outbox::{OutboxBacklog, OutboxMessage};
+
+pub mod postgres;
+
+/// Repository trait for the transactional outbox. Messages are written by the
+/// repositories that make the changes; this trait is the relay's side.
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    /// Take up to `limit` due messages, oldest first, hiding them from other
+    /// relays for `lease`
+    async fn claim(&self, limit: i64, lease: Duration) -> Result<Vec<OutboxMessage>>;
+
+    /// Record that messages were published
+    async fn mark_sent(&self, ids: &[i64]) -> Result<()>;
+
+    /// Record a failed attempt and when to try again
+    async fn mark_failed(&self, id: i64, error: &str, retry_at: DateTime<Utc>) -> Result<()>;
+
+    /// Delete messages published before `before`; returns the number deleted
+    async fn purge_sent(&self, before: DateTime<Utc>) -> Result<usize>;
+
+    /// Queue messages published at or after `since` to be published again,
+    /// with their attempts reset; returns the number requeued. Only messages
+    /// still within the retention can be replayed.
+    async fn replay(&self, since: DateTime<Utc>) -> Result<usize>;
+
+    /// Count the messages still waiting to be published
+    async fn backlog(&self) -> Result<OutboxBacklog>;
+}
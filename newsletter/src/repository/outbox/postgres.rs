@@ -0,0 +1,324 @@
+use crate::domain::newsletter::SubscriptionEvent;
+use crate::domain::outbox::{OutboxBacklog, OutboxMessage};
+use crate::infrastructure::db::db_schema::{outbox, subscriber_events};
+use crate::infrastructure::db::PgPool;
+use crate::infrastructure::tenant;
+use crate::repository::outbox::OutboxRepository;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Timestamptz};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use serde_json::Value;
+use tracing::{error, info, instrument};
+
+#[derive(Insertable)]
+#[diesel(table_name = outbox)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewOutboxRow<'a> {
+    pub event_type: &'a str,
+    pub payload: Value,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = subscriber_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewHistoryRow<'a> {
+    pub email: &'a str,
+    pub previous_email: Option<&'a str>,
+    pub event_type: &'a str,
+    pub status: Option<&'a str>,
+    pub payload: Value,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(QueryableByName)]
+struct BacklogRow {
+    #[diesel(sql_type = BigInt)]
+    pending: i64,
+    #[diesel(sql_type = BigInt)]
+    failing: i64,
+    #[diesel(sql_type = Nullable<Timestamptz>)]
+    oldest_pending_at: Option<DateTime<Utc>>,
+}
+
+/// Write events to the outbox, and to the subscriber history, on a
+/// connection the caller holds, so they commit or roll back together with
+/// the change they describe
+pub(crate) async fn enqueue(conn: &mut AsyncPgConnection, events: &[SubscriptionEvent]) -> QueryResult<()> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    let tenant = tenant::current().tenant().cloned();
+    let events = events
+        .iter()
+        .map(|event| {
+            let event = SubscriptionEvent {
+                tenant: tenant.clone(),
+                ..event.clone()
+            };
+            let payload = serde_json::to_value(&event)
+                .map_err(|e| diesel::result::Error::SerializationError(Box::new(e)))?;
+            Ok((event, payload))
+        })
+        .collect::<QueryResult<Vec<_>>>()?;
+
+    let rows: Vec<NewOutboxRow> = events
+        .iter()
+        .map(|(event, payload)| NewOutboxRow {
+            event_type: event.kind.as_str(),
+            payload: payload.clone(),
+        })
+        .collect();
+    diesel::insert_into(outbox::table)
+        .values(&rows)
+        .execute(conn)
+        .await?;
+
+    let history: Vec<NewHistoryRow> = events
+        .iter()
+        .map(|(event, payload)| NewHistoryRow {
+            email: &event.email,
+            previous_email: event.previous_email.as_deref(),
+            event_type: event.kind.as_str(),
+            status: event.status.as_ref().map(|status| status.as_str()),
+            payload: payload.clone(),
+            occurred_at: event.occurred_at,
+        })
+        .collect();
+    diesel::insert_into(subscriber_events::table)
+        .values(&history)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// PostgreSQL implementation of the OutboxRepository trait
+#[derive(Clone)]
+pub struct PostgresOutboxRepository {
+    pool: PgPool,
+}
+
+impl PostgresOutboxRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for PostgresOutboxRepository {
+    /// Rows locked by another relay are skipped rather than waited on, and the
+    /// lease keeps them hidden after this transaction commits
+    #[instrument(skip(self))]
+    async fn claim(&self, limit: i64, lease: Duration) -> Result<Vec<OutboxMessage>> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let now = Utc::now();
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let ids: Vec<i64> = outbox::table
+                        .filter(outbox::sent_at.is_null())
+                        .filter(outbox::available_at.le(now))
+                        .order((outbox::available_at.asc(), outbox::id.asc()))
+                        .limit(limit)
+                        .select(outbox::id)
+                        .for_update()
+                        .skip_locked()
+                        .load(conn)
+                        .await?;
+
+                    if ids.is_empty() {
+                        return Ok(Vec::new());
+                    }
+
+                    diesel::update(outbox::table.filter(outbox::id.eq_any(&ids)))
+                        .set(outbox::available_at.eq(now + lease))
+                        .returning((outbox::id, outbox::payload, outbox::attempts))
+                        .get_results::<(i64, Value, i32)>(conn)
+                        .await
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        let mut rows = match result {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "UPDATE", error = %e, "Failed to claim outbox messages");
+                return Err(e.into());
+            }
+        };
+        rows.sort_by_key(|(id, _, _)| *id);
+
+        let messages: Vec<OutboxMessage> = rows
+            .into_iter()
+            .filter_map(|(id, payload, attempts)| match serde_json::from_value(payload) {
+                Ok(event) => Some(OutboxMessage { id, event, attempts }),
+                Err(e) => {
+                    // Left leased; it comes back after the lease and is logged again
+                    error!(entity = "outbox_table", id = id, error = %e, "Skipping outbox message with an unreadable payload");
+                    None
+                }
+            })
+            .collect();
+
+        if !messages.is_empty() {
+            info!(entity = "outbox_table", crud_operation = "UPDATE", count = messages.len(), "Claimed outbox messages");
+        }
+        Ok(messages)
+    }
+
+    #[instrument(skip(self, ids), fields(count = ids.len()))]
+    async fn mark_sent(&self, ids: &[i64]) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::update(outbox::table.filter(outbox::id.eq_any(ids)))
+            .set(outbox::sent_at.eq(Utc::now()))
+            .execute(&mut conn)
+            .await
+        {
+            Ok(rows_affected) => {
+                info!(entity = "outbox_table", crud_operation = "UPDATE", rows_affected = rows_affected, "Marked outbox messages sent");
+                Ok(())
+            }
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "UPDATE", error = %e, "Failed to mark outbox messages sent");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, last_error), fields(id = id, retry_at = %retry_at))]
+    async fn mark_failed(&self, id: i64, last_error: &str, retry_at: DateTime<Utc>) -> Result<()> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::update(outbox::table.find(id))
+            .set((
+                outbox::attempts.eq(outbox::attempts + 1),
+                outbox::last_error.eq(last_error),
+                outbox::available_at.eq(retry_at),
+            ))
+            .execute(&mut conn)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "UPDATE", error = %e, "Failed to record outbox publish failure");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(before = %before))]
+    async fn purge_sent(&self, before: DateTime<Utc>) -> Result<usize> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "DELETE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::delete(outbox::table.filter(outbox::sent_at.lt(before)))
+            .execute(&mut conn)
+            .await
+        {
+            Ok(rows_affected) => {
+                info!(entity = "outbox_table", crud_operation = "DELETE", rows_affected = rows_affected, "Purged sent outbox messages");
+                Ok(rows_affected)
+            }
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "DELETE", error = %e, "Failed to purge sent outbox messages");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(since = %since))]
+    async fn replay(&self, since: DateTime<Utc>) -> Result<usize> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::update(outbox::table.filter(outbox::sent_at.ge(since)))
+            .set((
+                outbox::sent_at.eq(None::<DateTime<Utc>>),
+                outbox::attempts.eq(0),
+                outbox::last_error.eq(None::<String>),
+                outbox::available_at.eq(Utc::now()),
+            ))
+            .execute(&mut conn)
+            .await
+        {
+            Ok(rows_affected) => {
+                info!(entity = "outbox_table", crud_operation = "UPDATE", rows_affected = rows_affected, "Requeued outbox messages for replay");
+                Ok(rows_affected)
+            }
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "UPDATE", error = %e, "Failed to requeue outbox messages");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn backlog(&self) -> Result<OutboxBacklog> {
+        let mut conn = match self.pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        match diesel::sql_query(
+            "SELECT count(*) AS pending, count(*) FILTER (WHERE attempts > 0) AS failing, min(created_at) AS oldest_pending_at
+             FROM outbox WHERE sent_at IS NULL",
+        )
+        .get_result::<BacklogRow>(&mut conn)
+        .await
+        {
+            Ok(row) => Ok(OutboxBacklog {
+                pending: row.pending,
+                failing: row.failing,
+                oldest_pending_at: row.oldest_pending_at,
+            }),
+            Err(e) => {
+                error!(entity = "outbox_table", crud_operation = "READ", error = %e, "Failed to count pending outbox messages");
+                Err(e.into())
+            }
+        }
+    }
+}
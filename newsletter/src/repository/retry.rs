@@ -0,0 +1,168 @@
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tracing::warn;
+
+use crate::infrastructure::db;
+
+/// What a budget gains back per successful call: one retry per ten calls
+const BUDGET_REFILL_PER_SUCCESS: f64 = 0.1;
+
+/// Errors a [`Retrier`] can tell apart
+pub trait Transient {
+    /// Whether the same call may succeed if made again: serialization
+    /// failures, dropped connections, pool timeouts
+    fn is_transient(&self) -> bool;
+}
+
+/// Exponential backoff with full jitter for repository calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts per call, the first included; 1 disables retries
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Retries that can be spent back to back; successful calls refill it
+    pub budget: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(1),
+            budget: 10,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Random wait before `attempt + 1`, up to the exponential ceiling, so
+    /// calls that failed together do not retry together
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let ceiling = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        let random = RandomState::new().build_hasher().finish();
+        ceiling.mul_f64((random >> 11) as f64 / (1u64 << 53) as f64)
+    }
+}
+
+/// Retry counters since the last [`Retrier::take_counts`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryCounts {
+    /// Calls made again after a transient error
+    pub retries: u64,
+    /// Calls that succeeded after at least one retry
+    pub recovered: u64,
+    /// Calls that still failed on their last attempt
+    pub exhausted: u64,
+    /// Retries skipped because the budget was spent
+    pub over_budget: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    retries: AtomicU64,
+    recovered: AtomicU64,
+    exhausted: AtomicU64,
+    over_budget: AtomicU64,
+}
+
+/// Runs idempotent calls again when they fail with a transient error.
+///
+/// Retries are capped per call by the policy's attempts and across calls by
+/// a budget, so an outage does not multiply the load on the database.
+#[derive(Debug)]
+pub struct Retrier {
+    policy: RetryPolicy,
+    budget: Mutex<f64>,
+    counters: Counters,
+}
+
+impl Retrier {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+            budget: Mutex::new(f64::from(policy.budget)),
+            counters: Counters::default(),
+        }
+    }
+
+    pub fn policy(&self) -> RetryPolicy {
+        self.policy
+    }
+
+    /// Run `call` until it succeeds, fails for good, runs out of attempts or
+    /// the budget refuses another retry
+    pub async fn run<T, E, F, Fut>(&self, operation: &'static str, mut call: F) -> Result<T, E>
+    where
+        E: Transient + std::fmt::Display,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match call().await {
+                Ok(value) => {
+                    self.refill();
+                    if attempt > 1 {
+                        self.counters.recovered.fetch_add(1, Ordering::Relaxed);
+                    }
+                    return Ok(value);
+                }
+                // The error aborted the unit of work the call is part of
+                Err(e) if db::in_unit_of_work() => return Err(e),
+                Err(e) if e.is_transient() => {
+                    if attempt >= self.policy.max_attempts {
+                        self.counters.exhausted.fetch_add(1, Ordering::Relaxed);
+                        return Err(e);
+                    }
+                    if !self.withdraw() {
+                        self.counters.over_budget.fetch_add(1, Ordering::Relaxed);
+                        warn!(operation = operation, attempt = attempt, error = %e, "Retry budget spent, not retrying");
+                        return Err(e);
+                    }
+                    let delay = self.policy.delay(attempt);
+                    warn!(operation = operation, attempt = attempt, delay_ms = delay.as_millis() as u64, error = %e, "Transient database error, retrying");
+                    self.counters.retries.fetch_add(1, Ordering::Relaxed);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Counters since the previous call, which resets them
+    pub fn take_counts(&self) -> RetryCounts {
+        RetryCounts {
+            retries: self.counters.retries.swap(0, Ordering::Relaxed),
+            recovered: self.counters.recovered.swap(0, Ordering::Relaxed),
+            exhausted: self.counters.exhausted.swap(0, Ordering::Relaxed),
+            over_budget: self.counters.over_budget.swap(0, Ordering::Relaxed),
+        }
+    }
+
+    fn budget(&self) -> std::sync::MutexGuard<'_, f64> {
+        self.budget.lock().expect("retry budget lock poisoned")
+    }
+
+    fn withdraw(&self) -> bool {
+        let mut budget = self.budget();
+        if *budget < 1.0 {
+            return false;
+        }
+        *budget -= 1.0;
+        true
+    }
+
+    fn refill(&self) {
+        let mut budget = self.budget();
+        *budget = (*budget + BUDGET_REFILL_PER_SUCCESS).min(f64::from(self.policy.budget));
+    }
+}
@@ -0,0 +1,28 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use crate::domain::campaign::sending_domain::{GeneratedKey, NewSendingDomain, SendingDomain};
+
+pub mod postgres;
+
+/// Repository trait for the current tenant's sending domain and its DKIM keys
+#[async_trait]
+pub trait SendingDomainRepository: Send + Sync {
+    /// The tenant's sending domain with its keys
+    async fn get(&self) -> Result<Option<SendingDomain>>;
+
+    /// Persist a domain with its first key, pending; returns `None` if the
+    /// tenant already has a domain
+    async fn create(&self, domain: &NewSendingDomain, key: &GeneratedKey) -> Result<Option<SendingDomain>>;
+
+    /// Make `key` the domain's pending key, dropping any earlier pending one;
+    /// returns `None` if the domain no longer exists
+    async fn replace_pending_key(&self, domain_id: i64, key: &GeneratedKey) -> Result<Option<SendingDomain>>;
+
+    /// Activate the pending key `key_id` in place of the active one and mark
+    /// the domain verified; returns `None` if the key is no longer pending
+    async fn activate_key(&self, domain_id: i64, key_id: i64, at: DateTime<Utc>) -> Result<Option<SendingDomain>>;
+
+    /// Delete a domain and its keys; returns whether it existed
+    async fn delete(&self, id: i64) -> Result<bool>;
+}
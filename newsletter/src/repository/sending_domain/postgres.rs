@@ -0,0 +1,347 @@
+use crate::domain::campaign::sending_domain::{DkimKey, DkimKeyStatus, GeneratedKey, NewSendingDomain, SendingDomain};
+use crate::infrastructure::db::db_schema::{dkim_keys, sending_domains};
+use crate::infrastructure::db::{tenant_connection, PgPool};
+use crate::repository::sending_domain::SendingDomainRepository;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::DatabaseErrorKind;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use tracing::{error, info, instrument};
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = sending_domains)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct DomainRow {
+    pub id: i64,
+    pub domain: String,
+    pub from_address: String,
+    pub created_at: DateTime<Utc>,
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = sending_domains)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewDomainRow<'a> {
+    pub domain: &'a str,
+    pub from_address: &'a str,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = dkim_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct KeyRow {
+    pub id: i64,
+    pub selector: String,
+    pub private_key: String,
+    pub public_key: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub activated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = dkim_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewKeyRow<'a> {
+    pub domain_id: i64,
+    pub selector: &'a str,
+    pub private_key: &'a str,
+    pub public_key: &'a str,
+    pub status: &'static str,
+}
+
+impl<'a> NewKeyRow<'a> {
+    fn pending(domain_id: i64, key: &'a GeneratedKey) -> Self {
+        Self {
+            domain_id,
+            selector: &key.selector,
+            private_key: &key.private_key,
+            public_key: &key.public_key,
+            status: DkimKeyStatus::Pending.as_str(),
+        }
+    }
+}
+
+impl TryFrom<KeyRow> for DkimKey {
+    type Error = anyhow::Error;
+
+    fn try_from(row: KeyRow) -> Result<Self> {
+        let status = DkimKeyStatus::parse(&row.status)
+            .ok_or_else(|| anyhow::anyhow!("unknown DKIM key status {:?} for key {}", row.status, row.id))?;
+        Ok(Self {
+            id: row.id,
+            selector: row.selector,
+            private_key: row.private_key,
+            public_key: row.public_key,
+            status,
+            created_at: row.created_at,
+            activated_at: row.activated_at,
+        })
+    }
+}
+
+fn to_domain(row: DomainRow, keys: Vec<KeyRow>) -> Result<SendingDomain> {
+    let mut domain = SendingDomain {
+        id: row.id,
+        domain: row.domain,
+        from_address: row.from_address,
+        created_at: row.created_at,
+        verified_at: row.verified_at,
+        active_key: None,
+        pending_key: None,
+    };
+    for key in keys {
+        let key = DkimKey::try_from(key)?;
+        match key.status {
+            DkimKeyStatus::Active => domain.active_key = Some(key),
+            DkimKeyStatus::Pending => domain.pending_key = Some(key),
+        }
+    }
+    Ok(domain)
+}
+
+/// A domain row with its keys
+async fn load(conn: &mut AsyncPgConnection, domain: Option<DomainRow>) -> QueryResult<Option<(DomainRow, Vec<KeyRow>)>> {
+    let Some(domain) = domain else {
+        return Ok(None);
+    };
+    let keys = dkim_keys::table
+        .filter(dkim_keys::domain_id.eq(domain.id))
+        .select(KeyRow::as_select())
+        .load(conn)
+        .await?;
+    Ok(Some((domain, keys)))
+}
+
+/// Lock a domain row so key changes to it happen one at a time
+async fn lock_domain(conn: &mut AsyncPgConnection, domain_id: i64) -> QueryResult<Option<DomainRow>> {
+    sending_domains::table
+        .find(domain_id)
+        .select(DomainRow::as_select())
+        .for_update()
+        .first(conn)
+        .await
+        .optional()
+}
+
+/// PostgreSQL implementation of the SendingDomainRepository trait
+#[derive(Clone)]
+pub struct PostgresSendingDomainRepository {
+    pool: PgPool,
+}
+
+impl PostgresSendingDomainRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SendingDomainRepository for PostgresSendingDomainRepository {
+    #[instrument(skip(self))]
+    async fn get(&self) -> Result<Option<SendingDomain>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "sending_domains_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let result = async {
+            let domain = sending_domains::table
+                .order(sending_domains::id.asc())
+                .select(DomainRow::as_select())
+                .first(&mut conn)
+                .await
+                .optional()?;
+            load(&mut conn, domain).await
+        }
+        .await;
+
+        match result {
+            Ok(found) => found.map(|(domain, keys)| to_domain(domain, keys)).transpose(),
+            Err(e) => {
+                error!(entity = "sending_domains_table", crud_operation = "READ", error = %e, "Failed to retrieve sending domain");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, domain, key), fields(domain = %domain.domain))]
+    async fn create(&self, domain: &NewSendingDomain, key: &GeneratedKey) -> Result<Option<SendingDomain>> {
+        info!(entity = "sending_domains_table", crud_operation = "CREATE", "Starting database create operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "sending_domains_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let row = diesel::insert_into(sending_domains::table)
+                        .values(&NewDomainRow {
+                            domain: &domain.domain,
+                            from_address: &domain.from_address,
+                        })
+                        .returning(DomainRow::as_returning())
+                        .get_result(conn)
+                        .await?;
+                    diesel::insert_into(dkim_keys::table)
+                        .values(&NewKeyRow::pending(row.id, key))
+                        .execute(conn)
+                        .await?;
+                    load(conn, Some(row)).await
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(Some((row, keys))) => {
+                info!(entity = "sending_domains_table", crud_operation = "CREATE", id = row.id, "Successfully created sending domain");
+                to_domain(row, keys).map(Some)
+            }
+            Ok(None) => Ok(None),
+            Err(diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                info!(entity = "sending_domains_table", crud_operation = "CREATE", "Tenant already has a sending domain");
+                Ok(None)
+            }
+            Err(e) => {
+                error!(entity = "sending_domains_table", crud_operation = "CREATE", error = %e, "Failed to create sending domain");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, key), fields(selector = %key.selector))]
+    async fn replace_pending_key(&self, domain_id: i64, key: &GeneratedKey) -> Result<Option<SendingDomain>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "dkim_keys_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let Some(row) = lock_domain(conn, domain_id).await? else {
+                        return Ok(None);
+                    };
+                    diesel::delete(
+                        dkim_keys::table
+                            .filter(dkim_keys::domain_id.eq(domain_id))
+                            .filter(dkim_keys::status.eq(DkimKeyStatus::Pending.as_str())),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::insert_into(dkim_keys::table)
+                        .values(&NewKeyRow::pending(domain_id, key))
+                        .execute(conn)
+                        .await?;
+                    load(conn, Some(row)).await
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(found) => {
+                info!(entity = "dkim_keys_table", crud_operation = "CREATE", domain_id = domain_id, "Replaced pending DKIM key");
+                found.map(|(row, keys)| to_domain(row, keys)).transpose()
+            }
+            Err(e) => {
+                error!(entity = "dkim_keys_table", crud_operation = "CREATE", domain_id = domain_id, error = %e, "Failed to replace pending DKIM key");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn activate_key(&self, domain_id: i64, key_id: i64, at: DateTime<Utc>) -> Result<Option<SendingDomain>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "dkim_keys_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    if lock_domain(conn, domain_id).await?.is_none() {
+                        return Ok(None);
+                    }
+                    let pending = dkim_keys::table
+                        .find(key_id)
+                        .filter(dkim_keys::domain_id.eq(domain_id))
+                        .filter(dkim_keys::status.eq(DkimKeyStatus::Pending.as_str()))
+                        .select(dkim_keys::id)
+                        .first::<i64>(conn)
+                        .await
+                        .optional()?;
+                    if pending.is_none() {
+                        return Ok(None);
+                    }
+
+                    diesel::delete(
+                        dkim_keys::table
+                            .filter(dkim_keys::domain_id.eq(domain_id))
+                            .filter(dkim_keys::status.eq(DkimKeyStatus::Active.as_str())),
+                    )
+                    .execute(conn)
+                    .await?;
+                    diesel::update(dkim_keys::table.find(key_id))
+                        .set((
+                            dkim_keys::status.eq(DkimKeyStatus::Active.as_str()),
+                            dkim_keys::activated_at.eq(at),
+                        ))
+                        .execute(conn)
+                        .await?;
+                    // The first key verified dates the domain
+                    diesel::update(
+                        sending_domains::table
+                            .find(domain_id)
+                            .filter(sending_domains::verified_at.is_null()),
+                    )
+                    .set(sending_domains::verified_at.eq(at))
+                    .execute(conn)
+                    .await?;
+                    let row = lock_domain(conn, domain_id).await?;
+                    load(conn, row).await
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(found) => {
+                info!(entity = "dkim_keys_table", crud_operation = "UPDATE", domain_id = domain_id, key_id = key_id, activated = found.is_some(), "Activated DKIM key");
+                found.map(|(row, keys)| to_domain(row, keys)).transpose()
+            }
+            Err(e) => {
+                error!(entity = "dkim_keys_table", crud_operation = "UPDATE", domain_id = domain_id, key_id = key_id, error = %e, "Failed to activate DKIM key");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: i64) -> Result<bool> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "sending_domains_table", crud_operation = "DELETE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::delete(sending_domains::table.find(id)).execute(&mut conn).await {
+            Ok(rows_affected) => {
+                info!(entity = "sending_domains_table", crud_operation = "DELETE", id = id, rows_affected = rows_affected, "Deleted sending domain");
+                Ok(rows_affected > 0)
+            }
+            Err(e) => {
+                error!(entity = "sending_domains_table", crud_operation = "DELETE", id = id, error = %e, "Failed to delete sending domain");
+                Err(e.into())
+            }
+        }
+    }
+}
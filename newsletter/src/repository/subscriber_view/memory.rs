@@ -0,0 +1,125 @@
+use std::sync::{Mutex, MutexGuard};
+
+use async_trait::async_trait;
+
+use crate::domain::newsletter::error::Result;
+use crate::domain::newsletter::lifecycle::SubscriptionStatus;
+use crate::domain::newsletter::stats::SubscriberStats;
+use crate::domain::newsletter::view::{SubscriberView, ViewChange};
+use crate::domain::newsletter::SubscriptionEvent;
+use crate::domain::pagination::{Page, PageRequest, StaleCursor};
+use crate::domain::tenant::TenantId;
+use crate::infrastructure::tenant;
+use crate::repository::subscriber_view::SubscriberViewRepository;
+
+#[derive(Debug, Clone)]
+struct Row {
+    id: i64,
+    tenant: TenantId,
+    view: SubscriberView,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_id: i64,
+    rows: Vec<Row>,
+}
+
+/// SubscriberViewRepository kept in process memory, for tests that run without Postgres
+#[derive(Debug, Default)]
+pub struct InMemorySubscriberViewRepository {
+    state: Mutex<State>,
+}
+
+impl InMemorySubscriberViewRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("in-memory repository lock poisoned")
+    }
+}
+
+fn current_tenant() -> TenantId {
+    tenant::current().tenant().cloned().unwrap_or_default()
+}
+
+#[async_trait]
+impl SubscriberViewRepository for InMemorySubscriberViewRepository {
+    async fn apply(&self, event: &SubscriptionEvent) -> Result<bool> {
+        let tenant = current_tenant();
+        let mut state = self.state();
+        let find = |state: &State, email: &str| {
+            state
+                .rows
+                .iter()
+                .find(|r| r.tenant == tenant && r.view.email == email)
+                .map(|r| r.view.clone())
+        };
+        let current = find(&state, &event.email);
+        let previous = event.previous_email.as_deref().and_then(|email| find(&state, email));
+
+        let changes = SubscriberView::changes(event, current.as_ref(), previous.as_ref());
+        for change in &changes {
+            match change {
+                ViewChange::Upsert(view) => {
+                    match state.rows.iter_mut().find(|r| r.tenant == tenant && r.view.email == view.email) {
+                        Some(row) => row.view = view.clone(),
+                        None => {
+                            state.next_id += 1;
+                            let id = state.next_id;
+                            state.rows.push(Row { id, tenant: tenant.clone(), view: view.clone() });
+                        }
+                    }
+                }
+                ViewChange::Remove(email) => state.rows.retain(|r| !(r.tenant == tenant && r.view.email == *email)),
+            }
+        }
+        Ok(!changes.is_empty())
+    }
+
+    async fn list(&self, status: Option<SubscriptionStatus>, page: PageRequest) -> Result<Page<SubscriberView>> {
+        let tenant = current_tenant();
+        let state = self.state();
+        let mut rows: Vec<&Row> = state
+            .rows
+            .iter()
+            .filter(|r| r.tenant == tenant && status.is_none_or(|s| r.view.status == s))
+            .collect();
+        rows.sort_by_key(|r| std::cmp::Reverse((r.view.created_at, r.id)));
+
+        if let Some(after) = page.after {
+            let Some(cursor) = state.rows.iter().find(|r| r.id == after && r.tenant == tenant) else {
+                return Err(StaleCursor(after).into());
+            };
+            let key = (cursor.view.created_at, cursor.id);
+            rows.retain(|r| (r.view.created_at, r.id) < key);
+        }
+
+        let has_more = rows.len() as i64 > page.limit;
+        rows.truncate(page.limit as usize);
+        Ok(Page {
+            next_cursor: if has_more { rows.last().map(|r| r.id) } else { None },
+            items: rows.into_iter().map(|r| r.view.clone()).collect(),
+        })
+    }
+
+    async fn stats(&self) -> Result<SubscriberStats> {
+        let tenant = current_tenant();
+        let state = self.state();
+        let count = |status| {
+            state
+                .rows
+                .iter()
+                .filter(|r| r.tenant == tenant && r.view.status == status)
+                .count() as i64
+        };
+        Ok(SubscriberStats {
+            active: count(SubscriptionStatus::Active),
+            inactive: count(SubscriptionStatus::Pending),
+            unsubscribed: count(SubscriptionStatus::Unsubscribed),
+            suppressed: count(SubscriptionStatus::Suppressed),
+        })
+    }
+}
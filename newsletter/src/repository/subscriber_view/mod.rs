@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::domain::newsletter::error::Result;
+use crate::domain::newsletter::lifecycle::SubscriptionStatus;
+use crate::domain::newsletter::stats::SubscriberStats;
+use crate::domain::newsletter::view::SubscriberView;
+use crate::domain::newsletter::SubscriptionEvent;
+use crate::domain::pagination::{Page, PageRequest};
+
+#[cfg(any(test, feature = "testing"))]
+pub mod memory;
+pub mod postgres;
+
+/// Repository trait for the read model of the subscriber list. Rows are
+/// written by folding in subscription events, never by the commands
+/// themselves, so they trail the newsletters table by the relay's lag.
+#[async_trait]
+pub trait SubscriberViewRepository: Send + Sync {
+    /// Fold one event into the rows of the current tenant; returns whether
+    /// anything changed (`false` for an event already applied)
+    async fn apply(&self, event: &SubscriptionEvent) -> Result<bool>;
+
+    /// Get a page of subscriptions, newest first, optionally of one status only.
+    /// Fails with a `NewsletterError::Validation` if the cursor's row is gone.
+    async fn list(&self, status: Option<SubscriptionStatus>, page: PageRequest) -> Result<Page<SubscriberView>>;
+
+    /// Count subscriptions per status; `unsubscribed` counts the addresses
+    /// that are unsubscribed or were deleted, not the unsubscribes recorded
+    async fn stats(&self) -> Result<SubscriberStats>;
+}
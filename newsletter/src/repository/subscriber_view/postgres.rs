@@ -0,0 +1,289 @@
+use crate::domain::newsletter::error::{NewsletterError, Result};
+use crate::domain::newsletter::lifecycle::SubscriptionStatus;
+use crate::domain::newsletter::stats::SubscriberStats;
+use crate::domain::newsletter::view::{SubscriberView, ViewChange};
+use crate::domain::newsletter::SubscriptionEvent;
+use crate::domain::pagination::{Page, PageRequest, StaleCursor};
+use crate::infrastructure::db::db_schema::{newsletters, subscriber_views};
+use crate::infrastructure::db::{tenant_connection, PgPool};
+use crate::infrastructure::logging;
+use crate::repository::newsletter::postgres::parse_status;
+use crate::repository::subscriber_view::SubscriberViewRepository;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use tracing::{error, info, instrument};
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = subscriber_views)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct ViewRow {
+    pub id: i64,
+    pub email: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ViewRow {
+    fn into_view(self) -> Result<SubscriberView> {
+        Ok(SubscriberView {
+            status: parse_status(&self.status)?,
+            email: self.email,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = subscriber_views)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewViewRow<'a> {
+    pub email: &'a str,
+    pub status: &'a str,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Row of the current tenant for `email`, locked until the transaction ends
+async fn locked(conn: &mut AsyncPgConnection, email: &str) -> Result<Option<SubscriberView>> {
+    subscriber_views::table
+        .filter(subscriber_views::email.eq(email))
+        .select(ViewRow::as_select())
+        .for_update()
+        .first::<ViewRow>(conn)
+        .await
+        .optional()?
+        .map(ViewRow::into_view)
+        .transpose()
+}
+
+/// PostgreSQL implementation of the SubscriberViewRepository trait
+#[derive(Clone)]
+pub struct PostgresSubscriberViewRepository {
+    pool: PgPool,
+}
+
+impl PostgresSubscriberViewRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Replace the rows of the tenants in scope with the subscriptions
+    /// stored now, catching up on changes that record no event, such as
+    /// retention and the expiry of pending subscriptions. Returns the number
+    /// of rows written.
+    #[instrument(skip(self))]
+    pub async fn rebuild(&self) -> Result<usize> {
+        info!(entity = "subscriber_views_table", crud_operation = "CREATE", "Starting database rebuild operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "subscriber_views_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    diesel::delete(subscriber_views::table).execute(conn).await?;
+                    diesel::insert_into(subscriber_views::table)
+                        .values(newsletters::table.select((
+                            newsletters::tenant_id,
+                            newsletters::email,
+                            newsletters::status,
+                            newsletters::created_at,
+                            newsletters::updated_at,
+                        )))
+                        .into_columns((
+                            subscriber_views::tenant_id,
+                            subscriber_views::email,
+                            subscriber_views::status,
+                            subscriber_views::created_at,
+                            subscriber_views::updated_at,
+                        ))
+                        .execute(conn)
+                        .await
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(rows_affected) => {
+                info!(entity = "subscriber_views_table", crud_operation = "CREATE", rows_affected = rows_affected, "Successfully rebuilt the read model");
+                Ok(rows_affected)
+            }
+            Err(e) => {
+                error!(entity = "subscriber_views_table", crud_operation = "CREATE", error = %e, "Failed to rebuild the read model");
+                Err(e.into())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SubscriberViewRepository for PostgresSubscriberViewRepository {
+    #[instrument(skip(self, event), fields(event_type = %event.kind, email = %logging::email(&event.email)))]
+    async fn apply(&self, event: &SubscriptionEvent) -> Result<bool> {
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "subscriber_views_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let result = conn
+            .transaction::<_, NewsletterError, _>(|conn| {
+                async move {
+                    let current = locked(conn, &event.email).await?;
+                    let previous = match event.previous_email.as_deref() {
+                        Some(email) => locked(conn, email).await?,
+                        None => None,
+                    };
+
+                    let changes = SubscriberView::changes(event, current.as_ref(), previous.as_ref());
+                    for change in &changes {
+                        match change {
+                            ViewChange::Upsert(view) => {
+                                let row = NewViewRow {
+                                    email: &view.email,
+                                    status: view.status.as_str(),
+                                    created_at: view.created_at,
+                                    updated_at: view.updated_at,
+                                };
+                                diesel::insert_into(subscriber_views::table)
+                                    .values(&row)
+                                    .on_conflict((subscriber_views::tenant_id, subscriber_views::email))
+                                    .do_update()
+                                    .set(&row)
+                                    .execute(conn)
+                                    .await?;
+                            }
+                            ViewChange::Remove(email) => {
+                                diesel::delete(subscriber_views::table.filter(subscriber_views::email.eq(email)))
+                                    .execute(conn)
+                                    .await?;
+                            }
+                        }
+                    }
+                    Ok(!changes.is_empty())
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        if let Err(e) = &result {
+            error!(entity = "subscriber_views_table", crud_operation = "UPDATE", error = %e, "Failed to apply subscription event to the read model");
+        }
+        result
+    }
+
+    #[instrument(skip(self), fields(limit = page.limit, after = ?page.after))]
+    async fn list(&self, status: Option<SubscriptionStatus>, page: PageRequest) -> Result<Page<SubscriberView>> {
+        info!(entity = "subscriber_views_table", crud_operation = "READ", limit = page.limit, after = ?page.after, status = ?status, "Starting database list operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "subscriber_views_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let cursor = match page.after {
+            Some(after) => match subscriber_views::table
+                .find(after)
+                .select(subscriber_views::created_at)
+                .first::<DateTime<Utc>>(&mut conn)
+                .await
+                .optional()
+            {
+                Ok(Some(created_at)) => Some((after, created_at)),
+                Ok(None) => return Err(StaleCursor(after).into()),
+                Err(e) => {
+                    error!(entity = "subscriber_views_table", crud_operation = "READ", error = %e, "Failed to look up page cursor");
+                    return Err(e.into());
+                }
+            },
+            None => None,
+        };
+
+        let mut query = subscriber_views::table
+            .select(ViewRow::as_select())
+            .order((subscriber_views::created_at.desc(), subscriber_views::id.desc()))
+            .limit(page.limit + 1)
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(subscriber_views::status.eq(status.as_str()));
+        }
+        if let Some((id, created_at)) = cursor {
+            query = query.filter(
+                subscriber_views::created_at.lt(created_at)
+                    .or(subscriber_views::created_at.eq(created_at).and(subscriber_views::id.lt(id))),
+            );
+        }
+
+        let mut rows: Vec<ViewRow> = match query.load(&mut conn).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!(entity = "subscriber_views_table", crud_operation = "READ", error = %e, "Failed to list the read model");
+                return Err(e.into());
+            }
+        };
+
+        let has_more = rows.len() as i64 > page.limit;
+        rows.truncate(page.limit as usize);
+        let next_cursor = if has_more { rows.last().map(|r| r.id) } else { None };
+        info!(entity = "subscriber_views_table", crud_operation = "READ", count = rows.len(), "Successfully listed the read model");
+
+        Ok(Page {
+            items: rows.into_iter().map(ViewRow::into_view).collect::<Result<_>>()?,
+            next_cursor,
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn stats(&self) -> Result<SubscriberStats> {
+        info!(entity = "subscriber_views_table", crud_operation = "READ", "Starting database stats operation");
+
+        let mut conn = match tenant_connection(&self.pool).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(entity = "subscriber_views_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+                return Err(e.into());
+            }
+        };
+
+        let by_status = match subscriber_views::table
+            .group_by(subscriber_views::status)
+            .select((subscriber_views::status, diesel::dsl::count_star()))
+            .load::<(String, i64)>(&mut conn)
+            .await
+        {
+            Ok(by_status) => by_status,
+            Err(e) => {
+                error!(entity = "subscriber_views_table", crud_operation = "READ", error = %e, "Failed to count the read model");
+                return Err(e.into());
+            }
+        };
+
+        let mut stats = SubscriberStats::default();
+        for (status, count) in by_status {
+            match parse_status(&status)? {
+                SubscriptionStatus::Active => stats.active = count,
+                SubscriptionStatus::Pending => stats.inactive = count,
+                SubscriptionStatus::Unsubscribed => stats.unsubscribed = count,
+                SubscriptionStatus::Suppressed => stats.suppressed = count,
+            }
+        }
+        Ok(stats)
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Mutex, MutexGuard};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::domain::pagination::{Page, PageRequest};
+use crate::domain::template::asset::{self, StoredAsset, TemplateAsset};
+use crate::domain::template::translation::TemplateTranslation;
+use crate::domain::template::{NewTemplate, Template, TemplateError};
+use crate::repository::template::TemplateRepository;
+
+#[derive(Debug)]
+struct AssetRecord {
+    asset: TemplateAsset,
+    last_referenced_at: DateTime<Utc>,
+    sent_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    last_template_id: i64,
+    last_asset_id: i64,
+    templates: BTreeMap<i64, Template>,
+    /// Translations per template and locale
+    translations: BTreeMap<(i64, String), TemplateTranslation>,
+    assets: Vec<AssetRecord>,
+}
+
+/// TemplateRepository kept in process memory, for tests that run without
+/// Postgres; templates and assets are not scoped to tenants
+#[derive(Debug, Default)]
+pub struct InMemoryTemplateRepository {
+    state: Mutex<State>,
+}
+
+impl InMemoryTemplateRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("in-memory repository lock poisoned")
+    }
+
+    /// Move every asset's upload and last reference `by` into the past, as
+    /// if that much time went by
+    pub fn backdate_assets(&self, by: Duration) {
+        for record in &mut self.state().assets {
+            record.asset.created_at -= by;
+            record.last_referenced_at -= by;
+            if let Some(sent_at) = &mut record.sent_at {
+                *sent_at -= by;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TemplateRepository for InMemoryTemplateRepository {
+    async fn create(&self, template: &NewTemplate) -> Result<Template> {
+        let mut state = self.state();
+        state.last_template_id += 1;
+        let now = Utc::now();
+        let created = Template {
+            id: state.last_template_id,
+            name: template.name.clone(),
+            format: template.format,
+            body: template.body.clone(),
+            text_body: template.text_body.clone(),
+            required_variables: template.required_variables.clone(),
+            version: 1,
+            created_at: now,
+            updated_at: now,
+        };
+        state.templates.insert(created.id, created.clone());
+        Ok(created)
+    }
+
+    async fn get(&self, id: i64) -> Result<Option<Template>> {
+        Ok(self.state().templates.get(&id).cloned())
+    }
+
+    async fn save(&self, template: &Template) -> Result<Template> {
+        let mut state = self.state();
+        let stored = state
+            .templates
+            .get_mut(&template.id)
+            .ok_or_else(|| anyhow::anyhow!("template {} not found", template.id))?;
+        if stored.version != template.version {
+            return Err(TemplateError::VersionMismatch {
+                expected: template.version,
+                actual: stored.version,
+            }
+            .into());
+        }
+
+        *stored = Template {
+            version: template.version + 1,
+            updated_at: Utc::now(),
+            ..template.clone()
+        };
+        Ok(stored.clone())
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        let mut state = self.state();
+        state.translations.retain(|(template_id, _), _| *template_id != id);
+        Ok(state.templates.remove(&id).is_some())
+    }
+
+    async fn list(&self, page: PageRequest) -> Result<Page<Template>> {
+        let state = self.state();
+        let mut items: Vec<Template> = state
+            .templates
+            .values()
+            .rev()
+            .filter(|t| page.after.is_none_or(|after| t.id < after))
+            .take(page.limit as usize + 1)
+            .cloned()
+            .collect();
+
+        let has_more = items.len() as i64 > page.limit;
+        items.truncate(page.limit as usize);
+        let next_cursor = if has_more { items.last().map(|t| t.id) } else { None };
+        Ok(Page { items, next_cursor })
+    }
+
+    async fn put_translation(&self, template_id: i64, locale: &str, body: &str) -> Result<Option<TemplateTranslation>> {
+        let mut state = self.state();
+        if !state.templates.contains_key(&template_id) {
+            return Ok(None);
+        }
+
+        let now = Utc::now();
+        let translation = state
+            .translations
+            .entry((template_id, locale.to_string()))
+            .and_modify(|t| {
+                t.body = body.to_string();
+                t.updated_at = now;
+            })
+            .or_insert_with(|| TemplateTranslation {
+                template_id,
+                locale: locale.to_string(),
+                body: body.to_string(),
+                created_at: now,
+                updated_at: now,
+            });
+        Ok(Some(translation.clone()))
+    }
+
+    async fn put_translations(
+        &self,
+        template_id: i64,
+        bundle: &BTreeMap<String, String>,
+        replace: bool,
+    ) -> Result<Option<Vec<TemplateTranslation>>> {
+        {
+            let mut state = self.state();
+            if !state.templates.contains_key(&template_id) {
+                return Ok(None);
+            }
+            if replace {
+                state
+                    .translations
+                    .retain(|(id, locale), _| *id != template_id || bundle.contains_key(locale));
+            }
+        }
+
+        for (locale, body) in bundle {
+            self.put_translation(template_id, locale, body).await?;
+        }
+        self.list_translations(template_id).await.map(Some)
+    }
+
+    async fn delete_translation(&self, template_id: i64, locale: &str) -> Result<bool> {
+        Ok(self
+            .state()
+            .translations
+            .remove(&(template_id, locale.to_string()))
+            .is_some())
+    }
+
+    async fn list_translations(&self, template_id: i64) -> Result<Vec<TemplateTranslation>> {
+        Ok(self
+            .state()
+            .translations
+            .values()
+            .filter(|t| t.template_id == template_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn create_asset(&self, asset: &StoredAsset) -> Result<Option<TemplateAsset>> {
+        let mut state = self.state();
+        if state.assets.iter().any(|record| record.asset.name == asset.name) {
+            return Ok(None);
+        }
+
+        state.last_asset_id += 1;
+        let now = Utc::now();
+        let created = TemplateAsset {
+            id: state.last_asset_id,
+            name: asset.name.clone(),
+            content_type: asset.content_type.clone(),
+            size_bytes: asset.size_bytes,
+            sha256: asset.sha256.clone(),
+            storage_key: asset.storage_key.clone(),
+            created_at: now,
+        };
+        state.assets.push(AssetRecord {
+            asset: created.clone(),
+            last_referenced_at: now,
+            sent_at: None,
+        });
+        Ok(Some(created))
+    }
+
+    async fn get_asset(&self, name: &str) -> Result<Option<TemplateAsset>> {
+        Ok(self
+            .state()
+            .assets
+            .iter()
+            .find(|record| record.asset.name == name)
+            .map(|record| record.asset.clone()))
+    }
+
+    async fn mark_assets_referenced(&self, names: &BTreeSet<String>, at: DateTime<Utc>) -> Result<()> {
+        for record in &mut self.state().assets {
+            if names.contains(&record.asset.name) {
+                record.last_referenced_at = record.last_referenced_at.max(at);
+            }
+        }
+        Ok(())
+    }
+
+    async fn mark_assets_sent(&self, names: &BTreeSet<String>, at: DateTime<Utc>) -> Result<()> {
+        for record in &mut self.state().assets {
+            if names.contains(&record.asset.name) {
+                record.sent_at.get_or_insert(at);
+            }
+        }
+        Ok(())
+    }
+
+    async fn unreferenced_assets(&self, referenced_before: DateTime<Utc>) -> Result<Vec<TemplateAsset>> {
+        let state = self.state();
+        let mut referenced = BTreeSet::new();
+        for template in state.templates.values() {
+            referenced.extend(asset::template_references(template, &[]));
+        }
+        for translation in state.translations.values() {
+            referenced.extend(asset::references(&translation.body));
+        }
+
+        Ok(state
+            .assets
+            .iter()
+            .filter(|record| record.sent_at.is_none() && record.last_referenced_at < referenced_before)
+            .filter(|record| !referenced.contains(&record.asset.name))
+            .map(|record| record.asset.clone())
+            .collect())
+    }
+
+    async fn delete_asset(&self, id: i64) -> Result<bool> {
+        let mut state = self.state();
+        let before = state.assets.len();
+        state.assets.retain(|record| record.asset.id != id);
+        Ok(state.assets.len() < before)
+    }
+}
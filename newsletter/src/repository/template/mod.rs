@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use crate::domain::pagination::{Page, PageRequest};
+use crate::domain::template::asset::{StoredAsset, TemplateAsset};
+use crate::domain::template::translation::TemplateTranslation;
+use crate::domain::template::{NewTemplate, Template};
+
+#[cfg(any(test, feature = "testing"))]
+pub mod memory;
+pub mod postgres;
+
+/// Repository trait for template persistence
+#[async_trait]
+pub trait TemplateRepository: Send + Sync {
+    /// Persist a new template
+    async fn create(&self, template: &NewTemplate) -> Result<Template>;
+
+    /// Get a template by id
+    async fn get(&self, id: i64) -> Result<Option<Template>>;
+
+    /// Save the mutable fields of an existing template
+    async fn save(&self, template: &Template) -> Result<Template>;
+
+    /// Delete a template; returns whether it existed
+    async fn delete(&self, id: i64) -> Result<bool>;
+
+    /// Get a page of templates, newest first
+    async fn list(&self, page: PageRequest) -> Result<Page<Template>>;
+
+    /// Create or replace the translation of a template into `locale`;
+    /// `None` if the template does not exist
+    async fn put_translation(&self, template_id: i64, locale: &str, body: &str) -> Result<Option<TemplateTranslation>>;
+
+    /// Store a bundle of bodies keyed by locale in one transaction, replacing
+    /// existing translations of those locales; with `replace`, translations
+    /// of other locales are deleted. Returns every translation afterwards, or
+    /// `None` if the template does not exist.
+    async fn put_translations(
+        &self,
+        template_id: i64,
+        bundle: &BTreeMap<String, String>,
+        replace: bool,
+    ) -> Result<Option<Vec<TemplateTranslation>>>;
+
+    /// Delete a translation; returns whether it existed
+    async fn delete_translation(&self, template_id: i64, locale: &str) -> Result<bool>;
+
+    /// Get every translation of a template, ordered by locale
+    async fn list_translations(&self, template_id: i64) -> Result<Vec<TemplateTranslation>>;
+
+    /// Record an asset whose content was stored; `None` if the tenant
+    /// already has an asset of that name
+    async fn create_asset(&self, asset: &StoredAsset) -> Result<Option<TemplateAsset>>;
+
+    /// Get an asset by name
+    async fn get_asset(&self, name: &str) -> Result<Option<TemplateAsset>>;
+
+    /// Note that the named assets were referenced until `at`, as a body
+    /// using them is about to change or go
+    async fn mark_assets_referenced(&self, names: &BTreeSet<String>, at: DateTime<Utc>) -> Result<()>;
+
+    /// Note that the named assets were shown in emails sent at `at`, which
+    /// keeps them for good
+    async fn mark_assets_sent(&self, names: &BTreeSet<String>, at: DateTime<Utc>) -> Result<()>;
+
+    /// Assets last referenced before `referenced_before` that no template
+    /// or translation of their tenant references and no sent email showed,
+    /// across the tenants in scope
+    async fn unreferenced_assets(&self, referenced_before: DateTime<Utc>) -> Result<Vec<TemplateAsset>>;
+
+    /// Delete the record of an asset; returns whether it existed
+    async fn delete_asset(&self, id: i64) -> Result<bool>;
+}
@@ -0,0 +1,704 @@
+use crate::domain::pagination::{Page, PageRequest};
+use crate::domain::template::asset::{self, StoredAsset, TemplateAsset};
+use crate::domain::template::translation::TemplateTranslation;
+use crate::domain::template::{NewTemplate, Template, TemplateError, TemplateFormat};
+use crate::infrastructure::db::db_schema::{template_assets, template_translations, templates};
+use crate::infrastructure::db::{tenant_connection, PgPool};
+use crate::repository::template::TemplateRepository;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::dsl::exists;
+use diesel::prelude::*;
+use diesel::result::DatabaseErrorKind;
+use diesel::upsert::excluded;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{error, info, instrument};
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = templates)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct TemplateRow {
+    pub id: i64,
+    pub name: String,
+    pub format: String,
+    pub body: String,
+    pub required_variables: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub version: i64,
+    pub text_body: Option<String>,
+}
+
+impl TryFrom<TemplateRow> for Template {
+    type Error = anyhow::Error;
+
+    fn try_from(row: TemplateRow) -> Result<Self> {
+        let format = TemplateFormat::parse(&row.format)
+            .ok_or_else(|| anyhow::anyhow!("unknown template format in database: {}", row.format))?;
+
+        Ok(Template {
+            id: row.id,
+            name: row.name,
+            format,
+            body: row.body,
+            text_body: row.text_body,
+            required_variables: row.required_variables,
+            version: row.version,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = templates)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewTemplateRow<'a> {
+    pub name: &'a str,
+    pub format: &'a str,
+    pub body: &'a str,
+    pub text_body: Option<&'a str>,
+    pub required_variables: &'a [String],
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = templates)]
+#[diesel(treat_none_as_null = true)]
+struct TemplateChangeset<'a> {
+    pub name: &'a str,
+    pub format: &'a str,
+    pub body: &'a str,
+    pub text_body: Option<&'a str>,
+    pub required_variables: &'a [String],
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = template_translations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct TranslationRow {
+    pub template_id: i64,
+    pub locale: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<TranslationRow> for TemplateTranslation {
+    fn from(row: TranslationRow) -> Self {
+        TemplateTranslation {
+            template_id: row.template_id,
+            locale: row.locale,
+            body: row.body,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = template_translations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewTranslationRow<'a> {
+    pub template_id: i64,
+    pub locale: &'a str,
+    pub body: &'a str,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = template_assets)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct AssetRow {
+    pub id: i64,
+    pub name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub storage_key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AssetRow> for TemplateAsset {
+    fn from(row: AssetRow) -> Self {
+        TemplateAsset {
+            id: row.id,
+            name: row.name,
+            content_type: row.content_type,
+            size_bytes: row.size_bytes,
+            sha256: row.sha256,
+            storage_key: row.storage_key,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = template_assets)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewAssetRow<'a> {
+    pub name: &'a str,
+    pub content_type: &'a str,
+    pub size_bytes: i64,
+    pub sha256: &'a str,
+    pub storage_key: &'a str,
+}
+
+/// PostgreSQL implementation of the TemplateRepository trait
+#[derive(Clone)]
+pub struct PostgresTemplateRepository {
+    pool: PgPool,
+}
+
+impl PostgresTemplateRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TemplateRepository for PostgresTemplateRepository {
+    #[instrument(skip(self, template), fields(name = %template.name))]
+    async fn create(&self, template: &NewTemplate) -> Result<Template> {
+        info!(entity = "template_table", crud_operation = "CREATE", "Starting database create operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::insert_into(templates::table)
+            .values(&NewTemplateRow {
+                name: &template.name,
+                format: template.format.as_str(),
+                body: &template.body,
+                text_body: template.text_body.as_deref(),
+                required_variables: &template.required_variables,
+            })
+            .returning(TemplateRow::as_returning())
+            .get_result(&mut conn)
+            .await
+        {
+            Ok(row) => {
+                info!(entity = "template_table", crud_operation = "CREATE", id = row.id, "Successfully created template");
+                row.try_into()
+            }
+            Err(e) => {
+                error!(entity = "template_table", crud_operation = "CREATE", error = %e, "Failed to create template");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn get(&self, id: i64) -> Result<Option<Template>> {
+        info!(entity = "template_table", crud_operation = "READ", id = id, "Starting database get operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match templates::table
+            .find(id)
+            .select(TemplateRow::as_select())
+            .first(&mut conn)
+            .await
+            .optional()
+        {
+            Ok(row) => {
+                info!(entity = "template_table", crud_operation = "READ", id = id, found = row.is_some(), "Successfully retrieved template");
+                row.map(Template::try_from).transpose()
+            }
+            Err(e) => {
+                error!(entity = "template_table", crud_operation = "READ", id = id, error = %e, "Failed to retrieve template");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, template), fields(id = template.id))]
+    async fn save(&self, template: &Template) -> Result<Template> {
+        info!(entity = "template_table", crud_operation = "UPDATE", id = template.id, "Starting database save operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let changeset = TemplateChangeset {
+            name: &template.name,
+            format: template.format.as_str(),
+            body: &template.body,
+            text_body: template.text_body.as_deref(),
+            required_variables: &template.required_variables,
+            updated_at: Utc::now(),
+        };
+        match diesel::update(templates::table.find(template.id).filter(templates::version.eq(template.version)))
+            .set((&changeset, templates::version.eq(templates::version + 1)))
+            .returning(TemplateRow::as_returning())
+            .get_result(&mut conn)
+            .await
+            .optional()
+        {
+            Ok(Some(row)) => {
+                info!(entity = "template_table", crud_operation = "UPDATE", id = template.id, "Successfully saved template");
+                row.try_into()
+            }
+            Ok(None) => {
+                // Saved by someone else in between; a deleted template
+                // fails this lookup instead
+                let actual = templates::table
+                    .find(template.id)
+                    .select(templates::version)
+                    .first::<i64>(&mut conn)
+                    .await?;
+                error!(entity = "template_table", crud_operation = "UPDATE", id = template.id, expected = template.version, actual = actual, "Template changed since it was read");
+                Err(TemplateError::VersionMismatch {
+                    expected: template.version,
+                    actual,
+                }
+                .into())
+            }
+            Err(e) => {
+                error!(entity = "template_table", crud_operation = "UPDATE", id = template.id, error = %e, "Failed to save template");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn delete(&self, id: i64) -> Result<bool> {
+        info!(entity = "template_table", crud_operation = "DELETE", id = id, "Starting database delete operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_table", crud_operation = "DELETE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::delete(templates::table.find(id)).execute(&mut conn).await {
+            Ok(rows_affected) => {
+                info!(entity = "template_table", crud_operation = "DELETE", id = id, rows_affected = rows_affected, "Successfully deleted template");
+                Ok(rows_affected > 0)
+            }
+            Err(e) => {
+                error!(entity = "template_table", crud_operation = "DELETE", id = id, error = %e, "Failed to delete template");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self), fields(limit = page.limit, after = ?page.after))]
+    async fn list(&self, page: PageRequest) -> Result<Page<Template>> {
+        info!(entity = "template_table", crud_operation = "READ", limit = page.limit, "Starting database list operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let mut query = templates::table
+            .select(TemplateRow::as_select())
+            .order(templates::id.desc())
+            .limit(page.limit + 1)
+            .into_boxed();
+
+        if let Some(after) = page.after {
+            query = query.filter(templates::id.lt(after));
+        }
+
+        let mut rows: Vec<TemplateRow> = match query.load(&mut conn).await {
+            Ok(rows) => {
+                info!(entity = "template_table", crud_operation = "READ", rows_count = rows.len(), "Successfully retrieved templates");
+                rows
+            }
+            Err(e) => {
+                error!(entity = "template_table", crud_operation = "READ", error = %e, "Failed to retrieve templates");
+                return Err(e.into());
+            }
+        };
+
+        let has_more = rows.len() as i64 > page.limit;
+        rows.truncate(page.limit as usize);
+        let next_cursor = if has_more { rows.last().map(|r| r.id) } else { None };
+
+        Ok(Page {
+            items: rows
+                .into_iter()
+                .map(Template::try_from)
+                .collect::<Result<Vec<_>>>()?,
+            next_cursor,
+        })
+    }
+
+    #[instrument(skip(self, body))]
+    async fn put_translation(&self, template_id: i64, locale: &str, body: &str) -> Result<Option<TemplateTranslation>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_translations_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let found: bool = diesel::select(exists(templates::table.find(template_id)))
+                        .get_result(conn)
+                        .await?;
+                    if !found {
+                        return Ok(None);
+                    }
+
+                    diesel::insert_into(template_translations::table)
+                        .values(&NewTranslationRow { template_id, locale, body })
+                        .on_conflict((template_translations::template_id, template_translations::locale))
+                        .do_update()
+                        .set((
+                            template_translations::body.eq(excluded(template_translations::body)),
+                            template_translations::updated_at.eq(Utc::now()),
+                        ))
+                        .returning(TranslationRow::as_returning())
+                        .get_result(conn)
+                        .await
+                        .map(Some)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(row) => {
+                info!(entity = "template_translations_table", crud_operation = "UPDATE", template_id = template_id, locale = %locale, found = row.is_some(), "Saved template translation");
+                Ok(row.map(TemplateTranslation::from))
+            }
+            Err(e) => {
+                error!(entity = "template_translations_table", crud_operation = "UPDATE", template_id = template_id, locale = %locale, error = %e, "Failed to save template translation");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, bundle), fields(count = bundle.len()))]
+    async fn put_translations(
+        &self,
+        template_id: i64,
+        bundle: &BTreeMap<String, String>,
+        replace: bool,
+    ) -> Result<Option<Vec<TemplateTranslation>>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_translations_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let rows: Vec<NewTranslationRow> = bundle
+            .iter()
+            .map(|(locale, body)| NewTranslationRow { template_id, locale, body })
+            .collect();
+        let locales: Vec<&str> = bundle.keys().map(String::as_str).collect();
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    // Locked so a concurrent delete cannot orphan the bundle
+                    let found = templates::table
+                        .find(template_id)
+                        .select(templates::id)
+                        .for_update()
+                        .first::<i64>(conn)
+                        .await
+                        .optional()?;
+                    if found.is_none() {
+                        return Ok(None);
+                    }
+
+                    if replace {
+                        diesel::delete(
+                            template_translations::table
+                                .filter(template_translations::template_id.eq(template_id))
+                                .filter(template_translations::locale.ne_all(&locales)),
+                        )
+                        .execute(conn)
+                        .await?;
+                    }
+                    if !rows.is_empty() {
+                        diesel::insert_into(template_translations::table)
+                            .values(&rows)
+                            .on_conflict((template_translations::template_id, template_translations::locale))
+                            .do_update()
+                            .set((
+                                template_translations::body.eq(excluded(template_translations::body)),
+                                template_translations::updated_at.eq(Utc::now()),
+                            ))
+                            .execute(conn)
+                            .await?;
+                    }
+
+                    template_translations::table
+                        .filter(template_translations::template_id.eq(template_id))
+                        .order(template_translations::locale.asc())
+                        .select(TranslationRow::as_select())
+                        .load(conn)
+                        .await
+                        .map(Some)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(rows) => {
+                info!(entity = "template_translations_table", crud_operation = "UPDATE", template_id = template_id, uploaded = bundle.len(), replace = replace, found = rows.is_some(), "Saved template translation bundle");
+                Ok(rows.map(|rows| rows.into_iter().map(TemplateTranslation::from).collect()))
+            }
+            Err(e) => {
+                error!(entity = "template_translations_table", crud_operation = "UPDATE", template_id = template_id, error = %e, "Failed to save template translation bundle");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_translation(&self, template_id: i64, locale: &str) -> Result<bool> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_translations_table", crud_operation = "DELETE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::delete(template_translations::table.find((template_id, locale)))
+            .execute(&mut conn)
+            .await
+        {
+            Ok(rows_affected) => {
+                info!(entity = "template_translations_table", crud_operation = "DELETE", template_id = template_id, locale = %locale, rows_affected = rows_affected, "Deleted template translation");
+                Ok(rows_affected > 0)
+            }
+            Err(e) => {
+                error!(entity = "template_translations_table", crud_operation = "DELETE", template_id = template_id, locale = %locale, error = %e, "Failed to delete template translation");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list_translations(&self, template_id: i64) -> Result<Vec<TemplateTranslation>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_translations_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match template_translations::table
+            .filter(template_translations::template_id.eq(template_id))
+            .order(template_translations::locale.asc())
+            .select(TranslationRow::as_select())
+            .load(&mut conn)
+            .await
+        {
+            Ok(rows) => Ok(rows.into_iter().map(TemplateTranslation::from).collect()),
+            Err(e) => {
+                error!(entity = "template_translations_table", crud_operation = "READ", template_id = template_id, error = %e, "Failed to retrieve template translations");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self, asset), fields(name = %asset.name))]
+    async fn create_asset(&self, asset: &StoredAsset) -> Result<Option<TemplateAsset>> {
+        info!(entity = "template_assets_table", crud_operation = "CREATE", "Starting database create operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_assets_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::insert_into(template_assets::table)
+            .values(&NewAssetRow {
+                name: &asset.name,
+                content_type: &asset.content_type,
+                size_bytes: asset.size_bytes,
+                sha256: &asset.sha256,
+                storage_key: &asset.storage_key,
+            })
+            .returning(AssetRow::as_returning())
+            .get_result(&mut conn)
+            .await
+        {
+            Ok(row) => {
+                info!(entity = "template_assets_table", crud_operation = "CREATE", id = row.id, "Successfully created template asset");
+                Ok(Some(row.into()))
+            }
+            Err(diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                info!(entity = "template_assets_table", crud_operation = "CREATE", "Template asset name already taken");
+                Ok(None)
+            }
+            Err(e) => {
+                error!(entity = "template_assets_table", crud_operation = "CREATE", error = %e, "Failed to create template asset");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn get_asset(&self, name: &str) -> Result<Option<TemplateAsset>> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_assets_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match template_assets::table
+            .filter(template_assets::name.eq(name))
+            .select(AssetRow::as_select())
+            .first(&mut conn)
+            .await
+            .optional()
+        {
+            Ok(row) => Ok(row.map(TemplateAsset::from)),
+            Err(e) => {
+                error!(entity = "template_assets_table", crud_operation = "READ", name = %name, error = %e, "Failed to retrieve template asset");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn mark_assets_referenced(&self, names: &BTreeSet<String>, at: DateTime<Utc>) -> Result<()> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_assets_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::update(
+            template_assets::table
+                .filter(template_assets::name.eq_any(names))
+                .filter(template_assets::last_referenced_at.lt(at)),
+        )
+        .set(template_assets::last_referenced_at.eq(at))
+        .execute(&mut conn)
+        .await
+        {
+            Ok(rows_affected) => {
+                info!(entity = "template_assets_table", crud_operation = "UPDATE", rows_affected = rows_affected, "Marked template assets referenced");
+                Ok(())
+            }
+            Err(e) => {
+                error!(entity = "template_assets_table", crud_operation = "UPDATE", error = %e, "Failed to mark template assets referenced");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn mark_assets_sent(&self, names: &BTreeSet<String>, at: DateTime<Utc>) -> Result<()> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_assets_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::update(
+            template_assets::table
+                .filter(template_assets::name.eq_any(names))
+                .filter(template_assets::sent_at.is_null()),
+        )
+        .set(template_assets::sent_at.eq(at))
+        .execute(&mut conn)
+        .await
+        {
+            Ok(rows_affected) => {
+                info!(entity = "template_assets_table", crud_operation = "UPDATE", rows_affected = rows_affected, "Marked template assets sent");
+                Ok(())
+            }
+            Err(e) => {
+                error!(entity = "template_assets_table", crud_operation = "UPDATE", error = %e, "Failed to mark template assets sent");
+                Err(e.into())
+            }
+        }
+    }
+
+    /// References are found by parsing the bodies here rather than in SQL,
+    /// so they are read the same way the `asset` helper is
+    #[instrument(skip(self))]
+    async fn unreferenced_assets(&self, referenced_before: DateTime<Utc>) -> Result<Vec<TemplateAsset>> {
+        info!(entity = "template_assets_table", crud_operation = "READ", referenced_before = %referenced_before, "Starting database unreferenced assets operation");
+
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_assets_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let candidates = template_assets::table
+                        .filter(template_assets::sent_at.is_null())
+                        .filter(template_assets::last_referenced_at.lt(referenced_before))
+                        .order(template_assets::id.asc())
+                        .select((template_assets::tenant_id, AssetRow::as_select()))
+                        .load::<(String, AssetRow)>(conn)
+                        .await?;
+                    if candidates.is_empty() {
+                        return Ok(Vec::new());
+                    }
+
+                    let mut bodies = Vec::new();
+                    for (tenant, body, text_body) in templates::table
+                        .select((templates::tenant_id, templates::body, templates::text_body))
+                        .load::<(String, String, Option<String>)>(conn)
+                        .await?
+                    {
+                        if let Some(text_body) = text_body {
+                            bodies.push((tenant.clone(), text_body));
+                        }
+                        bodies.push((tenant, body));
+                    }
+                    bodies.extend(
+                        template_translations::table
+                            .select((template_translations::tenant_id, template_translations::body))
+                            .load::<(String, String)>(conn)
+                            .await?,
+                    );
+                    Ok(candidates
+                        .into_iter()
+                        .filter(|(tenant, row)| {
+                            !bodies.iter().any(|(owner, body)| {
+                                owner == tenant && asset::references(body).contains(&row.name)
+                            })
+                        })
+                        .map(|(_, row)| TemplateAsset::from(row))
+                        .collect())
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(assets) => {
+                info!(entity = "template_assets_table", crud_operation = "READ", rows_count = assets.len(), "Found unreferenced template assets");
+                Ok(assets)
+            }
+            Err(e) => {
+                error!(entity = "template_assets_table", crud_operation = "READ", error = %e, "Failed to find unreferenced template assets");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_asset(&self, id: i64) -> Result<bool> {
+        let mut conn = tenant_connection(&self.pool).await.map_err(|e| {
+            error!(entity = "template_assets_table", crud_operation = "DELETE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::delete(template_assets::table.find(id)).execute(&mut conn).await {
+            Ok(rows_affected) => {
+                info!(entity = "template_assets_table", crud_operation = "DELETE", id = id, rows_affected = rows_affected, "Deleted template asset");
+                Ok(rows_affected > 0)
+            }
+            Err(e) => {
+                error!(entity = "template_assets_table", crud_operation = "DELETE", id = id, error = %e, "Failed to delete template asset");
+                Err(e.into())
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::repository::warmup::WarmupRepository;
+
+#[derive(Debug, Default)]
+struct State {
+    started_on: HashMap<String, NaiveDate>,
+    /// Sends reserved per domain and day
+    sent: HashMap<(String, NaiveDate), i64>,
+}
+
+/// WarmupRepository kept in process memory, for tests that run without Postgres
+#[derive(Debug, Default)]
+pub struct InMemoryWarmupRepository {
+    state: Mutex<State>,
+}
+
+impl InMemoryWarmupRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("in-memory repository lock poisoned")
+    }
+}
+
+#[async_trait]
+impl WarmupRepository for InMemoryWarmupRepository {
+    async fn started_on(&self, domain: &str, today: NaiveDate) -> Result<NaiveDate> {
+        Ok(*self.state().started_on.entry(domain.to_string()).or_insert(today))
+    }
+
+    async fn reserve(&self, domain: &str, day: NaiveDate, wanted: i64, cap: i64) -> Result<i64> {
+        let mut state = self.state();
+        let sent = state.sent.entry((domain.to_string(), day)).or_insert(0);
+        let granted = wanted.min(cap - *sent).max(0);
+        *sent += granted;
+        Ok(granted)
+    }
+
+    async fn release(&self, domain: &str, day: NaiveDate, count: i64) -> Result<()> {
+        if let Some(sent) = self.state().sent.get_mut(&(domain.to_string(), day)) {
+            if *sent >= count {
+                *sent -= count;
+            }
+        }
+        Ok(())
+    }
+}
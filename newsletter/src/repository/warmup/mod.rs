@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use chrono::NaiveDate;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod memory;
+pub mod postgres;
+
+/// Repository trait for the send volumes of warming domains, shared by all tenants
+#[async_trait]
+pub trait WarmupRepository: Send + Sync {
+    /// The day `domain` started its warm-up, recording `today` for a domain
+    /// that never sent before
+    async fn started_on(&self, domain: &str, today: NaiveDate) -> Result<NaiveDate>;
+
+    /// Take up to `wanted` of the `cap` sends `domain` may make on `day`;
+    /// returns how many were granted
+    async fn reserve(&self, domain: &str, day: NaiveDate, wanted: i64, cap: i64) -> Result<i64>;
+
+    /// Give back `count` reserved sends that were not made
+    async fn release(&self, domain: &str, day: NaiveDate, count: i64) -> Result<()>;
+}
@@ -0,0 +1,131 @@
+use crate::infrastructure::db::db_schema::{warmup_domains, warmup_volumes};
+use crate::infrastructure::db::PgPool;
+use crate::repository::warmup::WarmupRepository;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use tracing::{error, info, instrument};
+
+/// PostgreSQL implementation of the WarmupRepository trait.
+///
+/// Warm-up volumes belong to no tenant, so connections come straight from
+/// the pool.
+#[derive(Clone)]
+pub struct PostgresWarmupRepository {
+    pool: PgPool,
+}
+
+impl PostgresWarmupRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl WarmupRepository for PostgresWarmupRepository {
+    #[instrument(skip(self))]
+    async fn started_on(&self, domain: &str, today: NaiveDate) -> Result<NaiveDate> {
+        let mut conn = self.pool.get().await.map_err(|e| {
+            error!(entity = "warmup_domains_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let result = async {
+            let inserted = diesel::insert_into(warmup_domains::table)
+                .values((warmup_domains::domain.eq(domain), warmup_domains::started_on.eq(today)))
+                .on_conflict_do_nothing()
+                .execute(&mut conn)
+                .await?;
+            if inserted > 0 {
+                info!(entity = "warmup_domains_table", crud_operation = "CREATE", domain = %domain, "Domain started warming up");
+            }
+            warmup_domains::table
+                .find(domain)
+                .select(warmup_domains::started_on)
+                .first::<NaiveDate>(&mut conn)
+                .await
+        }
+        .await;
+
+        result.map_err(|e| {
+            error!(entity = "warmup_domains_table", crud_operation = "READ", domain = %domain, error = %e, "Failed to read warm-up start");
+            e.into()
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn reserve(&self, domain: &str, day: NaiveDate, wanted: i64, cap: i64) -> Result<i64> {
+        let mut conn = self.pool.get().await.map_err(|e| {
+            error!(entity = "warmup_volumes_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    diesel::insert_into(warmup_volumes::table)
+                        .values((warmup_volumes::domain.eq(domain), warmup_volumes::day.eq(day)))
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+                    // Locked so concurrent batches of the domain share the cap
+                    let sent: i64 = warmup_volumes::table
+                        .find((domain, day))
+                        .select(warmup_volumes::sent)
+                        .for_update()
+                        .first(conn)
+                        .await?;
+
+                    let granted = wanted.min(cap - sent).max(0);
+                    if granted > 0 {
+                        diesel::update(warmup_volumes::table.find((domain, day)))
+                            .set(warmup_volumes::sent.eq(sent + granted))
+                            .execute(conn)
+                            .await?;
+                    }
+                    Ok(granted)
+                }
+                .scope_boxed()
+            })
+            .await;
+
+        match result {
+            Ok(granted) => {
+                info!(entity = "warmup_volumes_table", crud_operation = "UPDATE", domain = %domain, day = %day, granted = granted, "Reserved warm-up volume");
+                Ok(granted)
+            }
+            Err(e) => {
+                error!(entity = "warmup_volumes_table", crud_operation = "UPDATE", domain = %domain, day = %day, error = %e, "Failed to reserve warm-up volume");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn release(&self, domain: &str, day: NaiveDate, count: i64) -> Result<()> {
+        let mut conn = self.pool.get().await.map_err(|e| {
+            error!(entity = "warmup_volumes_table", crud_operation = "UPDATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::update(
+            warmup_volumes::table
+                .find((domain, day))
+                .filter(warmup_volumes::sent.ge(count)),
+        )
+        .set(warmup_volumes::sent.eq(warmup_volumes::sent - count))
+        .execute(&mut conn)
+        .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!(entity = "warmup_volumes_table", crud_operation = "UPDATE", domain = %domain, day = %day, error = %e, "Failed to release warm-up volume");
+                Err(e.into())
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use crate::domain::webhook::{DeadLetter, WebhookBacklog};
+
+pub mod postgres;
+
+/// Repository trait for failed webhook deliveries
+#[async_trait]
+pub trait WebhookDeadLetterRepository: Send + Sync {
+    /// Park a delivery that could not be completed
+    async fn record(&self, dead_letter: &DeadLetter) -> Result<()>;
+
+    /// Deliveries still queued and those dead-lettered, per endpoint, for
+    /// every endpoint that has either
+    async fn backlog(&self) -> Result<Vec<WebhookBacklog>>;
+}
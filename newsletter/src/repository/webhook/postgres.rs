@@ -0,0 +1,123 @@
+use crate::domain::jobs::JobKind;
+use crate::domain::webhook::{DeadLetter, WebhookBacklog};
+use crate::infrastructure::db::db_schema::webhook_dead_letters;
+use crate::infrastructure::db::PgPool;
+use crate::repository::webhook::WebhookDeadLetterRepository;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Text, Timestamptz};
+use diesel_async::RunQueryDsl;
+use serde_json::Value;
+use tracing::{error, info, instrument};
+
+#[derive(Insertable)]
+#[diesel(table_name = webhook_dead_letters)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NewDeadLetterRow<'a> {
+    pub url: &'a str,
+    pub event_type: &'a str,
+    pub payload: &'a Value,
+    pub attempts: i32,
+    pub last_error: &'a str,
+}
+
+/// Pending `deliver_webhook` jobs and dead letters, both grouped by endpoint
+const BACKLOG_QUERY: &str = "
+    SELECT url, sum(pending)::BIGINT AS pending, min(oldest_pending_at) AS oldest_pending_at,
+           sum(dead_letters)::BIGINT AS dead_letters
+    FROM (
+        SELECT payload->>'url' AS url, count(*) AS pending, min(created_at) AS oldest_pending_at, 0 AS dead_letters
+        FROM jobs WHERE kind = $1 AND status = 'pending' GROUP BY 1
+        UNION ALL
+        SELECT url, 0, NULL, count(*) FROM webhook_dead_letters GROUP BY url
+    ) deliveries
+    WHERE url IS NOT NULL
+    GROUP BY url
+    ORDER BY url";
+
+#[derive(QueryableByName)]
+struct BacklogRow {
+    #[diesel(sql_type = Text)]
+    url: String,
+    #[diesel(sql_type = BigInt)]
+    pending: i64,
+    #[diesel(sql_type = Nullable<Timestamptz>)]
+    oldest_pending_at: Option<DateTime<Utc>>,
+    #[diesel(sql_type = BigInt)]
+    dead_letters: i64,
+}
+
+/// PostgreSQL implementation of the WebhookDeadLetterRepository trait
+#[derive(Clone)]
+pub struct PostgresWebhookDeadLetterRepository {
+    pool: PgPool,
+}
+
+impl PostgresWebhookDeadLetterRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl WebhookDeadLetterRepository for PostgresWebhookDeadLetterRepository {
+    #[instrument(skip(self, dead_letter), fields(url = %dead_letter.url, event_type = %dead_letter.event_type))]
+    async fn record(&self, dead_letter: &DeadLetter) -> Result<()> {
+        let mut conn = self.pool.get().await.map_err(|e| {
+            error!(entity = "webhook_dead_letters_table", crud_operation = "CREATE", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::insert_into(webhook_dead_letters::table)
+            .values(&NewDeadLetterRow {
+                url: &dead_letter.url,
+                event_type: &dead_letter.event_type,
+                payload: &dead_letter.payload,
+                attempts: i32::try_from(dead_letter.attempts).unwrap_or(i32::MAX),
+                last_error: &dead_letter.last_error,
+            })
+            .execute(&mut conn)
+            .await
+        {
+            Ok(_) => {
+                info!(entity = "webhook_dead_letters_table", crud_operation = "CREATE", "Recorded webhook dead letter");
+                Ok(())
+            }
+            Err(e) => {
+                error!(entity = "webhook_dead_letters_table", crud_operation = "CREATE", error = %e, "Failed to record webhook dead letter");
+                Err(e.into())
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn backlog(&self) -> Result<Vec<WebhookBacklog>> {
+        let mut conn = self.pool.get().await.map_err(|e| {
+            error!(entity = "webhook_dead_letters_table", crud_operation = "READ", error = %e, "Failed to acquire database connection");
+            e
+        })?;
+
+        match diesel::sql_query(BACKLOG_QUERY)
+            .bind::<Text, _>(JobKind::DeliverWebhook.as_str())
+            .load::<BacklogRow>(&mut conn)
+            .await
+        {
+            Ok(rows) => Ok(rows
+                .into_iter()
+                .map(|row| WebhookBacklog {
+                    url: row.url,
+                    pending: row.pending,
+                    oldest_pending_at: row.oldest_pending_at,
+                    dead_letters: row.dead_letters,
+                })
+                .collect()),
+            Err(e) => {
+                error!(entity = "webhook_dead_letters_table", crud_operation = "READ", error = %e, "Failed to count pending webhook deliveries");
+                Err(e.into())
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::domain::analytics::{days_to_export, last_finished_day, Dataset};
+use crate::domain::jobs::Job;
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::analytics::AnalyticsSink;
+use crate::infrastructure::pseudonym::Pseudonymizer;
+use crate::infrastructure::tenant;
+use crate::repository::analytics::AnalyticsRepository;
+use crate::service::jobs::JobHandler;
+
+/// One day of a dataset written by an export run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportedPartition {
+    pub dataset: Dataset,
+    pub day: NaiveDate,
+    pub rows: usize,
+}
+
+/// Copies engagement and subscription events to the analytics bucket, one
+/// partition per dataset and finished UTC day, so the warehouse is loaded
+/// from there rather than from the OLTP database.
+///
+/// A day is written once, after it ends; a run that fails part way leaves
+/// the days it did not record to be written again by the next run, which
+/// replaces any partial file.
+#[derive(Clone)]
+pub struct AnalyticsExporter {
+    repository: Arc<dyn AnalyticsRepository>,
+    sink: Arc<dyn AnalyticsSink>,
+    pseudonyms: Option<Pseudonymizer>,
+}
+
+impl AnalyticsExporter {
+    pub fn new(repository: Arc<dyn AnalyticsRepository>, sink: Arc<dyn AnalyticsSink>) -> Self {
+        Self {
+            repository,
+            sink,
+            pseudonyms: None,
+        }
+    }
+
+    /// Export the subscriber's pseudonym in place of the address
+    pub fn with_pseudonyms(mut self, pseudonyms: Pseudonymizer) -> Self {
+        self.pseudonyms = Some(pseudonyms);
+        self
+    }
+
+    /// Write the days of every dataset that ended by `now` and were not
+    /// exported yet, across all tenants; returns what was written
+    pub async fn export(&self, now: DateTime<Utc>) -> Result<Vec<ExportedPartition>> {
+        let Some(last_finished) = last_finished_day(now) else {
+            return Ok(Vec::new());
+        };
+
+        tenant::scope(TenantScope::All, async {
+            let mut exported = Vec::new();
+            for dataset in Dataset::ALL {
+                let last_exported = self.repository.last_exported(dataset).await?;
+                let first_row = self.repository.first_day(dataset).await?;
+
+                for day in days_to_export(last_exported, first_row, last_finished) {
+                    let mut rows = self.repository.rows(dataset, day).await?;
+                    if let Some(pseudonyms) = &self.pseudonyms {
+                        rows.map_emails(|email| pseudonyms.pseudonym(email));
+                    }
+
+                    self.sink.write(day, &rows).await?;
+                    self.repository.mark_exported(dataset, day, rows.len()).await?;
+                    exported.push(ExportedPartition { dataset, day, rows: rows.len() });
+                }
+            }
+            Ok::<_, anyhow::Error>(exported)
+        })
+        .await
+    }
+}
+
+/// Recurring export of the days that ended since the last run
+#[async_trait]
+impl JobHandler for AnalyticsExporter {
+    async fn run(&self, _job: &Job) -> Result<()> {
+        for partition in self.export(Utc::now()).await? {
+            info!(sink = self.sink.name(), dataset = %partition.dataset, day = %partition.day, rows = partition.rows, "Exported analytics partition");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::domain::auth::{self, ApiKey};
+use crate::repository::api_key::ApiKeyRepository;
+
+/// How long a lookup result is reused; bounds how late a revocation takes effect
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Cached lookups kept before expired ones are swept
+const CACHE_SWEEP_THRESHOLD: usize = 10_000;
+
+/// Service trait for API key authentication
+#[async_trait]
+pub trait AuthService: Send + Sync {
+    /// Resolve a presented key; `None` if it is unknown or revoked
+    async fn authenticate(&self, key: &str) -> Result<Option<ApiKey>>;
+}
+
+/// Default implementation of the auth service, with a short-lived cache so
+/// that every RPC does not cost a database round trip
+pub struct DefaultAuthService<R: ApiKeyRepository> {
+    repository: Arc<R>,
+    cache_ttl: Duration,
+    cache: Mutex<HashMap<String, (Option<ApiKey>, Instant)>>,
+}
+
+impl<R: ApiKeyRepository> DefaultAuthService<R> {
+    pub fn new(repository: Arc<R>, cache_ttl: Duration) -> Self {
+        Self {
+            repository,
+            cache_ttl,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, HashMap<String, (Option<ApiKey>, Instant)>> {
+        self.cache.lock().expect("api key cache lock poisoned")
+    }
+}
+
+#[async_trait]
+impl<R: ApiKeyRepository + 'static> AuthService for DefaultAuthService<R> {
+    async fn authenticate(&self, key: &str) -> Result<Option<ApiKey>> {
+        let key_hash = auth::hash_key(key);
+        let now = Instant::now();
+
+        if let Some((api_key, cached_at)) = self.cache().get(&key_hash) {
+            if now.duration_since(*cached_at) < self.cache_ttl {
+                return Ok(api_key.clone());
+            }
+        }
+
+        let api_key = self.repository.find_active(&key_hash).await?;
+
+        let mut cache = self.cache();
+        if cache.len() >= CACHE_SWEEP_THRESHOLD {
+            cache.retain(|_, (_, cached_at)| now.duration_since(*cached_at) < self.cache_ttl);
+        }
+        cache.insert(key_hash, (api_key.clone(), now));
+
+        Ok(api_key)
+    }
+}
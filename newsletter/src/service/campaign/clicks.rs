@@ -0,0 +1,56 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::tenant;
+use crate::service::campaign::tracking::TrackingLinks;
+use crate::service::campaign::CampaignService;
+
+/// Link click announced by the shortlink service
+#[derive(Deserialize)]
+struct LinkClicked {
+    /// The link that was followed
+    url: String,
+}
+
+/// Turns the shortlink platform's click events into campaign engagement.
+///
+/// Only clicks on campaign tracking links count; every other link of the
+/// platform is ignored. This is for deployments where the shortlink
+/// service answers tracking links itself instead of forwarding them to the
+/// tracking server, so each click is recorded once either way.
+pub struct ClickRecorder {
+    links: TrackingLinks,
+    campaigns: Arc<dyn CampaignService>,
+}
+
+impl ClickRecorder {
+    pub fn new(links: TrackingLinks, campaigns: Arc<dyn CampaignService>) -> Self {
+        Self { links, campaigns }
+    }
+
+    /// Record the click an event payload describes, if it is one of ours.
+    /// Undecodable payloads are dropped; an error means the click could not
+    /// be stored and the event should be delivered again.
+    pub async fn handle(&self, payload: &[u8]) -> Result<()> {
+        let clicked: LinkClicked = match serde_json::from_slice(payload) {
+            Ok(clicked) => clicked,
+            Err(e) => {
+                warn!(error = %e, "Dropping undecodable click event");
+                return Ok(());
+            }
+        };
+        let Some(event) = self.links.verify_click_url(&clicked.url) else {
+            debug!(url = %clicked.url, "Click is not on a campaign link");
+            return Ok(());
+        };
+
+        // Tracking links name only the campaign, which decides the tenant
+        let campaign_id = event.campaign_id;
+        tenant::scope(TenantScope::All, self.campaigns.record_engagement(event)).await?;
+        info!(campaign_id = campaign_id, kind = "click", "Recorded click from the shortlink service");
+        Ok(())
+    }
+}
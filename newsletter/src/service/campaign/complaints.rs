@@ -0,0 +1,59 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::domain::campaign::complaint::{Complaint, ComplaintError, FeedbackReport};
+use crate::domain::newsletter::lifecycle::SubscriptionStatus;
+use crate::domain::newsletter::EmailAddress;
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::{logging, tenant};
+use crate::service::campaign::unsubscribe::UnsubscribeLinks;
+use crate::service::campaign::CampaignService;
+use crate::service::newsletter::NewsletterService;
+
+/// Turns feedback-loop complaint reports into suppressions.
+///
+/// Providers often redact the complaining address, so the recipient, its
+/// tenant and the campaign are read from the signed unsubscribe link of the
+/// returned email instead; a report without one cannot be attributed and
+/// is refused. That also keeps forged reports from suppressing anyone.
+pub struct ComplaintProcessor {
+    links: UnsubscribeLinks,
+    campaigns: Arc<dyn CampaignService>,
+    newsletters: Arc<dyn NewsletterService>,
+}
+
+impl ComplaintProcessor {
+    pub fn new(links: UnsubscribeLinks, campaigns: Arc<dyn CampaignService>, newsletters: Arc<dyn NewsletterService>) -> Self {
+        Self { links, campaigns, newsletters }
+    }
+
+    /// Suppress the recipient of a raw ARF report and record the complaint
+    /// against its campaign. Fails with a [`ComplaintError`] for reports
+    /// that are malformed or not about our email.
+    pub async fn process(&self, raw: &str) -> Result<Complaint> {
+        let report = FeedbackReport::parse(raw)?;
+        let link = self
+            .links
+            .find_in(&report.original_message)
+            .ok_or(ComplaintError::UnknownRecipient)?;
+        let email = EmailAddress::parse(&link.email).map_err(|_| ComplaintError::UnknownRecipient)?;
+
+        let complaint = Complaint {
+            campaign_id: link.campaign_id,
+            email: link.email.clone(),
+            feedback_type: report.feedback_type,
+            user_agent: report.user_agent,
+        };
+        let recorded = tenant::scope(TenantScope::One(link.tenant.clone()), async {
+            self.newsletters
+                .update_subscription_status(vec![email], SubscriptionStatus::Suppressed)
+                .await?;
+            self.campaigns.record_complaint(complaint.clone()).await
+        })
+        .await?;
+
+        info!(campaign_id = complaint.campaign_id, tenant = %link.tenant, email = %logging::email(&complaint.email), feedback_type = %complaint.feedback_type, recorded = recorded, "Suppressed subscriber after a complaint");
+        Ok(complaint)
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::domain::campaign::digest::Digest;
+use crate::domain::campaign::SendWindow;
+use crate::domain::jobs::{AssembleDigest, Job};
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::feed::ContentSource;
+use crate::infrastructure::tenant;
+use crate::repository::jobs::JobRepository;
+use crate::service::campaign::CampaignService;
+use crate::service::jobs::JobHandler;
+
+/// Assembles digest campaigns on their schedules.
+///
+/// Each due digest is an `AssembleDigest` job in its tenant, which queues the
+/// next occurrence before fetching anything, so a source that is down only
+/// costs that one digest. Jobs are keyed by topic and due time, so any number
+/// of instances can start the same digests.
+pub struct DigestScheduler {
+    digests: Vec<Digest>,
+    source: Arc<dyn ContentSource>,
+    campaigns: Arc<dyn CampaignService>,
+    jobs: Arc<dyn JobRepository>,
+}
+
+impl DigestScheduler {
+    pub fn new(
+        digests: Vec<Digest>,
+        source: Arc<dyn ContentSource>,
+        campaigns: Arc<dyn CampaignService>,
+        jobs: Arc<dyn JobRepository>,
+    ) -> Self {
+        Self {
+            digests,
+            source,
+            campaigns,
+            jobs,
+        }
+    }
+
+    /// Queue the next occurrence of every digest
+    pub async fn start(&self) -> Result<()> {
+        let now = Utc::now();
+        for digest in &self.digests {
+            tenant::scope(TenantScope::One(digest.tenant.clone()), self.queue_after(digest, now, None)).await?;
+        }
+        Ok(())
+    }
+
+    /// Queue the first occurrence after `after`, if the schedule has one
+    async fn queue_after(&self, digest: &Digest, after: DateTime<Utc>, since: Option<DateTime<Utc>>) -> Result<()> {
+        let Some(due_at) = digest.next_after(after) else {
+            warn!(topic = %digest.topic, "Digest schedule has no further occurrences");
+            return Ok(());
+        };
+
+        let next = AssembleDigest {
+            topic: digest.topic.clone(),
+            due_at,
+            since,
+        };
+        if self.jobs.enqueue(&next.job(&digest.tenant)?).await?.is_some() {
+            info!(topic = %digest.topic, tenant = %digest.tenant, due_at = %due_at, "Queued digest");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl JobHandler for DigestScheduler {
+    async fn run(&self, job: &Job) -> Result<()> {
+        let AssembleDigest { topic, due_at, since } = job.payload()?;
+        let scope = tenant::current();
+        let Some(digest) = self
+            .digests
+            .iter()
+            .find(|digest| digest.topic == topic && scope.tenant() == Some(&digest.tenant))
+        else {
+            // Removed from the configuration since it was queued
+            info!(topic = %topic, tenant = %scope, "Digest is no longer configured");
+            return Ok(());
+        };
+
+        self.queue_after(digest, due_at, Some(due_at)).await?;
+
+        let items = self.source.fetch(&digest.source_url, digest.source_format).await?;
+        let items = digest.select(items, since);
+        if items.is_empty() {
+            info!(topic = %topic, due_at = %due_at, "No new entries, skipping digest");
+            return Ok(());
+        }
+
+        let campaign = self.campaigns.create_campaign(digest.campaign(&items, due_at)).await?;
+        let now = Utc::now();
+        let window = SendWindow::new(now, now + digest.send_window)?;
+        self.campaigns.schedule_campaign(campaign.id, window).await?;
+        info!(topic = %topic, campaign_id = campaign.id, items = items.len(), "Scheduled digest campaign");
+        Ok(())
+    }
+}
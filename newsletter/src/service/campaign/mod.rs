@@ -0,0 +1,280 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::domain::campaign::audience::{AudienceEstimate, AudienceFilter, AUDIENCE_SAMPLE_SIZE};
+use crate::domain::campaign::complaint::Complaint;
+use crate::domain::campaign::engagement::{DomainStats, EngagementEvent, EngagementStats, LinkEngagement};
+use crate::domain::campaign::test_send::{TestRecipients, TestSend, TestSendOutcome};
+use crate::domain::campaign::{Campaign, CampaignError, CampaignStatus, CampaignUpdate, NewCampaign, SendWindow};
+use crate::domain::jobs::{DispatchCampaign, Job, JobKind, NewJob, SendCampaignBatch};
+use crate::domain::pagination::{Page, PageRequest};
+use crate::infrastructure::email::{EmailMessage, MailSender};
+use crate::repository::campaign::CampaignRepository;
+use crate::repository::jobs::JobRepository;
+use crate::service::jobs::{JobHandler, PermanentJobError};
+use crate::service::template::TemplateService;
+
+pub mod clicks;
+pub mod complaints;
+pub mod digest;
+pub mod reengagement;
+pub mod sender;
+pub mod sending_domains;
+pub mod tracking;
+pub mod unsubscribe;
+pub mod warmup;
+
+/// Service trait for campaign management
+#[async_trait]
+pub trait CampaignService: Send + Sync {
+    /// Create a draft campaign
+    async fn create_campaign(&self, campaign: NewCampaign) -> Result<Campaign>;
+
+    /// Update an editable campaign; returns `None` if it does not exist
+    async fn update_campaign(&self, id: i64, update: CampaignUpdate) -> Result<Option<Campaign>>;
+
+    /// Schedule a campaign for sending within the given window
+    async fn schedule_campaign(&self, id: i64, window: SendWindow) -> Result<Option<Campaign>>;
+
+    /// Cancel a draft or scheduled campaign
+    async fn cancel_campaign(&self, id: i64) -> Result<Option<Campaign>>;
+
+    /// Get a page of campaigns
+    async fn list_campaigns(&self, page: PageRequest) -> Result<Page<Campaign>>;
+
+    /// Record an open or click from a tracking link
+    async fn record_engagement(&self, event: EngagementEvent) -> Result<()>;
+
+    /// Record a recipient's spam complaint about a campaign; returns false
+    /// for a repeated report
+    async fn record_complaint(&self, complaint: Complaint) -> Result<bool>;
+
+    /// Open, click and complaint rates of a campaign; returns `None` if it does not exist
+    async fn campaign_engagement(&self, id: i64) -> Result<Option<EngagementStats>>;
+
+    /// Clicks per link of a campaign; returns `None` if it does not exist
+    async fn link_engagement(&self, id: i64) -> Result<Option<Vec<LinkEngagement>>>;
+
+    /// Subscribers, bounces and opens per email domain, most subscribers first
+    async fn domain_stats(&self, page: PageRequest) -> Result<Page<DomainStats>>;
+
+    /// How many active subscribers a segment holds, with a sample of them,
+    /// to size a campaign before sending it
+    async fn estimate_audience(&self, filter: AudienceFilter) -> Result<AudienceEstimate>;
+
+    /// Render a campaign for a sample subscriber and email it to allow-listed
+    /// internal addresses, in any status and without touching its deliveries
+    /// or engagement; returns `None` if it does not exist
+    async fn send_test_email(&self, id: i64, test: TestSend) -> Result<Option<TestSendOutcome>>;
+}
+
+/// What test sends render with, send through, and may send to
+#[derive(Clone)]
+struct TestSender {
+    templates: Arc<dyn TemplateService>,
+    mailer: Arc<dyn MailSender>,
+    recipients: TestRecipients,
+}
+
+/// Default implementation of the campaign service
+#[derive(Clone)]
+pub struct DefaultCampaignService<R: CampaignRepository> {
+    repository: Arc<R>,
+    jobs: Arc<dyn JobRepository>,
+    /// Test sends are refused without it
+    test_sender: Option<TestSender>,
+}
+
+impl<R: CampaignRepository> DefaultCampaignService<R> {
+    pub fn new(repository: Arc<R>, jobs: Arc<dyn JobRepository>) -> Self {
+        Self {
+            repository,
+            jobs,
+            test_sender: None,
+        }
+    }
+
+    pub fn with_test_sends(
+        mut self,
+        templates: Arc<dyn TemplateService>,
+        mailer: Arc<dyn MailSender>,
+        recipients: TestRecipients,
+    ) -> Self {
+        self.test_sender = Some(TestSender {
+            templates,
+            mailer,
+            recipients,
+        });
+        self
+    }
+
+    /// Load a campaign, apply a domain change and persist it
+    async fn modify<F>(&self, id: i64, change: F) -> Result<Option<Campaign>>
+    where
+        F: FnOnce(&mut Campaign) -> Result<(), CampaignError> + Send,
+    {
+        let Some(mut campaign) = self.repository.get(id).await? else {
+            return Ok(None);
+        };
+
+        change(&mut campaign)?;
+        self.repository.save(&campaign).await.map(Some)
+    }
+}
+
+#[async_trait]
+impl<R: CampaignRepository + 'static> CampaignService for DefaultCampaignService<R> {
+    async fn create_campaign(&self, campaign: NewCampaign) -> Result<Campaign> {
+        campaign.validate()?;
+        self.repository.create(&campaign).await
+    }
+
+    async fn update_campaign(&self, id: i64, update: CampaignUpdate) -> Result<Option<Campaign>> {
+        self.modify(id, |campaign| campaign.apply_update(update)).await
+    }
+
+    /// Rescheduling queues another dispatch; the earlier one finds the window
+    /// moved and does nothing
+    async fn schedule_campaign(&self, id: i64, window: SendWindow) -> Result<Option<Campaign>> {
+        let Some(campaign) = self.modify(id, |campaign| campaign.schedule(window)).await? else {
+            return Ok(None);
+        };
+
+        let job = NewJob::new(JobKind::DispatchCampaign, &DispatchCampaign { campaign_id: id })?
+            .run_at(window.start);
+        self.jobs.enqueue(&job).await?;
+        Ok(Some(campaign))
+    }
+
+    async fn cancel_campaign(&self, id: i64) -> Result<Option<Campaign>> {
+        self.modify(id, |campaign| campaign.cancel()).await
+    }
+
+    async fn list_campaigns(&self, page: PageRequest) -> Result<Page<Campaign>> {
+        self.repository.list(page).await
+    }
+
+    async fn record_engagement(&self, event: EngagementEvent) -> Result<()> {
+        self.repository.record_engagement(&event).await
+    }
+
+    async fn record_complaint(&self, complaint: Complaint) -> Result<bool> {
+        self.repository.record_complaint(&complaint).await
+    }
+
+    async fn campaign_engagement(&self, id: i64) -> Result<Option<EngagementStats>> {
+        if self.repository.get(id).await?.is_none() {
+            return Ok(None);
+        }
+        self.repository.engagement_stats(id).await.map(Some)
+    }
+
+    async fn link_engagement(&self, id: i64) -> Result<Option<Vec<LinkEngagement>>> {
+        if self.repository.get(id).await?.is_none() {
+            return Ok(None);
+        }
+        self.repository.link_engagement(id).await.map(Some)
+    }
+
+    async fn domain_stats(&self, page: PageRequest) -> Result<Page<DomainStats>> {
+        self.repository.domain_stats(page).await
+    }
+
+    async fn estimate_audience(&self, filter: AudienceFilter) -> Result<AudienceEstimate> {
+        self.repository.estimate_audience(&filter, AUDIENCE_SAMPLE_SIZE).await
+    }
+
+    async fn send_test_email(&self, id: i64, test: TestSend) -> Result<Option<TestSendOutcome>> {
+        let Some(sender) = &self.test_sender else {
+            return Err(CampaignError::Validation("test sends are not configured".to_string()).into());
+        };
+        sender.recipients.check(&test.recipients)?;
+
+        let Some(campaign) = self.repository.get(id).await? else {
+            return Ok(None);
+        };
+
+        let email = test.email.as_deref().unwrap_or(&test.recipients[0]);
+        let context = campaign.render_context(email, &test.attributes);
+        let Some(rendered) = sender
+            .templates
+            .render(campaign.template_id, &context, test.locale.as_deref())
+            .await?
+        else {
+            return Err(CampaignError::TemplateNotFound(campaign.template_id).into());
+        };
+
+        let subject = format!("[Test] {}", campaign.subject);
+        for to in &test.recipients {
+            let message = EmailMessage {
+                to: to.clone(),
+                subject: subject.clone(),
+                html: rendered.html.clone(),
+                text: Some(rendered.text.clone()),
+                headers: Vec::new(),
+                sender: None,
+            };
+            sender.mailer.send(&message).await?;
+        }
+        info!(campaign_id = id, recipients = test.recipients.len(), "Sent campaign test email");
+
+        Ok(Some(TestSendOutcome {
+            subject,
+            html: rendered.html,
+            locale: rendered.locale,
+            sent_to: test.recipients,
+        }))
+    }
+}
+
+/// Starts sending a scheduled campaign when its dispatch job comes due:
+/// records its audience and queues the first batch for `CampaignSender`
+pub struct CampaignDispatcher<R: CampaignRepository> {
+    repository: Arc<R>,
+    jobs: Arc<dyn JobRepository>,
+}
+
+impl<R: CampaignRepository> CampaignDispatcher<R> {
+    pub fn new(repository: Arc<R>, jobs: Arc<dyn JobRepository>) -> Self {
+        Self { repository, jobs }
+    }
+
+    async fn queue_first_batch(&self, campaign_id: i64) -> Result<()> {
+        let job = SendCampaignBatch { campaign_id, batch: 0 }.job()?;
+        self.jobs.enqueue(&job).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<R: CampaignRepository + 'static> JobHandler for CampaignDispatcher<R> {
+    async fn run(&self, job: &Job) -> Result<()> {
+        let DispatchCampaign { campaign_id } = job.payload()?;
+        let Some(mut campaign) = self.repository.get(campaign_id).await? else {
+            return Ok(());
+        };
+
+        let now = Utc::now();
+        match campaign.send_window {
+            // Started before a crash, but the first batch may never have been queued
+            _ if campaign.status == CampaignStatus::Sending => return self.queue_first_batch(campaign_id).await,
+            // Cancelled, sent, or back to draft
+            _ if campaign.status != CampaignStatus::Scheduled => return Ok(()),
+            // Rescheduled; a later dispatch job covers the new window
+            Some(window) if window.start > now => return Ok(()),
+            Some(window) if window.end <= now => {
+                warn!(campaign_id = campaign_id, window_end = %window.end, "Send window closed before the campaign was dispatched");
+                return Err(PermanentJobError("send window closed".to_string()).into());
+            }
+            _ => {}
+        }
+
+        campaign.start_sending(now)?;
+        let recipients = self.repository.start_sending(&campaign).await?;
+        info!(campaign_id = campaign_id, recipients = recipients, "Campaign dispatched");
+        self.queue_first_batch(campaign_id).await
+    }
+}
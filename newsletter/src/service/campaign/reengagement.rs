@@ -0,0 +1,132 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::domain::campaign::reengagement::Reengagement;
+use crate::domain::campaign::SendWindow;
+use crate::domain::jobs::{Job, RunReengagement};
+use crate::domain::newsletter::lifecycle::SubscriptionStatus;
+use crate::domain::newsletter::EmailAddress;
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::tenant;
+use crate::repository::campaign::CampaignRepository;
+use crate::repository::jobs::JobRepository;
+use crate::service::campaign::CampaignService;
+use crate::service::jobs::JobHandler;
+use crate::service::newsletter::NewsletterService;
+
+/// Runs each tenant's re-engagement flow on its schedule.
+///
+/// A run first settles the segment: members who engaged again or left on
+/// their own are untagged, and those whose grace period ran out are
+/// unsubscribed. It then tags the subscribers who went quiet since the last
+/// run and sends them the win-back campaign. Runs are `RunReengagement`
+/// jobs keyed by tenant and due time, like digests.
+pub struct ReengagementScheduler {
+    flows: Vec<Reengagement>,
+    repository: Arc<dyn CampaignRepository>,
+    campaigns: Arc<dyn CampaignService>,
+    newsletters: Arc<dyn NewsletterService>,
+    jobs: Arc<dyn JobRepository>,
+}
+
+impl ReengagementScheduler {
+    pub fn new(
+        flows: Vec<Reengagement>,
+        repository: Arc<dyn CampaignRepository>,
+        campaigns: Arc<dyn CampaignService>,
+        newsletters: Arc<dyn NewsletterService>,
+        jobs: Arc<dyn JobRepository>,
+    ) -> Self {
+        Self {
+            flows,
+            repository,
+            campaigns,
+            newsletters,
+            jobs,
+        }
+    }
+
+    /// Queue the next run of every flow
+    pub async fn start(&self) -> Result<()> {
+        let now = Utc::now();
+        for flow in &self.flows {
+            tenant::scope(TenantScope::One(flow.tenant.clone()), self.queue_after(flow, now)).await?;
+        }
+        Ok(())
+    }
+
+    async fn queue_after(&self, flow: &Reengagement, after: DateTime<Utc>) -> Result<()> {
+        let Some(due_at) = flow.next_after(after) else {
+            warn!(tenant = %flow.tenant, "Re-engagement schedule has no further occurrences");
+            return Ok(());
+        };
+
+        if self.jobs.enqueue(&RunReengagement { due_at }.job(&flow.tenant)?).await?.is_some() {
+            info!(tenant = %flow.tenant, due_at = %due_at, "Queued re-engagement run");
+        }
+        Ok(())
+    }
+
+    /// Untag members who leave the segment and unsubscribe the lapsed ones
+    async fn settle(&self, flow: &Reengagement, now: DateTime<Utc>) -> Result<()> {
+        let members = self.repository.segment_members(flow.segment.as_str()).await?;
+        let review = flow.review(members, now);
+
+        if !review.lapsed.is_empty() {
+            self.newsletters
+                .update_subscription_status(addresses(&review.lapsed), SubscriptionStatus::Unsubscribed)
+                .await?;
+            info!(segment = %flow.segment, count = review.lapsed.len(), "Unsubscribed lapsed subscribers");
+        }
+
+        let leaving: Vec<String> = review.released.into_iter().chain(review.lapsed).collect();
+        if !leaving.is_empty() {
+            self.newsletters.untag_subscribers(addresses(&leaving), &flow.segment).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl JobHandler for ReengagementScheduler {
+    async fn run(&self, job: &Job) -> Result<()> {
+        let RunReengagement { due_at } = job.payload()?;
+        let scope = tenant::current();
+        let Some(flow) = self.flows.iter().find(|flow| scope.tenant() == Some(&flow.tenant)) else {
+            // Removed from the configuration since it was queued
+            info!(tenant = %scope, "Re-engagement is no longer configured");
+            return Ok(());
+        };
+
+        self.queue_after(flow, due_at).await?;
+
+        let now = Utc::now();
+        self.settle(flow, now).await?;
+
+        let quiet = self.repository.find_unengaged(flow.inactive_since(now), flow.segment.as_str()).await?;
+        if quiet.is_empty() {
+            info!(due_at = %due_at, "No newly inactive subscribers");
+            return Ok(());
+        }
+
+        // Joining the segment comes last: until then a failed run finds the
+        // same subscribers again on retry instead of leaving them without
+        // a win-back mail
+        let cohort = flow.cohort(due_at)?;
+        self.newsletters.tag_subscribers(addresses(&quiet), &cohort).await?;
+        let campaign = self.campaigns.create_campaign(flow.campaign(&cohort, due_at)).await?;
+        let window = SendWindow::new(now, now + flow.send_window)?;
+        self.campaigns.schedule_campaign(campaign.id, window).await?;
+        self.newsletters.tag_subscribers(addresses(&quiet), &flow.segment).await?;
+
+        info!(segment = %flow.segment, campaign_id = campaign.id, count = quiet.len(), "Scheduled win-back campaign");
+        Ok(())
+    }
+}
+
+fn addresses(emails: &[String]) -> Vec<EmailAddress> {
+    emails.iter().filter_map(|email| EmailAddress::parse(email).ok()).collect()
+}
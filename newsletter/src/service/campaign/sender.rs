@@ -0,0 +1,286 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::domain::campaign::delivery::{DeliveryResult, Recipient};
+use crate::domain::campaign::sending_domain::SenderIdentity;
+use crate::domain::campaign::warmup;
+use crate::domain::campaign::{Campaign, CampaignStatus};
+use crate::domain::jobs::{Job, SendCampaignBatch};
+use crate::domain::locale;
+use crate::domain::template::asset;
+use crate::domain::template::translation::{self, TemplateTranslation};
+use crate::domain::template::Template;
+use crate::infrastructure::email::{EmailMessage, MailError, MailSender};
+use crate::infrastructure::template::TemplateEngine;
+use crate::repository::campaign::CampaignRepository;
+use crate::repository::jobs::JobRepository;
+use crate::repository::sending_domain::SendingDomainRepository;
+use crate::repository::template::TemplateRepository;
+use crate::infrastructure::tenant;
+use crate::service::campaign::tracking::TrackingLinks;
+use crate::service::campaign::unsubscribe::UnsubscribeLinks;
+use crate::service::campaign::warmup::WarmupLimiter;
+use crate::service::jobs::{JobHandler, PermanentJobError};
+
+/// How long a sender may hold claimed deliveries before another takes them over
+const DELIVERY_LEASE: Duration = Duration::minutes(10);
+
+/// Pace of campaign sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendThrottle {
+    /// Recipients per batch
+    pub batch_size: i64,
+    pub batches_per_minute: u32,
+}
+
+impl Default for SendThrottle {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            batches_per_minute: 6,
+        }
+    }
+}
+
+impl SendThrottle {
+    /// Wait between the start of one batch and the next
+    pub fn interval(&self) -> Duration {
+        Duration::milliseconds(60_000 / i64::from(self.batches_per_minute.max(1)))
+    }
+}
+
+/// Sends a campaign in throttled batches.
+///
+/// Every batch is its own job: it claims recipients from the campaign's
+/// deliveries, sends them the rendered template and queues the next batch.
+/// Each recipient gets the translation matching their locale, its language,
+/// or the first of the fallback locales, in that order; the template's own
+/// body when none exists.
+/// A domain warming up sends at most its daily cap across all campaigns;
+/// the rest of each waits for the next UTC day.
+/// A crash loses at most the batch in flight, which is resent once its lease
+/// runs out; batch jobs are keyed by their number, so a retried batch does
+/// not fork the chain.
+pub struct CampaignSender<R: CampaignRepository> {
+    campaigns: Arc<R>,
+    templates: Arc<dyn TemplateRepository>,
+    engine: TemplateEngine,
+    mailer: Arc<dyn MailSender>,
+    jobs: Arc<dyn JobRepository>,
+    throttle: SendThrottle,
+    /// Open and click tracking; emails go out untouched without it
+    tracking: Option<TrackingLinks>,
+    /// Per-recipient unsubscribe links, offered as `unsubscribe_url` and in
+    /// the `List-Unsubscribe` header
+    unsubscribe: Option<UnsubscribeLinks>,
+    /// Locales tried after the recipient's own, in order
+    locale_fallbacks: Vec<String>,
+    /// Tenants' sending domains; campaigns of a tenant with a verified one
+    /// go out from it, DKIM-signed
+    sending_domains: Option<Arc<dyn SendingDomainRepository>>,
+    /// Daily caps of domains warming up; batches go out in full without it
+    warmup: Option<WarmupLimiter>,
+}
+
+impl<R: CampaignRepository> CampaignSender<R> {
+    pub fn new(
+        campaigns: Arc<R>,
+        templates: Arc<dyn TemplateRepository>,
+        engine: TemplateEngine,
+        mailer: Arc<dyn MailSender>,
+        jobs: Arc<dyn JobRepository>,
+        throttle: SendThrottle,
+    ) -> Self {
+        Self {
+            campaigns,
+            templates,
+            engine,
+            mailer,
+            jobs,
+            throttle,
+            tracking: None,
+            unsubscribe: None,
+            locale_fallbacks: Vec::new(),
+            sending_domains: None,
+            warmup: None,
+        }
+    }
+
+    pub fn with_tracking(mut self, tracking: TrackingLinks) -> Self {
+        self.tracking = Some(tracking);
+        self
+    }
+
+    pub fn with_unsubscribe(mut self, links: UnsubscribeLinks) -> Self {
+        self.unsubscribe = Some(links);
+        self
+    }
+
+    pub fn with_locale_fallbacks(mut self, fallbacks: Vec<String>) -> Self {
+        self.locale_fallbacks = fallbacks;
+        self
+    }
+
+    pub fn with_sending_domains(mut self, domains: Arc<dyn SendingDomainRepository>) -> Self {
+        self.sending_domains = Some(domains);
+        self
+    }
+
+    pub fn with_warmup(mut self, warmup: WarmupLimiter) -> Self {
+        self.warmup = Some(warmup);
+        self
+    }
+
+    /// What the running tenant's campaigns are sent as; `None` sends from
+    /// `EMAIL_FROM` unsigned
+    async fn identity(&self) -> Result<Option<SenderIdentity>> {
+        let Some(domains) = &self.sending_domains else {
+            return Ok(None);
+        };
+        Ok(domains.get().await?.and_then(|domain| domain.identity()))
+    }
+
+    async fn send_to(
+        &self,
+        campaign: &Campaign,
+        template: &Template,
+        translations: &[TemplateTranslation],
+        recipient: &Recipient,
+        identity: Option<&SenderIdentity>,
+    ) -> DeliveryResult {
+        let chain = locale::lookup_chain(recipient.locale.as_deref(), &self.locale_fallbacks);
+        let (template, _) = translation::localize(template, translations, &chain);
+        let mut context = campaign.render_context(&recipient.email, &recipient.attributes);
+        // Batches run in their campaign's tenant, which the link must name
+        let unsubscribe_url = match (&self.unsubscribe, tenant::current().tenant()) {
+            (Some(links), Some(tenant)) => Some(links.url(campaign.id, tenant, &recipient.email)),
+            _ => None,
+        };
+        if let (Some(url), Some(context)) = (&unsubscribe_url, context.as_object_mut()) {
+            context.insert("unsubscribe_url".to_string(), url.clone().into());
+        }
+        let rendered = match self.engine.render(&template, &context) {
+            Ok(rendered) => rendered,
+            Err(e) => return DeliveryResult::Failed(e.to_string()),
+        };
+
+        // The plaintext was derived before instrumenting, so its links are
+        // the readable originals and it carries no open pixel
+        let html = match &self.tracking {
+            Some(tracking) => tracking.instrument(&rendered.html, campaign.id, &recipient.email),
+            None => rendered.html,
+        };
+
+        let message = EmailMessage {
+            to: recipient.email.clone(),
+            subject: campaign.subject.clone(),
+            html,
+            text: Some(rendered.text),
+            headers: unsubscribe_url.as_deref().map(UnsubscribeLinks::headers).unwrap_or_default(),
+            sender: identity.cloned(),
+        };
+        match self.mailer.send(&message).await {
+            Ok(()) => DeliveryResult::Sent,
+            Err(MailError::Permanent(e) | MailError::Rejected(e)) => DeliveryResult::Failed(e.to_string()),
+            Err(MailError::Transient(e)) => DeliveryResult::transient(recipient, e.to_string()),
+        }
+    }
+
+    async fn queue_next(&self, batch: SendCampaignBatch, after: Duration) -> Result<()> {
+        let next = SendCampaignBatch {
+            batch: batch.batch + 1,
+            ..batch
+        };
+        let job = next.job()?.run_at(Utc::now() + after);
+        self.jobs.enqueue(&job).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<R: CampaignRepository + 'static> JobHandler for CampaignSender<R> {
+    async fn run(&self, job: &Job) -> Result<()> {
+        let batch: SendCampaignBatch = job.payload()?;
+        let campaign_id = batch.campaign_id;
+        let Some(mut campaign) = self.campaigns.get(campaign_id).await? else {
+            return Ok(());
+        };
+        if campaign.status != CampaignStatus::Sending {
+            return Ok(());
+        }
+
+        let Some(template) = self.templates.get(campaign.template_id).await? else {
+            error!(campaign_id = campaign_id, template_id = campaign.template_id, "Campaign template no longer exists");
+            return Err(PermanentJobError(format!("template {} not found", campaign.template_id)).into());
+        };
+
+        let translations = self.templates.list_translations(template.id).await?;
+        let identity = self.identity().await?;
+
+        // A domain on its warm-up ramp sends what is left of its daily cap
+        let reservation = match &self.warmup {
+            Some(warmup) => {
+                let domain = identity.as_ref().map(|identity| identity.domain.as_str());
+                warmup.reserve(domain, self.throttle.batch_size, Utc::now()).await?
+            }
+            None => None,
+        };
+        let limit = reservation.as_ref().map_or(self.throttle.batch_size, |r| r.granted);
+
+        let recipients = match limit {
+            0 => Vec::new(),
+            limit => self.campaigns.claim_deliveries(campaign_id, limit, DELIVERY_LEASE).await?,
+        };
+        if let (Some(warmup), Some(reservation)) = (&self.warmup, &reservation) {
+            warmup.release(reservation, recipients.len() as i64).await?;
+        }
+
+        // The emails link to the assets for good, so they are never collected
+        let assets = asset::template_references(&template, &translations);
+        if !recipients.is_empty() && !assets.is_empty() {
+            self.templates.mark_assets_sent(&assets, Utc::now()).await?;
+        }
+
+        let mut results = Vec::with_capacity(recipients.len());
+        for recipient in &recipients {
+            let result = self
+                .send_to(&campaign, &template, &translations, recipient, identity.as_ref())
+                .await;
+            results.push((recipient.email.clone(), result));
+        }
+        self.campaigns.record_deliveries(campaign_id, &results).await?;
+
+        let sent = results.iter().filter(|(_, r)| *r == DeliveryResult::Sent).count();
+        info!(campaign_id = campaign_id, claimed = recipients.len(), sent = sent, "Sent campaign batch");
+
+        let counts = self.campaigns.delivery_counts(campaign_id).await?;
+        if counts.pending > 0 {
+            // Deliveries held for their recipients' local hours wait as a
+            // queued job, so they outlive restarts
+            let interval = self.throttle.interval();
+            let mut after = match self.campaigns.next_delivery_at(campaign_id).await? {
+                Some(at) => (at - Utc::now()).max(interval),
+                None => interval,
+            };
+            // The rest waits for the domain's next day on the ramp
+            if limit == 0 {
+                let now = Utc::now();
+                after = after.max(warmup::next_day(now) - now);
+                info!(campaign_id = campaign_id, pending = counts.pending, "Warm-up cap reached, deferring the rest of the campaign to tomorrow");
+            }
+            return self.queue_next(batch, after).await;
+        }
+        if counts.sending > 0 {
+            // Held by another sender; look again once its lease could have run out
+            return self.queue_next(batch, DELIVERY_LEASE).await;
+        }
+
+        campaign.finish_sending()?;
+        self.campaigns.save(&campaign).await?;
+        info!(campaign_id = campaign_id, sent = counts.sent, failed = counts.failed, skipped = counts.skipped, "Campaign sent");
+        Ok(())
+    }
+}
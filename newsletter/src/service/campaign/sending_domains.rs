@@ -0,0 +1,118 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::domain::campaign::sending_domain::{DomainVerification, NewSendingDomain, SendingDomain, SendingDomainError};
+use crate::infrastructure::dkim;
+use crate::repository::sending_domain::SendingDomainRepository;
+
+/// Reads the TXT records published at a name
+#[async_trait]
+pub trait TxtLookup: Send + Sync {
+    /// Every TXT record at `name`, its strings joined; empty when there is none
+    async fn txt_records(&self, name: &str) -> Result<Vec<String>>;
+}
+
+/// Service trait for the domain a tenant's campaigns are sent from
+#[async_trait]
+pub trait SendingDomainService: Send + Sync {
+    /// Configure the tenant's sending domain with a new, pending DKIM key
+    async fn create_sending_domain(&self, domain: NewSendingDomain) -> Result<SendingDomain>;
+
+    /// The tenant's sending domain and the DNS records it needs
+    async fn get_sending_domain(&self) -> Result<Option<SendingDomain>>;
+
+    /// Look the pending key up in DNS and start signing with it once it is
+    /// published
+    async fn verify_sending_domain(&self) -> Result<DomainVerification>;
+
+    /// Generate a new pending key; the active one keeps signing until the
+    /// new one is verified
+    async fn rotate_dkim_key(&self) -> Result<SendingDomain>;
+
+    /// Stop sending from the tenant's domain; returns whether it had one
+    async fn delete_sending_domain(&self) -> Result<bool>;
+}
+
+/// Default implementation of the sending domain service
+pub struct DefaultSendingDomainService {
+    repository: Arc<dyn SendingDomainRepository>,
+    dns: Arc<dyn TxtLookup>,
+}
+
+impl DefaultSendingDomainService {
+    pub fn new(repository: Arc<dyn SendingDomainRepository>, dns: Arc<dyn TxtLookup>) -> Self {
+        Self { repository, dns }
+    }
+
+    async fn current(&self) -> Result<SendingDomain> {
+        self.repository
+            .get()
+            .await?
+            .ok_or_else(|| SendingDomainError::NotFound.into())
+    }
+}
+
+#[async_trait]
+impl SendingDomainService for DefaultSendingDomainService {
+    async fn create_sending_domain(&self, domain: NewSendingDomain) -> Result<SendingDomain> {
+        let key = dkim::generate_key().await?;
+        match self.repository.create(&domain, &key).await? {
+            Some(created) => Ok(created),
+            None => {
+                let existing = self.repository.get().await?.map_or(domain.domain, |d| d.domain);
+                Err(SendingDomainError::AlreadyExists(existing).into())
+            }
+        }
+    }
+
+    async fn get_sending_domain(&self) -> Result<Option<SendingDomain>> {
+        self.repository.get().await
+    }
+
+    async fn verify_sending_domain(&self) -> Result<DomainVerification> {
+        let domain = self.current().await?;
+        let Some(pending) = &domain.pending_key else {
+            return Ok(DomainVerification { domain, verified: true });
+        };
+
+        let record = pending.dns_record(&domain.domain);
+        let found = self.dns.txt_records(&record.name).await?;
+        if !pending.published_in(&found) {
+            info!(domain = %domain.domain, selector = %pending.selector, "DKIM key not published yet");
+            return Ok(DomainVerification { domain, verified: false });
+        }
+
+        // Gone when a rotation replaced the key since it was read
+        let Some(activated) = self.repository.activate_key(domain.id, pending.id, Utc::now()).await? else {
+            let domain = self.current().await?;
+            return Ok(DomainVerification { domain, verified: false });
+        };
+        info!(domain = %activated.domain, selector = %pending.selector, "Activated DKIM key");
+        Ok(DomainVerification {
+            domain: activated,
+            verified: true,
+        })
+    }
+
+    async fn rotate_dkim_key(&self) -> Result<SendingDomain> {
+        let domain = self.current().await?;
+        let key = dkim::generate_key().await?;
+        let rotated = self
+            .repository
+            .replace_pending_key(domain.id, &key)
+            .await?
+            .ok_or(SendingDomainError::NotFound)?;
+        info!(domain = %rotated.domain, selector = %key.selector, "Generated DKIM key");
+        Ok(rotated)
+    }
+
+    async fn delete_sending_domain(&self) -> Result<bool> {
+        match self.repository.get().await? {
+            Some(domain) => self.repository.delete(domain.id).await,
+            None => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::campaign::engagement::{EngagementEvent, EngagementKind};
+use crate::infrastructure::token::TokenSigner;
+
+/// Who a tracking link belongs to, carried in its signed token. Unknown
+/// fields are refused so an unsubscribe token, signed with the same key,
+/// cannot pass for one.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TrackedRecipient {
+    #[serde(rename = "c")]
+    campaign_id: i64,
+    #[serde(rename = "e")]
+    email: String,
+    /// Link target; only click tokens carry one
+    #[serde(rename = "u", default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+/// Builds and verifies the open pixel and click links embedded in campaign
+/// emails.
+///
+/// The recipient and link target are signed into the link itself, so the
+/// tracking routes need no lookup and cannot be used as an open redirect.
+#[derive(Clone)]
+pub struct TrackingLinks {
+    signer: TokenSigner,
+    /// Public base of the tracking routes, usually served behind the shortlink domain
+    base_url: String,
+}
+
+impl TrackingLinks {
+    pub fn new(signer: TokenSigner, base_url: impl Into<String>) -> Self {
+        Self {
+            signer,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// URL of the 1x1 pixel that records an open
+    pub fn open_url(&self, campaign_id: i64, email: &str) -> String {
+        format!("{}/open/{}", self.base_url, self.token(campaign_id, email, None))
+    }
+
+    /// URL that records a click and redirects to `url`
+    pub fn click_url(&self, campaign_id: i64, email: &str, url: &str) -> String {
+        format!("{}/click/{}", self.base_url, self.token(campaign_id, email, Some(url)))
+    }
+
+    /// The event a token from an open or click URL stands for, if its
+    /// signature holds and it was issued for that kind of link
+    pub fn verify(&self, token: &str, kind: EngagementKind) -> Option<EngagementEvent> {
+        let payload = URL_SAFE_NO_PAD.decode(self.signer.verify(token)?).ok()?;
+        let recipient: TrackedRecipient = serde_json::from_slice(&payload).ok()?;
+
+        match (kind, &recipient.url) {
+            (EngagementKind::Open, None) | (EngagementKind::Click, Some(_)) => Some(EngagementEvent {
+                campaign_id: recipient.campaign_id,
+                email: recipient.email,
+                kind,
+                url: recipient.url,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The click a full click URL stands for, if it points at these
+    /// tracking routes and its token verifies
+    pub fn verify_click_url(&self, url: &str) -> Option<EngagementEvent> {
+        let token = url.strip_prefix(self.base_url.as_str())?.strip_prefix("/click/")?;
+        let token = token.split(['?', '#']).next()?;
+        self.verify(token, EngagementKind::Click)
+    }
+
+    /// Route every http(s) link of a rendered email through the click
+    /// endpoint, except its own unsubscribe link, and add the open pixel
+    pub fn instrument(&self, html: &str, campaign_id: i64, email: &str) -> String {
+        let mut out = String::with_capacity(html.len() * 2);
+        let mut rest = html;
+        // Leaving the list is not engagement, and must keep working without the redirect
+        let unsubscribe = format!("{}/unsubscribe/", self.base_url);
+
+        while let Some(start) = rest.find("href=\"") {
+            let value_start = start + "href=\"".len();
+            let Some(len) = rest[value_start..].find('"') else {
+                break;
+            };
+            let href = &rest[value_start..value_start + len];
+
+            out.push_str(&rest[..value_start]);
+            if (href.starts_with("http://") || href.starts_with("https://")) && !href.starts_with(&unsubscribe) {
+                out.push_str(&self.click_url(campaign_id, email, &href.replace("&amp;", "&")));
+            } else {
+                out.push_str(href);
+            }
+            rest = &rest[value_start + len..];
+        }
+        out.push_str(rest);
+
+        let pixel = format!(
+            "<img src=\"{}\" width=\"1\" height=\"1\" alt=\"\" style=\"display:none\" />",
+            self.open_url(campaign_id, email)
+        );
+        match out.rfind("</body>") {
+            Some(end) => out.insert_str(end, &pixel),
+            None => out.push_str(&pixel),
+        }
+        out
+    }
+
+    fn token(&self, campaign_id: i64, email: &str, url: Option<&str>) -> String {
+        let recipient = TrackedRecipient {
+            campaign_id,
+            email: email.to_string(),
+            url: url.map(str::to_string),
+        };
+        let payload = serde_json::to_vec(&recipient).expect("tracked recipient serializes");
+        self.signer.sign(&URL_SAFE_NO_PAD.encode(payload))
+    }
+}
@@ -0,0 +1,95 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::tenant::TenantId;
+use crate::infrastructure::token::TokenSigner;
+
+/// Body of an RFC 8058 one-click unsubscribe POST
+pub const ONE_CLICK_BODY: &str = "List-Unsubscribe=One-Click";
+
+/// Who an unsubscribe link belongs to, carried in its signed token
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UnsubscribingRecipient {
+    #[serde(rename = "c")]
+    campaign_id: i64,
+    #[serde(rename = "t")]
+    tenant: String,
+    #[serde(rename = "e")]
+    email: String,
+}
+
+/// A verified request to leave the list, from a link or a `List-Unsubscribe` header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsubscribeLink {
+    pub campaign_id: i64,
+    pub tenant: TenantId,
+    pub email: String,
+}
+
+/// Builds and verifies the per-recipient unsubscribe links of campaign
+/// emails, served next to the tracking routes.
+///
+/// The tenant is signed in with the recipient, since the link is followed
+/// without an API key to say whose list it is.
+#[derive(Clone)]
+pub struct UnsubscribeLinks {
+    signer: TokenSigner,
+    base_url: String,
+}
+
+impl UnsubscribeLinks {
+    pub fn new(signer: TokenSigner, base_url: impl Into<String>) -> Self {
+        Self {
+            signer,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// URL that unsubscribes `email` from `tenant`'s list: a confirmation
+    /// page on GET, at once on POST
+    pub fn url(&self, campaign_id: i64, tenant: &TenantId, email: &str) -> String {
+        let recipient = UnsubscribingRecipient {
+            campaign_id,
+            tenant: tenant.as_str().to_string(),
+            email: email.to_string(),
+        };
+        let payload = serde_json::to_vec(&recipient).expect("unsubscribing recipient serializes");
+        format!("{}/unsubscribe/{}", self.base_url, self.signer.sign(&URL_SAFE_NO_PAD.encode(payload)))
+    }
+
+    /// `List-Unsubscribe` and `List-Unsubscribe-Post` headers offering
+    /// one-click unsubscribe at `url`
+    pub fn headers(url: &str) -> Vec<(String, String)> {
+        vec![
+            ("List-Unsubscribe".to_string(), format!("<{url}>")),
+            ("List-Unsubscribe-Post".to_string(), ONE_CLICK_BODY.to_string()),
+        ]
+    }
+
+    /// The recipient of the first of our unsubscribe links found in `text`,
+    /// such as an email returned in a complaint report; quoted-printable
+    /// line breaks are joined first
+    pub fn find_in(&self, text: &str) -> Option<UnsubscribeLink> {
+        let text = text.replace("=\r\n", "").replace("=\n", "");
+        text.match_indices("/unsubscribe/").find_map(|(at, route)| {
+            let token = &text[at + route.len()..];
+            let end = token
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+                .unwrap_or(token.len());
+            self.verify(token[..end].trim_end_matches('.'))
+        })
+    }
+
+    /// The recipient a token from an unsubscribe URL stands for, if its signature holds
+    pub fn verify(&self, token: &str) -> Option<UnsubscribeLink> {
+        let payload = URL_SAFE_NO_PAD.decode(self.signer.verify(token)?).ok()?;
+        let recipient: UnsubscribingRecipient = serde_json::from_slice(&payload).ok()?;
+
+        Some(UnsubscribeLink {
+            campaign_id: recipient.campaign_id,
+            tenant: TenantId::parse(&recipient.tenant).ok()?,
+            email: recipient.email,
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::domain::campaign::warmup::{WarmupDomains, WarmupReservation, WarmupSchedule};
+use crate::repository::warmup::WarmupRepository;
+
+/// Caps the campaign sends of each domain on the warm-up ramp.
+///
+/// Only domains new to sending ramp: tenants' verified sending domains, and
+/// the `EMAIL_FROM` domain when operators list it. A domain's ramp starts the
+/// first day it sends a campaign unless operators set the day, or skip the
+/// domain because it already has a sending reputation.
+pub struct WarmupLimiter {
+    repository: Arc<dyn WarmupRepository>,
+    schedule: WarmupSchedule,
+    domains: WarmupDomains,
+    /// Domain of `EMAIL_FROM`; campaigns sent from it go uncapped when unknown
+    default_domain: Option<String>,
+}
+
+impl WarmupLimiter {
+    pub fn new(
+        repository: Arc<dyn WarmupRepository>,
+        schedule: WarmupSchedule,
+        domains: WarmupDomains,
+        default_domain: Option<String>,
+    ) -> Self {
+        Self {
+            repository,
+            schedule,
+            domains,
+            default_domain,
+        }
+    }
+
+    /// Set aside up to `wanted` sends from the tenant's verified `domain`,
+    /// or from the default domain when `None`; `None` when the domain sends
+    /// without a cap
+    pub async fn reserve(&self, domain: Option<&str>, wanted: i64, now: DateTime<Utc>) -> Result<Option<WarmupReservation>> {
+        let Some(domain) = self.domains.ramped(domain, self.default_domain.as_deref()) else {
+            return Ok(None);
+        };
+
+        let today = now.date_naive();
+        let started_on = match self.domains.started_on.get(domain) {
+            Some(day) => *day,
+            None => self.repository.started_on(domain, today).await?,
+        };
+        let Some(cap) = self.schedule.cap(started_on, today) else {
+            return Ok(None);
+        };
+
+        let granted = self.repository.reserve(domain, today, wanted, cap).await?;
+        if granted < wanted {
+            info!(domain = %domain, cap = cap, granted = granted, "Warm-up cap reached for today");
+        }
+        Ok(Some(WarmupReservation {
+            domain: domain.to_string(),
+            day: today,
+            granted,
+        }))
+    }
+
+    /// Return the part of a reservation that `used` sends left over
+    pub async fn release(&self, reservation: &WarmupReservation, used: i64) -> Result<()> {
+        let unused = reservation.granted - used;
+        if unused <= 0 {
+            return Ok(());
+        }
+        self.repository.release(&reservation.domain, reservation.day, unused).await
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::domain::outbox::OutboxBacklog;
+use crate::domain::webhook::WebhookBacklog;
+use crate::repository::outbox::OutboxRepository;
+use crate::repository::webhook::WebhookDeadLetterRepository;
+
+/// Attempts per destination the latency percentiles are taken over
+pub const LATENCY_SAMPLES: usize = 256;
+
+/// Delivery outcomes to one destination
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeliveryCounts {
+    pub delivered: u64,
+    /// Failed attempts, including those retried later
+    pub failed: u64,
+    /// Deliveries given up on
+    pub dead_lettered: u64,
+}
+
+impl DeliveryCounts {
+    /// Share of the attempts that went through; `None` before the first
+    pub fn success_rate(&self) -> Option<f64> {
+        let attempts = self.delivered + self.failed;
+        (attempts > 0).then(|| self.delivered as f64 / attempts as f64)
+    }
+
+    fn add(&mut self, other: DeliveryCounts) {
+        self.delivered += other.delivered;
+        self.failed += other.failed;
+        self.dead_lettered += other.dead_lettered;
+    }
+}
+
+/// How long recent attempts took, successful or not
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Latency {
+    pub p50: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl Latency {
+    fn of(samples: &VecDeque<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<Duration> = samples.iter().copied().collect();
+        sorted.sort_unstable();
+        // Nearest rank
+        let rank = |percent: usize| sorted[(sorted.len() * percent).div_ceil(100) - 1];
+        Some(Self {
+            p50: rank(50),
+            p95: rank(95),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// What this process saw delivering to one destination since it started
+#[derive(Debug, Clone, PartialEq)]
+pub struct DestinationStats {
+    /// A publisher name for the outbox relay, an endpoint URL for webhooks
+    pub destination: String,
+    pub counts: DeliveryCounts,
+    /// `None` before the first attempt
+    pub latency: Option<Latency>,
+    pub last_delivered_at: Option<DateTime<Utc>>,
+    pub last_failed_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Destination {
+    totals: DeliveryCounts,
+    /// Since the last `take_counts`
+    unreported: DeliveryCounts,
+    latencies: VecDeque<Duration>,
+    last_delivered_at: Option<DateTime<Utc>>,
+    last_failed_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+}
+
+impl Destination {
+    fn attempted(&mut self, latency: Duration, counts: DeliveryCounts) {
+        if self.latencies.len() == LATENCY_SAMPLES {
+            self.latencies.pop_front();
+        }
+        self.latencies.push_back(latency);
+        self.totals.add(counts);
+        self.unreported.add(counts);
+    }
+}
+
+/// Counts delivery attempts and their latency per destination.
+///
+/// Everything is kept in memory: counts start over with the process, and
+/// each instance only sees the deliveries it made itself.
+#[derive(Debug, Default)]
+pub struct DeliveryTracker {
+    destinations: Mutex<BTreeMap<String, Destination>>,
+}
+
+impl DeliveryTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn delivered(&self, destination: &str, latency: Duration) {
+        let mut destinations = self.destinations();
+        let entry = destinations.entry(destination.to_string()).or_default();
+        entry.attempted(latency, DeliveryCounts { delivered: 1, ..DeliveryCounts::default() });
+        entry.last_delivered_at = Some(Utc::now());
+    }
+
+    pub fn failed(&self, destination: &str, latency: Duration, error: &str) {
+        let mut destinations = self.destinations();
+        let entry = destinations.entry(destination.to_string()).or_default();
+        entry.attempted(latency, DeliveryCounts { failed: 1, ..DeliveryCounts::default() });
+        entry.last_failed_at = Some(Utc::now());
+        entry.last_error = Some(error.to_string());
+    }
+
+    /// A delivery was given up on after its attempts were recorded
+    pub fn dead_lettered(&self, destination: &str) {
+        let mut destinations = self.destinations();
+        let entry = destinations.entry(destination.to_string()).or_default();
+        entry.totals.dead_lettered += 1;
+        entry.unreported.dead_lettered += 1;
+    }
+
+    /// Every destination attempted so far, by name
+    pub fn stats(&self) -> Vec<DestinationStats> {
+        self.destinations()
+            .iter()
+            .map(|(destination, entry)| DestinationStats {
+                destination: destination.clone(),
+                counts: entry.totals,
+                latency: Latency::of(&entry.latencies),
+                last_delivered_at: entry.last_delivered_at,
+                last_failed_at: entry.last_failed_at,
+                last_error: entry.last_error.clone(),
+            })
+            .collect()
+    }
+
+    /// Counts per destination since the previous call, which resets them
+    pub fn take_counts(&self) -> Vec<(String, DeliveryCounts)> {
+        self.destinations()
+            .iter_mut()
+            .map(|(destination, entry)| (destination.clone(), std::mem::take(&mut entry.unreported)))
+            .collect()
+    }
+
+    fn destinations(&self) -> MutexGuard<'_, BTreeMap<String, Destination>> {
+        self.destinations.lock().expect("delivery tracker lock poisoned")
+    }
+}
+
+/// One webhook endpoint: what is still queued for it and how delivering to
+/// it went
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookStatus {
+    pub backlog: WebhookBacklog,
+    /// `None` while this process has not attempted the endpoint
+    pub stats: Option<DestinationStats>,
+}
+
+/// Where published events and webhook deliveries stand
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryStatus {
+    pub outbox: OutboxBacklog,
+    /// The outbox relay's publishers
+    pub publishers: Vec<DestinationStats>,
+    /// Empty when no webhook is configured
+    pub webhooks: Vec<WebhookStatus>,
+}
+
+/// Puts the outbox and webhook backlogs stored in the database together
+/// with the delivery counts of this process
+#[derive(Clone)]
+pub struct DeliveryMonitor {
+    outbox: Arc<dyn OutboxRepository>,
+    relay: Arc<DeliveryTracker>,
+    webhooks: Option<(Arc<dyn WebhookDeadLetterRepository>, Arc<DeliveryTracker>)>,
+}
+
+impl DeliveryMonitor {
+    /// `relay` is the tracker of the outbox relay
+    pub fn new(outbox: Arc<dyn OutboxRepository>, relay: Arc<DeliveryTracker>) -> Self {
+        Self {
+            outbox,
+            relay,
+            webhooks: None,
+        }
+    }
+
+    /// Report webhook deliveries too; `tracker` is the dispatcher's
+    pub fn with_webhooks(mut self, dead_letters: Arc<dyn WebhookDeadLetterRepository>, tracker: Arc<DeliveryTracker>) -> Self {
+        self.webhooks = Some((dead_letters, tracker));
+        self
+    }
+
+    pub fn relay(&self) -> &Arc<DeliveryTracker> {
+        &self.relay
+    }
+
+    pub fn webhooks(&self) -> Option<&Arc<DeliveryTracker>> {
+        self.webhooks.as_ref().map(|(_, tracker)| tracker)
+    }
+
+    pub async fn status(&self) -> Result<DeliveryStatus> {
+        let outbox = self.outbox.backlog().await?;
+        let webhooks = match &self.webhooks {
+            Some((dead_letters, tracker)) => {
+                let mut stats: BTreeMap<String, DestinationStats> = tracker
+                    .stats()
+                    .into_iter()
+                    .map(|stats| (stats.destination.clone(), stats))
+                    .collect();
+                let mut webhooks: Vec<WebhookStatus> = dead_letters
+                    .backlog()
+                    .await?
+                    .into_iter()
+                    .map(|backlog| WebhookStatus {
+                        stats: stats.remove(&backlog.url),
+                        backlog,
+                    })
+                    .collect();
+                // Endpoints attempted with nothing left pending or parked
+                webhooks.extend(stats.into_values().map(|stats| WebhookStatus {
+                    backlog: WebhookBacklog {
+                        url: stats.destination.clone(),
+                        pending: 0,
+                        oldest_pending_at: None,
+                        dead_letters: 0,
+                    },
+                    stats: Some(stats),
+                }));
+                webhooks.sort_by(|a, b| a.backlog.url.cmp(&b.backlog.url));
+                webhooks
+            }
+            None => Vec::new(),
+        };
+
+        Ok(DeliveryStatus {
+            outbox,
+            publishers: self.relay.stats(),
+            webhooks,
+        })
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::domain::idempotency::{self, IdempotencyError};
+use crate::infrastructure::tenant;
+use crate::repository::idempotency::{Claim, IdempotencyRepository};
+
+/// How long a processed key is replayed before it may be reused
+pub const DEFAULT_TTL: Duration = Duration::hours(24);
+
+/// Runs each keyed request at most once per TTL and replays its result on retries.
+///
+/// Only successful results are kept; a failed request releases its key so the
+/// client can retry it as-is.
+#[derive(Clone)]
+pub struct IdempotencyGuard {
+    repository: Arc<dyn IdempotencyRepository>,
+    ttl: Duration,
+}
+
+impl IdempotencyGuard {
+    pub fn new(repository: Arc<dyn IdempotencyRepository>, ttl: Duration) -> Self {
+        Self { repository, ttl }
+    }
+
+    /// Run `action` unless `key` was already used for this operation in the
+    /// current tenant; without a key the action always runs
+    pub async fn execute<T, F, Fut>(
+        &self,
+        key: Option<&str>,
+        operation: &str,
+        request_hash: &str,
+        action: F,
+    ) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut + Send,
+        Fut: Future<Output = Result<T>> + Send,
+    {
+        let Some(key) = key else {
+            return action().await;
+        };
+        idempotency::validate_key(key)?;
+
+        // Keys are shared by all tenants, so two of them picking the same key
+        // must not see each other's responses
+        let operation = &format!("{}:{operation}", tenant::current());
+        let expired_before = Utc::now() - self.ttl;
+        match self.repository.claim(key, operation, request_hash, expired_before).await? {
+            Claim::Acquired => {}
+            Claim::Existing(record) if record.request_hash != request_hash => {
+                return Err(IdempotencyError::Mismatch.into());
+            }
+            Claim::Existing(record) => {
+                let response = record.response.ok_or(IdempotencyError::InProgress)?;
+                info!(operation = operation, "Replaying idempotent response");
+                return Ok(serde_json::from_value(response)?);
+            }
+        }
+
+        match action().await {
+            Ok(value) => {
+                // The action already ran; failing to remember it must not fail the request
+                let stored = serde_json::to_value(&value).map_err(anyhow::Error::from);
+                let stored = match stored {
+                    Ok(response) => self.repository.complete(key, operation, &response).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = stored {
+                    warn!(operation = operation, error = %e, "Failed to store idempotent response");
+                }
+                Ok(value)
+            }
+            Err(e) => {
+                if let Err(release_error) = self.repository.release(key, operation).await {
+                    warn!(operation = operation, error = %release_error, "Failed to release idempotency key");
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Remove keys older than the TTL
+    pub async fn purge_expired(&self) -> Result<usize> {
+        self.repository.purge_expired(Utc::now() - self.ttl).await
+    }
+}
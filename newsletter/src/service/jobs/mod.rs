@@ -0,0 +1,155 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::domain::jobs::{Job, JobKind, NewJob};
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::tenant;
+use crate::repository::jobs::JobRepository;
+
+/// Jobs claimed per poll
+pub const DEFAULT_BATCH_SIZE: i64 = 20;
+
+/// How long a claimed job stays hidden from other workers; a worker that dies
+/// mid-job leaves it to be picked up again after this
+pub const DEFAULT_LEASE: Duration = Duration::minutes(5);
+
+/// Runs one kind of job
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    /// Do the work; an error retries the job with backoff until its attempts
+    /// run out, unless it is a [`PermanentJobError`]
+    async fn run(&self, job: &Job) -> Result<()>;
+}
+
+/// Failure that retrying will not fix, such as a rejected recipient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermanentJobError(pub String);
+
+impl fmt::Display for PermanentJobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for PermanentJobError {}
+
+/// Claims due jobs from the queue and runs them with the handler registered
+/// for their kind.
+///
+/// Any number of runners may share a queue; each job is claimed by one of
+/// them at a time and runs at least once.
+#[derive(Clone)]
+pub struct JobRunner {
+    repository: Arc<dyn JobRepository>,
+    handlers: HashMap<JobKind, Arc<dyn JobHandler>>,
+    /// Kinds that run forever at a fixed interval
+    recurring: HashMap<JobKind, Duration>,
+    batch_size: i64,
+    lease: Duration,
+}
+
+impl JobRunner {
+    pub fn new(repository: Arc<dyn JobRepository>) -> Self {
+        Self {
+            repository,
+            handlers: HashMap::new(),
+            recurring: HashMap::new(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            lease: DEFAULT_LEASE,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn register(mut self, kind: JobKind, handler: Arc<dyn JobHandler>) -> Self {
+        self.handlers.insert(kind, handler);
+        self
+    }
+
+    /// Register a handler that runs every `every`; the job is queued by [`Self::start`]
+    pub fn register_recurring(mut self, kind: JobKind, every: Duration, handler: Arc<dyn JobHandler>) -> Self {
+        self.recurring.insert(kind, every);
+        self.register(kind, handler)
+    }
+
+    /// Queue the recurring jobs unless another runner already has; they run
+    /// across all tenants
+    pub async fn start(&self) -> Result<()> {
+        for kind in self.recurring.keys() {
+            let job = NewJob::new(*kind, &serde_json::json!({}))?.unique_key(kind.as_str());
+            if tenant::scope(TenantScope::All, self.repository.enqueue(&job)).await?.is_some() {
+                info!(kind = %kind, "Queued recurring job");
+            }
+        }
+        Ok(())
+    }
+
+    /// Run one batch of due jobs concurrently; returns how many were claimed
+    pub async fn run_once(&self) -> Result<usize> {
+        let jobs = self.repository.claim(self.batch_size, self.lease).await?;
+        let claimed = jobs.len();
+
+        let results = join_all(jobs.iter().map(|job| self.run_job(job))).await;
+        for (job, result) in jobs.iter().zip(results) {
+            if let Err(e) = result {
+                // The lease runs out and the job is claimed again
+                error!(id = job.id, kind = %job.kind, error = %e, "Failed to record job outcome");
+            }
+        }
+
+        Ok(claimed)
+    }
+
+    /// Run batches while they come back full
+    pub async fn drain(&self) -> Result<usize> {
+        let mut total = 0;
+        loop {
+            let claimed = self.run_once().await?;
+            total += claimed;
+            if (claimed as i64) < self.batch_size {
+                return Ok(total);
+            }
+        }
+    }
+
+    async fn run_job(&self, job: &Job) -> Result<()> {
+        let Some(handler) = self.handlers.get(&job.kind) else {
+            warn!(id = job.id, kind = %job.kind, "No handler registered for job kind");
+            return self.repository.fail(job.id, "no handler registered").await;
+        };
+
+        let result = tenant::scope(job.tenant.clone(), handler.run(job)).await;
+        let every = self.recurring.get(&job.kind).copied();
+
+        match (result, every) {
+            (Ok(()), Some(every)) => self.repository.reschedule(job.id, Utc::now() + every).await,
+            (Ok(()), None) => {
+                info!(id = job.id, kind = %job.kind, attempt = job.attempts + 1, "Job completed");
+                self.repository.complete(job.id).await
+            }
+            // A recurring job that keeps failing still runs on its next interval
+            (Err(e), Some(every)) if job.is_last_attempt() => {
+                error!(id = job.id, kind = %job.kind, error = %e, "Recurring job failed");
+                self.repository.reschedule(job.id, Utc::now() + every).await
+            }
+            (Err(e), None) if job.is_last_attempt() || e.is::<PermanentJobError>() => {
+                error!(id = job.id, kind = %job.kind, attempts = job.attempts + 1, error = %e, "Job failed");
+                self.repository.fail(job.id, &e.to_string()).await
+            }
+            (Err(e), _) => {
+                let run_at = Utc::now() + job.retry_delay();
+                warn!(id = job.id, kind = %job.kind, attempt = job.attempts + 1, run_at = %run_at, error = %e, "Job failed, retrying");
+                self.repository.retry(job.id, &e.to_string(), run_at).await
+            }
+        }
+    }
+}
@@ -1 +1,9 @@
-pub mod newsletter;
\ No newline at end of file
+pub mod analytics;
+pub mod auth;
+pub mod campaign;
+pub mod delivery;
+pub mod idempotency;
+pub mod jobs;
+pub mod newsletter;
+pub mod outbox;
+pub mod template;
@@ -0,0 +1,400 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::Value;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+use crate::domain::newsletter::EmailAddress;
+use crate::service::newsletter::NewsletterService;
+
+/// Addresses handed to the service per insert
+pub const IMPORT_BATCH_SIZE: usize = 1000;
+
+/// Longest record accepted; guards against input without line breaks
+pub const MAX_RECORD_LEN: usize = 64 * 1024;
+
+/// Invalid rows reported back in detail; the rest are only counted
+pub const MAX_REPORTED_ERRORS: usize = 100;
+
+/// How often a batch waiting for the store checks it again
+const LOAD_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// CSV header names recognized as the email column, compared case-insensitively
+const EMAIL_COLUMNS: &[&str] = &["email", "email address", "e-mail", "email_address"];
+
+/// Layout of an import upload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Comma-separated values; the email column is found through a header row
+    /// such as Mailchimp's `Email Address`, or is the first column
+    Csv,
+    /// One JSON object with an `email` field, or one JSON string, per line
+    Ndjson,
+}
+
+/// Why an upload was rejected as a whole
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportError {
+    /// A single record exceeded `MAX_RECORD_LEN`
+    RecordTooLong { record: u64 },
+    /// The store stayed too busy to take the next batch; the records up to
+    /// `committed` were stored, the rest can be sent again after `retry_after`
+    Overloaded { committed: u64, retry_after: Duration },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::RecordTooLong { record } => {
+                write!(f, "record {record} is longer than {MAX_RECORD_LEN} bytes")
+            }
+            ImportError::Overloaded { committed, .. } => {
+                write!(f, "the store is too busy, records up to {committed} were imported")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// Tells the import whether the store can take another batch now
+pub trait StoreLoad: Send + Sync {
+    fn saturated(&self) -> bool;
+}
+
+/// Bounds the import batches written at once across every upload.
+///
+/// A batch waits up to `max_wait` for a slot and for the store to have room,
+/// and the upload is not read further meanwhile, so the client's stream
+/// stalls under flow control instead of piling up in memory. A batch still
+/// waiting after that fails the upload with [`ImportError::Overloaded`].
+#[derive(Clone)]
+pub struct ImportThrottle {
+    batches: Arc<Semaphore>,
+    max_batches: usize,
+    max_wait: Duration,
+    load: Option<Arc<dyn StoreLoad>>,
+}
+
+impl ImportThrottle {
+    pub fn new(max_batches: usize, max_wait: Duration) -> Self {
+        Self {
+            batches: Arc::new(Semaphore::new(max_batches)),
+            max_batches,
+            max_wait,
+            load: None,
+        }
+    }
+
+    /// Also wait while `load` reports the store saturated
+    pub fn with_load(mut self, load: Arc<dyn StoreLoad>) -> Self {
+        self.load = Some(load);
+        self
+    }
+
+    /// Batches being written now
+    pub fn in_flight(&self) -> usize {
+        self.max_batches - self.batches.available_permits()
+    }
+
+    /// A slot for the next batch, or `None` once `max_wait` ran out
+    async fn admit(&self) -> Option<OwnedSemaphorePermit> {
+        let deadline = Instant::now() + self.max_wait;
+        let permit = tokio::time::timeout_at(deadline, self.batches.clone().acquire_owned())
+            .await
+            .ok()?
+            .ok()?;
+        if let Some(load) = &self.load {
+            while load.saturated() {
+                if Instant::now() + LOAD_POLL_INTERVAL > deadline {
+                    return None;
+                }
+                tokio::time::sleep(LOAD_POLL_INTERVAL).await;
+            }
+        }
+        Some(permit)
+    }
+}
+
+/// A row that did not yield a valid address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidRow {
+    /// 1-based record number, counting a CSV header
+    pub record: u64,
+    pub reason: String,
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// New subscriptions created
+    pub imported: u64,
+    /// Valid addresses that were repeated in the upload or already subscribed
+    pub skipped: u64,
+    /// Rows without a valid address
+    pub invalid: u64,
+    /// The first `MAX_REPORTED_ERRORS` invalid rows
+    pub errors: Vec<InvalidRow>,
+}
+
+/// Splits an upload arriving in arbitrary chunks into records
+#[derive(Debug)]
+struct RecordSplitter {
+    format: ImportFormat,
+    buffer: Vec<u8>,
+    /// Bytes of `buffer` already scanned for a record end
+    scanned: usize,
+    /// Whether the scan position is inside a quoted CSV field
+    in_quotes: bool,
+}
+
+impl RecordSplitter {
+    fn new(format: ImportFormat) -> Self {
+        Self {
+            format,
+            buffer: Vec::new(),
+            scanned: 0,
+            in_quotes: false,
+        }
+    }
+
+    /// Append a chunk and return the records it completed
+    fn push(&mut self, chunk: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut records = Vec::new();
+        let mut start = 0;
+        for i in self.scanned..self.buffer.len() {
+            match self.buffer[i] {
+                // Quoted CSV fields may span lines
+                b'"' if self.format == ImportFormat::Csv => self.in_quotes = !self.in_quotes,
+                b'\n' if !self.in_quotes => {
+                    records.push(self.buffer[start..i].to_vec());
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+
+        self.buffer.drain(..start);
+        self.scanned = self.buffer.len();
+        records
+    }
+
+    /// Bytes waiting for a record end
+    fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn finish(self) -> Option<Vec<u8>> {
+        (!self.buffer.is_empty()).then_some(self.buffer)
+    }
+}
+
+/// Streaming subscriber import: parses records, validates and deduplicates
+/// addresses, and inserts them in batches of `IMPORT_BATCH_SIZE`.
+///
+/// Imported addresses are stored as confirmed, since they come from a list
+/// that already had consent, and no lifecycle events are published for them.
+/// A dry run parses and validates the same way but only counts the
+/// addresses that would be new. With an [`ImportThrottle`] each batch waits
+/// for its turn before it is written.
+pub struct SubscriberImport {
+    format: ImportFormat,
+    dry_run: bool,
+    throttle: Option<ImportThrottle>,
+    splitter: RecordSplitter,
+    /// Records seen so far, including a CSV header
+    records: u64,
+    /// Records whose addresses were all written
+    committed: u64,
+    /// Index of the email column, known once the first CSV record is read
+    email_column: Option<usize>,
+    seen: HashSet<String>,
+    batch: Vec<EmailAddress>,
+    summary: ImportSummary,
+}
+
+impl SubscriberImport {
+    pub fn new(format: ImportFormat) -> Self {
+        Self {
+            format,
+            dry_run: false,
+            throttle: None,
+            splitter: RecordSplitter::new(format),
+            records: 0,
+            committed: 0,
+            email_column: None,
+            seen: HashSet::new(),
+            batch: Vec::with_capacity(IMPORT_BATCH_SIZE),
+            summary: ImportSummary::default(),
+        }
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn with_throttle(mut self, throttle: ImportThrottle) -> Self {
+        self.throttle = Some(throttle);
+        self
+    }
+
+    pub fn format(&self) -> ImportFormat {
+        self.format
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Process the next chunk of the upload
+    pub async fn push(&mut self, service: &dyn NewsletterService, chunk: &[u8]) -> Result<()> {
+        for record in self.splitter.push(chunk) {
+            self.record(&record);
+            if self.batch.len() >= IMPORT_BATCH_SIZE {
+                self.flush(service).await?;
+            }
+        }
+
+        if self.splitter.pending() > MAX_RECORD_LEN {
+            return Err(ImportError::RecordTooLong {
+                record: self.records + 1,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Process the rest of the upload and insert the last batch
+    pub async fn finish(mut self, service: &dyn NewsletterService) -> Result<ImportSummary> {
+        let splitter = std::mem::replace(&mut self.splitter, RecordSplitter::new(self.format));
+        if let Some(record) = splitter.finish() {
+            self.record(&record);
+        }
+
+        self.flush(service).await?;
+        Ok(self.summary)
+    }
+
+    async fn flush(&mut self, service: &dyn NewsletterService) -> Result<()> {
+        if self.batch.is_empty() {
+            self.committed = self.records;
+            return Ok(());
+        }
+
+        let _permit = match &self.throttle {
+            Some(throttle) => Some(throttle.admit().await.ok_or(ImportError::Overloaded {
+                committed: self.committed,
+                retry_after: throttle.max_wait,
+            })?),
+            None => None,
+        };
+        let batch = std::mem::take(&mut self.batch);
+        let total = batch.len() as u64;
+        let imported = if self.dry_run {
+            service.preview_import(batch).await?
+        } else {
+            service.import_subscribers(batch).await?
+        } as u64;
+
+        self.summary.imported += imported;
+        self.summary.skipped += total.saturating_sub(imported);
+        self.committed = self.records;
+        Ok(())
+    }
+
+    fn record(&mut self, raw: &[u8]) {
+        self.records += 1;
+        let record = self.records;
+
+        let mut raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+        if record == 1 {
+            raw = raw.strip_prefix("\u{feff}".as_bytes()).unwrap_or(raw);
+        }
+        if raw.iter().all(u8::is_ascii_whitespace) {
+            return;
+        }
+
+        let parsed = match self.format {
+            ImportFormat::Csv => self.csv_email(raw),
+            ImportFormat::Ndjson => ndjson_email(raw).map(Some),
+        };
+
+        match parsed {
+            // The CSV header row
+            Ok(None) => {}
+            Ok(Some(value)) => match EmailAddress::parse(&value) {
+                Ok(email) => {
+                    if self.seen.insert(email.as_str().to_string()) {
+                        self.batch.push(email);
+                    } else {
+                        self.summary.skipped += 1;
+                    }
+                }
+                Err(e) => self.invalid(record, e.to_string()),
+            },
+            Err(reason) => self.invalid(record, reason),
+        }
+    }
+
+    /// The email of a CSV record, or `None` for the header row
+    fn csv_email(&mut self, raw: &[u8]) -> Result<Option<String>, String> {
+        let fields = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(raw)
+            .records()
+            .next()
+            .transpose()
+            .map_err(|e| format!("malformed CSV: {e}"))?
+            .unwrap_or_default();
+
+        let column = match self.email_column {
+            Some(column) => column,
+            None => {
+                let header = fields.iter().position(|field| {
+                    EMAIL_COLUMNS
+                        .iter()
+                        .any(|name| field.trim().eq_ignore_ascii_case(name))
+                });
+                self.email_column = Some(header.unwrap_or(0));
+                if header.is_some() {
+                    return Ok(None);
+                }
+                0
+            }
+        };
+
+        fields
+            .get(column)
+            .map(|field| Some(field.to_string()))
+            .ok_or_else(|| format!("missing column {}", column + 1))
+    }
+
+    fn invalid(&mut self, record: u64, reason: String) {
+        self.summary.invalid += 1;
+        if self.summary.errors.len() < MAX_REPORTED_ERRORS {
+            self.summary.errors.push(InvalidRow { record, reason });
+        }
+    }
+}
+
+fn ndjson_email(raw: &[u8]) -> Result<String, String> {
+    let value: Value = serde_json::from_slice(raw).map_err(|e| format!("malformed JSON: {e}"))?;
+
+    match value {
+        Value::String(email) => Ok(email),
+        Value::Object(mut fields) => match fields.remove("email").or_else(|| fields.remove("email_address")) {
+            Some(Value::String(email)) => Ok(email),
+            Some(_) => Err("email must be a string".to_string()),
+            None => Err("missing email field".to_string()),
+        },
+        _ => Err("expected a JSON object or string".to_string()),
+    }
+}
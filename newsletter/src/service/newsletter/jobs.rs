@@ -0,0 +1,117 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::domain::jobs::{Job, JobKind, SendConfirmation, SendTopicConfirmation};
+use crate::infrastructure::email::{MailError, MailSender};
+use crate::infrastructure::metrics::MetricsSink;
+use crate::service::jobs::{JobHandler, PermanentJobError};
+use crate::service::newsletter::{ConfirmationConfig, NewsletterService};
+
+/// Sends the double opt-in email queued by `subscribe`, the one confirming
+/// a new address queued by `request_email_change` and the one confirming a
+/// topic opt-in queued by `set_preferences`
+pub struct ConfirmationMailer {
+    confirmation: ConfirmationConfig,
+    mailer: Arc<dyn MailSender>,
+}
+
+impl ConfirmationMailer {
+    pub fn new(confirmation: ConfirmationConfig, mailer: Arc<dyn MailSender>) -> Self {
+        Self { confirmation, mailer }
+    }
+}
+
+#[async_trait]
+impl JobHandler for ConfirmationMailer {
+    async fn run(&self, job: &Job) -> Result<()> {
+        let message = if job.kind == JobKind::SendTopicConfirmation {
+            let SendTopicConfirmation { email, token_id, topic_name } = job.payload()?;
+            let token = self.confirmation.signer.sign(&token_id.to_string());
+            self.confirmation.topic(&email, &topic_name, &token)
+        } else {
+            let SendConfirmation { email, token_id } = job.payload()?;
+            let token = self.confirmation.signer.sign(&token_id.to_string());
+            match job.kind {
+                JobKind::SendEmailChange => self.confirmation.email_change(&email, &token),
+                _ => self.confirmation.email(&email, &token),
+            }
+        };
+
+        match self.mailer.send(&message).await {
+            Ok(()) => Ok(()),
+            Err(MailError::Permanent(e) | MailError::Rejected(e)) => Err(PermanentJobError(e.to_string()).into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Recurring removal of pending subscriptions that were never confirmed
+pub struct ExpirePending {
+    service: Arc<dyn NewsletterService>,
+}
+
+impl ExpirePending {
+    pub fn new(service: Arc<dyn NewsletterService>) -> Self {
+        Self { service }
+    }
+}
+
+#[async_trait]
+impl JobHandler for ExpirePending {
+    async fn run(&self, _job: &Job) -> Result<()> {
+        let purge = self.service.purge_expired_pending().await?;
+        info!(deleted = purge.deleted, reverted = purge.reverted, "Purged expired pending subscriptions");
+        Ok(())
+    }
+}
+
+/// Recurring snapshot of every tenant's list for the last finished UTC day
+pub struct RollupGrowth {
+    service: Arc<dyn NewsletterService>,
+}
+
+impl RollupGrowth {
+    pub fn new(service: Arc<dyn NewsletterService>) -> Self {
+        Self { service }
+    }
+}
+
+#[async_trait]
+impl JobHandler for RollupGrowth {
+    async fn run(&self, _job: &Job) -> Result<()> {
+        let day = (Utc::now() - Duration::days(1)).date_naive();
+        let recorded = self.service.record_daily_metrics(day).await?;
+        if recorded > 0 {
+            info!(day = %day, tenants = recorded, "Recorded daily list metrics");
+        }
+        Ok(())
+    }
+}
+
+/// Recurring anonymization or deletion of unsubscribed subscriptions past
+/// their tenant's retention, counted per tenant
+pub struct ApplyRetention {
+    service: Arc<dyn NewsletterService>,
+    metrics: Arc<dyn MetricsSink>,
+}
+
+impl ApplyRetention {
+    pub fn new(service: Arc<dyn NewsletterService>, metrics: Arc<dyn MetricsSink>) -> Self {
+        Self { service, metrics }
+    }
+}
+
+#[async_trait]
+impl JobHandler for ApplyRetention {
+    async fn run(&self, _job: &Job) -> Result<()> {
+        for run in self.service.apply_retention().await? {
+            let tags = [("tenant", run.tenant.as_str()), ("action", run.action.as_str())];
+            self.metrics.count("retention.anonymized", run.purge.anonymized as u64, &tags);
+            self.metrics.count("retention.deleted", run.purge.deleted as u64, &tags);
+        }
+        Ok(())
+    }
+}
@@ -1,96 +1,1106 @@
 use async_trait::async_trait;
-use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
 
-use crate::domain::newsletter::Newsletter;
+use crate::domain::newsletter::attributes::{self as attributes, AttributeDefinition, AttributeError, Attributes};
+use crate::domain::newsletter::consent::{ConsentContext, ConsentRecord};
+use crate::domain::newsletter::email_change::EmailChange;
+use crate::domain::newsletter::error::{NewsletterError, Result};
+use crate::domain::newsletter::export::SubscriberExport;
+use crate::domain::newsletter::growth::{self, GrowthPoint, GrowthRange};
+use crate::domain::newsletter::history::{self, SubscriberHistory};
+use crate::domain::newsletter::lifecycle::{PendingPurge, SubscriptionStatus};
+use crate::domain::newsletter::mask::{NewsletterMask, PartialNewsletter};
+use crate::domain::newsletter::merge::SubscriberMerge;
+use crate::domain::newsletter::normalize::Normalization;
+use crate::domain::newsletter::preferences::{
+    PreferencesError, PreferencesUpdate, Topic, TopicConfirmation, TopicPreference, TopicSubscription,
+};
+use crate::domain::newsletter::preview::BulkPreview;
+use crate::domain::newsletter::query::NewsletterQuery;
+use crate::domain::newsletter::retention::{RetentionRules, TenantRetention};
+use crate::domain::newsletter::search::{SearchHit, SearchQuery};
+use crate::domain::newsletter::signup::SignupDetails;
+use crate::domain::newsletter::stats::SubscriberStats;
+use crate::domain::newsletter::unsubscribe::{
+    ReasonCount, UnsubscribeEvent, UnsubscribeEventFilter, UnsubscribeFeedback,
+};
+use crate::domain::newsletter::view::SubscriberView;
+use crate::domain::newsletter::{EmailAddress, Newsletter, Tag};
+use crate::domain::jobs::{JobKind, NewJob, SendConfirmation, SendTopicConfirmation};
+use crate::domain::{locale, timezone};
+use crate::domain::pagination::{Page, PageRequest};
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::cache::Cache;
+use crate::infrastructure::email::EmailMessage;
+use crate::infrastructure::logging;
+use crate::infrastructure::tenant;
+use crate::infrastructure::token::TokenSigner;
+use crate::repository::jobs::JobRepository;
 use crate::repository::newsletter::NewsletterRepository;
+use crate::repository::subscriber_view::SubscriberViewRepository;
+use crate::service::newsletter::verification::AddressVerifier;
+
+pub mod import;
+pub mod jobs;
+pub mod seed;
+pub mod verification;
+
+/// Result of a subscribe request under double opt-in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubscribeOutcome {
+    /// The address is already confirmed; nothing was changed
+    AlreadyActive,
+    /// A pending subscription was recorded and must be confirmed with `token`
+    /// before `expires_at`
+    PendingConfirmation { token: String, expires_at: DateTime<Utc> },
+    /// The address had unsubscribed and is active again without confirmation
+    Resubscribed,
+}
+
+/// An address change waiting for the new address to confirm it with
+/// `token` before `expires_at`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingEmailChange {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Settings for the double opt-in confirmation flow
+#[derive(Clone)]
+pub struct ConfirmationConfig {
+    pub signer: TokenSigner,
+    pub ttl: Duration,
+    /// Link target for the confirmation email; the token is appended as `?token=`
+    pub confirm_url: String,
+    /// Link target for the email confirming a new address, built the same way
+    pub email_change_url: String,
+    /// Link target for the email confirming a topic opt-in, built the same way
+    pub topic_url: String,
+    /// Whether an address that unsubscribed confirms again when it comes
+    /// back; without this it is active at once
+    pub required_on_resubscribe: bool,
+    /// How long an unconfirmed subscription outlives its confirmation link
+    /// before it is purged
+    pub pending_retention: Duration,
+}
+
+impl ConfirmationConfig {
+    pub(crate) fn email(&self, to: &str, token: &str) -> EmailMessage {
+        let link = format!("{}?token={}", self.confirm_url, token);
+
+        EmailMessage {
+            to: to.to_string(),
+            subject: "Please confirm your newsletter subscription".to_string(),
+            html: format!(
+                "<p>Thanks for subscribing! Please confirm your email address by following \
+                 <a href=\"{link}\">this link</a>.</p>\
+                 <p>If you did not subscribe, you can safely ignore this email.</p>"
+            ),
+            text: Some(format!(
+                "Thanks for subscribing! Please confirm your email address by opening {link}\n\n\
+                 If you did not subscribe, you can safely ignore this email."
+            )),
+            headers: Vec::new(),
+            sender: None,
+        }
+    }
+
+    pub(crate) fn email_change(&self, to: &str, token: &str) -> EmailMessage {
+        let link = format!("{}?token={}", self.email_change_url, token);
+
+        EmailMessage {
+            to: to.to_string(),
+            subject: "Please confirm your new newsletter address".to_string(),
+            html: format!(
+                "<p>You asked to receive the newsletter at this address from now on. Please confirm \
+                 it by following <a href=\"{link}\">this link</a>.</p>\
+                 <p>If you did not ask for this, you can safely ignore this email.</p>"
+            ),
+            text: Some(format!(
+                "You asked to receive the newsletter at this address from now on. Please confirm it \
+                 by opening {link}\n\n\
+                 If you did not ask for this, you can safely ignore this email."
+            )),
+            headers: Vec::new(),
+            sender: None,
+        }
+    }
+
+    pub(crate) fn topic(&self, to: &str, topic_name: &str, token: &str) -> EmailMessage {
+        let link = format!("{}?token={}", self.topic_url, token);
+
+        EmailMessage {
+            to: to.to_string(),
+            subject: format!("Please confirm you want to receive {topic_name}"),
+            html: format!(
+                "<p>You asked to receive {topic_name} from our newsletter. Please confirm by \
+                 following <a href=\"{link}\">this link</a>.</p>\
+                 <p>If you did not ask for this, you can safely ignore this email.</p>"
+            ),
+            text: Some(format!(
+                "You asked to receive {topic_name} from our newsletter. Please confirm by opening \
+                 {link}\n\n\
+                 If you did not ask for this, you can safely ignore this email."
+            )),
+            headers: Vec::new(),
+            sender: None,
+        }
+    }
+}
 
 /// Service trait for newsletter business logic operations
 #[async_trait]
 pub trait NewsletterService: Send + Sync {
-    /// Get all newsletters
-    async fn list_newsletters(&self) -> Result<Vec<Newsletter>>;
+    /// Get a page of newsletters
+    async fn list_newsletters(&self, page: PageRequest) -> Result<Page<Newsletter>>;
+
+    /// Get a filtered, sorted page of newsletters with only the masked fields
+    async fn list_newsletters_masked(
+        &self,
+        query: &NewsletterQuery,
+        page: PageRequest,
+        mask: NewsletterMask,
+    ) -> Result<Page<PartialNewsletter>>;
+
+    /// Find subscriptions by part of their address or a misspelling of it,
+    /// best match first
+    async fn search(&self, query: &SearchQuery, page: PageRequest) -> Result<Page<SearchHit>>;
+
+    /// Get a newsletter by email with only the masked fields
+    async fn get_newsletter(&self, email: &EmailAddress, mask: NewsletterMask) -> Result<Option<PartialNewsletter>>;
     
-    /// Subscribe to newsletter
-    async fn subscribe(&self, email: &str) -> Result<()>;
+    /// Subscribe to newsletter, recording the consent given; the subscription
+    /// stays pending until confirmed. An address that unsubscribed before is
+    /// recorded as resubscribed, and skips confirmation unless
+    /// `ConfirmationConfig::required_on_resubscribe` is set.
+    async fn subscribe(&self, email: &EmailAddress, consent: ConsentContext) -> Result<SubscribeOutcome>;
+
+    /// Subscribe, and record the locale, source and topic choice on the
+    /// pending or resubscribed subscription; an active one keeps what it has. Unknown topics,
+    /// attributes and malformed locales fail with `NewsletterError::Validation`
+    /// before anything is stored.
+    async fn sign_up(
+        &self,
+        email: &EmailAddress,
+        consent: ConsentContext,
+        details: SignupDetails,
+    ) -> Result<SubscribeOutcome>;
+
+    /// Confirm a pending subscription, recording the confirmed consent; returns
+    /// the confirmed email, or `None` if the token is forged, unknown or expired
+    async fn confirm(&self, token: &str, consent: ConsentContext) -> Result<Option<String>>;
+
+    /// Move the active subscription of `email` to `new_email` once the new
+    /// address confirms it through the link sent there. Fails with
+    /// `NewsletterError::NotFound` without a subscription,
+    /// `InvalidTransition` unless it is active, and `AlreadySubscribed` or
+    /// `Suppressed` if `new_email` has one of its own.
+    async fn request_email_change(&self, email: &EmailAddress, new_email: &EmailAddress) -> Result<PendingEmailChange>;
+
+    /// Complete an address change, recording the consent confirmed at the
+    /// new address; returns both addresses, or `None` if the token is forged,
+    /// unknown or expired or the subscription is no longer active
+    async fn confirm_email_change(&self, token: &str, consent: ConsentContext) -> Result<Option<EmailChange>>;
+
+    /// Complete an opt-in to a topic that requires confirmation; returns the
+    /// address and topic, or `None` if the token is forged, unknown or
+    /// expired
+    async fn confirm_topic(&self, token: &str) -> Result<Option<TopicConfirmation>>;
+
+    /// Remove pending subscriptions whose confirmation link expired longer
+    /// than the retention ago
+    async fn purge_expired_pending(&self) -> Result<PendingPurge>;
+
+    /// Anonymize or delete the subscriptions that unsubscribed longer ago
+    /// than their tenant's retention, in every tenant in scope; returns what
+    /// was done in each tenant that has a policy
+    async fn apply_retention(&self) -> Result<Vec<TenantRetention>>;
     
-    /// Unsubscribe from newsletter
-    async fn unsubscribe(&self, email: &str) -> Result<()>;
+    /// Unsubscribe from newsletter, recording the reader's feedback; returns
+    /// whether the address was subscribed
+    async fn unsubscribe(&self, email: &EmailAddress, feedback: UnsubscribeFeedback) -> Result<bool>;
+
+    /// Get a page of recorded unsubscribes with per-reason totals for the same filter
+    async fn list_unsubscribe_reasons(
+        &self,
+        filter: UnsubscribeEventFilter,
+        page: PageRequest,
+    ) -> Result<(Page<UnsubscribeEvent>, Vec<ReasonCount>)>;
+
+    /// Get a page of the consent history of an email, newest first
+    async fn list_consents(&self, email: &EmailAddress, page: PageRequest) -> Result<Page<ConsentRecord>>;
+
+    /// Count the subscriptions and unsubscribes of the current tenant
+    async fn stats(&self) -> Result<SubscriberStats>;
+
+    /// Roll `day` up into the daily metrics of every tenant in scope that has
+    /// no snapshot for it yet; returns how many were written
+    async fn record_daily_metrics(&self, day: NaiveDate) -> Result<usize>;
+
+    /// The recorded growth of the current tenant over `range`, one point per
+    /// bucket that has a snapshot
+    async fn growth_time_series(&self, range: GrowthRange) -> Result<Vec<GrowthPoint>>;
     
-    /// Get newsletter subscription status by email
-    async fn get_subscription_status(&self, email: &str) -> Result<bool>;
+    /// Get the status of a subscription by email; `None` if it does not exist
+    async fn get_subscription_status(&self, email: &EmailAddress) -> Result<Option<SubscriptionStatus>>;
     
-    /// Update subscription status for multiple emails
-    async fn update_subscription_status(&self, emails: Vec<String>, active: bool) -> Result<()>;
+    /// Move many subscriptions to `status`, creating unknown addresses when
+    /// activating; fails with `NewsletterError::InvalidTransition`, changing
+    /// nothing, if the lifecycle forbids the move for any of them
+    async fn update_subscription_status(&self, emails: Vec<EmailAddress>, status: SubscriptionStatus) -> Result<()>;
     
     /// Delete multiple newsletter subscriptions
-    async fn delete_subscriptions(&self, emails: Vec<String>) -> Result<()>;
+    async fn delete_subscriptions(&self, emails: Vec<EmailAddress>) -> Result<()>;
+
+    /// Fold the subscription of `merged_email` into the one of `email` and
+    /// drop it, keeping its history under `email`. The addresses are taken as
+    /// given rather than normalized, since duplicates usually differ in just
+    /// what normalization folds. Fails with `NewsletterError::Validation` for
+    /// the same address twice and `NotFound` unless both are subscribed.
+    async fn merge_subscribers(&self, email: &EmailAddress, merged_email: &EmailAddress) -> Result<SubscriberMerge>;
+
+    /// Report what `update_subscription_status` would do, without writing;
+    /// forbidden moves are listed instead of failing the preview
+    async fn preview_status_update(&self, emails: Vec<EmailAddress>, status: SubscriptionStatus) -> Result<BulkPreview>;
+
+    /// Report what `delete_subscriptions` would do, without writing
+    async fn preview_delete(&self, emails: Vec<EmailAddress>) -> Result<BulkPreview>;
+
+    /// Tag existing subscriptions; returns how many gained the tag
+    async fn tag_subscribers(&self, emails: Vec<EmailAddress>, tag: &Tag) -> Result<usize>;
+
+    /// Remove a tag from subscriptions; returns how many lost the tag
+    async fn untag_subscribers(&self, emails: Vec<EmailAddress>, tag: &Tag) -> Result<usize>;
+
+    /// Get a page of the subscriptions in a tag segment
+    async fn list_by_tag(&self, tag: &Tag, page: PageRequest) -> Result<Page<Newsletter>>;
+
+    /// Store a batch of addresses imported from another list as confirmed
+    /// subscriptions; returns how many were new
+    async fn import_subscribers(&self, emails: Vec<EmailAddress>) -> Result<usize>;
+
+    /// Count the addresses of a batch `import_subscribers` would add, without writing
+    async fn preview_import(&self, emails: Vec<EmailAddress>) -> Result<usize>;
+
+    /// Collect everything stored about an address for a subject-access request
+    async fn export_subscriber_data(&self, email: &EmailAddress) -> Result<SubscriberExport>;
+
+    /// The subscription of an address as it stood at `at`, replayed from the
+    /// recorded events, following changes of address back to where it began
+    async fn subscriber_history(&self, email: &EmailAddress, at: DateTime<Utc>) -> Result<SubscriberHistory>;
+
+    /// Every topic with whether the subscriber receives it; fails with
+    /// `NewsletterError::NotFound` for unknown addresses
+    async fn get_preferences(&self, email: &EmailAddress) -> Result<Vec<TopicSubscription>>;
+
+    /// The attribute schema registry
+    async fn list_attribute_definitions(&self) -> Result<Vec<AttributeDefinition>>;
+
+    /// Register an attribute; fails with `NewsletterError::Conflict` for a known key
+    async fn define_attribute(&self, definition: AttributeDefinition) -> Result<AttributeDefinition>;
+
+    /// The custom attributes of a subscription; fails with
+    /// `NewsletterError::NotFound` for unknown addresses
+    async fn get_attributes(&self, email: &EmailAddress) -> Result<Attributes>;
+
+    /// Validate changes against the registry and merge them into a
+    /// subscription, removing keys set to `null`; returns the result
+    async fn set_attributes(&self, email: &EmailAddress, changes: Attributes) -> Result<Attributes>;
+
+    /// Set the preferred locale of a subscription, or clear it with `None`;
+    /// returns the stored tag. Fails with `NewsletterError::Validation` for
+    /// a malformed tag and `NewsletterError::NotFound` for an unknown address.
+    async fn set_locale(&self, email: &EmailAddress, locale: Option<&str>) -> Result<Option<String>>;
+
+    /// Set the IANA timezone of a subscription, such as `Europe/Berlin`, or
+    /// clear it with `None`. Campaigns with local send hours wait for them in
+    /// this zone. Fails with `NewsletterError::Validation` for an unknown
+    /// zone and `NewsletterError::NotFound` for an unknown address.
+    async fn set_timezone(&self, email: &EmailAddress, timezone: Option<&str>) -> Result<()>;
+
+    /// Change some topic choices, leaving the others as they are, and return
+    /// the resulting preferences. The last choice wins for a repeated topic.
+    /// Opting an active subscription in to a topic that requires confirmation
+    /// only sends the link for it; such opt-ins fail with
+    /// `NewsletterError::InvalidTransition` for unsubscribed and suppressed
+    /// subscriptions.
+    async fn set_preferences(
+        &self,
+        email: &EmailAddress,
+        preferences: Vec<TopicPreference>,
+    ) -> Result<PreferencesUpdate>;
+
+    /// Reload the disposable domain blocklist; returns how many domains it
+    /// holds. Fails with `NewsletterError::Validation` when none is configured.
+    async fn refresh_disposable_domains(&self) -> Result<usize>;
 }
 
-/// Default implementation of the newsletter service
+/// Default implementation of the newsletter service.
+///
+/// Subscription events are not published from here: the repository writes
+/// them to the outbox with each change and `OutboxRelay` delivers them.
+/// Addresses are normalized before they reach the repository.
 #[derive(Clone)]
 pub struct DefaultNewsletterService<R: NewsletterRepository> {
     repository: Arc<R>,
+    confirmation: ConfirmationConfig,
+    jobs: Arc<dyn JobRepository>,
+    normalization: Normalization,
+    /// Subscription status and stats lookups; every change made through this
+    /// service drops the entries it affects
+    cache: Option<Cache>,
+    /// Blocklist and MX checks run on subscribe
+    verifier: Option<Arc<AddressVerifier>>,
+    retention: RetentionRules,
+    /// Serves stats and the lists it can answer; trails the writes by the
+    /// outbox relay's lag
+    views: Option<Arc<dyn SubscriberViewRepository>>,
 }
 
 impl<R: NewsletterRepository> DefaultNewsletterService<R> {
-    pub fn new(repository: Arc<R>) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: Arc<R>,
+        confirmation: ConfirmationConfig,
+        jobs: Arc<dyn JobRepository>,
+    ) -> Self {
+        Self {
+            repository,
+            confirmation,
+            jobs,
+            normalization: Normalization::default(),
+            cache: None,
+            verifier: None,
+            retention: RetentionRules::default(),
+            views: None,
+        }
     }
+
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn with_verifier(mut self, verifier: Arc<AddressVerifier>) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+
+    pub fn with_retention(mut self, retention: RetentionRules) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Read stats, and the lists `SubscriberView::serves`, from the read model
+    pub fn with_read_model(mut self, views: Arc<dyn SubscriberViewRepository>) -> Self {
+        self.views = Some(views);
+        self
+    }
+
+    /// Take the opt-ins to topics that require confirmation, and that `email`
+    /// does not receive yet, out of `choices` and send the link for each;
+    /// returns the keys of those topics. A pending subscription keeps them,
+    /// since confirming the subscription confirms its topics too. Fails with
+    /// `NewsletterError::InvalidTransition`, holding nothing, for such
+    /// opt-ins of a subscription that is neither pending nor active.
+    async fn hold_unconfirmed_topics(&self, email: &str, choices: &mut Vec<TopicPreference>) -> Result<Vec<String>> {
+        let current = self
+            .repository
+            .get_preferences(email)
+            .await?
+            .ok_or(PreferencesError::NotSubscribed)?;
+        if let Some(unknown) = choices.iter().find(|c| !current.iter().any(|t| t.topic.key == c.topic)) {
+            return Err(PreferencesError::UnknownTopic(unknown.topic.clone()).into());
+        }
+        let held: Vec<Topic> = current
+            .into_iter()
+            .filter(|t| t.topic.requires_confirmation && !t.subscribed)
+            .filter(|t| choices.iter().any(|c| c.subscribed && c.topic == t.topic.key))
+            .map(|t| t.topic)
+            .collect();
+        if held.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let status = self
+            .repository
+            .get_by_email(email)
+            .await?
+            .map(|n| n.status)
+            .ok_or(PreferencesError::NotSubscribed)?;
+        match status {
+            SubscriptionStatus::Pending => return Ok(Vec::new()),
+            SubscriptionStatus::Active => {}
+            status => {
+                return Err(NewsletterError::InvalidTransition(format!(
+                    "only an active subscription can opt in to {}, {email} is {}",
+                    held[0].key,
+                    status.as_str()
+                )))
+            }
+        }
+
+        choices.retain(|c| !held.iter().any(|t| t.key == c.topic));
+        let expires_at = Utc::now() + self.confirmation.ttl;
+        let mut awaiting = Vec::with_capacity(held.len());
+        for topic in held {
+            let token_id = Uuid::new_v4();
+            if !self
+                .repository
+                .request_topic_confirmation(email, &topic.key, token_id, expires_at)
+                .await?
+            {
+                // Left the active status since the check above
+                return Err(NewsletterError::InvalidTransition(format!(
+                    "only an active subscription can opt in to {}",
+                    topic.key
+                )));
+            }
+            info!(entity = "newsletter", email = %logging::email(&email), topic = %topic.key, expires_at = %expires_at, "Issued topic confirmation token");
+
+            let job = NewJob::new(
+                JobKind::SendTopicConfirmation,
+                &SendTopicConfirmation {
+                    email: email.to_string(),
+                    token_id,
+                    topic_name: topic.name,
+                },
+            )
+            .map_err(NewsletterError::database)?;
+            self.jobs.enqueue(&job).await?;
+            awaiting.push(topic.key);
+        }
+        Ok(awaiting)
+    }
+
+    /// Record a subscribe request of the normalized, verified `email` and
+    /// queue its confirmation mail; run it in a unit of work, so the mail is
+    /// only sent for a subscription that was stored
+    async fn record_subscription(&self, email: &str, consent: &ConsentContext) -> Result<SubscribeOutcome> {
+        let mut returning = false;
+        if let Some(existing) = self.repository.get_by_email(email).await? {
+            match existing.status {
+                SubscriptionStatus::Active => return Ok(SubscribeOutcome::AlreadyActive),
+                SubscriptionStatus::Suppressed => return Err(NewsletterError::Suppressed(email.to_string())),
+                SubscriptionStatus::Unsubscribed => returning = true,
+                SubscriptionStatus::Pending => {}
+            }
+        }
+
+        // When the status moved since the check above, double opt-in sorts it out
+        if returning
+            && !self.confirmation.required_on_resubscribe
+            && self.repository.resubscribe(email, consent).await?
+        {
+            info!(entity = "newsletter", email = %logging::email(&email), "Resubscribed without confirmation");
+            return Ok(SubscribeOutcome::Resubscribed);
+        }
+
+        let token_id = Uuid::new_v4();
+        let expires_at = Utc::now() + self.confirmation.ttl;
+        if !self.repository.add_pending(email, token_id, expires_at, consent).await? {
+            // Confirmed by a concurrent request since the check above
+            return Ok(SubscribeOutcome::AlreadyActive);
+        }
+
+        let token = self.confirmation.signer.sign(&token_id.to_string());
+        info!(entity = "newsletter", email = %logging::email(&email), expires_at = %expires_at, "Issued confirmation token");
+
+        let job = NewJob::new(
+            JobKind::SendConfirmation,
+            &SendConfirmation {
+                email: email.to_string(),
+                token_id,
+            },
+        )
+        .map_err(NewsletterError::database)?;
+        self.jobs.enqueue(&job).await?;
+
+        Ok(SubscribeOutcome::PendingConfirmation { token, expires_at })
+    }
+
+    /// Drop the cached status of `emails` and the tenant's stats. Changes
+    /// made elsewhere, such as the expiry sweep across all tenants, show up
+    /// once the entries expire.
+    async fn invalidate(&self, emails: &[String]) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        let mut keys: Vec<String> = emails.iter().map(|email| subscriber_key(email)).collect();
+        keys.push(stats_key());
+        cache.invalidate(&keys).await;
+    }
+
+    /// Status of the subscriptions stored under `emails`, keyed by address
+    async fn current_statuses(&self, emails: &[String]) -> Result<HashMap<String, SubscriptionStatus>> {
+        Ok(self
+            .repository
+            .get_many(emails)
+            .await?
+            .into_iter()
+            .map(|newsletter| (newsletter.email, newsletter.status))
+            .collect())
+    }
+}
+
+fn subscriber_key(email: &str) -> String {
+    format!("{}:subscriber:{email}", tenant::current())
+}
+
+fn stats_key() -> String {
+    format!("{}:stats", tenant::current())
+}
+
+/// A locale checked and brought into canonical casing for storage
+fn canonical_locale(tag: &str) -> Result<String> {
+    if !locale::is_valid(tag) {
+        return Err(NewsletterError::Validation(format!("invalid locale: {tag}")));
+    }
+    Ok(locale::canonical(tag))
 }
 
 #[async_trait]
 impl<R: NewsletterRepository + 'static> NewsletterService for DefaultNewsletterService<R> {
-    async fn list_newsletters(&self) -> Result<Vec<Newsletter>> {
-        self.repository.list().await
+    async fn list_newsletters(&self, page: PageRequest) -> Result<Page<Newsletter>> {
+        self.repository.list(page).await
     }
     
-    async fn subscribe(&self, email: &str) -> Result<()> {
-        // Add business logic validation if needed
-        if email.trim().is_empty() {
-            return Err(anyhow::anyhow!("Email cannot be empty"));
+    async fn list_newsletters_masked(
+        &self,
+        query: &NewsletterQuery,
+        page: PageRequest,
+        mask: NewsletterMask,
+    ) -> Result<Page<PartialNewsletter>> {
+        match &self.views {
+            Some(views) if SubscriberView::serves(query, mask) => {
+                let page = views.list(query.filter.status, page).await?;
+                Ok(Page {
+                    items: page.items.into_iter().map(|view| view.masked(mask)).collect(),
+                    next_cursor: page.next_cursor,
+                })
+            }
+            _ => self.repository.list_masked(query, page, mask).await,
         }
-        
-        // Basic email validation
-        if !email.contains('@') {
-            return Err(anyhow::anyhow!("Invalid email format"));
+    }
+
+    async fn search(&self, query: &SearchQuery, page: PageRequest) -> Result<Page<SearchHit>> {
+        self.repository.search(query, page).await
+    }
+
+    async fn get_newsletter(&self, email: &EmailAddress, mask: NewsletterMask) -> Result<Option<PartialNewsletter>> {
+        self.repository.get_masked(self.normalization.apply(email).as_str(), mask).await
+    }
+
+    async fn subscribe(&self, email: &EmailAddress, consent: ConsentContext) -> Result<SubscribeOutcome> {
+        let email = self.normalization.apply(email);
+        if let Some(verifier) = &self.verifier {
+            verifier.verify(&email).await?;
+        }
+        let email = email.as_str();
+
+        let outcome = self
+            .repository
+            .with_transaction(self.record_subscription(email, &consent))
+            .await?;
+        if !matches!(outcome, SubscribeOutcome::AlreadyActive) {
+            self.invalidate(&[email.to_string()]).await;
         }
-        
-        self.repository.add(email).await
+        Ok(outcome)
     }
-    
-    async fn unsubscribe(&self, email: &str) -> Result<()> {
-        if email.trim().is_empty() {
-            return Err(anyhow::anyhow!("Email cannot be empty"));
+
+    async fn sign_up(
+        &self,
+        email: &EmailAddress,
+        consent: ConsentContext,
+        details: SignupDetails,
+    ) -> Result<SubscribeOutcome> {
+        let preferences = if details.topics.is_empty() {
+            Vec::new()
+        } else {
+            details.preferences(&self.repository.list_topics().await?)?
+        };
+        let changes = details.attributes();
+        if !changes.is_empty() {
+            let definitions = self.repository.list_attribute_definitions().await?;
+            attributes::validate(&definitions, &changes)?;
+        }
+        let locale = details.locale.as_deref().map(canonical_locale).transpose()?;
+
+        let email = self.normalization.apply(email);
+        if let Some(verifier) = &self.verifier {
+            verifier.verify(&email).await?;
+        }
+        let email = email.as_str();
+
+        // The details are stored with the subscription or not at all
+        let outcome = self
+            .repository
+            .with_transaction(async {
+                let outcome = self.record_subscription(email, &consent).await?;
+                if matches!(outcome, SubscribeOutcome::AlreadyActive) {
+                    return Ok(outcome);
+                }
+                if !changes.is_empty() {
+                    self.repository.set_attributes(email, &changes).await?;
+                }
+                if locale.is_some() {
+                    self.repository.set_locale(email, locale.as_deref()).await?;
+                }
+                if !preferences.is_empty() {
+                    let mut preferences = preferences;
+                    self.hold_unconfirmed_topics(email, &mut preferences).await?;
+                    self.repository.set_preferences(email, &preferences).await?;
+                }
+                Ok(outcome)
+            })
+            .await?;
+        if !matches!(outcome, SubscribeOutcome::AlreadyActive) {
+            self.invalidate(&[email.to_string()]).await;
         }
-        
-        self.repository.delete(email).await
+        Ok(outcome)
     }
-    
-    async fn get_subscription_status(&self, email: &str) -> Result<bool> {
-        match self.repository.get_by_email(email).await? {
-            Some(newsletter) => Ok(newsletter.active),
-            None => Ok(false),
+
+    async fn confirm(&self, token: &str, consent: ConsentContext) -> Result<Option<String>> {
+        let token_id = match self
+            .confirmation
+            .signer
+            .verify(token)
+            .and_then(|payload| Uuid::parse_str(payload).ok())
+        {
+            Some(token_id) => token_id,
+            None => return Ok(None),
+        };
+
+        let confirmed = self.repository.confirm(token_id, Utc::now(), &consent).await?;
+        if let Some(email) = &confirmed {
+            self.invalidate(std::slice::from_ref(email)).await;
+        }
+        Ok(confirmed)
+    }
+
+    async fn request_email_change(&self, email: &EmailAddress, new_email: &EmailAddress) -> Result<PendingEmailChange> {
+        let email = self.normalization.apply(email);
+        let new_email = self.normalization.apply(new_email);
+        if email == new_email {
+            return Err(NewsletterError::Validation("new_email is the current address".to_string()));
+        }
+        if let Some(verifier) = &self.verifier {
+            verifier.verify(&new_email).await?;
+        }
+        let new_email = new_email.as_str();
+
+        let existing = self
+            .repository
+            .get_by_email(email.as_str())
+            .await?
+            .ok_or_else(|| NewsletterError::NotFound(format!("{email} is not subscribed")))?;
+        let not_active = || {
+            NewsletterError::InvalidTransition(format!(
+                "only an active subscription can change its address, {email} is {}",
+                existing.status.as_str()
+            ))
+        };
+        if existing.status != SubscriptionStatus::Active {
+            return Err(not_active());
+        }
+
+        let token_id = Uuid::new_v4();
+        let expires_at = Utc::now() + self.confirmation.ttl;
+        if !self
+            .repository
+            .request_email_change(&existing.email, new_email, token_id, expires_at)
+            .await?
+        {
+            // Left the active status since the check above
+            return Err(not_active());
+        }
+        info!(entity = "newsletter", email = %logging::email(&existing.email), new_email = %logging::email(&new_email), expires_at = %expires_at, "Issued email change token");
+
+        let job = NewJob::new(
+            JobKind::SendEmailChange,
+            &SendConfirmation {
+                email: new_email.to_string(),
+                token_id,
+            },
+        )
+        .map_err(NewsletterError::database)?;
+        self.jobs.enqueue(&job).await?;
+
+        Ok(PendingEmailChange {
+            token: self.confirmation.signer.sign(&token_id.to_string()),
+            expires_at,
+        })
+    }
+
+    async fn confirm_email_change(&self, token: &str, consent: ConsentContext) -> Result<Option<EmailChange>> {
+        let Some(token_id) = self
+            .confirmation
+            .signer
+            .verify(token)
+            .and_then(|payload| Uuid::parse_str(payload).ok())
+        else {
+            return Ok(None);
+        };
+
+        let change = self.repository.change_email(token_id, Utc::now(), &consent).await?;
+        if let Some(change) = &change {
+            self.invalidate(&[change.from.clone(), change.to.clone()]).await;
+        }
+        Ok(change)
+    }
+
+    async fn confirm_topic(&self, token: &str) -> Result<Option<TopicConfirmation>> {
+        let Some(token_id) = self
+            .confirmation
+            .signer
+            .verify(token)
+            .and_then(|payload| Uuid::parse_str(payload).ok())
+        else {
+            return Ok(None);
+        };
+
+        let confirmed = self.repository.confirm_topic(token_id, Utc::now()).await?;
+        if let Some(confirmed) = &confirmed {
+            info!(entity = "newsletter", email = %logging::email(&confirmed.email), topic = %confirmed.topic, "Confirmed topic opt-in");
+        }
+        Ok(confirmed)
+    }
+
+    async fn purge_expired_pending(&self) -> Result<PendingPurge> {
+        let expired_by = Utc::now() - self.confirmation.pending_retention;
+        let purge = self.repository.purge_expired_pending(expired_by).await?;
+        if purge.total() > 0 {
+            self.invalidate(&[]).await;
+        }
+        Ok(purge)
+    }
+
+    async fn apply_retention(&self) -> Result<Vec<TenantRetention>> {
+        if !self.retention.is_enabled() {
+            return Ok(Vec::new());
         }
+
+        let now = Utc::now();
+        let mut runs = Vec::new();
+        for tenant in self.repository.list_tenants().await? {
+            let Some(policy) = self.retention.policy(&tenant) else {
+                continue;
+            };
+            // Each tenant's cutoff differs, so each runs on its own
+            let purge = tenant::scope(TenantScope::One(tenant.clone()), async {
+                let purge = self
+                    .repository
+                    .apply_retention(now - policy.unsubscribed_for, policy.action)
+                    .await?;
+                if purge.total() > 0 {
+                    self.invalidate(&[]).await;
+                }
+                Ok::<_, NewsletterError>(purge)
+            })
+            .await?;
+            if purge.total() > 0 {
+                info!(entity = "newsletter", tenant = %tenant, action = %policy.action, anonymized = purge.anonymized, deleted = purge.deleted, "Applied retention to unsubscribed subscriptions");
+            }
+            runs.push(TenantRetention {
+                tenant,
+                action: policy.action,
+                purge,
+            });
+        }
+        Ok(runs)
+    }
+    
+    async fn unsubscribe(&self, email: &EmailAddress, feedback: UnsubscribeFeedback) -> Result<bool> {
+        let email = self.normalization.apply(email).into_inner();
+        let unsubscribed = self.repository.unsubscribe(&email, &feedback).await?;
+        // Even when nothing changed, a cached copy may still show the address
+        // as subscribed
+        self.invalidate(&[email]).await;
+        Ok(unsubscribed)
     }
     
-    async fn update_subscription_status(&self, emails: Vec<String>, active: bool) -> Result<()> {
-        for email in emails {
-            if active {
-                // Subscribe if not already subscribed
-                self.repository.add(&email).await?;
-            } else {
-                // Unsubscribe
-                self.repository.delete(&email).await?;
+    async fn list_unsubscribe_reasons(
+        &self,
+        filter: UnsubscribeEventFilter,
+        page: PageRequest,
+    ) -> Result<(Page<UnsubscribeEvent>, Vec<ReasonCount>)> {
+        let (events, counts) = futures::try_join!(
+            self.repository.list_unsubscribe_events(filter, page),
+            self.repository.count_unsubscribe_reasons(filter),
+        )?;
+        Ok((events, counts))
+    }
+
+    async fn list_consents(&self, email: &EmailAddress, page: PageRequest) -> Result<Page<ConsentRecord>> {
+        self.repository.list_consents(self.normalization.apply(email).as_str(), page).await
+    }
+
+    async fn stats(&self) -> Result<SubscriberStats> {
+        let load = || async {
+            match &self.views {
+                Some(views) => views.stats().await,
+                None => self.repository.stats().await,
+            }
+        };
+        match &self.cache {
+            Some(cache) => cache.get_or_load(&stats_key(), load).await,
+            None => load().await,
+        }
+    }
+
+    async fn record_daily_metrics(&self, day: NaiveDate) -> Result<usize> {
+        self.repository.record_daily_metrics(day).await
+    }
+
+    async fn growth_time_series(&self, range: GrowthRange) -> Result<Vec<GrowthPoint>> {
+        let days = self.repository.list_daily_metrics(range.from, range.to).await?;
+        Ok(growth::time_series(&days, range.granularity))
+    }
+
+    async fn get_subscription_status(&self, email: &EmailAddress) -> Result<Option<SubscriptionStatus>> {
+        let email = self.normalization.apply(email);
+        let email = email.as_str();
+        let newsletter = match &self.cache {
+            Some(cache) => {
+                cache
+                    .get_or_load(&subscriber_key(email), || self.repository.get_by_email(email))
+                    .await?
             }
+            None => self.repository.get_by_email(email).await?,
+        };
+        Ok(newsletter.map(|newsletter| newsletter.status))
+    }
+    
+    async fn update_subscription_status(&self, emails: Vec<EmailAddress>, status: SubscriptionStatus) -> Result<()> {
+        let emails = dedup(emails, self.normalization);
+
+        if status.is_active() {
+            // Insert unknown addresses, then reactivate the ones that already existed
+            self.repository.add_many(&emails).await?;
         }
+        self.repository.set_status_many(&emails, status).await?;
+        self.invalidate(&emails).await;
         Ok(())
     }
     
-    async fn delete_subscriptions(&self, emails: Vec<String>) -> Result<()> {
-        for email in emails {
-            self.repository.delete(&email).await?;
+    async fn delete_subscriptions(&self, emails: Vec<EmailAddress>) -> Result<()> {
+        let emails = dedup(emails, self.normalization);
+        self.repository.delete_many(&emails).await?;
+        self.invalidate(&emails).await;
+        Ok(())
+    }
+
+    async fn merge_subscribers(&self, email: &EmailAddress, merged_email: &EmailAddress) -> Result<SubscriberMerge> {
+        if email == merged_email {
+            return Err(NewsletterError::Validation("merged_email is the kept address".to_string()));
+        }
+
+        let Some(merge) = self
+            .repository
+            .merge_subscribers(email.as_str(), merged_email.as_str())
+            .await?
+        else {
+            // Name the address that is missing
+            let missing = match self.repository.get_by_email(email.as_str()).await? {
+                Some(_) => merged_email,
+                None => email,
+            };
+            return Err(NewsletterError::NotFound(format!("{missing} is not subscribed")));
+        };
+        info!(entity = "newsletter", email = %logging::email(&merge.email), merged_email = %logging::email(&merge.merged_email), status = merge.status.as_str(), "Merged subscribers");
+
+        self.invalidate(&[merge.email.clone(), merge.merged_email.clone()]).await;
+        Ok(merge)
+    }
+
+    async fn preview_status_update(&self, emails: Vec<EmailAddress>, status: SubscriptionStatus) -> Result<BulkPreview> {
+        let emails = dedup(emails, self.normalization);
+        let current = self.current_statuses(&emails).await?;
+        Ok(BulkPreview::status_update(&emails, &current, status))
+    }
+
+    async fn preview_delete(&self, emails: Vec<EmailAddress>) -> Result<BulkPreview> {
+        let emails = dedup(emails, self.normalization);
+        let current = self.current_statuses(&emails).await?;
+        Ok(BulkPreview::delete(&emails, &current))
+    }
+
+    async fn tag_subscribers(&self, emails: Vec<EmailAddress>, tag: &Tag) -> Result<usize> {
+        self.repository.tag(&dedup(emails, self.normalization), tag.as_str()).await
+    }
+
+    async fn untag_subscribers(&self, emails: Vec<EmailAddress>, tag: &Tag) -> Result<usize> {
+        self.repository.untag(&dedup(emails, self.normalization), tag.as_str()).await
+    }
+
+    async fn list_by_tag(&self, tag: &Tag, page: PageRequest) -> Result<Page<Newsletter>> {
+        self.repository.list_by_tag(tag.as_str(), page).await
+    }
+
+    async fn import_subscribers(&self, emails: Vec<EmailAddress>) -> Result<usize> {
+        let emails = dedup(emails, self.normalization);
+        let imported = self.repository.add_many(&emails).await?;
+        if imported > 0 {
+            self.invalidate(&emails).await;
+        }
+        Ok(imported)
+    }
+
+    async fn preview_import(&self, emails: Vec<EmailAddress>) -> Result<usize> {
+        let emails = dedup(emails, self.normalization);
+        let current = self.current_statuses(&emails).await?;
+        Ok(emails.iter().filter(|email| !current.contains_key(*email)).count())
+    }
+
+    async fn export_subscriber_data(&self, email: &EmailAddress) -> Result<SubscriberExport> {
+        let export = self.repository.export(self.normalization.apply(email).as_str()).await?;
+
+        // Disclosures of personal data are themselves worth a record
+        info!(email = %logging::email(&email), empty = export.is_empty(), "Exported subscriber data");
+        Ok(export)
+    }
+
+    async fn subscriber_history(&self, email: &EmailAddress, at: DateTime<Utc>) -> Result<SubscriberHistory> {
+        let email = self.normalization.apply(email);
+        let mut events = self.repository.list_subscriber_events(email.as_str(), at).await?;
+
+        // Earlier addresses count up to the moment the subscription left them
+        let mut visited = HashSet::from([email.as_str().to_string()]);
+        let mut pending: Vec<(String, DateTime<Utc>)> = history::predecessors(&events, email.as_str())
+            .into_iter()
+            .map(|(from, changed_at)| (from.to_string(), changed_at))
+            .collect();
+        while let Some((from, changed_at)) = pending.pop() {
+            if !visited.insert(from.clone()) {
+                continue;
+            }
+            let earlier = self.repository.list_subscriber_events(&from, changed_at).await?;
+            pending.extend(
+                history::predecessors(&earlier, &from)
+                    .into_iter()
+                    .map(|(from, changed_at)| (from.to_string(), changed_at)),
+            );
+            for event in earlier {
+                if !events.contains(&event) {
+                    events.push(event);
+                }
+            }
+        }
+        events.sort_by_key(|event| event.occurred_at);
+
+        info!(email = %logging::email(&email), at = %at, events = events.len(), "Replayed subscriber history");
+        Ok(SubscriberHistory {
+            state: history::state_at(&events, email.as_str(), at),
+            events,
+        })
+    }
+
+    async fn list_attribute_definitions(&self) -> Result<Vec<AttributeDefinition>> {
+        self.repository.list_attribute_definitions().await
+    }
+
+    async fn define_attribute(&self, definition: AttributeDefinition) -> Result<AttributeDefinition> {
+        if !self.repository.define_attribute(&definition).await? {
+            return Err(AttributeError::AlreadyDefined(definition.key).into());
+        }
+        info!(key = %definition.key, kind = %definition.kind, "Defined subscriber attribute");
+        Ok(definition)
+    }
+
+    async fn get_attributes(&self, email: &EmailAddress) -> Result<Attributes> {
+        self.repository
+            .get_attributes(self.normalization.apply(email).as_str())
+            .await?
+            .ok_or_else(|| AttributeError::NotSubscribed.into())
+    }
+
+    async fn set_attributes(&self, email: &EmailAddress, changes: Attributes) -> Result<Attributes> {
+        let definitions = self.repository.list_attribute_definitions().await?;
+        attributes::validate(&definitions, &changes)?;
+
+        self.repository
+            .set_attributes(self.normalization.apply(email).as_str(), &changes)
+            .await?
+            .ok_or_else(|| AttributeError::NotSubscribed.into())
+    }
+
+    async fn set_locale(&self, email: &EmailAddress, locale: Option<&str>) -> Result<Option<String>> {
+        let locale = locale.map(canonical_locale).transpose()?;
+        let email = self.normalization.apply(email);
+
+        if !self.repository.set_locale(email.as_str(), locale.as_deref()).await? {
+            return Err(NewsletterError::NotFound(format!("{email} is not subscribed")));
+        }
+        Ok(locale)
+    }
+
+    async fn set_timezone(&self, email: &EmailAddress, timezone: Option<&str>) -> Result<()> {
+        if let Some(name) = timezone.filter(|name| !timezone::is_valid(name)) {
+            return Err(NewsletterError::Validation(format!("unknown timezone: {name}")));
+        }
+        let email = self.normalization.apply(email);
+
+        if !self.repository.set_timezone(email.as_str(), timezone).await? {
+            return Err(NewsletterError::NotFound(format!("{email} is not subscribed")));
         }
         Ok(())
     }
+
+    async fn get_preferences(&self, email: &EmailAddress) -> Result<Vec<TopicSubscription>> {
+        self.repository
+            .get_preferences(self.normalization.apply(email).as_str())
+            .await?
+            .ok_or_else(|| PreferencesError::NotSubscribed.into())
+    }
+
+    async fn set_preferences(
+        &self,
+        email: &EmailAddress,
+        preferences: Vec<TopicPreference>,
+    ) -> Result<PreferencesUpdate> {
+        let mut choices: Vec<TopicPreference> = Vec::with_capacity(preferences.len());
+        for preference in preferences {
+            let topic = preference.topic.trim().to_string();
+            choices.retain(|c| c.topic != topic);
+            choices.push(TopicPreference { topic, ..preference });
+        }
+
+        let normalized = self.normalization.apply(email);
+        let awaiting_confirmation = self.hold_unconfirmed_topics(normalized.as_str(), &mut choices).await?;
+        if !self.repository.set_preferences(normalized.as_str(), &choices).await? {
+            return Err(PreferencesError::NotSubscribed.into());
+        }
+        info!(email = %logging::email(&email), changed = choices.len(), awaiting_confirmation = awaiting_confirmation.len(), "Updated topic preferences");
+
+        Ok(PreferencesUpdate {
+            preferences: self.get_preferences(email).await?,
+            awaiting_confirmation,
+        })
+    }
+
+    async fn refresh_disposable_domains(&self) -> Result<usize> {
+        let Some(verifier) = &self.verifier else {
+            return Err(NewsletterError::Validation("address verification is not configured".to_string()));
+        };
+        Ok(verifier.refresh().await?)
+    }
+}
+
+/// Normalize addresses and drop repeats while keeping the first occurrence order
+fn dedup(emails: Vec<EmailAddress>, normalization: Normalization) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    emails
+        .iter()
+        .map(|email| normalization.apply(email))
+        .filter(|email| seen.insert(email.clone()))
+        .map(EmailAddress::into_inner)
+        .collect()
 }
\ No newline at end of file
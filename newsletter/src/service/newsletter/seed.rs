@@ -0,0 +1,208 @@
+use std::fmt;
+
+use anyhow::Result;
+use fake::faker::name::en::{FirstName, LastName};
+use fake::Fake;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::domain::newsletter::lifecycle::SubscriptionStatus;
+use crate::domain::newsletter::EmailAddress;
+use crate::service::newsletter::import::IMPORT_BATCH_SIZE;
+use crate::service::newsletter::NewsletterService;
+
+/// Most subscribers one seed run creates
+pub const MAX_SEED_COUNT: usize = 100_000;
+
+/// Domains used when a plan names none; reserved for documentation, so no
+/// mail to them is ever delivered
+pub const DEFAULT_SEED_DOMAINS: &[&str] = &["example.com", "example.org", "example.net"];
+
+/// Generated name as an address part: lowercase letters, digits and hyphens
+fn local_part(name: &str) -> String {
+    name.chars()
+        .filter_map(|c| match c {
+            'a'..='z' | '0'..='9' | '-' => Some(c),
+            'A'..='Z' => Some(c.to_ascii_lowercase()),
+            ' ' => Some('-'),
+            _ => None,
+        })
+        .collect()
+}
+
+/// How seeded subscribers are spread over statuses, as relative weights
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActivityMix {
+    pub active: u32,
+    pub unsubscribed: u32,
+    pub suppressed: u32,
+}
+
+impl Default for ActivityMix {
+    fn default() -> Self {
+        Self {
+            active: 80,
+            unsubscribed: 15,
+            suppressed: 5,
+        }
+    }
+}
+
+impl ActivityMix {
+    fn total(&self) -> u64 {
+        u64::from(self.active) + u64::from(self.unsubscribed) + u64::from(self.suppressed)
+    }
+
+    fn pick(&self, rng: &mut impl Rng) -> SubscriptionStatus {
+        let roll = rng.gen_range(0..self.total());
+        if roll < u64::from(self.active) {
+            SubscriptionStatus::Active
+        } else if roll < u64::from(self.active) + u64::from(self.unsubscribed) {
+            SubscriptionStatus::Unsubscribed
+        } else {
+            SubscriptionStatus::Suppressed
+        }
+    }
+}
+
+/// What a seed run creates; the same plan always yields the same subscribers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedPlan {
+    pub count: usize,
+    pub domains: Vec<String>,
+    pub mix: ActivityMix,
+    pub seed: u64,
+}
+
+impl SeedPlan {
+    pub fn new(count: usize) -> Self {
+        Self {
+            count,
+            domains: DEFAULT_SEED_DOMAINS.iter().map(|domain| domain.to_string()).collect(),
+            mix: ActivityMix::default(),
+            seed: 0,
+        }
+    }
+
+    /// Spread the addresses over `domains` instead of the defaults; none keeps them
+    pub fn with_domains(mut self, domains: Vec<String>) -> Self {
+        if !domains.is_empty() {
+            self.domains = domains;
+        }
+        self
+    }
+
+    pub fn with_mix(mut self, mix: ActivityMix) -> Self {
+        self.mix = mix;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), SeedError> {
+        if self.count == 0 || self.count > MAX_SEED_COUNT {
+            return Err(SeedError::Count(self.count));
+        }
+        if self.mix.total() == 0 {
+            return Err(SeedError::EmptyMix);
+        }
+        if let Some(domain) = self
+            .domains
+            .iter()
+            .find(|domain| EmailAddress::parse(&format!("seed@{domain}")).is_err())
+        {
+            return Err(SeedError::Domain(domain.clone()));
+        }
+        Ok(())
+    }
+
+    /// The subscribers of this plan, in order
+    pub fn generate(&self) -> Vec<SeededSubscriber> {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        (0..self.count)
+            .map(|n| {
+                let first = local_part(&FirstName().fake_with_rng::<String, _>(&mut rng));
+                let last = local_part(&LastName().fake_with_rng::<String, _>(&mut rng));
+                let domain = &self.domains[rng.gen_range(0..self.domains.len())];
+                // The index keeps addresses unique however the names fall
+                let email = EmailAddress::parse(&format!("{first}.{last}.{n}@{domain}"))
+                    .expect("seed domains are validated");
+                SeededSubscriber {
+                    email,
+                    status: self.mix.pick(&mut rng),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Why a seed plan was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeedError {
+    Count(usize),
+    EmptyMix,
+    Domain(String),
+}
+
+impl fmt::Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedError::Count(count) => write!(f, "count must be between 1 and {MAX_SEED_COUNT}, not {count}"),
+            SeedError::EmptyMix => f.write_str("at least one status needs a weight above zero"),
+            SeedError::Domain(domain) => write!(f, "{domain:?} is not a valid email domain"),
+        }
+    }
+}
+
+impl std::error::Error for SeedError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeededSubscriber {
+    pub email: EmailAddress,
+    pub status: SubscriptionStatus,
+}
+
+/// Outcome of a seed run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SeedSummary {
+    /// Addresses that had no subscription yet; a repeated run creates none
+    pub created: usize,
+    pub active: usize,
+    pub unsubscribed: usize,
+    pub suppressed: usize,
+}
+
+/// Store the subscribers of `plan` as imported subscriptions of the current
+/// tenant, then move the ones drawn inactive to their status. Running the
+/// same plan again creates nothing new.
+pub async fn seed(service: &dyn NewsletterService, plan: &SeedPlan) -> Result<SeedSummary> {
+    plan.validate()?;
+
+    let mut summary = SeedSummary::default();
+    for batch in plan.generate().chunks(IMPORT_BATCH_SIZE) {
+        summary.created += service
+            .import_subscribers(batch.iter().map(|subscriber| subscriber.email.clone()).collect())
+            .await?;
+
+        for status in [SubscriptionStatus::Unsubscribed, SubscriptionStatus::Suppressed] {
+            let emails: Vec<EmailAddress> = batch
+                .iter()
+                .filter(|subscriber| subscriber.status == status)
+                .map(|subscriber| subscriber.email.clone())
+                .collect();
+            if emails.is_empty() {
+                continue;
+            }
+            match status {
+                SubscriptionStatus::Unsubscribed => summary.unsubscribed += emails.len(),
+                _ => summary.suppressed += emails.len(),
+            }
+            service.update_subscription_status(emails, status).await?;
+        }
+    }
+    summary.active = plan.count - summary.unsubscribed - summary.suppressed;
+    Ok(summary)
+}
@@ -0,0 +1,103 @@
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use tracing::{info, warn};
+
+use crate::domain::newsletter::error::{NewsletterError, Result};
+use crate::domain::newsletter::verification::DisposableDomains;
+use crate::domain::newsletter::EmailAddress;
+
+/// Answers whether a domain can receive mail
+#[async_trait]
+pub trait MailDomainResolver: Send + Sync {
+    /// `false` when the domain does not exist, publishes a null MX, or has
+    /// neither MX nor address records
+    async fn accepts_mail(&self, domain: &str) -> anyhow::Result<bool>;
+}
+
+/// Where the disposable domain list is kept
+#[async_trait]
+pub trait DomainListSource: Send + Sync {
+    async fn load(&self) -> anyhow::Result<DisposableDomains>;
+}
+
+/// Checks on top of address syntax that refuse obviously fake subscriptions:
+/// a disposable-domain blocklist and, optionally, whether the domain takes
+/// mail at all. A lookup that fails rather than answering lets the address
+/// through, so a DNS outage does not stop sign-ups.
+pub struct AddressVerifier {
+    disposable: RwLock<Arc<DisposableDomains>>,
+    source: Option<Arc<dyn DomainListSource>>,
+    resolver: Option<Arc<dyn MailDomainResolver>>,
+}
+
+impl Default for AddressVerifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AddressVerifier {
+    /// A verifier that accepts every address until given checks
+    pub fn new() -> Self {
+        Self {
+            disposable: RwLock::new(Arc::new(DisposableDomains::default())),
+            source: None,
+            resolver: None,
+        }
+    }
+
+    /// Refuse the domains `source` lists; call [`Self::refresh`] to load them
+    pub fn with_disposable_domains(mut self, source: Arc<dyn DomainListSource>) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Refuse domains `resolver` says cannot receive mail
+    pub fn with_mx_lookup(mut self, resolver: Arc<dyn MailDomainResolver>) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Fail with `NewsletterError::Validation` if the address should not be
+    /// subscribed
+    pub async fn verify(&self, email: &EmailAddress) -> Result<()> {
+        let domain = email.domain();
+        if self.disposable().contains(domain) {
+            info!(domain = domain, "Refused disposable email domain");
+            return Err(NewsletterError::Validation(format!("{domain} is a disposable email domain")));
+        }
+
+        let Some(resolver) = &self.resolver else {
+            return Ok(());
+        };
+        match resolver.accepts_mail(domain).await {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                info!(domain = domain, "Refused email domain without mail servers");
+                Err(NewsletterError::Validation(format!("{domain} does not accept email")))
+            }
+            Err(e) => {
+                warn!(domain = domain, error = %e, "MX lookup failed, accepting the address");
+                Ok(())
+            }
+        }
+    }
+
+    /// Load the disposable domain list again; returns how many domains it
+    /// holds. The previous list stays in use if loading fails.
+    pub async fn refresh(&self) -> anyhow::Result<usize> {
+        let Some(source) = &self.source else {
+            return Err(NewsletterError::Validation("no disposable domain list is configured".to_string()).into());
+        };
+        let domains = source.load().await?;
+        let count = domains.len();
+        *self.disposable.write().expect("disposable domains lock poisoned") = Arc::new(domains);
+        info!(domains = count, "Loaded disposable email domains");
+        Ok(count)
+    }
+
+    fn disposable(&self) -> Arc<DisposableDomains> {
+        self.disposable.read().expect("disposable domains lock poisoned").clone()
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{info, warn};
+
+use crate::domain::newsletter::SubscriptionEvent;
+use crate::domain::tenant::TenantScope;
+use crate::infrastructure::events::EventPublisher;
+use crate::infrastructure::pseudonym::Pseudonymizer;
+use crate::infrastructure::tenant;
+use crate::repository::outbox::OutboxRepository;
+use crate::repository::subscriber_view::SubscriberViewRepository;
+use crate::service::delivery::DeliveryTracker;
+
+/// Messages claimed per batch
+pub const DEFAULT_BATCH_SIZE: i64 = 100;
+
+/// How long claimed messages stay hidden from other relays; long enough to
+/// publish a batch, short enough that a crashed relay's batch is soon retried
+pub const DEFAULT_LEASE: Duration = Duration::seconds(60);
+
+/// How long published messages are kept before being purged
+pub const DEFAULT_RETENTION: Duration = Duration::days(7);
+
+/// Publishes outbox messages and records the outcome.
+///
+/// Delivery is at least once: a relay that dies between publishing and
+/// `mark_sent` leaves the message to be published again after its lease.
+/// With a read model attached, each event is folded into it before it is
+/// published; a message that cannot be applied is retried like a failed publish.
+#[derive(Clone)]
+pub struct OutboxRelay {
+    repository: Arc<dyn OutboxRepository>,
+    publisher: Arc<dyn EventPublisher>,
+    batch_size: i64,
+    lease: Duration,
+    pseudonyms: Option<Pseudonymizer>,
+    tracker: Arc<DeliveryTracker>,
+    views: Option<Arc<dyn SubscriberViewRepository>>,
+}
+
+impl OutboxRelay {
+    pub fn new(repository: Arc<dyn OutboxRepository>, publisher: Arc<dyn EventPublisher>) -> Self {
+        Self {
+            repository,
+            publisher,
+            batch_size: DEFAULT_BATCH_SIZE,
+            lease: DEFAULT_LEASE,
+            pseudonyms: None,
+            tracker: Arc::new(DeliveryTracker::new()),
+            views: None,
+        }
+    }
+
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Publish events with the subscriber's pseudonym in place of the address
+    pub fn with_pseudonyms(mut self, pseudonyms: Pseudonymizer) -> Self {
+        self.pseudonyms = Some(pseudonyms);
+        self
+    }
+
+    /// Keep the read model of the subscriber list up to date with the events relayed
+    pub fn with_read_model(mut self, views: Arc<dyn SubscriberViewRepository>) -> Self {
+        self.views = Some(views);
+        self
+    }
+
+    /// Publish attempts by publisher name, shared by the relay's clones
+    pub fn tracker(&self) -> Arc<DeliveryTracker> {
+        self.tracker.clone()
+    }
+
+    /// Publish one batch in the order the events were written; returns how many were sent
+    pub async fn relay_once(&self) -> Result<usize> {
+        let messages = self.repository.claim(self.batch_size, self.lease).await?;
+
+        let mut sent = Vec::with_capacity(messages.len());
+        for message in &messages {
+            if let Some(views) = &self.views {
+                // The read model keeps the address, so it gets the event as written
+                let scope = TenantScope::One(message.event.tenant.clone().unwrap_or_default());
+                if let Err(e) = tenant::scope(scope, views.apply(&message.event)).await {
+                    let retry_at = Utc::now() + message.retry_delay();
+                    warn!(id = message.id, event_type = %message.event.kind, attempts = message.attempts + 1, retry_at = %retry_at, error = %e, "Failed to apply outbox message to the read model");
+                    self.repository.mark_failed(message.id, &e.to_string(), retry_at).await?;
+                    continue;
+                }
+            }
+
+            let event = match &self.pseudonyms {
+                Some(pseudonyms) => &SubscriptionEvent {
+                    email: pseudonyms.pseudonym(&message.event.email),
+                    previous_email: message.event.previous_email.as_deref().map(|email| pseudonyms.pseudonym(email)),
+                    ..message.event.clone()
+                },
+                None => &message.event,
+            };
+            let started = Instant::now();
+            let published = self.publisher.publish(event).await;
+            let latency = started.elapsed();
+            match published {
+                Ok(()) => {
+                    self.tracker.delivered(self.publisher.name(), latency);
+                    sent.push(message.id);
+                }
+                Err(e) => {
+                    self.tracker.failed(self.publisher.name(), latency, &e.to_string());
+                    let retry_at = Utc::now() + message.retry_delay();
+                    warn!(id = message.id, event_type = %message.event.kind, attempts = message.attempts + 1, retry_at = %retry_at, error = %e, "Failed to publish outbox message");
+                    self.repository.mark_failed(message.id, &e.to_string(), retry_at).await?;
+                }
+            }
+        }
+
+        self.repository.mark_sent(&sent).await?;
+        Ok(sent.len())
+    }
+
+    /// Publish batches while they come back full; stops at the first batch
+    /// with a failure so a broken sink is not retried in a tight loop
+    pub async fn drain(&self) -> Result<usize> {
+        let mut total = 0;
+        loop {
+            let sent = self.relay_once().await?;
+            total += sent;
+            if (sent as i64) < self.batch_size {
+                return Ok(total);
+            }
+        }
+    }
+
+    /// Delete messages published longer than `retention` ago
+    pub async fn purge_sent(&self, retention: Duration) -> Result<usize> {
+        let purged = self.repository.purge_sent(Utc::now() - retention).await?;
+        if purged > 0 {
+            info!(purged = purged, "Purged sent outbox messages");
+        }
+        Ok(purged)
+    }
+}
@@ -0,0 +1,54 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::tenant::TenantId;
+use crate::infrastructure::token::TokenSigner;
+
+/// Which asset a link stands for, carried in its signed token
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LinkedAsset {
+    #[serde(rename = "t")]
+    tenant: String,
+    #[serde(rename = "n")]
+    name: String,
+}
+
+/// Builds and verifies the links `{{asset "name"}}` renders to, served next
+/// to the tracking routes, which redirect to a freshly signed URL of the
+/// blob. Emails keep working after any one signed URL expires.
+///
+/// The tenant is signed in with the name, since the link is followed
+/// without an API key to say whose asset it is.
+#[derive(Clone)]
+pub struct AssetLinks {
+    signer: TokenSigner,
+    base_url: String,
+}
+
+impl AssetLinks {
+    pub fn new(signer: TokenSigner, base_url: impl Into<String>) -> Self {
+        Self {
+            signer,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Public URL of `tenant`'s asset `name`
+    pub fn url(&self, tenant: &TenantId, name: &str) -> String {
+        let asset = LinkedAsset {
+            tenant: tenant.as_str().to_string(),
+            name: name.to_string(),
+        };
+        let payload = serde_json::to_vec(&asset).expect("linked asset serializes");
+        format!("{}/assets/{}", self.base_url, self.signer.sign(&URL_SAFE_NO_PAD.encode(payload)))
+    }
+
+    /// The tenant and asset name a token from an asset URL stands for, if its signature holds
+    pub fn verify(&self, token: &str) -> Option<(TenantId, String)> {
+        let payload = URL_SAFE_NO_PAD.decode(self.signer.verify(token)?).ok()?;
+        let asset: LinkedAsset = serde_json::from_slice(&payload).ok()?;
+
+        Some((TenantId::parse(&asset.tenant).ok()?, asset.name))
+    }
+}
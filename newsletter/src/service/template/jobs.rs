@@ -0,0 +1,30 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::domain::jobs::Job;
+use crate::service::jobs::JobHandler;
+use crate::service::template::TemplateService;
+
+/// Recurring deletion of template assets no template references any more
+pub struct CollectAssets {
+    service: Arc<dyn TemplateService>,
+}
+
+impl CollectAssets {
+    pub fn new(service: Arc<dyn TemplateService>) -> Self {
+        Self { service }
+    }
+}
+
+#[async_trait]
+impl JobHandler for CollectAssets {
+    async fn run(&self, _job: &Job) -> Result<()> {
+        let deleted = self.service.collect_assets().await?;
+        if deleted > 0 {
+            info!(deleted = deleted, "Collected unreferenced template assets");
+        }
+        Ok(())
+    }
+}
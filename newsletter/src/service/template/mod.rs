@@ -0,0 +1,382 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::domain::locale;
+use crate::domain::pagination::{Page, PageRequest};
+use crate::domain::template::asset::{self, NewAsset, SignedAsset, StoredAsset, TemplateAsset};
+use crate::domain::template::lint::{self, TemplateDraft, TemplateIssue, TemplateReport};
+use crate::domain::template::translation::{self, TemplateTranslation};
+use crate::domain::template::{NewTemplate, RenderedTemplate, Template, TemplateError, TemplateUpdate};
+use crate::infrastructure::assets::AssetStore;
+use crate::infrastructure::template::TemplateEngine;
+use crate::infrastructure::tenant;
+use crate::repository::template::TemplateRepository;
+use crate::service::newsletter::NewsletterService;
+
+pub mod assets;
+pub mod jobs;
+
+/// Service trait for template management and rendering
+#[async_trait]
+pub trait TemplateService: Send + Sync {
+    /// Create a template after checking that it compiles
+    async fn create_template(&self, template: NewTemplate) -> Result<Template>;
+
+    /// Get a template by id
+    async fn get_template(&self, id: i64) -> Result<Option<Template>>;
+
+    /// Update a template; returns `None` if it does not exist
+    async fn update_template(&self, id: i64, update: TemplateUpdate) -> Result<Option<Template>>;
+
+    /// Delete a template; returns whether it existed
+    async fn delete_template(&self, id: i64) -> Result<bool>;
+
+    /// Get a page of templates
+    async fn list_templates(&self, page: PageRequest) -> Result<Page<Template>>;
+
+    /// Check an unsaved template: that it compiles, reads only variables
+    /// sends provide and stays under Gmail's clipping size, with HTML and
+    /// plaintext previews rendered from sample data. `sample` is laid over
+    /// the made-up subscriber the previews are rendered for.
+    async fn validate_template(&self, draft: TemplateDraft, sample: Option<serde_json::Value>) -> Result<TemplateReport>;
+
+    /// Render a template with the given JSON object context in the
+    /// translation a reader of `locale` would get; returns `None` if the
+    /// template does not exist
+    async fn render(
+        &self,
+        template_id: i64,
+        context: &serde_json::Value,
+        locale: Option<&str>,
+    ) -> Result<Option<RenderedTemplate>>;
+
+    /// Create or replace a translation after checking that it compiles in the
+    /// template's format; returns `None` if the template does not exist
+    async fn put_translation(&self, template_id: i64, locale: &str, body: &str) -> Result<Option<TemplateTranslation>>;
+
+    /// Store translations into several locales at once, all or none, after
+    /// checking each like `put_translation`; with `replace`, translations
+    /// into locales missing from the bundle are deleted. Returns every
+    /// translation afterwards, or `None` if the template does not exist.
+    async fn upload_translations(
+        &self,
+        template_id: i64,
+        bundle: HashMap<String, String>,
+        replace: bool,
+    ) -> Result<Option<Vec<TemplateTranslation>>>;
+
+    /// Delete a translation; returns whether it existed
+    async fn delete_translation(&self, template_id: i64, locale: &str) -> Result<bool>;
+
+    /// Get every translation of a template, ordered by locale
+    async fn list_translations(&self, template_id: i64) -> Result<Vec<TemplateTranslation>>;
+
+    /// Store an asset after checking its content matches its declared type;
+    /// names are taken once and never replaced
+    async fn upload_asset(&self, asset: NewAsset, content: Vec<u8>) -> Result<TemplateAsset>;
+
+    /// Get an asset by name with a short-lived URL to download it
+    async fn get_asset(&self, name: &str) -> Result<Option<SignedAsset>>;
+
+    /// Delete the assets of every tenant in scope that no template or
+    /// translation has referenced for [`asset::UNREFERENCED_GRACE`] and no
+    /// sent email showed; returns how many were deleted
+    async fn collect_assets(&self) -> Result<usize>;
+}
+
+/// Default implementation of the template service
+#[derive(Clone)]
+pub struct DefaultTemplateService<R: TemplateRepository> {
+    repository: Arc<R>,
+    engine: TemplateEngine,
+    /// Locales tried after the requested one, as the campaign sender does
+    locale_fallbacks: Vec<String>,
+    /// Where asset content is kept; uploads fail without one
+    assets: Option<Arc<dyn AssetStore>>,
+    /// Source of the attribute schema validation checks variables against;
+    /// without one every `attributes.*` variable is unknown
+    attribute_schema: Option<Arc<dyn NewsletterService>>,
+}
+
+impl<R: TemplateRepository> DefaultTemplateService<R> {
+    pub fn new(repository: Arc<R>, engine: TemplateEngine) -> Self {
+        Self {
+            repository,
+            engine,
+            locale_fallbacks: Vec::new(),
+            assets: None,
+            attribute_schema: None,
+        }
+    }
+
+    pub fn with_locale_fallbacks(mut self, fallbacks: Vec<String>) -> Self {
+        self.locale_fallbacks = fallbacks;
+        self
+    }
+
+    pub fn with_assets(mut self, store: Arc<dyn AssetStore>) -> Self {
+        self.assets = Some(store);
+        self
+    }
+
+    pub fn with_attribute_schema(mut self, newsletters: Arc<dyn NewsletterService>) -> Self {
+        self.attribute_schema = Some(newsletters);
+        self
+    }
+
+    fn asset_store(&self) -> Result<&Arc<dyn AssetStore>, TemplateError> {
+        self.assets.as_ref().ok_or(TemplateError::AssetStorageDisabled)
+    }
+
+    /// Start the grace period of the assets a body about to change or go
+    /// references. Done before the write, so a failed write only keeps
+    /// them longer.
+    async fn mark_referenced(&self, names: BTreeSet<String>) -> Result<()> {
+        if names.is_empty() {
+            return Ok(());
+        }
+        self.repository.mark_assets_referenced(&names, Utc::now()).await
+    }
+
+    /// The translation of `template_id` into `locale`, if there is one
+    async fn translation(&self, template_id: i64, locale: &str) -> Result<Option<TemplateTranslation>> {
+        let translations = self.repository.list_translations(template_id).await?;
+        Ok(translations.into_iter().find(|t| t.locale == locale))
+    }
+}
+
+#[async_trait]
+impl<R: TemplateRepository + 'static> TemplateService for DefaultTemplateService<R> {
+    async fn create_template(&self, template: NewTemplate) -> Result<Template> {
+        template.validate()?;
+        self.engine.validate(template.format, &template.body)?;
+        if let Some(text_body) = &template.text_body {
+            self.engine.validate_text(text_body)?;
+        }
+
+        self.repository.create(&template).await
+    }
+
+    async fn get_template(&self, id: i64) -> Result<Option<Template>> {
+        self.repository.get(id).await
+    }
+
+    async fn update_template(&self, id: i64, update: TemplateUpdate) -> Result<Option<Template>> {
+        let Some(mut template) = self.repository.get(id).await? else {
+            return Ok(None);
+        };
+
+        let referenced = asset::template_references(&template, &[]);
+        template.apply_update(update)?;
+        self.engine.validate(template.format, &template.body)?;
+        if let Some(text_body) = &template.text_body {
+            self.engine.validate_text(text_body)?;
+        }
+
+        self.mark_referenced(referenced).await?;
+        self.repository.save(&template).await.map(Some)
+    }
+
+    async fn delete_template(&self, id: i64) -> Result<bool> {
+        if let Some(template) = self.repository.get(id).await? {
+            let translations = self.repository.list_translations(id).await?;
+            self.mark_referenced(asset::template_references(&template, &translations)).await?;
+        }
+
+        self.repository.delete(id).await
+    }
+
+    async fn list_templates(&self, page: PageRequest) -> Result<Page<Template>> {
+        self.repository.list(page).await
+    }
+
+    async fn validate_template(&self, draft: TemplateDraft, sample: Option<serde_json::Value>) -> Result<TemplateReport> {
+        if sample.as_ref().is_some_and(|sample| !sample.is_object()) {
+            return Err(TemplateError::Validation("sample must be an object".to_string()).into());
+        }
+        let compiled = self.engine.validate(draft.format, &draft.body).and_then(|()| match &draft.text_body {
+            Some(text_body) => self.engine.validate_text(text_body),
+            None => Ok(()),
+        });
+        if let Err(e) = compiled {
+            return Ok(TemplateReport {
+                issues: vec![TemplateIssue::syntax(e)],
+                ..TemplateReport::default()
+            });
+        }
+
+        let definitions = match &self.attribute_schema {
+            Some(newsletters) => newsletters.list_attribute_definitions().await?,
+            None => Vec::new(),
+        };
+        let mut context = lint::sample_context(&definitions, &draft.required_variables);
+        let mut unknown = self.engine.unknown_variables(&draft.body, &mut context);
+        if let Some(text_body) = &draft.text_body {
+            unknown.extend(self.engine.unknown_variables(text_body, &mut context));
+        }
+        let mut report = TemplateReport {
+            issues: unknown.into_iter().map(TemplateIssue::unknown_variable).collect(),
+            ..TemplateReport::default()
+        };
+        if let Some(sample) = sample {
+            lint::merge_sample(&mut context, sample);
+        }
+
+        let rendered = self.engine.render_body(draft.format, &draft.body, &context).and_then(|html| {
+            let text = self.engine.render_text(draft.text_body.as_deref(), &html, &context)?;
+            Ok((html, text))
+        });
+        match rendered {
+            Ok((html, text)) => {
+                report.issues.extend(TemplateIssue::clipping(&html));
+                report.html = Some(html);
+                report.text = Some(text);
+            }
+            Err(e) => report.issues.push(TemplateIssue::render(e)),
+        }
+        Ok(report)
+    }
+
+    async fn render(
+        &self,
+        template_id: i64,
+        context: &serde_json::Value,
+        locale: Option<&str>,
+    ) -> Result<Option<RenderedTemplate>> {
+        if !context.is_object() {
+            return Err(TemplateError::Validation("render context must be an object".to_string()).into());
+        }
+
+        let Some(template) = self.repository.get(template_id).await? else {
+            return Ok(None);
+        };
+        let translations = self.repository.list_translations(template_id).await?;
+
+        let chain = locale::lookup_chain(locale, &self.locale_fallbacks);
+        let (template, locale) = translation::localize(&template, &translations, &chain);
+        let rendered = self.engine.render(&template, context)?;
+
+        Ok(Some(RenderedTemplate {
+            locale: locale.map(str::to_string),
+            ..rendered
+        }))
+    }
+
+    async fn put_translation(&self, template_id: i64, locale: &str, body: &str) -> Result<Option<TemplateTranslation>> {
+        let locale = TemplateTranslation::validate(locale, body)?;
+        let Some(template) = self.repository.get(template_id).await? else {
+            return Ok(None);
+        };
+        self.engine.validate(template.format, body)?;
+
+        if let Some(replaced) = self.translation(template_id, &locale).await? {
+            self.mark_referenced(asset::references(&replaced.body)).await?;
+        }
+        self.repository.put_translation(template_id, &locale, body).await
+    }
+
+    async fn upload_translations(
+        &self,
+        template_id: i64,
+        bundle: HashMap<String, String>,
+        replace: bool,
+    ) -> Result<Option<Vec<TemplateTranslation>>> {
+        let bundle = translation::validate_bundle(bundle)?;
+        let Some(template) = self.repository.get(template_id).await? else {
+            return Ok(None);
+        };
+        for (locale, body) in &bundle {
+            self.engine
+                .validate(template.format, body)
+                .map_err(|e| TemplateError::Validation(format!("{locale} translation: {e}")))?;
+        }
+
+        let translations = self.repository.list_translations(template_id).await?;
+        let referenced = translations.iter().flat_map(|t| asset::references(&t.body)).collect();
+        self.mark_referenced(referenced).await?;
+        self.repository.put_translations(template_id, &bundle, replace).await
+    }
+
+    async fn delete_translation(&self, template_id: i64, locale: &str) -> Result<bool> {
+        let locale = locale::canonical(locale);
+        if let Some(deleted) = self.translation(template_id, &locale).await? {
+            self.mark_referenced(asset::references(&deleted.body)).await?;
+        }
+
+        self.repository.delete_translation(template_id, &locale).await
+    }
+
+    async fn list_translations(&self, template_id: i64) -> Result<Vec<TemplateTranslation>> {
+        self.repository.list_translations(template_id).await
+    }
+
+    async fn upload_asset(&self, asset: NewAsset, content: Vec<u8>) -> Result<TemplateAsset> {
+        asset.validate()?;
+        let store = self.asset_store()?;
+        if self.repository.get_asset(&asset.name).await?.is_some() {
+            return Err(TemplateError::AssetExists(asset.name).into());
+        }
+        asset.check_content(&content)?;
+
+        let stored = StoredAsset {
+            size_bytes: content.len() as i64,
+            sha256: Sha256::digest(&content).iter().map(|b| format!("{b:02x}")).collect(),
+            storage_key: format!("{}/{}", tenant::current().setting(), Uuid::new_v4()),
+            name: asset.name,
+            content_type: asset.content_type,
+        };
+        store.put(&stored.storage_key, &stored.content_type, content).await?;
+
+        match self.repository.create_asset(&stored).await {
+            Ok(Some(created)) => Ok(created),
+            // Lost a race for the name; the blob was never referenced
+            Ok(None) => {
+                store.delete(&stored.storage_key).await?;
+                Err(TemplateError::AssetExists(stored.name).into())
+            }
+            Err(e) => {
+                if let Err(cleanup) = store.delete(&stored.storage_key).await {
+                    error!(key = %stored.storage_key, error = %cleanup, "Failed to delete asset blob after failed insert");
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn get_asset(&self, name: &str) -> Result<Option<SignedAsset>> {
+        let Some(asset) = self.repository.get_asset(name).await? else {
+            return Ok(None);
+        };
+        let store = self.asset_store()?;
+
+        let expires_at = Utc::now() + asset::SIGNED_URL_TTL;
+        let url = store.signed_url(&asset.storage_key, asset::SIGNED_URL_TTL).await?;
+        Ok(Some(SignedAsset { asset, url, expires_at }))
+    }
+
+    async fn collect_assets(&self) -> Result<usize> {
+        let store = self.asset_store()?;
+        let unreferenced = self
+            .repository
+            .unreferenced_assets(Utc::now() - asset::UNREFERENCED_GRACE)
+            .await?;
+
+        let mut deleted = 0;
+        for asset in unreferenced {
+            // Blob first: a row left behind is retried on the next run, a
+            // blob left behind would never be found again
+            store.delete(&asset.storage_key).await?;
+            if self.repository.delete_asset(asset.id).await? {
+                info!(id = asset.id, name = %asset.name, "Deleted unreferenced template asset");
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,270 @@
+//! Stand-ins for the repositories `CampaignSender` reads, holding one
+//! campaign that is already sending. Its template is kept in the world's
+//! in-memory template repository.
+//!
+//! Only what the sender calls is implemented; the rest panics.
+
+use std::sync::{Mutex, MutexGuard};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+
+use newsletter::domain::campaign::audience::{AudienceEstimate, AudienceFilter};
+use newsletter::domain::campaign::complaint::Complaint;
+use newsletter::domain::campaign::delivery::{DeliveryCounts, DeliveryResult, Recipient};
+use newsletter::domain::campaign::engagement::{DomainStats, EngagementEvent, EngagementStats, LinkEngagement};
+use newsletter::domain::campaign::reengagement::SegmentMember;
+use newsletter::domain::campaign::sending_domain::{
+    DkimKey, DkimKeyStatus, GeneratedKey, NewSendingDomain, SendingDomain,
+};
+use newsletter::domain::campaign::{Campaign, CampaignStatus, NewCampaign};
+use newsletter::domain::newsletter::attributes::Attributes;
+use newsletter::domain::pagination::{Page, PageRequest};
+use newsletter::repository::campaign::CampaignRepository;
+use newsletter::repository::sending_domain::SendingDomainRepository;
+
+pub const CAMPAIGN_ID: i64 = 1;
+/// The first template the world's repository creates
+pub const TEMPLATE_ID: i64 = 1;
+/// Domain of the `EMAIL_FROM` address campaigns go out from by default
+pub const EMAIL_FROM_DOMAIN: &str = "example.com";
+/// Where the campaign's recipients are, apart from everyone else mailed
+pub const CAMPAIGN_RECIPIENT_DOMAIN: &str = "@example.net";
+
+#[derive(Debug)]
+struct State {
+    campaign: Campaign,
+    pending: Vec<Recipient>,
+    /// Pending but held for their recipients' local send hours
+    held: Vec<Recipient>,
+    /// Claimed by a batch and not recorded yet
+    sending: Vec<Recipient>,
+    sent: i64,
+    failed: i64,
+}
+
+/// The deliveries of a single campaign in `Sending`
+#[derive(Debug)]
+pub struct StubCampaigns {
+    state: Mutex<State>,
+}
+
+impl StubCampaigns {
+    pub fn sending() -> Self {
+        let now = Utc::now();
+        Self {
+            state: Mutex::new(State {
+                campaign: Campaign {
+                    id: CAMPAIGN_ID,
+                    name: "Launch".to_string(),
+                    subject: "We launched".to_string(),
+                    template_id: TEMPLATE_ID,
+                    status: CampaignStatus::Sending,
+                    send_window: None,
+                    topic: None,
+                    tag: None,
+                    variables: serde_json::json!({}),
+                    local_send_hours: None,
+                    version: 1,
+                    created_at: now,
+                    updated_at: now,
+                },
+                pending: Vec::new(),
+                held: Vec::new(),
+                sending: Vec::new(),
+                sent: 0,
+                failed: 0,
+            }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    /// Queue `count` more deliveries, held for their recipients' local send
+    /// hours or due at once
+    pub fn add_recipients(&self, count: usize, held: bool) {
+        let mut state = self.state();
+        let first = state.pending.len() + state.held.len() + state.sending.len() + (state.sent + state.failed) as usize;
+        let recipients = (first..first + count).map(|n| Recipient {
+            email: format!("reader{n}{CAMPAIGN_RECIPIENT_DOMAIN}"),
+            attributes: Attributes::new(),
+            locale: None,
+            attempts: 0,
+        });
+        if held {
+            state.held.extend(recipients);
+        } else {
+            state.pending.extend(recipients);
+        }
+    }
+
+    /// Make the held deliveries due, as their local send hour came
+    pub fn release_held(&self) {
+        let mut state = self.state();
+        let held = std::mem::take(&mut state.held);
+        state.pending.extend(held);
+    }
+
+    pub fn pending(&self) -> usize {
+        let state = self.state();
+        state.pending.len() + state.held.len()
+    }
+
+    pub fn status(&self) -> CampaignStatus {
+        self.state().campaign.status
+    }
+}
+
+#[async_trait]
+impl CampaignRepository for StubCampaigns {
+    async fn create(&self, _campaign: &NewCampaign) -> Result<Campaign> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn get(&self, id: i64) -> Result<Option<Campaign>> {
+        let state = self.state();
+        Ok((id == state.campaign.id).then(|| state.campaign.clone()))
+    }
+
+    async fn save(&self, campaign: &Campaign) -> Result<Campaign> {
+        self.state().campaign = campaign.clone();
+        Ok(campaign.clone())
+    }
+
+    async fn list(&self, _page: PageRequest) -> Result<Page<Campaign>> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn start_sending(&self, _campaign: &Campaign) -> Result<i64> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn claim_deliveries(&self, _campaign_id: i64, limit: i64, _lease: Duration) -> Result<Vec<Recipient>> {
+        let mut state = self.state();
+        let count = state.pending.len().min(limit as usize);
+        let claimed: Vec<Recipient> = state.pending.drain(..count).collect();
+        state.sending.extend(claimed.iter().cloned());
+        Ok(claimed)
+    }
+
+    async fn record_deliveries(&self, _campaign_id: i64, results: &[(String, DeliveryResult)]) -> Result<()> {
+        let mut state = self.state();
+        for (email, result) in results {
+            let Some(index) = state.sending.iter().position(|r| &r.email == email) else {
+                continue;
+            };
+            let recipient = state.sending.remove(index);
+            match result {
+                DeliveryResult::Sent => state.sent += 1,
+                DeliveryResult::Failed(_) => state.failed += 1,
+                DeliveryResult::Retry(_) => state.pending.push(recipient),
+            }
+        }
+        Ok(())
+    }
+
+    async fn delivery_counts(&self, _campaign_id: i64) -> Result<DeliveryCounts> {
+        let state = self.state();
+        Ok(DeliveryCounts {
+            pending: (state.pending.len() + state.held.len()) as i64,
+            sending: state.sending.len() as i64,
+            sent: state.sent,
+            failed: state.failed,
+            skipped: 0,
+        })
+    }
+
+    async fn next_delivery_at(&self, _campaign_id: i64) -> Result<Option<DateTime<Utc>>> {
+        Ok((!self.state().held.is_empty()).then(|| Utc::now() + Duration::hours(1)))
+    }
+
+    async fn record_engagement(&self, _event: &EngagementEvent) -> Result<()> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn engagement_stats(&self, _campaign_id: i64) -> Result<EngagementStats> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn record_complaint(&self, _complaint: &Complaint) -> Result<bool> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn link_engagement(&self, _campaign_id: i64) -> Result<Vec<LinkEngagement>> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn domain_stats(&self, _page: PageRequest) -> Result<Page<DomainStats>> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn estimate_audience(&self, _filter: &AudienceFilter, _sample: i64) -> Result<AudienceEstimate> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn find_unengaged(&self, _since: DateTime<Utc>, _segment: &str) -> Result<Vec<String>> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn segment_members(&self, _segment: &str) -> Result<Vec<SegmentMember>> {
+        unimplemented!("not used by the sender")
+    }
+}
+
+/// A tenant's sending domain whose key was verified
+#[derive(Debug)]
+pub struct VerifiedSendingDomain {
+    domain: SendingDomain,
+}
+
+impl VerifiedSendingDomain {
+    pub fn new(domain: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            domain: SendingDomain {
+                id: 1,
+                domain: domain.to_string(),
+                from_address: format!("news@{domain}"),
+                created_at: now,
+                verified_at: Some(now),
+                // Never used to sign, as the scenarios' mailer only records
+                active_key: Some(DkimKey {
+                    id: 1,
+                    selector: "s1".to_string(),
+                    private_key: String::new(),
+                    public_key: String::new(),
+                    status: DkimKeyStatus::Active,
+                    created_at: now,
+                    activated_at: Some(now),
+                }),
+                pending_key: None,
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl SendingDomainRepository for VerifiedSendingDomain {
+    async fn get(&self) -> Result<Option<SendingDomain>> {
+        Ok(Some(self.domain.clone()))
+    }
+
+    async fn create(&self, _domain: &NewSendingDomain, _key: &GeneratedKey) -> Result<Option<SendingDomain>> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn replace_pending_key(&self, _domain_id: i64, _key: &GeneratedKey) -> Result<Option<SendingDomain>> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn activate_key(&self, _domain_id: i64, _key_id: i64, _at: DateTime<Utc>) -> Result<Option<SendingDomain>> {
+        unimplemented!("not used by the sender")
+    }
+
+    async fn delete(&self, _id: i64) -> Result<bool> {
+        unimplemented!("not used by the sender")
+    }
+}
@@ -0,0 +1,1488 @@
+//! World shared by the cucumber suites.
+//!
+//! Steps drive the real `DefaultNewsletterService` on top of the in-memory
+//! repository, so the scenarios exercise the same validation and double
+//! opt-in flow as the gRPC path without needing Postgres.
+
+// Each suite only uses part of the helpers.
+#![allow(dead_code)]
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use cucumber::World;
+use newsletter::domain::analytics::{partition, DailyRows, EngagementRecord, SubscriptionEventRecord};
+use newsletter::domain::newsletter::attributes::Attributes;
+use newsletter::domain::newsletter::error::NewsletterError;
+use newsletter::domain::newsletter::consent::{ConsentContext, ConsentRecord};
+use newsletter::domain::newsletter::growth::{Granularity, GrowthPoint, GrowthRange};
+use newsletter::domain::newsletter::history::SubscriberHistory;
+use newsletter::domain::newsletter::lifecycle::{PendingPurge, SubscriptionStatus};
+use newsletter::domain::newsletter::mask::{NewsletterMask, PartialNewsletter};
+use newsletter::domain::newsletter::normalize::Normalization;
+use newsletter::domain::newsletter::preferences::{TopicPreference, TopicSubscription};
+use newsletter::domain::newsletter::preview::BulkPreview;
+use newsletter::domain::newsletter::query::NewsletterQuery;
+use newsletter::domain::newsletter::retention::{RetentionAction, RetentionPolicy, RetentionRules, TenantRetention};
+use newsletter::domain::newsletter::search::{SearchHit, SearchQuery};
+use newsletter::domain::newsletter::signup::SignupDetails;
+use newsletter::domain::newsletter::stats::SubscriberStats;
+use newsletter::domain::newsletter::unsubscribe::UnsubscribeFeedback;
+use newsletter::domain::newsletter::{EmailAddress, Newsletter, SubscriptionEvent};
+use newsletter::domain::pagination::{PageRequest, MAX_PAGE_SIZE};
+use newsletter::domain::campaign::warmup::{WarmupDomains, WarmupSchedule};
+use newsletter::domain::jobs::{Job, JobKind, SendCampaignBatch};
+use newsletter::domain::template::asset::NewAsset;
+use newsletter::domain::template::{NewTemplate, Template, TemplateFormat, TemplateUpdate};
+use newsletter::domain::tenant::{TenantId, TenantScope};
+use newsletter::infrastructure::analytics::AnalyticsSink;
+use newsletter::infrastructure::assets::AssetStore;
+use newsletter::infrastructure::cache::memory::InMemoryCacheProvider;
+use newsletter::infrastructure::cache::{Cache, DEFAULT_TTL};
+use newsletter::infrastructure::email::failover::{FailoverMailSender, FailoverPolicy, Provider};
+use newsletter::infrastructure::email::{EmailMessage, MailError, MailSender};
+use newsletter::infrastructure::events::EventPublisher;
+use newsletter::infrastructure::pseudonym::Pseudonymizer;
+use newsletter::infrastructure::template::TemplateEngine;
+use newsletter::infrastructure::tenant;
+use newsletter::infrastructure::token::TokenSigner;
+use newsletter::infrastructure::verification::DomainListLocation;
+use newsletter::repository::analytics::memory::InMemoryAnalyticsRepository;
+use newsletter::repository::breaker::{BreakerCounts, BreakerPolicy, CircuitBreaker};
+use newsletter::repository::jobs::memory::InMemoryJobRepository;
+#[cfg(not(any(feature = "sqlite", feature = "postgres-tests")))]
+use newsletter::repository::newsletter::memory::InMemoryNewsletterRepository;
+#[cfg(feature = "postgres-tests")]
+use newsletter::repository::newsletter::postgres::PostgresNewsletterRepository;
+#[cfg(all(feature = "sqlite", not(feature = "postgres-tests")))]
+use newsletter::repository::newsletter::sqlite::SqliteNewsletterRepository;
+use newsletter::repository::newsletter::NewsletterRepository;
+#[cfg(feature = "postgres-tests")]
+use newsletter::repository::outbox::postgres::PostgresOutboxRepository;
+use newsletter::repository::outbox::OutboxRepository;
+use newsletter::repository::retry::{Retrier, RetryCounts, RetryPolicy};
+#[cfg(not(feature = "postgres-tests"))]
+use newsletter::repository::subscriber_view::memory::InMemorySubscriberViewRepository;
+#[cfg(feature = "postgres-tests")]
+use newsletter::repository::subscriber_view::postgres::PostgresSubscriberViewRepository;
+use newsletter::repository::subscriber_view::SubscriberViewRepository;
+use newsletter::repository::template::memory::InMemoryTemplateRepository;
+use newsletter::repository::template::TemplateRepository;
+use newsletter::repository::warmup::memory::InMemoryWarmupRepository;
+use newsletter::repository::warmup::WarmupRepository;
+use newsletter::service::analytics::{AnalyticsExporter, ExportedPartition};
+use newsletter::service::campaign::sender::{CampaignSender, SendThrottle};
+use newsletter::service::campaign::warmup::WarmupLimiter;
+use newsletter::service::jobs::{JobHandler, JobRunner};
+use newsletter::service::newsletter::jobs::ConfirmationMailer;
+use newsletter::service::newsletter::import::{ImportFormat, ImportSummary, ImportThrottle, StoreLoad, SubscriberImport};
+use newsletter::service::newsletter::seed::{self, SeedPlan, SeedSummary};
+use newsletter::service::newsletter::verification::{AddressVerifier, MailDomainResolver};
+use newsletter::service::newsletter::{
+    ConfirmationConfig, DefaultNewsletterService, NewsletterService, SubscribeOutcome,
+};
+use newsletter::service::outbox::OutboxRelay;
+use newsletter::service::template::assets::AssetLinks;
+use newsletter::service::template::{DefaultTemplateService, TemplateService};
+
+pub mod campaign;
+#[cfg(feature = "postgres-tests")]
+pub mod postgres;
+
+use campaign::{
+    StubCampaigns, VerifiedSendingDomain, CAMPAIGN_ID, CAMPAIGN_RECIPIENT_DOMAIN, EMAIL_FROM_DOMAIN, TEMPLATE_ID,
+};
+
+/// Store load a scenario switches by hand
+#[derive(Debug, Default)]
+pub struct SwitchableLoad {
+    saturated: AtomicBool,
+}
+
+impl SwitchableLoad {
+    pub fn set(&self, saturated: bool) {
+        self.saturated.store(saturated, Ordering::SeqCst);
+    }
+}
+
+impl StoreLoad for SwitchableLoad {
+    fn saturated(&self) -> bool {
+        self.saturated.load(Ordering::SeqCst)
+    }
+}
+
+/// Keeps the recipients of sent mail instead of delivering it
+#[derive(Debug, Default)]
+pub struct RecordingMailer {
+    recipients: Mutex<Vec<String>>,
+    /// Recipient and link token of every message that carried one
+    tokens: Mutex<Vec<(String, String)>>,
+}
+
+impl RecordingMailer {
+    pub fn recipients(&self) -> Vec<String> {
+        self.recipients.lock().unwrap().clone()
+    }
+
+    /// Token of the link in the last message sent to `to`
+    pub fn last_token(&self, to: &str) -> Option<String> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(recipient, _)| recipient == to)
+            .map(|(_, token)| token.clone())
+    }
+}
+
+#[async_trait]
+impl MailSender for RecordingMailer {
+    fn provider(&self) -> &'static str {
+        "recording"
+    }
+
+    async fn send(&self, message: &EmailMessage) -> Result<(), MailError> {
+        self.recipients.lock().unwrap().push(message.to.clone());
+        let token = message
+            .text
+            .as_deref()
+            .and_then(|text| text.split("?token=").nth(1))
+            .and_then(|rest| rest.split_whitespace().next());
+        if let Some(token) = token {
+            self.tokens.lock().unwrap().push((message.to.clone(), token.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// How a [`ScriptedProvider`] answers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProviderBehavior {
+    #[default]
+    Accepting,
+    /// Every send fails, like a provider answering 4xx
+    FailingTransiently,
+    /// Every send fails for good, like a 535 reply to bad credentials
+    FailingPermanently,
+    /// Every recipient is refused, like a 550 reply
+    Rejecting,
+}
+
+/// Email provider of the failover scenarios, counting what reached it
+#[derive(Debug, Default)]
+pub struct ScriptedProvider {
+    behavior: Mutex<ProviderBehavior>,
+    attempts: AtomicU32,
+    sent: AtomicU32,
+}
+
+impl ScriptedProvider {
+    pub fn behave(&self, behavior: ProviderBehavior) {
+        *self.behavior.lock().unwrap() = behavior;
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts.load(Ordering::SeqCst)
+    }
+
+    pub fn sent(&self) -> u32 {
+        self.sent.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl MailSender for ScriptedProvider {
+    fn provider(&self) -> &'static str {
+        "scripted"
+    }
+
+    async fn send(&self, _message: &EmailMessage) -> Result<(), MailError> {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        match *self.behavior.lock().unwrap() {
+            ProviderBehavior::Accepting => {
+                self.sent.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            ProviderBehavior::FailingTransiently => {
+                Err(MailError::Transient(anyhow::anyhow!("421 service not available")))
+            }
+            ProviderBehavior::FailingPermanently => {
+                Err(MailError::Permanent(anyhow::anyhow!("535 authentication credentials invalid")))
+            }
+            ProviderBehavior::Rejecting => Err(MailError::Rejected(anyhow::anyhow!("550 mailbox unavailable"))),
+        }
+    }
+}
+
+/// Keeps published events as `"<type> <email>"` so scenarios can assert on them
+#[derive(Debug, Default)]
+pub struct RecordingPublisher {
+    events: Mutex<Vec<String>>,
+    down: AtomicBool,
+}
+
+impl RecordingPublisher {
+    pub fn published(&self) -> Vec<String> {
+        self.events.lock().unwrap().clone()
+    }
+
+    /// Fail every publish from now on, like an unreachable broker
+    pub fn go_down(&self) {
+        self.down.store(true, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl EventPublisher for RecordingPublisher {
+    fn name(&self) -> &'static str {
+        "recording"
+    }
+
+    async fn publish(&self, event: &SubscriptionEvent) -> anyhow::Result<()> {
+        if self.down.load(Ordering::SeqCst) {
+            anyhow::bail!("broker unreachable");
+        }
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", event.kind, event.email));
+        Ok(())
+    }
+}
+
+/// Keeps the days written by the analytics export by partition
+#[derive(Debug, Default)]
+pub struct RecordingAnalyticsSink {
+    written: Mutex<Vec<(String, DailyRows)>>,
+}
+
+impl RecordingAnalyticsSink {
+    /// Rows last written to `partition`, if it was written
+    pub fn partition(&self, name: &str) -> Option<DailyRows> {
+        self.written
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(written, _)| written == name)
+            .map(|(_, rows)| rows.clone())
+    }
+
+    pub fn rows(&self) -> Vec<DailyRows> {
+        self.written.lock().unwrap().iter().map(|(_, rows)| rows.clone()).collect()
+    }
+}
+
+#[async_trait]
+impl AnalyticsSink for RecordingAnalyticsSink {
+    fn name(&self) -> &'static str {
+        "recording"
+    }
+
+    async fn write(&self, day: chrono::NaiveDate, rows: &DailyRows) -> anyhow::Result<()> {
+        self.written
+            .lock()
+            .unwrap()
+            .push((partition(rows.dataset(), day), rows.clone()));
+        Ok(())
+    }
+}
+
+/// Keeps the keys of the template asset blobs stored instead of their content
+#[derive(Debug, Default)]
+pub struct RecordingAssetStore {
+    keys: Mutex<Vec<String>>,
+}
+
+impl RecordingAssetStore {
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.lock().unwrap().iter().any(|stored| stored == key)
+    }
+}
+
+#[async_trait]
+impl AssetStore for RecordingAssetStore {
+    fn name(&self) -> &'static str {
+        "recording"
+    }
+
+    async fn put(&self, key: &str, _content_type: &str, _content: Vec<u8>) -> anyhow::Result<()> {
+        self.keys.lock().unwrap().push(key.to_string());
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.keys.lock().unwrap().retain(|stored| stored != key);
+        Ok(())
+    }
+
+    async fn signed_url(&self, key: &str, _expires_in: chrono::Duration) -> anyhow::Result<String> {
+        Ok(format!("https://assets.example.com/{key}"))
+    }
+}
+
+/// Answers MX lookups from a list of dead domains instead of DNS
+#[derive(Debug, Default)]
+pub struct StubMailDomains {
+    without_mail: Mutex<Vec<String>>,
+    timing_out: AtomicBool,
+}
+
+impl StubMailDomains {
+    pub fn refuse(&self, domain: &str) {
+        self.without_mail.lock().unwrap().push(domain.to_string());
+    }
+
+    pub fn time_out(&self) {
+        self.timing_out.store(true, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl MailDomainResolver for StubMailDomains {
+    async fn accepts_mail(&self, domain: &str) -> anyhow::Result<bool> {
+        if self.timing_out.load(Ordering::SeqCst) {
+            anyhow::bail!("request timed out");
+        }
+        Ok(!self.without_mail.lock().unwrap().iter().any(|dead| dead == domain))
+    }
+}
+
+fn confirmation() -> ConfirmationConfig {
+    ConfirmationConfig {
+        signer: TokenSigner::new("cucumber-secret"),
+        ttl: chrono::Duration::hours(1),
+        confirm_url: "http://localhost/confirm".to_string(),
+        email_change_url: "http://localhost/confirm-email".to_string(),
+        topic_url: "http://localhost/confirm-topic".to_string(),
+        required_on_resubscribe: true,
+        pending_retention: chrono::Duration::zero(),
+    }
+}
+
+/// Renders `{{asset "name"}}` as the campaign sender does when assets are on
+fn template_engine() -> TemplateEngine {
+    TemplateEngine::new().with_assets(AssetLinks::new(TokenSigner::new("cucumber-secret"), "http://localhost"))
+}
+
+/// The in-memory repository, with `--features sqlite` a private SQLite
+/// database, or with `--features postgres-tests` a private Postgres database,
+/// so the same scenarios check all three
+#[cfg(not(any(feature = "sqlite", feature = "postgres-tests")))]
+pub type Repository = InMemoryNewsletterRepository;
+#[cfg(all(feature = "sqlite", not(feature = "postgres-tests")))]
+pub type Repository = SqliteNewsletterRepository;
+#[cfg(feature = "postgres-tests")]
+pub type Repository = PostgresNewsletterRepository;
+
+/// A fresh repository, the outbox its writes land in and the read model the
+/// relay can keep
+type Stores = (Arc<Repository>, Arc<dyn OutboxRepository>, Arc<dyn SubscriberViewRepository>);
+
+#[cfg(not(any(feature = "sqlite", feature = "postgres-tests")))]
+fn repository(pseudonyms: Option<Pseudonymizer>) -> Stores {
+    let repository = InMemoryNewsletterRepository::new();
+    let repository = Arc::new(match pseudonyms {
+        Some(pseudonyms) => repository.with_pseudonyms(pseudonyms),
+        None => repository,
+    });
+    (repository.clone(), repository, Arc::new(InMemorySubscriberViewRepository::new()))
+}
+
+#[cfg(all(feature = "sqlite", not(feature = "postgres-tests")))]
+fn repository(pseudonyms: Option<Pseudonymizer>) -> Stores {
+    let repository = SqliteNewsletterRepository::open(":memory:").expect("failed to open SQLite database");
+    let repository = Arc::new(match pseudonyms {
+        Some(pseudonyms) => repository.with_pseudonyms(pseudonyms),
+        None => repository,
+    });
+    (repository.clone(), repository, Arc::new(InMemorySubscriberViewRepository::new()))
+}
+
+#[cfg(feature = "postgres-tests")]
+fn repository(pseudonyms: Option<Pseudonymizer>) -> Stores {
+    let pool = postgres::database();
+    let repository = PostgresNewsletterRepository::new(pool.clone());
+    let repository = match pseudonyms {
+        Some(pseudonyms) => repository.with_pseudonyms(pseudonyms),
+        None => repository,
+    };
+    (
+        Arc::new(repository),
+        Arc::new(PostgresOutboxRepository::new(pool.clone())),
+        Arc::new(PostgresSubscriberViewRepository::new(pool)),
+    )
+}
+
+#[derive(World)]
+#[world(init = Self::new)]
+pub struct NewsletterWorld {
+    pub repository: Arc<Repository>,
+    pub outbox: Arc<dyn OutboxRepository>,
+    pub views: Arc<dyn SubscriberViewRepository>,
+    pub service: Arc<dyn NewsletterService>,
+    pub publisher: Arc<RecordingPublisher>,
+    pub relay: OutboxRelay,
+    pub jobs: Arc<InMemoryJobRepository>,
+    pub mailer: Arc<RecordingMailer>,
+    pub runner: JobRunner,
+    pub last_response: Option<String>,
+    pub last_list: Vec<Newsletter>,
+    pub last_get: Option<Newsletter>,
+    pub last_masked_list: Vec<PartialNewsletter>,
+    pub last_preferences: Vec<TopicSubscription>,
+    /// Topics of the last preference change that wait for their link
+    pub awaiting_topics: Vec<String>,
+    pub last_attributes: Attributes,
+    pub last_locale: Option<String>,
+    pub last_timezone: Option<String>,
+    pub last_import: Option<ImportSummary>,
+    /// Paces imports once a scenario throttles them
+    pub import_throttle: Option<ImportThrottle>,
+    pub store_load: Arc<SwitchableLoad>,
+    pub last_preview: Option<BulkPreview>,
+    pub last_consents: Vec<ConsentRecord>,
+    /// Time noted by the scenario, to look back at later
+    pub noted_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_history: Option<SubscriberHistory>,
+    pub last_stats: Option<SubscriberStats>,
+    pub last_purge: Option<PendingPurge>,
+    pub retention: RetentionRules,
+    pub last_retention: Vec<TenantRetention>,
+    pub last_rollup: Option<usize>,
+    pub last_growth: Vec<GrowthPoint>,
+    /// The plan of the last seed run and, if it went through, its outcome
+    pub last_seed: Option<(SeedPlan, Option<SeedSummary>)>,
+    pub last_search: Vec<SearchHit>,
+    /// Cursor of the next page of the last search, and the text searched
+    pub next_search: Option<(String, i64)>,
+    /// Token of the last requested address change, as emailed to the new address
+    pub email_change_token: Option<String>,
+    pub retry_policy: RetryPolicy,
+    pub last_attempts: u32,
+    pub last_retry_counts: RetryCounts,
+    pub breaker: CircuitBreaker,
+    /// Breaker counters since the scenario started
+    pub breaker_counts: BreakerCounts,
+    /// Disposable domain list read by the verifier, one file per world
+    pub blocklist: PathBuf,
+    pub mail_domains: Arc<StubMailDomains>,
+    pub pseudonyms: Option<Pseudonymizer>,
+    pub analytics: Arc<InMemoryAnalyticsRepository>,
+    pub analytics_sink: Arc<RecordingAnalyticsSink>,
+    pub last_export: Vec<ExportedPartition>,
+    /// Providers of the failover scenarios, in priority order
+    pub email_providers: Vec<(String, Arc<ScriptedProvider>)>,
+    /// Limits of each provider, taken when the first email is sent
+    pub email_limits: Vec<Provider>,
+    pub failover_policy: FailoverPolicy,
+    pub failover: Option<FailoverMailSender>,
+    /// Daily caps of the warm-up scenarios; none turns warm-up off
+    pub warmup_caps: Vec<i64>,
+    pub warmup_domains: WarmupDomains,
+    pub warmup_volumes: Arc<InMemoryWarmupRepository>,
+    /// The tenant's verified sending domain; campaigns go out from
+    /// `EMAIL_FROM` without one
+    pub sending_domain: Option<String>,
+    pub campaigns: Arc<StubCampaigns>,
+    pub campaign_jobs: Arc<InMemoryJobRepository>,
+    /// Number of the campaign's next batch
+    pub campaign_batch: u32,
+    /// Holds the campaign's template, created on first use
+    pub templates: Arc<InMemoryTemplateRepository>,
+    pub asset_store: Arc<RecordingAssetStore>,
+    /// Assets deleted by the last collection
+    pub last_collected: Option<usize>,
+}
+
+impl fmt::Debug for NewsletterWorld {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NewsletterWorld")
+            .field("published", &self.publisher.published())
+            .field("mailed", &self.mailer.recipients())
+            .field("last_response", &self.last_response)
+            .field("last_list", &self.last_list)
+            .field("last_get", &self.last_get)
+            .field("last_masked_list", &self.last_masked_list)
+            .field("last_preferences", &self.last_preferences)
+            .field("awaiting_topics", &self.awaiting_topics)
+            .field("last_attributes", &self.last_attributes)
+            .field("last_locale", &self.last_locale)
+            .field("last_timezone", &self.last_timezone)
+            .field("last_import", &self.last_import)
+            .field("store_load", &self.store_load)
+            .field("last_preview", &self.last_preview)
+            .field("last_consents", &self.last_consents)
+            .field("noted_at", &self.noted_at)
+            .field("last_history", &self.last_history)
+            .field("last_stats", &self.last_stats)
+            .field("last_purge", &self.last_purge)
+            .field("last_retention", &self.last_retention)
+            .field("last_rollup", &self.last_rollup)
+            .field("last_growth", &self.last_growth)
+            .field("last_seed", &self.last_seed)
+            .field("last_search", &self.last_search)
+            .field("last_attempts", &self.last_attempts)
+            .field("last_retry_counts", &self.last_retry_counts)
+            .field("breaker", &self.breaker.state())
+            .field("breaker_counts", &self.breaker_counts)
+            .field("last_export", &self.last_export)
+            .field("email_providers", &self.email_providers)
+            .field("failover_policy", &self.failover_policy)
+            .field("warmup_caps", &self.warmup_caps)
+            .field("warmup_domains", &self.warmup_domains)
+            .field("sending_domain", &self.sending_domain)
+            .field("campaigns", &self.campaigns)
+            .finish()
+    }
+}
+
+impl Drop for NewsletterWorld {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.blocklist);
+    }
+}
+
+impl Default for NewsletterWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NewsletterWorld {
+    pub fn new() -> Self {
+        let (repository, outbox, views) = repository(None);
+        let confirmation = confirmation();
+        let jobs = Arc::new(InMemoryJobRepository::new());
+        let mailer = Arc::new(RecordingMailer::default());
+        let confirmation_mailer = Arc::new(ConfirmationMailer::new(confirmation.clone(), mailer.clone()));
+        let runner = JobRunner::new(jobs.clone())
+            .register(JobKind::SendConfirmation, confirmation_mailer.clone())
+            .register(JobKind::SendEmailChange, confirmation_mailer.clone())
+            .register(JobKind::SendTopicConfirmation, confirmation_mailer);
+        let service = Arc::new(DefaultNewsletterService::new(
+            repository.clone(),
+            confirmation,
+            jobs.clone(),
+        ));
+        let publisher = Arc::new(RecordingPublisher::default());
+        let relay = OutboxRelay::new(outbox.clone(), publisher.clone());
+
+        Self {
+            repository,
+            outbox,
+            views,
+            service,
+            publisher,
+            relay,
+            jobs,
+            mailer,
+            runner,
+            last_response: None,
+            last_list: Vec::new(),
+            last_get: None,
+            last_masked_list: Vec::new(),
+            last_preferences: Vec::new(),
+            awaiting_topics: Vec::new(),
+            last_attributes: Attributes::new(),
+            last_locale: None,
+            last_timezone: None,
+            last_import: None,
+            import_throttle: None,
+            store_load: Arc::new(SwitchableLoad::default()),
+            last_preview: None,
+            last_consents: Vec::new(),
+            noted_at: None,
+            last_history: None,
+            last_stats: None,
+            last_purge: None,
+            retention: RetentionRules::default(),
+            last_retention: Vec::new(),
+            last_rollup: None,
+            last_growth: Vec::new(),
+            last_seed: None,
+            last_search: Vec::new(),
+            next_search: None,
+            email_change_token: None,
+            retry_policy: RetryPolicy {
+                base_delay: Duration::from_millis(1),
+                max_delay: Duration::from_millis(5),
+                ..RetryPolicy::default()
+            },
+            last_attempts: 0,
+            last_retry_counts: RetryCounts::default(),
+            breaker: CircuitBreaker::new(BreakerPolicy::default()),
+            breaker_counts: BreakerCounts::default(),
+            blocklist: std::env::temp_dir().join(format!("newsletter-blocklist-{}.txt", uuid::Uuid::new_v4())),
+            mail_domains: Arc::new(StubMailDomains::default()),
+            pseudonyms: None,
+            analytics: Arc::new(InMemoryAnalyticsRepository::new()),
+            analytics_sink: Arc::new(RecordingAnalyticsSink::default()),
+            last_export: Vec::new(),
+            email_providers: Vec::new(),
+            email_limits: Vec::new(),
+            failover_policy: FailoverPolicy::default(),
+            failover: None,
+            warmup_caps: Vec::new(),
+            warmup_domains: WarmupDomains::default(),
+            warmup_volumes: Arc::new(InMemoryWarmupRepository::new()),
+            sending_domain: None,
+            campaigns: Arc::new(StubCampaigns::sending()),
+            campaign_jobs: Arc::new(InMemoryJobRepository::new()),
+            campaign_batch: 0,
+            templates: Arc::new(InMemoryTemplateRepository::new()),
+            asset_store: Arc::new(RecordingAssetStore::default()),
+            last_collected: None,
+        }
+    }
+
+    pub fn cleanup(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Swap in a service that folds Gmail aliases, keeping the stored data
+    pub fn fold_gmail_aliases(&mut self) {
+        let normalization = Normalization {
+            fold_gmail_aliases: true,
+        };
+        self.service = Arc::new(
+            DefaultNewsletterService::new(self.repository.clone(), confirmation(), self.jobs.clone())
+                .with_normalization(normalization),
+        );
+    }
+
+    /// Start over with consent and unsubscribe records and published events
+    /// carrying pseudonyms instead of addresses
+    pub fn pseudonymize(&mut self, key: &str) {
+        let pseudonyms = Pseudonymizer::new(key);
+        (self.repository, self.outbox, self.views) = repository(Some(pseudonyms.clone()));
+        self.service = Arc::new(DefaultNewsletterService::new(
+            self.repository.clone(),
+            confirmation(),
+            self.jobs.clone(),
+        ));
+        self.relay = OutboxRelay::new(self.outbox.clone(), self.publisher.clone())
+            .with_pseudonyms(pseudonyms.clone());
+        self.pseudonyms = Some(pseudonyms);
+    }
+
+    /// Swap in a service reading stats and plain listings from the read
+    /// model, and a relay keeping it, keeping the stored data
+    pub fn serve_from_read_model(&mut self) {
+        self.service = Arc::new(
+            DefaultNewsletterService::new(self.repository.clone(), confirmation(), self.jobs.clone())
+                .with_read_model(self.views.clone()),
+        );
+        self.relay = OutboxRelay::new(self.outbox.clone(), self.publisher.clone())
+            .with_read_model(self.views.clone());
+    }
+
+    /// Swap in a service that lets unsubscribed addresses back in without
+    /// confirming again, keeping the stored data
+    pub fn skip_confirmation_on_resubscribe(&mut self) {
+        let confirmation = ConfirmationConfig {
+            required_on_resubscribe: false,
+            ..confirmation()
+        };
+        self.service = Arc::new(DefaultNewsletterService::new(
+            self.repository.clone(),
+            confirmation,
+            self.jobs.clone(),
+        ));
+    }
+
+    /// Swap in a service whose confirmation links have expired by the time
+    /// they are sent, keeping lapsed signups for `retention` afterwards
+    pub fn expire_links_at_once(&mut self, retention: chrono::Duration) {
+        let confirmation = ConfirmationConfig {
+            ttl: chrono::Duration::zero(),
+            pending_retention: retention,
+            ..confirmation()
+        };
+        self.service = Arc::new(DefaultNewsletterService::new(
+            self.repository.clone(),
+            confirmation,
+            self.jobs.clone(),
+        ));
+    }
+
+    /// Run the purge of lapsed signups through the service
+    pub async fn purge_expired_pending(&mut self) {
+        self.last_purge = Some(self.service.purge_expired_pending().await.expect("in-memory purge"));
+    }
+
+    /// Keep unsubscribed subscriptions of `tenant`, or of every tenant
+    /// without an override, for `days` before `action`; no action keeps
+    /// them forever. Zero days clears them on the next run.
+    pub fn retain_unsubscribed(&mut self, tenant: Option<TenantId>, days: i64, action: Option<RetentionAction>) {
+        let policy = action.map(|action| RetentionPolicy {
+            unsubscribed_for: chrono::Duration::days(days),
+            action,
+        });
+        match tenant {
+            Some(tenant) => {
+                self.retention.overrides.insert(tenant, policy);
+            }
+            None => self.retention.default = policy,
+        }
+        self.service = Arc::new(
+            DefaultNewsletterService::new(self.repository.clone(), confirmation(), self.jobs.clone())
+                .with_retention(self.retention.clone()),
+        );
+    }
+
+    /// Run retention over every tenant, as the hourly job does
+    pub async fn apply_retention(&mut self) {
+        self.last_retention = tenant::scope(TenantScope::All, self.service.apply_retention())
+            .await
+            .expect("in-memory retention");
+    }
+
+    /// Noon today; the export runs at this time, so a scenario's days do
+    /// not depend on how close to midnight it runs
+    pub fn analytics_now() -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc::now()
+            .date_naive()
+            .and_hms_opt(12, 0, 0)
+            .expect("noon is a valid time")
+            .and_utc()
+    }
+
+    pub fn record_engagement(&self, kind: &str, email: &str, days_ago: i64) {
+        self.analytics.record_engagement(EngagementRecord {
+            tenant: TenantId::default().to_string(),
+            campaign_id: 1,
+            email: email.to_string(),
+            kind: kind.to_string(),
+            url: (kind == "click").then(|| "https://example.com".to_string()),
+            occurred_at: Self::analytics_now() - chrono::Duration::days(days_ago),
+        });
+    }
+
+    pub fn record_subscription_event(&self, event_type: &str, email: &str, days_ago: i64) {
+        self.analytics.record_subscription_event(SubscriptionEventRecord {
+            tenant: TenantId::default().to_string(),
+            event_type: event_type.to_string(),
+            email: email.to_string(),
+            previous_email: None,
+            status: None,
+            occurred_at: Self::analytics_now() - chrono::Duration::days(days_ago),
+        });
+    }
+
+    /// Run the export as the hourly job does, with the world's pseudonyms
+    pub async fn export_analytics(&mut self) {
+        let mut exporter = AnalyticsExporter::new(self.analytics.clone(), self.analytics_sink.clone());
+        if let Some(pseudonyms) = &self.pseudonyms {
+            exporter = exporter.with_pseudonyms(pseudonyms.clone());
+        }
+        self.last_export = exporter.export(Self::analytics_now()).await.expect("in-memory export");
+    }
+
+    /// Snapshot today's growth, as the nightly rollup does for the day before
+    pub async fn rollup_growth(&mut self) {
+        let today = chrono::Utc::now().date_naive();
+        self.last_rollup = Some(self.service.record_daily_metrics(today).await.expect("in-memory rollup"));
+    }
+
+    pub async fn growth(&mut self, from: chrono::NaiveDate, to: chrono::NaiveDate, granularity: Granularity) {
+        let result = async {
+            let range = GrowthRange::new(from, to, granularity)?;
+            self.service.growth_time_series(range).await
+        }
+        .await;
+        self.last_growth = result.as_ref().cloned().unwrap_or_default();
+        self.record(result);
+    }
+
+    pub async fn search(&mut self, text: &str, page_size: i64, after: Option<i64>) {
+        let result = async {
+            let query = SearchQuery::parse(text)?;
+            self.service.search(&query, PageRequest::new(page_size, after)).await
+        }
+        .await;
+        match &result {
+            Ok(page) => {
+                self.last_search = page.items.clone();
+                self.next_search = page.next_cursor.map(|cursor| (text.to_string(), cursor));
+            }
+            Err(_) => {
+                self.last_search = Vec::new();
+                self.next_search = None;
+            }
+        }
+        self.record(result);
+    }
+
+    /// When `email` last subscribed again after unsubscribing
+    pub async fn resubscribed_at(&self, email: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+        let email = EmailAddress::parse(email).expect("valid address in scenario");
+        self.service
+            .get_newsletter(&email, NewsletterMask::ALL)
+            .await
+            .expect("in-memory read")
+            .and_then(|n| n.resubscribed_at)
+    }
+
+    /// Swap in a service caching status and stats reads, keeping the stored data
+    pub fn cache_reads(&mut self) {
+        let cache = Cache::new(Arc::new(InMemoryCacheProvider::new()), DEFAULT_TTL);
+        self.service = Arc::new(
+            DefaultNewsletterService::new(self.repository.clone(), confirmation(), self.jobs.clone())
+                .with_cache(cache),
+        );
+    }
+
+    /// Write the disposable domain list, one domain per line
+    pub fn write_blocklist(&self, domains: &[&str]) {
+        std::fs::write(&self.blocklist, domains.join("\n")).expect("writable temp dir");
+    }
+
+    /// Swap in a service checking the world's blocklist and stub MX answers,
+    /// keeping the stored data
+    pub async fn verify_addresses(&mut self) {
+        let source = DomainListLocation::new(self.blocklist.to_str().expect("utf-8 temp path")).expect("file source");
+        let verifier = AddressVerifier::new()
+            .with_disposable_domains(Arc::new(source))
+            .with_mx_lookup(self.mail_domains.clone());
+        verifier.refresh().await.expect("blocklist written before verification is enabled");
+        self.service = Arc::new(
+            DefaultNewsletterService::new(self.repository.clone(), confirmation(), self.jobs.clone())
+                .with_verifier(Arc::new(verifier)),
+        );
+    }
+
+    pub async fn refresh_disposable_domains(&mut self) {
+        let result = self.service.refresh_disposable_domains().await;
+        self.record(result);
+    }
+
+    /// Change a subscription in the repository without going through the
+    /// service, as another writer would
+    pub async fn deactivate_in_repository(&self, email: &str) {
+        self.repository
+            .set_status_many(&[email.to_string()], SubscriptionStatus::Unsubscribed)
+            .await
+            .expect("in-memory deactivate");
+    }
+
+    /// Purge pending subscriptions as if every confirmation link had expired
+    pub async fn expire_confirmations(&self) {
+        self.repository
+            .purge_expired_pending(chrono::Utc::now() + chrono::Duration::days(1))
+            .await
+            .expect("in-memory purge");
+    }
+
+    /// Run a read failing `failures` times with `error` through a retrier
+    /// with the world's policy, recording how many attempts it took
+    pub async fn run_flaky(&mut self, failures: u32, error: fn() -> NewsletterError) {
+        let retrier = Retrier::new(self.retry_policy);
+        let attempts = AtomicU32::new(0);
+        let result = retrier
+            .run("flaky_read", || async {
+                if attempts.fetch_add(1, Ordering::SeqCst) < failures {
+                    Err(error())
+                } else {
+                    Ok(())
+                }
+            })
+            .await;
+        self.last_attempts = attempts.load(Ordering::SeqCst);
+        self.last_retry_counts = retrier.take_counts();
+        self.record(result);
+    }
+
+    /// Run a read through the world's circuit breaker, failing with `error`
+    /// when given; `last_attempts` counts the reads that reached the store
+    pub async fn run_through_breaker(&mut self, error: Option<fn() -> NewsletterError>) {
+        let mut reached = false;
+        let result = self
+            .breaker
+            .run("flaky_read", async {
+                reached = true;
+                match error {
+                    Some(error) => Err(error()),
+                    None => Ok(()),
+                }
+            })
+            .await;
+        self.last_attempts += u32::from(reached);
+        let counts = self.breaker.take_counts();
+        self.breaker_counts.opened += counts.opened;
+        self.breaker_counts.rejected += counts.rejected;
+        self.record(result);
+    }
+
+    /// A provider of the failover scenarios by name
+    pub fn email_provider(&self, name: &str) -> Arc<ScriptedProvider> {
+        self.email_providers
+            .iter()
+            .find(|(provider, _)| provider == name)
+            .map(|(_, provider)| provider.clone())
+            .unwrap_or_else(|| panic!("no email provider {name:?} in the scenario"))
+    }
+
+    /// Send `count` emails through the providers, failing over between them;
+    /// the outcome of the last one is recorded
+    pub async fn send_through_providers(&mut self, count: u32) {
+        let failover = self.failover.get_or_insert_with(|| {
+            FailoverMailSender::new(std::mem::take(&mut self.email_limits), self.failover_policy)
+        });
+        let message = EmailMessage {
+            to: "reader@example.com".to_string(),
+            subject: "Hello".to_string(),
+            html: "<p>Hello</p>".to_string(),
+            text: None,
+            headers: Vec::new(),
+            sender: None,
+        };
+        let mut result = Ok(());
+        for _ in 0..count {
+            result = failover.send(&message).await;
+        }
+        self.record(result);
+    }
+
+    /// Record that `domain` started its ramp `days` ago, as if it had sent then
+    pub async fn started_warming_up(&self, domain: &str, days: u64) {
+        let day = chrono::Utc::now().date_naive() - chrono::Days::new(days);
+        self.warmup_volumes.started_on(domain, day).await.expect("in-memory warm-up start");
+    }
+
+    /// Run the campaign's next batch, sending from the tenant's domain or
+    /// from the `EMAIL_FROM` domain
+    pub async fn send_campaign_batch(&mut self) {
+        self.campaign_template().await;
+        let mut sender = CampaignSender::new(
+            self.campaigns.clone(),
+            self.templates.clone(),
+            template_engine(),
+            self.mailer.clone(),
+            self.campaign_jobs.clone(),
+            SendThrottle::default(),
+        );
+        if let Some(domain) = &self.sending_domain {
+            sender = sender.with_sending_domains(Arc::new(VerifiedSendingDomain::new(domain)));
+        }
+        if !self.warmup_caps.is_empty() {
+            sender = sender.with_warmup(WarmupLimiter::new(
+                self.warmup_volumes.clone(),
+                WarmupSchedule::new(self.warmup_caps.clone()),
+                self.warmup_domains.clone(),
+                Some(EMAIL_FROM_DOMAIN.to_string()),
+            ));
+        }
+
+        let batch = SendCampaignBatch {
+            campaign_id: CAMPAIGN_ID,
+            batch: self.campaign_batch,
+        };
+        self.campaign_batch += 1;
+        let job = Job {
+            id: i64::from(batch.batch) + 1,
+            kind: JobKind::SendCampaignBatch,
+            payload: serde_json::to_value(batch).expect("batch payload"),
+            attempts: 0,
+            max_attempts: 1,
+            tenant: tenant::current(),
+        };
+        let result = sender.run(&job).await;
+        self.record(result);
+    }
+
+    /// The campaign's template, created the first time it is asked for
+    pub async fn campaign_template(&self) -> Template {
+        if let Some(template) = self.templates.get(TEMPLATE_ID).await.expect("template lookup") {
+            return template;
+        }
+        let template = self
+            .templates
+            .create(&NewTemplate {
+                name: "launch".to_string(),
+                format: TemplateFormat::Handlebars,
+                body: "<p>Hello {{email}}</p>".to_string(),
+                text_body: None,
+                required_variables: Vec::new(),
+            })
+            .await
+            .expect("campaign template");
+        assert_eq!(template.id, TEMPLATE_ID, "the campaign's template must be the first one");
+        template
+    }
+
+    pub fn template_service(&self) -> DefaultTemplateService<InMemoryTemplateRepository> {
+        DefaultTemplateService::new(self.templates.clone(), template_engine()).with_assets(self.asset_store.clone())
+    }
+
+    /// Upload a PNG named `name`
+    pub async fn upload_asset(&mut self, name: &str) {
+        let asset = NewAsset {
+            name: name.to_string(),
+            content_type: "image/png".to_string(),
+        };
+        let content = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        let result = self.template_service().upload_asset(asset, content).await;
+        self.record(result);
+    }
+
+    /// Replace the body of the campaign's template, or of its translation
+    /// into `locale`
+    pub async fn set_campaign_body(&mut self, locale: Option<&str>, body: &str) {
+        let template = self.campaign_template().await;
+        let service = self.template_service();
+        let result = match locale {
+            Some(locale) => service.put_translation(template.id, locale, body).await.map(drop),
+            None => {
+                let update = TemplateUpdate {
+                    body: Some(body.to_string()),
+                    ..TemplateUpdate::default()
+                };
+                service.update_template(template.id, update).await.map(drop)
+            }
+        };
+        self.record(result);
+    }
+
+    pub async fn delete_campaign_translation(&mut self, locale: &str) {
+        let result = self.template_service().delete_translation(TEMPLATE_ID, locale).await;
+        self.record(result);
+    }
+
+    pub async fn collect_assets(&mut self) {
+        let result = self.template_service().collect_assets().await;
+        self.last_collected = result.as_ref().ok().copied();
+        self.record(result);
+    }
+
+    /// Whether the asset `name` is still recorded and its blob still stored
+    pub async fn asset_kept(&self, name: &str) -> bool {
+        match self.templates.get_asset(name).await.expect("asset lookup") {
+            Some(asset) => self.asset_store.contains(&asset.storage_key),
+            None => false,
+        }
+    }
+
+    /// Campaign emails the mailer was handed
+    pub fn campaign_emails(&self) -> usize {
+        self.mailer
+            .recipients()
+            .iter()
+            .filter(|to| to.ends_with(CAMPAIGN_RECIPIENT_DOMAIN))
+            .count()
+    }
+
+    fn record<T, E: fmt::Display>(&mut self, result: Result<T, E>) {
+        self.last_response = Some(match result {
+            Ok(_) => "success".to_string(),
+            Err(e) => format!("error: {e}"),
+        });
+    }
+
+    /// Subscribe and follow the confirmation link, as a reader would
+    pub async fn subscribe(&mut self, email: &str) {
+        self.subscribe_with_consent(email, ConsentContext::default()).await;
+    }
+
+    pub async fn subscribe_with_consent(&mut self, email: &str, consent: ConsentContext) {
+        let result = async {
+            let email = EmailAddress::parse(email)?;
+            if let SubscribeOutcome::PendingConfirmation { token, .. } =
+                self.service.subscribe(&email, consent).await?
+            {
+                self.service.confirm(&token, ConsentContext::default()).await?;
+            }
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+        self.record(result);
+    }
+
+    /// Sign up with a locale and topic choice, confirm, and read back the
+    /// attributes and preferences
+    pub async fn sign_up(&mut self, email: &str, locale: &str, topics: &str) {
+        let result = async {
+            let email = EmailAddress::parse(email)?;
+            let details = SignupDetails {
+                locale: Some(locale.to_string()),
+                source: Some("landing-page".to_string()),
+                topics: topics.split(", ").map(str::to_string).collect(),
+            };
+            if let SubscribeOutcome::PendingConfirmation { token, .. } =
+                self.service.sign_up(&email, ConsentContext::default(), details).await?
+            {
+                self.service.confirm(&token, ConsentContext::default()).await?;
+            }
+            self.last_attributes = self.service.get_attributes(&email).await?;
+            self.last_locale = self.locale_of(&email).await?;
+            self.last_preferences = self.service.get_preferences(&email).await?;
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+        self.record(result);
+    }
+
+    /// Subscribe without following the confirmation link
+    pub async fn request_subscription(&mut self, email: &str) {
+        let result = async {
+            let email = EmailAddress::parse(email)?;
+            self.service.subscribe(&email, ConsentContext::default()).await
+        }
+        .await;
+        self.record(result);
+    }
+
+    /// Ask to move the subscription of `email` to `new_email` without
+    /// following the link sent there
+    pub async fn request_email_change(&mut self, email: &str, new_email: &str) {
+        let result = async {
+            let email = EmailAddress::parse(email)?;
+            let new_email = EmailAddress::parse(new_email)?;
+            let pending = self.service.request_email_change(&email, &new_email).await?;
+            self.email_change_token = Some(pending.token);
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+        self.record(result);
+    }
+
+    /// Fold the subscription of `merged_email` into the one of `email`
+    pub async fn merge_subscribers(&mut self, email: &str, merged_email: &str) {
+        let result = async {
+            let email = EmailAddress::parse(email)?;
+            let merged_email = EmailAddress::parse(merged_email)?;
+            self.service.merge_subscribers(&email, &merged_email).await?;
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+        self.record(result);
+    }
+
+    /// Add `emails` straight to the repository in one unit of work, which
+    /// fails after the last of them if `fail`
+    pub async fn add_in_transaction(&mut self, emails: &[&str], fail: bool) {
+        let repository = self.repository.clone();
+        let result = repository
+            .with_transaction(async {
+                for email in emails {
+                    repository.add(email).await?;
+                }
+                if fail {
+                    return Err(NewsletterError::Validation("the unit of work gave up".to_string()));
+                }
+                Ok(())
+            })
+            .await;
+        self.record(result);
+    }
+
+    /// Follow the link of the last requested address change
+    pub async fn confirm_email_change(&mut self) {
+        let token = self.email_change_token.clone().expect("an address change was requested");
+        let result: anyhow::Result<()> = match self.service.confirm_email_change(&token, ConsentContext::default()).await {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => Err(anyhow::anyhow!("email change token is invalid or expired")),
+            Err(e) => Err(e.into()),
+        };
+        self.record(result);
+    }
+
+    pub async fn list_consents(&mut self, email: &str) {
+        let email = EmailAddress::parse(email).expect("valid email in scenario");
+        self.last_consents = self
+            .service
+            .list_consents(&email, PageRequest::new(MAX_PAGE_SIZE, None))
+            .await
+            .expect("in-memory consent history")
+            .items;
+    }
+
+    /// Note the current time, kept apart from the changes around it
+    pub async fn note_time(&mut self) {
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        self.noted_at = Some(chrono::Utc::now());
+        tokio::time::sleep(Duration::from_millis(2)).await;
+    }
+
+    /// Replay the history of `email` up to `at`, now if `None`
+    pub async fn subscriber_history(&mut self, email: &str, at: Option<chrono::DateTime<chrono::Utc>>) {
+        let email = EmailAddress::parse(email).expect("valid email in scenario");
+        self.last_history = Some(
+            self.service
+                .subscriber_history(&email, at.unwrap_or_else(chrono::Utc::now))
+                .await
+                .expect("in-memory subscriber history"),
+        );
+    }
+
+    pub async fn stats(&mut self) {
+        self.last_stats = Some(self.service.stats().await.expect("in-memory stats"));
+    }
+
+    pub async fn run_jobs(&mut self) {
+        let result = self.runner.drain().await;
+        self.record(result);
+    }
+
+    pub async fn relay_outbox(&mut self) {
+        let result = self.relay.drain().await;
+        self.record(result);
+    }
+
+    /// Delete every published outbox message, as the relay does once they are past retention
+    pub async fn purge_outbox(&mut self) {
+        let result = self.relay.purge_sent(chrono::Duration::zero()).await;
+        self.record(result);
+    }
+
+    /// Requeue the events published since `since`, as `newsletter-admin replay-outbox` does
+    pub async fn replay_outbox(&mut self, since: chrono::DateTime<chrono::Utc>) {
+        let result = self.outbox.replay(since).await;
+        self.record(result);
+    }
+
+    pub async fn unsubscribe(&mut self, email: &str) {
+        let result = async {
+            let email = EmailAddress::parse(email)?;
+            self.service.unsubscribe(&email, UnsubscribeFeedback::default()).await
+        }
+        .await;
+        self.record(result);
+    }
+
+    /// Move subscriptions to another status, as `UpdateStatus` does
+    pub async fn set_status(&mut self, emails: Vec<String>, status: SubscriptionStatus) {
+        let result = async {
+            let emails = emails
+                .iter()
+                .map(|e| EmailAddress::parse(e))
+                .collect::<Result<Vec<_>, _>>()?;
+            self.service.update_subscription_status(emails, status).await
+        }
+        .await;
+        self.record(result);
+    }
+
+    /// Preview a status update, as a dry run of `UpdateStatus` does
+    pub async fn preview_status(&mut self, emails: Vec<String>, status: SubscriptionStatus) {
+        let result = async {
+            let emails = emails
+                .iter()
+                .map(|e| EmailAddress::parse(e))
+                .collect::<Result<Vec<_>, _>>()?;
+            self.service.preview_status_update(emails, status).await
+        }
+        .await;
+        if let Ok(preview) = &result {
+            self.last_preview = Some(preview.clone());
+        }
+        self.record(result);
+    }
+
+    /// Preview a delete, as a dry run of `Delete` does
+    pub async fn preview_delete(&mut self, emails: Vec<String>) {
+        let result = async {
+            let emails = emails
+                .iter()
+                .map(|e| EmailAddress::parse(e))
+                .collect::<Result<Vec<_>, _>>()?;
+            self.service.preview_delete(emails).await
+        }
+        .await;
+        if let Ok(preview) = &result {
+            self.last_preview = Some(preview.clone());
+        }
+        self.record(result);
+    }
+
+    pub async fn delete_all(&mut self, emails: Vec<String>) {
+        let result = async {
+            let emails = emails
+                .iter()
+                .map(|e| EmailAddress::parse(e))
+                .collect::<Result<Vec<_>, _>>()?;
+            self.service.delete_subscriptions(emails).await
+        }
+        .await;
+        self.record(result);
+    }
+
+    pub async fn get_preferences(&mut self, email: &str) {
+        let result = async {
+            let email = EmailAddress::parse(email)?;
+            self.service.get_preferences(&email).await
+        }
+        .await;
+        if let Ok(topics) = &result {
+            self.last_preferences = topics.clone();
+        }
+        self.record(result);
+    }
+
+    pub async fn set_preference(&mut self, email: &str, topic: &str, subscribed: bool) {
+        let result = async {
+            let email = EmailAddress::parse(email)?;
+            let preference = TopicPreference {
+                topic: topic.to_string(),
+                subscribed,
+            };
+            self.service.set_preferences(&email, vec![preference]).await
+        }
+        .await;
+        if let Ok(update) = &result {
+            self.last_preferences = update.preferences.clone();
+            self.awaiting_topics = update.awaiting_confirmation.clone();
+        }
+        self.record(result);
+    }
+
+    /// Make opting in to `topic` take a confirmed link
+    pub async fn require_topic_confirmation(&self, topic: &str) {
+        let found = self
+            .repository
+            .set_topic_requires_confirmation(topic, true)
+            .await
+            .expect("in-memory topic update");
+        assert!(found, "Unknown topic {topic}");
+    }
+
+    /// Send the queued mail and follow the link in the last one to `email`
+    pub async fn confirm_topic(&mut self, email: &str) {
+        self.runner.drain().await.expect("in-memory jobs");
+        let token = self.mailer.last_token(email).expect("no link was mailed");
+        let result = self.service.confirm_topic(&token).await;
+        self.record(result);
+    }
+
+    pub async fn set_attribute(&mut self, email: &str, key: &str, value: serde_json::Value) {
+        let result = async {
+            let email = EmailAddress::parse(email)?;
+            let mut changes = Attributes::new();
+            changes.insert(key.to_string(), value);
+            self.service.set_attributes(&email, changes).await
+        }
+        .await;
+        if let Ok(attributes) = &result {
+            self.last_attributes = attributes.clone();
+        }
+        self.record(result);
+    }
+
+    /// Set or, with `None`, clear the preferred locale of a subscriber
+    pub async fn set_locale(&mut self, email: &str, locale: Option<&str>) {
+        let result = async {
+            let email = EmailAddress::parse(email)?;
+            self.service.set_locale(&email, locale).await?;
+            self.locale_of(&email).await
+        }
+        .await;
+        if let Ok(locale) = &result {
+            self.last_locale = locale.clone();
+        }
+        self.record(result);
+    }
+
+    pub async fn set_timezone(&mut self, email: &str, timezone: Option<&str>) {
+        let result = async {
+            let email = EmailAddress::parse(email)?;
+            self.service.set_timezone(&email, timezone).await?;
+            let newsletter = self.service.get_newsletter(&email, NewsletterMask::ALL).await?;
+            Ok::<_, NewsletterError>(newsletter.and_then(|n| n.timezone))
+        }
+        .await;
+        if let Ok(timezone) = &result {
+            self.last_timezone = timezone.clone();
+        }
+        self.record(result);
+    }
+
+    async fn locale_of(&self, email: &EmailAddress) -> Result<Option<String>, NewsletterError> {
+        let newsletter = self.service.get_newsletter(email, NewsletterMask::ALL).await?;
+        Ok(newsletter.and_then(|n| n.locale))
+    }
+
+    /// Throttle imports to one batch at a time, waiting on `store_load`
+    pub fn throttle_imports(&mut self, max_wait: Duration) {
+        self.import_throttle = Some(ImportThrottle::new(1, max_wait).with_load(self.store_load.clone()));
+    }
+
+    /// Feed an upload through the importer in fixed-size chunks
+    pub async fn import(&mut self, format: ImportFormat, upload: &str, chunk_size: usize, dry_run: bool) {
+        let mut import = SubscriberImport::new(format).with_dry_run(dry_run);
+        if let Some(throttle) = &self.import_throttle {
+            import = import.with_throttle(throttle.clone());
+        }
+        let result = async {
+            for chunk in upload.as_bytes().chunks(chunk_size) {
+                import.push(self.service.as_ref(), chunk).await?;
+            }
+            import.finish(self.service.as_ref()).await
+        }
+        .await;
+        self.last_import = result.as_ref().ok().cloned();
+        self.record(result);
+    }
+
+    pub async fn seed(&mut self, plan: SeedPlan) {
+        let result = seed::seed(self.service.as_ref(), &plan).await;
+        self.last_seed = Some((plan, result.as_ref().ok().copied()));
+        self.record(result);
+    }
+
+    pub async fn get(&mut self, email: &str) {
+        self.last_get = self
+            .repository
+            .get_by_email(email)
+            .await
+            .expect("in-memory lookup");
+    }
+
+    pub async fn list_all(&mut self) {
+        self.last_list = self
+            .service
+            .list_newsletters(PageRequest::new(MAX_PAGE_SIZE, None))
+            .await
+            .expect("in-memory list")
+            .items;
+    }
+
+    pub async fn list_masked(&mut self, paths: &[&str]) {
+        let mask = NewsletterMask::from_paths(paths).expect("valid field mask in scenario");
+        self.list_query(&NewsletterQuery::default(), mask).await;
+    }
+
+    pub async fn list_query(&mut self, query: &NewsletterQuery, mask: NewsletterMask) {
+        self.last_masked_list = self
+            .service
+            .list_newsletters_masked(query, PageRequest::new(MAX_PAGE_SIZE, None), mask)
+            .await
+            .expect("in-memory masked list")
+            .items;
+    }
+
+    pub async fn is_active(&self, email: &str) -> bool {
+        self.status(email).await.is_some_and(|status| status.is_active())
+    }
+
+    pub async fn status(&self, email: &str) -> Option<SubscriptionStatus> {
+        let email = EmailAddress::parse(email).expect("valid email in scenario");
+        self.service
+            .get_subscription_status(&email)
+            .await
+            .expect("in-memory status")
+    }
+
+    pub async fn exists(&self, email: &str) -> bool {
+        self.repository
+            .get_by_email(email)
+            .await
+            .expect("in-memory lookup")
+            .is_some()
+    }
+}
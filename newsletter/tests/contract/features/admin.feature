@@ -0,0 +1,44 @@
+Feature: Admin API contract
+  AdminService called over gRPC on the running server with an operator key
+
+  Scenario: Runtime settings are changed and inspected
+    When I set the log level to "info,newsletter=debug"
+    Then the call should succeed
+    When I set the log level to "=="
+    Then the call should fail with INVALID_ARGUMENT
+    When I get the build info
+    Then the response should mention "version: \"0.1.0\""
+    When I get the version
+    Then the response should mention "version: \"0.1.0\""
+    And the response should mention "built_at: Some("
+    When I get the migration status
+    Then the response should mention "pending: 0"
+
+  Scenario: Maintenance tasks report what they did
+    When I flush the cache
+    Then the response should mention "entries: 0"
+    When I replay the outbox since an hour ago
+    Then the call should succeed
+    When I purge expired pending subscriptions
+    Then the response should mention "deleted: 0"
+    When I refresh the blocklist
+    Then the response should mention "domains: 2"
+
+  Scenario: Subscribers are seeded into the caller's tenant
+    When I seed 30 subscribers with seed 5
+    Then the response should mention "created: 30"
+    When I seed 30 subscribers with seed 5
+    Then the response should mention "created: 0"
+    When I switch to another tenant
+    And I get the subscriber stats
+    Then the response should mention "active: 0"
+    When I seed 0 subscribers with seed 5
+    Then the call should fail with INVALID_ARGUMENT
+
+  Scenario: Delivery status reports the outbox and the failing webhook endpoint
+    When I subscribe "delivery@example.com"
+    And I wait for the delivery status to mention "destination: \"fanout\""
+    Then the response should mention "outbox: Some(OutboxStatus {"
+    When I wait for the delivery status to mention "url: \"{webhook}\""
+    And I wait for the delivery status to mention "last_error: \"error sending request"
+    Then the response should mention "delivered: 0"
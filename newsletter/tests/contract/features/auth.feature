@@ -0,0 +1,32 @@
+Feature: Authentication and tenancy contract
+  The auth and tenant layers in front of every service
+
+  Scenario: Calls without a key are refused
+    Given I call without an api key
+    When I list the subscribers
+    Then the call should fail with UNAUTHENTICATED
+
+  Scenario: Unknown keys are refused
+    Given I call with the api key "not-a-key"
+    When I list the subscribers
+    Then the call should fail with UNAUTHENTICATED
+
+  Scenario: Admin keys cannot reach the admin service
+    Given I call with the admin key
+    When I list the subscribers
+    Then the call should succeed
+    When I get the build info
+    Then the call should fail with PERMISSION_DENIED
+    When I get the version
+    Then the call should succeed
+
+  Scenario: Tenants only see their own subscribers
+    Given "ada@example.com" is subscribed
+    When I switch to another tenant
+    And I get "ada@example.com"
+    Then the response should mention "status: Unspecified"
+
+  Scenario: Malformed tenant ids are refused
+    Given I call for the tenant "no spaces allowed"
+    When I list the subscribers
+    Then the call should fail with INVALID_ARGUMENT
@@ -0,0 +1,69 @@
+Feature: Campaign API contract
+  CampaignService called over gRPC on the running server
+
+  Background:
+    Given the template "launch" with body "Hello {{attributes.first_name}}" exists
+
+  Scenario: A campaign is created, updated, scheduled and cancelled
+    When I create the campaign "Launch" with subject "We are live"
+    Then the response should mention "status: Draft"
+    When I rename the campaign to "Launch day"
+    Then the response should mention "Launch day"
+    When I schedule the campaign for tomorrow
+    Then the response should mention "status: Scheduled"
+    When I list the campaigns
+    Then the response should mention "Launch day"
+    When I cancel the campaign
+    Then the response should mention "status: Cancelled"
+
+  Scenario: Engagement of an unsent campaign is empty
+    Given the campaign "Launch" with subject "We are live" exists
+    When I get the engagement of the campaign
+    Then the response should mention "sent: 0"
+    When I list the link engagement of the campaign
+    Then the response should mention "links: []"
+    When I get the domain stats
+    Then the call should succeed
+
+  Scenario: Audiences are estimated and test emails rendered
+    Given "ada@example.com" is subscribed
+    And the campaign "Launch" with subject "We are live" exists
+    When I estimate the audience on domain "example.com"
+    Then the response should mention "recipients: 1"
+    When I send a test email of the campaign to "qa@example.com"
+    Then the response should mention "We are live"
+    And the response should mention "qa@example.com"
+
+  Scenario: Invalid campaign calls are refused
+    When I create the campaign "" with subject "We are live"
+    Then the call should fail with INVALID_ARGUMENT
+    When I get the engagement of campaign 999999
+    Then the call should fail with NOT_FOUND
+    Given the campaign "Launch" with subject "We are live" exists
+    When I rename the campaign to "Relaunch" expecting version 7
+    Then the call should fail with ABORTED
+    When I send a test email of the campaign to "qa@elsewhere.com"
+    Then the call should fail with PERMISSION_DENIED
+
+  Scenario: A sending domain is configured with a DKIM key to publish
+    When I create the sending domain "news.example.com" from "hello@news.example.com"
+    Then the response should mention "._domainkey.news.example.com"
+    And the response should mention "v=DKIM1; k=rsa; p="
+    When I create the sending domain "mail.example.com" from "hello@mail.example.com"
+    Then the call should fail with ALREADY_EXISTS
+    When I rotate the DKIM key
+    Then the response should mention "._domainkey.news.example.com"
+    When I switch to another tenant
+    And I get the sending domain
+    Then the call should fail with NOT_FOUND
+
+  Scenario: Invalid sending domains are refused
+    When I create the sending domain "news.example.com" from "hello@example.org"
+    Then the call should fail with INVALID_ARGUMENT
+    When I get the sending domain
+    Then the call should fail with NOT_FOUND
+    When I create the sending domain "news.example.com" from "hello@news.example.com"
+    And I delete the sending domain
+    Then the call should succeed
+    When I delete the sending domain
+    Then the call should fail with NOT_FOUND
@@ -0,0 +1,142 @@
+Feature: Newsletter API contract
+  NewsletterService v1 and v2 called over gRPC on the running server,
+  checking the status codes and payloads clients rely on
+
+  Scenario: A signup stays pending until its link is followed
+    When I subscribe "ada@example.com"
+    Then the call should succeed
+    When I get "ada@example.com"
+    Then the response should mention "status: Pending"
+    When I confirm the subscription of "ada@example.com"
+    Then the response should mention "ada@example.com"
+    When I get "ada@example.com"
+    Then the response should mention "status: Active"
+
+  Scenario: Signing up through v2 returns the pending subscription
+    When I subscribe "grace@example.com" through v2 to topic "weekly_digest"
+    Then the response should mention "grace@example.com"
+    And the response should mention "status: Pending"
+    And the response should mention "weekly_digest"
+
+  Scenario: Malformed input is refused
+    When I subscribe "not-an-email"
+    Then the call should fail with INVALID_ARGUMENT
+    When I subscribe "someone@mailinator.com"
+    Then the call should fail with INVALID_ARGUMENT
+    When I confirm with token "forged"
+    Then the call should fail with NOT_FOUND
+
+  Scenario: Unknown addresses read as inactive
+    When I get "nobody@example.com"
+    Then the response should mention "active: false"
+    And the response should mention "status: Unspecified"
+
+  Scenario: Unsubscribing records the reason
+    Given "ada@example.com" is subscribed
+    When I unsubscribe "ada@example.com"
+    Then the call should succeed
+    When I list the unsubscribe reasons
+    Then the response should mention "reason: TooFrequent"
+    When I get the subscriber stats
+    Then the response should mention "unsubscribed: 1"
+
+  Scenario: Imported subscribers can be listed and searched
+    When I import the CSV:
+      """
+      email
+      ada@example.com
+      grace@example.com
+      not-an-email
+      """
+    Then the response should mention "imported: 2"
+    And the response should mention "invalid: 1"
+    When I list the subscribers
+    Then the response should mention "ada@example.com"
+    And the response should mention "grace@example.com"
+    When I search the subscribers for "grace"
+    Then the response should mention "grace@example.com"
+
+  Scenario: Bulk status changes and deletes report what they touched
+    Given "ada@example.com" is subscribed
+    When I preview setting the status of "ada@example.com" to suppressed
+    Then the response should mention "changed: 1"
+    When I set the status of "ada@example.com" to suppressed
+    Then the call should succeed
+    When I get "ada@example.com"
+    Then the response should mention "status: Suppressed"
+    When I delete "ada@example.com"
+    Then the call should succeed
+    When I get "ada@example.com"
+    Then the response should mention "status: Unspecified"
+
+  Scenario: Subscribers are tagged and listed by tag
+    Given "ada@example.com" is subscribed
+    When I tag "ada@example.com" with "vip"
+    Then the response should mention "tagged: 1"
+    When I list the subscribers tagged "vip"
+    Then the response should mention "ada@example.com"
+    When I untag "ada@example.com" from "vip"
+    Then the response should mention "untagged: 1"
+
+  Scenario: A subscriber's data and consent history are exported
+    Given "ada@example.com" is subscribed
+    When I export the data of "ada@example.com"
+    Then the response should mention "ada@example.com"
+    When I list the consents of "ada@example.com"
+    Then the response should mention "action: Given"
+    And the response should mention "action: Confirmed"
+
+  Scenario: Topic preferences are read and changed
+    Given "ada@example.com" is subscribed
+    When I get the preferences of "ada@example.com"
+    Then the response should mention "weekly_digest"
+    When I opt "ada@example.com" out of topic "weekly_digest"
+    Then the call should succeed
+    When I opt "ada@example.com" out of topic "no_such_topic"
+    Then the call should fail with INVALID_ARGUMENT
+
+  Scenario: Custom attributes are defined, set and read
+    Given "ada@example.com" is subscribed
+    When I define the string attribute "company"
+    Then the response should mention "company"
+    When I list the attribute definitions
+    Then the response should mention "first_name"
+    When I set attribute "company" of "ada@example.com" to "Analytical Engines"
+    Then the response should mention "Analytical Engines"
+    When I get the attributes of "ada@example.com"
+    Then the response should mention "Analytical Engines"
+    When I set attribute "undefined_key" of "ada@example.com" to "x"
+    Then the call should fail with INVALID_ARGUMENT
+
+  Scenario: Locale and time zone are validated
+    Given "ada@example.com" is subscribed
+    When I set the locale of "ada@example.com" to "en-GB"
+    Then the response should mention "en-GB"
+    When I set the time zone of "ada@example.com" to "Europe/London"
+    Then the call should succeed
+    When I set the time zone of "ada@example.com" to "Mars/Olympus"
+    Then the call should fail with INVALID_ARGUMENT
+
+  Scenario: Growth is reported over a date range
+    When I get the growth from "2026-01-01" to "2026-01-31"
+    Then the call should succeed
+    When I get the growth from "2026-01-31" to "2026-01-01"
+    Then the call should fail with INVALID_ARGUMENT
+
+  Scenario: The disposable domain list can be reloaded
+    When I refresh the disposable domains
+    Then the response should mention "domains: 2"
+
+  Scenario: Large listings are compressed for clients that accept it
+    When I seed 50 subscribers with seed 7
+    And I list 50 subscribers accepting gzip
+    Then the response should be compressed with gzip
+    When I list 50 subscribers accepting zstd
+    Then the response should be compressed with zstd
+    # Below the threshold the message itself goes uncompressed; the header still names the codec
+    When I list 1 subscriber accepting gzip
+    Then the response should mention "newsletters: ["
+
+  Scenario: Import chunks above the receive limit are refused
+    When I import a single chunk of 17 MiB
+    Then the call should fail with OUT_OF_RANGE
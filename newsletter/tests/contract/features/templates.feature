@@ -0,0 +1,65 @@
+Feature: Template API contract
+  TemplateService called over gRPC on the running server
+
+  Scenario: A template is created, rendered, updated and deleted
+    When I create the template "welcome" with body "Hello {{first_name}}" requiring "first_name"
+    Then the response should mention "welcome"
+    When I get the template
+    Then the response should mention "Hello {{first_name}}"
+    When I render the template with first name "Ada"
+    Then the response should mention "Hello Ada"
+    When I update the template body to "Hi {{first_name}}"
+    Then the response should mention "version: 2"
+    When I list the templates
+    Then the response should mention "welcome"
+    When I delete the template
+    Then the call should succeed
+    When I get the template
+    Then the call should fail with NOT_FOUND
+
+  Scenario: Translations are stored per locale
+    Given the template "welcome" with body "Hello {{first_name}}" exists
+    When I put the "de" translation "Hallo {{first_name}}"
+    Then the response should mention "Hallo {{first_name}}"
+    When I upload the "fr" translation "Bonjour {{first_name}}"
+    Then the response should mention "Bonjour {{first_name}}"
+    When I list the translations
+    Then the response should mention "locale: \"de\""
+    And the response should mention "locale: \"fr\""
+    When I render the template in "de" with first name "Ada"
+    Then the response should mention "Hallo Ada"
+    When I delete the "de" translation
+    Then the call should succeed
+
+  Scenario: The plaintext part is derived unless the template has its own
+    Given the template "welcome" with body "<p>Hello {{first_name}}</p>" exists
+    When I render the template with first name "Ada"
+    Then the response should mention "text: \"Hello Ada"
+    When I set the template text body to "Hi {{first_name}} & co"
+    Then the response should mention "text_body: \"Hi {{first_name}} & co\""
+    When I render the template with first name "Ada"
+    Then the response should mention "text: \"Hi Ada & co\""
+    When I set the template text body to ""
+    And I render the template with first name "Ada"
+    Then the response should mention "text: \"Hello Ada"
+
+  Scenario: Invalid templates are refused
+    When I create the template "" with body "Hello" requiring ""
+    Then the call should fail with INVALID_ARGUMENT
+    Given the template "welcome" with body "Hello {{first_name}}" exists
+    When I update the template body to "Hi" expecting version 7
+    Then the call should fail with ABORTED
+
+  Scenario: Templates are checked before they are saved
+    When I validate a template with body "Hello {{attributes.first_name}}, {{attributes.nickname}}"
+    Then the response should mention "valid: false"
+    And the response should mention "variable: \"attributes.nickname\""
+    And the response should mention "Hello [first_name], [attributes.nickname]"
+    When I validate a template with body "Hello {{#if}}"
+    Then the response should mention "kind: Syntax"
+
+  Scenario: Assets need an asset store
+    When I upload the asset "logo.png" of type "image/png"
+    Then the call should fail with FAILED_PRECONDITION
+    When I get the asset "logo.png"
+    Then the call should fail with NOT_FOUND
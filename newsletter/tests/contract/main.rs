@@ -0,0 +1,816 @@
+//! Contract suite: every RPC of the public protos called through the
+//! generated tonic clients against the server binary and a real Postgres.
+//!
+//! Needs Docker for the Postgres container, so it is ignored by default:
+//!
+//!     cargo test --test contract -- --ignored
+//!
+//! Set `CONTRACT_DATABASE_URL` to a superuser URL to use a running Postgres instead.
+
+mod world;
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, Utc};
+use cucumber::gherkin::Step;
+use cucumber::{given, then, when, World};
+use prost_types::value::Kind;
+use prost_types::{Struct, Timestamp, Value};
+use tonic::codec::CompressionEncoding;
+
+use newsletter::infrastructure::rpc::admin::v1::proto as admin;
+use newsletter::infrastructure::rpc::campaign::v1::proto as campaign;
+use newsletter::infrastructure::rpc::newsletter::v1::proto as v1;
+use newsletter::infrastructure::rpc::newsletter::v2::proto as v2;
+use newsletter::infrastructure::rpc::template::v1::proto as template;
+use world::{parse_code, ContractWorld, TestDatabase, TestServer, ADMIN_KEY, UNREACHABLE_WEBHOOK};
+
+fn timestamp(at: chrono::DateTime<Utc>) -> Timestamp {
+    Timestamp {
+        seconds: at.timestamp(),
+        nanos: 0,
+    }
+}
+
+fn string_struct(key: &str, value: &str) -> Struct {
+    Struct {
+        fields: BTreeMap::from([(
+            key.to_string(),
+            Value {
+                kind: Some(Kind::StringValue(value.to_string())),
+            },
+        )]),
+    }
+}
+
+// Callers
+
+#[given("I call without an api key")]
+async fn without_api_key(world: &mut ContractWorld) {
+    world.api_key = None;
+}
+
+#[given(regex = r#"^I call with the api key "([^"]*)"$"#)]
+async fn with_api_key(world: &mut ContractWorld, key: String) {
+    world.api_key = Some(key);
+}
+
+#[given("I call with the admin key")]
+async fn with_admin_key(world: &mut ContractWorld) {
+    world.api_key = Some(ADMIN_KEY.to_string());
+}
+
+#[given(regex = r#"^I call for the tenant "([^"]*)"$"#)]
+async fn for_tenant(world: &mut ContractWorld, tenant: String) {
+    world.tenant = tenant;
+}
+
+#[when("I switch to another tenant")]
+async fn switch_tenant(world: &mut ContractWorld) {
+    world.tenant = format!("{}-other", world.tenant);
+}
+
+// NewsletterService v1
+
+#[given(regex = r#"^"([^"]+)" is subscribed$"#)]
+async fn is_subscribed(world: &mut ContractWorld, email: String) {
+    subscribe(world, email.clone()).await;
+    world.response();
+    confirm_subscription(world, email).await;
+    world.response();
+}
+
+#[when(regex = r#"^I subscribe "([^"]*)"$"#)]
+async fn subscribe(world: &mut ContractWorld, email: String) {
+    let request = world.request(v1::SubscribeRequest {
+        email,
+        consent_version: "2026-01".to_string(),
+        source: "contract".to_string(),
+        ..Default::default()
+    });
+    let result = world.newsletter().subscribe(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I confirm the subscription of "([^"]+)"$"#)]
+async fn confirm_subscription(world: &mut ContractWorld, email: String) {
+    let token = world.confirmation_token(&email).await;
+    confirm_with_token(world, token).await;
+}
+
+#[when(regex = r#"^I confirm with token "([^"]*)"$"#)]
+async fn confirm_with_token(world: &mut ContractWorld, token: String) {
+    let request = world.request(v1::ConfirmRequest { token });
+    let result = world.newsletter().confirm(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I get "([^"]+)"$"#)]
+async fn get(world: &mut ContractWorld, email: String) {
+    let request = world.request(v1::GetRequest {
+        email,
+        read_mask: None,
+    });
+    let result = world.newsletter().get(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I unsubscribe "([^"]+)"$"#)]
+async fn unsubscribe(world: &mut ContractWorld, email: String) {
+    let request = world.request(v1::UnSubscribeRequest {
+        email,
+        reason: v1::UnsubscribeReason::TooFrequent as i32,
+        comment: "weekly is plenty".to_string(),
+    });
+    let result = world.newsletter().un_subscribe(request).await;
+    world.record(result);
+}
+
+#[when("I list the unsubscribe reasons")]
+async fn list_unsubscribe_reasons(world: &mut ContractWorld) {
+    let request = world.request(v1::ListUnsubscribeReasonsRequest {
+        page_size: 10,
+        ..Default::default()
+    });
+    let result = world.newsletter().list_unsubscribe_reasons(request).await;
+    world.record(result);
+}
+
+#[when("I get the subscriber stats")]
+async fn get_stats(world: &mut ContractWorld) {
+    let request = world.request(v1::GetStatsRequest {});
+    let result = world.newsletter().get_stats(request).await;
+    world.record(result);
+}
+
+#[when("I import the CSV:")]
+async fn import_csv(world: &mut ContractWorld, step: &Step) {
+    let csv = step.docstring().expect("CSV docstring").trim_start().to_string();
+    let request = world.request(futures::stream::iter(vec![v1::ImportSubscribersRequest {
+        format: v1::ImportFormat::Csv as i32,
+        chunk: csv.into_bytes(),
+        dry_run: false,
+    }]));
+    let result = world.newsletter().import_subscribers(request).await;
+    world.record(result);
+}
+
+#[when(regex = r"^I import a single chunk of (\d+) MiB$")]
+async fn import_large_chunk(world: &mut ContractWorld, mebibytes: usize) {
+    let mut csv = b"email\n".to_vec();
+    csv.resize(mebibytes * 1024 * 1024, b' ');
+    let request = world.request(futures::stream::iter(vec![v1::ImportSubscribersRequest {
+        format: v1::ImportFormat::Csv as i32,
+        chunk: csv,
+        dry_run: false,
+    }]));
+    let result = world.newsletter().import_subscribers(request).await;
+    world.record(result);
+}
+
+#[when("I list the subscribers")]
+async fn list(world: &mut ContractWorld) {
+    let request = world.request(v1::ListRequest {
+        page_size: 10,
+        ..Default::default()
+    });
+    let result = world.newsletter().list(request).await;
+    world.record(result);
+}
+
+#[when(regex = r"^I list (\d+) subscribers? accepting (gzip|zstd)$")]
+async fn list_compressed(world: &mut ContractWorld, page_size: i32, codec: String) {
+    let encoding = match codec.as_str() {
+        "gzip" => CompressionEncoding::Gzip,
+        _ => CompressionEncoding::Zstd,
+    };
+    let request = world.request(v1::ListRequest {
+        page_size,
+        ..Default::default()
+    });
+    let result = world.newsletter().accept_compressed(encoding).list(request).await;
+    world.encoding = result
+        .as_ref()
+        .ok()
+        .and_then(|response| response.metadata().get("grpc-encoding"))
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    world.record(result);
+}
+
+#[when(regex = r#"^I search the subscribers for "([^"]+)"$"#)]
+async fn search(world: &mut ContractWorld, query: String) {
+    let request = world.request(v1::SearchRequest {
+        query,
+        page_size: 10,
+        page_token: String::new(),
+    });
+    let result = world.newsletter().search(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I (set|preview setting) the status of "([^"]+)" to (pending|active|unsubscribed|suppressed)$"#)]
+async fn update_status(world: &mut ContractWorld, mode: String, email: String, status: String) {
+    let status = match status.as_str() {
+        "pending" => v1::SubscriptionStatus::Pending,
+        "active" => v1::SubscriptionStatus::Active,
+        "unsubscribed" => v1::SubscriptionStatus::Unsubscribed,
+        _ => v1::SubscriptionStatus::Suppressed,
+    };
+    let request = world.request(v1::UpdateStatusRequest {
+        emails: vec![email],
+        status: status as i32,
+        dry_run: mode == "preview setting",
+        ..Default::default()
+    });
+    let result = world.newsletter().update_status(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I delete "([^"]+)"$"#)]
+async fn delete(world: &mut ContractWorld, email: String) {
+    let request = world.request(v1::DeleteRequest {
+        emails: vec![email],
+        delete_type: v1::DeleteType::HardDelete as i32,
+        dry_run: false,
+    });
+    let result = world.newsletter().delete(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I tag "([^"]+)" with "([^"]+)"$"#)]
+async fn tag_subscriber(world: &mut ContractWorld, email: String, tag: String) {
+    let request = world.request(v1::TagSubscribersRequest {
+        emails: vec![email],
+        tag,
+    });
+    let result = world.newsletter().tag_subscribers(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I untag "([^"]+)" from "([^"]+)"$"#)]
+async fn untag_subscriber(world: &mut ContractWorld, email: String, tag: String) {
+    let request = world.request(v1::UntagSubscribersRequest {
+        emails: vec![email],
+        tag,
+    });
+    let result = world.newsletter().untag_subscribers(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I list the subscribers tagged "([^"]+)"$"#)]
+async fn list_by_tag(world: &mut ContractWorld, tag: String) {
+    let request = world.request(v1::ListByTagRequest {
+        tag,
+        page_size: 10,
+        page_token: String::new(),
+    });
+    let result = world.newsletter().list_by_tag(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I export the data of "([^"]+)"$"#)]
+async fn export(world: &mut ContractWorld, email: String) {
+    let request = world.request(v1::ExportSubscriberDataRequest { email });
+    let result = world.newsletter().export_subscriber_data(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I list the consents of "([^"]+)"$"#)]
+async fn list_consents(world: &mut ContractWorld, email: String) {
+    let request = world.request(v1::ListConsentsRequest {
+        email,
+        page_size: 10,
+        page_token: String::new(),
+    });
+    let result = world.newsletter().list_consents(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I get the preferences of "([^"]+)"$"#)]
+async fn get_preferences(world: &mut ContractWorld, email: String) {
+    let request = world.request(v1::GetPreferencesRequest { email });
+    let result = world.newsletter().get_preferences(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I opt "([^"]+)" out of topic "([^"]+)"$"#)]
+async fn opt_out(world: &mut ContractWorld, email: String, topic: String) {
+    let request = world.request(v1::SetPreferencesRequest {
+        email,
+        preferences: vec![v1::TopicPreference {
+            topic,
+            subscribed: false,
+        }],
+    });
+    let result = world.newsletter().set_preferences(request).await;
+    world.record(result);
+}
+
+#[when("I list the attribute definitions")]
+async fn list_attribute_definitions(world: &mut ContractWorld) {
+    let request = world.request(v1::ListAttributeDefinitionsRequest {});
+    let result = world.newsletter().list_attribute_definitions(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I define the string attribute "([^"]+)"$"#)]
+async fn define_attribute(world: &mut ContractWorld, key: String) {
+    let request = world.request(v1::DefineAttributeRequest {
+        definition: Some(v1::AttributeDefinition {
+            key,
+            kind: v1::AttributeType::String as i32,
+            description: "Defined by the contract suite".to_string(),
+        }),
+    });
+    let result = world.newsletter().define_attribute(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I get the attributes of "([^"]+)"$"#)]
+async fn get_attributes(world: &mut ContractWorld, email: String) {
+    let request = world.request(v1::GetAttributesRequest { email });
+    let result = world.newsletter().get_attributes(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I set attribute "([^"]+)" of "([^"]+)" to "([^"]*)"$"#)]
+async fn set_attribute(world: &mut ContractWorld, key: String, email: String, value: String) {
+    let request = world.request(v1::SetAttributesRequest {
+        email,
+        attributes: Some(string_struct(&key, &value)),
+    });
+    let result = world.newsletter().set_attributes(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I set the locale of "([^"]+)" to "([^"]*)"$"#)]
+async fn set_locale(world: &mut ContractWorld, email: String, locale: String) {
+    let request = world.request(v1::SetLocaleRequest { email, locale });
+    let result = world.newsletter().set_locale(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I set the time zone of "([^"]+)" to "([^"]*)"$"#)]
+async fn set_timezone(world: &mut ContractWorld, email: String, timezone: String) {
+    let request = world.request(v1::SetTimezoneRequest { email, timezone });
+    let result = world.newsletter().set_timezone(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I get the growth from "([^"]+)" to "([^"]+)"$"#)]
+async fn get_growth(world: &mut ContractWorld, start_date: String, end_date: String) {
+    let request = world.request(v1::GetGrowthTimeSeriesRequest {
+        start_date,
+        end_date,
+        granularity: v1::Granularity::Day as i32,
+    });
+    let result = world.newsletter().get_growth_time_series(request).await;
+    world.record(result);
+}
+
+#[when("I refresh the disposable domains")]
+async fn refresh_disposable_domains(world: &mut ContractWorld) {
+    let request = world.request(v1::RefreshDisposableDomainsRequest {});
+    let result = world.newsletter().refresh_disposable_domains(request).await;
+    world.record(result);
+}
+
+// NewsletterService v2
+
+#[when(regex = r#"^I subscribe "([^"]+)" through v2 to topic "([^"]+)"$"#)]
+async fn subscribe_v2(world: &mut ContractWorld, email: String, topic: String) {
+    let request = world.request(v2::SubscribeRequest {
+        email,
+        source: "contract".to_string(),
+        topics: vec![topic],
+        consent: Some(v2::Consent {
+            policy_version: "2026-01".to_string(),
+        }),
+        ..Default::default()
+    });
+    let result = world.newsletter_v2().subscribe(request).await;
+    world.record(result);
+}
+
+// TemplateService
+
+#[given(regex = r#"^the template "([^"]+)" with body "([^"]*)" exists$"#)]
+async fn template_exists(world: &mut ContractWorld, name: String, body: String) {
+    create_template(world, name, body, String::new()).await;
+    world.response();
+}
+
+#[when(regex = r#"^I create the template "([^"]*)" with body "([^"]*)" requiring "([^"]*)"$"#)]
+async fn create_template(world: &mut ContractWorld, name: String, body: String, required: String) {
+    let request = world.request(template::CreateRequest {
+        name,
+        format: template::TemplateFormat::Handlebars as i32,
+        body,
+        required_variables: required.split(',').filter(|v| !v.is_empty()).map(String::from).collect(),
+        text_body: None,
+    });
+    let result = world.templates().create(request).await;
+    if let Ok(response) = &result {
+        world.template_id = response.get_ref().template.as_ref().map_or(0, |t| t.id);
+    }
+    world.record(result);
+}
+
+#[when("I get the template")]
+async fn get_template(world: &mut ContractWorld) {
+    let request = world.request(template::GetRequest { id: world.template_id });
+    let result = world.templates().get(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I render the template(?: in "([^"]+)")? with first name "([^"]+)"$"#)]
+async fn render_template(world: &mut ContractWorld, locale: String, first_name: String) {
+    let request = world.request(template::RenderRequest {
+        template_id: world.template_id,
+        context: Some(string_struct("first_name", &first_name)),
+        locale,
+    });
+    let result = world.templates().render(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I validate a template with body "([^"]*)"$"#)]
+async fn validate_template(world: &mut ContractWorld, body: String) {
+    let request = world.request(template::ValidateTemplateRequest {
+        format: template::TemplateFormat::Handlebars as i32,
+        body,
+        ..Default::default()
+    });
+    let result = world.templates().validate_template(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I update the template body to "([^"]*)"(?: expecting version (\d+))?$"#)]
+async fn update_template(world: &mut ContractWorld, body: String, version: String) {
+    let request = world.request(template::UpdateRequest {
+        id: world.template_id,
+        body: Some(body),
+        expected_version: version.parse().ok(),
+        ..Default::default()
+    });
+    let result = world.templates().update(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I set the template text body to "([^"]*)"$"#)]
+async fn set_template_text_body(world: &mut ContractWorld, text_body: String) {
+    let request = world.request(template::UpdateRequest {
+        id: world.template_id,
+        text_body: Some(text_body),
+        ..Default::default()
+    });
+    let result = world.templates().update(request).await;
+    world.record(result);
+}
+
+#[when("I list the templates")]
+async fn list_templates(world: &mut ContractWorld) {
+    let request = world.request(template::ListRequest {
+        page_size: 10,
+        page_token: String::new(),
+    });
+    let result = world.templates().list(request).await;
+    world.record(result);
+}
+
+#[when("I delete the template")]
+async fn delete_template(world: &mut ContractWorld) {
+    let request = world.request(template::DeleteRequest { id: world.template_id });
+    let result = world.templates().delete(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I put the "([^"]+)" translation "([^"]*)"$"#)]
+async fn put_translation(world: &mut ContractWorld, locale: String, body: String) {
+    let request = world.request(template::PutTranslationRequest {
+        template_id: world.template_id,
+        locale,
+        body,
+    });
+    let result = world.templates().put_translation(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I upload the "([^"]+)" translation "([^"]*)"$"#)]
+async fn upload_translation(world: &mut ContractWorld, locale: String, body: String) {
+    let request = world.request(template::UploadTranslationsRequest {
+        template_id: world.template_id,
+        translations: HashMap::from([(locale, body)]),
+        replace: false,
+    });
+    let result = world.templates().upload_translations(request).await;
+    world.record(result);
+}
+
+#[when("I list the translations")]
+async fn list_translations(world: &mut ContractWorld) {
+    let request = world.request(template::ListTranslationsRequest {
+        template_id: world.template_id,
+    });
+    let result = world.templates().list_translations(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I delete the "([^"]+)" translation$"#)]
+async fn delete_translation(world: &mut ContractWorld, locale: String) {
+    let request = world.request(template::DeleteTranslationRequest {
+        template_id: world.template_id,
+        locale,
+    });
+    let result = world.templates().delete_translation(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I upload the asset "([^"]+)" of type "([^"]+)"$"#)]
+async fn upload_asset(world: &mut ContractWorld, name: String, content_type: String) {
+    let request = world.request(futures::stream::iter(vec![
+        template::UploadAssetRequest {
+            part: Some(template::upload_asset_request::Part::Metadata(template::AssetMetadata { name, content_type })),
+        },
+        template::UploadAssetRequest {
+            part: Some(template::upload_asset_request::Part::Chunk(b"\x89PNG\r\n\x1a\n".to_vec())),
+        },
+    ]));
+    let result = world.templates().upload_asset(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I get the asset "([^"]+)"$"#)]
+async fn get_asset(world: &mut ContractWorld, name: String) {
+    let request = world.request(template::GetAssetRequest { name });
+    let result = world.templates().get_asset(request).await;
+    world.record(result);
+}
+
+// CampaignService
+
+#[given(regex = r#"^the campaign "([^"]+)" with subject "([^"]*)" exists$"#)]
+async fn campaign_exists(world: &mut ContractWorld, name: String, subject: String) {
+    create_campaign(world, name, subject).await;
+    world.response();
+}
+
+#[when(regex = r#"^I create the campaign "([^"]*)" with subject "([^"]*)"$"#)]
+async fn create_campaign(world: &mut ContractWorld, name: String, subject: String) {
+    let request = world.request(campaign::CreateRequest {
+        name,
+        subject,
+        template_id: world.template_id,
+        local_send_hours: None,
+    });
+    let result = world.campaigns().create(request).await;
+    if let Ok(response) = &result {
+        world.campaign_id = response.get_ref().campaign.as_ref().map_or(0, |c| c.id);
+    }
+    world.record(result);
+}
+
+#[when(regex = r#"^I rename the campaign to "([^"]+)"(?: expecting version (\d+))?$"#)]
+async fn rename_campaign(world: &mut ContractWorld, name: String, version: String) {
+    let request = world.request(campaign::UpdateRequest {
+        id: world.campaign_id,
+        name: Some(name),
+        expected_version: version.parse().ok(),
+        ..Default::default()
+    });
+    let result = world.campaigns().update(request).await;
+    world.record(result);
+}
+
+#[when("I schedule the campaign for tomorrow")]
+async fn schedule_campaign(world: &mut ContractWorld) {
+    let start = Utc::now() + Duration::days(1);
+    let request = world.request(campaign::ScheduleRequest {
+        id: world.campaign_id,
+        send_window: Some(campaign::SendWindow {
+            start: Some(timestamp(start)),
+            end: Some(timestamp(start + Duration::hours(2))),
+        }),
+    });
+    let result = world.campaigns().schedule(request).await;
+    world.record(result);
+}
+
+#[when("I list the campaigns")]
+async fn list_campaigns(world: &mut ContractWorld) {
+    let request = world.request(campaign::ListRequest {
+        page_size: 10,
+        page_token: String::new(),
+    });
+    let result = world.campaigns().list(request).await;
+    world.record(result);
+}
+
+#[when("I cancel the campaign")]
+async fn cancel_campaign(world: &mut ContractWorld) {
+    let request = world.request(campaign::CancelRequest { id: world.campaign_id });
+    let result = world.campaigns().cancel(request).await;
+    world.record(result);
+}
+
+#[when(regex = r"^I get the engagement of (?:the campaign|campaign (\d+))$")]
+async fn get_engagement(world: &mut ContractWorld, id: String) {
+    let id = id.parse().unwrap_or(world.campaign_id);
+    let request = world.request(campaign::GetEngagementRequest { id });
+    let result = world.campaigns().get_engagement(request).await;
+    world.record(result);
+}
+
+#[when("I list the link engagement of the campaign")]
+async fn list_link_engagement(world: &mut ContractWorld) {
+    let request = world.request(campaign::ListLinkEngagementRequest { id: world.campaign_id });
+    let result = world.campaigns().list_link_engagement(request).await;
+    world.record(result);
+}
+
+#[when("I get the domain stats")]
+async fn get_domain_stats(world: &mut ContractWorld) {
+    let request = world.request(campaign::GetDomainStatsRequest {
+        page_size: 10,
+        page_token: String::new(),
+    });
+    let result = world.campaigns().get_domain_stats(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I estimate the audience on domain "([^"]+)"$"#)]
+async fn estimate_audience(world: &mut ContractWorld, domain: String) {
+    let request = world.request(campaign::EstimateAudienceRequest {
+        domain,
+        ..Default::default()
+    });
+    let result = world.campaigns().estimate_audience(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I send a test email of the campaign to "([^"]+)"$"#)]
+async fn send_test_email(world: &mut ContractWorld, recipient: String) {
+    let request = world.request(campaign::SendTestEmailRequest {
+        id: world.campaign_id,
+        recipients: vec![recipient],
+        attributes: Some(string_struct("first_name", "Ada")),
+        ..Default::default()
+    });
+    let result = world.campaigns().send_test_email(request).await;
+    world.record(result);
+}
+
+#[when(regex = r#"^I create the sending domain "([^"]*)" from "([^"]*)"$"#)]
+async fn create_sending_domain(world: &mut ContractWorld, domain: String, from_address: String) {
+    let request = world.request(campaign::CreateSendingDomainRequest { domain, from_address });
+    let result = world.campaigns().create_sending_domain(request).await;
+    world.record(result);
+}
+
+#[when("I get the sending domain")]
+async fn get_sending_domain(world: &mut ContractWorld) {
+    let request = world.request(campaign::GetSendingDomainRequest {});
+    let result = world.campaigns().get_sending_domain(request).await;
+    world.record(result);
+}
+
+#[when("I rotate the DKIM key")]
+async fn rotate_dkim_key(world: &mut ContractWorld) {
+    let request = world.request(campaign::RotateDkimKeyRequest {});
+    let result = world.campaigns().rotate_dkim_key(request).await;
+    world.record(result);
+}
+
+#[when("I delete the sending domain")]
+async fn delete_sending_domain(world: &mut ContractWorld) {
+    let request = world.request(campaign::DeleteSendingDomainRequest {});
+    let result = world.campaigns().delete_sending_domain(request).await;
+    world.record(result);
+}
+
+// AdminService
+
+#[when(regex = r#"^I set the log level to "([^"]*)"$"#)]
+async fn set_log_level(world: &mut ContractWorld, filter: String) {
+    let request = world.request(admin::SetLogLevelRequest { filter });
+    let result = world.admin().set_log_level(request).await;
+    world.record(result);
+}
+
+#[when("I get the build info")]
+async fn get_build_info(world: &mut ContractWorld) {
+    let request = world.request(admin::GetBuildInfoRequest {});
+    let result = world.admin().get_build_info(request).await;
+    world.record(result);
+}
+
+#[when("I get the version")]
+async fn get_version(world: &mut ContractWorld) {
+    let request = world.request(admin::GetVersionRequest {});
+    let result = world.admin().get_version(request).await;
+    world.record(result);
+}
+
+#[when("I get the migration status")]
+async fn get_migration_status(world: &mut ContractWorld) {
+    let request = world.request(admin::GetMigrationStatusRequest {});
+    let result = world.admin().get_migration_status(request).await;
+    world.record(result);
+}
+
+#[when("I flush the cache")]
+async fn flush_cache(world: &mut ContractWorld) {
+    let request = world.request(admin::FlushCacheRequest {});
+    let result = world.admin().flush_cache(request).await;
+    world.record(result);
+}
+
+#[when("I replay the outbox since an hour ago")]
+async fn replay_outbox(world: &mut ContractWorld) {
+    let request = world.request(admin::ReplayOutboxRequest {
+        since: Some(timestamp(Utc::now() - Duration::hours(1))),
+    });
+    let result = world.admin().replay_outbox(request).await;
+    world.record(result);
+}
+
+#[when("I purge expired pending subscriptions")]
+async fn purge_expired_pending(world: &mut ContractWorld) {
+    let request = world.request(admin::PurgeExpiredPendingRequest {});
+    let result = world.admin().purge_expired_pending(request).await;
+    world.record(result);
+}
+
+#[when("I refresh the blocklist")]
+async fn refresh_blocklist(world: &mut ContractWorld) {
+    let request = world.request(admin::RefreshBlocklistRequest {});
+    let result = world.admin().refresh_blocklist(request).await;
+    world.record(result);
+}
+
+#[when(regex = r"^I seed (\d+) subscribers with seed (\d+)$")]
+async fn seed_subscribers(world: &mut ContractWorld, count: i32, seed: u64) {
+    let request = world.request(admin::SeedSubscribersRequest {
+        count,
+        seed,
+        ..Default::default()
+    });
+    let result = world.admin().seed_subscribers(request).await;
+    world.record(result);
+}
+
+// Outcomes
+
+#[then("the call should succeed")]
+async fn call_should_succeed(world: &mut ContractWorld) {
+    world.response();
+}
+
+#[then(regex = r"^the call should fail with ([A-Z_]+)$")]
+async fn call_should_fail_with(world: &mut ContractWorld, code: String) {
+    let status = world.failure();
+    assert_eq!(status.code(), parse_code(&code), "Unexpected status: {}", status.message());
+}
+
+#[when(regex = r#"^I wait for the delivery status to mention "(.*)"$"#)]
+async fn wait_for_delivery_status(world: &mut ContractWorld, text: String) {
+    let text = text.replace(r#"\""#, "\"").replace("{webhook}", UNREACHABLE_WEBHOOK);
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(15);
+    loop {
+        let request = world.request(admin::GetDeliveryStatusRequest {});
+        let result = world.admin().get_delivery_status(request).await;
+        world.record(result);
+        if world.response().contains(&text) {
+            return;
+        }
+        assert!(tokio::time::Instant::now() < deadline, "Expected {text:?} in {}", world.response());
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+    }
+}
+
+#[then(regex = r#"^the response should mention "(.*)"$"#)]
+async fn response_should_mention(world: &mut ContractWorld, text: String) {
+    let text = text.replace(r#"\""#, "\"");
+    let response = world.response();
+    assert!(response.contains(&text), "Expected {text:?} in {response}");
+}
+
+#[then(regex = r"^the response should be compressed with (gzip|zstd)$")]
+async fn response_should_be_compressed(world: &mut ContractWorld, codec: String) {
+    world.response();
+    assert_eq!(world.encoding.as_deref(), Some(codec.as_str()));
+}
+
+#[tokio::test]
+#[ignore = "needs Docker for Postgres; run with --ignored"]
+async fn run_contract_tests() {
+    let database = TestDatabase::start().await.expect("failed to start Postgres");
+    let _server = TestServer::start(&database).await.expect("failed to start the server");
+
+    ContractWorld::cucumber()
+        .with_default_cli()
+        .fail_on_skipped()
+        .run_and_exit("tests/contract/features")
+        .await;
+}
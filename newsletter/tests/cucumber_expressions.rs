@@ -1,45 +1,8 @@
-use cucumber::{given, then, when, World};
-use std::collections::HashMap;
-
-// Simple in-memory newsletter store for testing
-#[derive(Debug, Clone)]
-pub struct Newsletter {
-    pub email: String,
-    pub active: bool,
-}
-
-#[derive(Debug, World)]
-#[world(init = Self::new)]
-pub struct NewsletterWorld {
-    pub newsletters: HashMap<String, Newsletter>,
-    pub last_response: Option<String>,
-    pub last_list: Vec<Newsletter>,
-    pub last_get: Option<Newsletter>,
-}
-
-impl Default for NewsletterWorld {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl NewsletterWorld {
-    pub fn new() -> Self {
-        Self {
-            newsletters: HashMap::new(),
-            last_response: None,
-            last_list: Vec::new(),
-            last_get: None,
-        }
-    }
+mod common;
 
-    pub fn cleanup(&mut self) {
-        self.newsletters.clear();
-        self.last_response = None;
-        self.last_list.clear();
-        self.last_get = None;
-    }
-}
+use common::NewsletterWorld;
+use cucumber::{given, then, when, World};
+use newsletter::domain::newsletter::EmailAddress;
 
 // Background steps
 #[given("the newsletter service is running")]
@@ -55,12 +18,7 @@ async fn database_is_clean(world: &mut NewsletterWorld) {
 // Create operations using cucumber expressions
 #[when(expr = "I subscribe email {string}")]
 async fn subscribe_email(world: &mut NewsletterWorld, email: String) {
-    let newsletter = Newsletter {
-        email: email.clone(),
-        active: true,
-    };
-    world.newsletters.insert(email, newsletter);
-    world.last_response = Some("success".to_string());
+    world.subscribe(&email).await;
 }
 
 #[given(expr = "I have subscribed email {string}")]
@@ -77,19 +35,18 @@ async fn subscribe_email_again(world: &mut NewsletterWorld, email: String) {
 // Read operations using cucumber expressions
 #[when(expr = "I get the subscription for {string}")]
 async fn get_subscription(world: &mut NewsletterWorld, email: String) {
-    world.last_get = world.newsletters.get(&email).cloned();
+    world.get(&email).await;
 }
 
 #[when("I list all subscriptions")]
 async fn list_all_subscriptions(world: &mut NewsletterWorld) {
-    world.last_list = world.newsletters.values().cloned().collect();
+    world.list_all().await;
 }
 
 // Delete operations using cucumber expressions
 #[when(expr = "I unsubscribe email {string}")]
 async fn unsubscribe_email(world: &mut NewsletterWorld, email: String) {
-    world.newsletters.remove(&email);
-    world.last_response = Some("success".to_string());
+    world.unsubscribe(&email).await;
 }
 
 // Assertion steps using cucumber expressions
@@ -115,9 +72,7 @@ async fn subscriptions_deleted_successfully(world: &mut NewsletterWorld) {
 
 #[then(expr = "the email {string} should be active")]
 async fn email_should_be_active(world: &mut NewsletterWorld, email: String) {
-    let newsletter = world.newsletters.get(&email);
-    assert!(newsletter.is_some(), "Email {} should exist", email);
-    assert!(newsletter.unwrap().active, "Email {} should be active", email);
+    assert!(world.is_active(&email).await, "Email {} should be active", email);
 }
 
 #[then(expr = "{string} should be active")]
@@ -127,33 +82,36 @@ async fn email_active(world: &mut NewsletterWorld, email: String) {
 
 #[then(expr = "{string} should not be active")]
 async fn email_should_not_be_active(world: &mut NewsletterWorld, email: String) {
-    let newsletter = world.newsletters.get(&email);
-    // Email should either not exist or be inactive
-    assert!(newsletter.is_none(), "Email {} should not exist (should be deleted)", email);
+    assert!(!world.is_active(&email).await, "Email {} should not be active", email);
 }
 
 #[then(expr = "the email {string} should not exist")]
 async fn email_should_not_exist(world: &mut NewsletterWorld, email: String) {
-    let newsletter = world.newsletters.get(&email);
-    assert!(newsletter.is_none(), "Email {} should not exist", email);
+    assert!(!world.exists(&email).await, "Email {} should not exist", email);
+}
+
+#[then(expr = "the subscription of {string} should be {word}")]
+async fn subscription_status(world: &mut NewsletterWorld, email: String, status: String) {
+    let actual = world.status(&email).await;
+    assert_eq!(actual.map(|s| s.as_str()), Some(status.as_str()), "Unexpected status of {email}");
 }
 
 #[then(expr = "the email {string} should still exist")]
 async fn email_should_still_exist(world: &mut NewsletterWorld, email: String) {
-    let newsletter = world.newsletters.get(&email);
-    assert!(newsletter.is_some(), "Email {} should still exist", email);
+    assert!(world.exists(&email).await, "Email {} should still exist", email);
 }
 
 #[then(expr = "there should be only one subscription for {string}")]
 async fn only_one_subscription(world: &mut NewsletterWorld, email: String) {
-    let count = world.newsletters.values().filter(|n| n.email == email).count();
+    world.list_all().await;
+    let count = world.last_list.iter().filter(|n| n.email == email).count();
     assert_eq!(count, 1, "Should have exactly one subscription for {}", email);
 }
 
 #[then("the subscription should exist")]
 async fn subscription_should_exist(world: &mut NewsletterWorld) {
     assert!(world.last_get.is_some(), "Should have found a subscription");
-    assert!(world.last_get.as_ref().unwrap().active, "Subscription should be active");
+    assert!(world.last_get.as_ref().unwrap().is_active(), "Subscription should be active");
 }
 
 #[then("the subscription should not exist")]
@@ -172,20 +130,20 @@ async fn email_should_be(world: &mut NewsletterWorld, expected_email: String) {
 async fn status_should_be_active(world: &mut NewsletterWorld) {
     assert!(world.last_get.is_some(), "Should have a get response");
     let newsletter = world.last_get.as_ref().unwrap();
-    assert!(newsletter.active, "Status should be active");
+    assert!(newsletter.is_active(), "Status should be active");
 }
 
 #[then("the status should be inactive")]
 async fn status_should_be_inactive(world: &mut NewsletterWorld) {
-    // In our simple model, inactive means not found
-    assert!(world.last_get.is_none(), "Status should be inactive (not found)");
+    let active = world.last_get.as_ref().is_some_and(|n| n.is_active());
+    assert!(!active, "Status should be inactive");
 }
 
 // Using {int} parameter type for numeric values
 #[then(expr = "I should get {int} subscriptions")]
 async fn should_get_subscriptions_count(world: &mut NewsletterWorld, count: i32) {
     // First, populate the list with current subscriptions
-    world.last_list = world.newsletters.values().cloned().collect();
+    world.list_all().await;
     assert_eq!(world.last_list.len(), count as usize, "Should have {} subscriptions", count);
 }
 
@@ -206,51 +164,48 @@ async fn subscribe_multiple_emails_with_domain(world: &mut NewsletterWorld, coun
 
 #[then(expr = "there should be {int} active subscriptions")]
 async fn should_have_active_subscriptions(world: &mut NewsletterWorld, count: i32) {
-    let active_count = world.newsletters.values().filter(|n| n.active).count();
+    world.list_all().await;
+    let active_count = world.last_list.iter().filter(|n| n.is_active()).count();
     assert_eq!(active_count, count as usize, "Should have {} active subscriptions", count);
 }
 
 // Custom step for bulk operations
 #[when(expr = "I perform bulk unsubscribe for domain {string}")]
 async fn bulk_unsubscribe_by_domain(world: &mut NewsletterWorld, domain: String) {
+    world.list_all().await;
     let emails_to_remove: Vec<String> = world
-        .newsletters
-        .keys()
-        .filter(|email| email.contains(&format!("@{}", domain)))
-        .cloned()
+        .last_list
+        .iter()
+        .filter(|n| n.email.ends_with(&format!("@{}", domain)))
+        .map(|n| n.email.clone())
         .collect();
-    
-    for email in emails_to_remove {
-        world.newsletters.remove(&email);
-    }
-    
-    world.last_response = Some("success".to_string());
+
+    world.delete_all(emails_to_remove).await;
 }
 
 #[then(expr = "no emails with domain {string} should exist")]
 async fn no_emails_with_domain_should_exist(world: &mut NewsletterWorld, domain: String) {
+    world.list_all().await;
     let domain_emails = world
-        .newsletters
-        .values()
-        .filter(|n| n.email.contains(&format!("@{}", domain)))
+        .last_list
+        .iter()
+        .filter(|n| n.email.ends_with(&format!("@{}", domain)))
         .count();
     
     assert_eq!(domain_emails, 0, "No emails with domain {} should exist", domain);
 }
 
-// Email validation step using custom parameter type concept
+// Email validation step backed by the EmailAddress value object
 #[then(expr = "the email {string} should be valid")]
 async fn email_should_be_valid(_world: &mut NewsletterWorld, email: String) {
-    // Simple email validation
-    assert!(email.contains('@'), "Email {} should contain @ symbol", email);
-    assert!(email.contains('.'), "Email {} should contain a dot", email);
-    let parts: Vec<&str> = email.split('@').collect();
-    assert_eq!(parts.len(), 2, "Email {} should have exactly one @ symbol", email);
-    assert!(!parts[0].is_empty(), "Email {} should have a local part", email);
-    assert!(!parts[1].is_empty(), "Email {} should have a domain part", email);
+    let parsed = EmailAddress::parse(&email);
+    assert!(parsed.is_ok(), "Email {} should be valid: {:?}", email, parsed.err());
 }
 
 #[tokio::test]
 async fn run_cucumber_expressions_tests() {
+    #[cfg(feature = "postgres-tests")]
+    let _postgres = common::postgres::start().await;
+
     NewsletterWorld::run("tests/features").await;
 }
\ No newline at end of file
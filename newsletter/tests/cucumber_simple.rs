@@ -1,44 +1,33 @@
-use cucumber::{given, then, when, World};
-use std::collections::HashMap;
-
-// Simple in-memory newsletter store for testing
-#[derive(Debug, Clone)]
-pub struct Newsletter {
-    pub email: String,
-    pub active: bool,
-}
-
-#[derive(Debug, World)]
-#[world(init = Self::new)]
-pub struct NewsletterWorld {
-    pub newsletters: HashMap<String, Newsletter>,
-    pub last_response: Option<String>,
-    pub last_list: Vec<Newsletter>,
-    pub last_get: Option<Newsletter>,
-}
-
-impl Default for NewsletterWorld {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+mod common;
 
-impl NewsletterWorld {
-    pub fn new() -> Self {
-        Self {
-            newsletters: HashMap::new(),
-            last_response: None,
-            last_list: Vec::new(),
-            last_get: None,
-        }
-    }
+use common::campaign::EMAIL_FROM_DOMAIN;
+use common::{NewsletterWorld, ProviderBehavior, ScriptedProvider};
+use cucumber::gherkin::Step;
+use cucumber::{given, then, when, World};
+use diesel::result::{ConnectionError, DatabaseErrorKind};
+use diesel_async::pooled_connection::bb8::RunError;
+use diesel_async::pooled_connection::PoolError;
+use prost::Message;
+use newsletter::domain::analytics::{partition, DailyRows, Dataset};
+use newsletter::domain::campaign::CampaignStatus;
+use newsletter::domain::jobs::JobKind;
+use newsletter::domain::newsletter::error::NewsletterError;
+use newsletter::domain::newsletter::consent::ConsentContext;
+use newsletter::domain::newsletter::growth::Granularity;
+use newsletter::domain::newsletter::lifecycle::SubscriptionStatus;
+use newsletter::domain::newsletter::mask::NewsletterMask;
+use newsletter::domain::newsletter::retention::RetentionAction;
+use newsletter::domain::newsletter::query::{NewsletterFilter, NewsletterOrder, NewsletterQuery};
+use newsletter::domain::tenant::{TenantId, TenantScope};
+use newsletter::infrastructure::email::failover::{Provider, SelectionStrategy};
+use newsletter::infrastructure::rpc::{self, service_config};
+use newsletter::infrastructure::tenant;
+use newsletter::repository::breaker::{BreakerPolicy, BreakerState, CircuitBreaker};
+use newsletter::service::newsletter::import::ImportFormat;
+use newsletter::service::newsletter::seed::{ActivityMix, SeedPlan};
 
-    pub fn cleanup(&mut self) {
-        self.newsletters.clear();
-        self.last_response = None;
-        self.last_list.clear();
-        self.last_get = None;
-    }
+fn tenant_scope(name: &str) -> TenantScope {
+    TenantScope::One(TenantId::parse(name).expect("valid tenant in scenario"))
 }
 
 // Background steps
@@ -52,55 +41,585 @@ async fn database_is_clean(world: &mut NewsletterWorld) {
     world.cleanup();
 }
 
+#[given("Gmail alias folding is enabled")]
+async fn gmail_alias_folding_enabled(world: &mut NewsletterWorld) {
+    world.fold_gmail_aliases();
+}
+
+#[given(regex = r#"^subscriber addresses are pseudonymized with key "([^"]+)"$"#)]
+async fn addresses_pseudonymized(world: &mut NewsletterWorld, key: String) {
+    world.pseudonymize(&key);
+}
+
+#[given("reads are cached")]
+async fn reads_are_cached(world: &mut NewsletterWorld) {
+    world.cache_reads();
+}
+
+#[given("stats and listings are served from the read model")]
+async fn served_from_read_model(world: &mut NewsletterWorld) {
+    world.serve_from_read_model();
+}
+
+#[given("returning subscribers skip confirmation")]
+async fn returning_subscribers_skip_confirmation(world: &mut NewsletterWorld) {
+    world.skip_confirmation_on_resubscribe();
+}
+
+#[given(regex = r"^confirmation links expire at once(?: and lapsed signups are kept for (\d+) hours)?$")]
+async fn confirmation_links_expire_at_once(world: &mut NewsletterWorld, hours: String) {
+    let hours: i64 = if hours.is_empty() { 0 } else { hours.parse().unwrap() };
+    world.expire_links_at_once(chrono::Duration::hours(hours));
+}
+
 // Create operations
-#[when(regex = r"^I subscribe email (.+)$")]
+#[when(regex = r#"^I subscribe email "?([^"\s]+)"?$"#)]
 async fn subscribe_email(world: &mut NewsletterWorld, email: String) {
-    // Remove quotes if present
-    let clean_email = email.trim_matches('"').to_string();
-    let newsletter = Newsletter {
-        email: clean_email.clone(),
-        active: true,
-    };
-    world.newsletters.insert(clean_email, newsletter);
-    world.last_response = Some("success".to_string());
+    world.subscribe(&email).await;
 }
 
-#[given(regex = r"^I have subscribed email (.+)$")]
+#[given(regex = r#"^I have subscribed email "?([^"\s]+)"?$"#)]
 async fn have_subscribed_email(world: &mut NewsletterWorld, email: String) {
     subscribe_email(world, email).await;
 }
 
-#[when(regex = r"^I subscribe email ([a-zA-Z0-9@.]+) again$")]
+#[when(regex = r#"^I subscribe email "?([^"\s]+)"? again$"#)]
 async fn subscribe_email_again(world: &mut NewsletterWorld, email: String) {
     // This should not create a duplicate - just update existing
     subscribe_email(world, email).await;
 }
 
-// Table-based steps removed for simplicity
+#[when(regex = r#"^I subscribe email "([^"]+)" under consent version "([^"]+)" from "([^"]+)"$"#)]
+async fn subscribe_with_consent(world: &mut NewsletterWorld, email: String, version: String, source: String) {
+    let consent = ConsentContext::new(Some(version), Some(source), Some("203.0.113.7".to_string()));
+    world.subscribe_with_consent(&email, consent).await;
+}
+
+// Tenants
+#[when(regex = r#"^tenant "([^"]+)" subscribes email "([^"]+)"$"#)]
+async fn tenant_subscribes(world: &mut NewsletterWorld, name: String, email: String) {
+    tenant::scope(tenant_scope(&name), world.subscribe(&email)).await;
+}
+
+#[when(regex = r#"^tenant "([^"]+)" requests a subscription for "([^"]+)"$"#)]
+async fn tenant_requests_subscription(world: &mut NewsletterWorld, name: String, email: String) {
+    tenant::scope(tenant_scope(&name), world.request_subscription(&email)).await;
+}
+
+#[when(regex = r#"^tenant "([^"]+)" unsubscribes email "([^"]+)"$"#)]
+async fn tenant_unsubscribes(world: &mut NewsletterWorld, name: String, email: String) {
+    tenant::scope(tenant_scope(&name), world.unsubscribe(&email)).await;
+}
+
+#[when(regex = r#"^I read the stats of tenant "([^"]+)"$"#)]
+async fn read_tenant_stats(world: &mut NewsletterWorld, name: String) {
+    tenant::scope(tenant_scope(&name), world.stats()).await;
+}
+
+#[when(regex = r#"^"([^"]+)" is deactivated behind the service's back$"#)]
+async fn deactivated_in_repository(world: &mut NewsletterWorld, email: String) {
+    world.deactivate_in_repository(&email).await;
+}
+
+#[when(regex = r#"^I set the status of "([^"]+)" to (pending|active|unsubscribed|suppressed)$"#)]
+async fn set_status(world: &mut NewsletterWorld, email: String, status: String) {
+    let status = SubscriptionStatus::parse(&status).expect("status named in scenario");
+    world.set_status(vec![email], status).await;
+}
+
+#[when(regex = r#"^I preview setting the status of "([^"]+)" to (pending|active|unsubscribed|suppressed)$"#)]
+async fn preview_status(world: &mut NewsletterWorld, emails: String, status: String) {
+    let status = SubscriptionStatus::parse(&status).expect("status named in scenario");
+    world.preview_status(emails.split(", ").map(str::to_string).collect(), status).await;
+}
+
+#[when(regex = r#"^I preview deleting "([^"]+)"$"#)]
+async fn preview_delete(world: &mut NewsletterWorld, emails: String) {
+    world.preview_delete(emails.split(", ").map(str::to_string).collect()).await;
+}
+
+#[given(regex = r#"^the disposable domain list holds "([^"]*)"$"#)]
+async fn disposable_domain_list(world: &mut NewsletterWorld, domains: String) {
+    world.write_blocklist(&domains.split(", ").collect::<Vec<_>>());
+}
+
+#[when(regex = r#"^the disposable domain list is changed to "([^"]*)"$"#)]
+async fn disposable_domain_list_changed(world: &mut NewsletterWorld, domains: String) {
+    disposable_domain_list(world, domains).await;
+}
+
+#[given("subscribe checks disposable domains and MX records")]
+async fn subscribe_verifies_addresses(world: &mut NewsletterWorld) {
+    world.verify_addresses().await;
+}
+
+#[given(regex = r#"^"([^"]+)" does not accept mail$"#)]
+async fn domain_without_mail(world: &mut NewsletterWorld, domain: String) {
+    world.mail_domains.refuse(&domain);
+}
+
+#[given("MX lookups time out")]
+async fn mx_lookups_time_out(world: &mut NewsletterWorld) {
+    world.mail_domains.time_out();
+}
+
+#[when("I refresh the disposable domain list")]
+async fn refresh_disposable_domains(world: &mut NewsletterWorld) {
+    world.refresh_disposable_domains().await;
+}
+
+#[given(regex = r"^repository retries allow (\d+) attempts? and a budget of (\d+)$")]
+async fn retry_policy(world: &mut NewsletterWorld, attempts: u32, budget: u32) {
+    world.retry_policy.max_attempts = attempts;
+    world.retry_policy.budget = budget;
+}
+
+#[when(regex = r"^a read fails (\d+) times? with (a serialization failure|a dropped connection|a unique violation)$")]
+async fn flaky_read(world: &mut NewsletterWorld, failures: u32, error: String) {
+    world.run_flaky(failures, database_error(&error)).await;
+}
+
+#[given(regex = r"^the circuit breaker opens when (\d+)% of the last (\d+) reads fail, for (\d+) ms$")]
+async fn breaker_policy(world: &mut NewsletterWorld, failure_percent: u32, window: u32, open_ms: u64) {
+    world.breaker = CircuitBreaker::new(BreakerPolicy {
+        window,
+        min_calls: window,
+        failure_percent,
+        open_for: std::time::Duration::from_millis(open_ms),
+    });
+}
+
+#[when(regex = r"^(\d+) reads? through the breaker (?:succeeds?|fails? with (a serialization failure|a dropped connection))$")]
+async fn reads_through_breaker(world: &mut NewsletterWorld, reads: u32, error: String) {
+    let error = (!error.is_empty()).then(|| database_error(&error));
+    world.last_attempts = 0;
+    for _ in 0..reads {
+        world.run_through_breaker(error).await;
+    }
+}
+
+#[when("the circuit breaker's open time has passed")]
+async fn breaker_open_time_passed(world: &mut NewsletterWorld) {
+    tokio::time::sleep(world.breaker.policy().open_for).await;
+}
+
+#[given(regex = r#"^email providers "([^"]+)" tried by (priority|round robin|weight)$"#)]
+async fn email_providers(world: &mut NewsletterWorld, names: String, strategy: String) {
+    world.failover_policy.strategy = match strategy.as_str() {
+        "priority" => SelectionStrategy::Priority,
+        "round robin" => SelectionStrategy::RoundRobin,
+        _ => SelectionStrategy::Weighted,
+    };
+    for name in names.split(',').map(str::trim) {
+        let provider = std::sync::Arc::new(ScriptedProvider::default());
+        world.email_providers.push((name.to_string(), provider.clone()));
+        world.email_limits.push(Provider {
+            name: name.to_string(),
+            sender: provider,
+            weight: 1,
+            rate: None,
+            daily_quota: None,
+        });
+    }
+}
+
+/// Limits of a provider, before the first email is sent
+fn email_limits<'a>(world: &'a mut NewsletterWorld, name: &str) -> &'a mut Provider {
+    world
+        .email_limits
+        .iter_mut()
+        .find(|provider| provider.name == name)
+        .unwrap_or_else(|| panic!("no email provider {name:?} in the scenario"))
+}
+
+#[given(regex = r#"^email provider "([^"]+)" has a daily quota of (\d+)$"#)]
+async fn email_provider_quota(world: &mut NewsletterWorld, name: String, quota: u64) {
+    email_limits(world, &name).daily_quota = Some(quota);
+}
+
+#[given(regex = r#"^email provider "([^"]+)" has a weight of (\d+)$"#)]
+async fn email_provider_weight(world: &mut NewsletterWorld, name: String, weight: u32) {
+    email_limits(world, &name).weight = weight;
+}
+
+#[given(regex = r"^email providers leave rotation after (\d+) failures? in a row, for (\d+) ms$")]
+async fn email_failover_policy(world: &mut NewsletterWorld, failures: u32, cooldown_ms: u64) {
+    world.failover_policy.failover_after = failures;
+    world.failover_policy.cooldown = std::time::Duration::from_millis(cooldown_ms);
+}
+
+#[given(regex = r#"^email provider "([^"]+)" is (failing transiently|failing permanently|rejecting messages)$"#)]
+async fn email_provider_fails(world: &mut NewsletterWorld, name: String, behavior: String) {
+    let behavior = match behavior.as_str() {
+        "failing transiently" => ProviderBehavior::FailingTransiently,
+        "failing permanently" => ProviderBehavior::FailingPermanently,
+        _ => ProviderBehavior::Rejecting,
+    };
+    world.email_provider(&name).behave(behavior);
+}
+
+#[when(regex = r#"^email provider "([^"]+)" recovers$"#)]
+async fn email_provider_recovers(world: &mut NewsletterWorld, name: String) {
+    world.email_provider(&name).behave(ProviderBehavior::Accepting);
+}
+
+#[when(regex = r"^(\d+) emails? (?:is|are) sent through the providers$")]
+async fn send_through_providers(world: &mut NewsletterWorld, count: u32) {
+    world.send_through_providers(count).await;
+}
+
+#[when("the email providers' cooldown has passed")]
+async fn email_cooldown_passed(world: &mut NewsletterWorld) {
+    tokio::time::sleep(world.failover_policy.cooldown).await;
+}
+
+#[then(regex = r#"^email provider "([^"]+)" should have sent (\d+) emails? in (\d+) attempts?$"#)]
+async fn email_provider_sent(world: &mut NewsletterWorld, name: String, sent: u32, attempts: u32) {
+    let provider = world.email_provider(&name);
+    assert_eq!(provider.sent(), sent, "Unexpected emails sent through {name}");
+    assert_eq!(provider.attempts(), attempts, "Unexpected attempts with {name}");
+}
+
+#[given(regex = r#"^campaigns warm up by "([^"]+)"$"#)]
+async fn campaigns_warm_up(world: &mut NewsletterWorld, caps: String) {
+    world.warmup_caps = caps
+        .split(',')
+        .map(|cap| cap.trim().parse().expect("numeric cap in scenario"))
+        .collect();
+}
+
+#[given(regex = r#"^the tenant sends campaigns from the verified domain "([^"]+)"$"#)]
+async fn tenant_sending_domain(world: &mut NewsletterWorld, domain: String) {
+    world.sending_domain = Some(domain);
+}
+
+#[given("the EMAIL_FROM domain is listed for warm-up")]
+async fn email_from_listed(world: &mut NewsletterWorld) {
+    world.warmup_domains.listed.push(EMAIL_FROM_DOMAIN.to_string());
+}
+
+#[given(regex = r#"^"([^"]+)" skips warm-up$"#)]
+async fn domain_skips_warmup(world: &mut NewsletterWorld, domain: String) {
+    world.warmup_domains.skipped.push(domain);
+}
+
+#[given(regex = r#"^"([^"]+)" started warming up (\d+) days? ago$"#)]
+async fn domain_started_warming_up(world: &mut NewsletterWorld, domain: String, days: u64) {
+    world.started_warming_up(&domain, days).await;
+}
+
+#[given(regex = r#"^the warm-up of "([^"]+)" is set to have started (\d+) days? ago$"#)]
+async fn warmup_start_set(world: &mut NewsletterWorld, domain: String, days: u64) {
+    let day = chrono::Utc::now().date_naive() - chrono::Days::new(days);
+    world.warmup_domains.started_on.insert(domain, day);
+}
+
+#[given(regex = r"^the campaign is sending to (\d+) subscribers?$")]
+async fn campaign_sending_to(world: &mut NewsletterWorld, count: usize) {
+    world.campaigns.add_recipients(count, false);
+}
+
+#[given(regex = r"^(\d+) more subscribers? (?:is|are) held for their local send hours?$")]
+async fn campaign_recipients_held(world: &mut NewsletterWorld, count: usize) {
+    world.campaigns.add_recipients(count, true);
+}
+
+#[when("the held deliveries come due")]
+async fn held_deliveries_due(world: &mut NewsletterWorld) {
+    world.campaigns.release_held();
+}
+
+#[when("a campaign batch is sent")]
+async fn campaign_batch_sent(world: &mut NewsletterWorld) {
+    world.send_campaign_batch().await;
+    assert_eq!(world.last_response.as_deref(), Some("success"), "campaign batch failed");
+}
+
+#[then(regex = r"^(\d+) campaign emails? should have been sent$")]
+async fn campaign_emails_sent(world: &mut NewsletterWorld, count: usize) {
+    assert_eq!(world.campaign_emails(), count, "Unexpected campaign emails sent");
+}
+
+#[then(regex = r"^(\d+) campaign deliver(?:y|ies) should be pending$")]
+async fn campaign_deliveries_pending(world: &mut NewsletterWorld, count: usize) {
+    assert_eq!(world.campaigns.pending(), count, "Unexpected pending deliveries");
+}
+
+#[then("the campaign should be sent")]
+async fn campaign_is_sent(world: &mut NewsletterWorld) {
+    assert_eq!(world.campaigns.status(), CampaignStatus::Sent);
+}
+
+#[given(regex = r#"^the asset "([^"]+)" was uploaded$"#)]
+async fn asset_uploaded(world: &mut NewsletterWorld, name: String) {
+    world.upload_asset(&name).await;
+    assert_eq!(world.last_response.as_deref(), Some("success"), "asset upload failed");
+}
+
+/// Body showing `name`, or none when empty
+fn campaign_body(name: &str) -> String {
+    match name {
+        "" => "<p>Hello {{email}}</p>".to_string(),
+        name => format!(r#"<p>Hello {{{{email}}}}</p><img src="{{{{asset "{name}"}}}}">"#),
+    }
+}
+
+async fn show_asset(world: &mut NewsletterWorld, locale: String, name: String) {
+    let locale = (!locale.is_empty()).then_some(locale);
+    world.set_campaign_body(locale.as_deref(), &campaign_body(&name)).await;
+    assert_eq!(world.last_response.as_deref(), Some("success"), "template update failed");
+}
+
+#[given(regex = r#"^the campaign's template(?: translation into "([^"]+)")? shows the asset "([^"]+)"$"#)]
+async fn template_shows_asset(world: &mut NewsletterWorld, locale: String, name: String) {
+    show_asset(world, locale, name).await;
+}
+
+#[when(regex = r#"^the campaign's template(?: translation into "([^"]+)")? stops showing assets$"#)]
+async fn template_drops_assets(world: &mut NewsletterWorld, locale: String) {
+    show_asset(world, locale, String::new()).await;
+}
+
+#[when(regex = r#"^the campaign's template translation into "([^"]+)" is deleted$"#)]
+async fn template_translation_deleted(world: &mut NewsletterWorld, locale: String) {
+    world.delete_campaign_translation(&locale).await;
+    assert_eq!(world.last_response.as_deref(), Some("success"), "translation delete failed");
+}
+
+fn pass_time(world: &mut NewsletterWorld, amount: i64, unit: &str) {
+    let by = match unit {
+        "hour" | "hours" => chrono::Duration::hours(amount),
+        _ => chrono::Duration::days(amount),
+    };
+    world.templates.backdate_assets(by);
+}
+
+#[given(regex = r"^(\d+) (hours?|days?) (?:pass|passes)$")]
+async fn time_passed(world: &mut NewsletterWorld, amount: i64, unit: String) {
+    pass_time(world, amount, &unit);
+}
+
+#[when(regex = r"^(\d+) (hours?|days?) (?:pass|passes)$")]
+async fn time_passes(world: &mut NewsletterWorld, amount: i64, unit: String) {
+    pass_time(world, amount, &unit);
+}
+
+#[when("unused assets are collected")]
+async fn unused_assets_collected(world: &mut NewsletterWorld) {
+    world.collect_assets().await;
+    assert_eq!(world.last_response.as_deref(), Some("success"), "asset collection failed");
+}
+
+#[then(regex = r"^(\d+) assets? should have been collected$")]
+async fn assets_collected(world: &mut NewsletterWorld, count: usize) {
+    assert_eq!(world.last_collected, Some(count), "Unexpected number of assets collected");
+}
+
+#[then(regex = r#"^the asset "([^"]+)" should be (kept|gone)$"#)]
+async fn asset_kept_or_gone(world: &mut NewsletterWorld, name: String, state: String) {
+    assert_eq!(world.asset_kept(&name).await, state == "kept", "asset {name} should be {state}");
+}
+
+/// Error a flaky read fails with, by its step wording
+fn database_error(error: &str) -> fn() -> NewsletterError {
+    match error {
+        "a serialization failure" => || {
+            diesel::result::Error::DatabaseError(
+                DatabaseErrorKind::SerializationFailure,
+                Box::new("could not serialize access due to concurrent update".to_string()),
+            )
+            .into()
+        },
+        "a dropped connection" => || {
+            RunError::User(PoolError::ConnectionError(ConnectionError::BadConnection(
+                "connection reset by peer".to_string(),
+            )))
+            .into()
+        },
+        _ => || {
+            diesel::result::Error::DatabaseError(
+                DatabaseErrorKind::UniqueViolation,
+                Box::new("duplicate key value violates unique constraint".to_string()),
+            )
+            .into()
+        },
+    }
+}
 
 // Read operations
 #[when(regex = r"^I get the subscription for (.+)$")]
 async fn get_subscription(world: &mut NewsletterWorld, email: String) {
     let clean_email = email.trim_matches('"').to_string();
-    world.last_get = world.newsletters.get(&clean_email).cloned();
+    world.get(&clean_email).await;
 }
 
 #[when("I list all subscriptions")]
 async fn list_all_subscriptions(world: &mut NewsletterWorld) {
-    world.last_list = world.newsletters.values().cloned().collect();
+    world.list_all().await;
+}
+
+#[when(regex = r#"^I list all subscriptions with only the fields "([^"]+)"$"#)]
+async fn list_subscriptions_masked(world: &mut NewsletterWorld, fields: String) {
+    let paths: Vec<&str> = fields.split(',').map(str::trim).collect();
+    world.list_masked(&paths).await;
 }
 
-// Update operations removed for simplicity - using direct subscribe/unsubscribe instead
+#[when(regex = r#"^I list subscriptions at domain "([^"]+)" ordered by "([^"]+)"$"#)]
+async fn list_subscriptions_by_domain(world: &mut NewsletterWorld, domain: String, order_by: String) {
+    let query = NewsletterQuery {
+        filter: NewsletterFilter {
+            email_domain: NewsletterFilter::email_domain(&domain).expect("valid domain in scenario"),
+            ..NewsletterFilter::default()
+        },
+        order: NewsletterOrder::parse(&order_by).expect("valid order in scenario"),
+    };
+    world.list_query(&query, NewsletterMask::ALL).await;
+}
+
+#[when(regex = r"^I list the (pending|active|unsubscribed|suppressed) subscriptions$")]
+async fn list_subscriptions_by_status(world: &mut NewsletterWorld, status: String) {
+    let query = NewsletterQuery {
+        filter: NewsletterFilter {
+            status: SubscriptionStatus::parse(&status),
+            ..NewsletterFilter::default()
+        },
+        ..NewsletterQuery::default()
+    };
+    let mask = NewsletterMask::from_paths(&["email", "status"]).expect("valid field mask in scenario");
+    world.list_query(&query, mask).await;
+}
 
 // Delete operations
 #[when(regex = r"^I unsubscribe email (.+)$")]
 async fn unsubscribe_email(world: &mut NewsletterWorld, email: String) {
     let clean_email = email.trim_matches('"').to_string();
-    world.newsletters.remove(&clean_email);
-    world.last_response = Some("success".to_string());
+    world.unsubscribe(&clean_email).await;
+}
+
+// Import operations
+#[given(regex = r"^imports are throttled to wait (\d+) ms for the store$")]
+async fn imports_throttled(world: &mut NewsletterWorld, max_wait_ms: u64) {
+    world.throttle_imports(std::time::Duration::from_millis(max_wait_ms));
+}
+
+#[given(regex = r"^the store is (saturated|free)$")]
+async fn store_load(world: &mut NewsletterWorld, load: String) {
+    world.store_load.set(load == "saturated");
+}
+
+#[when(regex = r"^I (import|dry-run the import of) this (CSV|NDJSON) in chunks of (\d+) bytes:$")]
+async fn import_upload(world: &mut NewsletterWorld, step: &Step, mode: String, format: String, chunk_size: usize) {
+    let format = match format.as_str() {
+        "CSV" => ImportFormat::Csv,
+        _ => ImportFormat::Ndjson,
+    };
+    let upload = step.docstring.as_deref().expect("upload docstring");
+    world.import(format, upload, chunk_size, mode != "import").await;
+}
+
+#[when(regex = r#"^I sign up "([^"]+)" with locale "([^"]+)" for topics "([^"]+)"$"#)]
+async fn sign_up(world: &mut NewsletterWorld, email: String, locale: String, topics: String) {
+    world.sign_up(&email, &locale, &topics).await;
+}
+
+// Preference operations
+#[when(regex = r#"^I get the preferences for "([^"]+)"$"#)]
+async fn get_preferences(world: &mut NewsletterWorld, email: String) {
+    world.get_preferences(&email).await;
+}
+
+#[when(regex = r#"^I opt (in to|out of) topic "([^"]+)" for "([^"]+)"$"#)]
+async fn set_preference(world: &mut NewsletterWorld, choice: String, topic: String, email: String) {
+    world.set_preference(&email, &topic, choice == "in to").await;
+}
+
+#[when(regex = r#"^I list the consent history for "([^"]+)"$"#)]
+async fn list_consents(world: &mut NewsletterWorld, email: String) {
+    world.list_consents(&email).await;
+}
+
+// Subscriber history
+#[when("I note the time")]
+async fn note_time(world: &mut NewsletterWorld) {
+    world.note_time().await;
+}
+
+#[when(regex = r#"^I look up the history of "([^"]+)" (now|at the noted time)$"#)]
+async fn look_up_history(world: &mut NewsletterWorld, email: String, when: String) {
+    let at = match when.as_str() {
+        "now" => None,
+        _ => Some(world.noted_at.expect("the scenario noted a time")),
+    };
+    world.subscriber_history(&email, at).await;
+}
+
+// Background jobs
+#[when(regex = r#"^I request a subscription for "([^"]+)"$"#)]
+async fn request_subscription(world: &mut NewsletterWorld, email: String) {
+    world.request_subscription(&email).await;
+}
+
+#[when(regex = r#"^I ask to move "([^"]+)" to "([^"]+)"$"#)]
+async fn request_email_change(world: &mut NewsletterWorld, email: String, new_email: String) {
+    world.request_email_change(&email, &new_email).await;
+}
+
+#[when("I confirm the new address")]
+async fn confirm_email_change(world: &mut NewsletterWorld) {
+    world.confirm_email_change().await;
+}
+
+#[when(regex = r#"^I merge "([^"]+)" into "([^"]+)"$"#)]
+async fn merge_subscribers(world: &mut NewsletterWorld, merged_email: String, email: String) {
+    world.merge_subscribers(&email, &merged_email).await;
+}
+
+#[when(regex = r#"^a unit of work adds "([^"]+)"( and then fails)?$"#)]
+async fn add_in_transaction(world: &mut NewsletterWorld, emails: String, fails: String) {
+    let emails: Vec<&str> = emails.split(',').map(str::trim).collect();
+    world.add_in_transaction(&emails, !fails.is_empty()).await;
+}
+
+#[when("the job runner runs")]
+async fn run_jobs(world: &mut NewsletterWorld) {
+    world.run_jobs().await;
+}
+
+// Outbox operations
+#[when("the outbox relay runs")]
+async fn relay_outbox(world: &mut NewsletterWorld) {
+    world.relay_outbox().await;
+}
+
+#[when("I purge the published outbox events")]
+async fn purge_outbox(world: &mut NewsletterWorld) {
+    world.purge_outbox().await;
+}
+
+#[when(regex = r"^I replay the outbox events sent (in the last hour|from now on)$")]
+async fn replay_outbox(world: &mut NewsletterWorld, window: String) {
+    let since = match window.as_str() {
+        "in the last hour" => chrono::Utc::now() - chrono::Duration::hours(1),
+        _ => chrono::Utc::now(),
+    };
+    world.replay_outbox(since).await;
+}
+
+// Attribute operations
+#[when(regex = r#"^I set attribute "([^"]+)" to (.+) for "([^"]+)"$"#)]
+async fn set_attribute(world: &mut NewsletterWorld, key: String, value: String, email: String) {
+    let value: serde_json::Value = serde_json::from_str(&value).expect("JSON attribute value in scenario");
+    world.set_attribute(&email, &key, value).await;
 }
 
-// Bulk delete operations removed for simplicity
+#[when(regex = r#"^I list subscriptions with attribute "([^"]+)" equal to (.+)$"#)]
+async fn list_subscriptions_by_attribute(world: &mut NewsletterWorld, key: String, value: String) {
+    let value: serde_json::Value = serde_json::from_str(&value).expect("JSON attribute value in scenario");
+    let mut query = NewsletterQuery::default();
+    query.filter.attributes.insert(key, value);
+    query.order = NewsletterOrder::parse("email").expect("valid order");
+    world.list_query(&query, NewsletterMask::ALL).await;
+}
 
 // Assertion steps
 #[then("the subscription should be created successfully")]
@@ -123,84 +642,350 @@ async fn subscriptions_deleted_successfully(world: &mut NewsletterWorld) {
     assert_eq!(world.last_response, Some("success".to_string()));
 }
 
-// Bulk operation assertions removed for simplicity
-
 #[then(regex = r"^the email (.+) should be active$")]
 async fn email_should_be_active(world: &mut NewsletterWorld, email: String) {
     let clean_email = email.trim_matches('"').to_string();
-    let newsletter = world.newsletters.get(&clean_email);
-    assert!(newsletter.is_some(), "Email {} should exist", clean_email);
-    assert!(newsletter.unwrap().active, "Email {} should be active", clean_email);
+    assert!(world.is_active(&clean_email).await, "Email {} should be active", clean_email);
 }
 
-#[then(regex = r"^([a-zA-Z0-9@.]+) should be active$")]
+#[then(regex = r#"^"?([a-zA-Z0-9@.-]+)"? should be active$"#)]
 async fn email_active(world: &mut NewsletterWorld, email: String) {
     email_should_be_active(world, email).await;
 }
 
-#[then(regex = r"^([a-zA-Z0-9@.]+) should not be active$")]
+#[then(regex = r#"^"?([a-zA-Z0-9@.-]+)"? should not be active$"#)]
 async fn email_should_not_be_active(world: &mut NewsletterWorld, email: String) {
-    let newsletter = world.newsletters.get(&email);
-    // Email should either not exist or be inactive
-    assert!(newsletter.is_none(), "Email {} should not exist (should be deleted)", email);
+    assert!(!world.is_active(&email).await, "Email {} should not be active", email);
 }
 
-// Additional step definitions for quoted emails
-#[then("\"update1@example.com\" should not be active")]
-async fn update1_should_not_be_active(world: &mut NewsletterWorld) {
-    email_should_not_be_active(world, "update1@example.com".to_string()).await;
+#[then(regex = r"^the email (.+) should not exist$")]
+async fn email_should_not_exist(world: &mut NewsletterWorld, email: String) {
+    let clean_email = email.trim_matches('"').to_string();
+    assert!(!world.exists(&clean_email).await, "Email {} should not exist", clean_email);
 }
 
-#[then("\"update2@example.com\" should not be active")]
-async fn update2_should_not_be_active(world: &mut NewsletterWorld) {
-    email_should_not_be_active(world, "update2@example.com".to_string()).await;
+#[then(regex = r#"^the email "([^"]+)" should (not )?exist in tenant "([^"]+)"$"#)]
+async fn email_should_exist_in_tenant(world: &mut NewsletterWorld, email: String, not: String, name: String) {
+    let exists = tenant::scope(tenant_scope(&name), world.exists(&email)).await;
+    assert_eq!(exists, not.is_empty(), "Email {email} should {not}exist in tenant {name}");
 }
 
-#[then("\"reactive1@example.com\" should be active")]
-async fn reactive1_should_be_active(world: &mut NewsletterWorld) {
-    email_should_be_active(world, "reactive1@example.com".to_string()).await;
+#[when("the confirmation tokens expire")]
+async fn confirmation_tokens_expire(world: &mut NewsletterWorld) {
+    world.expire_confirmations().await;
 }
 
-#[then("\"reactive2@example.com\" should be active")]
-async fn reactive2_should_be_active(world: &mut NewsletterWorld) {
-    email_should_be_active(world, "reactive2@example.com".to_string()).await;
+#[when("expired pending subscriptions are purged")]
+async fn expired_pending_purged(world: &mut NewsletterWorld) {
+    world.purge_expired_pending().await;
 }
 
-#[then("\"workflow@example.com\" should not be active")]
-async fn workflow_should_not_be_active(world: &mut NewsletterWorld) {
-    email_should_not_be_active(world, "workflow@example.com".to_string()).await;
+#[when(regex = r"^today's growth is rolled up( for every tenant)?$")]
+async fn growth_rolled_up(world: &mut NewsletterWorld, every_tenant: String) {
+    if every_tenant.is_empty() {
+        world.rollup_growth().await;
+    } else {
+        tenant::scope(TenantScope::All, world.rollup_growth()).await;
+    }
 }
 
-#[then("\"workflow@example.com\" should be active")]
-async fn workflow_should_be_active(world: &mut NewsletterWorld) {
-    email_should_be_active(world, "workflow@example.com".to_string()).await;
+#[when(regex = r#"^I read the (daily|weekly|monthly) growth of the last (\d+) days(?: in tenant "([^"]+)")?$"#)]
+async fn read_growth(world: &mut NewsletterWorld, granularity: String, days: i64, name: String) {
+    let granularity = match granularity.as_str() {
+        "weekly" => Granularity::Week,
+        "monthly" => Granularity::Month,
+        _ => Granularity::Day,
+    };
+    let today = chrono::Utc::now().date_naive();
+    let from = today - chrono::Duration::days(days - 1);
+    if name.is_empty() {
+        world.growth(from, today, granularity).await;
+    } else {
+        tenant::scope(tenant_scope(&name), world.growth(from, today, granularity)).await;
+    }
 }
 
-#[then(regex = r"^the email (.+) should not exist$")]
-async fn email_should_not_exist(world: &mut NewsletterWorld, email: String) {
-    let clean_email = email.trim_matches('"').to_string();
-    let newsletter = world.newsletters.get(&clean_email);
-    assert!(newsletter.is_none(), "Email {} should not exist", clean_email);
+#[when(regex = r"^I read the daily growth from (\S+) to (\S+)$")]
+async fn read_growth_between(world: &mut NewsletterWorld, from: String, to: String) {
+    let parse = |day: &str| chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d").expect("valid date in scenario");
+    world.growth(parse(&from), parse(&to), Granularity::Day).await;
+}
+
+#[then(regex = r"^(\d+) daily snapshots? should have been recorded$")]
+async fn snapshots_recorded(world: &mut NewsletterWorld, recorded: usize) {
+    assert_eq!(world.last_rollup, Some(recorded), "Unexpected number of snapshots");
+}
+
+#[then(regex = r"^the growth should have (\d+) points?$")]
+async fn growth_points(world: &mut NewsletterWorld, points: usize) {
+    assert_eq!(world.last_growth.len(), points, "Unexpected growth: {:?}", world.last_growth);
+}
+
+#[then(regex = r"^the latest growth point should show (\d+) subscribers?, (\d+) confirms?, (\d+) unsubscribes? and a net growth of (-?\d+)$")]
+async fn latest_growth_point(world: &mut NewsletterWorld, subscribers: i64, confirms: i64, unsubscribes: i64, net_growth: i64) {
+    let point = world.last_growth.last().expect("no growth points");
+    assert_eq!(
+        (point.subscribers, point.confirms, point.unsubscribes, point.net_growth),
+        (subscribers, confirms, unsubscribes, net_growth),
+        "Unexpected growth point: {point:?}"
+    );
+}
+
+#[then(regex = r"^the latest growth point should start on (today|this week's Monday|the first of this month)$")]
+async fn latest_growth_point_start(world: &mut NewsletterWorld, start: String) {
+    let today = chrono::Utc::now().date_naive();
+    let expected = match start.as_str() {
+        "this week's Monday" => Granularity::Week.bucket(today),
+        "the first of this month" => Granularity::Month.bucket(today),
+        _ => today,
+    };
+    let point = world.last_growth.last().expect("no growth points");
+    assert_eq!(point.start, expected, "Unexpected bucket start");
+}
+
+#[when(regex = r#"^I search for "([^"]*)"(?: (\d+) at a time)?$"#)]
+async fn search(world: &mut NewsletterWorld, text: String, page_size: String) {
+    let page_size = page_size.parse().unwrap_or(0);
+    world.search(&text, page_size, None).await;
+}
+
+#[when("I fetch the next page of search results")]
+async fn search_next_page(world: &mut NewsletterWorld) {
+    let (text, cursor) = world.next_search.clone().expect("no further page of search results");
+    let page_size = world.last_search.len() as i64;
+    world.search(&text, page_size, Some(cursor)).await;
+}
+
+#[then(regex = r#"^the search should find (.+)$"#)]
+async fn search_found(world: &mut NewsletterWorld, emails: String) {
+    let found: Vec<&str> = world.last_search.iter().map(|hit| hit.newsletter.email.as_str()).collect();
+    let expected: Vec<&str> = match emails.as_str() {
+        "nothing" => Vec::new(),
+        emails => emails.split(", ").map(|e| e.trim_matches('"')).collect(),
+    };
+    assert_eq!(found, expected, "Unexpected search results: {:?}", world.last_search);
+}
+
+#[then(regex = r"^there should (not )?be more search results$")]
+async fn more_search_results(world: &mut NewsletterWorld, not: String) {
+    assert_eq!(world.next_search.is_some(), not.is_empty(), "Unexpected next page: {:?}", world.next_search);
+}
+
+#[then(regex = r#"^"([^"]+)" should score (1|below 1)$"#)]
+async fn search_score(world: &mut NewsletterWorld, email: String, score: String) {
+    let hit = world
+        .last_search
+        .iter()
+        .find(|hit| hit.newsletter.email == email)
+        .expect("address not among the search results");
+    assert_eq!(hit.score == 1.0, score == "1", "Unexpected score: {hit:?}");
+}
+
+#[then(regex = r"^(\d+) pending subscriptions? should have been deleted and (\d+) reverted$")]
+async fn pending_purged(world: &mut NewsletterWorld, deleted: usize, reverted: usize) {
+    let purge = world.last_purge.expect("no purge ran");
+    assert_eq!((purge.deleted, purge.reverted), (deleted, reverted), "Unexpected purge: {purge:?}");
+}
+
+fn retention_action(verb: &str) -> RetentionAction {
+    match verb {
+        "anonymized" | "anonymizes" => RetentionAction::Anonymize,
+        _ => RetentionAction::Delete,
+    }
+}
+
+#[given(regex = r"^unsubscribed subscriptions are (anonymized|deleted) after (\d+) days?$")]
+async fn retention_default(world: &mut NewsletterWorld, action: String, days: i64) {
+    world.retain_unsubscribed(None, days, Some(retention_action(&action)));
+}
+
+#[given(regex = r#"^tenant "([^"]+)" (anonymizes|deletes) unsubscribed subscriptions after (\d+) days?$"#)]
+async fn retention_tenant(world: &mut NewsletterWorld, name: String, action: String, days: i64) {
+    let tenant = TenantId::parse(&name).expect("valid tenant in scenario");
+    world.retain_unsubscribed(Some(tenant), days, Some(retention_action(&action)));
+}
+
+#[given(regex = r#"^tenant "([^"]+)" keeps unsubscribed subscriptions forever$"#)]
+async fn retention_tenant_forever(world: &mut NewsletterWorld, name: String) {
+    let tenant = TenantId::parse(&name).expect("valid tenant in scenario");
+    world.retain_unsubscribed(Some(tenant), 0, None);
+}
+
+#[when("the retention job runs")]
+async fn retention_runs(world: &mut NewsletterWorld) {
+    world.apply_retention().await;
+}
+
+#[then(regex = r#"^retention should have anonymized (\d+) and deleted (\d+)(?: in tenant "([^"]+)")?$"#)]
+async fn retention_purged(world: &mut NewsletterWorld, anonymized: usize, deleted: usize, name: String) {
+    let tenant = if name.is_empty() {
+        TenantId::default()
+    } else {
+        TenantId::parse(&name).expect("valid tenant in scenario")
+    };
+    let purge = world
+        .last_retention
+        .iter()
+        .find(|run| run.tenant == tenant)
+        .map(|run| run.purge)
+        .unwrap_or_default();
+    assert_eq!((purge.anonymized, purge.deleted), (anonymized, deleted), "Unexpected retention: {:?}", world.last_retention);
+}
+
+#[then(regex = r#"^"([^"]+)" should (not )?have been resubscribed$"#)]
+async fn resubscribed(world: &mut NewsletterWorld, email: String, not: String) {
+    let resubscribed_at = world.resubscribed_at(&email).await;
+    assert_eq!(resubscribed_at.is_some(), not.is_empty(), "Unexpected resubscribed_at: {resubscribed_at:?}");
+}
+
+#[then(regex = r#"^the subscription of "([^"]+)" should be (pending|active|unsubscribed|suppressed)(?: in tenant "([^"]+)")?$"#)]
+async fn subscription_status(world: &mut NewsletterWorld, email: String, status: String, name: String) {
+    let actual = if name.is_empty() {
+        world.status(&email).await
+    } else {
+        tenant::scope(tenant_scope(&name), world.status(&email)).await
+    };
+    assert_eq!(actual.map(|s| s.as_str()), Some(status.as_str()), "Unexpected status of {email}");
+}
+
+#[when(regex = r#"^I seed (\d+) subscribers with seed (\d+)(?: on domain "([^"]*)")?(?: weighted (\d+) active, (\d+) unsubscribed and (\d+) suppressed)?$"#)]
+async fn seed_subscribers(
+    world: &mut NewsletterWorld,
+    count: usize,
+    seed: u64,
+    domain: String,
+    active: String,
+    unsubscribed: String,
+    suppressed: String,
+) {
+    let mut plan = SeedPlan::new(count).with_seed(seed);
+    if !domain.is_empty() {
+        plan = plan.with_domains(vec![domain]);
+    }
+    if let (Ok(active), Ok(unsubscribed), Ok(suppressed)) = (active.parse(), unsubscribed.parse(), suppressed.parse()) {
+        plan = plan.with_mix(ActivityMix {
+            active,
+            unsubscribed,
+            suppressed,
+        });
+    }
+    world.seed(plan).await;
+}
+
+#[then(regex = r"^the seed should report (\d+) created(?:, (\d+) active, (\d+) unsubscribed and (\d+) suppressed)?$")]
+async fn seed_summary(
+    world: &mut NewsletterWorld,
+    created: usize,
+    active: String,
+    unsubscribed: String,
+    suppressed: String,
+) {
+    let (_, summary) = world.last_seed.as_ref().expect("a seed was run");
+    let summary = summary.expect("the seed succeeded");
+    assert_eq!(summary.created, created, "Unexpected seed summary: {summary:?}");
+    if let (Ok(active), Ok(unsubscribed), Ok(suppressed)) =
+        (active.parse::<usize>(), unsubscribed.parse::<usize>(), suppressed.parse::<usize>())
+    {
+        assert_eq!(
+            (summary.active, summary.unsubscribed, summary.suppressed),
+            (active, unsubscribed, suppressed),
+            "Unexpected seed summary: {summary:?}"
+        );
+    }
+}
+
+#[then("the seeded subscribers should have their drawn statuses")]
+async fn seeded_statuses(world: &mut NewsletterWorld) {
+    let (plan, _) = world.last_seed.as_ref().expect("a seed was run");
+    for subscriber in plan.generate() {
+        let status = world.status(subscriber.email.as_str()).await;
+        assert_eq!(status, Some(subscriber.status), "Unexpected status of {}", subscriber.email);
+    }
+}
+
+#[then(regex = r#"^every seeded address should end in "([^"]+)"$"#)]
+async fn seeded_domains(world: &mut NewsletterWorld, suffix: String) {
+    let (plan, _) = world.last_seed.as_ref().expect("a seed was run");
+    for subscriber in plan.generate() {
+        assert!(subscriber.email.as_str().ends_with(&suffix), "Unexpected address {}", subscriber.email);
+    }
+}
+
+#[then(regex = r"^the stats should show (\d+) active, (\d+) inactive and (\d+) unsubscribed$")]
+async fn stats_should_show(world: &mut NewsletterWorld, active: i64, inactive: i64, unsubscribed: i64) {
+    let stats = world.last_stats.expect("stats were read");
+    assert_eq!(
+        (stats.active, stats.inactive, stats.unsubscribed),
+        (active, inactive, unsubscribed),
+        "Unexpected stats"
+    );
+}
+
+#[then(regex = r"^the read should (succeed|fail) after (\d+) attempts?$")]
+async fn read_outcome(world: &mut NewsletterWorld, outcome: String, attempts: u32) {
+    let succeeded = world.last_response.as_deref() == Some("success");
+    assert_eq!(succeeded, outcome == "succeed", "Unexpected outcome: {:?}", world.last_response);
+    assert_eq!(world.last_attempts, attempts, "Unexpected number of attempts");
+}
+
+#[then(regex = r"^(\d+) retr(?:y|ies) should be counted, (\d+) recovered, (\d+) exhausted and (\d+) over budget$")]
+async fn retry_counts(world: &mut NewsletterWorld, retries: u64, recovered: u64, exhausted: u64, over_budget: u64) {
+    let counts = world.last_retry_counts;
+    assert_eq!(
+        (counts.retries, counts.recovered, counts.exhausted, counts.over_budget),
+        (retries, recovered, exhausted, over_budget),
+        "Unexpected retry counts"
+    );
+}
+
+#[then(regex = r"^the circuit breaker should be (closed|open|half-open)$")]
+async fn breaker_state(world: &mut NewsletterWorld, state: String) {
+    let expected = match state.as_str() {
+        "closed" => BreakerState::Closed,
+        "open" => BreakerState::Open,
+        _ => BreakerState::HalfOpen,
+    };
+    assert_eq!(world.breaker.state(), expected, "Unexpected breaker state");
+}
+
+#[then(regex = r"^(\d+) of them should have reached the database$")]
+async fn reads_reached_database(world: &mut NewsletterWorld, reached: u32) {
+    assert_eq!(world.last_attempts, reached, "Unexpected number of reads reaching the database");
+}
+
+#[then("the last read should be refused as unavailable")]
+async fn read_refused(world: &mut NewsletterWorld) {
+    let response = world.last_response.as_deref().unwrap_or_default();
+    assert!(
+        response.starts_with("error: the database is unavailable"),
+        "Unexpected response: {response}"
+    );
+}
+
+#[then(regex = r"^the circuit breaker should have opened (\d+) times? and refused (\d+) reads?$")]
+async fn breaker_counts(world: &mut NewsletterWorld, opened: u64, rejected: u64) {
+    let counts = world.breaker_counts;
+    assert_eq!((counts.opened, counts.rejected), (opened, rejected), "Unexpected breaker counts");
 }
 
 #[then(regex = r"^the email (.+) should still exist$")]
 async fn email_should_still_exist(world: &mut NewsletterWorld, email: String) {
     let clean_email = email.trim_matches('"').to_string();
-    let newsletter = world.newsletters.get(&clean_email);
-    assert!(newsletter.is_some(), "Email {} should still exist", clean_email);
+    assert!(world.exists(&clean_email).await, "Email {} should still exist", clean_email);
 }
 
 #[then(regex = r"^there should be only one subscription for (.+)$")]
 async fn only_one_subscription(world: &mut NewsletterWorld, email: String) {
     let clean_email = email.trim_matches('"').to_string();
-    let count = world.newsletters.values().filter(|n| n.email == clean_email).count();
+    world.list_all().await;
+    let count = world.last_list.iter().filter(|n| n.email == clean_email).count();
     assert_eq!(count, 1, "Should have exactly one subscription for {}", clean_email);
 }
 
 #[then("the subscription should exist")]
 async fn subscription_should_exist(world: &mut NewsletterWorld) {
     assert!(world.last_get.is_some(), "Should have found a subscription");
-    assert!(world.last_get.as_ref().unwrap().active, "Subscription should be active");
+    assert!(world.last_get.as_ref().unwrap().is_active(), "Subscription should be active");
 }
 
 #[then("the subscription should not exist")]
@@ -220,13 +1005,130 @@ async fn email_should_be(world: &mut NewsletterWorld, expected_email: String) {
 async fn status_should_be_active(world: &mut NewsletterWorld) {
     assert!(world.last_get.is_some(), "Should have a get response");
     let newsletter = world.last_get.as_ref().unwrap();
-    assert!(newsletter.active, "Status should be active");
+    assert!(newsletter.is_active(), "Status should be active");
 }
 
 #[then("the status should be inactive")]
 async fn status_should_be_inactive(world: &mut NewsletterWorld) {
-    // In our simple model, inactive means not found
-    assert!(world.last_get.is_none(), "Status should be inactive (not found)");
+    let active = world.last_get.as_ref().is_some_and(|n| n.is_active());
+    assert!(!active, "Status should be inactive");
+}
+
+#[then(regex = r"^the import should report (\d+) imported, (\d+) skipped and (\d+) invalid$")]
+async fn import_summary(world: &mut NewsletterWorld, imported: u64, skipped: u64, invalid: u64) {
+    let summary = world.last_import.as_ref().expect("an import was run");
+    assert_eq!(
+        (summary.imported, summary.skipped, summary.invalid),
+        (imported, skipped, invalid),
+        "unexpected import summary: {summary:?}"
+    );
+}
+
+#[then(regex = r"^the preview should report (\d+) changed, (\d+) unchanged, (\d+) created and (\d+) not found$")]
+async fn preview_counts(world: &mut NewsletterWorld, changed: u64, unchanged: u64, created: u64, not_found: u64) {
+    let preview = world.last_preview.as_ref().expect("a preview was run");
+    assert_eq!(
+        (preview.changed, preview.unchanged, preview.created, preview.not_found),
+        (changed, unchanged, created, not_found),
+        "unexpected preview: {preview:?}"
+    );
+}
+
+#[then(regex = r#"^the preview should reject "([^"]*)"$"#)]
+async fn preview_rejected(world: &mut NewsletterWorld, emails: String) {
+    let preview = world.last_preview.as_ref().expect("a preview was run");
+    let rejected: Vec<&str> = preview.rejected.iter().map(|entry| entry.email.as_str()).collect();
+    assert_eq!(rejected.join(", "), emails, "Unexpected rejected entries");
+}
+
+#[then(regex = r#"^the operation should fail with "([^"]+)"$"#)]
+async fn operation_failed_with(world: &mut NewsletterWorld, message: String) {
+    let response = world.last_response.as_deref().unwrap_or_default();
+    assert!(
+        response.starts_with("error:") && response.contains(&message),
+        "Expected an error containing {message:?}, got {response:?}"
+    );
+}
+
+#[given(regex = r#"^topic "([^"]+)" requires confirmation$"#)]
+async fn topic_requires_confirmation(world: &mut NewsletterWorld, topic: String) {
+    world.require_topic_confirmation(&topic).await;
+}
+
+#[when(regex = r#"^I follow the topic confirmation link sent to "([^"]+)"$"#)]
+async fn follow_topic_link(world: &mut NewsletterWorld, email: String) {
+    world.confirm_topic(&email).await;
+}
+
+#[then(regex = r#"^topics? "([^"]*)" should be awaiting confirmation$"#)]
+async fn topics_awaiting(world: &mut NewsletterWorld, expected: String) {
+    assert_eq!(world.awaiting_topics.join(", "), expected, "Unexpected topics awaiting confirmation");
+}
+
+#[then(regex = r#"^topic "([^"]+)" should be (subscribed|unsubscribed)$"#)]
+async fn topic_status(world: &mut NewsletterWorld, topic: String, status: String) {
+    let subscription = world
+        .last_preferences
+        .iter()
+        .find(|t| t.topic.key == topic)
+        .unwrap_or_else(|| panic!("topic {topic} missing from {:?}", world.last_preferences));
+    assert_eq!(subscription.subscribed, status == "subscribed", "Unexpected status for topic {topic}");
+}
+
+#[then(regex = r#"^attribute "([^"]+)" should be (.+)$"#)]
+async fn attribute_value(world: &mut NewsletterWorld, key: String, value: String) {
+    let expected: serde_json::Value = serde_json::from_str(&value).expect("JSON attribute value in scenario");
+    assert_eq!(world.last_attributes.get(&key), Some(&expected), "Unexpected value for attribute {key}");
+}
+
+#[then(regex = r#"^attribute "([^"]+)" should not be set$"#)]
+async fn attribute_unset(world: &mut NewsletterWorld, key: String) {
+    assert!(!world.last_attributes.contains_key(&key), "Attribute {key} should not be set");
+}
+
+#[when(regex = r#"^I set the locale of "([^"]+)" to "([^"]+)"$"#)]
+async fn set_locale(world: &mut NewsletterWorld, email: String, locale: String) {
+    world.set_locale(&email, Some(&locale)).await;
+}
+
+#[when(regex = r#"^I clear the locale of "([^"]+)"$"#)]
+async fn clear_locale(world: &mut NewsletterWorld, email: String) {
+    world.set_locale(&email, None).await;
+}
+
+#[then(regex = r#"^the locale should be "([^"]+)"$"#)]
+async fn locale_value(world: &mut NewsletterWorld, locale: String) {
+    assert_eq!(world.last_locale.as_deref(), Some(locale.as_str()), "Unexpected locale");
+}
+
+#[then(regex = r"^the locale should not be set$")]
+async fn locale_unset(world: &mut NewsletterWorld) {
+    assert_eq!(world.last_locale, None, "Locale should not be set");
+}
+
+#[when(regex = r#"^I set the timezone of "([^"]+)" to "([^"]+)"$"#)]
+async fn set_timezone(world: &mut NewsletterWorld, email: String, timezone: String) {
+    world.set_timezone(&email, Some(&timezone)).await;
+}
+
+#[when(regex = r#"^I clear the timezone of "([^"]+)"$"#)]
+async fn clear_timezone(world: &mut NewsletterWorld, email: String) {
+    world.set_timezone(&email, None).await;
+}
+
+#[then(regex = r#"^the timezone should be "([^"]+)"$"#)]
+async fn timezone_value(world: &mut NewsletterWorld, timezone: String) {
+    assert_eq!(world.last_timezone.as_deref(), Some(timezone.as_str()), "Unexpected timezone");
+}
+
+#[then(regex = r"^the timezone should not be set$")]
+async fn timezone_unset(world: &mut NewsletterWorld) {
+    assert_eq!(world.last_timezone, None, "Timezone should not be set");
+}
+
+#[then(regex = r"^I should see (\d+) topics$")]
+async fn topic_count(world: &mut NewsletterWorld, count: usize) {
+    assert_eq!(world.last_preferences.len(), count, "Unexpected number of topics");
 }
 
 #[then(regex = r"^I should get (\d+) subscriptions$")]
@@ -234,6 +1136,126 @@ async fn should_get_subscriptions_count(world: &mut NewsletterWorld, count: usiz
     assert_eq!(world.last_list.len(), count, "Should have {} subscriptions", count);
 }
 
+#[then(regex = r"^the listed subscriptions should only carry their email$")]
+async fn masked_list_only_email(world: &mut NewsletterWorld) {
+    assert!(!world.last_masked_list.is_empty(), "Masked list should not be empty");
+    for n in &world.last_masked_list {
+        assert!(n.email.is_some(), "Email should be selected: {n:?}");
+        assert!(
+            n.active.is_none() && n.status.is_none() && n.created_at.is_none(),
+            "Only email should be selected: {n:?}"
+        );
+    }
+}
+
+#[then(regex = r#"^the listed emails should be "([^"]*)"$"#)]
+async fn listed_emails_should_be(world: &mut NewsletterWorld, expected: String) {
+    let emails: Vec<&str> = world
+        .last_masked_list
+        .iter()
+        .filter_map(|n| n.email.as_deref())
+        .collect();
+    assert_eq!(emails.join(", "), expected, "Unexpected listed emails");
+}
+
+#[then(regex = r"^(\d+) confirmation emails? should be queued$")]
+async fn confirmation_emails_queued(world: &mut NewsletterWorld, count: usize) {
+    let queued = world
+        .jobs
+        .pending()
+        .into_iter()
+        .filter(|job| job.kind == JobKind::SendConfirmation)
+        .count();
+    assert_eq!(queued, count, "Unexpected number of queued confirmation emails");
+}
+
+#[then(regex = r#"^confirmation emails should have been sent to "([^"]*)"$"#)]
+async fn confirmation_emails_sent(world: &mut NewsletterWorld, expected: String) {
+    assert_eq!(world.mailer.recipients().join(", "), expected, "Unexpected confirmation recipients");
+}
+
+#[then("no subscription events should have been published")]
+async fn no_events_published(world: &mut NewsletterWorld) {
+    let published = world.publisher.published();
+    assert!(published.is_empty(), "Nothing should be published before the relay runs: {published:?}");
+}
+
+#[given("the event publisher is down")]
+async fn publisher_down(world: &mut NewsletterWorld) {
+    world.publisher.go_down();
+}
+
+#[then(regex = r"^the outbox backlog should hold (\d+) pending events?, (\d+) failing$")]
+async fn outbox_backlog(world: &mut NewsletterWorld, pending: i64, failing: i64) {
+    let backlog = world.outbox.backlog().await.expect("in-memory backlog");
+    assert_eq!((backlog.pending, backlog.failing), (pending, failing), "Unexpected outbox backlog");
+    assert_eq!(backlog.oldest_pending_at.is_some(), pending > 0, "Unexpected oldest pending event: {backlog:?}");
+}
+
+#[then(regex = r#"^the relay should have published (\d+) events? and failed (\d+) times?(?:, last with "([^"]*)")?$"#)]
+async fn relay_stats(world: &mut NewsletterWorld, delivered: u64, failed: u64, last_error: String) {
+    let stats = world.relay.tracker().stats();
+    let [stats] = stats.as_slice() else {
+        panic!("Expected one publisher, got {stats:?}");
+    };
+    assert_eq!(stats.destination, "recording");
+    assert_eq!((stats.counts.delivered, stats.counts.failed), (delivered, failed), "Unexpected relay counts");
+    assert!(stats.latency.is_some(), "Latency should be recorded: {stats:?}");
+    assert_eq!(stats.last_error.clone().unwrap_or_default(), last_error, "Unexpected last error");
+}
+
+#[then(regex = r#"^the published events should be "([^"]*)"$"#)]
+async fn published_events_should_be(world: &mut NewsletterWorld, expected: String) {
+    assert_eq!(world.publisher.published().join(", "), expected, "Unexpected published events");
+}
+
+#[then(regex = r#"^the published events should be "([^"]*)" for the pseudonym of "([^"]+)"$"#)]
+async fn published_events_for_pseudonym(world: &mut NewsletterWorld, kinds: String, email: String) {
+    let pseudonyms = world.pseudonyms.as_ref().expect("addresses are not pseudonymized");
+    let pseudonym = pseudonyms.pseudonym(&email);
+    let expected: Vec<String> = kinds.split(", ").map(|kind| format!("{kind} {pseudonym}")).collect();
+    assert_eq!(world.publisher.published(), expected, "Unexpected published events");
+}
+
+#[then(regex = r#"^the history should show "([^"]+)" as (pending|active|unsubscribed|suppressed)$"#)]
+async fn history_should_show(world: &mut NewsletterWorld, email: String, status: String) {
+    let history = world.last_history.as_ref().expect("a history was looked up");
+    let state = history.state.as_ref().expect("a subscription in the history");
+    assert_eq!(state.email, email, "Unexpected address: {history:?}");
+    assert_eq!(Some(state.status), SubscriptionStatus::parse(&status), "Unexpected status: {history:?}");
+}
+
+#[then("the history should show no subscription")]
+async fn history_should_show_none(world: &mut NewsletterWorld) {
+    let history = world.last_history.as_ref().expect("a history was looked up");
+    assert!(history.state.is_none(), "Unexpected subscription: {history:?}");
+}
+
+#[then(regex = r#"^the history events should be "([^"]*)"$"#)]
+async fn history_events_should_be(world: &mut NewsletterWorld, expected: String) {
+    let history = world.last_history.as_ref().expect("a history was looked up");
+    let events: Vec<String> = history.events.iter().map(|e| format!("{} {}", e.kind, e.email)).collect();
+    assert_eq!(events.join(", "), expected, "Unexpected history events");
+}
+
+#[then(regex = r#"^the consent history should be "([^"]*)"$"#)]
+async fn consent_history_should_be(world: &mut NewsletterWorld, expected: String) {
+    let actions: Vec<&str> = world.last_consents.iter().map(|c| c.action.as_str()).collect();
+    assert_eq!(actions.join(", "), expected, "Unexpected consent history");
+}
+
+#[then(regex = r#"^the "([^"]+)" consent should record version "([^"]+)", source "([^"]+)" and IP "([^"]+)"$"#)]
+async fn consent_should_record(world: &mut NewsletterWorld, action: String, version: String, source: String, ip: String) {
+    let consent = world
+        .last_consents
+        .iter()
+        .find(|c| c.action.as_str() == action)
+        .unwrap_or_else(|| panic!("no {action} consent in {:?}", world.last_consents));
+    assert_eq!(consent.policy_version.as_deref(), Some(version.as_str()), "Unexpected policy version");
+    assert_eq!(consent.source.as_deref(), Some(source.as_str()), "Unexpected source");
+    assert_eq!(consent.ip_address.as_deref(), Some(ip.as_str()), "Unexpected IP address");
+}
+
 #[then(regex = r"^the list should contain (.+)$")]
 async fn list_should_contain_email(world: &mut NewsletterWorld, email: String) {
     let clean_email = email.trim_matches('"').to_string();
@@ -241,7 +1263,90 @@ async fn list_should_contain_email(world: &mut NewsletterWorld, email: String) {
     assert!(found, "List should contain email {}", clean_email);
 }
 
+#[then("the published service config should match the code")]
+async fn service_config_published(_world: &mut NewsletterWorld) {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/infrastructure/rpc/service_config.json");
+    let published: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).expect("service config file")).expect("valid JSON");
+    assert_eq!(
+        published,
+        service_config::service_config(),
+        "{path} is stale, regenerate it with `newsletter-admin service-config`"
+    );
+}
+
+#[then("every retried method should be defined in the protos")]
+async fn retried_methods_defined(_world: &mut NewsletterWorld) {
+    let mut defined = Vec::new();
+    for bytes in [
+        rpc::newsletter::v1::proto::FILE_DESCRIPTOR_SET,
+        rpc::newsletter::v2::proto::FILE_DESCRIPTOR_SET,
+        rpc::campaign::v1::proto::FILE_DESCRIPTOR_SET,
+        rpc::template::v1::proto::FILE_DESCRIPTOR_SET,
+        rpc::admin::v1::proto::FILE_DESCRIPTOR_SET,
+    ] {
+        let set = prost_types::FileDescriptorSet::decode(bytes).expect("valid descriptor set");
+        for file in set.file {
+            for service in &file.service {
+                for method in &service.method {
+                    defined.push(format!("/{}.{}/{}", file.package(), service.name(), method.name()));
+                }
+            }
+        }
+    }
+    for path in service_config::read_methods() {
+        assert!(defined.iter().any(|d| d == path), "{path} is not defined in the protos");
+    }
+}
+
+// Analytics export
+#[given(regex = r#"^an? (open|click) by "([^"]+)" was recorded (\d+) days? ago$"#)]
+async fn engagement_recorded(world: &mut NewsletterWorld, kind: String, email: String, days: i64) {
+    world.record_engagement(&kind, &email, days);
+}
+
+#[given(regex = r#"^a "([^"]+)" event of "([^"]+)" was recorded (\d+) days? ago$"#)]
+async fn subscription_event_recorded(world: &mut NewsletterWorld, event_type: String, email: String, days: i64) {
+    world.record_subscription_event(&event_type, &email, days);
+}
+
+#[when("the analytics export runs")]
+async fn analytics_export_runs(world: &mut NewsletterWorld) {
+    world.export_analytics().await;
+}
+
+#[then(regex = r"^the analytics export should have written (\d+) partitions?$")]
+async fn analytics_partitions_written(world: &mut NewsletterWorld, count: usize) {
+    assert_eq!(world.last_export.len(), count, "partitions written: {:?}", world.last_export);
+}
+
+#[then(regex = r#"^the "([^"]+)" partition of (\d+) days? ago should hold (\d+) rows?$"#)]
+async fn analytics_partition_rows(world: &mut NewsletterWorld, dataset: String, days: i64, count: usize) {
+    let dataset = Dataset::parse(&dataset).expect("known dataset in scenario");
+    let day = (NewsletterWorld::analytics_now() - chrono::Duration::days(days)).date_naive();
+    let name = partition(dataset, day);
+    let rows = world.analytics_sink.partition(&name).unwrap_or_else(|| panic!("{name} was not written"));
+    assert_eq!(rows.len(), count, "rows in {name}");
+}
+
+#[then(regex = r#"^no exported row should mention "([^"]+)"$"#)]
+async fn exported_rows_do_not_mention(world: &mut NewsletterWorld, email: String) {
+    for rows in world.analytics_sink.rows() {
+        let emails: Vec<String> = match rows {
+            DailyRows::Engagement(rows) => rows.into_iter().map(|r| r.email).collect(),
+            DailyRows::SubscriptionEvents(rows) => rows
+                .into_iter()
+                .flat_map(|r| std::iter::once(r.email).chain(r.previous_email))
+                .collect(),
+        };
+        assert!(!emails.contains(&email), "{email} was exported");
+    }
+}
+
 #[tokio::test]
 async fn run_cucumber_tests() {
+    #[cfg(feature = "postgres-tests")]
+    let _postgres = common::postgres::start().await;
+
     NewsletterWorld::run("tests/features").await;
-}
\ No newline at end of file
+}
@@ -0,0 +1,43 @@
+Feature: Analytics export
+  As a data analyst
+  I want engagement and subscription events copied to the warehouse bucket day by day
+  So that I can query them without touching the production database
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: Every finished day of both datasets is written once
+    Given an open by "first@example.com" was recorded 2 days ago
+    And a click by "second@example.com" was recorded 2 days ago
+    And a "newsletter.confirmed" event of "first@example.com" was recorded 3 days ago
+    When the analytics export runs
+    Then the analytics export should have written 5 partitions
+    And the "engagement_events" partition of 2 days ago should hold 2 rows
+    And the "engagement_events" partition of 1 day ago should hold 0 rows
+    And the "subscription_events" partition of 3 days ago should hold 1 row
+    And the "subscription_events" partition of 1 day ago should hold 0 rows
+    When the analytics export runs
+    Then the analytics export should have written 0 partitions
+
+  Scenario: Today is left until it ends
+    Given an open by "first@example.com" was recorded 0 days ago
+    When the analytics export runs
+    Then the analytics export should have written 0 partitions
+
+  Scenario: A long backlog is caught up a week at a time
+    Given an open by "first@example.com" was recorded 10 days ago
+    When the analytics export runs
+    Then the analytics export should have written 7 partitions
+    When the analytics export runs
+    Then the analytics export should have written 3 partitions
+    And the "engagement_events" partition of 1 day ago should hold 0 rows
+
+  Scenario: Addresses are exported as pseudonyms
+    Given subscriber addresses are pseudonymized with key "analytics-key"
+    And a click by "first@example.com" was recorded 1 day ago
+    And a "newsletter.email_changed" event of "second@example.com" was recorded 1 day ago
+    When the analytics export runs
+    Then the "engagement_events" partition of 1 day ago should hold 1 row
+    And no exported row should mention "first@example.com"
+    And no exported row should mention "second@example.com"
@@ -0,0 +1,40 @@
+Feature: Subscriber attributes
+  As a marketing team
+  I want to store custom fields such as a first name on subscriptions
+  So that campaigns can be personalised and segmented
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: Set and remove a registered attribute
+    Given I have subscribed email "ada@example.com"
+    When I set attribute "first_name" to "Ada" for "ada@example.com"
+    Then the operation should complete successfully
+    And attribute "first_name" should be "Ada"
+    When I set attribute "first_name" to null for "ada@example.com"
+    Then attribute "first_name" should not be set
+
+  Scenario: Unregistered attributes are rejected
+    Given I have subscribed email "ada@example.com"
+    When I set attribute "shoe_size" to 42 for "ada@example.com"
+    Then the operation should fail with "unknown attribute: shoe_size"
+
+  Scenario: Attribute values must match the registered type
+    Given I have subscribed email "ada@example.com"
+    When I set attribute "locale" to true for "ada@example.com"
+    Then the operation should fail with "attribute locale must be a string"
+
+  Scenario: Attributes need a subscription
+    When I set attribute "first_name" to "Ghost" for "ghost@example.com"
+    Then the operation should fail with "not subscribed"
+
+  Scenario: Filter subscriptions by attribute
+    Given I have subscribed email "blog1@example.com"
+    And I have subscribed email "blog2@example.com"
+    And I have subscribed email "ads@example.com"
+    When I set attribute "signup_source" to "blog" for "blog2@example.com"
+    And I set attribute "signup_source" to "blog" for "blog1@example.com"
+    And I set attribute "signup_source" to "ads" for "ads@example.com"
+    And I list subscriptions with attribute "signup_source" equal to "blog"
+    Then the listed emails should be "blog1@example.com, blog2@example.com"
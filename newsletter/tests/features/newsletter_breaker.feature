@@ -0,0 +1,46 @@
+Feature: Circuit breaker around the database
+  As an operator
+  I want calls refused at once while Postgres is down
+  So that callers are not stuck waiting on pool timeouts and the database can recover
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: The breaker opens once enough reads fail
+    Given the circuit breaker opens when 50% of the last 4 reads fail, for 50 ms
+    When 2 reads through the breaker succeed
+    And 2 reads through the breaker fail with a dropped connection
+    Then the circuit breaker should be open
+    And the circuit breaker should have opened 1 time and refused 0 reads
+
+  Scenario: An open breaker refuses reads without reaching the database
+    Given the circuit breaker opens when 50% of the last 4 reads fail, for 5000 ms
+    When 4 reads through the breaker fail with a dropped connection
+    And 3 reads through the breaker succeed
+    Then 0 of them should have reached the database
+    And the last read should be refused as unavailable
+    And the circuit breaker should have opened 1 time and refused 3 reads
+
+  Scenario: A successful trial read closes the breaker
+    Given the circuit breaker opens when 50% of the last 4 reads fail, for 50 ms
+    When 4 reads through the breaker fail with a dropped connection
+    And the circuit breaker's open time has passed
+    Then the circuit breaker should be half-open
+    When 1 read through the breaker succeeds
+    Then 1 of them should have reached the database
+    And the circuit breaker should be closed
+
+  Scenario: A failed trial read opens the breaker again
+    Given the circuit breaker opens when 50% of the last 4 reads fail, for 50 ms
+    When 4 reads through the breaker fail with a dropped connection
+    And the circuit breaker's open time has passed
+    And 1 read through the breaker fails with a dropped connection
+    Then the circuit breaker should be open
+    And the circuit breaker should have opened 2 times and refused 0 reads
+
+  Scenario: Failures of the call itself do not open the breaker
+    Given the circuit breaker opens when 50% of the last 4 reads fail, for 50 ms
+    When 4 reads through the breaker fail with a serialization failure
+    Then the circuit breaker should be closed
+    And the circuit breaker should have opened 0 times and refused 0 reads
@@ -0,0 +1,38 @@
+Feature: Cached reads
+  As the preference center
+  I want subscription status and stats served from a cache
+  So that page views do not all reach the database
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+    And reads are cached
+
+  Scenario: A cached status is served until the service changes it
+    When I subscribe email "reader@example.com"
+    Then "reader@example.com" should be active
+    When "reader@example.com" is deactivated behind the service's back
+    Then "reader@example.com" should be active
+    When I unsubscribe email "reader@example.com"
+    Then "reader@example.com" should not be active
+
+  Scenario: Subscribing again after unsubscribing is seen at once
+    When I subscribe email "back@example.com"
+    And I unsubscribe email "back@example.com"
+    Then "back@example.com" should not be active
+    When I subscribe email "back@example.com"
+    Then "back@example.com" should be active
+
+  Scenario: Cached stats follow subscribes and unsubscribes in their tenant
+    When I read the stats of tenant "acme"
+    Then the stats should show 0 active, 0 inactive and 0 unsubscribed
+    When tenant "acme" subscribes email "one@example.com"
+    And tenant "acme" subscribes email "two@example.com"
+    And tenant "globex" subscribes email "three@example.com"
+    And I read the stats of tenant "acme"
+    Then the stats should show 2 active, 0 inactive and 0 unsubscribed
+    When tenant "acme" unsubscribes email "two@example.com"
+    And I read the stats of tenant "acme"
+    Then the stats should show 1 active, 0 inactive and 1 unsubscribed
+    When I read the stats of tenant "globex"
+    Then the stats should show 1 active, 0 inactive and 0 unsubscribed
@@ -0,0 +1,83 @@
+Feature: Warm-up of new sending domains
+  As an operator
+  I want campaigns from a domain new to sending to ramp up day by day
+  So that mailbox providers build trust in it before it sends in bulk
+
+  Background:
+    Given the newsletter service is running
+
+  Scenario: A new sending domain sends at most its first day's cap
+    Given campaigns warm up by "2, 5, 10"
+    And the tenant sends campaigns from the verified domain "news.example.org"
+    And the campaign is sending to 4 subscribers
+    When a campaign batch is sent
+    Then 2 campaign emails should have been sent
+    And 2 campaign deliveries should be pending
+
+  Scenario: The rest of the campaign waits once the day's cap is used up
+    Given campaigns warm up by "2, 5, 10"
+    And the tenant sends campaigns from the verified domain "news.example.org"
+    And the campaign is sending to 4 subscribers
+    When a campaign batch is sent
+    And a campaign batch is sent
+    Then 2 campaign emails should have been sent
+    And 2 campaign deliveries should be pending
+
+  Scenario: The cap grows on the next day of the ramp
+    Given campaigns warm up by "2, 5, 10"
+    And the tenant sends campaigns from the verified domain "news.example.org"
+    And "news.example.org" started warming up 1 day ago
+    And the campaign is sending to 8 subscribers
+    When a campaign batch is sent
+    Then 5 campaign emails should have been sent
+    And 3 campaign deliveries should be pending
+
+  Scenario: A domain past the last step sends without a cap
+    Given campaigns warm up by "2, 5, 10"
+    And the tenant sends campaigns from the verified domain "news.example.org"
+    And "news.example.org" started warming up 3 days ago
+    And the campaign is sending to 20 subscribers
+    When a campaign batch is sent
+    Then 20 campaign emails should have been sent
+    And the campaign should be sent
+
+  Scenario: Sends a batch did not use go back to the day's cap
+    Given campaigns warm up by "5"
+    And the tenant sends campaigns from the verified domain "news.example.org"
+    And the campaign is sending to 2 subscribers
+    And 4 more subscribers are held for their local send hours
+    When a campaign batch is sent
+    And the held deliveries come due
+    And a campaign batch is sent
+    Then 5 campaign emails should have been sent
+    And 1 campaign delivery should be pending
+
+  Scenario: The EMAIL_FROM domain is not ramped unless listed
+    Given campaigns warm up by "2"
+    And the campaign is sending to 4 subscribers
+    When a campaign batch is sent
+    Then 4 campaign emails should have been sent
+    And the campaign should be sent
+
+  Scenario: A listed EMAIL_FROM domain ramps
+    Given campaigns warm up by "2"
+    And the EMAIL_FROM domain is listed for warm-up
+    And the campaign is sending to 4 subscribers
+    When a campaign batch is sent
+    Then 2 campaign emails should have been sent
+
+  Scenario: A skipped domain sends without a cap
+    Given campaigns warm up by "2"
+    And the tenant sends campaigns from the verified domain "news.example.org"
+    And "news.example.org" skips warm-up
+    And the campaign is sending to 4 subscribers
+    When a campaign batch is sent
+    Then 4 campaign emails should have been sent
+
+  Scenario: A ramp started by hand counts from the day set
+    Given campaigns warm up by "2, 5, 10"
+    And the tenant sends campaigns from the verified domain "news.example.org"
+    And the warm-up of "news.example.org" is set to have started 2 days ago
+    And the campaign is sending to 12 subscribers
+    When a campaign batch is sent
+    Then 10 campaign emails should have been sent
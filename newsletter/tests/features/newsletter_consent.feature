@@ -0,0 +1,39 @@
+Feature: Consent history
+  As the legal team
+  I want every opt-in step recorded with its circumstances
+  So that we can prove each subscriber consented
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: Double opt-in records the consent given and confirmed
+    When I subscribe email "legal@example.com" under consent version "2026-10" from "footer-form"
+    And I list the consent history for "legal@example.com"
+    Then the consent history should be "confirmed, given"
+    And the "given" consent should record version "2026-10", source "footer-form" and IP "203.0.113.7"
+
+  Scenario: An unconfirmed subscription only records the consent given
+    When I request a subscription for "pending@example.com"
+    And I list the consent history for "pending@example.com"
+    Then the consent history should be "given"
+
+  Scenario: Subscribing an active address again records nothing
+    Given I have subscribed email "legal@example.com"
+    When I subscribe email "legal@example.com" again
+    And I list the consent history for "legal@example.com"
+    Then the consent history should be "confirmed, given"
+
+  Scenario: The history records the unsubscribe
+    Given I have subscribed email "legal@example.com"
+    When I unsubscribe email "legal@example.com"
+    And I list the consent history for "Legal@Example.com"
+    Then the subscription of "legal@example.com" should be unsubscribed
+    And the consent history should be "withdrawn, confirmed, given"
+
+  Scenario: Pseudonymized records are still listed under the address
+    Given subscriber addresses are pseudonymized with key "consent-key"
+    And I have subscribed email "legal@example.com"
+    When I unsubscribe email "legal@example.com"
+    And I list the consent history for "legal@example.com"
+    Then the consent history should be "withdrawn, confirmed, given"
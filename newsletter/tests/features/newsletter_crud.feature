@@ -40,6 +40,20 @@ Feature: Newsletter CRUD Operations
     And the list should contain "list2@example.com"
     And the list should contain "list3@example.com"
 
+  Scenario: List subscriptions with a read mask
+    Given I have subscribed email "mask1@example.com"
+    And I have subscribed email "mask2@example.com"
+    When I list all subscriptions with only the fields "email"
+    Then the listed subscriptions should only carry their email
+
+  Scenario: Filter subscriptions by domain and sort by email
+    Given I have subscribed email "carol@example.com"
+    And I have subscribed email "alice@example.com"
+    And I have subscribed email "bob@other.org"
+    And I have subscribed email "dave@EXAMPLE.com"
+    When I list subscriptions at domain "@Example.com" ordered by "email desc"
+    Then the listed emails should be "dave@example.com, carol@example.com, alice@example.com"
+
   Scenario: List subscriptions when none exist
     When I list all subscriptions
     Then I should get 0 subscriptions
@@ -64,7 +78,7 @@ Feature: Newsletter CRUD Operations
     Given I have subscribed email "delete-me@example.com"
     When I unsubscribe email "delete-me@example.com"
     Then the subscription should be deleted successfully
-    And the email "delete-me@example.com" should not exist
+    And the subscription of "delete-me@example.com" should be unsubscribed
 
   Scenario: Delete non-existent newsletter subscription
     When I unsubscribe email "not-exists@example.com"
@@ -77,8 +91,8 @@ Feature: Newsletter CRUD Operations
     When I unsubscribe email "bulk1@example.com"
     And I unsubscribe email "bulk2@example.com"
     Then the operation should complete successfully
-    And the email "bulk1@example.com" should not exist
-    And the email "bulk2@example.com" should not exist
+    And the subscription of "bulk1@example.com" should be unsubscribed
+    And the subscription of "bulk2@example.com" should be unsubscribed
     And the email "bulk3@example.com" should still exist
 
   Scenario: Complex workflow - Subscribe, Update, and Delete
@@ -89,4 +103,11 @@ Feature: Newsletter CRUD Operations
     When I subscribe email "workflow@example.com"
     Then "workflow@example.com" should be active
     When I unsubscribe email "workflow@example.com"
-    Then the email "workflow@example.com" should not exist
\ No newline at end of file
+    Then the subscription of "workflow@example.com" should be unsubscribed
+
+  Scenario: Gmail aliases share one subscription when folding is enabled
+    Given Gmail alias folding is enabled
+    When I subscribe email "Jane.Doe+news@googlemail.com"
+    And I subscribe email "janedoe@gmail.com" again
+    Then the email "janedoe@gmail.com" should be active
+    And there should be only one subscription for "janedoe@gmail.com"
\ No newline at end of file
@@ -48,7 +48,7 @@ Feature: Newsletter CRUD Operations with Cucumber Expressions
     Given I have subscribed email "delete-me@example.com"
     When I unsubscribe email "delete-me@example.com"
     Then the subscription should be deleted successfully
-    And the email "delete-me@example.com" should not exist
+    And the subscription of "delete-me@example.com" should be unsubscribed
 
   Scenario: Delete non-existent subscription
     When I unsubscribe email "not-exists@example.com"
@@ -62,7 +62,7 @@ Feature: Newsletter CRUD Operations with Cucumber Expressions
     When I subscribe email "workflow@example.com"
     Then "workflow@example.com" should be active
     When I unsubscribe email "workflow@example.com"
-    Then the email "workflow@example.com" should not exist
+    Then the subscription of "workflow@example.com" should be unsubscribed
 
   Scenario: Bulk subscription with integer and string parameters
     When I subscribe 5 emails with domain "testdomain.com"
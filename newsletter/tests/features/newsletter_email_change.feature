@@ -0,0 +1,47 @@
+Feature: Changing the subscribed address
+  As a subscriber
+  I want to move my subscription to another address
+  So that I keep my topic choices and consent history instead of starting over
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: The subscription moves once the new address confirms
+    Given I have subscribed email "old@example.com"
+    When I opt out of topic "promotions" for "old@example.com"
+    And I ask to move "old@example.com" to "new@example.com"
+    And the job runner runs
+    Then confirmation emails should have been sent to "old@example.com, new@example.com"
+    And "old@example.com" should be active
+    When I confirm the new address
+    Then the operation should complete successfully
+    And the email old@example.com should not exist
+    And "new@example.com" should be active
+    When I get the preferences for "new@example.com"
+    Then topic "promotions" should be unsubscribed
+    And topic "weekly_digest" should be subscribed
+    When I list the consent history for "new@example.com"
+    Then the consent history should be "confirmed, confirmed, given"
+    When the outbox relay runs
+    Then the published events should be "newsletter.subscribed old@example.com, newsletter.confirmed old@example.com, newsletter.email_changed new@example.com"
+
+  Scenario: An address with a subscription of its own cannot be taken over
+    Given I have subscribed email "old@example.com"
+    And I have subscribed email "taken@example.com"
+    When I ask to move "old@example.com" to "taken@example.com"
+    Then the operation should fail with "taken@example.com is already subscribed"
+
+  Scenario: Only an active subscription can move
+    When I request a subscription for "pending@example.com"
+    And I ask to move "pending@example.com" to "new@example.com"
+    Then the operation should fail with "only an active subscription can change its address"
+
+  Scenario: A change the new address did not confirm in time is dropped
+    Given I have subscribed email "old@example.com"
+    When I ask to move "old@example.com" to "new@example.com"
+    And the confirmation tokens expire
+    And I confirm the new address
+    Then the operation should fail with "invalid or expired"
+    And "old@example.com" should be active
+    And the email new@example.com should not exist
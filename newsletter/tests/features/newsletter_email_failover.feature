@@ -0,0 +1,98 @@
+Feature: Failover between email providers
+  As an operator
+  I want mail to move to another provider when one fails or runs out of quota
+  So that confirmations and campaigns keep going out
+
+  Background:
+    Given the newsletter service is running
+
+  Scenario: Priority sends everything through the first provider
+    Given email providers "primary, backup" tried by priority
+    When 3 emails are sent through the providers
+    Then email provider "primary" should have sent 3 emails in 3 attempts
+    And email provider "backup" should have sent 0 emails in 0 attempts
+
+  Scenario: A transient failure moves the message to the next provider
+    Given email providers "primary, backup" tried by priority
+    And email provider "primary" is failing transiently
+    When 2 emails are sent through the providers
+    Then email provider "primary" should have sent 0 emails in 2 attempts
+    And email provider "backup" should have sent 2 emails in 2 attempts
+
+  Scenario: A provider that keeps failing is taken out of rotation
+    Given email providers "primary, backup" tried by priority
+    And email providers leave rotation after 2 failures in a row, for 5000 ms
+    And email provider "primary" is failing transiently
+    When 4 emails are sent through the providers
+    Then email provider "primary" should have sent 0 emails in 2 attempts
+    And email provider "backup" should have sent 4 emails in 4 attempts
+
+  Scenario: A provider comes back after its cooldown
+    Given email providers "primary, backup" tried by priority
+    And email providers leave rotation after 1 failure in a row, for 50 ms
+    And email provider "primary" is failing transiently
+    When 1 email is sent through the providers
+    And email provider "primary" recovers
+    And the email providers' cooldown has passed
+    And 1 email is sent through the providers
+    Then email provider "primary" should have sent 1 email in 2 attempts
+    And email provider "backup" should have sent 1 email in 1 attempt
+
+  Scenario: A 5xx reply from a provider moves the message to the next one
+    Given email providers "primary, backup" tried by priority
+    And email providers leave rotation after 2 failures in a row, for 5000 ms
+    And email provider "primary" is failing permanently
+    When 3 emails are sent through the providers
+    Then email provider "primary" should have sent 0 emails in 2 attempts
+    And email provider "backup" should have sent 3 emails in 3 attempts
+
+  Scenario: A rejected message is not tried with another provider
+    Given email providers "primary, backup" tried by priority
+    And email provider "primary" is rejecting messages
+    When 1 email is sent through the providers
+    Then the operation should fail with "550 mailbox unavailable"
+    And email provider "backup" should have sent 0 emails in 0 attempts
+
+  Scenario: Rejected messages do not take a provider out of rotation
+    Given email providers "primary, backup" tried by priority
+    And email providers leave rotation after 1 failure in a row, for 5000 ms
+    And email provider "primary" is rejecting messages
+    When 2 emails are sent through the providers
+    And email provider "primary" recovers
+    And 1 email is sent through the providers
+    Then email provider "primary" should have sent 1 email in 3 attempts
+    And email provider "backup" should have sent 0 emails in 0 attempts
+
+  Scenario: A transient failure is reported over a permanent one when every provider fails
+    Given email providers "primary, backup" tried by priority
+    And email provider "primary" is failing transiently
+    And email provider "backup" is failing permanently
+    When 1 email is sent through the providers
+    Then the operation should fail with "421 service not available"
+
+  Scenario: The message fails when every provider fails
+    Given email providers "primary, backup" tried by priority
+    And email provider "primary" is failing transiently
+    And email provider "backup" is failing transiently
+    When 1 email is sent through the providers
+    Then the operation should fail with "421 service not available"
+
+  Scenario: A provider at its daily quota is passed over
+    Given email providers "primary, backup" tried by priority
+    And email provider "primary" has a daily quota of 2
+    When 3 emails are sent through the providers
+    Then email provider "primary" should have sent 2 emails in 2 attempts
+    And email provider "backup" should have sent 1 email in 1 attempt
+
+  Scenario: Round robin takes turns
+    Given email providers "primary, backup" tried by round robin
+    When 4 emails are sent through the providers
+    Then email provider "primary" should have sent 2 emails in 2 attempts
+    And email provider "backup" should have sent 2 emails in 2 attempts
+
+  Scenario: Weights split mail between providers
+    Given email providers "primary, backup" tried by weight
+    And email provider "primary" has a weight of 0
+    When 3 emails are sent through the providers
+    Then email provider "primary" should have sent 0 emails in 0 attempts
+    And email provider "backup" should have sent 3 emails in 3 attempts
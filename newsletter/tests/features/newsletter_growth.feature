@@ -0,0 +1,65 @@
+Feature: List growth analytics
+  As a marketing analyst
+  I want daily snapshots of each list's growth
+  So that I can chart subscribers, confirmations and churn over time
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: The rollup snapshots the day's growth
+    Given I have subscribed email "first@example.com"
+    And I have subscribed email "second@example.com"
+    When I unsubscribe email "second@example.com"
+    And today's growth is rolled up
+    And I read the daily growth of the last 7 days
+    Then 1 daily snapshot should have been recorded
+    And the growth should have 1 point
+    And the latest growth point should start on today
+    And the latest growth point should show 1 subscriber, 2 confirms, 1 unsubscribe and a net growth of 1
+
+  Scenario: A day is only snapshotted once
+    Given I have subscribed email "first@example.com"
+    When today's growth is rolled up
+    And I subscribe email "second@example.com"
+    And today's growth is rolled up
+    And I read the daily growth of the last 7 days
+    Then 0 daily snapshots should have been recorded
+    And the latest growth point should show 1 subscriber, 1 confirm, 0 unsubscribes and a net growth of 1
+
+  Scenario: Unconfirmed signups are not counted
+    When I request a subscription for "pending@example.com"
+    And today's growth is rolled up
+    And I read the daily growth of the last 7 days
+    Then the latest growth point should show 0 subscribers, 0 confirms, 0 unsubscribes and a net growth of 0
+
+  Scenario: The nightly rollup covers every tenant
+    When tenant "acme" subscribes email "reader@acme.example"
+    And tenant "globex" subscribes email "reader@globex.example"
+    And tenant "globex" subscribes email "other@globex.example"
+    And today's growth is rolled up for every tenant
+    And I read the daily growth of the last 7 days in tenant "globex"
+    Then 2 daily snapshots should have been recorded
+    And the latest growth point should show 2 subscribers, 2 confirms, 0 unsubscribes and a net growth of 2
+
+  Scenario: Days are summed into weeks and months
+    Given I have subscribed email "first@example.com"
+    When today's growth is rolled up
+    And I read the weekly growth of the last 7 days
+    Then the latest growth point should start on this week's Monday
+    When I read the monthly growth of the last 31 days
+    Then the latest growth point should start on the first of this month
+    And the latest growth point should show 1 subscriber, 1 confirm, 0 unsubscribes and a net growth of 1
+
+  Scenario: Nothing is reported before the first rollup
+    Given I have subscribed email "first@example.com"
+    When I read the daily growth of the last 30 days
+    Then the growth should have 0 points
+
+  Scenario: A range that ends before it starts is rejected
+    When I read the daily growth from 2026-10-10 to 2026-10-01
+    Then the operation should fail with "before it starts"
+
+  Scenario: A range of too many points is rejected
+    When I read the daily growth from 2025-01-01 to 2026-10-01
+    Then the operation should fail with "at most 366 points"
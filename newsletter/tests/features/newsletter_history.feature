@@ -0,0 +1,50 @@
+Feature: Subscriber history
+  As a compliance officer
+  I want every change of a subscription recorded
+  So that I can tell what state a subscriber was in on a given day
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: The state at a past time is replayed from the recorded events
+    Given I have subscribed email "reader@example.com"
+    When I note the time
+    And I unsubscribe email "reader@example.com"
+    And I look up the history of "reader@example.com" at the noted time
+    Then the history should show "reader@example.com" as active
+    And the history events should be "newsletter.subscribed reader@example.com, newsletter.confirmed reader@example.com"
+    When I look up the history of "reader@example.com" now
+    Then the history should show "reader@example.com" as unsubscribed
+    And the history events should be "newsletter.subscribed reader@example.com, newsletter.confirmed reader@example.com, newsletter.unsubscribed reader@example.com"
+
+  Scenario: An address with no subscription at the time has no state
+    When I note the time
+    And I subscribe email "late@example.com"
+    And I look up the history of "late@example.com" at the noted time
+    Then the history should show no subscription
+    And the history events should be ""
+
+  Scenario: The history survives the outbox purging published events
+    Given I have subscribed email "kept@example.com"
+    When the outbox relay runs
+    And I purge the published outbox events
+    And I look up the history of "kept@example.com" now
+    Then the history should show "kept@example.com" as active
+
+  Scenario: A changed address carries the history of the one it replaced
+    Given I have subscribed email "old@example.com"
+    When I ask to move "old@example.com" to "new@example.com"
+    And I confirm the new address
+    And I look up the history of "new@example.com" now
+    Then the history should show "new@example.com" as active
+    And the history events should be "newsletter.subscribed old@example.com, newsletter.confirmed old@example.com, newsletter.email_changed new@example.com"
+
+  Scenario: Deleting an unsubscribed address by retention erases its history
+    Given unsubscribed subscriptions are deleted after 0 days
+    And I have subscribed email "gone@example.com"
+    When I unsubscribe email "gone@example.com"
+    And the retention job runs
+    And I look up the history of "gone@example.com" now
+    Then the history should show no subscription
+    And the history events should be ""
@@ -0,0 +1,69 @@
+Feature: Newsletter list import
+  As a marketing team
+  I want to upload subscriber lists exported from other tools
+  So that existing audiences can be migrated without losing anyone
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: Import a Mailchimp CSV export uploaded in small chunks
+    Given I have subscribed email "existing@example.com"
+    When I import this CSV in chunks of 7 bytes:
+      """
+      Email Address,First Name,Last Name
+      new1@example.com,Ann,"Smith, Jr."
+      NEW1@example.com,Ann,Smith
+      existing@example.com,Bob,"multi
+      line"
+      not-an-email,Eve,Doe
+      new2@example.com,Joe,Doe
+      """
+    Then the import should report 2 imported, 2 skipped and 1 invalid
+    And the email "new1@example.com" should be active
+    And the email "new2@example.com" should be active
+
+  Scenario: Import NDJSON
+    When I import this NDJSON in chunks of 5 bytes:
+      """
+      {"email": "json1@example.com", "source": "crm"}
+      "json2@example.com"
+      {"name": "no email"}
+      """
+    Then the import should report 2 imported, 0 skipped and 1 invalid
+    And the email "json1@example.com" should be active
+
+  Scenario: A dry run reports the summary without storing anything
+    Given I have subscribed email "existing@example.com"
+    When I dry-run the import of this CSV in chunks of 16 bytes:
+      """
+      email
+      fresh@example.com
+      existing@example.com
+      fresh@example.com
+      broken
+      """
+    Then the import should report 1 imported, 2 skipped and 1 invalid
+    And the email "fresh@example.com" should not exist
+
+  Scenario: A throttled import runs while the store has room
+    Given imports are throttled to wait 100 ms for the store
+    And the store is free
+    When I import this CSV in chunks of 8 bytes:
+      """
+      email
+      calm1@example.com
+      calm2@example.com
+      """
+    Then the import should report 2 imported, 0 skipped and 0 invalid
+
+  Scenario: A saturated store pushes the import back
+    Given imports are throttled to wait 100 ms for the store
+    And the store is saturated
+    When I import this CSV in chunks of 8 bytes:
+      """
+      email
+      busy@example.com
+      """
+    Then the operation should fail with "the store is too busy, records up to 0 were imported"
+    And the email "busy@example.com" should not exist
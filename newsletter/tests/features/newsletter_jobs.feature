@@ -0,0 +1,18 @@
+Feature: Background jobs
+  As an operator
+  I want slow or failure-prone work to run from a durable queue
+  So that a subscribe request neither waits on nor fails with the mail provider
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: The confirmation email is sent by the job runner
+    When I request a subscription for "jobs@example.com"
+    Then the operation should complete successfully
+    And 1 confirmation email should be queued
+    And confirmation emails should have been sent to ""
+    When the job runner runs
+    Then the operation should complete successfully
+    And 0 confirmation emails should be queued
+    And confirmation emails should have been sent to "jobs@example.com"
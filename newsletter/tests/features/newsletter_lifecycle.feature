@@ -0,0 +1,60 @@
+Feature: Subscription lifecycle
+  As a newsletter operator
+  I want every subscription to move through pending, active, unsubscribed and suppressed
+  So that its history is kept and blocked addresses stay blocked
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: Unsubscribing keeps the subscription
+    Given I have subscribed email "keep@example.com"
+    When I unsubscribe email "keep@example.com"
+    Then the subscription of "keep@example.com" should be unsubscribed
+    And "keep@example.com" should not be active
+
+  Scenario: Signing up again after unsubscribing starts over as pending
+    Given I have subscribed email "back@example.com"
+    When I unsubscribe email "back@example.com"
+    And I request a subscription for "back@example.com"
+    Then the subscription of "back@example.com" should be pending
+    When I subscribe email "back@example.com"
+    Then the subscription of "back@example.com" should be active
+
+  Scenario: A suppressed address cannot subscribe
+    Given I have subscribed email "bounced@example.com"
+    When I set the status of "bounced@example.com" to suppressed
+    And I subscribe email "bounced@example.com"
+    Then the operation should fail with "is suppressed"
+    And the subscription of "bounced@example.com" should be suppressed
+
+  Scenario: A suppressed address cannot be reactivated
+    Given I have subscribed email "blocked@example.com"
+    When I set the status of "blocked@example.com" to suppressed
+    And I set the status of "blocked@example.com" to active
+    Then the operation should fail with "cannot move a suppressed subscription to active"
+    And the subscription of "blocked@example.com" should be suppressed
+
+  Scenario: Lifting a suppression leaves the address unsubscribed
+    Given I have subscribed email "lifted@example.com"
+    When I set the status of "lifted@example.com" to suppressed
+    And I set the status of "lifted@example.com" to unsubscribed
+    Then the operation should complete successfully
+    And the subscription of "lifted@example.com" should be unsubscribed
+
+  Scenario: Previewing a status update lists forbidden moves and changes nothing
+    Given I have subscribed email "moving@example.com"
+    And I have subscribed email "stuck@example.com"
+    When I set the status of "stuck@example.com" to suppressed
+    And I preview setting the status of "moving@example.com, stuck@example.com, new@example.com" to active
+    Then the operation should complete successfully
+    And the preview should report 0 changed, 1 unchanged, 1 created and 0 not found
+    And the preview should reject "stuck@example.com"
+    And the subscription of "stuck@example.com" should be suppressed
+    And the email "new@example.com" should not exist
+
+  Scenario: Previewing a delete counts the known addresses
+    Given I have subscribed email "going@example.com"
+    When I preview deleting "going@example.com, unknown@example.com"
+    Then the preview should report 1 changed, 0 unchanged, 0 created and 1 not found
+    And the email "going@example.com" should still exist
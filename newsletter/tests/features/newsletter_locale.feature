@@ -0,0 +1,25 @@
+Feature: Subscriber locale
+  As a marketing team
+  I want to know the language each subscriber prefers
+  So that campaigns reach them in a translation they can read
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: Set and clear a locale
+    Given I have subscribed email "ada@example.com"
+    When I set the locale of "ada@example.com" to "EN-gb"
+    Then the operation should complete successfully
+    And the locale should be "en-GB"
+    When I clear the locale of "ada@example.com"
+    Then the locale should not be set
+
+  Scenario: Malformed locales are rejected
+    Given I have subscribed email "ada@example.com"
+    When I set the locale of "ada@example.com" to "english please"
+    Then the operation should fail with "invalid locale: english please"
+
+  Scenario: A locale needs a subscription
+    When I set the locale of "ghost@example.com" to "en"
+    Then the operation should fail with "not subscribed"
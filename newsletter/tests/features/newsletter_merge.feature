@@ -0,0 +1,42 @@
+Feature: Merging duplicate subscribers
+  As an operator
+  I want to fold a duplicate subscription into another
+  So that one mailbox gets one copy of each mailing and keeps its history
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: The duplicate's choices and history move to the kept address
+    Given I have subscribed email "jane@example.com"
+    And I have subscribed email "jane.doe@example.com"
+    When I opt out of topic "promotions" for "jane.doe@example.com"
+    And I merge "jane.doe@example.com" into "jane@example.com"
+    Then the operation should complete successfully
+    And the email jane.doe@example.com should not exist
+    And "jane@example.com" should be active
+    When I get the preferences for "jane@example.com"
+    Then topic "promotions" should be unsubscribed
+    When I list the consent history for "jane@example.com"
+    Then the consent history should be "confirmed, given, confirmed, given"
+    When the outbox relay runs
+    Then the published events should be "newsletter.subscribed jane@example.com, newsletter.confirmed jane@example.com, newsletter.subscribed jane.doe@example.com, newsletter.confirmed jane.doe@example.com, newsletter.merged jane@example.com"
+
+  Scenario: A suppression survives the merge
+    Given I have subscribed email "jane@example.com"
+    And I have subscribed email "jane.doe@example.com"
+    When I set the status of "jane.doe@example.com" to suppressed
+    And I merge "jane.doe@example.com" into "jane@example.com"
+    Then the operation should complete successfully
+    And the subscription of "jane@example.com" should be suppressed
+
+  Scenario: Both addresses need a subscription
+    Given I have subscribed email "jane@example.com"
+    When I merge "nobody@example.com" into "jane@example.com"
+    Then the operation should fail with "nobody@example.com is not subscribed"
+    And "jane@example.com" should be active
+
+  Scenario: An address cannot be merged into itself
+    Given I have subscribed email "jane@example.com"
+    When I merge "jane@example.com" into "jane@example.com"
+    Then the operation should fail with "merged_email is the kept address"
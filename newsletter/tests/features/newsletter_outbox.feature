@@ -0,0 +1,56 @@
+Feature: Subscription event outbox
+  As a downstream consumer
+  I want to hear about every committed subscription change
+  So that my copy of the subscriber list never drifts from the service
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: Events wait in the outbox until the relay publishes them
+    When I subscribe email "outbox@example.com"
+    Then no subscription events should have been published
+    When the outbox relay runs
+    Then the operation should complete successfully
+    And the published events should be "newsletter.subscribed outbox@example.com, newsletter.confirmed outbox@example.com"
+
+  Scenario: Published events carry pseudonyms when configured
+    Given subscriber addresses are pseudonymized with key "outbox-key"
+    When I subscribe email "Outbox@example.com"
+    And the outbox relay runs
+    Then the published events should be "newsletter.subscribed, newsletter.confirmed" for the pseudonym of "outbox@example.com"
+
+  Scenario: Relayed events are not published again
+    Given I have subscribed email "outbox@example.com"
+    When I unsubscribe email "outbox@example.com"
+    And the outbox relay runs
+    And the outbox relay runs
+    Then the published events should be "newsletter.subscribed outbox@example.com, newsletter.confirmed outbox@example.com, newsletter.unsubscribed outbox@example.com"
+
+  Scenario: Replayed events are published again
+    Given I have subscribed email "replay@example.com"
+    When the outbox relay runs
+    And I replay the outbox events sent in the last hour
+    And the outbox relay runs
+    Then the published events should be "newsletter.subscribed replay@example.com, newsletter.confirmed replay@example.com, newsletter.subscribed replay@example.com, newsletter.confirmed replay@example.com"
+
+  Scenario: Events sent before the replay window stay published
+    Given I have subscribed email "settled@example.com"
+    When the outbox relay runs
+    And I replay the outbox events sent from now on
+    And the outbox relay runs
+    Then the published events should be "newsletter.subscribed settled@example.com, newsletter.confirmed settled@example.com"
+
+  Scenario: The backlog counts the events the relay has not published
+    When I subscribe email "backlog@example.com"
+    Then the outbox backlog should hold 2 pending events, 0 failing
+    When the outbox relay runs
+    Then the outbox backlog should hold 0 pending events, 0 failing
+    And the relay should have published 2 events and failed 0 times
+
+  Scenario: Events that fail to publish stay in the backlog
+    Given the event publisher is down
+    When I subscribe email "stuck@example.com"
+    And the outbox relay runs
+    Then the outbox backlog should hold 2 pending events, 2 failing
+    And the relay should have published 0 events and failed 2 times, last with "broker unreachable"
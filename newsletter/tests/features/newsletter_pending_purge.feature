@@ -0,0 +1,37 @@
+Feature: Purging unconfirmed subscriptions
+  As a newsletter operator
+  I want signups that were never confirmed cleared out after a while
+  So that the list only keeps addresses whose owners asked to be on it
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: A lapsed signup is purged with its link
+    Given confirmation links expire at once
+    When I request a subscription for "late@example.com"
+    And expired pending subscriptions are purged
+    Then 1 pending subscription should have been deleted and 0 reverted
+    And the email late@example.com should not exist
+
+  Scenario: A lapsed signup is kept for the retention
+    Given confirmation links expire at once and lapsed signups are kept for 48 hours
+    When I request a subscription for "late@example.com"
+    And expired pending subscriptions are purged
+    Then 0 pending subscriptions should have been deleted and 0 reverted
+    And the subscription of "late@example.com" should be pending
+
+  Scenario: A signup with a live link is not purged
+    When I request a subscription for "fresh@example.com"
+    And expired pending subscriptions are purged
+    Then 0 pending subscriptions should have been deleted and 0 reverted
+    And the subscription of "fresh@example.com" should be pending
+
+  Scenario: A lapsed resubscription goes back to unsubscribed
+    Given I have subscribed email "back@example.com"
+    And confirmation links expire at once
+    When I unsubscribe email "back@example.com"
+    And I request a subscription for "back@example.com"
+    And expired pending subscriptions are purged
+    Then 0 pending subscriptions should have been deleted and 1 reverted
+    And the subscription of "back@example.com" should be unsubscribed
@@ -0,0 +1,38 @@
+Feature: Topic preferences
+  As a subscriber
+  I want to choose which kinds of mail I receive
+  So that I can stay subscribed without getting everything
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: New subscribers receive every topic by default
+    Given I have subscribed email "prefs@example.com"
+    When I get the preferences for "prefs@example.com"
+    Then the operation should complete successfully
+    And I should see 3 topics
+    And topic "weekly_digest" should be subscribed
+
+  Scenario: Opt out of a single topic
+    Given I have subscribed email "prefs@example.com"
+    When I opt out of topic "promotions" for "prefs@example.com"
+    And I get the preferences for "prefs@example.com"
+    Then topic "promotions" should be unsubscribed
+    And topic "product_updates" should be subscribed
+    And "prefs@example.com" should be active
+
+  Scenario: Opt back in after opting out
+    Given I have subscribed email "prefs@example.com"
+    When I opt out of topic "weekly_digest" for "prefs@example.com"
+    And I opt in to topic "weekly_digest" for "prefs@example.com"
+    Then topic "weekly_digest" should be subscribed
+
+  Scenario: Unknown topics are rejected
+    Given I have subscribed email "prefs@example.com"
+    When I opt out of topic "horoscopes" for "prefs@example.com"
+    Then the operation should fail with "unknown topic: horoscopes"
+
+  Scenario: Preferences need a subscription
+    When I get the preferences for "stranger@example.com"
+    Then the operation should fail with "not subscribed"
@@ -0,0 +1,53 @@
+Feature: Read model of the subscriber list
+  As an operator of a large list
+  I want stats and plain listings served from a table the relay keeps
+  So that dashboards do not compete with subscribes for the newsletters table
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+    And stats and listings are served from the read model
+
+  Scenario: Stats follow the events the relay has applied
+    When tenant "acme" subscribes email "one@example.com"
+    And tenant "acme" requests a subscription for "two@example.com"
+    And I read the stats of tenant "acme"
+    Then the stats should show 0 active, 0 inactive and 0 unsubscribed
+    When the outbox relay runs
+    And I read the stats of tenant "acme"
+    Then the stats should show 1 active, 1 inactive and 0 unsubscribed
+    When tenant "acme" unsubscribes email "one@example.com"
+    And the outbox relay runs
+    And I read the stats of tenant "acme"
+    Then the stats should show 0 active, 1 inactive and 1 unsubscribed
+    When I read the stats of tenant "globex"
+    Then the stats should show 0 active, 0 inactive and 0 unsubscribed
+
+  Scenario: Listings by status come from the read model, newest first
+    Given I have subscribed email "first@example.com"
+    And I have subscribed email "second@example.com"
+    And I have subscribed email "gone@example.com"
+    When I unsubscribe email "gone@example.com"
+    And I list the active subscriptions
+    Then the listed emails should be ""
+    When the outbox relay runs
+    And I list the active subscriptions
+    Then the listed emails should be "second@example.com, first@example.com"
+    When I list the unsubscribed subscriptions
+    Then the listed emails should be "gone@example.com"
+
+  Scenario: Addresses activated by an administrator reach the read model
+    When I set the status of "admin@example.com" to active
+    And the outbox relay runs
+    And I list the active subscriptions
+    Then the listed emails should be "admin@example.com"
+
+  Scenario: Replayed events leave the read model as it was
+    Given I have subscribed email "replay@example.com"
+    When I unsubscribe email "replay@example.com"
+    And the outbox relay runs
+    And I replay the outbox events sent in the last hour
+    And the outbox relay runs
+    And I list the unsubscribed subscriptions
+    Then the listed emails should be "replay@example.com"
+    And the operation should complete successfully
@@ -0,0 +1,47 @@
+Feature: Resubscribing after unsubscribing
+  As a newsletter operator
+  I want returning subscribers told apart from new ones
+  So that downstream systems and reports know who came back
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: A first subscription is not a resubscription
+    When I subscribe email "first@example.com"
+    And the outbox relay runs
+    Then "first@example.com" should not have been resubscribed
+    And the published events should be "newsletter.subscribed first@example.com, newsletter.confirmed first@example.com"
+
+  Scenario: Subscribing again after unsubscribing is recorded and confirmed again
+    Given I have subscribed email "back@example.com"
+    When I unsubscribe email "back@example.com"
+    And I request a subscription for "back@example.com"
+    Then the subscription of "back@example.com" should be pending
+    And "back@example.com" should have been resubscribed
+    When the outbox relay runs
+    Then the published events should be "newsletter.subscribed back@example.com, newsletter.confirmed back@example.com, newsletter.unsubscribed back@example.com, newsletter.resubscribed back@example.com"
+
+  Scenario: Returning subscribers can skip confirmation
+    Given returning subscribers skip confirmation
+    And I have subscribed email "quick@example.com"
+    When I unsubscribe email "quick@example.com"
+    And I request a subscription for "quick@example.com"
+    Then the operation should complete successfully
+    And the subscription of "quick@example.com" should be active
+    And "quick@example.com" should have been resubscribed
+    When the outbox relay runs
+    Then the published events should be "newsletter.subscribed quick@example.com, newsletter.confirmed quick@example.com, newsletter.unsubscribed quick@example.com, newsletter.resubscribed quick@example.com"
+
+  Scenario: Skipping confirmation does not apply to new addresses
+    Given returning subscribers skip confirmation
+    When I request a subscription for "new@example.com"
+    Then the subscription of "new@example.com" should be pending
+    And "new@example.com" should not have been resubscribed
+
+  Scenario: A resubscription left pending keeps its history when the token expires
+    Given I have subscribed email "lapsed@example.com"
+    When I unsubscribe email "lapsed@example.com"
+    And I request a subscription for "lapsed@example.com"
+    And the confirmation tokens expire
+    Then the subscription of "lapsed@example.com" should be unsubscribed
@@ -0,0 +1,61 @@
+Feature: Retention of unsubscribed subscriptions
+  As a newsletter operator
+  I want unsubscribed addresses anonymized or deleted after a while
+  So that we do not keep personal data longer than we need it
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: An unsubscribed address is anonymized but still counted
+    Given unsubscribed subscriptions are anonymized after 0 days
+    When tenant "acme" subscribes email "gone@example.com"
+    And tenant "acme" unsubscribes email "gone@example.com"
+    And the retention job runs
+    Then retention should have anonymized 1 and deleted 0 in tenant "acme"
+    And the email "gone@example.com" should not exist in tenant "acme"
+    When I read the stats of tenant "acme"
+    Then the stats should show 0 active, 0 inactive and 1 unsubscribed
+
+  Scenario: An unsubscribed address is deleted
+    Given unsubscribed subscriptions are deleted after 0 days
+    When tenant "acme" subscribes email "gone@example.com"
+    And tenant "acme" unsubscribes email "gone@example.com"
+    And the retention job runs
+    Then retention should have anonymized 0 and deleted 1 in tenant "acme"
+    And the email "gone@example.com" should not exist in tenant "acme"
+    When I read the stats of tenant "acme"
+    Then the stats should show 0 active, 0 inactive and 0 unsubscribed
+
+  Scenario: A recent unsubscribe is kept until its retention runs out
+    Given unsubscribed subscriptions are deleted after 30 days
+    When tenant "acme" subscribes email "recent@example.com"
+    And tenant "acme" unsubscribes email "recent@example.com"
+    And the retention job runs
+    Then retention should have anonymized 0 and deleted 0 in tenant "acme"
+    And the subscription of "recent@example.com" should be unsubscribed in tenant "acme"
+
+  Scenario: Active subscriptions are left alone
+    Given unsubscribed subscriptions are deleted after 0 days
+    When tenant "acme" subscribes email "reader@example.com"
+    And tenant "acme" subscribes email "gone@example.com"
+    And tenant "acme" unsubscribes email "gone@example.com"
+    And the retention job runs
+    Then retention should have anonymized 0 and deleted 1 in tenant "acme"
+    And the subscription of "reader@example.com" should be active in tenant "acme"
+
+  Scenario: Tenants override the default policy
+    Given unsubscribed subscriptions are anonymized after 0 days
+    And tenant "globex" deletes unsubscribed subscriptions after 0 days
+    And tenant "initech" keeps unsubscribed subscriptions forever
+    When tenant "acme" subscribes email "gone@example.com"
+    And tenant "acme" unsubscribes email "gone@example.com"
+    And tenant "globex" subscribes email "gone@example.com"
+    And tenant "globex" unsubscribes email "gone@example.com"
+    And tenant "initech" subscribes email "gone@example.com"
+    And tenant "initech" unsubscribes email "gone@example.com"
+    And the retention job runs
+    Then retention should have anonymized 1 and deleted 0 in tenant "acme"
+    And retention should have anonymized 0 and deleted 1 in tenant "globex"
+    And retention should have anonymized 0 and deleted 0 in tenant "initech"
+    And the subscription of "gone@example.com" should be unsubscribed in tenant "initech"
@@ -0,0 +1,38 @@
+Feature: Retrying transient database errors
+  As an operator
+  I want reads retried when Postgres hiccups
+  So that a serialization failure or a dropped connection does not reach the caller
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: A serialization failure is retried until the read succeeds
+    Given repository retries allow 3 attempts and a budget of 10
+    When a read fails 2 times with a serialization failure
+    Then the read should succeed after 3 attempts
+    And 2 retries should be counted, 1 recovered, 0 exhausted and 0 over budget
+
+  Scenario: A dropped connection is retried
+    Given repository retries allow 3 attempts and a budget of 10
+    When a read fails 1 time with a dropped connection
+    Then the read should succeed after 2 attempts
+    And 1 retry should be counted, 1 recovered, 0 exhausted and 0 over budget
+
+  Scenario: Retries stop at the attempt limit
+    Given repository retries allow 3 attempts and a budget of 10
+    When a read fails 5 times with a serialization failure
+    Then the read should fail after 3 attempts
+    And 2 retries should be counted, 0 recovered, 1 exhausted and 0 over budget
+
+  Scenario: Errors that would repeat are not retried
+    Given repository retries allow 3 attempts and a budget of 10
+    When a read fails 1 time with a unique violation
+    Then the read should fail after 1 attempt
+    And 0 retries should be counted, 0 recovered, 0 exhausted and 0 over budget
+
+  Scenario: A spent budget stops retries
+    Given repository retries allow 3 attempts and a budget of 1
+    When a read fails 5 times with a serialization failure
+    Then the read should fail after 2 attempts
+    And 1 retry should be counted, 0 recovered, 0 exhausted and 1 over budget
@@ -0,0 +1,55 @@
+Feature: Subscriber search
+  As a support agent
+  I want to find a subscriber from part of their address or a misspelling of it
+  So that I can help readers who do not remember exactly how they signed up
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: A misspelled address finds the subscription
+    Given I have subscribed email "jon@example.com"
+    And I have subscribed email "alice@another.org"
+    When I search for "jon@exmple.com"
+    Then the search should find "jon@example.com"
+
+  Scenario: Part of an address finds every subscription containing it
+    Given I have subscribed email "maria@shop.example"
+    And I have subscribed email "mario@shop.example"
+    And I have subscribed email "bob@elsewhere.org"
+    When I search for "mari"
+    Then the search should find "maria@shop.example", "mario@shop.example"
+
+  Scenario: An exact match ranks first
+    Given I have subscribed email "anna.smith@example.com"
+    And I have subscribed email "anna@example.com"
+    When I search for "ANNA@example.com"
+    Then the search should find "anna@example.com", "anna.smith@example.com"
+    And "anna@example.com" should score 1
+    And "anna.smith@example.com" should score below 1
+
+  Scenario: Wildcards in the search text match literally
+    Given I have subscribed email "first_last@example.com"
+    And I have subscribed email "firstxlast@example.com"
+    When I search for "t_l"
+    Then the search should find "first_last@example.com"
+
+  Scenario: Unrelated addresses are not found
+    Given I have subscribed email "jon@example.com"
+    When I search for "zzzzqqqq"
+    Then the search should find nothing
+
+  Scenario: Results come a page at a time
+    Given I have subscribed email "team1@example.com"
+    And I have subscribed email "team2@example.com"
+    And I have subscribed email "team3@example.com"
+    When I search for "team" 2 at a time
+    Then the search should find "team1@example.com", "team2@example.com"
+    And there should be more search results
+    When I fetch the next page of search results
+    Then the search should find "team3@example.com"
+    And there should not be more search results
+
+  Scenario: A search text that is too short is rejected
+    When I search for "jo"
+    Then the operation should fail with "at least 3 characters"
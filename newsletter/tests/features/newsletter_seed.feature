@@ -0,0 +1,32 @@
+Feature: Seed data
+  As an operator of a demo or load test environment
+  I want to fill a tenant with generated subscribers
+  So that dashboards and campaigns have an audience without real addresses
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: Seeding stores every generated subscriber with its drawn status
+    When I seed 40 subscribers with seed 7
+    Then the seed should report 40 created
+    And the seeded subscribers should have their drawn statuses
+
+  Scenario: The same seed generates the same subscribers again
+    When I seed 25 subscribers with seed 3 on domain "demo.test"
+    And I seed 25 subscribers with seed 3 on domain "demo.test"
+    Then the seed should report 0 created
+    And every seeded address should end in "@demo.test"
+    And the seeded subscribers should have their drawn statuses
+
+  Scenario: Weights decide the statuses
+    When I seed 20 subscribers with seed 1 weighted 0 active, 1 unsubscribed and 0 suppressed
+    Then the seed should report 20 created, 0 active, 20 unsubscribed and 0 suppressed
+
+  Scenario: Invalid plans are refused
+    When I seed 0 subscribers with seed 1
+    Then the operation should fail with "count must be between 1 and 100000"
+    When I seed 5 subscribers with seed 1 on domain "not a domain"
+    Then the operation should fail with "is not a valid email domain"
+    When I seed 5 subscribers with seed 1 weighted 0 active, 0 unsubscribed and 0 suppressed
+    Then the operation should fail with "at least one status needs a weight above zero"
@@ -0,0 +1,10 @@
+Feature: Client service config
+  As a client developer
+  I want a service config telling my gRPC channel what it may retry
+  So that reads survive a restart of the server without my own retry code
+
+  Scenario: The published service config is up to date
+    Then the published service config should match the code
+
+  Scenario: Only methods of the protos are retried
+    Then every retried method should be defined in the protos
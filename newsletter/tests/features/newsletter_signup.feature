@@ -0,0 +1,22 @@
+Feature: Signup details
+  As a marketer
+  I want the locale, source and topics given at signup kept with the subscription
+  So that mail goes out in the right language and only on the chosen topics
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: Signup records locale, source and topics
+    When I sign up "signup@example.com" with locale "de-DE" for topics "weekly_digest"
+    Then the operation should complete successfully
+    And "signup@example.com" should be active
+    And the locale should be "de-DE"
+    And attribute "signup_source" should be "landing-page"
+    And topic "weekly_digest" should be subscribed
+    And topic "promotions" should be unsubscribed
+
+  Scenario: Unknown topics are refused before anything is stored
+    When I sign up "signup@example.com" with locale "de-DE" for topics "weekly_digest, horoscopes"
+    Then the operation should fail with "unknown topic: horoscopes"
+    And the email "signup@example.com" should not exist
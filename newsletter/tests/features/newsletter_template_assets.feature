@@ -0,0 +1,71 @@
+Feature: Collection of unused template assets
+  As an operator
+  I want assets no template uses any more to be deleted after a while
+  So that storage does not fill up, without breaking emails already sent
+
+  Background:
+    Given the newsletter service is running
+
+  Scenario: An asset no template uses is collected after the grace period
+    Given the asset "logo.png" was uploaded
+    When 25 hours pass
+    And unused assets are collected
+    Then 1 asset should have been collected
+    And the asset "logo.png" should be gone
+
+  Scenario: A fresh upload waits for the template that will use it
+    Given the asset "logo.png" was uploaded
+    When unused assets are collected
+    Then 0 assets should have been collected
+    And the asset "logo.png" should be kept
+
+  Scenario: An asset a template shows is kept
+    Given the asset "logo.png" was uploaded
+    And the campaign's template shows the asset "logo.png"
+    When 30 days pass
+    And unused assets are collected
+    Then the asset "logo.png" should be kept
+
+  Scenario: An old asset dropped from a template is kept for the grace period
+    Given the asset "logo.png" was uploaded
+    And the campaign's template shows the asset "logo.png"
+    And 30 days pass
+    When the campaign's template stops showing assets
+    And 12 hours pass
+    And unused assets are collected
+    Then the asset "logo.png" should be kept
+    When 13 hours pass
+    And unused assets are collected
+    Then the asset "logo.png" should be gone
+
+  Scenario: An old asset dropped from a translation is kept for the grace period
+    Given the asset "logo-de.png" was uploaded
+    And the campaign's template translation into "de" shows the asset "logo-de.png"
+    And 30 days pass
+    When the campaign's template translation into "de" stops showing assets
+    And 12 hours pass
+    And unused assets are collected
+    Then the asset "logo-de.png" should be kept
+    When 13 hours pass
+    And unused assets are collected
+    Then the asset "logo-de.png" should be gone
+
+  Scenario: An old asset of a deleted translation is kept for the grace period
+    Given the asset "logo-de.png" was uploaded
+    And the campaign's template translation into "de" shows the asset "logo-de.png"
+    And 30 days pass
+    When the campaign's template translation into "de" is deleted
+    And 12 hours pass
+    And unused assets are collected
+    Then the asset "logo-de.png" should be kept
+
+  Scenario: An asset a sent campaign showed is never collected
+    Given the asset "logo.png" was uploaded
+    And the campaign's template shows the asset "logo.png"
+    And the campaign is sending to 2 subscribers
+    When a campaign batch is sent
+    And the campaign's template stops showing assets
+    And 30 days pass
+    And unused assets are collected
+    Then 2 campaign emails should have been sent
+    And the asset "logo.png" should be kept
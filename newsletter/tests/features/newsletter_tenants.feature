@@ -0,0 +1,32 @@
+Feature: Tenant isolation
+  As an operator running newsletters for several brands
+  I want each workspace's subscribers kept apart
+  So that one deployment can serve every tenant
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: The same address is subscribed separately in each tenant
+    When tenant "acme" subscribes email "reader@example.com"
+    And tenant "globex" subscribes email "reader@example.com"
+    And tenant "globex" unsubscribes email "reader@example.com"
+    Then the email "reader@example.com" should exist in tenant "acme"
+    And the subscription of "reader@example.com" should be active in tenant "acme"
+    And the subscription of "reader@example.com" should be unsubscribed in tenant "globex"
+
+  Scenario: A tenant does not see the subscribers of another
+    When tenant "acme" subscribes email "private@example.com"
+    Then the email "private@example.com" should not exist in tenant "globex"
+    And the email "private@example.com" should not exist
+
+  Scenario: Stats only count the tenant's own subscriptions
+    When tenant "acme" subscribes email "one@example.com"
+    And tenant "acme" subscribes email "two@example.com"
+    And tenant "acme" requests a subscription for "three@example.com"
+    And tenant "acme" unsubscribes email "two@example.com"
+    And tenant "globex" subscribes email "four@example.com"
+    And I read the stats of tenant "acme"
+    Then the stats should show 1 active, 1 inactive and 1 unsubscribed
+    When I read the stats of tenant "globex"
+    Then the stats should show 1 active, 0 inactive and 0 unsubscribed
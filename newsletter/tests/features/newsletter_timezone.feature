@@ -0,0 +1,25 @@
+Feature: Subscriber timezone
+  As a marketing team
+  I want to know the timezone each subscriber lives in
+  So that campaigns can arrive in their morning rather than at night
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: Set and clear a timezone
+    Given I have subscribed email "ada@example.com"
+    When I set the timezone of "ada@example.com" to "Europe/Berlin"
+    Then the operation should complete successfully
+    And the timezone should be "Europe/Berlin"
+    When I clear the timezone of "ada@example.com"
+    Then the timezone should not be set
+
+  Scenario: Unknown timezones are rejected
+    Given I have subscribed email "ada@example.com"
+    When I set the timezone of "ada@example.com" to "Mars/Olympus_Mons"
+    Then the operation should fail with "unknown timezone: Mars/Olympus_Mons"
+
+  Scenario: A timezone needs a subscription
+    When I set the timezone of "ghost@example.com" to "Asia/Tokyo"
+    Then the operation should fail with "not subscribed"
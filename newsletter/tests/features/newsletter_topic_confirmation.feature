@@ -0,0 +1,51 @@
+Feature: Double opt-in for topics
+  As a newsletter operator
+  I want some topics to take a confirmed link before anyone receives them
+  So that sensitive mail only goes to readers who asked for it twice
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+    And topic "promotions" requires confirmation
+
+  Scenario: A topic that needs confirmation is not received by default
+    Given I have subscribed email "prefs@example.com"
+    When I get the preferences for "prefs@example.com"
+    Then topic "promotions" should be unsubscribed
+    And topic "weekly_digest" should be subscribed
+
+  Scenario: Opting in waits for the confirmation link
+    Given I have subscribed email "prefs@example.com"
+    When I opt in to topic "promotions" for "prefs@example.com"
+    Then the operation should complete successfully
+    And topic "promotions" should be awaiting confirmation
+    And topic "promotions" should be unsubscribed
+
+  Scenario: Following the link subscribes the topic
+    Given I have subscribed email "prefs@example.com"
+    When I opt in to topic "promotions" for "prefs@example.com"
+    And I follow the topic confirmation link sent to "prefs@example.com"
+    Then the operation should complete successfully
+    When I get the preferences for "prefs@example.com"
+    Then topic "promotions" should be subscribed
+
+  Scenario: Opting out needs no confirmation
+    Given I have subscribed email "prefs@example.com"
+    When I opt in to topic "promotions" for "prefs@example.com"
+    And I follow the topic confirmation link sent to "prefs@example.com"
+    And I opt out of topic "promotions" for "prefs@example.com"
+    Then topics "" should be awaiting confirmation
+    And topic "promotions" should be unsubscribed
+
+  Scenario: Other topics are not held back
+    Given I have subscribed email "prefs@example.com"
+    When I opt out of topic "weekly_digest" for "prefs@example.com"
+    And I opt in to topic "weekly_digest" for "prefs@example.com"
+    Then topics "" should be awaiting confirmation
+    And topic "weekly_digest" should be subscribed
+
+  Scenario: An unsubscribed address cannot opt in
+    Given I have subscribed email "prefs@example.com"
+    When I unsubscribe email "prefs@example.com"
+    And I opt in to topic "promotions" for "prefs@example.com"
+    Then the operation should fail with "only an active subscription can opt in to promotions"
@@ -0,0 +1,20 @@
+Feature: Units of work
+  As a developer of the service layer
+  I want to group repository calls into one transaction
+  So that a step failing halfway leaves nothing half done
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+
+  Scenario: A unit of work keeps every change it made
+    When a unit of work adds "first@example.com, second@example.com"
+    Then the operation should complete successfully
+    And the email first@example.com should be active
+    And the email second@example.com should be active
+
+  Scenario: A failed unit of work leaves nothing behind
+    When a unit of work adds "first@example.com, second@example.com" and then fails
+    Then the operation should fail with "the unit of work gave up"
+    And the email first@example.com should not exist
+    And the email second@example.com should not exist
@@ -0,0 +1,41 @@
+Feature: Address verification
+  As the newsletter owner
+  I want obviously fake addresses refused at sign-up
+  So that the list only holds mailboxes that can be reached
+
+  Background:
+    Given the newsletter service is running
+    And the database is clean
+    And the disposable domain list holds "mailinator.com, guerrillamail.com"
+    And subscribe checks disposable domains and MX records
+
+  Scenario: A disposable domain and its subdomains are refused
+    When I subscribe email "throwaway@mailinator.com"
+    Then the operation should fail with "is a disposable email domain"
+    When I subscribe email "throwaway@eu.guerrillamail.com"
+    Then the operation should fail with "is a disposable email domain"
+    When I subscribe email "reader@example.com"
+    Then the operation should complete successfully
+    And "reader@example.com" should be active
+
+  Scenario: A domain without mail servers is refused
+    Given "nowhere.example" does not accept mail
+    When I subscribe email "reader@nowhere.example"
+    Then the operation should fail with "does not accept email"
+    And "reader@nowhere.example" should not be active
+
+  Scenario: An unanswered MX lookup lets the address through
+    Given MX lookups time out
+    When I subscribe email "reader@example.org"
+    Then "reader@example.org" should be active
+
+  Scenario: A refreshed list applies from the next subscribe
+    When the disposable domain list is changed to "example.net"
+    And I subscribe email "before@example.net"
+    Then "before@example.net" should be active
+    When I refresh the disposable domain list
+    Then the operation should complete successfully
+    When I subscribe email "after@example.net"
+    Then the operation should fail with "is a disposable email domain"
+    When I subscribe email "after@mailinator.com"
+    Then "after@mailinator.com" should be active